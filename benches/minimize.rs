@@ -0,0 +1,133 @@
+// Benchmarks of each stage of minimizing, on the tables of minterm::testutil:
+// reading the CSV, building the minterm equations, the greedy and exact
+// minimizers, the exact minimizer's chart and cover search on 13 inputs,
+// looking up every row of a table, and the whole command line.  Run with 'cargo bench'.
+#[macro_use]
+extern crate criterion;
+extern crate minterm;
+use std::process::Command;
+use criterion::{BenchmarkId, Criterion};
+use minterm::{Cells, Equation, InputEncoding, OutputEncoding, HEADER_LINES, equations,
+              exact, parse_cells, progress};
+use minterm::testutil::{self, Fixture};
+
+fn fixture_equations(f: &Fixture) -> Vec<Equation> {
+	let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+	equations(&f.truth, outvars, f.invars.clone())
+}
+
+fn parse(c: &mut Criterion) {
+	let mut group = c.benchmark_group("parse");
+	for &nin in [8, 11, 14].iter() {
+		let f = testutil::dense(nin, 4, 1);
+		let csv = f.csv();
+		group.bench_with_input(BenchmarkId::new("dense", nin), &csv, |b, csv| {
+			b.iter(|| parse_cells(csv.as_bytes(), HEADER_LINES, nin, 4,
+			                      InputEncoding::Binary, OutputEncoding::Binary,
+			                      Cells::Warn, None).unwrap())
+		});
+	}
+	group.finish();
+}
+
+fn construct(c: &mut Criterion) {
+	let mut group = c.benchmark_group("equations");
+	for f in [testutil::dense(14, 4, 1), testutil::sparse(14, 4, 8, 1)].iter() {
+		group.bench_function(&f.name, |b| b.iter(|| fixture_equations(f)));
+	}
+	group.finish();
+}
+
+fn greedy(c: &mut Criterion) {
+	let mut group = c.benchmark_group("simplify");
+	group.sample_size(10);
+	let fixtures = [testutil::dense(8, 2, 1), testutil::dense(11, 2, 1),
+	                testutil::sparse(12, 2, 8, 1), testutil::parity(10),
+	                testutil::capabilities()];
+	for f in fixtures.iter() {
+		let eqns = fixture_equations(f);
+		let nin = f.invars.len();
+		group.bench_function(&f.name, |b| b.iter(|| {
+			let mut eqns = eqns.clone();
+			minterm::minimize(&mut eqns, nin, false, &vec![1; nin],
+			                  &mut exact::Budget::unlimited(), &mut progress::Silent);
+			eqns
+		}));
+	}
+	group.finish();
+}
+
+fn exact(c: &mut Criterion) {
+	let mut group = c.benchmark_group("exact");
+	group.sample_size(10);
+	let fixtures = [testutil::dense(6, 1, 1), testutil::sparse(8, 1, 4, 1),
+	                testutil::parity(6), testutil::capabilities()];
+	for f in fixtures.iter() {
+		let eqns = fixture_equations(f);
+		let nin = f.invars.len();
+		group.bench_function(&f.name, |b| b.iter(|| {
+			let mut budget = exact::Budget::unlimited();
+			eqns.iter().map(|e| exact::minimize(e, nin, &mut budget)).count()
+		}));
+	}
+	group.finish();
+}
+
+// The exact minimizer on 13 inputs, the search cut off after a fixed number
+// of nodes: what it measures is the chart and the cover search's set
+// operations, not how long the search would go on.
+fn cover(c: &mut Criterion) {
+	let mut group = c.benchmark_group("cover");
+	group.sample_size(10);
+	{
+  let f = &testutil::noisy(13, 20, 1);
+		let eqns = fixture_equations(f);
+		let nin = f.invars.len();
+		group.bench_function(&f.name, |b| b.iter(|| {
+			let mut budget = exact::Budget::new(None, Some(200_000));
+			eqns.iter().map(|e| exact::minimize(e, nin, &mut budget)).count()
+		}));
+	}
+	group.finish();
+}
+
+// Every input pattern of a 12-input table looked up, as checking a table
+// exhaustively does: a pass over the rows for each, and through an index.
+// The rows are shuffled, so that the index has to be a map.
+fn lookup(c: &mut Criterion) {
+	let mut group = c.benchmark_group("lookup");
+	group.sample_size(10);
+	let mut tbl = testutil::dense(12, 4, 1).truth;
+	let n = tbl.len();
+	for i in 0..n {
+		tbl.swap_rows(i, i * 7 % n);
+	}
+	let inputs: Vec<Vec<bool>> = tbl.rows().iter().map(|e| e.input.clone()).collect();
+	group.bench_function("scan", |b| b.iter(|| {
+		inputs.iter().filter(|inp| tbl.solution(inp.to_vec())[0]).count()
+	}));
+	group.bench_function("index", |b| b.iter(|| {
+		let rows = tbl.index();
+		inputs.iter().filter(|inp| rows.solution(&tbl, inp)[0]).count()
+	}));
+	group.finish();
+}
+
+fn cli(c: &mut Criterion) {
+	let mut group = c.benchmark_group("cli");
+	group.sample_size(10);
+	let dir = std::env::temp_dir();
+	for f in [testutil::capabilities(), testutil::dense(10, 4, 1)].iter() {
+		let path = f.write(&dir);
+		group.bench_function(&f.name, |b| b.iter(|| {
+			let out = Command::new(env!("CARGO_BIN_EXE_minterm"))
+				.arg("--table").arg(&path).args(f.args()).arg("--quiet")
+				.output().unwrap();
+			assert!(out.status.success());
+		}));
+	}
+	group.finish();
+}
+
+criterion_group!(benches, parse, construct, greedy, exact, cover, lookup, cli);
+criterion_main!(benches);
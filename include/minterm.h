@@ -0,0 +1,60 @@
+/* The C interface to the minimizer; see src/ffi.rs.  Build the library with
+ *   cargo rustc --release --features ffi --lib --crate-type staticlib
+ * and link target/release/libminterm.a, along with the system libraries that
+ * 'cargo rustc ... -- --print native-static-libs' lists.
+ *
+ * Every function returns one of the statuses below.  Strings are UTF-8 and
+ * NUL-terminated. */
+#ifndef MINTERM_H
+#define MINTERM_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MINTERM_OK 0
+#define MINTERM_USAGE 2     /* a null pointer, or a bad name or option */
+#define MINTERM_INVALID 3   /* the table is not one that can be minimized */
+#define MINTERM_UNPROVEN 5  /* the exact search ran out of steps */
+#define MINTERM_PANIC (-1)  /* a bug in the minimizer */
+
+typedef struct minterm_table minterm_table;
+
+typedef struct minterm_options {
+	const char *format;         /* as --format; NULL for "text" */
+	const char *default_output; /* as --default-output; NULL when the table
+	                               lists every input */
+	int exact;                  /* nonzero for --algorithm exact */
+	uint64_t max_steps;         /* as --max-steps; 0 for no limit */
+} minterm_options;
+
+/* Makes an empty table with 'nin' inputs and 'nout' outputs of the given
+ * names, to free with minterm_table_free. */
+int minterm_table_new(const char *const *invars, size_t nin,
+                      const char *const *outvars, size_t nout,
+                      minterm_table **table);
+
+/* Adds a row of 'nin' input bytes and 'nout' output bytes, each 0 or 1; NULL
+ * outputs are don't-cares.  MINTERM_INVALID for another byte, or a second row
+ * for the same inputs. */
+int minterm_table_add_row(minterm_table *table, const uint8_t *inputs,
+                          const uint8_t *outputs);
+
+/* Minimizes every output and stores the equations, written in the format, in
+ * '*result'; NULL options are all defaults.  On failure '*result' is the error
+ * message instead, if the arguments allowed one.  Free it with
+ * minterm_string_free. */
+int minterm_minimize(const minterm_table *table, const minterm_options *options,
+                     char **result);
+
+void minterm_string_free(char *s);
+void minterm_table_free(minterm_table *table);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,173 @@
+// The algebraic normal form (a.k.a. the positive-polarity Reed-Muller
+// expansion) of an output.  Instead of an OR of products, an output is written
+// as the XOR of products of uncomplemented inputs:
+//    x = 1 ^ a ^ bc ^ abc
+// Parity-like functions, which are the worst case for sum-of-products, are
+// tiny in this form: a 3-input parity is 4 minterms in SOP but just "a ^ b ^ c"
+// here.
+use std::fmt;
+use super::{Truth, enumerate};
+use super::bits::{BitOrder, bit_pack, bit_unpack};
+
+// An XorEquation is a collection of monomials, where the XOR of the monomials
+// gives the result.  Each monomial is the (sorted) list of input indices that
+// are ANDed together; the empty monomial is the constant 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XorEquation {
+	index: usize,
+	monomials: Vec<Vec<usize>>,
+	varname: String,
+	names: Vec<String>,
+}
+
+impl XorEquation {
+	/// @param tbl the (complete) truth table to compute this from
+	/// @param idx the index of the output variable we're creating
+	/// @param vn the variable name of the output variable
+	/// @param invars the names of the input variables
+	// Computes the ANF with the binary Moebius transform.  The table is first
+	// laid out as a vector indexed by input pattern (first input is the MSB),
+	// then each butterfly stage XORs the lower half of every block into the
+	// upper half.  Afterwards element 'm' is the coefficient of the monomial
+	// made of the inputs whose bits are set in 'm'.
+	// An empty table gives the constant 0.  Fails when the inputs have more
+	// than 2^limit patterns, as the vector has one element for each.
+	pub fn new(tbl: &Truth, idx: usize, vn: &str, invars: &[String], limit: usize) ->
+		Result<Self, String> {
+		let nbits = invars.len();
+		enumerate::check(nbits, limit, "; the ANF goes through them all")?;
+		let mut coef: Vec<bool> = vec![false; 1 << nbits];
+		for ent in tbl.table.iter() {
+			assert!(idx < ent.output.len());
+			coef[pattern_index(&ent.input)] = ent.output[idx];
+		}
+
+		let mut stride: usize = 1;
+		while stride < coef.len() {
+			for block in (0..coef.len()).step_by(2*stride) {
+				for i in block..block+stride {
+					coef[i+stride] ^= coef[i];
+				}
+			}
+			stride *= 2;
+		}
+
+		let mut monomials: Vec<Vec<usize>> = coef.iter().enumerate()
+			.filter(|&(_, &c)| c)
+			.map(|(m, _)| {
+				let bits = bit_unpack(m as u64, nbits, BitOrder::Msb);
+				(0..nbits).filter(|&v| bits[v]).collect()
+			}).collect();
+		// lowest degree first, then lexicographically: "1 ^ a ^ bc ^ abc".
+		monomials.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+		Ok(XorEquation{index: idx, monomials, varname: vn.to_string(),
+		               names: invars.to_vec()})
+	}
+
+	// Evaluates the equation for the given input pattern.
+	pub fn evaluate(&self, inp: &[bool]) -> bool {
+		self.monomials.iter()
+			.filter(|mono| mono.iter().all(|&v| inp[v]))
+			.count() % 2 == 1
+	}
+
+	// True if this equation reproduces its column of the given table, where
+	// that isn't a don't-care.
+	pub fn verify(&self, tbl: &Truth) -> bool {
+		tbl.table.iter().all(|ent| !ent.cares(self.index) ||
+		                           self.evaluate(&ent.input) == ent.output[self.index])
+	}
+}
+
+// Index of an input pattern when the patterns are enumerated in order, i.e.
+// the pattern read as a binary number with the first input as the MSB.
+fn pattern_index(bits: &[bool]) -> usize {
+	bit_pack(bits, BitOrder::Msb) as usize
+}
+
+impl fmt::Display for XorEquation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} = ", self.varname)?;
+		if self.monomials.is_empty() {
+			return write!(f, "0;");
+		}
+		for (i, mono) in self.monomials.iter().enumerate() {
+			if i > 0 {
+				write!(f, " ^ ")?;
+			}
+			if mono.is_empty() {
+				write!(f, "1")?;
+			}
+			for &v in mono.iter() {
+				assert!(v < self.names.len());
+				write!(f, "{}", self.names[v])?;
+			}
+		}
+		write!(f, ";")
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::gray_code;
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|e| e.to_string()).collect()
+	}
+
+	// builds the complete 3-input table for the given function.
+	fn table(f: &dyn Fn(&[bool]) -> bool) -> Truth {
+		let inputs = gray_code(3);
+		let outputs = inputs.iter().map(|i| vec![f(i)]).collect();
+		Truth::new(inputs, outputs)
+	}
+
+	#[test]
+	fn parity() {
+		let tbl = table(&|i| i[0] ^ i[1] ^ i[2]);
+		let eqn = XorEquation::new(&tbl, 0, "x", &names(), 3).unwrap();
+		assert_eq!(format!("{}", eqn), "x = a ^ b ^ c;");
+		assert!(eqn.verify(&tbl));
+	}
+
+	#[test]
+	fn and() {
+		let tbl = table(&|i| i[0] && i[1] && i[2]);
+		let eqn = XorEquation::new(&tbl, 0, "x", &names(), 3).unwrap();
+		assert_eq!(format!("{}", eqn), "x = abc;");
+		assert!(eqn.verify(&tbl));
+	}
+
+	#[test]
+	fn constants() {
+		let zero = table(&|_| false);
+		let eqn = XorEquation::new(&zero, 0, "x", &names(), 3).unwrap();
+		assert_eq!(format!("{}", eqn), "x = 0;");
+		let nand = table(&|i| !(i[0] && i[1] && i[2]));
+		let eqn = XorEquation::new(&nand, 0, "x", &names(), 3).unwrap();
+		assert_eq!(format!("{}", eqn), "x = 1 ^ abc;");
+		// no rows at all.
+		let empty = XorEquation::new(&Truth::default(), 0, "x", &names(), 3).unwrap();
+		assert_eq!(format!("{}", empty), "x = 0;");
+	}
+
+	#[test]
+	fn too_many_inputs() {
+		let tbl = table(&|_| true);
+		let err = XorEquation::new(&tbl, 0, "x", &names(), 2).unwrap_err();
+		assert!(err.starts_with("Cannot enumerate the 2^3 patterns of 3 inputs"),
+		        "{}", err);
+	}
+
+	#[test]
+	fn small_verifies() {
+		let small = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		let truth = super::super::parse(small.as_bytes(), 0, 3, 2);
+		for (b, ov) in ["x", "y"].iter().enumerate() {
+			let e = XorEquation::new(&truth, b, ov, &names(), 3).unwrap();
+			assert!(e.verify(&truth), "{} does not match the table", e);
+		}
+	}
+}
@@ -0,0 +1,267 @@
+// 'minterm batch': minimizes every table of a directory, each as minimize
+// would with the names and policies its schema line or sidecar file gives,
+// and writes the equations of each to a file of its own.  The tables are
+// independent, so several are minimized at once; one failing doesn't stop the
+// others, and the summary at the end lists them all.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use minterm::{Algorithm, schema};
+use minterm::bits::BitOrder;
+use minterm::embed::{self, MinimizeOptions, Names};
+use super::{Failure, MINIMIZE_USAGE, choice, load, parse_args, schema_args};
+
+pub const USAGE: &str = "
+Usage: minterm batch --dir=<dir> --out-dir=<dir> [options]
+       minterm batch --help
+
+Minimizes each table in the directory whose name matches --glob, and writes
+its equations to a file of the same name in the output directory, with the
+extension of the format.  Each table names its inputs and outputs, and may
+give what to do about missing and duplicate inputs, either in a schema line
+(see 'minterm minimize --help') or in a sidecar file, named like the table
+but ending .toml, such as
+
+    inputs = [\"a\", \"b\", \"c\"]
+    outputs = [\"x\", \"y\"]
+    sparse = \"dc\"
+
+but not both.  A table that fails is reported, and the others are still
+minimized.  At the end a summary lists each table with its terms and literals
+or why it failed; the exit status is 3 if any failed.
+
+Options:
+  --dir=<dir>        The directory of the tables.
+  --out-dir=<dir>    The directory to write the equations to, created if
+                     need be.
+  --glob=<pat>       The names of the tables to minimize, in which '*'
+                     matches any characters and '?' any one.  [default: *.csv]
+  --format=<fmt>     How to write the equations: 'text', 'python',
+                     'verilog-casez', 'vhdl', 'switch-c', 'switch-rust' or
+                     'rust-const', as minimize does.  [default: text]
+  --algorithm=<alg>  'greedy' or 'exact', as for minimize.  [default: greedy]
+  --cost=<cost>      'terms', 'conditions' or 'literals', as for minimize.
+                     [default: terms]
+  --timeout=<secs>   Stop the exact search of each table after this many
+                     seconds.
+  --max-steps=<n>    Stop the exact search of each table after this many
+                     search nodes.
+  --jobs=<n>         How many tables to minimize at once; by default as many
+                     as there are processors.
+  --quiet            Print nothing but the summary and errors.
+  -h, --help         Show this text.
+";
+
+// The extension of the files written in each format.
+const EXTENSIONS: [(&str, &str); 7] = [
+	("text", "txt"), ("python", "py"), ("verilog-casez", "v"), ("vhdl", "vhd"),
+	("switch-c", "c"), ("switch-rust", "rs"), ("rust-const", "rs"),
+];
+
+// What became of one table.
+struct Outcome {
+	name: String,
+	result: Result<Stats, Failure>,
+	secs: f64,
+}
+
+struct Stats {
+	inputs: usize,
+	outputs: usize,
+	terms: usize,
+	literals: usize,
+	minimal: bool,
+}
+
+// Whether the name matches the pattern, '*' standing for any run of
+// characters and '?' for any one.
+fn matches(pattern: &[char], name: &[char]) -> bool {
+	match pattern.split_first() {
+		None => name.is_empty(),
+		Some((&'*', rest)) => (0..name.len() + 1).any(|k| matches(rest, &name[k..])),
+		Some((&p, rest)) => match name.split_first() {
+			Some((&c, more)) => (p == '?' || p == c) && matches(rest, more),
+			None => false,
+		},
+	}
+}
+
+// The tables of the directory matching the pattern, by name.
+fn tables(dir: &str, pattern: &str) -> Result<Vec<PathBuf>, Failure> {
+	let entries = match fs::read_dir(dir) {
+		Ok(e) => e,
+		Err(e) => fail!(Usage, "Cannot read the directory {}: {}", dir, e),
+	};
+	let pattern: Vec<char> = pattern.chars().collect();
+	let mut rv: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path())
+		// a sidecar file is never a table itself.
+		.filter(|p| p.is_file() && p.extension().is_none_or(|e| e != "toml"))
+		.filter(|p| {
+			let name = p.file_name().unwrap().to_string_lossy();
+			matches(&pattern, &name.chars().collect::<Vec<char>>())
+		}).collect();
+	rv.sort();
+	if rv.is_empty() {
+		let pattern: String = pattern.iter().collect();
+		fail!(Usage, "No file in {} matches '{}'.", dir, pattern);
+	}
+	Ok(rv)
+}
+
+// The options minimize would take for the table: its names and policies from
+// its sidecar file, if it has one, and otherwise from its schema line.
+fn table_argv(table: &Path) -> Result<Vec<String>, Failure> {
+	let file = table.to_string_lossy().to_string();
+	let mut argv: Vec<String> = vec!["minterm".to_string(), "minimize".to_string(),
+	                                 "--table".to_string(), file.clone()];
+	let sidecar = table.with_extension("toml");
+	if sidecar.is_file() {
+		let text = match fs::read_to_string(&sidecar) {
+			Ok(t) => t,
+			Err(e) => fail!(Usage, "Cannot read {}: {}", sidecar.display(), e),
+		};
+		let s = match schema::parse_toml(&text) {
+			Ok(s) => s,
+			Err(e) => fail!(Usage, "Invalid {}: {}", sidecar.display(), e),
+		};
+		let first = fs::read_to_string(table).unwrap_or_default();
+		if let Ok(Some(_)) = schema::parse(first.lines().next().unwrap_or("").trim_end()) {
+			fail!(Usage, "{} has both a schema line and the sidecar {}; give one.", file,
+			      sidecar.display());
+		}
+		argv.extend(s.options());
+	}
+	let argv = schema_args(argv)?;
+	if !argv.iter().any(|a| a.starts_with("--ovar")) {
+		fail!(Usage, "{} names no inputs or outputs; give it a schema line or a sidecar \
+		              {}.", file, sidecar.display());
+	}
+	Ok(argv)
+}
+
+// Minimizes the table and writes its equations into 'out'.
+fn minimize(table: &Path, opts: &MinimizeOptions, format: &str, out: &Path) ->
+	Result<Stats, Failure> {
+	let argv = table_argv(table)?;
+	let args = parse_args(MINIMIZE_USAGE, &argv)?;
+	let ld = load(&args, &argv, &|_| {})?;
+	let opts = opts.clone().default_output(ld.policy.clone());
+	let fail = |e: embed::Error| match e {
+		embed::Error::Usage(msg) => Failure::Usage(msg),
+		embed::Error::Invalid(msg) => Failure::Invalid(msg),
+		e => Failure::Invalid(e.to_string()),
+	};
+	let result = embed::minimize(&ld.tbl, &Names::new(&ld.invars, &ld.outvars), &opts)
+		.map_err(&fail)?;
+	let text = embed::render(&result, &ld.tbl, &ld.invars, format, ld.policy.clone(),
+	                         BitOrder::default()).map_err(&fail)?;
+	if let Err(e) = fs::write(out, text) {
+		fail!(Usage, "Cannot write {}: {}", out.display(), e);
+	}
+	Ok(Stats{inputs: ld.invars.len(), outputs: ld.outvars.len(),
+	         terms: result.stats.iter().map(|s| s.terms).sum(),
+	         literals: result.stats.iter().map(|s| s.literals).sum(),
+	         minimal: result.minimal()})
+}
+
+// The minimize options the batch gives every table.
+fn options(args: &::docopt::ArgvMap) -> Result<MinimizeOptions, Failure> {
+	let mut opts = MinimizeOptions::new();
+	if choice(args, "--algorithm", &["greedy", "exact"])? == "exact" {
+		opts = opts.algorithm(Algorithm::Exact);
+	}
+	let cost = choice(args, "--cost", &["terms", "conditions", "literals"])?;
+	opts = opts.cost(embed::Cost::parse(cost).map_err(Failure::Usage)?);
+	match args.get_str("--timeout") {
+		"" => {},
+		s => match s.parse::<f64>() {
+			Ok(secs) if secs >= 0.0 =>
+				opts = opts.timeout(Duration::from_millis((secs*1000.0) as u64)),
+			_ => fail!(Usage, "Invalid --timeout '{}'; expected a number of seconds.", s),
+		},
+	}
+	match args.get_str("--max-steps") {
+		"" => {},
+		s => match s.parse::<u64>() {
+			Ok(n) => opts = opts.max_steps(n),
+			Err(_) => fail!(Usage, "Invalid --max-steps '{}'; expected an integer.", s),
+		},
+	}
+	opts.algorithms(&[]).map_err(Failure::Usage)?;
+	Ok(opts)
+}
+
+pub fn run(args: &::docopt::ArgvMap) -> Result<(), Failure> {
+	let quiet = args.get_bool("--quiet");
+	let formats: Vec<&str> = EXTENSIONS.iter().map(|&(f, _)| f).collect();
+	let format = choice(args, "--format", &formats)?;
+	let ext = EXTENSIONS.iter().find(|&&(f, _)| f == format).unwrap().1;
+	let opts = options(args)?;
+	let jobs = match args.get_str("--jobs") {
+		"" => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+		s => match s.parse::<usize>() {
+			Ok(n) if n > 0 => n,
+			_ => fail!(Usage, "Invalid --jobs '{}'; expected a positive integer.", s),
+		},
+	};
+	let tables = tables(args.get_str("--dir"), args.get_str("--glob"))?;
+	let out_dir = Path::new(args.get_str("--out-dir"));
+	if let Err(e) = fs::create_dir_all(out_dir) {
+		fail!(Usage, "Cannot create the directory {}: {}", out_dir.display(), e);
+	}
+	// each worker takes the next table until there are none.
+	let next = AtomicUsize::new(0);
+	let outcomes: Mutex<Vec<(usize, Outcome)>> = Mutex::new(vec![]);
+	thread::scope(|s| {
+		for _ in 0..jobs.min(tables.len()) {
+			s.spawn(|| loop {
+				let k = next.fetch_add(1, Ordering::SeqCst);
+				let table = match tables.get(k) {
+					Some(t) => t,
+					None => break,
+				};
+				let name = table.file_name().unwrap().to_string_lossy().to_string();
+				let out = out_dir.join(Path::new(&name).with_extension(ext));
+				let start = Instant::now();
+				let result = minimize(table, &opts, format, &out);
+				let secs = start.elapsed().as_secs_f64();
+				if !quiet {
+					let what = if result.is_ok() { "done" } else { "failed" };
+					eprintln!("{}: {}", name, what);
+				}
+				outcomes.lock().unwrap().push((k, Outcome{name, result, secs}));
+			});
+		}
+	});
+	let mut outcomes = outcomes.into_inner().unwrap();
+	outcomes.sort_by_key(|&(k, _)| k);
+	let width = outcomes.iter().map(|(_, o)| o.name.len()).max().unwrap_or(0).max(5);
+	println!("{:w$}  inputs  outputs  terms  literals  seconds  result", "table",
+	         w = width);
+	let mut failed = 0;
+	for (_, o) in outcomes.iter() {
+		match o.result {
+			Ok(ref s) => println!("{:w$}  {:>6}  {:>7}  {:>5}  {:>8}  {:>7.2}  {}", o.name,
+			                      s.inputs, s.outputs, s.terms, s.literals, o.secs,
+			                      if s.minimal { "ok" } else { "ok, not proven minimal" },
+			                      w = width),
+			Err(ref f) => {
+				failed += 1;
+				let why = match *f {
+					Failure::Usage(ref msg) | Failure::Invalid(ref msg) |
+					Failure::Verification(ref msg) => msg.as_str(),
+					Failure::Unproven | Failure::Changed => "",
+				};
+				println!("{:w$}  {:>6}  {:>7}  {:>5}  {:>8}  {:>7.2}  failed: {}", o.name,
+				         "-", "-", "-", "-", o.secs, why, w = width);
+			},
+		}
+	}
+	if failed > 0 {
+		fail!(Invalid, "{} of {} tables failed.", failed, outcomes.len());
+	}
+	Ok(())
+}
@@ -0,0 +1,234 @@
+// Reduced ordered binary decision diagrams of an output.  Each node tests one
+// input and goes to its 'low' child when the input is 0 and to its 'high'
+// child when it is 1, down to the terminals 0 and 1; the inputs are tested in
+// the same order along every path.  No node has two equal children and no two
+// nodes test the same input with the same children, so for a given order the
+// diagram of a function is unique, and its size measures how complex the
+// function is under that order.  There are no complemented edges.
+//
+// The size can differ wildly between orders: (a1 b1) + (a2 b2) + (a3 b3) needs
+// 6 nodes when each a is next to its b, but 14 when the a's all come first.
+// Finding the best order is hard, so sift() only improves on a given one.
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::Write;
+use super::{Truth, enumerate};
+use super::bits::{BitOrder, bit_pack};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Node {
+	level: usize,
+	low: usize,
+	high: usize,
+}
+
+// Nodes 0 and 1 are the terminals; the root is the last node made.
+#[derive(Clone, Debug)]
+pub struct Bdd {
+	order: Vec<usize>,
+	nodes: Vec<Node>,
+	unique: HashMap<(usize, usize, usize), usize>,
+	root: usize,
+}
+
+impl Bdd {
+	// The diagram of output 'idx' of the table, testing the inputs in 'order',
+	// which lists the index of every input once.  Inputs the table doesn't list,
+	// and its don't-cares, give 0.  Fails when the inputs have more than
+	// 2^limit patterns, as the diagram is built from the value of each.
+	pub fn from_truth(tbl: &Truth, idx: usize, order: &[usize], limit: usize) ->
+		Result<Self, String> {
+		enumerate::check(order.len(), limit, "; the diagram goes through them all")?;
+		Ok(Bdd::unchecked(tbl, idx, order))
+	}
+
+	// from_truth() without the check on the number of inputs.
+	fn unchecked(tbl: &Truth, idx: usize, order: &[usize]) -> Self {
+		let nbits = order.len();
+		let mut values = vec![false; 1 << nbits];
+		for ent in tbl.table.iter() {
+			let bits: Vec<bool> = order.iter().map(|&v| ent.input[v]).collect();
+			values[bit_pack(&bits, BitOrder::Msb) as usize] = ent.output[idx];
+		}
+		let terminal = |v| Node{level: nbits, low: v, high: v};
+		let mut bdd = Bdd{order: order.to_vec(), nodes: vec![terminal(0), terminal(1)],
+		                  unique: HashMap::new(), root: 0};
+		bdd.root = bdd.build(&values, 0);
+		bdd
+	}
+
+	// The node for the values of every pattern of the inputs from 'level' on.
+	fn build(&mut self, values: &[bool], level: usize) -> usize {
+		if values.len() == 1 {
+			return values[0] as usize;
+		}
+		let half = values.len() / 2;
+		let low = self.build(&values[..half], level+1);
+		let high = self.build(&values[half..], level+1);
+		self.node(level, low, high)
+	}
+
+	// The node testing the input at 'level', made unless an equal one exists.
+	fn node(&mut self, level: usize, low: usize, high: usize) -> usize {
+		if low == high {
+			return low;
+		}
+		let next = self.nodes.len();
+		let id = *self.unique.entry((level, low, high)).or_insert(next);
+		if id == next {
+			self.nodes.push(Node{level, low, high});
+		}
+		id
+	}
+
+	// The number of nodes testing an input, leaving out the terminals.
+	pub fn size(&self) -> usize {
+		self.nodes.len() - 2
+	}
+
+	pub fn order(&self) -> &[usize] {
+		&self.order
+	}
+
+	// The number of nodes testing each input, by level.
+	fn level_sizes(&self) -> Vec<usize> {
+		let mut sizes = vec![0; self.order.len()];
+		for n in self.nodes[2..].iter() {
+			sizes[n.level] += 1;
+		}
+		sizes
+	}
+
+	// Follows the input pattern from the root to a terminal.
+	pub fn evaluate(&self, inp: &[bool]) -> bool {
+		let mut at = self.root;
+		while at > 1 {
+			let n = &self.nodes[at];
+			at = if inp[self.order[n.level]] { n.high } else { n.low };
+		}
+		at == 1
+	}
+
+	// The diagram in Graphviz's DOT language, as a graph named for the output.
+	// Edges to a low child are dashed.
+	pub fn to_dot(&self, name: &str, invars: &[String]) -> String {
+		let order: Vec<&str> = self.order.iter().map(|&v| invars[v].as_str()).collect();
+		let mut rv = format!("digraph \"{}\" {{\n", name);
+		// write! to a String can't fail.
+		writeln!(rv, "\t// {} nodes; order {}", self.size(), order.join(" ")).unwrap();
+		rv += "\tn0 [label=\"0\", shape=box];\n\tn1 [label=\"1\", shape=box];\n";
+		for (id, n) in self.nodes.iter().enumerate().skip(2) {
+			writeln!(rv, "\tn{} [label=\"{}\"];", id, order[n.level]).unwrap();
+			writeln!(rv, "\tn{} -> n{} [style=dashed];", id, n.low).unwrap();
+			writeln!(rv, "\tn{} -> n{};", id, n.high).unwrap();
+		}
+		rv += "}\n";
+		rv
+	}
+}
+
+// Greedy sifting: each input in turn, those at the most nodes first, is tried
+// at every level with the others kept in their order, and stays where the
+// diagram is smallest.  Returns the smallest diagram found, which is no larger
+// than the one for 'order'.  Each try builds the diagram anew from the table.
+// Fails like Bdd::from_truth().
+pub fn sift(tbl: &Truth, idx: usize, order: &[usize], limit: usize) ->
+	Result<Bdd, String> {
+	let mut best = Bdd::from_truth(tbl, idx, order, limit)?;
+	let sizes = best.level_sizes();
+	let mut inputs: Vec<(usize, usize)> = order.iter().cloned().zip(sizes).collect();
+	inputs.sort_by_key(|&(_, n)| Reverse(n));
+	for &(v, _) in inputs.iter() {
+		let mut rest = best.order.clone();
+		rest.retain(|&o| o != v);
+		for at in 0..rest.len()+1 {
+			let mut tried = rest.clone();
+			tried.insert(at, v);
+			if tried != best.order {
+				let bdd = Bdd::unchecked(tbl, idx, &tried);
+				if bdd.size() < best.size() {
+					best = bdd;
+				}
+			}
+		}
+	}
+	Ok(best)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+	use super::super::bits::bit_unpack;
+
+	fn all_inputs(nbits: usize) -> Vec<Vec<bool>> {
+		(0..1u64 << nbits).map(|i| bit_unpack(i, nbits, BitOrder::Msb)).collect()
+	}
+
+	#[test]
+	fn xor() {
+		let tbl = Truth::from_fn(3, 1, |i| vec![i[0] ^ i[1] ^ i[2]]).unwrap();
+		// one node for a, then two each for b and c: even and odd so far.
+		for order in [[0, 1, 2], [2, 0, 1], [1, 2, 0]].iter() {
+			let bdd = Bdd::from_truth(&tbl, 0, order, 6).unwrap();
+			assert_eq!(bdd.size(), 5);
+			assert_eq!(bdd.level_sizes(), vec![1, 2, 2]);
+		}
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let bdd = Bdd::from_truth(&tbl, 0, &[0, 1, 2], 6).unwrap();
+		let dot = bdd.to_dot("x", &names);
+		assert!(dot.starts_with("digraph \"x\" {\n\t// 5 nodes; order a b c\n"),
+		        "{}", dot);
+		assert_eq!(dot.matches("style=dashed").count(), 5);
+	}
+
+	#[test]
+	fn evaluates_table() {
+		let small = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		let tbl = parse(small.as_bytes(), 0, 3, 2);
+		for idx in 0..2 {
+			for order in [[0, 1, 2], [2, 1, 0], [1, 0, 2]].iter() {
+				let bdd = Bdd::from_truth(&tbl, idx, order, 6).unwrap();
+				for ent in tbl.table.iter() {
+					assert_eq!(bdd.evaluate(&ent.input), ent.output[idx], "{:?}", order);
+				}
+			}
+		}
+		// y = c' + ab' tests all three inputs.
+		assert_eq!(Bdd::from_truth(&tbl, 1, &[0, 1, 2], 6).unwrap().size(), 3);
+		// unlisted inputs are 0.
+		let sparse = parse("0,0,,1\n1,1,,1\n".as_bytes(), 0, 2, 1);
+		let bdd = Bdd::from_truth(&sparse, 0, &[0, 1], 6).unwrap();
+		assert_eq!(bdd.size(), 3);
+		assert!(!bdd.evaluate(&[true, false]));
+	}
+
+	#[test]
+	fn sifting() {
+		// a1 b1 + a2 b2 + a3 b3, with the a's first.
+		let f = |i: &[bool]| vec![i[0] && i[3] || i[1] && i[4] || i[2] && i[5]];
+		let tbl = Truth::from_fn(6, 1, f).unwrap();
+		let natural = Bdd::from_truth(&tbl, 0, &[0, 1, 2, 3, 4, 5], 6).unwrap();
+		assert_eq!(natural.size(), 14);
+		let paired = Bdd::from_truth(&tbl, 0, &[0, 3, 1, 4, 2, 5], 6).unwrap();
+		assert_eq!(paired.size(), 6);
+		let best = sift(&tbl, 0, &[0, 1, 2, 3, 4, 5], 6).unwrap();
+		assert!(best.size() < natural.size());
+		for inp in all_inputs(6) {
+			assert_eq!(best.evaluate(&inp), f(&inp)[0]);
+		}
+		// no order makes XOR smaller.
+		let tbl = Truth::from_fn(3, 1, |i| vec![i[0] ^ i[1] ^ i[2]]).unwrap();
+		assert_eq!(sift(&tbl, 0, &[0, 1, 2], 6).unwrap().order(), &[0, 1, 2]);
+	}
+
+	#[test]
+	fn too_many_inputs() {
+		let tbl = Truth::from_fn(3, 1, |i| vec![i[0] ^ i[1] ^ i[2]]).unwrap();
+		let err = Bdd::from_truth(&tbl, 0, &[0, 1, 2], 2).unwrap_err();
+		assert!(err.starts_with("Cannot enumerate the 2^3 patterns of 3 inputs"),
+		        "{}", err);
+		assert_eq!(sift(&tbl, 0, &[0, 1, 2], 2).unwrap_err(), err);
+	}
+}
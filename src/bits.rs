@@ -0,0 +1,87 @@
+// Packing variables into integers and back.  Wherever a row's inputs or
+// outputs become one number -- an index-encoded input column, a decimal
+// output column, the packed inputs of a lookup or jump table and the outputs
+// of its entries, a casez selector, the rows of a template -- the variables
+// are packed here, in the order --bit-order gives.
+//
+// With BitOrder::Lsb, the default, the first variable is bit 0, the least
+// significant bit, and so on up: a, b, c packs as a | b << 1 | c << 2.  With
+// BitOrder::Msb the first variable is the most significant bit, as when the
+// variables are written out as a binary number.
+
+// Which bit of a packed value the first variable is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BitOrder {
+	#[default]
+	Lsb,
+	Msb,
+}
+
+impl BitOrder {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"lsb" => Ok(BitOrder::Lsb),
+			"msb" => Ok(BitOrder::Msb),
+			_ => Err(format!("Invalid --bit-order '{}'; expected 'msb' or 'lsb'.", s)),
+		}
+	}
+
+	// The bit of the packed value that variable k of nbits is.
+	pub fn shift(self, k: usize, nbits: usize) -> usize {
+		match self {
+			BitOrder::Lsb => k,
+			BitOrder::Msb => nbits - 1 - k,
+		}
+	}
+
+	// The variables listed from the most significant bit down, as a binary
+	// number or a Verilog concatenation writes them.
+	pub fn msb_down<T>(self, vars: &[T]) -> Vec<&T> {
+		match self {
+			BitOrder::Lsb => vars.iter().rev().collect(),
+			BitOrder::Msb => vars.iter().collect(),
+		}
+	}
+}
+
+// Expands 'value' into 'nbits' bits.  Bits past the 64th are 0.
+pub fn bit_unpack(value: u64, nbits: usize, order: BitOrder) -> Vec<bool> {
+	(0..nbits).map(|k| {
+		let shift = order.shift(k, nbits);
+		shift < 64 && (value >> shift) & 1 == 1
+	}).collect()
+}
+
+// The inverse of bit_unpack: packs the bits, at most 64 of them, into a value.
+pub fn bit_pack(bits: &[bool], order: BitOrder) -> u64 {
+	assert!(bits.len() <= 64);
+	bit_pack_wide(bits, order) as u64
+}
+
+// As bit_pack, for up to 128 bits, such as the outputs of a lookup table.
+pub fn bit_pack_wide(bits: &[bool], order: BitOrder) -> u128 {
+	assert!(bits.len() <= 128);
+	let nbits = bits.len();
+	bits.iter().enumerate().fold(0, |v, (k, &on)| v | (on as u128) << order.shift(k, nbits))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn round_trips() {
+		for &order in [BitOrder::Lsb, BitOrder::Msb].iter() {
+			for v in 0..16 {
+				assert_eq!(bit_pack(&bit_unpack(v, 4, order), order), v);
+			}
+		}
+		assert_eq!(bit_unpack(1, 3, BitOrder::Lsb), vec![true, false, false]);
+		assert_eq!(bit_unpack(1, 3, BitOrder::Msb), vec![false, false, true]);
+		assert_eq!(bit_pack(&[true, true, false], BitOrder::Msb), 6);
+		assert_eq!(bit_pack_wide(&[false; 100].iter().chain([true].iter()).cloned()
+		                         .collect::<Vec<bool>>(), BitOrder::Lsb), 1 << 100);
+		assert_eq!(BitOrder::Lsb.msb_down(&["a", "b", "c"]), vec![&"c", &"b", &"a"]);
+		assert!(BitOrder::parse("big").is_err());
+	}
+}
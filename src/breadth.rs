@@ -0,0 +1,104 @@
+// --format coverage: how much of the input space each term of the covers
+// claims, and of that how much are rows of the table and how much are
+// don't-cares, listed or unlisted.  In a sparse table a term may grow over
+// many more inputs than the rows it covers, by taking every don't-care it
+// meets; this lists the broadest first and flags those covering much of the
+// space mostly through don't-cares.
+use std::cmp::Reverse;
+use super::{Equation, Truth};
+use super::notation::Notation;
+
+// The most inputs whose patterns a term's size can be counted for.
+pub const MAX_BITS: usize = 127;
+
+pub struct Breadth {
+	pub output: String,
+	pub term: String,
+	// the input patterns the term is 1 for, of which 'rows' are rows of the
+	// table setting the output to 1 and the rest don't-cares.
+	pub size: u128,
+	pub rows: usize,
+	pub dont_cares: u128,
+	// the share of the input space, in percent.
+	pub percent: f64,
+	pub broad: bool,
+}
+
+// Every term of the equations, broadest first.  A term is broad when it
+// covers more than 'threshold' percent of the input space and more
+// don't-cares than rows.
+pub fn breadth(eqns: &[Equation], tbl: &Truth, nbits: usize, threshold: f64,
+               n: &Notation) -> Vec<Breadth> {
+	let mut rv: Vec<Breadth> = vec![];
+	for eqn in eqns.iter() {
+		for t in eqn.terms.iter() {
+			let size = 1u128 << (nbits - t.len());
+			let rows = tbl.minterms(eqn.index).filter(|e| t.evaluate(&e.input)).count();
+			let dont_cares = size - rows as u128;
+			let percent = 100.0 / 2f64.powi(t.len() as i32);
+			rv.push(Breadth{output: eqn.varname.clone(), term: n.term(t), size,
+			                rows, dont_cares, percent,
+			                broad: percent > threshold && dont_cares > rows as u128});
+		}
+	}
+	// stable, so terms alike in size stay in the order of the equations.
+	rv.sort_by_key(|b| Reverse(b.size));
+	rv
+}
+
+// The terms as a table, a line each.
+pub fn report(terms: &[Breadth]) -> String {
+	let width = |h: &str, f: &dyn Fn(&Breadth) -> usize| {
+		terms.iter().map(f).max().unwrap_or(0).max(h.len())
+	};
+	let wo = width("output", &|b| b.output.len());
+	let wt = width("term", &|b| b.term.len());
+	let mut s = format!("{:wo$}  {:wt$}  {:>8}  {:>6}  {:>11}  {:>7}\n", "output", "term",
+	                    "inputs", "rows", "don't-cares", "space", wo = wo, wt = wt);
+	for b in terms.iter() {
+		let line = format!("{:wo$}  {:wt$}  {:>8}  {:>6}  {:>11}  {:>6.2}%", b.output,
+		                   b.term, b.size, b.rows, b.dont_cares, b.percent, wo = wo,
+		                   wt = wt);
+		s += line.trim_end();
+		s += if b.broad { "  broad\n" } else { "\n" };
+	}
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{Term, equations};
+
+	#[test]
+	fn sparse_breadth() {
+		// four of the sixteen inputs are listed; the rest are don't-cares.
+		let inp = |s: &str| -> Vec<bool> { s.chars().map(|c| c == '1').collect() };
+		let tbl = Truth::new(vec![inp("0000"), inp("0001"), inp("0110"), inp("1111")],
+		                     vec![inp("101"), inp("110"), inp("000"), inp("010")]);
+		let invars: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string())
+			.collect();
+		let mut eqns = equations(&tbl, vec!["x", "y", "z"], invars.clone());
+		let term = |bits: Vec<(usize, bool)>| Term{bits, names: invars.clone()};
+		eqns[0].terms = vec![term(vec![(0, false), (1, false)])];
+		eqns[1].terms = vec![term(vec![(3, true)])];
+		eqns[2].terms = vec![term(vec![(0, false), (1, false), (2, false), (3, false)])];
+		let terms = breadth(&eqns, &tbl, 4, 25.0, &Notation::classic());
+		let rows: Vec<(&str, &str, u128, usize, u128, bool)> = terms.iter()
+			.map(|b| (b.output.as_str(), b.term.as_str(), b.size, b.rows, b.dont_cares,
+			          b.broad)).collect();
+		assert_eq!(rows, vec![("y", "d", 8, 2, 6, true), ("x", "a'b'", 4, 2, 2, false),
+		                      ("z", "a'b'c'd'", 1, 1, 0, false)]);
+		assert_eq!(terms.iter().map(|b| b.percent).collect::<Vec<f64>>(),
+		           vec![50.0, 25.0, 6.25]);
+		assert_eq!(report(&terms),
+		           "output  term        inputs    rows  don't-cares    space\n\
+		            y       d                8       2            6   50.00%  broad\n\
+		            x       a'b'             4       2            2   25.00%\n\
+		            z       a'b'c'd'         1       1            0    6.25%\n");
+		// x has as many rows as don't-cares, so no threshold flags it.
+		assert!(!breadth(&eqns, &tbl, 4, 10.0, &Notation::classic())[1].broad);
+		let flagged = breadth(&eqns, &tbl, 4, 0.0, &Notation::classic());
+		assert!(flagged.iter().all(|b| b.broad == (b.output == "y")));
+	}
+}
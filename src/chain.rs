@@ -0,0 +1,141 @@
+// If/else-if chains, for outputs of which at most one is 1 for any input, as a
+// decoder's are.  Each branch is then only reached where the outputs tested
+// before it are 0, so the inputs their covers hold are don't-cares for it:
+// its cover minimized again with them may lose literals, or all of them, for
+// the last branch of a decoder setting some output for every input, which
+// becomes a plain 'else'.
+use super::{Equation, Term};
+use super::emit::{Syntax, sum_of_products};
+use super::exact::{Budget, Cube, minimize, term_cube};
+use super::switch::c_name;
+
+const C: Syntax = Syntax{not: "!", and: " && ", or: " || ", zero: "false", one: "true"};
+
+// The cube where both terms are 1, None when they test an input differently.
+fn meet(a: &Term, b: &Term, nbits: usize) -> Option<Cube> {
+	let mut rv = term_cube(a, nbits);
+	for (x, y) in rv.iter_mut().zip(term_cube(b, nbits)) {
+		match (*x, y) {
+			(Some(u), Some(v)) if u != v => return None,
+			(None, _) => *x = y,
+			_ => {},
+		}
+	}
+	Some(rv)
+}
+
+// The first two outputs whose covers are both 1 for some input, with the cube
+// of such inputs one pair of their terms has; None when the outputs are
+// mutually exclusive.  The covers decide, so a don't-care that one of them
+// covers counts as a 1.
+pub fn overlap(eqns: &[Equation], nbits: usize) -> Option<(usize, usize, Cube)> {
+	for i in 0..eqns.len() {
+		for j in i + 1..eqns.len() {
+			for a in eqns[i].terms.iter() {
+				for b in eqns[j].terms.iter() {
+					if let Some(c) = meet(a, b, nbits) {
+						return Some((i, j, c));
+					}
+				}
+			}
+		}
+	}
+	None
+}
+
+fn literals(eqn: &Equation) -> usize {
+	eqn.terms.iter().map(|t| t.len()).sum()
+}
+
+pub struct Chain {
+	// the conditions in the order they are tested, each named for its output.
+	pub branches: Vec<Equation>,
+	// the literals of the covers tested each on its own, and of the chain.
+	pub separate: usize,
+	pub chained: usize,
+}
+
+// The chain for mutually exclusive covers.  An output that is never 1 gets no
+// branch, and the others are tested fewest literals first, so that the larger
+// covers come later and have the more don't-cares.  A cover is only replaced
+// by one with fewer literals.
+pub fn chain(eqns: &[Equation], nbits: usize) -> Chain {
+	let mut order: Vec<&Equation> = eqns.iter().filter(|e| !e.terms.is_empty()).collect();
+	order.sort_by_key(|e| literals(e));
+	let mut branches: Vec<Equation> = vec![];
+	for (k, eqn) in order.iter().enumerate() {
+		// the inputs of the branches before, whose covers hold there and this
+		// one's doesn't.
+		let reached = Equation{index: eqn.index, terms: eqn.terms.clone(),
+		                       dc: order[..k].iter().flat_map(|e| e.terms.iter().cloned())
+		                           .collect(),
+		                       varname: eqn.varname.clone()};
+		let mut budget = Budget::new(None, Some(10000));
+		let mut pruned = reached.clone();
+		pruned.terms = minimize(&reached, nbits, &mut budget).terms;
+		pruned.dc.clear();
+		if literals(&pruned) >= literals(eqn) {
+			pruned.terms = eqn.terms.clone();
+		}
+		pruned.canonicalize();
+		branches.push(pruned);
+	}
+	Chain{separate: eqns.iter().map(literals).sum(),
+	      chained: branches.iter().map(literals).sum(), branches}
+}
+
+// Writes a C function 'minterm' taking the inputs and a pointer per output,
+// which clears the outputs and sets the one whose branch of the chain is taken.
+pub fn if_chain_c(eqns: &[Equation], invars: &[String]) -> String {
+	let chain = chain(eqns, invars.len());
+	let inputs: Vec<String> = invars.iter().map(|n| c_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| c_name(&e.varname)).collect();
+	let params: Vec<String> = inputs.iter().map(|n| format!("bool {}", n))
+		.chain(outputs.iter().map(|n| format!("bool *{}", n))).collect();
+	let clear: Vec<String> = outputs.iter().map(|o| format!("*{} = false;", o)).collect();
+	let mut s = "#include <stdbool.h>\n\n".to_string();
+	s += "// At most one output is 1 for any input, so each condition leaves out what\n\
+	      // the branches before it rule out.\n";
+	s += &format!("void minterm({}) {{\n", params.join(", "));
+	s += &format!("\t{}\n", clear.join(" "));
+	for (k, b) in chain.branches.iter().enumerate() {
+		let cond = sum_of_products(b, &inputs, &C);
+		s += &match (k, b.terms.iter().any(|t| t.is_universal())) {
+			(0, _) => format!("\tif ({}) {{\n", cond),
+			(_, true) => "\t} else {\n".to_string(),
+			_ => format!("\t}} else if ({}) {{\n", cond),
+		};
+		s += &format!("\t\t*{} = true;\n", c_name(&b.varname));
+	}
+	if !chain.branches.is_empty() {
+		s += "\t}\n";
+	}
+	s += "}\n";
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, testutil};
+
+	#[test]
+	fn decoder_chain() {
+		let f = testutil::decoder(2);
+		let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+		let mut eqns = equations(&f.truth, outvars, f.invars.clone());
+		eqns.iter_mut().for_each(|e| e.simplify());
+		assert_eq!(overlap(&eqns, 2), None);
+		let chain = chain(&eqns, 2);
+		// i0'i1', then i0' and i1' for the rest of o1 and o2, and then o3
+		// for whatever is left.
+		assert_eq!((chain.separate, chain.chained), (8, 4));
+		let code = if_chain_c(&eqns, &f.invars);
+		assert!(code.contains("\tif (!i0 && !i1) {\n\t\t*o0 = true;\n\t} else if (!i0) {\n\
+		                       \t\t*o1 = true;\n\t} else if (!i1) {\n\t\t*o2 = true;\n\
+		                       \t} else {\n\t\t*o3 = true;\n\t}\n"), "{}", code);
+		// o1 is 1 where o3 is too.
+		eqns[1].terms = eqns[3].terms.clone();
+		assert_eq!(overlap(&eqns, 2), Some((1, 3, vec![Some(true), Some(true)])));
+	}
+}
@@ -0,0 +1,132 @@
+// --emit-complements: each output's complement as an output of its own, 'x_n'
+// beside 'x', for logic wanting both rails of a signal.  The complements are
+// columns of the table like the outputs, 1 where their output is 0 and 0
+// where it is 1, so whatever minimizes the outputs, shares terms between them
+// and writes them does the same for the complements.  As columns they leave
+// the don't-cares to be covered as suits each polarity; fixed, a complement
+// is instead minimized from its output's cover, whose 0s are its 1s on every
+// input, don't-cares included.
+use std::collections::BTreeSet;
+use super::{Entry, Equation, Term, Truth};
+use super::enumerate::Inputs;
+use super::emit::DefaultOutput;
+
+pub const SUFFIX: &str = "_n";
+
+// The names of the outputs followed by those of their complements.
+pub fn names(outvars: &[String]) -> Vec<String> {
+	let mut rv = outvars.to_vec();
+	rv.extend(outvars.iter().map(|o| format!("{}{}", o, SUFFIX)));
+	rv
+}
+
+// The table with a column after the outputs for each one's complement.  The
+// don't-cares are don't-cares of both.
+pub fn extend_table(tbl: &Truth) -> Truth {
+	let mut rv = tbl.clone();
+	for ent in rv.table.iter_mut() {
+		let negated: Vec<bool> = ent.output.iter().map(|&b| !b).collect();
+		let nout = negated.len();
+		ent.output.extend(negated);
+		let dc: Vec<usize> = ent.dc.iter().map(|&o| o + nout).collect();
+		ent.dc.extend(dc);
+	}
+	rv
+}
+
+// The policy for the inputs the table doesn't list, with the complements:
+// where the outputs are 0 or given bits, their complements are the opposite.
+pub fn extend_policy(policy: &DefaultOutput, nout: usize) -> DefaultOutput {
+	let bits = match *policy {
+		DefaultOutput::Zero => vec![false; nout],
+		DefaultOutput::Bits(ref b) => b.clone(),
+		DefaultOutput::DontCare | DefaultOutput::Panic => return policy.clone(),
+	};
+	let mut rv = bits.clone();
+	rv.extend(bits.iter().map(|&b| !b));
+	DefaultOutput::Bits(rv)
+}
+
+// The complement 'comp' fixed from its output's cover over 'nbits' inputs:
+// 1 on every input the cover is 0 for, but for the impossible ones, which
+// stay don't-cares.
+pub fn fixed(cover: &Equation, comp: &Equation, nbits: usize,
+             impossible: &BTreeSet<Vec<bool>>) -> Equation {
+	let names = comp.terms.iter().chain(comp.dc.iter()).chain(cover.terms.iter())
+		.map(|t| t.names.clone()).next().unwrap_or_default();
+	let term = |inp: &Vec<bool>| {
+		let mut t = Term::compute(inp);
+		t.names = names.clone();
+		t
+	};
+	let mut rv = Equation{index: comp.index, terms: vec![], dc: vec![],
+	                      varname: comp.varname.clone()};
+	for inp in Inputs::every(nbits) {
+		if impossible.contains(&inp) {
+			rv.dc.push(term(&inp));
+		} else if !cover.evaluate(&inp) {
+			rv.terms.push(term(&inp));
+		}
+	}
+	rv
+}
+
+// The first of the inputs for which the covers are alike, with their value;
+// None when they are complementary on them all.  The inputs are taken one at
+// a time, so they may be every pattern there is.
+pub fn clash<I: Iterator<Item = Vec<bool>>>(a: &Equation, b: &Equation, inputs: I) ->
+	Option<(Vec<bool>, bool)> {
+	inputs.map(|inp| { let v = a.evaluate(&inp); (inp, v) })
+		.find(|&(ref inp, v)| b.evaluate(inp) == v)
+}
+
+// The rows of the table, as complement pairs are checked on them.
+pub fn rows(tbl: &Truth) -> impl Iterator<Item = &Vec<bool>> {
+	tbl.table.iter().map(|e: &Entry| &e.input)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, exact};
+
+	#[test]
+	fn complementary_covers() {
+		// x is 1 for 001, 010, 011 and 111, 0 for 100; the rest are don't-cares.
+		let inp = |s: &str| -> Vec<bool> { s.chars().map(|c| c == '1').collect() };
+		let listed = ["001", "010", "011", "100", "111"];
+		let mut tbl = Truth::new(listed.iter().map(|s| inp(s)).collect(),
+		                         listed.iter().map(|s| vec![*s != "100"]).collect());
+		tbl.dc = vec![inp("000"), inp("101"), inp("110")];
+		let outvars = names(&["x".to_string()]);
+		assert_eq!(outvars, vec!["x", "x_n"]);
+		let both = extend_table(&tbl);
+		assert_eq!(both.table[3].output, vec![false, true]);
+		assert_eq!(both.dc, tbl.dc);
+		let invars: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string())
+			.collect();
+		let mut eqns = equations(&both, vec!["x", "x_n"], invars.clone());
+		// covers taking the don't-cares each likes: both are 0 for a'b'c' and 1
+		// for abc'.
+		let term = |bits: Vec<(usize, bool)>| Term{bits, names: invars.clone()};
+		eqns[0].terms = vec![term(vec![(1, true)]), term(vec![(2, true)])];
+		eqns[1].terms = vec![term(vec![(0, true), (2, false)])];
+		assert_eq!(clash(&eqns[0], &eqns[1], rows(&both).cloned()), None);
+		assert_eq!(clash(&eqns[0], &eqns[1], Inputs::every(3)), Some((inp("000"), false)));
+		// fixed from x's cover, x_n is 1 exactly where x is 0.
+		let mut fixed = fixed(&eqns[0], &eqns[1], 3, &BTreeSet::new());
+		assert_eq!(fixed.terms.len(), 2);
+		fixed.terms = exact::minimize(&fixed, 3, &mut exact::Budget::unlimited()).terms;
+		assert_eq!(fixed.to_string(), "x_n = b'c' + ;");
+		assert_eq!(clash(&eqns[0], &fixed, Inputs::every(3)), None);
+		// an impossible input is left to the minimizer.
+		let impossible: BTreeSet<Vec<bool>> = [inp("000")].iter().cloned().collect();
+		let partial = super::fixed(&eqns[0], &eqns[1], 3, &impossible);
+		assert_eq!((partial.terms.len(), partial.dc.len()), (1, 1));
+		assert_eq!(extend_policy(&DefaultOutput::Bits(vec![true, false]), 2),
+		           DefaultOutput::Bits(vec![true, false, false, true]));
+		assert_eq!(extend_policy(&DefaultOutput::Zero, 1),
+		           DefaultOutput::Bits(vec![false, true]));
+		assert_eq!(extend_policy(&DefaultOutput::DontCare, 1), DefaultOutput::DontCare);
+	}
+}
@@ -0,0 +1,315 @@
+// Whether a table lists each input pattern exactly once.  A pattern no row
+// gives is missing, and --missing says what it is instead; a pattern several
+// rows give is repeated, and --duplicates says which of them counts.  Rows
+// given as cubes count for every pattern they expand to.
+use std::collections::{HashMap, HashSet};
+use super::{Entry, ParseOutcome};
+use super::bits::{BitOrder, bit_unpack};
+use super::formats::expand;
+
+// The most missing or repeated patterns a report lists.
+pub const EXAMPLES: usize = 5;
+
+// What the table's missing input patterns are taken as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Missing {
+	// the table must list every pattern, unless --default-output says otherwise.
+	Error,
+	// as Zero, or as --default-output says, after a warning listing them.
+	Warn,
+	DontCare,
+	Zero,
+}
+
+impl Missing {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"error" => Ok(Missing::Error),
+			"warn" => Ok(Missing::Warn),
+			"dc" => Ok(Missing::DontCare),
+			"zero" => Ok(Missing::Zero),
+			_ => Err(format!("Invalid --missing '{}'; expected 'error', 'warn', 'dc' \
+			                  or 'zero'.", s)),
+		}
+	}
+}
+
+// Which of the rows giving the same input pattern counts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Duplicates {
+	// none: the table is invalid.
+	Error,
+	// the first, if they all give the same outputs; if not, the table is
+	// invalid.  A row of don't-cares gives way to one with outputs, and an
+	// output that is a don't-care on the first to another row's value for it.
+	Merge,
+	// the last in the file, don't-cares or not.
+	LastWins,
+}
+
+impl Duplicates {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"error" => Ok(Duplicates::Error),
+			"merge" => Ok(Duplicates::Merge),
+			"last-wins" => Ok(Duplicates::LastWins),
+			_ => Err(format!("Invalid --duplicates '{}'; expected 'error', 'merge' or \
+			                  'last-wins'.", s)),
+		}
+	}
+}
+
+pub fn pattern(bits: &[bool]) -> String {
+	bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+// "011, 101 and 2 others".
+pub fn examples(patterns: &[Vec<bool>], count: u64) -> String {
+	let strs: Vec<String> = patterns.iter().take(EXAMPLES).map(|p| pattern(p)).collect();
+	match count.saturating_sub(strs.len() as u64) {
+		0 => strs.join(", "),
+		1 => format!("{} and 1 other", strs.join(", ")),
+		n => format!("{} and {} others", strs.join(", "), n),
+	}
+}
+
+// How the rows of a table cover its input patterns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Coverage {
+	// the patterns the rows expand to, counting repeats, and without.
+	pub listed: u64,
+	pub distinct: u64,
+	// the patterns more than one row gives, the first few in order, with the
+	// number of rows giving each.
+	pub repeated: Vec<(Vec<bool>, usize)>,
+	// how many patterns no row gives, and the first few of them in order.
+	pub missing: u64,
+	pub missing_examples: Vec<Vec<bool>>,
+}
+
+impl Coverage {
+	// The rows beyond the first for each repeated pattern.
+	pub fn surplus(&self) -> u64 { self.listed - self.distinct }
+}
+
+// Counts the patterns over 'nbits' inputs that each row's cube expands to.
+// Fails when a cube is too large to expand.
+pub fn coverage(cubes: &[Vec<Option<bool>>], nbits: usize) -> Result<Coverage, String> {
+	let mut counts: HashMap<Vec<bool>, usize> = HashMap::new();
+	let mut listed: u64 = 0;
+	for cube in cubes.iter() {
+		for inp in expand(cube)? {
+			*counts.entry(inp).or_insert(0) += 1;
+			listed += 1;
+		}
+	}
+	let mut repeated: Vec<(Vec<bool>, usize)> = counts.iter().filter(|&(_, &n)| n > 1)
+		.map(|(inp, &n)| (inp.clone(), n)).collect();
+	repeated.sort();
+	repeated.truncate(EXAMPLES);
+	let distinct = counts.len() as u64;
+	// every pattern, where there are more than a u64 counts.
+	let patterns = if nbits < 64 { 1u64 << nbits } else { u64::MAX };
+	let missing = patterns.saturating_sub(distinct);
+	// at most 'distinct' patterns are skipped on the way to the examples.
+	let missing_examples = (0..patterns).map(|i| bit_unpack(i, nbits, BitOrder::Msb))
+		.filter(|inp| !counts.contains_key(inp))
+		.take(std::cmp::min(missing, EXAMPLES as u64) as usize)
+		.collect();
+	Ok(Coverage{listed, distinct, repeated, missing, missing_examples})
+}
+
+// The line a parsed row was read from.
+fn line(e: &Entry) -> usize {
+	e.line().expect("a parsed row's line")
+}
+
+// A row's pattern, line and outputs (None for don't-cares).
+type Row = (Vec<bool>, usize, Option<Vec<Option<bool>>>);
+
+// Applies the policy to the rows giving the same input pattern as another,
+// keeping the lines of the don't-cares left in step, and giving the row kept
+// the frequencies of all of them.  Returns the number of rows dropped, or why
+// the table is invalid.
+pub fn resolve_duplicates(outcome: &mut ParseOutcome, policy: Duplicates) ->
+	Result<usize, String> {
+	// in file order.
+	let mut rows: Vec<Row> = vec![];
+	{
+		let tbl = &outcome.truth;
+		rows.extend(tbl.table.iter().map(|e| (e.input.clone(), line(e), Some(e.cells()))));
+		rows.extend(tbl.dc.iter().zip(outcome.dc_lines.iter())
+			.map(|(inp, &l)| (inp.clone(), l, None)));
+	}
+	rows.sort_by_key(|r| r.1);
+	let mut lines: HashMap<&Vec<bool>, Vec<usize>> = HashMap::new();
+	for r in rows.iter() {
+		lines.entry(&r.0).or_default().push(r.1);
+	}
+	let mut repeated: Vec<(&Vec<bool>, &Vec<usize>)> = lines.iter()
+		.filter(|&(_, ls)| ls.len() > 1).map(|(&inp, ls)| (inp, ls)).collect();
+	if repeated.is_empty() {
+		return Ok(0);
+	}
+	repeated.sort_by_key(|&(_, ls)| ls[0]);
+	let on_lines = |ls: &[usize]| {
+		let strs: Vec<String> = ls.iter().map(|l| l.to_string()).collect();
+		strs.join(", ")
+	};
+	// each row has a line of its own.
+	let outputs = |l: usize| {
+		&rows[rows.binary_search_by_key(&l, |r| r.1).expect("a row's line")].2
+	};
+	if policy == Duplicates::Error {
+		let strs: Vec<String> = repeated.iter().take(EXAMPLES)
+			.map(|&(inp, ls)| format!("{} on lines {}", pattern(inp), on_lines(ls)))
+			.collect();
+		return Err(format!("{} input pattern(s) given more than once: {}.",
+		                   repeated.len(), strs.join("; ")));
+	}
+	// the line of the row to keep for each repeated pattern, and the outputs
+	// of those that merging gave values.
+	let mut keep: HashSet<usize> = HashSet::new();
+	let mut merged: HashMap<usize, Vec<Option<bool>>> = HashMap::new();
+	for &(inp, ls) in repeated.iter() {
+		let kept = match policy {
+			Duplicates::LastWins => *ls.last().unwrap(),
+			_ => {
+				let given: Vec<usize> = ls.iter().cloned()
+					.filter(|&l| outputs(l).is_some()).collect();
+				// the outputs of the first, those it leaves open taking the others'.
+				let mut cells: Option<Vec<Option<bool>>> = None;
+				for other in given.iter().filter_map(|&l| outputs(l).as_ref()) {
+					let first = cells.get_or_insert_with(|| other.clone());
+					for (c, &o) in first.iter_mut().zip(other.iter()) {
+						if c.is_some() && o.is_some() && *c != o {
+							return Err(format!("input pattern {} has different outputs on \
+							                    lines {}.", pattern(inp),
+							                   on_lines(&given)));
+						}
+						if c.is_none() {
+							*c = o;
+						}
+					}
+				}
+				let kept = *given.first().unwrap_or(&ls[0]);
+				if let Some(cells) = cells {
+					merged.insert(kept, cells);
+				}
+				kept
+			},
+		};
+		keep.insert(kept);
+		// the pattern occurs as often as all its rows together.
+		if !outcome.frequencies.is_empty() {
+			let total = ls.iter()
+				.map(|l| outcome.frequencies.get(l).cloned().unwrap_or(1.0)).sum();
+			outcome.frequencies.insert(kept, total);
+		}
+	}
+	let dropped: HashSet<usize> = repeated.iter().flat_map(|&(_, ls)| ls.iter().cloned())
+		.filter(|l| !keep.contains(l)).collect();
+	let tbl = &mut outcome.truth;
+	tbl.table.retain(|e| !dropped.contains(&line(e)));
+	for e in tbl.table.iter_mut() {
+		if let Some(cells) = merged.get(&line(e)) {
+			let rebuilt = Entry::from_cells(e.input.clone(), cells);
+			e.output = rebuilt.output;
+			e.dc = rebuilt.dc;
+		}
+	}
+	let mut kept_dc = vec![];
+	let mut dc = vec![];
+	for (inp, &l) in tbl.dc.drain(..).zip(outcome.dc_lines.iter()) {
+		if !dropped.contains(&l) {
+			dc.push(inp);
+			kept_dc.push(l);
+		}
+	}
+	tbl.dc = dc;
+	outcome.dc_lines = kept_dc;
+	Ok(dropped.len())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{Cells, InputEncoding, OutputEncoding, parse_cells};
+	use super::super::formats::parse_cube;
+
+	fn read(csv: &str) -> ParseOutcome {
+		parse_cells(csv.as_bytes(), 0, 2, 1, InputEncoding::Binary,
+		            OutputEncoding::Binary, Cells::Warn, None).unwrap()
+	}
+
+	fn rows(outcome: &ParseOutcome) -> Vec<String> {
+		let mut rv: Vec<String> = outcome.truth.table.iter()
+			.map(|e| format!("{}:{}={}", line(e), pattern(&e.input), pattern(&e.output)))
+			.collect();
+		rv.extend(outcome.truth.dc.iter().zip(outcome.dc_lines.iter())
+			.map(|(inp, l)| format!("{}:{}=-", l, pattern(inp))));
+		rv
+	}
+
+	#[test]
+	fn policies() {
+		assert_eq!(Missing::parse("dc"), Ok(Missing::DontCare));
+		assert!(Missing::parse("one").is_err());
+		assert_eq!(Duplicates::parse("last-wins"), Ok(Duplicates::LastWins));
+		assert!(Duplicates::parse("first-wins").is_err());
+	}
+
+	#[test]
+	fn cube_coverage() {
+		let cube = |s: &str| -> Vec<Option<bool>> { parse_cube(s, true).unwrap() };
+		// 1-- and -1- overlap in 110 and 111; 000 is the only one missing.
+		let cov = coverage(&[cube("1--"), cube("-1-"), cube("001")], 3).unwrap();
+		assert_eq!((cov.listed, cov.distinct, cov.surplus()), (9, 7, 2));
+		assert_eq!(cov.repeated, vec![(vec![true, true, false], 2),
+		                              (vec![true, true, true], 2)]);
+		assert_eq!((cov.missing, cov.missing_examples.clone()),
+		           (1, vec![vec![false, false, false]]));
+		let cov = coverage(&[cube("1--")], 3).unwrap();
+		assert_eq!(examples(&cov.missing_examples, cov.missing), "000, 001, 010, 011");
+		let cov = coverage(&[cube("11--")], 4).unwrap();
+		assert_eq!(cov.missing, 12);
+		assert_eq!(examples(&cov.missing_examples, cov.missing),
+		           "0000, 0001, 0010, 0011, 0100 and 7 others");
+		assert!(coverage(&[vec![None; 30]], 30).is_err());
+	}
+
+	#[test]
+	fn duplicate_rows() {
+		let csv = "0,0,,0\n0,1,,1\n0,0,,0\n1,0,,2\n1,0,,1\n1,1,,0\n";
+		let mut outcome = read(csv);
+		let err = resolve_duplicates(&mut outcome, Duplicates::Error).unwrap_err();
+		assert_eq!(err, "2 input pattern(s) given more than once: 00 on lines 1, 3; \
+		                 10 on lines 4, 5.");
+		// the don't-care gives way, and the repeated 00 agrees.
+		let mut outcome = read(csv);
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::Merge), Ok(2));
+		assert_eq!(rows(&outcome), vec!["1:00=0", "2:01=1", "5:10=1", "6:11=0"]);
+		let mut outcome = read(csv);
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::LastWins), Ok(2));
+		assert_eq!(rows(&outcome), vec!["2:01=1", "3:00=0", "5:10=1", "6:11=0"]);
+		// a don't-care last wins over outputs.
+		let mut outcome = read("0,0,,1\n0,0,,x\n0,1,,1\n1,0,,1\n1,1,,1\n");
+		resolve_duplicates(&mut outcome, Duplicates::LastWins).unwrap();
+		assert_eq!(rows(&outcome), vec!["3:01=1", "4:10=1", "5:11=1", "2:00=-"]);
+		let mut outcome = read("0,0,,1\n0,0,,0\n");
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::Merge).unwrap_err(),
+		           "input pattern 00 has different outputs on lines 1, 2.");
+		let mut outcome = read("0,0,,1\n0,1,,x\n");
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::Error), Ok(0));
+		// an output left open on one row takes the other's value.
+		let read2 = |csv: &str| parse_cells(csv.as_bytes(), 0, 2, 2, InputEncoding::Binary,
+		                                    OutputEncoding::Binary, Cells::Warn, None)
+			.unwrap();
+		let mut outcome = read2("0,0,,x,1\n0,0,,1,x\n0,1,,0,0\n");
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::Merge), Ok(1));
+		assert_eq!(outcome.truth.table[0].cells(), vec![Some(true), Some(true)]);
+		let mut outcome = read2("0,0,,x,1\n0,0,,1,0\n");
+		assert_eq!(resolve_duplicates(&mut outcome, Duplicates::Merge).unwrap_err(),
+		           "input pattern 00 has different outputs on lines 1, 2.");
+	}
+}
@@ -0,0 +1,206 @@
+// The one completion of the table the covers stand for.  Each output is
+// minimized on its own, its cover giving a don't-care row whichever value
+// was cheapest, which is all the equations need; but the generated code
+// writing out a value per input -- the lookup tables, the jump tables and the
+// other templates with a default branch -- needs the row as a whole.  Each of
+// the table's don't-care rows is completed with the outputs the covers give
+// it and listed with them, rather than falling to the default branch, so that
+// the code agrees with the covers on every input.  So is each row with just
+// some outputs don't-cares, keeping the outputs it gives.
+//
+// An output group is the exception: its values are minimized as conditions
+// of their own, and on a don't-care row those may hold for several values or
+// for none, so that no completion agrees with all of them.  The row is then
+// given the value the group's match gives it, and the conflict is reported.
+use std::collections::BTreeSet;
+use super::{Entry, Equation, Truth};
+use super::complete::pattern;
+use super::symbolic::{self, Group};
+
+// A don't-care row that a group's conditions don't settle: the values whose
+// conditions hold on it, and the one it was given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+	pub input: Vec<bool>,
+	pub holding: Vec<usize>,
+	pub chosen: usize,
+}
+
+impl Conflict {
+	// What to warn of, the values named as 'values' has them.
+	pub fn describe(&self, group: &Group, values: &[Vec<bool>]) -> String {
+		let chosen = pattern(&values[self.chosen]);
+		let holding: Vec<String> = self.holding.iter().map(|&v| pattern(&values[v]))
+			.collect();
+		let how = match holding.len() {
+			0 => "no value's condition holds".to_string(),
+			_ => format!("the conditions of {} all hold", holding.join(", ")),
+		};
+		format!("--group {}: {} for the don't-care row {}; it is completed with {}, as \
+		         the match gives it.", group.name, how, pattern(&self.input), chosen)
+	}
+}
+
+// The table with its rows giving every output first, then the rows with some
+// outputs don't-cares, and after them each don't-care row that isn't also a
+// row of it, every output that was a don't-care given what its cover gives the
+// row; outputs without a cover are 0.  'nout' is the table's outputs.
+// Returns it with the position of the first row completed.
+pub fn complete(tbl: &Truth, eqns: &[Equation], nout: usize) -> (Truth, usize) {
+	let listed: BTreeSet<&Vec<bool>> = tbl.table.iter().map(|e| &e.input).collect();
+	let dc: BTreeSet<&Vec<bool>> = tbl.dc.iter().filter(|inp| !listed.contains(inp))
+		.collect();
+	let (given, partial): (Vec<Entry>, Vec<Entry>) = tbl.table.iter().cloned()
+		.partition(|e| e.dc.is_empty());
+	let from = given.len();
+	let mut rv = Truth{table: given, dc: vec![]};
+	let open = vec![None; nout];
+	let rows = partial.into_iter()
+		.chain(dc.into_iter().map(|inp| Entry::from_cells(inp.clone(), &open)));
+	for mut e in rows {
+		for eqn in eqns.iter().filter(|q| q.index < nout) {
+			if !e.cares(eqn.index) {
+				e.output[eqn.index] = eqn.evaluate(&e.input);
+			}
+		}
+		e.dc.clear();
+		rv.table.push(e);
+	}
+	(rv, from)
+}
+
+// Gives the group's outputs on the completed rows, those from 'from' on, the
+// value the group's match gives each; 'conds' are the conditions of the
+// values.  The rows where the conditions don't hold for exactly one value are
+// the conflicts.
+pub fn resolve(tbl: &mut Truth, from: usize, group: &Group, values: &[Vec<bool>],
+               conds: &[Equation]) -> Vec<Conflict> {
+	let mut rv = vec![];
+	if values.is_empty() {
+		return rv;
+	}
+	for e in tbl.table[from..].iter_mut() {
+		let holding: Vec<usize> = (0..conds.len()).filter(|&v| conds[v].evaluate(&e.input))
+			.collect();
+		let chosen = symbolic::matched(conds, &e.input);
+		for (&o, &b) in group.outputs.iter().zip(values[chosen].iter()) {
+			e.output[o] = b;
+		}
+		if holding.len() != 1 {
+			rv.push(Conflict{input: e.input.clone(), holding, chosen});
+		}
+	}
+	rv
+}
+
+// The completed rows, those from 'from' on, as a CSV table: a line of the
+// column names, then a row each, the inputs and the outputs 'shown'
+// separated by an empty column.
+pub fn csv(tbl: &Truth, from: usize, invars: &[String], outvars: &[String],
+           shown: &[usize]) -> String {
+	let names: Vec<&str> = shown.iter().map(|&o| outvars[o].as_str()).collect();
+	let mut s = format!("{},,{}\n", invars.join(","), names.join(","));
+	for e in tbl.table[from..].iter() {
+		let bit = |b: bool| if b { "1" } else { "0" };
+		let ins: Vec<&str> = e.input.iter().map(|&b| bit(b)).collect();
+		let outs: Vec<&str> = shown.iter().map(|&o| bit(e.output[o])).collect();
+		s += &format!("{},,{}\n", ins.join(","), outs.join(","));
+	}
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse};
+	use super::super::bits::BitOrder;
+	use super::super::emit::{DefaultOutput, Unlisted};
+	use super::super::enumerate::Inputs;
+	use super::super::lut;
+	use super::super::symbolic::parse_groups;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn lut_agrees_with_covers() {
+		// 011 and 111 are don't-cares, 101 and 110 unlisted, given x = 0 and y = 1.
+		let rows = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n1,0,0,,1,1\n1,1,1,,x,x\n\
+		           0,1,1,,?,?\n";
+		let tbl = parse(rows.as_bytes(), 0, 3, 2);
+		assert_eq!(tbl.dc.len(), 2);
+		let invars = names(&["a", "b", "c"]);
+		let mut eqns = equations(&tbl, vec!["x", "y"], invars.clone());
+		let policy = DefaultOutput::Bits(vec![false, true]);
+		let missing = [vec![true, false, true], vec![true, true, false]];
+		for eqn in eqns.iter_mut() {
+			if eqn.index == 1 {
+				eqn.terms.extend(missing.iter().map(|inp| {
+					let mut t = super::super::Term::compute(inp);
+					t.names = invars.clone();
+					t
+				}));
+			}
+			eqn.simplify();
+		}
+		let (whole, from) = complete(&tbl, &eqns, 2);
+		assert_eq!(from, 4);
+		let added: Vec<&Vec<bool>> = whole.table[from..].iter().map(|e| &e.input)
+			.collect();
+		assert_eq!(added, vec![&vec![false, true, true], &vec![true, true, true]]);
+		assert!(whole.dc.is_empty());
+		for &(ref unlisted, agrees) in [(Unlisted::new(policy.clone(), &tbl, 3, &invars),
+		                                 false),
+		                                (Unlisted::new(policy.clone(), &whole, 3, &invars),
+		                                 true)].iter() {
+			let entries = lut::entries(&eqns, 3, unlisted, BitOrder::Lsb);
+			let inputs = Inputs::every(3).ordered(BitOrder::Lsb);
+			let same = inputs.zip(entries).all(|(inp, v)| {
+				let covers: Vec<bool> = eqns.iter().map(|e| e.evaluate(&inp)).collect();
+				super::super::bits::bit_pack_wide(&covers, BitOrder::Lsb) == v
+			});
+			assert_eq!(same, agrees);
+		}
+		let out = csv(&whole, from, &invars, &names(&["x", "y"]), &[1]);
+		assert_eq!(out, format!("a,b,c,,y\n0,1,1,,{}\n1,1,1,,{}\n",
+		                        eqns[1].evaluate(&[false, true, true]) as u8,
+		                        eqns[1].evaluate(&[true, true, true]) as u8));
+	}
+
+	#[test]
+	fn partial_rows() {
+		// x is a don't-care on 01, which gives y; the row goes after the others.
+		let tbl = parse("0,0,,0,1\n0,1,,x,1\n1,0,,1,0\n1,1,,1,1\n".as_bytes(), 0, 2, 2);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names(&["a", "b"]));
+		eqns.iter_mut().for_each(Equation::simplify);
+		let (whole, from) = complete(&tbl, &eqns, 2);
+		assert_eq!(from, 3);
+		assert_eq!(whole.table[3].input, vec![false, true]);
+		assert_eq!(whole.table[3].output, vec![eqns[0].evaluate(&[false, true]), true]);
+		assert!(whole.table.iter().all(|e| e.dc.is_empty()));
+	}
+
+	#[test]
+	fn group_conflicts() {
+		// s1 s0 is 00 on a'b', 01 on a'b and 10 on ab', with ab a don't-care.
+		let tbl = parse("0,0,,0,0\n0,1,,0,1\n1,0,,1,0\n1,1,,x,x\n".as_bytes(), 0, 2, 2);
+		let outvars = names(&["s1", "s0"]);
+		let groups = parse_groups(vec!["sel=s1,s0"], &outvars).unwrap();
+		let values = symbolic::values(&groups[0], &tbl);
+		let mut conds = symbolic::equations(&groups[0], &values, &tbl,
+		                                    &names(&["a", "b"]));
+		conds.iter_mut().for_each(Equation::simplify);
+		// 01 = b and 10 = a both hold for 11.
+		let (mut whole, _) = complete(&tbl, &[], 2);
+		let conflicts = resolve(&mut whole, 3, &groups[0], &values, &conds);
+		assert_eq!(conflicts.len(), 1);
+		assert_eq!(conflicts[0].holding, vec![1, 2]);
+		let chosen = &values[conflicts[0].chosen];
+		assert_eq!(&whole.table[3].output, chosen);
+		assert_eq!(conflicts[0].describe(&groups[0], &values),
+		           format!("--group sel: the conditions of 01, 10 all hold for the \
+		                    don't-care row 11; it is completed with {}, as the match \
+		                    gives it.", pattern(chosen)));
+	}
+}
@@ -0,0 +1,174 @@
+// Composing two tables, where some outputs of the first feed inputs of the
+// second: the composite maps the first table's inputs, and the second's inputs
+// no output feeds, straight to the second's outputs, so a pipeline of two
+// stages can be minimized end to end.
+//
+// The composite is built by evaluating both tables on every input pattern.
+// An input the first table doesn't list, or lists with don't-care outputs,
+// is a don't-care of the composite, as is one that leads the second table to
+// such an input.
+use super::{MAX_ENUMERATED_BITS, Truth};
+
+// A table with the names of its inputs and outputs.
+pub struct Stage<'a> {
+	pub truth: &'a Truth,
+	pub invars: &'a [String],
+	pub outvars: &'a [String],
+}
+
+// The composite table and the names of its inputs and outputs.
+#[derive(Debug)]
+pub struct Composite {
+	pub truth: Truth,
+	pub invars: Vec<String>,
+	pub outvars: Vec<String>,
+}
+
+// Parses the --wire options, each a list of "aout=bin" pairs separated by
+// commas, into (output of a, input of b) pairs.  Every input of b is fed by
+// at most one output; an output may feed several inputs.
+pub fn parse_wires(specs: Vec<&str>, a: &Stage, b: &Stage) ->
+	Result<Vec<(usize, usize)>, String> {
+	let mut rv: Vec<(usize, usize)> = vec![];
+	for pair in specs.iter().flat_map(|s| s.split(',')).map(|p| p.trim()) {
+		let (from, to) = match pair.find('=') {
+			Some(eq) => (pair[..eq].trim(), pair[eq+1..].trim()),
+			None => return Err(format!("Invalid --wire '{}'; expected <aout>=<bin>.",
+			                           pair)),
+		};
+		let out = match a.outvars.iter().position(|n| n == from) {
+			Some(o) => o,
+			None => return Err(format!("'{}' in --wire '{}' is not an output of the first \
+			                            table.", from, pair)),
+		};
+		let inp = match b.invars.iter().position(|n| n == to) {
+			Some(i) => i,
+			None => return Err(format!("'{}' in --wire '{}' is not an input of the second \
+			                            table.", to, pair)),
+		};
+		if rv.iter().any(|&(_, i)| i == inp) {
+			return Err(format!("Input '{}' of the second table is wired more than once.",
+			                   to));
+		}
+		rv.push((out, inp));
+	}
+	if rv.is_empty() {
+		return Err("--wire connects no output to an input.".to_string());
+	}
+	Ok(rv)
+}
+
+// Composes the tables.  The composite's inputs are a's followed by b's that
+// no wire feeds, and its outputs b's followed, with 'carry', by a's outputs
+// that feed nothing.
+pub fn compose(a: &Stage, b: &Stage, wires: &[(usize, usize)], carry: bool) ->
+	Result<Composite, String> {
+	let free: Vec<usize> = (0..b.invars.len())
+		.filter(|&i| wires.iter().all(|&(_, w)| w != i)).collect();
+	let carried: Vec<usize> = (0..a.outvars.len())
+		.filter(|&o| carry && wires.iter().all(|&(w, _)| w != o)).collect();
+	let mut invars = a.invars.to_vec();
+	invars.extend(free.iter().map(|&i| b.invars[i].clone()));
+	let mut outvars = b.outvars.to_vec();
+	outvars.extend(carried.iter().map(|&o| a.outvars[o].clone()));
+	for names in [&invars, &outvars].iter() {
+		for (k, n) in names.iter().enumerate() {
+			if names[..k].contains(n) {
+				return Err(format!("Both tables have a variable '{}'; the composite \
+				                    needs distinct names.", n));
+			}
+		}
+	}
+	if invars.len() > MAX_ENUMERATED_BITS {
+		return Err(format!("The composite has {} inputs; at most {} are supported.",
+		                   invars.len(), MAX_ENUMERATED_BITS));
+	}
+	let (first, second) = (a.truth.index(), b.truth.index());
+	let nin = a.invars.len();
+	let truth = Truth::from_fn_partial(invars.len(), outvars.len(), |inp| {
+		let mid = &a.truth.table[first.position(&inp[..nin])?].output;
+		let mut binp = vec![false; b.invars.len()];
+		for &(o, i) in wires.iter() {
+			binp[i] = mid[o];
+		}
+		for (&i, &v) in free.iter().zip(inp[nin..].iter()) {
+			binp[i] = v;
+		}
+		let out = &b.truth.table[second.position(&binp)?].output;
+		let mut rv = out.to_vec();
+		rv.extend(carried.iter().map(|&o| mid[o]));
+		Some(rv)
+	})?;
+	Ok(Composite{truth, invars, outvars})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	// a: p, q -> s = p^q, c = pq (a half adder); b: s, r -> y = s|r, z = s&r'.
+	const A: &str = "0,0,,0,0\n0,1,,1,0\n1,0,,1,0\n1,1,,0,1\n";
+	const B: &str = "0,0,,0,0\n0,1,,1,0\n1,0,,1,1\n1,1,,1,0\n";
+
+	#[test]
+	fn composite() {
+		let (ta, tb) = (parse(A.as_bytes(), 0, 2, 2), parse(B.as_bytes(), 0, 2, 2));
+		let (ain, aout) = (names(&["p", "q"]), names(&["s", "c"]));
+		let (bin, bout) = (names(&["t", "r"]), names(&["y", "z"]));
+		let a = Stage{truth: &ta, invars: &ain, outvars: &aout};
+		let b = Stage{truth: &tb, invars: &bin, outvars: &bout};
+		let wires = parse_wires(vec!["s=t"], &a, &b).unwrap();
+		assert_eq!(wires, vec![(0, 0)]);
+		let c = compose(&a, &b, &wires, true).unwrap();
+		assert_eq!((c.invars, c.outvars),
+		           (names(&["p", "q", "r"]), names(&["y", "z", "c"])));
+		// every row as evaluating the two tables by hand gives it.
+		for e in c.truth.table.iter() {
+			let (p, q, r) = (e.input[0], e.input[1], e.input[2]);
+			let s = p != q;
+			assert_eq!(e.output, vec![s || r, s && !r, p && q], "{:?}", e.input);
+		}
+		assert_eq!((c.truth.len(), c.truth.dc.len()), (8, 0));
+	}
+
+	#[test]
+	fn unlisted_rows() {
+		// without a's row for 11 and b's for 10, the inputs leading there are
+		// don't-cares.
+		let ta = parse("0,0,,0,0\n0,1,,1,0\n1,0,,1,0\n".as_bytes(), 0, 2, 2);
+		let tb = parse("0,0,,0,0\n0,1,,1,0\n1,1,,1,0\n".as_bytes(), 0, 2, 2);
+		let (ain, aout) = (names(&["p", "q"]), names(&["s", "c"]));
+		let (bin, bout) = (names(&["t", "r"]), names(&["y", "z"]));
+		let a = Stage{truth: &ta, invars: &ain, outvars: &aout};
+		let b = Stage{truth: &tb, invars: &bin, outvars: &bout};
+		let c = compose(&a, &b, &[(0, 0)], false).unwrap();
+		assert_eq!(c.outvars, names(&["y", "z"]));
+		assert_eq!(c.truth.dc, vec![vec![false, true, false], vec![true, false, false],
+		                            vec![true, true, false], vec![true, true, true]]);
+	}
+
+	#[test]
+	fn bad_wires() {
+		let t = Truth::default();
+		let (ain, aout) = (names(&["p", "q"]), names(&["s", "c"]));
+		let (bin, bout) = (names(&["t", "r"]), names(&["y"]));
+		let a = Stage{truth: &t, invars: &ain, outvars: &aout};
+		let b = Stage{truth: &t, invars: &bin, outvars: &bout};
+		assert_eq!(parse_wires(vec!["s=u"], &a, &b).unwrap_err(),
+		           "'u' in --wire 's=u' is not an input of the second table.");
+		assert_eq!(parse_wires(vec!["y=t"], &a, &b).unwrap_err(),
+		           "'y' in --wire 'y=t' is not an output of the first table.");
+		assert_eq!(parse_wires(vec!["s=t,c=t"], &a, &b).unwrap_err(),
+		           "Input 't' of the second table is wired more than once.");
+		assert!(parse_wires(vec!["s"], &a, &b).is_err());
+		// r is a free input of b, clashing with a's input of that name.
+		let ain = names(&["p", "r"]);
+		let a = Stage{truth: &t, invars: &ain, outvars: &aout};
+		assert!(compose(&a, &b, &[(0, 0)], false).unwrap_err().contains("'r'"));
+	}
+}
@@ -0,0 +1,115 @@
+// Table files stored compressed, as large generated tables are checked in:
+// gzip always, and zstd when minterm is built with the 'zstd' feature.  The
+// data is recognized by its magic bytes, or failing those by the name's
+// extension, and decompressed whole before any parser sees it, so that a
+// corrupt or truncated archive is reported as one rather than as rows the
+// parser can't make sense of.  Anything else is passed on as it is.
+use std::io::Read;
+use flate2::read::MultiGzDecoder;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+	Plain,
+	Gzip,
+	Zstd,
+}
+
+impl Compression {
+	// How the data named 'name' is stored: by its first bytes, or by the
+	// extension when they are no archive's, so that an archive whose header is
+	// damaged isn't read as text.
+	pub fn detect(name: &str, data: &[u8]) -> Self {
+		if data.starts_with(GZIP_MAGIC) {
+			Compression::Gzip
+		} else if data.starts_with(ZSTD_MAGIC) {
+			Compression::Zstd
+		} else if name.ends_with(".gz") {
+			Compression::Gzip
+		} else if name.ends_with(".zst") {
+			Compression::Zstd
+		} else {
+			Compression::Plain
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Compression::Plain => "plain",
+			Compression::Gzip => "gzip",
+			Compression::Zstd => "zstd",
+		}
+	}
+}
+
+#[cfg(feature = "zstd")]
+fn unzstd(data: &[u8]) -> Result<Vec<u8>, String> {
+	::zstd::stream::decode_all(data).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "zstd"))]
+fn unzstd(_: &[u8]) -> Result<Vec<u8>, String> {
+	Err("minterm was built without the 'zstd' feature to read it".to_string())
+}
+
+// The data of 'name', decompressed if it is an archive.
+pub fn decompress(name: &str, data: Vec<u8>) -> Result<Vec<u8>, String> {
+	let how = Compression::detect(name, &data);
+	let rv = match how {
+		Compression::Plain => return Ok(data),
+		Compression::Gzip => {
+			let mut rv = vec![];
+			MultiGzDecoder::new(&data[..]).read_to_end(&mut rv).map(|_| rv)
+				.map_err(|e| e.to_string())
+		},
+		Compression::Zstd => unzstd(&data),
+	};
+	rv.map_err(|e| format!("{} is a {} archive that can't be decompressed: {}.", name,
+	                       how.name(), e))
+}
+
+// Reads all of 'r', the contents of 'name', decompressing them.
+pub fn read_all<R: Read>(name: &str, mut r: R) -> Result<Vec<u8>, String> {
+	let mut data = vec![];
+	if let Err(e) = r.read_to_end(&mut data) {
+		return Err(format!("Cannot read {}: {}", name, e));
+	}
+	decompress(name, data)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::io::Write;
+	use flate2::Compression as Level;
+	use flate2::write::GzEncoder;
+	use super::super::parse;
+
+	fn gzip(data: &[u8]) -> Vec<u8> {
+		let mut enc = GzEncoder::new(vec![], Level::default());
+		enc.write_all(data).unwrap();
+		enc.finish().unwrap()
+	}
+
+	#[test]
+	fn gzipped_tables() {
+		let csv = "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n\
+		           0,1,1,,0,0\n1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		let gz = gzip(csv.as_bytes());
+		assert_eq!(Compression::detect("t.csv", &gz), Compression::Gzip);
+		assert_eq!(Compression::detect("t.csv.gz", b"a,b"), Compression::Gzip);
+		assert_eq!(Compression::detect("t.csv", csv.as_bytes()), Compression::Plain);
+		let read = read_all("t.csv.gz", &gz[..]).unwrap();
+		let (plain, unzipped) = (parse(csv.as_bytes(), 2, 3, 2),
+		                         parse(&read[..], 2, 3, 2));
+		assert_eq!((unzipped.table, unzipped.dc), (plain.table, plain.dc));
+		// plain data is passed on as it is.
+		assert_eq!(read_all("t.csv", csv.as_bytes()).unwrap(), csv.as_bytes());
+		let cut = &gz[..gz.len() / 2];
+		let err = read_all("t.csv.gz", cut).unwrap_err();
+		assert!(err.starts_with("t.csv.gz is a gzip archive that can't be decompressed: "),
+		        "{}", err);
+	}
+}
@@ -0,0 +1,182 @@
+// 'minterm convert': rewrites a table in another file format, or with other
+// input and output encodings, without minimizing it unless asked to.
+use std::fs::File;
+use std::path::Path;
+use minterm::{check_outputs, equations, exact, output_names, progress, write_encoded,
+              EmptyTable, Truth, MAX_ENUMERATED_BITS};
+use minterm::formats::{self, Sheet};
+use super::{Failure, bit_order, choice, encodings, read_table, table_data, warn};
+
+pub const USAGE: &str = concat!("
+Usage: minterm convert <in> <out> [--ivar=<foo>]... [--ovar=<bar>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm convert --help
+
+Reads the table <in> and writes it to <out>, or to stdout if <out> is '-', in
+the --to format.  CSV tables need --ivar and --ovar to name their variables;
+PLA and JSON files name their own, which --ivar and --ovar then replace.  CSV
+has no way to write don't-care rows or comments, so converting to it drops
+them, with a warning.
+
+Options:
+  --from=<fmt>       The format of <in>: 'csv', 'pla' or 'json'.  By default,
+                     the extension of <in> says, and otherwise 'csv'.
+  --to=<fmt>         The format of <out>, likewise.
+  --minimize         Minimize every output first, and write the terms of the
+                     equations as the rows: a PLA cover, as espresso writes.
+  --allow-empty      Convert a table with no rows with outputs, such as a
+                     header alone, rather than failing.
+  --to-input-encoding=<enc>  How to store the inputs in a CSV <out>:
+                     'binary', 'index' or 'row', as below.  [default: binary]
+  --to-output-encoding=<enc>  How to store the outputs in a CSV <out>:
+                     'binary' or 'decimal', as below.  [default: binary]", table_options!());
+
+const FORMATS: [&str; 3] = ["csv", "pla", "json"];
+
+// The format named by the option, or by the file's extension.
+fn format(args: &::docopt::ArgvMap, opt: &str, file: &str) ->
+	Result<&'static str, Failure> {
+	if !args.get_str(opt).is_empty() {
+		return choice(args, opt, &FORMATS);
+	}
+	let ext = Path::new(file).extension().and_then(|e| e.to_str()).unwrap_or("");
+	Ok(FORMATS.iter().find(|&&f| f == ext.to_lowercase()).cloned().unwrap_or("csv"))
+}
+
+// Replaces the names the file gave with those of the option, if any.
+fn rename(names: &mut Vec<String>, given: &[String], file: &str, what: &str,
+          opt: &str) -> Result<(), Failure> {
+	if given.is_empty() {
+		return Ok(());
+	}
+	if given.len() != names.len() {
+		fail!(Usage, "{} has {} {}, but {} {} names were given.", file, names.len(),
+		      what, given.len(), opt);
+	}
+	*names = given.to_vec();
+	Ok(())
+}
+
+fn read(args: &::docopt::ArgvMap, file: &str, from: &str) -> Result<Sheet, Failure> {
+	let invars: Vec<String> = args.get_vec("--ivar").iter().map(|s| s.to_string())
+		.collect();
+	let outvars = output_names(args.get_vec("--ovar"), args.get_str("--ovar-width"))
+		.map_err(Failure::Usage)?;
+	if from == "csv" {
+		if invars.is_empty() || outvars.is_empty() {
+			fail!(Usage, "Reading a CSV table needs --ivar and --ovar.");
+		}
+		let (inenc, outenc) = encodings(args)?;
+		let tbl = read_table(args, file, invars.len(), outvars.len(), inenc, outenc)?;
+		// as in a PLA, a row can't leave just some of its outputs open.
+		if let Some(e) = tbl.rows().iter().find(|e| !e.dc.is_empty()) {
+			fail!(Invalid, "Error parsing {}: line {}: don't-cares for some outputs of a \
+			                row aren't supported.", file, e.line().unwrap_or(0));
+		}
+		return Ok(Sheet::from_truth(&tbl, &invars, &outvars));
+	}
+	if ["--filter", "--filter-out", "--ocol", "--map"].iter()
+		.any(|o| !args.get_vec(o).is_empty()) {
+		fail!(Usage, "--filter, --filter-out, --ocol and --map only apply to CSV tables.");
+	}
+	let data = table_data(file)?;
+	let mut sheet = match if from == "pla" { formats::read_pla(&data[..]) }
+	                      else { formats::read_json(&data[..]) } {
+		Ok(s) => s,
+		Err(ref e) if e.invalid => fail!(Invalid, "Error parsing {}: {}", file, e),
+		Err(e) => fail!(Usage, "Error parsing {}: {}", file, e),
+	};
+	rename(&mut sheet.invars, &invars, file, "inputs", "--ivar")?;
+	rename(&mut sheet.outvars, &outvars, file, "outputs", "--ovar")?;
+	Ok(sheet)
+}
+
+// The cover of the table's minimized equations, and the complete table they
+// compute.
+fn minimize(args: &::docopt::ArgvMap, sheet: &Sheet, tbl: &Truth) ->
+	Result<(Sheet, Truth), Failure> {
+	let nin = sheet.invars.len();
+	let listed = tbl.len() + tbl.dont_cares().count();
+	if !sheet.unlisted_zero && (nin > MAX_ENUMERATED_BITS || listed < 1 << nin) {
+		warn(args, &["inputs the table doesn't list are 0 in the minimized cover."
+		             .to_string()])?;
+	}
+	let outvars: Vec<&str> = sheet.outvars.iter().map(|s| s.as_str()).collect();
+	// a table of don't-cares, or of nothing, is covered by no terms.
+	let mut eqns = vec![];
+	if !tbl.rows().is_empty() {
+		eqns = equations(tbl, outvars, sheet.invars.clone());
+		::minterm::minimize(&mut eqns, nin, false, &vec![1; nin],
+		                    &mut exact::Budget::unlimited(), &mut progress::Silent);
+	}
+	let mut cover = Sheet::from_equations(&eqns, &sheet.invars, &sheet.outvars);
+	cover.comments = sheet.comments.clone();
+	let computed = if eqns.is_empty() { cover.to_truth() }
+	               else { Truth::from_equations(&eqns, nin) };
+	Ok((cover, computed.map_err(Failure::Invalid)?))
+}
+
+fn write<W: ::std::io::Write>(wrt: &mut W, args: &::docopt::ArgvMap, sheet: &Sheet,
+                              tbl: &Truth, to: &str) -> Result<(), Failure> {
+	let written = match to {
+		"pla" => formats::write_pla(wrt, sheet),
+		"json" => formats::write_json(wrt, sheet),
+		_ => {
+			let order = bit_order(args)?;
+			let inenc = match choice(args, "--to-input-encoding",
+			                         &["binary", "index", "row"])? {
+				"binary" => ::minterm::InputEncoding::Binary,
+				"index" => ::minterm::InputEncoding::Index{order},
+				_ => ::minterm::InputEncoding::Row{order},
+			};
+			let outenc = match choice(args, "--to-output-encoding",
+			                          &["binary", "decimal"])? {
+				"binary" => ::minterm::OutputEncoding::Binary,
+				_ => ::minterm::OutputEncoding::Decimal{order},
+			};
+			let mut warnings: Vec<String> = vec![];
+			if tbl.dont_cares().next().is_some() {
+				warnings.push(format!("CSV cannot hold don't-cares; dropping {} don't-care \
+				                       input(s).", tbl.dont_cares().count()));
+			}
+			if !sheet.comments.is_empty() {
+				warnings.push(format!("CSV cannot hold comments; dropping {}.",
+				                      sheet.comments.len()));
+			}
+			warn(args, &warnings)?;
+			write_encoded(wrt, tbl, &sheet.invars, &sheet.outvars, inenc, outenc)
+		},
+	};
+	written.map_err(Failure::Invalid)
+}
+
+pub fn run(args: &::docopt::ArgvMap, note: &dyn Fn(String)) -> Result<(), Failure> {
+	let (infile, outfile) = (args.get_str("<in>"), args.get_str("<out>"));
+	// a compressed table is named for its format before the .gz or .zst.
+	let from = format(args, "--from",
+	                  infile.trim_end_matches(".gz").trim_end_matches(".zst"))?;
+	let to = format(args, "--to", outfile)?;
+	let mut sheet = read(args, infile, from)?;
+	let mut tbl = sheet.to_truth().map_err(Failure::Invalid)?;
+	match check_outputs(&tbl, sheet.outvars.len()) {
+		Err(EmptyTable::NoRows) if args.get_bool("--allow-empty") => {},
+		checked => checked?,
+	}
+	if args.get_bool("--minimize") {
+		let (cover, computed) = minimize(args, &sheet, &tbl)?;
+		sheet = cover;
+		tbl = computed;
+	}
+	match outfile {
+		"-" => {
+			let stdout = ::std::io::stdout();
+			let mut lock = stdout.lock();
+			write(&mut lock, args, &sheet, &tbl, to)?
+		},
+		out => match File::create(out) {
+			Ok(mut fp) => write(&mut fp, args, &sheet, &tbl, to)?,
+			Err(e) => fail!(Usage, "Cannot create {}: {}", out, e),
+		},
+	};
+	note(format!("Converted {} rows.", sheet.rows.len()));
+	Ok(())
+}
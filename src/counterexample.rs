@@ -0,0 +1,127 @@
+// Reports of the rows an --assert-eq equation gets wrong, for 'check --report':
+// how many there are, the first few with the table's output and the
+// equation's, and a minimized counterexample.  That is the cube grown from the
+// first wrong row by taking out, one input at a time, each literal whose
+// removal leaves only rows the equation gets wrong the same way: with 16
+// inputs, "a'c" says far more about the mistake than the row it came from.
+use super::{Equation, RowSource, Term, Truth, describe_sources};
+
+pub struct Counterexample {
+	pub input: Vec<bool>,
+	// what the table has; the equation gives the opposite.
+	pub expected: bool,
+	pub sources: Vec<RowSource>,
+}
+
+pub struct Report {
+	pub assertion: String,
+	pub output: String,
+	pub rows: usize,
+	pub mismatches: usize,
+	pub counterexamples: Vec<Counterexample>,
+	// the cube and how many rows of the table it has.
+	pub minimized: Option<(Term, usize)>,
+}
+
+// Checks the equation parsed from 's' against the table, keeping the first
+// 'max' counterexamples.
+pub fn report(eqn: &Equation, s: &str, tbl: &Truth, invars: &[String], max: usize) ->
+	Report {
+	let bad = eqn.counterexamples(tbl);
+	let counterexamples = bad.iter().take(max).map(|ent| Counterexample{
+		input: ent.input.clone(), expected: ent.output[eqn.index],
+		sources: ent.sources.clone(),
+	}).collect();
+	Report{assertion: s.to_string(), output: eqn.varname.clone(), rows: tbl.len(),
+	       mismatches: bad.len(), counterexamples,
+	       minimized: bad.first().map(|ent| shrink(eqn, tbl, &ent.input, invars))}
+}
+
+// The cube with the fewest literals, found greedily, that holds 'input' and
+// whose rows the equation all gets wrong like that one.  Input patterns the
+// table has no row for don't count either way, nor do rows where the output
+// is a don't-care.  Returns it with the number of its rows.
+pub fn shrink(eqn: &Equation, tbl: &Truth, input: &[bool], names: &[String]) ->
+	(Term, usize) {
+	let wrong = |cube: &[Option<bool>]| -> Option<usize> {
+		let mut n = 0;
+		for ent in tbl.table.iter() {
+			let inside = cube.iter().zip(ent.input.iter())
+				.all(|(c, &b)| c.is_none_or(|v| v == b));
+			if !inside || !ent.cares(eqn.index) {
+				continue;
+			}
+			// the equation gives the opposite of the table, and of it for 'input'.
+			if eqn.evaluate(&ent.input) == ent.output[eqn.index] ||
+			   eqn.evaluate(&ent.input) != eqn.evaluate(input) {
+				return None;
+			}
+			n += 1;
+		}
+		Some(n)
+	};
+	let mut cube: Vec<Option<bool>> = input.iter().map(|&b| Some(b)).collect();
+	// the row 'input' is from, at least.
+	let mut rows = wrong(&cube).unwrap_or(1);
+	for k in 0..cube.len() {
+		cube[k] = None;
+		match wrong(&cube) {
+			Some(n) => rows = n,
+			None => cube[k] = Some(input[k]),
+		}
+	}
+	let bits = cube.iter().enumerate().filter_map(|(i, b)| b.map(|v| (i, v))).collect();
+	(Term{bits, names: names.to_vec()}, rows)
+}
+
+// The reports as a JSON object, an entry per assertion.
+pub fn json(reports: &[Report], invars: &[String]) -> String {
+	let entries: Vec<serde_json::Value> = reports.iter().map(|r| {
+		let counterexamples: Vec<serde_json::Value> = r.counterexamples.iter()
+			.map(|c| {
+				let inputs: serde_json::Map<String, serde_json::Value> = invars.iter()
+					.zip(c.input.iter())
+					.map(|(n, &b)| (n.clone(), serde_json::json!(b as u8))).collect();
+				serde_json::json!({
+					"inputs": inputs, "expected": c.expected as u8,
+					"computed": !c.expected as u8, "source": describe_sources(&c.sources),
+				})
+			}).collect();
+		let minimized = r.minimized.as_ref().map(|&(ref t, n)| serde_json::json!({
+			"cube": t.to_string(), "rows": n,
+		}));
+		serde_json::json!({
+			"assertion": r.assertion, "output": r.output, "holds": r.mismatches == 0,
+			"rows": r.rows, "mismatches": r.mismatches,
+			"counterexamples": counterexamples, "minimized": minimized,
+		})
+	}).collect();
+	serde_json::json!({"assertions": entries}).to_string()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse_assertion;
+
+	#[test]
+	fn pinpoints_a_wrong_term() {
+		// the table is x = ab over 6 inputs, and the equation has a term too many,
+		// a'c, which is 1 on 16 rows where the table has 0.
+		let names: Vec<String> = ["a", "b", "c", "d", "e", "f"].iter()
+			.map(|s| s.to_string()).collect();
+		let tbl = Truth::from_fn(6, 1, |inp| vec![inp[0] && inp[1]]).unwrap();
+		let eqn = parse_assertion("x = ab + a'c", &["x"], &names).unwrap();
+		let r = report(&eqn, "x = ab + a'c", &tbl, &names, 3);
+		assert_eq!((r.rows, r.mismatches, r.counterexamples.len()), (64, 16, 3));
+		assert!(r.counterexamples.iter().all(|c| !c.expected && !c.input[0] && c.input[2]));
+		let (ref cube, rows) = *r.minimized.as_ref().unwrap();
+		assert_eq!((cube.to_string(), rows), ("a'c".to_string(), 16));
+		let json = json(&[r], &names);
+		assert!(json.contains("\"minimized\":{\"cube\":\"a'c\",\"rows\":16}"), "{}", json);
+		assert!(json.contains("\"holds\":false"), "{}", json);
+		let eqn = parse_assertion("x = ab", &["x"], &names).unwrap();
+		let r = report(&eqn, "x = ab", &tbl, &names, 3);
+		assert_eq!((r.mismatches, r.minimized.is_none()), (0, true));
+	}
+}
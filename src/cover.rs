@@ -0,0 +1,296 @@
+// Cover files: the equations minimize found, written by --emit-cover and read
+// back by --from-cover, so that a slow minimization can run once, elsewhere,
+// and code be generated from its result without the table.  A cover file is
+// text, a line of words each, '#' starting a comment:
+//
+//   minterm-cover 1
+//   inputs a b c
+//   outputs x y
+//   algorithm exact terms
+//   default-output zero
+//   listed all
+//   output x proven 2 3
+//   1-0
+//   -11
+//   output y greedy 1 1
+//   --0
+//   end
+//
+// The first line gives the version of the format; a file of another version
+// is refused rather than misread.  'algorithm' is what minimized the table,
+// the --algorithm, 'mixed' with --algorithm-for, and the --cost, and is only
+// recorded.  'default-output' and 'listed', the latter 'all' or a number of
+// cubes covering the inputs the table lists, are what the generators need for
+// the inputs it doesn't.  Each 'output' line names an output; whether its
+// cover is 'proven' minimal by the exact search, the 'greedy' simplifier's,
+// taken to be minimal but not proven so, or 'unproven', a search or
+// simplifier stopping short; and its numbers of terms and literals, the terms
+// following as cubes over the inputs, '-' for an input the term doesn't test.
+// 'end' ends the file, so a truncated one is noticed.
+use super::{Equation, Term, Truth};
+use super::emit::{DefaultOutput, Unlisted};
+use super::exact::{Cube, cube_to_term, term_cube};
+use super::formats::{cube_string, parse_cube};
+
+// The version of the format written, and the only one read.
+pub const COVER_VERSION: u32 = 1;
+
+pub struct Cover {
+	pub invars: Vec<String>,
+	// every output of the table, whether or not the cover has its equation.
+	pub outvars: Vec<String>,
+	pub algorithm: String,
+	pub cost: String,
+	pub unlisted: Unlisted,
+	pub eqns: Vec<Equation>,
+	// per equation, whether it is taken to be minimal, and whether it is
+	// proven so.
+	pub minimal: Vec<bool>,
+	pub proven: Vec<bool>,
+}
+
+fn literals(eqn: &Equation) -> usize {
+	eqn.terms.iter().map(|t| t.len()).sum()
+}
+
+fn policy_string(policy: &DefaultOutput) -> String {
+	match *policy {
+		DefaultOutput::Zero => "zero".to_string(),
+		DefaultOutput::DontCare => "dc".to_string(),
+		DefaultOutput::Panic => "panic".to_string(),
+		DefaultOutput::Bits(ref bits) => bits.iter().map(|&b| if b { '1' } else { '0' })
+			.collect(),
+	}
+}
+
+// The cover file.  Fails on a variable name a word can't hold.
+pub fn write(cov: &Cover) -> Result<String, String> {
+	let unwritable = |n: &&String| n.is_empty() || n.contains(char::is_whitespace);
+	if let Some(n) = cov.invars.iter().chain(cov.outvars.iter()).find(unwritable) {
+		return Err(format!("The variable name '{}' can't be written to a cover file.", n));
+	}
+	let words = |ns: &[String]| ns.join(" ");
+	let mut rv = format!("minterm-cover {}\ninputs {}\noutputs {}\nalgorithm {} {}\n\
+	                      default-output {}\n", COVER_VERSION, words(&cov.invars),
+	                     words(&cov.outvars), cov.algorithm, cov.cost,
+	                     policy_string(&cov.unlisted.policy));
+	let nbits = cov.invars.len();
+	let cubes = |terms: &[Term]| -> String {
+		terms.iter().map(|t| cube_string(&term_cube(t, nbits)) + "\n").collect()
+	};
+	match cov.unlisted.listed {
+		None => rv += "listed all\n",
+		Some(ref listed) => {
+			rv += &format!("listed {}\n", listed.terms.len());
+			rv += &cubes(&listed.terms);
+		},
+	}
+	for (e, eqn) in cov.eqns.iter().enumerate() {
+		let status = if cov.proven[e] { "proven" }
+		             else if cov.minimal[e] { "greedy" }
+		             else { "unproven" };
+		rv += &format!("output {} {} {} {}\n", eqn.varname, status, eqn.terms.len(),
+		               literals(eqn));
+		rv += &cubes(&eqn.terms);
+	}
+	rv += "end\n";
+	Ok(rv)
+}
+
+fn read_cube(s: &str, nbits: usize) -> Result<Cube, String> {
+	if s.chars().count() != nbits {
+		return Err(format!("the cube '{}' should have {} inputs", s, nbits));
+	}
+	parse_cube(s, true).map_err(|e| format!("{} of the cube '{}'", e, s))
+}
+
+// Reads a cover file, failing with the line that is wrong.
+pub fn read(text: &str) -> Result<Cover, String> {
+	// the lines with words, numbered from 1, and how many have been read.
+	let lines: Vec<(usize, Vec<&str>)> = text.lines().enumerate()
+		.map(|(n, l)| (n + 1, l.split('#').next().unwrap_or("").split_whitespace()
+			.collect::<Vec<&str>>()))
+		.filter(|(_, words)| !words.is_empty())
+		.collect();
+	let mut pos = 0;
+	let next = |pos: &mut usize, what: &str| -> Result<(usize, &[&str]), String> {
+		*pos += 1;
+		match lines.get(*pos - 1) {
+			Some(&(n, ref words)) => Ok((n, words.as_slice())),
+			None => Err(format!("the file ends before {}", what)),
+		}
+	};
+	let (n, words) = next(&mut pos, "its version")?;
+	match words {
+		["minterm-cover", v] if *v == COVER_VERSION.to_string() => {},
+		["minterm-cover", v] => return Err(format!(
+			"line {}: this is version {} of the cover format, but only version {} can \
+			 be read; minimize the table again to write it.", n, v, COVER_VERSION)),
+		_ => return Err(format!("line {}: not a cover file; it should start with \
+		                         'minterm-cover {}'.", n, COVER_VERSION)),
+	}
+	// the words after the keyword of the next line, which has to be 'key'.
+	let field = |pos: &mut usize, key: &str| -> Result<(usize, Vec<String>), String> {
+		let (n, words) = next(pos, &format!("'{}'", key))?;
+		if words[0] != key {
+			return Err(format!("line {}: expected '{}', found '{}'.", n, key, words[0]));
+		}
+		Ok((n, words[1..].iter().map(|w| w.to_string()).collect()))
+	};
+	let invars = field(&mut pos, "inputs")?.1;
+	let (n, outvars) = field(&mut pos, "outputs")?;
+	if outvars.is_empty() {
+		return Err(format!("line {}: a cover needs at least one output.", n));
+	}
+	let (n, algorithm) = field(&mut pos, "algorithm")?;
+	if algorithm.len() != 2 {
+		return Err(format!("line {}: 'algorithm' takes the algorithm and the cost.", n));
+	}
+	let (n, policy) = field(&mut pos, "default-output")?;
+	let policy = DefaultOutput::parse(policy.first().map_or("", |s| s.as_str()),
+	                                  outvars.len())
+		.map_err(|e| format!("line {}: {}", n, e))?;
+	let cubes = |pos: &mut usize, count: usize| -> Result<Vec<Term>, String> {
+		let mut rv = vec![];
+		for _ in 0..count {
+			let (n, words) = next(pos, "the last cube")?;
+			let cube = read_cube(&words.concat(), invars.len())
+				.map_err(|e| format!("line {}: {}.", n, e))?;
+			rv.push(cube_to_term(&cube, &invars));
+		}
+		Ok(rv)
+	};
+	let count = |n: usize, s: &str| s.parse::<usize>()
+		.map_err(|_| format!("line {}: '{}' is not a number.", n, s));
+	let (n, listed) = field(&mut pos, "listed")?;
+	let listed = match listed.first().map(|s| s.as_str()) {
+		Some("all") => None,
+		Some(c) => Some(Equation{index: 0, terms: cubes(&mut pos, count(n, c)?)?,
+		                         dc: vec![], varname: "listed".to_string()}),
+		None => return Err(format!("line {}: 'listed' takes 'all' or a number.", n)),
+	};
+	let mut eqns: Vec<Equation> = vec![];
+	let mut minimal: Vec<bool> = vec![];
+	let mut proven: Vec<bool> = vec![];
+	loop {
+		let (n, words) = next(&mut pos, "'end'")?;
+		match words {
+			["end"] => break,
+			[_, name, status, terms, lits] if words[0] == "output" => {
+				let index = match outvars.iter().position(|o| o == name) {
+					Some(i) => i,
+					None => return Err(format!("line {}: '{}' is not one of the outputs.",
+					                           n, name)),
+				};
+				if eqns.iter().any(|e| e.index == index) {
+					return Err(format!("line {}: '{}' is given twice.", n, name));
+				}
+				let (m, p) = match *status {
+					"proven" => (true, true),
+					"greedy" => (true, false),
+					"unproven" => (false, false),
+					_ => return Err(format!("line {}: expected 'proven', 'greedy' or \
+					                         'unproven', found '{}'.", n, status)),
+				};
+				minimal.push(m);
+				proven.push(p);
+				let eqn = Equation{index, terms: cubes(&mut pos, count(n, terms)?)?,
+				                   dc: vec![], varname: name.to_string()};
+				if literals(&eqn) != count(n, lits)? {
+					return Err(format!("line {}: '{}' should have {} literals, but its \
+					                    terms have {}.", n, name, lits, literals(&eqn)));
+				}
+				eqns.push(eqn);
+			},
+			_ => return Err(format!("line {}: expected 'output' or 'end', found '{}'.", n,
+			                        words.join(" "))),
+		}
+	}
+	if let Some(&(n, _)) = lines.get(pos) {
+		return Err(format!("line {}: nothing may follow 'end'.", n));
+	}
+	Ok(Cover{invars, outvars, algorithm: algorithm[0].clone(),
+	         cost: algorithm[1].clone(), unlisted: Unlisted{policy, listed},
+	         eqns, minimal, proven})
+}
+
+// Checks the equations against the rows of a table with the cover's inputs and
+// outputs, describing those they get wrong.
+pub fn verify(cov: &Cover, tbl: &Truth) -> Result<(), String> {
+	let wrong: Vec<String> = cov.eqns.iter().filter_map(|eqn| {
+		let bad = eqn.counterexamples(tbl);
+		bad.first().map(|ent| format!("{} gets {} row(s) wrong, the first {}{}.",
+		                              eqn.varname, bad.len(), cube_string(&ent.input.iter()
+			.map(|&b| Some(b)).collect::<Vec<_>>()), super::sources_note(&ent.sources)))
+	}).collect();
+	if wrong.is_empty() { Ok(()) } else { Err(wrong.join("\n")) }
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, testutil};
+
+	fn cover() -> Cover {
+		let f = testutil::dense(4, 2, 3);
+		let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+		let mut eqns = equations(&f.truth, outvars, f.invars.clone());
+		eqns.iter_mut().for_each(|e| { e.simplify(); e.dc.clear(); });
+		let listed = Equation{index: 0, terms: vec![eqns[0].terms[0].clone()], dc: vec![],
+		                      varname: "listed".to_string()};
+		Cover{invars: f.invars.clone(), outvars: f.outvars.clone(),
+		      algorithm: "greedy".to_string(), cost: "terms".to_string(),
+		      unlisted: Unlisted{policy: DefaultOutput::Bits(vec![true, false]),
+		                         listed: Some(listed)},
+		      eqns, minimal: vec![true, false], proven: vec![false, false]}
+	}
+
+	#[test]
+	fn round_trip() {
+		let cov = cover();
+		let text = write(&cov).unwrap();
+		assert!(text.starts_with("minterm-cover 1\ninputs i0 i1 i2 i3\n\
+		                          outputs o0 o1\nalgorithm greedy terms\n\
+		                          default-output 10\nlisted 1\n"), "{}", text);
+		let back = read(&text).unwrap();
+		assert_eq!((back.invars.clone(), back.outvars.clone()),
+		           (cov.invars.clone(), cov.outvars.clone()));
+		for (a, b) in back.eqns.iter().zip(cov.eqns.iter()) {
+			assert_eq!((a.index, &a.varname, &a.terms), (b.index, &b.varname, &b.terms));
+		}
+		assert!(text.contains("output o0 greedy ") && text.contains("output o1 unproven "),
+		        "{}", text);
+		assert_eq!((back.minimal.clone(), back.proven.clone()),
+		           (vec![true, false], vec![false, false]));
+		assert_eq!(back.unlisted.policy, DefaultOutput::Bits(vec![true, false]));
+		assert_eq!(write(&back).unwrap(), text);
+		// comments and blank lines are skipped.
+		let commented = text.replace("inputs", "# from the build server\n\ninputs");
+		assert!(read(&commented).is_ok());
+		assert!(verify(&back, &testutil::dense(4, 2, 3).truth).is_ok());
+		let mut other = testutil::dense(4, 2, 3).truth;
+		other.table[5].output[1] = !other.table[5].output[1];
+		assert_eq!(verify(&back, &other).unwrap_err(),
+		           "o1 gets 1 row(s) wrong, the first 0101.");
+	}
+
+	#[test]
+	fn rejected_files() {
+		let text = write(&cover()).unwrap();
+		let err = |text: &str| read(text).err().unwrap();
+		assert_eq!(err(&text.replace("minterm-cover 1", "minterm-cover 2")),
+		           "line 1: this is version 2 of the cover format, but only version 1 \
+		            can be read; minimize the table again to write it.");
+		assert!(err("inputs a\n").starts_with("line 1: not a cover file"));
+		assert_eq!(err(&text.replace("end\n", "")), "the file ends before 'end'");
+		let truncated: String = text.lines().take(9).map(|l| l.to_string() + "\n")
+			.collect();
+		assert_eq!(err(&truncated), "the file ends before the last cube");
+		let lits = text.lines().find(|l| l.starts_with("output o0")).unwrap();
+		let wrong = format!("{}9", &lits[..lits.len() - 1]);
+		assert!(err(&text.replace(lits, &wrong)).contains("should have"), "{}", wrong);
+		assert!(err(&text.replace("output o1", "output z")).contains("'z' is not one of"));
+		assert!(err(&text.replace(" greedy ", " minimal ")).contains("expected 'proven', \
+		                                                              'greedy' or"));
+	}
+}
@@ -0,0 +1,147 @@
+// Sets of small integers as bit vectors, a u64 block per 64 members: which
+// minterms a prime implicant covers, and which the primes chosen so far do.
+// The cover search and its reductions ask little else of them than unions,
+// intersections and counts, which on bit vectors take a pass over a few words
+// rather than over lists of indices.
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct BitSet {
+	blocks: Vec<u64>,
+}
+
+impl BitSet {
+	// An empty set with room for the members 0 to n-1.
+	pub fn new(n: usize) -> Self {
+		BitSet{blocks: vec![0; n.div_ceil(64)]}
+	}
+
+	// The set of all of 0 to n-1.
+	pub fn full(n: usize) -> Self {
+		let mut rv = BitSet::new(n);
+		for (k, b) in rv.blocks.iter_mut().enumerate() {
+			*b = if n >= 64 * (k + 1) { !0 } else { (1 << (n - 64 * k)) - 1 };
+		}
+		rv
+	}
+
+	pub fn insert(&mut self, i: usize) {
+		self.blocks[i / 64] |= 1 << (i % 64);
+	}
+
+	pub fn remove(&mut self, i: usize) {
+		self.blocks[i / 64] &= !(1 << (i % 64));
+	}
+
+	pub fn contains(&self, i: usize) -> bool {
+		self.blocks[i / 64] & 1 << (i % 64) != 0
+	}
+
+	// Adds the members of 'other', which has room for the same members.
+	pub fn union_with(&mut self, other: &BitSet) {
+		for (a, b) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+			*a |= *b;
+		}
+	}
+
+	// Keeps only the members of 'other'.
+	pub fn intersect_with(&mut self, other: &BitSet) {
+		for (a, b) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+			*a &= *b;
+		}
+	}
+
+	// Drops the members of 'other'.
+	pub fn difference_with(&mut self, other: &BitSet) {
+		for (a, b) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+			*a &= !*b;
+		}
+	}
+
+	// Whether every member is also one of 'other'.
+	pub fn is_subset(&self, other: &BitSet) -> bool {
+		self.blocks.iter().zip(other.blocks.iter()).all(|(a, b)| a & !b == 0)
+	}
+
+	// The number of members.
+	pub fn count(&self) -> usize {
+		self.blocks.iter().map(|b| b.count_ones() as usize).sum()
+	}
+
+	// The number of members that aren't in 'other': the minterms a prime
+	// would newly cover, with 'other' those already covered.
+	pub fn count_without(&self, other: &BitSet) -> usize {
+		self.blocks.iter().zip(other.blocks.iter())
+			.map(|(a, b)| (a & !b).count_ones() as usize).sum()
+	}
+
+	// The members in increasing order.
+	pub fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = usize> + 'a> {
+		Box::new(ones(self.blocks.iter().cloned()))
+	}
+
+	// The members that aren't in 'other', in increasing order.
+	pub fn iter_without<'a>(&'a self, other: &'a BitSet) ->
+		Box<dyn Iterator<Item = usize> + 'a> {
+		Box::new(ones(self.blocks.iter().zip(other.blocks.iter()).map(|(a, b)| a & !b)))
+	}
+}
+
+// The positions of the set bits of the blocks, counting on from block to block.
+fn ones<I: Iterator<Item = u64>>(blocks: I) -> impl Iterator<Item = usize> {
+	blocks.enumerate().flat_map(|(k, b)| {
+		let mut rest = b;
+		std::iter::from_fn(move || {
+			if rest == 0 {
+				return None;
+			}
+			let i = rest.trailing_zeros() as usize;
+			rest &= rest - 1;
+			Some(64 * k + i)
+		})
+	})
+}
+
+impl fmt::Debug for BitSet {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_set().entries(self.iter()).finish()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn set_operations() {
+		let mut a = BitSet::new(130);
+		for &i in [0, 63, 64, 129].iter() {
+			a.insert(i);
+		}
+		assert!(a.contains(64) && !a.contains(65));
+		assert_eq!(a.count(), 4);
+		assert_eq!(a.iter().collect::<Vec<usize>>(), vec![0, 63, 64, 129]);
+		let mut b = BitSet::new(130);
+		b.insert(1);
+		b.insert(64);
+		assert_eq!(b.count_without(&a), 1);
+		assert_eq!(b.iter_without(&a).collect::<Vec<usize>>(), vec![1]);
+		assert!(!b.is_subset(&a));
+		b.union_with(&a);
+		assert_eq!(b.count(), 5);
+		assert!(a.is_subset(&b));
+		assert_eq!(BitSet::full(130).count(), 130);
+		assert_eq!(BitSet::full(64).count(), 64);
+		assert!(b.is_subset(&BitSet::full(130)));
+		let mut c = b.clone();
+		c.intersect_with(&a);
+		assert_eq!(c, a);
+		c = b.clone();
+		c.difference_with(&a);
+		assert_eq!(c.iter().collect::<Vec<usize>>(), vec![1]);
+		c.remove(1);
+		c.remove(2);
+		assert_eq!(c.count(), 0);
+		assert_eq!(format!("{:?}", BitSet::full(3)), "{0, 1, 2}");
+	}
+}
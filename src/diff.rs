@@ -0,0 +1,95 @@
+// 'minterm diff': compares two versions of a table by what they compute
+// rather than by their text, so reordered rows or reformatted cells don't
+// count as changes.
+use std::collections::HashMap;
+use super::{Entry, Equation, RowSource, Truth, sources_note};
+use super::notation::Notation;
+
+fn bits(values: &[bool]) -> String {
+	values.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+// The outputs, x for a don't-care.
+fn cells(values: &[Option<bool>]) -> String {
+	values.iter().map(|&b| b.map_or('x', |b| if b { '1' } else { '0' })).collect()
+}
+
+fn rows(tbl: &Truth) -> HashMap<&[bool], &Entry> {
+	tbl.table.iter().map(|e| (e.input.as_slice(), e)).collect()
+}
+
+// The outputs for the input pattern, all 0 when the table doesn't list it.
+fn outputs(rows: &HashMap<&[bool], &Entry>, inp: &[bool], nout: usize) ->
+	Vec<Option<bool>> {
+	rows.get(inp).map_or(vec![Some(false); nout], |e| e.cells())
+}
+
+// Describes how the tables differ: every input pattern whose outputs changed,
+// with the old and new outputs and the lines giving them, then for each
+// output either both minimized equations or that it is unchanged.  Returns
+// the report and whether the tables compute the same outputs.
+pub fn diff(old: &Truth, new: &Truth, outvars: &[String], invars: &Vec<String>,
+            n: &Notation) -> (String, bool) {
+	let changed = old.changed_rows(new);
+	let nout = outvars.len();
+	let (before, after) = (rows(old), rows(new));
+	let mut s = String::new();
+	for inp in changed.iter() {
+		let sources: Vec<RowSource> = before.get(inp.as_slice()).into_iter()
+			.chain(after.get(inp.as_slice()))
+			.flat_map(|e| e.sources.iter().cloned()).collect();
+		s += &format!("{}: {} -> {}{}\n", bits(inp), cells(&outputs(&before, inp, nout)),
+		              cells(&outputs(&after, inp, nout)), sources_note(&sources));
+	}
+	for (o, name) in outvars.iter().enumerate() {
+		let differs = changed.iter().any(|inp| {
+			outputs(&before, inp, nout)[o] != outputs(&after, inp, nout)[o]
+		});
+		if !differs {
+			s += &format!("{} is unchanged.\n", name);
+			continue;
+		}
+		let minimized = |t: &Truth| {
+			let mut eqn = Equation::new(t, o, name, invars);
+			eqn.simplify();
+			eqn
+		};
+		s += &format!("- {}\n+ {}\n", n.equation(&minimized(old)),
+		              n.equation(&minimized(new)));
+	}
+	(s, changed.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	const OLD: &str = "0,0,,0,1\n0,1,,1,1\n1,0,,1,0\n1,1,,0,0\n";
+
+	#[test]
+	fn reformatted() {
+		// reordered rows, a wider spacer and a quoted 0.
+		let new = "1,1,,,\"0\",0\n0,1,,,1,1\n0,0,,,0,1\n1,0,,,1,0\n";
+		let (report, same) = diff(&parse(OLD.as_bytes(), 0, 2, 2),
+		                          &parse(new.as_bytes(), 0, 2, 2), &names(&["x", "y"]),
+		                          &names(&["a", "b"]), &Notation::classic());
+		assert!(same);
+		assert_eq!(report, "x is unchanged.\ny is unchanged.\n");
+	}
+
+	#[test]
+	fn one_bit() {
+		let new = "0,0,,0,1\n0,1,,1,1\n1,0,,1,0\n1,1,,1,0\n";
+		let (report, same) = diff(&parse(OLD.as_bytes(), 0, 2, 2),
+		                          &parse(new.as_bytes(), 0, 2, 2), &names(&["x", "y"]),
+		                          &names(&["a", "b"]), &Notation::classic());
+		assert!(!same);
+		assert_eq!(report, "11: 00 -> 10 (line 4, line 4)\n- x = a'b + ab' + ;\n\
+		                    + x = b + a + ;\ny is unchanged.\n");
+	}
+}
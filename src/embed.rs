@@ -0,0 +1,864 @@
+// Minimizing a whole table in one call, for the interfaces that embed the
+// minimizer (ffi, wasm, library users) rather than parse a command line:
+// minimize takes the table and a MinimizeOptions, built up from its defaults,
+// and returns the equations with what it took to get them; generate writes
+// them in one of the --format languages.  The command line translates its
+// flags to the same options.  Nothing here touches files or the process.
+use std::fmt;
+use std::time::{Duration, Instant};
+use super::{Algorithm, Equation, Ran, Term, Truth, algorithm_options, enumerate, exact,
+            factor, fixpoint, hdl, improve, lut, minimize_each, missing_inputs, progress,
+            python, rng, switch, MAX_ENUMERATED_BITS};
+use super::bits::BitOrder;
+use super::emit::{self, DefaultOutput, Shared, Unlisted};
+use super::pregroup::{self, Pregroup};
+
+pub const FORMATS: &[&str] = &["text", "python", "verilog-casez",
+                                                "vhdl", "switch-c", "switch-rust",
+                                                "rust-const"];
+
+pub struct Options {
+	pub format: String,
+	// as --default-output; None when the table must list every input.
+	pub default_output: Option<String>,
+	pub exact: bool,
+	pub max_steps: Option<u64>,
+	// as --bit-order, for the formats that pack the inputs.
+	pub bit_order: BitOrder,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options{format: "text".to_string(), default_output: None, exact: false,
+		        max_steps: None, bit_order: BitOrder::default()}
+	}
+}
+
+// Why a table could not be minimized: the options are wrong, or the table is.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+	Usage(String),
+	Invalid(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Error::Usage(ref msg) | Error::Invalid(ref msg) => write!(f, "{}", msg),
+		}
+	}
+}
+
+/// What a minimization keeps down, as --cost.  More costs may come, so a
+/// match on one needs a wildcard arm outside the crate.
+///
+/// ```compile_fail
+/// use minterm::prelude::*;
+/// fn name(c: Cost) -> &'static str {
+///     match c {
+///         Cost::Terms => "terms",
+///         Cost::Conditions => "conditions",
+///         Cost::Literals => "literals",
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Cost {
+	// the terms of each output on its own.
+	Terms,
+	// the distinct terms of all the outputs, shared where they can be.
+	Conditions,
+	// the literals of all the outputs, merging terms greedily.
+	Literals,
+}
+
+impl Cost {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"terms" => Ok(Cost::Terms),
+			"conditions" => Ok(Cost::Conditions),
+			"literals" => Ok(Cost::Literals),
+			_ => Err(format!("Unknown cost '{}'; expected 'terms', 'conditions' or \
+			                  'literals'.", s)),
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Cost::Terms => "terms",
+			Cost::Conditions => "conditions",
+			Cost::Literals => "literals",
+		}
+	}
+
+	// What the equations cost: each output's terms and literals added up for
+	// Terms, and otherwise the system's, a term several outputs have counting
+	// once.
+	pub fn tally(&self, eqns: &[Equation], nbits: usize) -> factor::Tally {
+		match *self {
+			Cost::Terms => factor::Tally{
+				terms: eqns.iter().map(|e| e.terms.len()).sum(),
+				literals: eqns.iter().flat_map(|e| e.terms.iter()).map(Term::len).sum(),
+			},
+			_ => factor::cost(eqns, nbits),
+		}
+	}
+
+	// A tally's counts in the order they are weighed.
+	pub fn key(&self, t: &factor::Tally) -> (usize, usize) {
+		match *self {
+			Cost::Literals => (t.literals, t.terms),
+			_ => (t.terms, t.literals),
+		}
+	}
+}
+
+/// How `minimize` goes about a table.  The defaults are the command line's:
+/// the greedy simplifier, each output's terms as the cost, no shared
+/// products, a table listing every input, canonical order and no limits.
+/// Each setting has a method taking it and returning the options, so they
+/// chain from `MinimizeOptions::new()`; the settings themselves are private,
+/// so that a new one is no breaking change.
+///
+/// ```compile_fail
+/// use minterm::prelude::*;
+/// let mut opts = MinimizeOptions::new();
+/// opts.seed = 7;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinimizeOptions {
+	algorithm: Algorithm,
+	// outputs by name that take another algorithm than 'algorithm'.
+	algorithm_for: Vec<(String, Algorithm)>,
+	cost: Cost,
+	// whether to name the products several outputs have, as --share-terms.
+	share_terms: bool,
+	// what the inputs the table has no row for give; None when it must list
+	// every input.
+	default_output: Option<DefaultOutput>,
+	// the terms in canonical order, rather than the order they came in.
+	canonical: bool,
+	// the limits of the exact search, past which its best cover yet is kept.
+	timeout: Option<Duration>,
+	max_steps: Option<u64>,
+	// the limits of the greedy simplifier on each output, past which it keeps
+	// the cover it has, partially simplified.
+	max_passes: Option<usize>,
+	max_merges: Option<usize>,
+	// how long to polish the greedy covers, as --improve, and the seed of its
+	// random choices.
+	improve: Option<Duration>,
+	seed: u64,
+	// what the covers are minimized from, as --pregroup.
+	pregroup: Pregroup,
+}
+
+impl Default for MinimizeOptions {
+	fn default() -> Self {
+		MinimizeOptions{algorithm: Algorithm::Greedy, algorithm_for: vec![],
+		                cost: Cost::Terms, share_terms: false, default_output: None,
+		                canonical: true, timeout: None, max_steps: None, max_passes: None,
+		                max_merges: None, improve: None, seed: 0,
+		                pregroup: Pregroup::None}
+	}
+}
+
+impl MinimizeOptions {
+	pub fn new() -> Self { MinimizeOptions::default() }
+
+	pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+		self.algorithm = algorithm;
+		self
+	}
+
+	pub fn algorithm_for(mut self, output: &str, algorithm: Algorithm) -> Self {
+		self.algorithm_for.push((output.to_string(), algorithm));
+		self
+	}
+
+	pub fn cost(mut self, cost: Cost) -> Self {
+		self.cost = cost;
+		self
+	}
+
+	pub fn share_terms(mut self, share: bool) -> Self {
+		self.share_terms = share;
+		self
+	}
+
+	pub fn default_output(mut self, policy: DefaultOutput) -> Self {
+		self.default_output = Some(policy);
+		self
+	}
+
+	pub fn canonical(mut self, canonical: bool) -> Self {
+		self.canonical = canonical;
+		self
+	}
+
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	pub fn max_steps(mut self, steps: u64) -> Self {
+		self.max_steps = Some(steps);
+		self
+	}
+
+	pub fn max_passes(mut self, passes: usize) -> Self {
+		self.max_passes = Some(passes);
+		self
+	}
+
+	pub fn max_merges(mut self, merges: usize) -> Self {
+		self.max_merges = Some(merges);
+		self
+	}
+
+	pub fn improve(mut self, time: Duration) -> Self {
+		self.improve = Some(time);
+		self
+	}
+
+	pub fn seed(mut self, seed: u64) -> Self {
+		self.seed = seed;
+		self
+	}
+
+	pub fn pregroup(mut self, pregroup: Pregroup) -> Self {
+		self.pregroup = pregroup;
+		self
+	}
+
+	// The algorithm for each of the outputs, failing on settings that can't
+	// go together.
+	pub fn algorithms(&self, outvars: &[String]) -> Result<Vec<Algorithm>, String> {
+		let algorithms = algorithm_options(&self.algorithm_for, outvars, self.algorithm)?;
+		let exact = self.algorithm == Algorithm::Exact;
+		let any_exact = algorithms.contains(&Algorithm::Exact);
+		let shared = self.cost == Cost::Conditions;
+		let factored = self.cost == Cost::Literals;
+		if shared && outvars.len() > 64 {
+			return Err("--cost conditions handles at most 64 outputs.".to_string());
+		}
+		if !self.algorithm_for.is_empty() && self.cost != Cost::Terms {
+			return Err(format!("--algorithm-for can't be combined with --cost {}.",
+			                   self.cost.name()));
+		}
+		if factored && exact {
+			return Err("--cost literals merges terms greedily; it can't be combined with \
+			            --algorithm exact.".to_string());
+		}
+		if shared && self.pregroup != Pregroup::None {
+			return Err("--cost conditions searches from the minterms whatever they are \
+			            grouped into; it can't be combined with --pregroup.".to_string());
+		}
+		if self.improve.is_some() {
+			let unsupported = [("--algorithm exact", exact),
+			                   ("an exact --algorithm-for", any_exact),
+			                   ("--cost conditions", shared),
+			                   ("--cost literals", factored)];
+			for &(opt, given) in unsupported.iter() {
+				if given {
+					return Err(format!("--improve polishes the greedy covers; it can't \
+					                    be combined with {}.", opt));
+				}
+			}
+		}
+		Ok(algorithms)
+	}
+
+	// The budget of the exact search and the simplifier.
+	pub fn budget(&self) -> exact::Budget {
+		let mut rv = exact::Budget::new(self.timeout, self.max_steps);
+		rv.limit_simplify(self.max_passes, self.max_merges);
+		rv
+	}
+}
+
+// The names of a table's inputs and outputs.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Names {
+	pub inputs: Vec<String>,
+	pub outputs: Vec<String>,
+}
+
+impl Names {
+	pub fn new<S: AsRef<str>>(inputs: &[S], outputs: &[S]) -> Self {
+		Names{inputs: inputs.iter().map(|s| s.as_ref().to_string()).collect(),
+		      outputs: outputs.iter().map(|s| s.as_ref().to_string()).collect()}
+	}
+}
+
+// What minimizing an output came to.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Stats {
+	// the algorithm, with --cost terms; the others minimize the outputs
+	// together.
+	pub ran: Option<Ran>,
+	pub minimal: bool,
+	// false when the simplifier stopped at max_passes or max_merges.
+	pub completed: bool,
+	pub terms: usize,
+	pub literals: usize,
+	// whether the cover was minimized from the compressed table.
+	pub pregrouped: bool,
+}
+
+// How the equations were made, as a cover file's header records it.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Provenance {
+	pub version: &'static str,
+	// 'greedy', 'exact', or 'mixed' when outputs took different algorithms.
+	pub algorithm: &'static str,
+	pub cost: &'static str,
+	// the seed of --improve's random choices, when it ran.
+	pub seed: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MinimizeResult {
+	// an equation per output of the table, in its order.
+	pub equations: Vec<Equation>,
+	// with share_terms, the products several equations have.
+	pub shared: Vec<Shared>,
+	// per equation.
+	pub stats: Vec<Stats>,
+	// what a caller may want to pass on: outputs not proven minimal, and
+	// outputs left as they were by --improve.
+	pub warnings: Vec<String>,
+	pub provenance: Provenance,
+}
+
+impl MinimizeResult {
+	// Whether every equation is known to be minimal.
+	pub fn minimal(&self) -> bool {
+		self.stats.iter().all(|s| s.minimal)
+	}
+}
+
+// The covers minimize_equations came to.
+pub struct Covers {
+	// whether each cover is taken to be minimal, as the greedy simplifier's
+	// are, and whether it is proven so, as only the exact searches' are.
+	pub minimal: Vec<bool>,
+	pub proven: Vec<bool>,
+	// with Cost::Terms, what each equation's algorithm came to.
+	pub ran: Vec<Ran>,
+	// with Cost::Conditions, the conditions and the outputs each sets.
+	pub conditions: Option<Vec<exact::Condition>>,
+	// with Cost::Literals, the merges weighed.
+	pub decisions: Vec<factor::Decision>,
+	// per equation, whether its cover was minimized from the compressed table.
+	pub pregrouped: Vec<bool>,
+	// the pass's, when it was undone; see guarded.
+	pub warnings: Vec<String>,
+}
+
+// Runs a pass over the equations and keeps the covers it makes, unless they
+// cost more under 'cost' than those it started from: a merge that is cheaper
+// for one output may cost the system a term others shared.  Then the covers
+// from before are put back, and the warning naming the pass says by how
+// much it would have raised the cost.  Returns what the pass did.
+pub fn guarded<R, F>(pass: &str, eqns: &mut [Equation], nbits: usize, cost: Cost,
+                     run: F) -> (R, Option<String>)
+	where F: FnOnce(&mut [Equation]) -> R {
+	let covers: Vec<Vec<Term>> = eqns.iter().map(|e| e.terms.clone()).collect();
+	let before = cost.tally(eqns, nbits);
+	let rv = run(eqns);
+	let after = cost.tally(eqns, nbits);
+	if cost.key(&after) <= cost.key(&before) {
+		return (rv, None);
+	}
+	for (eqn, terms) in eqns.iter_mut().zip(covers) {
+		eqn.terms = terms;
+	}
+	(rv, Some(format!("The {} pass raised the cost under --cost {} from {} to {}; the \
+	                   covers from before it are kept.", pass, cost.name(), before,
+	                  after)))
+}
+
+// The conditions of the equations' terms: each distinct term, with the
+// outputs that have it.
+fn conditions(eqns: &[Equation]) -> Vec<exact::Condition> {
+	let mut rv: Vec<exact::Condition> = vec![];
+	for (e, eqn) in eqns.iter().enumerate() {
+		for t in eqn.terms.iter() {
+			match rv.iter_mut().find(|c| c.term == *t) {
+				Some(c) => if !c.outputs.contains(&e) { c.outputs.push(e) },
+				None => rv.push(exact::Condition{term: t.clone(), outputs: vec![e]}),
+			}
+		}
+	}
+	rv
+}
+
+// Minimizes the equations as the options' cost says, with 'algorithms' from
+// MinimizeOptions::algorithms for --cost terms, indexed by the equations'
+// outputs.
+pub fn minimize_equations(eqns: &mut [Equation], nbits: usize, opts: &MinimizeOptions,
+                          algorithms: &[Algorithm], weights: &[usize],
+                          budget: &mut exact::Budget,
+                          progress: &mut dyn progress::Progress) -> Covers {
+	let pass = match opts.cost {
+		Cost::Terms => "minimize",
+		Cost::Conditions => "shared search",
+		Cost::Literals => "merge",
+	};
+	let (mut rv, warning) = guarded(pass, eqns, nbits, opts.cost, |eqns| {
+		let run = |eqns: &mut [Equation]| {
+			cover(eqns, nbits, opts.cost, algorithms, weights, budget, progress)
+		};
+		from_start(eqns, nbits, opts.cost, opts.pregroup, run)
+	});
+	if let Some(w) = warning {
+		// the covers are those minimizing started from.
+		rv.minimal = vec![false; eqns.len()];
+		rv.proven = vec![false; eqns.len()];
+		if rv.conditions.is_some() {
+			rv.conditions = Some(conditions(eqns));
+		}
+		rv.decisions.clear();
+		rv.warnings.push(w);
+	}
+	rv
+}
+
+// Covers the equations by 'run' from the start 'pregroup' says.  With auto,
+// from both starts: with --cost terms each output keeps the cheaper of its
+// two covers, and otherwise, the outputs costing what they do together, the
+// system keeps the cheaper of its two; a cover from the compressed table
+// has to be cheaper, and verified, to be kept.
+fn from_start<F>(eqns: &mut [Equation], nbits: usize, cost: Cost, pregroup: Pregroup,
+                 mut run: F) -> Covers
+	where F: FnMut(&mut [Equation]) -> Covers {
+	let n = eqns.len();
+	if pregroup == Pregroup::None {
+		return Covers{pregrouped: vec![false; n], ..run(eqns)};
+	}
+	let mut grouped = pregroup::compress(eqns, nbits);
+	if pregroup == Pregroup::Rows {
+		eqns.clone_from_slice(&grouped);
+		return Covers{pregrouped: vec![true; n], ..run(eqns)};
+	}
+	let start = eqns.to_vec();
+	let mut rv = Covers{pregrouped: vec![false; n], ..run(eqns)};
+	let alt = run(&mut grouped);
+	let ok = |e: usize| pregroup::verified(&grouped[e], &start[e], nbits);
+	let cheaper = |a: &[Equation], b: &[Equation]| {
+		cost.key(&cost.tally(a, nbits)) < cost.key(&cost.tally(b, nbits))
+	};
+	if cost != Cost::Terms {
+		if (0..n).all(&ok) && cheaper(&grouped, eqns) {
+			eqns.clone_from_slice(&grouped);
+			rv = Covers{pregrouped: vec![true; n], ..alt};
+		}
+		return rv;
+	}
+	for e in 0..n {
+		if ok(e) && cheaper(&grouped[e..e + 1], &eqns[e..e + 1]) {
+			eqns[e] = grouped[e].clone();
+			rv.ran[e] = alt.ran[e];
+			rv.minimal[e] = alt.minimal[e];
+			rv.proven[e] = alt.proven[e];
+			rv.pregrouped[e] = true;
+		}
+	}
+	rv
+}
+
+fn cover(eqns: &mut [Equation], nbits: usize, cost: Cost, algorithms: &[Algorithm],
+         weights: &[usize], budget: &mut exact::Budget,
+         progress: &mut dyn progress::Progress) -> Covers {
+	let mut rv = Covers{minimal: vec![], proven: vec![], ran: vec![], conditions: None,
+	                    decisions: vec![], pregrouped: vec![], warnings: vec![]};
+	match cost {
+		Cost::Conditions => {
+			let sol = exact::minimize_shared(eqns, nbits, weights, budget, progress);
+			for (e, eqn) in eqns.iter_mut().enumerate() {
+				eqn.terms = sol.conditions.iter().filter(|c| c.outputs.contains(&e))
+					.map(|c| c.term.clone()).collect();
+			}
+			rv.minimal = vec![sol.minimal; eqns.len()];
+			rv.proven = rv.minimal.clone();
+			rv.conditions = Some(sol.conditions);
+		},
+		Cost::Literals => {
+			progress.phase("merge");
+			rv.decisions = factor::merge(eqns, nbits, factor::Model::Literals);
+			rv.minimal = vec![true; eqns.len()];
+			rv.proven = vec![false; eqns.len()];
+		},
+		Cost::Terms => {
+			let algs: Vec<Algorithm> = eqns.iter().map(|e| algorithms[e.index]).collect();
+			rv.ran = minimize_each(eqns, nbits, &algs, weights, budget, progress);
+			rv.minimal = rv.ran.iter().map(Ran::minimal).collect();
+			rv.proven = rv.ran.iter().map(|&r| r == Ran::Exact).collect();
+		},
+	}
+	rv
+}
+
+// Polishes each greedy cover for its share of the options' improve time,
+// from 'start', what it was minimized from.  None for an equation with too
+// many inputs to try.
+pub fn improve(eqns: &mut [Equation], start: &[Equation], nbits: usize,
+               opts: &MinimizeOptions) -> Vec<Option<improve::Improvement>> {
+	let time = match opts.improve {
+		None => return vec![],
+		Some(time) => time,
+	};
+	let mut rng = rng::Rng::new(opts.seed);
+	let deadline = Instant::now() + time;
+	let n = eqns.len();
+	eqns.iter_mut().enumerate().map(|(e, eqn)| {
+		// the outputs still to go share the time left evenly.
+		let left = deadline.saturating_duration_since(Instant::now());
+		let share = left / (n - e) as u32;
+		improve::improve(eqn, &start[e], nbits, Instant::now() + share, usize::MAX,
+		                 &mut rng)
+	}).collect()
+}
+
+/// Minimizes every output of the table, named by `names`, as `opts` says.
+/// Fails with `Error::Usage` on options that don't go together or don't fit
+/// the table, and `Error::Invalid` on a table that can't be minimized.
+///
+/// ```
+/// use minterm::{Algorithm, Truth};
+/// use minterm::embed::{Cost, MinimizeOptions, Names, minimize};
+/// use minterm::emit::DefaultOutput;
+/// // x is set when a and b are; y is set when either is.
+/// let tbl = Truth::from_fn(2, 2, |i| vec![i[0] && i[1], i[0] || i[1]]).unwrap();
+/// let names = Names::new(&["a", "b"], &["x", "y"]);
+///
+/// // the defaults: each output simplified greedily.
+/// let r = minimize(&tbl, &names, &MinimizeOptions::new()).unwrap();
+/// assert_eq!(r.equations[1].to_string(), "y = a + b + ;");
+/// assert!(r.minimal());
+///
+/// // the exact search, stopped after a million steps at most.
+/// let opts = MinimizeOptions::new().algorithm(Algorithm::Exact).max_steps(1_000_000);
+/// let r = minimize(&tbl, &names, &opts).unwrap();
+/// assert_eq!((r.provenance.algorithm, r.stats[0].literals), ("exact", 2));
+///
+/// // the fewest distinct terms over both outputs, with a table that leaves
+/// // out a = b = 1 and doesn't care what it gives.
+/// let partial = Truth::from_fn_partial(2, 2, |i| {
+///     if i[0] && i[1] { None } else { Some(vec![false, i[0] || i[1]]) }
+/// }).unwrap();
+/// let opts = MinimizeOptions::new().cost(Cost::Conditions)
+///     .default_output(DefaultOutput::DontCare);
+/// let r = minimize(&partial, &names, &opts).unwrap();
+/// assert_eq!(r.equations[0].to_string(), "x = ;");
+/// assert_eq!(r.provenance.cost, "conditions");
+/// ```
+pub fn minimize(tbl: &Truth, names: &Names, opts: &MinimizeOptions) ->
+	Result<MinimizeResult, Error> {
+	if tbl.table.is_empty() {
+		return Err(Error::Invalid("The table has no rows with outputs.".to_string()));
+	}
+	let (invars, outvars) = (&names.inputs, &names.outputs);
+	let nbits = invars.len();
+	if tbl.table[0].input.len() != nbits || tbl.table[0].output.len() != outvars.len() {
+		return Err(Error::Usage(format!(
+			"The table has {} inputs and {} outputs, but {} and {} are named.",
+			tbl.table[0].input.len(), tbl.table[0].output.len(), nbits, outvars.len())));
+	}
+	let algorithms = opts.algorithms(outvars).map_err(Error::Usage)?;
+	if let Some(DefaultOutput::Bits(ref bits)) = opts.default_output {
+		if bits.len() != outvars.len() {
+			return Err(Error::Usage(format!("The default output has {} bits for {} \
+			                                 outputs.", bits.len(), outvars.len())));
+		}
+	}
+	let missing = missing_inputs(tbl, nbits, MAX_ENUMERATED_BITS).map_err(Error::Usage)?;
+	let policy = match opts.default_output {
+		None if !missing.is_empty() => {
+			return Err(Error::Invalid(format!(
+				"The table has no row for {} input(s); give a default output to allow \
+				 missing inputs.", missing.len())));
+		},
+		None => DefaultOutput::Zero,
+		Some(ref p) => p.clone(),
+	};
+	let mut eqns = equations(tbl, invars, outvars, &policy, &missing);
+	let start = if opts.improve.is_some() { eqns.clone() } else { vec![] };
+	let mut budget = opts.budget();
+	let (mut covers, mut improved) = (None, vec![]);
+	let outcome = fixpoint::minimize(&mut eqns, nbits, opts.cost, |eqns| {
+		covers = Some(minimize_equations(eqns, nbits, opts, &algorithms, &vec![1; nbits],
+		                                 &mut budget, &mut progress::Silent));
+	}, |eqns| improved = improve(eqns, &start, nbits, opts));
+	let covers = covers.expect("the first round minimizes");
+	let mut warnings = covers.warnings.clone();
+	warnings.extend(outcome.warnings.iter().cloned());
+	warnings.extend(outcome.note());
+	for (eqn, _) in eqns.iter().zip(improved.iter()).filter(|&(_, i)| i.is_none()) {
+		warnings.push(format!("{} has more than {} inputs to improve; it was left as \
+		                       it is.", eqn.varname, improve::MAX_IMPROVE_BITS));
+	}
+	if opts.canonical {
+		eqns.iter_mut().for_each(Equation::canonicalize);
+	}
+	let shared = if !opts.share_terms { vec![] } else {
+		let products = emit::shared_products(&eqns, "t", 2);
+		if let Some(p) = products.iter().find(|p| invars.contains(&p.name) ||
+		                                          outvars.contains(&p.name)) {
+			return Err(Error::Usage(format!("The shared product name '{}' is already a \
+			                                 variable.", p.name)));
+		}
+		products
+	};
+	let stats: Vec<Stats> = eqns.iter().enumerate().map(|(e, eqn)| Stats{
+		ran: covers.ran.get(e).cloned(), minimal: covers.minimal[e],
+		completed: covers.ran.get(e).is_none_or(Ran::completed),
+		terms: eqn.terms.len(), literals: eqn.terms.iter().map(Term::len).sum(),
+		pregrouped: covers.pregrouped[e],
+	}).collect();
+	for (eqn, s) in eqns.iter().zip(stats.iter()).filter(|&(_, s)| !s.minimal) {
+		warnings.push(if s.completed {
+			format!("{} is not proven minimal; the exact search stopped at the budget.",
+			        eqn.varname)
+		} else {
+			format!("{} is partially simplified; the simplifier stopped at its limit on \
+			         passes or merges.", eqn.varname)
+		});
+	}
+	let algorithm = if algorithms.iter().any(|&a| a != algorithms[0]) { "mixed" }
+	                else if opts.cost == Cost::Conditions { "exact" }
+	                else { algorithms.first().map_or("greedy", Algorithm::name) };
+	let provenance = Provenance{version: env!("CARGO_PKG_VERSION"), algorithm,
+	                            cost: opts.cost.name(),
+	                            seed: opts.improve.map(|_| opts.seed)};
+	Ok(MinimizeResult{equations: eqns, shared, stats, warnings, provenance})
+}
+
+// An equation per output, with the inputs the table has no row for added as
+// 'policy' says.
+fn equations(tbl: &Truth, invars: &[String], outvars: &[String], policy: &DefaultOutput,
+             missing: &[Vec<bool>]) -> Vec<Equation> {
+	let invars = invars.to_vec();
+	let term = |inp: &Vec<bool>| {
+		let mut term = Term::compute(inp);
+		term.names = invars.clone();
+		term
+	};
+	outvars.iter().enumerate().map(|(b, name)| {
+		let mut eqn = Equation::new(tbl, b, name, &invars);
+		match *policy {
+			DefaultOutput::Zero => {},
+			DefaultOutput::Bits(ref bits) => if bits[b] {
+				eqn.terms.extend(missing.iter().map(&term));
+			},
+			DefaultOutput::DontCare | DefaultOutput::Panic =>
+				eqn.dc.extend(missing.iter().map(&term)),
+		}
+		eqn
+	}).collect()
+}
+
+// The equations of every output written in the format, and whether they are
+// all known to be minimal.
+pub fn generate(tbl: &Truth, invars: &[String], outvars: &[String], opts: &Options) ->
+	Result<(String, bool), Error> {
+	if !FORMATS.contains(&opts.format.as_str()) {
+		return Err(Error::Usage(format!("Unknown format '{}'; expected one of {}.",
+		                                opts.format, FORMATS.join(", "))));
+	}
+	let mut minimize_opts = MinimizeOptions::new()
+		.algorithm(if opts.exact { Algorithm::Exact } else { Algorithm::Greedy });
+	minimize_opts.max_steps = opts.max_steps;
+	if let Some(ref s) = opts.default_output {
+		let policy = DefaultOutput::parse(s, outvars.len()).map_err(Error::Usage)?;
+		minimize_opts = minimize_opts.default_output(policy);
+	}
+	let result = minimize(tbl, &Names::new(invars, outvars), &minimize_opts)?;
+	let policy = minimize_opts.default_output.unwrap_or(DefaultOutput::Zero);
+	let text = render(&result, tbl, invars, &opts.format, policy, opts.bit_order)?;
+	Ok((text, result.minimal()))
+}
+
+// The equations of a minimization of 'tbl' written in one of the FORMATS, the
+// inputs the table doesn't list giving what 'policy' says.
+pub fn render(result: &MinimizeResult, tbl: &Truth, invars: &[String], format: &str,
+              policy: DefaultOutput, bit_order: BitOrder) -> Result<String, Error> {
+	let eqns = &result.equations;
+	if format == "rust-const" && eqns.len() > lut::MAX_OUTPUTS {
+		return Err(Error::Usage(format!("Format rust-const handles at most {} outputs.",
+		                                lut::MAX_OUTPUTS)));
+	}
+	if format == "switch-c" || format == "switch-rust" || format == "rust-const" {
+		enumerate::check(invars.len(), MAX_ENUMERATED_BITS,
+		                 "; give a format that doesn't list them, such as text")
+			.map_err(Error::Usage)?;
+	}
+	let invars = invars.to_vec();
+	let unlisted = Unlisted::new(policy, tbl, invars.len(), &invars);
+	Ok(match format {
+		"python" => python::function(eqns, &invars, &unlisted, false),
+		"verilog-casez" => hdl::verilog_casez(eqns, &invars, &unlisted, bit_order),
+		"vhdl" => hdl::vhdl(eqns, &invars, &unlisted),
+		"switch-c" => switch::switch_c(eqns, &invars, &unlisted, bit_order, false),
+		"switch-rust" => switch::switch_rust(eqns, &invars, &unlisted, bit_order, false),
+		"rust-const" => lut::rust_const(eqns, &invars, &unlisted, bit_order),
+		_ => eqns.iter().zip(result.stats.iter()).map(|(e, s)| {
+			if !s.completed { format!("{} (partially simplified)\n", e) }
+			else if s.minimal { format!("{}\n", e) }
+			else { format!("{} (not proven minimal)\n", e) }
+		}).collect(),
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{parse_assertion, testutil};
+
+	#[test]
+	fn option_defaults() {
+		let opts = MinimizeOptions::new();
+		assert_eq!(opts, MinimizeOptions::default());
+		assert_eq!((opts.algorithm, opts.cost), (Algorithm::Greedy, Cost::Terms));
+		assert!(opts.algorithm_for.is_empty());
+		assert!(!opts.share_terms && opts.canonical);
+		assert_eq!(opts.default_output, None);
+		assert_eq!((opts.timeout, opts.max_steps, opts.improve, opts.seed),
+		           (None, None, None, 0));
+		let outvars = vec!["x".to_string(), "y".to_string()];
+		assert_eq!(opts.algorithms(&outvars), Ok(vec![Algorithm::Greedy; 2]));
+		let opts = opts.algorithm_for("y", Algorithm::Exact);
+		assert_eq!(opts.algorithms(&outvars),
+		           Ok(vec![Algorithm::Greedy, Algorithm::Exact]));
+		assert!(opts.clone().cost(Cost::Literals).algorithms(&outvars).is_err());
+		assert!(opts.improve(Duration::from_millis(1)).algorithms(&outvars).is_err());
+		let opts = MinimizeOptions::new().algorithm(Algorithm::Exact).cost(Cost::Literals);
+		assert!(opts.algorithms(&outvars).is_err());
+	}
+
+	#[test]
+	fn results() {
+		// y has no essential primes, so its search has to branch.
+		let tbl = Truth::from_fn(3, 2, |i| {
+			vec![i[0] != i[1], !(i[0] != i[1] && i[1] == i[2])]
+		}).unwrap();
+		let names = Names::new(&["a", "b", "c"], &["x", "y"]);
+		let opts = MinimizeOptions::new().algorithm(Algorithm::Exact).max_steps(1)
+			.algorithm_for("x", Algorithm::Greedy);
+		let r = minimize(&tbl, &names, &opts).unwrap();
+		assert_eq!(r.provenance.algorithm, "mixed");
+		assert_eq!(r.stats[0].ran, Some(Ran::Greedy));
+		assert_eq!((r.stats[1].ran, r.minimal()), (Some(Ran::ExactStopped), false));
+		assert_eq!(r.warnings, vec!["y is not proven minimal; the exact search stopped \
+		                             at the budget.".to_string()]);
+		let r = minimize(&tbl, &names, &MinimizeOptions::new().share_terms(true)).unwrap();
+		assert_eq!((r.stats[0].terms, r.stats[0].literals), (2, 4));
+		assert!(r.shared.is_empty());
+		let short = Names::new(&["a", "b"], &["x", "y"]);
+		assert_eq!(minimize(&tbl, &short, &opts).unwrap_err(),
+		           Error::Usage("The table has 3 inputs and 2 outputs, but 2 and 2 are \
+		                         named.".to_string()));
+	}
+
+	#[test]
+	fn undoes_a_costlier_pass() {
+		// the system at the top of lib.rs: merging ab'c' + abc' into ac' in x
+		// alone is one term fewer for x, but y still has both, so the system
+		// has one more distinct term.
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let tbl = Truth::from_fn(3, 2, |i| {
+			let v = i.iter().fold(0, |v, &b| 2*v + b as u8);
+			vec![[1, 2, 4, 6].contains(&v), [0, 2, 4, 5, 6].contains(&v)]
+		}).unwrap();
+		let mut eqns: Vec<Equation> = ["x", "y"].iter().enumerate()
+			.map(|(b, n)| Equation::new(&tbl, b, n, &names)).collect();
+		let merge = |eqns: &mut [Equation]| {
+			eqns[0] = parse_assertion("x = a'b'c + ac' + a'bc'", &["x", "y"], &names)
+				.unwrap();
+		};
+		let before = Cost::Conditions.tally(&eqns, 3);
+		assert_eq!((before.terms, before.literals), (6, 18));
+		let ((), warning) = guarded("merge", &mut eqns, 3, Cost::Conditions, merge);
+		assert_eq!(warning.unwrap(), "The merge pass raised the cost under --cost \
+		                              conditions from 6 terms, 18 literals to 7 terms, 20 \
+		                              literals; the covers from before it are kept.");
+		assert_eq!(Cost::Conditions.tally(&eqns, 3), before);
+		assert_eq!(eqns[0].terms.len(), 4);
+		// each output on its own, it is a merge like any other.
+		let ((), warning) = guarded("merge", &mut eqns, 3, Cost::Terms, merge);
+		assert_eq!((warning, eqns[0].terms.len()), (None, 3));
+		// nor does the whole minimization raise the cost it is asked to lower.
+		for &cost in [Cost::Terms, Cost::Conditions, Cost::Literals].iter() {
+			let start: Vec<Equation> = ["x", "y"].iter().enumerate()
+				.map(|(b, n)| Equation::new(&tbl, b, n, &names)).collect();
+			let names = Names::new(&["a", "b", "c"], &["x", "y"]);
+			let r = minimize(&tbl, &names, &MinimizeOptions::new().cost(cost)).unwrap();
+			assert!(cost.key(&cost.tally(&r.equations, 3)) <=
+			        cost.key(&cost.tally(&start, 3)));
+			assert!(r.warnings.is_empty(), "{:?}", r.warnings);
+		}
+	}
+
+	#[test]
+	fn simplify_limits() {
+		// the example at the top of lib.rs, each output stopped after a merge.
+		let tbl = Truth::from_fn(3, 2, |i| {
+			let v = i.iter().fold(0, |v, &b| 2*v + b as u8);
+			vec![[1, 2, 4, 6].contains(&v), [0, 2, 4, 5, 6].contains(&v)]
+		}).unwrap();
+		let names = Names::new(&["a", "b", "c"], &["x", "y"]);
+		let full = minimize(&tbl, &names, &MinimizeOptions::new()).unwrap();
+		let opts = MinimizeOptions::new().max_merges(1);
+		assert_eq!(opts.max_merges, Some(1));
+		let r = minimize(&tbl, &names, &opts).unwrap();
+		for (e, s) in r.equations.iter().zip(r.stats.iter()) {
+			assert_eq!((s.ran, s.completed, s.minimal),
+			           (Some(Ran::GreedyStopped), false, false));
+			assert!(tbl.table.iter().all(|t| e.evaluate(&t.input) == t.output[e.index]),
+			        "{}", e);
+		}
+		let literals = |r: &MinimizeResult| {
+			r.stats.iter().map(|s| s.literals).sum::<usize>()
+		};
+		assert!(literals(&r) > literals(&full), "{:?} {:?}", r.stats, full.stats);
+		assert_eq!(r.warnings[0], "x is partially simplified; the simplifier stopped at \
+		                           its limit on passes or merges.");
+		let text = render(&r, &tbl, &names.inputs, "text", DefaultOutput::Zero,
+		                  BitOrder::default()).unwrap();
+		assert_eq!(text.matches(" (partially simplified)\n").count(), 2, "{}", text);
+		assert!(full.stats.iter().all(|s| s.completed));
+		// a pass is a level of merging; one leaves y short of c'.
+		let r = minimize(&tbl, &names, &MinimizeOptions::new().max_passes(1)).unwrap();
+		assert!(!r.stats[1].completed);
+		assert!(r.stats[1].literals > full.stats[1].literals);
+	}
+
+	#[test]
+	fn pregroup_auto() {
+		// the greedy covers from the rows and from the compressed table: the
+		// compressed table wins the first, the rows the second.
+		let cost = |f: &testutil::Fixture, p: Pregroup| {
+			let names = Names::new(&f.invars, &f.outvars);
+			let opts = MinimizeOptions::new().pregroup(p);
+			let r = minimize(&f.truth, &names, &opts).unwrap();
+			((r.stats[0].terms, r.stats[0].literals), r.stats[0].pregrouped)
+		};
+		let rows_win = testutil::dense(3, 1, 12);
+		assert_eq!(cost(&rows_win, Pregroup::None), ((4, 8), false));
+		assert_eq!(cost(&rows_win, Pregroup::Rows), ((3, 6), true));
+		assert_eq!(cost(&rows_win, Pregroup::Auto), ((3, 6), true));
+		let none_wins = testutil::dense(3, 1, 14);
+		assert_eq!(cost(&none_wins, Pregroup::None), ((3, 5), false));
+		assert_eq!(cost(&none_wins, Pregroup::Rows), ((4, 10), true));
+		assert_eq!(cost(&none_wins, Pregroup::Auto), ((3, 5), false));
+		let opts = MinimizeOptions::new().cost(Cost::Conditions).pregroup(Pregroup::Auto);
+		assert!(opts.algorithms(&none_wins.outvars).is_err());
+	}
+}
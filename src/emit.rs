@@ -0,0 +1,305 @@
+// The common core of the code generators: writing a sum of products in some
+// language's boolean operators, and what to do for inputs the table doesn't
+// list.  The generators decide how names are spelled and what surrounds the
+// expressions.
+use std::collections::BTreeSet;
+use super::{Equation, Term, Truth};
+use super::exact::{Budget, minimize};
+use super::notation::Notation;
+
+// What the generated code gives for inputs that are not in the table.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DefaultOutput {
+	// every output is 0; the equations already say so.
+	Zero,
+	// the given value for each output of the table.
+	Bits(Vec<bool>),
+	// anything: the minimizer may use those inputs freely.
+	DontCare,
+	// the inputs are a bug; the code fails an assertion.
+	Panic,
+}
+
+impl DefaultOutput {
+	// Parses a --default-output value: 'zero', 'dc', 'panic', or one 0/1 per
+	// output of the table.
+	pub fn parse(s: &str, nout: usize) -> Result<Self, String> {
+		match s {
+			"zero" => return Ok(DefaultOutput::Zero),
+			"dc" => return Ok(DefaultOutput::DontCare),
+			"panic" => return Ok(DefaultOutput::Panic),
+			_ => {},
+		}
+		if s.len() != nout || !s.chars().all(|c| c == '0' || c == '1') {
+			return Err(format!("Invalid --default-output '{}'; expected 'zero', \
+			                    'dc', 'panic' or {} bits.", s, nout));
+		}
+		Ok(DefaultOutput::Bits(s.chars().map(|c| c == '1').collect()))
+	}
+}
+
+// The inputs a table lists, for generating the default branch.
+#[derive(Clone)]
+pub struct Unlisted {
+	pub policy: DefaultOutput,
+	// a cover of the listed inputs, or None when the table lists every input
+	// and no default branch is needed.
+	pub listed: Option<Equation>,
+}
+
+impl Unlisted {
+	pub fn new(policy: DefaultOutput, tbl: &Truth, nbits: usize,
+	           invars: &[String]) -> Self {
+		// ordered, so the cover is the same from run to run.
+		let rows: BTreeSet<&Vec<bool>> = tbl.table.iter().map(|e| &e.input).collect();
+		if rows.len() == 1 << nbits {
+			return Unlisted{policy, listed: None};
+		}
+		let mut cover = Equation{index: 0, terms: vec![], dc: vec![],
+		                         varname: "listed".to_string()};
+		for inp in rows {
+			let mut term = Term::compute(inp);
+			term.names = invars.to_vec();
+			cover.terms.push(term);
+		}
+		// any cover will do, but a small one reads better in the generated code.
+		let mut budget = Budget::new(None, Some(10000));
+		cover.terms = minimize(&cover, nbits, &mut budget).terms;
+		Unlisted{policy, listed: Some(cover)}
+	}
+
+	// Whether a default branch is needed, i.e. some input is unlisted and the
+	// equations alone don't give the right answer for it.
+	pub fn branch(&self) -> Option<&Equation> {
+		match self.policy {
+			DefaultOutput::Zero | DefaultOutput::DontCare => None,
+			_ => self.listed.as_ref(),
+		}
+	}
+
+	// Whether the table lists the input pattern.
+	pub fn is_listed(&self, inp: &[bool]) -> bool {
+		self.listed.as_ref().is_none_or(|c| c.evaluate(inp))
+	}
+}
+
+// A language's spelling of the boolean operators and constants.
+pub struct Syntax {
+	pub not: &'static str, // prefix, including any space needed after it
+	pub and: &'static str,
+	pub or: &'static str,
+	pub zero: &'static str,
+	pub one: &'static str,
+}
+
+// A product term, e.g. "not a and b".  'names' are the input names as the
+// language spells them.  A term without literals is always true.
+pub fn product(term: &Term, names: &[String], syn: &Syntax) -> String {
+	if term.is_universal() {
+		return syn.one.to_string();
+	}
+	let lits: Vec<String> = term.bits.iter().map(|&(idx, val)| {
+		if val {
+			names[idx].clone()
+		} else {
+			format!("{}{}", syn.not, names[idx])
+		}
+	}).collect();
+	lits.join(syn.and)
+}
+
+// The equation's right-hand side.  Products are parenthesized when there is
+// more than one of them, as some languages give 'and' and 'or' the same
+// precedence.
+pub fn sum_of_products(eqn: &Equation, names: &[String], syn: &Syntax) -> String {
+	if eqn.terms.is_empty() {
+		return syn.zero.to_string();
+	}
+	if eqn.terms.len() == 1 {
+		return product(&eqn.terms[0], names, syn);
+	}
+	let products: Vec<String> = eqn.terms.iter().map(|t| {
+		if t.bits.len() > 1 {
+			format!("({})", product(t, names, syn))
+		} else {
+			product(t, names, syn)
+		}
+	}).collect();
+	products.join(syn.or)
+}
+
+// A product several equations have, under the name it is defined by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shared {
+	pub name: String,
+	pub term: Term,
+}
+
+// The products that at least 'min_uses' of the equations have, named
+// 'prefix' and a number in the order they first appear.  Single literals are
+// left alone; naming them would save nothing.
+pub fn shared_products(eqns: &[Equation], prefix: &str, min_uses: usize) -> Vec<Shared> {
+	let mut seen: Vec<Term> = vec![];
+	for eqn in eqns.iter() {
+		for t in eqn.terms.iter() {
+			if t.bits.len() > 1 && !seen.contains(t) {
+				seen.push(t.clone());
+			}
+		}
+	}
+	seen.into_iter()
+		.filter(|t| eqns.iter().filter(|e| e.terms.contains(t)).count() >= min_uses)
+		.enumerate()
+		.map(|(k, t)| Shared{name: format!("{}{}", prefix, k), term: t})
+		.collect()
+}
+
+// The equations with each shared product replaced by a variable of its name;
+// the variables come after the inputs, in the order of 'shared'.
+// Returns those equations and the names of all variables.
+pub fn substitute(eqns: &[Equation], shared: &[Shared], invars: &[String]) ->
+	(Vec<Equation>, Vec<String>) {
+	let mut names = invars.to_vec();
+	names.extend(shared.iter().map(|s| s.name.clone()));
+	let eqns = eqns.iter().map(|eqn| {
+		let mut copy = eqn.clone();
+		copy.terms = eqn.terms.iter().map(|t| {
+			match shared.iter().position(|s| s.term == *t) {
+				Some(k) => Term{bits: vec![(invars.len() + k, true)],
+				                names: names.clone()},
+				None => t.clone(),
+			}
+		}).collect();
+		copy
+	}).collect();
+	(eqns, names)
+}
+
+// Whether following what each equation uses from 'from' leads to 'to'.
+fn uses(used: &[Option<usize>], from: usize, to: usize) -> bool {
+	let mut e = from;
+	loop {
+		if e == to {
+			return true;
+		}
+		match used[e] {
+			Some(next) => e = next,
+			None => return false,
+		}
+	}
+}
+
+// The equations with each computed from another output where that takes fewer
+// literals (see Equation::substitute), reordered so that every output comes
+// after the one it uses.  Each uses the one saving the most literals, but
+// never one that uses it in turn: for outputs that are equal, x = y and y = x
+// would define neither.
+pub fn reuse_outputs(eqns: &[Equation]) -> Vec<Equation> {
+	let literals = |e: &Equation| e.terms.iter().map(|t| t.len()).sum::<usize>();
+	let mut used: Vec<Option<usize>> = vec![None; eqns.len()];
+	let mut rewritten = eqns.to_vec();
+	for e in 0..eqns.len() {
+		let best = (0..eqns.len()).filter(|&o| !uses(&used, o, e))
+			.filter_map(|o| eqns[e].substitute(&eqns[o]).map(|eqn| (o, eqn)))
+			.min_by_key(|&(o, ref eqn)| (literals(eqn), o));
+		if let Some((o, eqn)) = best {
+			used[e] = Some(o);
+			rewritten[e] = eqn;
+		}
+	}
+	// each time the first listed whose output it uses is out.
+	let mut out = vec![false; eqns.len()];
+	let mut rv: Vec<Equation> = vec![];
+	while rv.len() < eqns.len() {
+		let e = (0..eqns.len()).find(|&e| !out[e] && used[e].is_none_or(|o| out[o]))
+			.expect("no output uses one using it");
+		out[e] = true;
+		rv.push(rewritten[e].clone());
+	}
+	rv
+}
+
+// The shared products' definitions, 't0 = bc';', followed by the equations
+// using them.
+pub fn text(eqns: &[Equation], shared: &[Shared], invars: &[String], n: &Notation) ->
+	String {
+	let mut s = String::new();
+	for sh in shared.iter() {
+		s += &format!("{} = {};\n", sh.name, n.term(&sh.term));
+	}
+	for eqn in substitute(eqns, shared, invars).0 {
+		s += &format!("{}\n", n.equation(&eqn));
+	}
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const C: Syntax = Syntax{not: "!", and: " && ", or: " || ", zero: "false",
+	                         one: "true"};
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|n| n.to_string()).collect()
+	}
+
+	fn sop(s: &str) -> String {
+		sum_of_products(&Equation::parse(s, &names()).unwrap(), &names(), &C)
+	}
+
+	#[test]
+	fn default_outputs() {
+		assert_eq!(DefaultOutput::parse("zero", 2), Ok(DefaultOutput::Zero));
+		assert_eq!(DefaultOutput::parse("dc", 2), Ok(DefaultOutput::DontCare));
+		assert_eq!(DefaultOutput::parse("01", 2),
+		           Ok(DefaultOutput::Bits(vec![false, true])));
+		assert!(DefaultOutput::parse("011", 2).is_err());
+		assert!(DefaultOutput::parse("0x", 2).is_err());
+	}
+
+	#[test]
+	fn shared() {
+		// the small example's shared cover.
+		let eqns = vec![Equation::parse("x = a'b'c + bc' + ac'", &names()).unwrap(),
+		                Equation::parse("y = c' + bc' + ac' + ab'", &names()).unwrap()];
+		let shared = shared_products(&eqns, "t", 2);
+		let defs: Vec<String> = shared.iter().map(|s| format!("{} = {}", s.name, s.term))
+			.collect();
+		assert_eq!(defs, vec!["t0 = bc'", "t1 = ac'"]);
+		let out = text(&eqns, &shared, &names(), &Notation::classic());
+		assert_eq!(out, "t0 = bc';\nt1 = ac';\nx = a'b'c + t0 + t1 + ;\n\
+		                 y = c' + t0 + t1 + ab' + ;\n");
+		assert_eq!(out.matches("bc'").count(), 1);
+		assert!(shared_products(&eqns, "t", 3).is_empty());
+		assert_eq!(shared_products(&eqns, "s_", 1).len(), 4);
+	}
+
+	#[test]
+	fn reused_outputs() {
+		let parse = |s: &str, idx: usize| {
+			let mut eqn = Equation::parse(s, &names()).unwrap();
+			eqn.index = idx;
+			eqn
+		};
+		// y is listed first but uses x, which z uses too.
+		let eqns = vec![parse("y = ab + c", 0), parse("x = ab", 1), parse("z = abc", 2)];
+		let strs: Vec<String> = reuse_outputs(&eqns).iter().map(|e| e.to_string())
+			.collect();
+		assert_eq!(strs, vec!["x = ab + ;", "y = x + c + ;", "z = cx + ;"]);
+		// equal outputs: one is computed from the other, not both.
+		let eqns = vec![parse("p = ab + bc", 0), parse("q = ab + bc", 1)];
+		let strs: Vec<String> = reuse_outputs(&eqns).iter().map(|e| e.to_string())
+			.collect();
+		assert_eq!(strs, vec!["q = ab + bc + ;", "p = q + ;"]);
+	}
+
+	#[test]
+	fn sums() {
+		assert_eq!(sop("x = a'b + c"), "(!a && b) || c");
+		assert_eq!(sop("x = a'b"), "!a && b");
+		assert_eq!(sop("x = 0"), "false");
+		assert_eq!(sop("x = 1"), "true");
+		assert_eq!(sop("x = 1 + a"), "true || a");
+	}
+}
@@ -0,0 +1,148 @@
+// Listing every input pattern.  Whatever goes through all the patterns over
+// some inputs -- a template, a table built from a function or expressions,
+// the inputs a table doesn't list or the constraints rule out, the entries of
+// a lookup or jump table, a fixed complement -- takes them from here, after
+// check has weighed their count against the limit, --max-enumerate on the
+// command line.  The patterns are made one at a time as they are taken, or a
+// chunk at a time, so that going through them needs no more memory than what
+// is kept of them.
+use super::bits::{BitOrder, bit_unpack};
+use super::rng::Rng;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+// The most inputs whose patterns can be counted, whatever the limit.
+pub const MAX_BITS: usize = 63;
+
+// Fails when the patterns over 'nbits' inputs are too many to go through,
+// more than 2^limit; 'instead' says what else would do, after a semicolon.
+pub fn check(nbits: usize, limit: usize, instead: &str) -> Result<(), String> {
+	let limit = limit.min(MAX_BITS);
+	if nbits <= limit {
+		return Ok(());
+	}
+	Err(format!("Cannot enumerate the 2^{} patterns of {} inputs; at most 2^{} are \
+	             allowed{}.", nbits, nbits, limit, instead))
+}
+
+// The patterns over 'nbits' inputs in counting order, the first input the
+// most significant, once check allows them.
+pub fn inputs(nbits: usize, limit: usize) -> Result<Inputs, String> {
+	check(nbits, limit, "")?;
+	Ok(Inputs::every(nbits))
+}
+
+// The patterns, made as they are taken.
+#[derive(Clone, Debug)]
+pub struct Inputs {
+	next: u64,
+	end: u64,
+	nbits: usize,
+	order: BitOrder,
+}
+
+impl Inputs {
+	// Every pattern over 'nbits' inputs, for callers that have checked their
+	// count already, as the writers of lookup and jump tables rely on
+	// theirs to.
+	pub fn every(nbits: usize) -> Self {
+		assert!(nbits <= MAX_BITS);
+		Inputs{next: 0, end: 1 << nbits, nbits, order: BitOrder::Msb}
+	}
+
+	// The patterns in counting order of their values packed as 'order' says,
+	// so that the k'th is the one whose value is k.
+	pub fn ordered(self, order: BitOrder) -> Self {
+		Inputs{order, ..self}
+	}
+
+	// The patterns 'size' at a time, the last chunk holding what is left.
+	pub fn chunks(self, size: usize) -> Chunks {
+		assert!(size > 0);
+		Chunks{inputs: self, size}
+	}
+}
+
+impl Iterator for Inputs {
+	type Item = Vec<bool>;
+
+	fn next(&mut self) -> Option<Vec<bool>> {
+		if self.next == self.end {
+			return None;
+		}
+		self.next += 1;
+		Some(bit_unpack(self.next - 1, self.nbits, self.order))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let left = usize::try_from(self.end - self.next).ok();
+		(left.unwrap_or(usize::MAX), left)
+	}
+}
+
+pub struct Chunks {
+	inputs: Inputs,
+	size: usize,
+}
+
+impl Iterator for Chunks {
+	type Item = Vec<Vec<bool>>;
+
+	fn next(&mut self) -> Option<Vec<Vec<bool>>> {
+		let chunk: Vec<Vec<bool>> = self.inputs.by_ref().take(self.size).collect();
+		if chunk.is_empty() { None } else { Some(chunk) }
+	}
+}
+
+// 'count' distinct patterns over 'nbits' inputs picked at random from 'seed',
+// or every pattern when there are no more than that, in counting order with
+// the first input the most significant.
+pub fn sample(nbits: usize, count: u64, seed: u64) -> Vec<Vec<bool>> {
+	if nbits <= MAX_BITS && count >= 1 << nbits {
+		return Inputs::every(nbits).collect();
+	}
+	let mut rng = Rng::new(seed);
+	let mut rv: BTreeSet<Vec<bool>> = BTreeSet::new();
+	while (rv.len() as u64) < count {
+		rv.insert((0..nbits).map(|_| rng.bit()).collect());
+	}
+	rv.into_iter().collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn guarded_enumeration() {
+		let err = inputs(40, 24).unwrap_err();
+		assert_eq!(err, "Cannot enumerate the 2^40 patterns of 40 inputs; at most 2^24 \
+		                 are allowed.");
+		assert!(check(70, 100, "").unwrap_err().contains("at most 2^63"));
+		assert_eq!(check(30, 24, "; give --sample").unwrap_err(),
+		           "Cannot enumerate the 2^30 patterns of 30 inputs; at most 2^24 are \
+		            allowed; give --sample.");
+		// twenty inputs stream: nothing is listed before it is taken, and no
+		// chunk holds more than its size.
+		let all = inputs(20, 24).unwrap();
+		assert_eq!(all.size_hint(), (1 << 20, Some(1 << 20)));
+		assert!(std::mem::size_of::<Inputs>() <= 32);
+		let (mut chunks, mut seen, mut last) = (0, 0, vec![]);
+		for chunk in all.chunks(4096) {
+			assert!(chunk.len() <= 4096);
+			chunks += 1;
+			seen += chunk.len();
+			last = chunk.last().unwrap().clone();
+		}
+		assert_eq!((chunks, seen), (256, 1 << 20));
+		assert_eq!(last, vec![true; 20]);
+		let lsb: Vec<Vec<bool>> = Inputs::every(2).ordered(BitOrder::Lsb).collect();
+		assert_eq!(lsb, vec![vec![false, false], vec![true, false], vec![false, true],
+		                     vec![true, true]]);
+		let picked = sample(40, 100, 7);
+		assert_eq!(picked.len(), 100);
+		assert!(picked.windows(2).all(|w| w[0] < w[1]));
+		assert_eq!(sample(40, 100, 7), picked);
+		assert_eq!(sample(2, 10, 7).len(), 4);
+	}
+}
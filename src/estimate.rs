@@ -0,0 +1,281 @@
+// How hard a table is to minimize, estimated before minimizing it: for each
+// output the minterms, don't-cares and maxterms, an upper bound on its prime
+// implicants, and whether it is constant or unate, and from those which
+// algorithm to use and roughly how long it will take.
+//
+// Counting the rows is cheap and always exact.  Checking unateness looks at
+// each row's neighbours, so above SAMPLE_ROWS rows only a sample of them is
+// checked; a sampled table may be reported unate when it isn't quite.
+use std::collections::HashMap;
+use serde_json;
+use super::{Entry, Truth};
+
+// The most rows whose neighbours are all checked for unateness.
+pub const SAMPLE_ROWS: usize = 1 << 16;
+
+// What the estimate knows about one output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputProfile {
+	pub name: String,
+	pub minterms: usize,
+	pub dont_cares: usize,
+	pub maxterms: usize,
+	// the value of an output that has it on every row listing it.
+	pub constant: Option<bool>,
+	// whether, in each input, the output only rises or only falls as the
+	// input does, on the rows checked.
+	pub unate: bool,
+	// at most this many prime implicants; see prime_bound.
+	pub prime_bound: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+	pub inputs: usize,
+	pub rows: usize,
+	pub outputs: Vec<OutputProfile>,
+	// whether unateness was checked on a sample of the rows only.
+	pub sampled: bool,
+}
+
+// An upper bound on the prime implicants of a function of 'nbits' inputs
+// with 'on' minterms and 'dc' don't-cares.  A prime of d free inputs holds
+// 2^d of the on and dc inputs, so d is at most log2(on + dc), and it holds
+// some minterm, of which there are 'on', each in C(n, d) cubes of that size.
+// No function has more than 3^n / n primes either.
+pub fn prime_bound(nbits: usize, on: usize, dc: usize) -> f64 {
+	if on == 0 {
+		return 0.0;
+	}
+	let dims = ((on + dc) as f64).log2().floor() as usize;
+	let mut cubes = 0.0;
+	let mut choose = 1.0;
+	for d in 0..dims.min(nbits) + 1 {
+		cubes += choose;
+		choose = choose * (nbits - d) as f64 / (d + 1) as f64;
+	}
+	let most = 3f64.powi(nbits as i32) / (nbits.max(1) as f64);
+	(on as f64 * cubes).min(most).max(1.0)
+}
+
+// The profile of each output of the table, over 'nbits' inputs.
+pub fn profile(tbl: &Truth, nbits: usize, outvars: &[String]) -> Profile {
+	let rows: HashMap<&[bool], &Entry> = tbl.table.iter()
+		.map(|e| (e.input.as_slice(), e)).collect();
+	let sampled = tbl.table.len() > SAMPLE_ROWS;
+	// every k'th row, k spreading the sample across the whole table.
+	let step = if sampled { tbl.table.len() / SAMPLE_ROWS + 1 } else { 1 };
+	let outputs = outvars.iter().enumerate().map(|(o, name)| {
+		let minterms = tbl.minterms(o).count();
+		let maxterms = tbl.maxterms(o).count();
+		let dont_cares = tbl.dont_cares_of(o).count();
+		let constant = match (minterms, maxterms) {
+			(_, 0) => Some(true),
+			(0, _) => Some(false),
+			_ => None,
+		};
+		// rises[i]: some row where raising input i raises the output.
+		let mut rises = vec![false; nbits];
+		let mut falls = vec![false; nbits];
+		for e in tbl.table.iter().step_by(step)
+			.filter(|e| e.cares(o) && !e.input.iter().all(|&b| b)) {
+			let mut flipped = e.input.clone();
+			for i in (0..nbits).filter(|&i| !e.input[i]) {
+				flipped[i] = true;
+				if let Some(other) = rows.get(flipped.as_slice()).filter(|r| r.cares(o)) {
+					match (e.output[o], other.output[o]) {
+						(false, true) => rises[i] = true,
+						(true, false) => falls[i] = true,
+						_ => {},
+					}
+				}
+				flipped[i] = false;
+			}
+		}
+		OutputProfile{name: name.clone(), minterms, dont_cares, maxterms, constant,
+		              unate: (0..nbits).all(|i| !(rises[i] && falls[i])),
+		              prime_bound: prime_bound(nbits, minterms, dont_cares)}
+	}).collect();
+	Profile{inputs: nbits, rows: tbl.table.len(), outputs, sampled}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+	Exact,
+	Greedy,
+	// greedy, with --memory-limit keeping the tables of merged terms in bounds.
+	GreedyLimited,
+}
+
+impl Algorithm {
+	// The options selecting it.
+	pub fn options(&self) -> &'static str {
+		match *self {
+			Algorithm::Exact => "--algorithm exact",
+			Algorithm::Greedy => "--algorithm greedy",
+			Algorithm::GreedyLimited => "--algorithm greedy with --memory-limit",
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recommendation {
+	pub algorithm: Algorithm,
+	// the order of magnitude of the time it takes: "milliseconds", "seconds",
+	// "minutes" or "hours".
+	pub runtime: &'static str,
+	pub reason: String,
+}
+
+// The time 'work' steps take, to an order of magnitude.
+fn runtime(work: f64) -> &'static str {
+	if work < 1e6 {
+		"milliseconds"
+	} else if work < 1e9 {
+		"seconds"
+	} else if work < 1e11 {
+		"minutes"
+	} else {
+		"hours"
+	}
+}
+
+// Which algorithm suits the profile.  Constant and unate outputs have a
+// single minimal cover, of their essential primes, which the exact search
+// finds at once.  Otherwise the exact search's chart grows with the primes
+// and the minterms they cover, and is worth it while that is small; greedy
+// merging grows with the minterms and don't-cares and the inputs, and needs
+// --memory-limit once the cubes it expands into may not fit in memory.
+pub fn recommend(profile: &Profile) -> Recommendation {
+	let n = profile.inputs as f64;
+	let cubes: f64 = profile.outputs.iter()
+		.map(|o| (o.minterms + o.dont_cares) as f64 * n * n).sum();
+	if profile.outputs.iter().all(|o| o.constant.is_some() || o.unate) {
+		return Recommendation{algorithm: Algorithm::Exact, runtime: runtime(cubes),
+		                      reason: "every output is constant or unate, so its \
+		                               minimal cover is its essential primes".to_string()};
+	}
+	let chart: f64 = profile.outputs.iter().filter(|o| o.constant.is_none() && !o.unate)
+		.map(|o| o.prime_bound * o.minterms as f64).sum();
+	if chart < 1e8 {
+		Recommendation{algorithm: Algorithm::Exact, runtime: runtime(chart + cubes),
+		               reason: "the prime implicant chart is small enough to search"
+		                       .to_string()}
+	} else if profile.inputs <= 20 {
+		Recommendation{algorithm: Algorithm::Greedy, runtime: runtime(cubes),
+		               reason: "the exact search could face too many prime implicants"
+		                       .to_string()}
+	} else {
+		Recommendation{algorithm: Algorithm::GreedyLimited, runtime: runtime(cubes * n),
+		               reason: "the cubes of more than 20 inputs may not fit in memory"
+		                       .to_string()}
+	}
+}
+
+// The profile and recommendation for people, a line per output.
+pub fn report(profile: &Profile, rec: &Recommendation) -> String {
+	let mut s = format!("{} rows over {} inputs.\n", profile.rows, profile.inputs);
+	for o in profile.outputs.iter() {
+		let kind = match o.constant {
+			Some(v) => format!("constant {}", v as u8),
+			None if o.unate => "unate".to_string(),
+			None => "not unate".to_string(),
+		};
+		s += &format!("{}: {} minterms, {} don't-cares, {} maxterms; at most {:.0} \
+		               primes; {}.\n", o.name, o.minterms, o.dont_cares, o.maxterms,
+		              o.prime_bound, kind);
+	}
+	if profile.sampled {
+		s += &format!("Unateness was checked on a sample of about {} rows.\n",
+		              SAMPLE_ROWS);
+	}
+	s += &format!("Recommended: {}, taking {}; {}.\n", rec.algorithm.options(),
+	              rec.runtime, rec.reason);
+	s
+}
+
+// The same as a JSON object.
+pub fn json(profile: &Profile, rec: &Recommendation) -> String {
+	let outputs: Vec<serde_json::Value> = profile.outputs.iter().map(|o| serde_json::json!({
+		"name": o.name, "minterms": o.minterms, "dont_cares": o.dont_cares,
+		"maxterms": o.maxterms, "constant": o.constant, "unate": o.unate,
+		"prime_bound": o.prime_bound,
+	})).collect();
+	serde_json::json!({
+		"inputs": profile.inputs, "rows": profile.rows, "sampled": profile.sampled,
+		"outputs": outputs,
+		"recommendation": {"algorithm": rec.algorithm.options(), "runtime": rec.runtime,
+		                   "reason": rec.reason},
+	}).to_string()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	fn output(minterms: usize, dc: usize, unate: bool, nbits: usize) -> OutputProfile {
+		OutputProfile{name: "x".to_string(), minterms, dont_cares: dc,
+		              maxterms: (1 << nbits) - minterms - dc, constant: None,
+		              unate, prime_bound: prime_bound(nbits, minterms, dc)}
+	}
+
+	fn synthetic(nbits: usize, outputs: Vec<OutputProfile>) -> Profile {
+		Profile{inputs: nbits, rows: 1 << nbits, outputs, sampled: false}
+	}
+
+	#[test]
+	fn recommendations() {
+		// small and binate: the exact search, at once.
+		let rec = recommend(&synthetic(4, vec![output(6, 0, false, 4)]));
+		assert_eq!((rec.algorithm, rec.runtime), (Algorithm::Exact, "milliseconds"));
+		// unate, however large: its essential primes.
+		let rec = recommend(&synthetic(20, vec![output(1 << 19, 0, true, 20)]));
+		assert_eq!(rec.algorithm, Algorithm::Exact);
+		// a large binate function: too many primes to search.
+		let rec = recommend(&synthetic(18, vec![output(1 << 17, 1 << 10, false, 18)]));
+		assert_eq!((rec.algorithm, rec.runtime), (Algorithm::Greedy, "seconds"));
+		let rec = recommend(&synthetic(24, vec![output(1 << 23, 0, false, 24)]));
+		assert_eq!((rec.algorithm, rec.runtime), (Algorithm::GreedyLimited, "hours"));
+	}
+
+	#[test]
+	fn prime_bounds() {
+		assert_eq!(prime_bound(3, 0, 0), 0.0);
+		// a single minterm is its only prime.
+		assert_eq!(prime_bound(3, 1, 0), 1.0);
+		// 4 minterms could make cubes of up to 2 free inputs, but no function
+		// of 3 inputs has more than 9 primes.
+		assert_eq!(prime_bound(3, 4, 0), 9.0);
+		assert!(prime_bound(10, 512, 0) <= 3f64.powi(10) / 10.0);
+	}
+
+	#[test]
+	fn profiles() {
+		// x is binate in c; y = c' + ab' falls with b and c and rises with a.
+		let tbl = parse("0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		                 1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n".as_bytes(),
+		                0, 3, 2);
+		let names = vec!["x".to_string(), "y".to_string()];
+		let p = profile(&tbl, 3, &names);
+		assert_eq!((p.outputs[0].minterms, p.outputs[0].maxterms), (4, 4));
+		assert!(!p.outputs[0].unate);
+		assert_eq!(p.outputs[1].minterms, 5);
+		assert!(p.outputs[1].unate);
+		let rec = recommend(&p);
+		assert_eq!(rec.algorithm, Algorithm::Exact);
+		assert!(report(&p, &rec).ends_with("Recommended: --algorithm exact, taking \
+		                                    milliseconds; the prime implicant chart is \
+		                                    small enough to search.\n"));
+		let doc: serde_json::Value = serde_json::from_str(&json(&p, &rec)).unwrap();
+		assert_eq!(doc["outputs"][1]["minterms"], 5);
+		assert_eq!(doc["recommendation"]["algorithm"], "--algorithm exact");
+		// or and and are unate; a constant is reported as such.
+		let tbl = Truth::from_fn(2, 3, |inp| vec![inp[0] || inp[1], inp[0] && !inp[1],
+		                                         true]).unwrap();
+		let names: Vec<String> = vec!["or".into(), "andn".into(), "one".into()];
+		let p = profile(&tbl, 2, &names);
+		assert!(p.outputs[0].unate && p.outputs[1].unate);
+		assert_eq!(p.outputs[2].constant, Some(true));
+	}
+}
@@ -0,0 +1,1229 @@
+// Exact two-level minimization of a single output.  The prime implicants are
+// generated Quine-McCluskey style from the output's minterms, then a minimum
+// cost subset of them that covers every minterm is found by branch and bound,
+// on what is left of the chart once the essential primes are taken and the
+// dominated rows and columns dropped.
+//
+// The cover search is exponential in the worst case, so it runs against a
+// Budget.  The search always holds a valid cover (the greedy one to start
+// with) and only ever replaces it by a cheaper one, so running out of budget
+// still yields a correct answer; it just isn't proven to be minimal.
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::mem;
+use std::time::{Duration, Instant};
+use super::{Equation, Term};
+use super::bits::{BitOrder, bit_unpack};
+use super::coverage::BitSet;
+use super::progress::{Progress, Silent};
+
+// Limits on the work the exact search may do: a wall-clock deadline and/or a
+// maximum number of search nodes.  One Budget can be shared by several
+// equations, in which case the limits apply to all of them together.
+//
+// It may also limit the memory the cube expansion and the prime implicant
+// chart take, and the passes and merges of the greedy simplifier's
+// expansion, which unlike the others apply to each equation on its own.
+pub struct Budget {
+	deadline: Option<Instant>,
+	max_steps: Option<u64>,
+	steps: u64,
+	exhausted: bool,
+	max_bytes: Option<usize>,
+	out_of_memory: bool,
+	max_passes: Option<usize>,
+	max_merges: Option<usize>,
+}
+
+impl Budget {
+	pub fn unlimited() -> Self { Budget::new(None, None) }
+	pub fn new(timeout: Option<Duration>, max_steps: Option<u64>) -> Self {
+		Budget{deadline: timeout.map(|t| Instant::now() + t), max_steps,
+		       steps: 0, exhausted: false, max_bytes: None, out_of_memory: false,
+		       max_passes: None, max_merges: None}
+	}
+
+	// Limits the cubes and charts to about 'bytes'.  A search whose tables would
+	// be larger stops building them and falls back to the greedy simplifier
+	// over the cubes it has, and the expansion of the greedy simplifier stops
+	// where it is.
+	pub fn limit_memory(&mut self, bytes: usize) {
+		self.max_bytes = Some(bytes);
+	}
+
+	// Limits the greedy simplifier's expansion to 'passes' levels of merging
+	// and 'merges' merged cubes, None for no limit.  An expansion reaching
+	// either stops where it is, as at the memory limit, and the equation is
+	// covered from the cubes it got to: correctly, but less simply.
+	pub fn limit_simplify(&mut self, passes: Option<usize>, merges: Option<usize>) {
+		self.max_passes = passes;
+		self.max_merges = merges;
+	}
+
+	// Whether the limits were reached.
+	pub fn exhausted(&self) -> bool { self.exhausted }
+
+	// Whether some equation's tables would have outgrown the memory limit.
+	pub fn out_of_memory(&self) -> bool { self.out_of_memory }
+
+	// Whether tables of about 'bytes' are within the memory limit; if not, that
+	// is recorded.
+	fn fits(&mut self, bytes: usize) -> bool {
+		match self.max_bytes {
+			Some(max) if bytes > max => {
+				self.out_of_memory = true;
+				false
+			},
+			_ => true,
+		}
+	}
+
+	// Accounts for one unit of work.  Returns false once the budget is used up;
+	// it stays used up from then on.
+	fn step(&mut self) -> bool {
+		if self.exhausted {
+			return false;
+		}
+		self.steps += 1;
+		if let Some(max) = self.max_steps {
+			if self.steps > max {
+				self.exhausted = true;
+			}
+		}
+		// reading the clock is comparatively slow; the steps are not.
+		if let Some(deadline) = self.deadline {
+			if self.steps % 64 == 1 && Instant::now() >= deadline {
+				self.exhausted = true;
+			}
+		}
+		!self.exhausted
+	}
+}
+
+// How far an expansion of cubes got.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expansion {
+	Finished,
+	// stopped at the budget's memory limit.
+	OutOfMemory,
+	// stopped at the budget's limit on the simplifier's passes or merges.
+	Limited,
+}
+
+// The result of the exact search: a cover for the output, and whether the
+// search finished and thus proved it minimal.
+pub struct Solution {
+	pub terms: Vec<Term>,
+	pub minimal: bool,
+	// whether the chart would have outgrown the memory limit, so that the
+	// cover is the greedy simplifier's.
+	pub simplified: bool,
+}
+
+// A cube over all input variables: Some(polarity) for a literal, None for a
+// variable that has been eliminated.
+pub type Cube = Vec<Option<bool>>;
+
+pub fn cube_to_term(cube: &Cube, names: &[String]) -> Term {
+	let bits = cube.iter().enumerate()
+		.filter_map(|(i, b)| b.map(|v| (i, v)))
+		.collect();
+	Term{bits, names: names.to_vec()}
+}
+
+pub fn cube_covers(cube: &Cube, minterm: &[bool]) -> bool {
+	cube.iter().zip(minterm.iter()).all(|(c, m)| c.is_none_or(|v| v == *m))
+}
+
+// The cost of a cube's literals, each input's literal costing its weight.
+fn weighted(cube: &Cube, weights: &[usize]) -> usize {
+	cube.iter().zip(weights.iter()).filter(|&(c, _)| c.is_some()).map(|(_, &w)| w)
+		.sum()
+}
+
+fn literals(cube: &Cube) -> usize {
+	cube.iter().filter(|c| c.is_some()).count()
+}
+
+// The cube over 'nbits' inputs that a term describes.
+pub fn term_cube(term: &Term, nbits: usize) -> Cube {
+	let mut rv: Cube = vec![None; nbits];
+	for &(idx, val) in term.bits.iter() {
+		rv[idx] = Some(val);
+	}
+	rv
+}
+
+// Expands a term into the minterms (complete input patterns) it covers.
+fn expand(term: &Term, nbits: usize) -> Vec<Vec<bool>> {
+	expand_cube(&term_cube(term, nbits))
+}
+
+// Expands a cube into the minterms it covers.
+pub fn expand_cube(base: &Cube) -> Vec<Vec<bool>> {
+	let nbits = base.len();
+	let free: Vec<usize> = (0..nbits).filter(|&i| base[i].is_none()).collect();
+	let mut rv = Vec::with_capacity(1 << free.len());
+	for m in 0..1u64 << free.len() {
+		let mut pattern: Vec<bool> = base.iter().map(|b| b.unwrap_or(false)).collect();
+		for (&idx, b) in free.iter().zip(bit_unpack(m, free.len(), BitOrder::Lsb)) {
+			pattern[idx] = b;
+		}
+		rv.push(pattern);
+	}
+	rv
+}
+
+// The cube obtained by merging two cubes that differ in exactly one literal,
+// which must be present in both with opposite polarity.
+fn combine(a: &Cube, b: &Cube) -> Option<Cube> {
+	let mut differ: Option<usize> = None;
+	for (i, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+		if x == y {
+			continue;
+		}
+		if x.is_none() || y.is_none() || differ.is_some() {
+			return None;
+		}
+		differ = Some(i);
+	}
+	differ.map(|i| {
+		let mut rv = a.clone();
+		rv[i] = None;
+		rv
+	})
+}
+
+// Whether the cube has no literals, covering every input.
+pub fn is_universal(cube: &Cube) -> bool {
+	cube.iter().all(|b| b.is_none())
+}
+
+// Whether cube 'a' contains cube 'b': every literal of 'a' is one of 'b'.
+pub fn cube_contains(a: &Cube, b: &Cube) -> bool {
+	a.iter().zip(b.iter()).all(|(x, y)| x.is_none() || x == y)
+}
+
+// The largest cubes that merging the given ones pairwise can give.  Each
+// level merges the cubes of the previous one that have the same eliminated
+// inputs and differ in one literal, which as in Quine-McCluskey means they
+// sit in neighbouring buckets of (eliminated inputs, positive literals).  A
+// cube is kept for as long as some merge might still need it, and is final
+// once nothing on its level merges with it.  Starting from minterms this
+// gives every prime implicant, whatever order the cubes come in; starting
+// from larger cubes, the final cubes that others contain are dropped.  Once
+// the cube of no literals appears, e.g. from a and a', it contains every
+// other, and is the only one returned.  Reports the number of cubes after
+// each level.
+pub fn expand_cubes(cubes: Vec<Cube>, progress: &mut dyn Progress) -> Vec<Cube> {
+	expand_cubes_within(cubes, progress, &mut Budget::unlimited(), false).0
+}
+
+// The approximate size of 'n' cubes over 'nbits' inputs, as the expansion
+// holds them: the cube, and its index in a bucket.
+fn cube_bytes(n: usize, nbits: usize) -> usize {
+	n * (mem::size_of::<Cube>() + nbits * mem::size_of::<Option<bool>>() +
+	     mem::size_of::<usize>())
+}
+
+// As expand_cubes, stopping at the budget's memory limit and, when
+// 'limited', at its limits on the simplifier's passes and merges.  Returns
+// the cubes and how far the expansion got; when it stopped, the cubes are
+// the final ones so far and those of the level it stopped on, with at a
+// limit those merged from them so far, which still contain every cube given,
+// but need not be prime.
+pub fn expand_cubes_within(cubes: Vec<Cube>, progress: &mut dyn Progress,
+                           budget: &mut Budget, limited: bool) -> (Vec<Cube>, Expansion) {
+	let nbits = cubes.first().map_or(0, |c| c.len());
+	let mut primes: Vec<Cube> = vec![];
+	let mut level: BTreeSet<Cube> = cubes.into_iter().collect();
+	if let Some(one) = level.iter().find(|c| is_universal(c)) {
+		return (vec![one.clone()], Expansion::Finished);
+	}
+	let mixed = level.iter().map(literals).collect::<BTreeSet<_>>().len() > 1;
+	let (max_passes, max_merges) = if limited {
+		(budget.max_passes, budget.max_merges)
+	} else {
+		(None, None)
+	};
+	let mut levels: usize = 0;
+	let mut merges: usize = 0;
+	progress.update(level.len(), levels);
+	while !level.is_empty() {
+		let cubes: Vec<Cube> = level.into_iter().collect();
+		if !budget.fits(cube_bytes(cubes.len() + primes.len(), nbits)) {
+			primes.extend(cubes);
+			return (primes, Expansion::OutOfMemory);
+		}
+		if max_passes.is_some_and(|max| levels >= max) {
+			primes.extend(cubes);
+			return (primes, Expansion::Limited);
+		}
+		let key = |c: &Cube| (c.iter().map(|b| b.is_none()).collect::<Vec<bool>>(),
+		                      c.iter().filter(|&&b| b == Some(true)).count());
+		let mut buckets: HashMap<(Vec<bool>, usize), Vec<usize>> = HashMap::new();
+		for (i, c) in cubes.iter().enumerate() {
+			buckets.entry(key(c)).or_default().push(i);
+		}
+		let mut merged = vec![false; cubes.len()];
+		let mut next: BTreeSet<Cube> = BTreeSet::new();
+		for (i, c) in cubes.iter().enumerate() {
+			let (free, pos) = key(c);
+			for &j in buckets.get(&(free, pos + 1)).map_or(&[][..], |v| &v[..]) {
+				if let Some(m) = combine(c, &cubes[j]) {
+					merged[i] = true;
+					merged[j] = true;
+					progress.merged(c, &cubes[j], &m);
+					if is_universal(&m) {
+						progress.update(1, levels + 1);
+						return (vec![m], Expansion::Finished);
+					}
+					next.insert(m);
+					merges += 1;
+					if max_merges.is_some_and(|max| merges >= max) {
+						primes.extend(cubes);
+						primes.extend(next);
+						return (primes, Expansion::Limited);
+					}
+				}
+			}
+			// the next level is where the cubes multiply.
+			if !budget.fits(cube_bytes(cubes.len() + next.len() + primes.len(), nbits)) {
+				primes.extend(cubes);
+				return (primes, Expansion::OutOfMemory);
+			}
+		}
+		for (i, c) in cubes.into_iter().enumerate() {
+			if !merged[i] {
+				primes.push(c);
+			}
+		}
+		level = next;
+		levels += 1;
+		progress.update(level.len() + primes.len(), levels);
+	}
+	if mixed {
+		let all = primes.clone();
+		primes.retain(|p| !all.iter().any(|q| q != p && cube_contains(q, p)));
+	}
+	(primes, Expansion::Finished)
+}
+
+// Computes every prime implicant of the function whose ON-set is 'minterms'.
+fn prime_implicants(minterms: &[Vec<bool>]) -> Vec<Cube> {
+	prime_implicants_within(minterms, &mut Budget::unlimited())
+		.expect("no memory limit")
+}
+
+// As prime_implicants, or None when they would outgrow the budget's memory
+// limit.
+fn prime_implicants_within(minterms: &[Vec<bool>], budget: &mut Budget) ->
+	Option<Vec<Cube>> {
+	let (mut primes, expanded) = expand_cubes_within(minterms.iter()
+		.map(|m| m.iter().map(|&b| Some(b)).collect())
+		.collect(), &mut Silent, budget, false);
+	if expanded != Expansion::Finished {
+		return None;
+	}
+	// fewest literals first, so covers print as "c' + ab'".
+	primes.sort_by_key(|c| (literals(c), c.clone()));
+	Some(primes)
+}
+
+// Cost of a cover: fewer terms first, then fewer literals.
+type Cost = (usize, usize);
+
+// The prime implicant chart and the state of the branch and bound search.
+struct Search<'a> {
+	// for each minterm, the indices of the primes covering it.
+	candidates: Vec<Vec<usize>>,
+	// for each prime, the minterms it covers, and how many.
+	covers: Vec<BitSet>,
+	sizes: Vec<usize>,
+	lits: Vec<usize>,
+	// every minterm.
+	all: BitSet,
+	budget: &'a mut Budget,
+	progress: &'a mut dyn Progress,
+	improvements: usize,
+	best: Vec<usize>,
+	best_cost: Cost,
+	// the cost of the terms taken before the search, which every cover has.
+	taken: Cost,
+	aborted: bool,
+	// when collecting every minimum cover, at most this many of them, each
+	// sorted so that covers differing only in order are the same.
+	max_alternatives: usize,
+	alternatives: BTreeSet<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+	fn new(candidates: Vec<Vec<usize>>, covers: Vec<BitSet>, lits: Vec<usize>,
+	       budget: &'a mut Budget, progress: &'a mut dyn Progress,
+	       max_alternatives: usize) -> Self {
+		let sizes = covers.iter().map(|c| c.count()).collect();
+		let all = BitSet::full(candidates.len());
+		Search{candidates, covers, sizes, lits, all, budget, progress, improvements: 0,
+		       best: vec![], best_cost: (0, 0), taken: (0, 0), aborted: false,
+		       max_alternatives, alternatives: BTreeSet::new()}
+	}
+
+	fn cost(&self, sel: &[usize]) -> Cost {
+		let lits: usize = sel.iter().map(|&p| self.lits[p]).sum();
+		(self.taken.0 + sel.len(), self.taken.1 + lits)
+	}
+
+	// Repeatedly takes the prime covering the most uncovered minterms (ties go
+	// to fewer literals, then to the earlier prime).  Each prime's count of
+	// uncovered minterms goes down as the minterms are covered.
+	fn greedy(&self) -> Vec<usize> {
+		let mut covered = BitSet::new(self.candidates.len());
+		let mut gain = self.sizes.clone();
+		let mut sel: Vec<usize> = vec![];
+		while !self.all.is_subset(&covered) {
+			let pick = (0..self.covers.len())
+				.filter(|&p| gain[p] > 0)
+				.min_by_key(|&p| (usize::MAX - gain[p], self.lits[p], p))
+				.expect("every minterm is covered by some prime");
+			for m in self.covers[pick].iter_without(&covered) {
+				for &p in self.candidates[m].iter() {
+					gain[p] -= 1;
+				}
+			}
+			covered.union_with(&self.covers[pick]);
+			sel.push(pick);
+		}
+		sel
+	}
+
+	fn search(&mut self, sel: &mut Vec<usize>, covered: &BitSet) {
+		if self.aborted {
+			return;
+		}
+		if !self.budget.step() {
+			self.aborted = true;
+			return;
+		}
+		// branch on the uncovered minterm with the fewest candidate primes; a
+		// minterm with a single candidate makes that prime essential.
+		let next = self.all.iter_without(covered)
+			.min_by_key(|&m| self.candidates[m].len());
+		let m = match next {
+			None => {
+				let cost = self.cost(sel);
+				if cost < self.best_cost {
+					self.best = sel.clone();
+					self.best_cost = cost;
+					self.improvements += 1;
+					self.progress.update(cost.0, self.improvements);
+					self.alternatives.clear();
+				}
+				if cost == self.best_cost && self.alternatives.len() < self.max_alternatives {
+					let mut cover = sel.clone();
+					cover.sort();
+					self.alternatives.insert(cover);
+				}
+				return;
+			},
+			Some(m) => m,
+		};
+		let mut options = self.candidates[m].clone();
+		options.sort_by_key(|&p| (usize::MAX - self.sizes[p], self.lits[p], p));
+		let (nterms, nlits) = self.cost(sel);
+		for p in options {
+			// any completion needs at least this prime on top of what we have;
+			// when collecting alternatives, ties are worth exploring too.
+			let bound = (nterms+1, nlits+self.lits[p]);
+			if bound > self.best_cost ||
+			   (bound == self.best_cost && self.max_alternatives == 0) {
+				continue;
+			}
+			let mut with = covered.clone();
+			with.union_with(&self.covers[p]);
+			sel.push(p);
+			self.search(sel, &with);
+			sel.pop();
+			if self.aborted {
+				return;
+			}
+		}
+	}
+}
+
+// Finds a minimum cost cover for the given equation, whose inputs are 'nbits'
+// wide.  The equation's current terms define the ON-set, and its don't-care
+// terms inputs that may be covered or not.
+pub fn minimize(eqn: &Equation, nbits: usize, budget: &mut Budget) -> Solution {
+	minimize_with(eqn, nbits, budget, &mut Silent)
+}
+
+// As minimize, reporting the phases and each improvement of the best cover.
+pub fn minimize_with(eqn: &Equation, nbits: usize, budget: &mut Budget,
+                     progress: &mut dyn Progress) -> Solution {
+	minimize_weighted(eqn, nbits, &vec![1; nbits], budget, progress)
+}
+
+// As minimize_with, with literals of each input costing that input's weight
+// rather than 1, so covers avoid the heavier inputs where they can.
+pub fn minimize_weighted(eqn: &Equation, nbits: usize, weights: &[usize],
+                         budget: &mut Budget, progress: &mut dyn Progress) ->
+	Solution {
+	if eqn.terms.is_empty() {
+		return Solution{terms: vec![], minimal: true, simplified: false};
+	}
+	if let Some(one) = eqn.terms.iter().find(|t| t.is_universal()) {
+		return Solution{terms: vec![one.clone()], minimal: true, simplified: false};
+	}
+	let names = eqn.terms[0].names.clone();
+	progress.phase("prime implicants");
+	let (primes, candidates, covers, lits) = match chart(eqn, nbits, weights, budget) {
+		Some(chart) => chart,
+		None => return fallback(eqn, budget, progress),
+	};
+	let (chosen, minimal) = cover(candidates, covers, lits, budget, progress);
+	Solution{terms: chosen.iter().map(|&p| cube_to_term(&primes[p], &names)).collect(),
+	         minimal, simplified: false}
+}
+
+// The greedy simplifier's cover, for an equation whose chart would outgrow
+// the budget's memory limit; it isn't known to be minimal.
+fn fallback(eqn: &Equation, budget: &mut Budget, progress: &mut dyn Progress) ->
+	Solution {
+	progress.phase("simplify");
+	let mut copy = eqn.clone();
+	copy.simplify_within(progress, budget);
+	Solution{terms: copy.terms, minimal: false, simplified: true}
+}
+
+// Finds every minimum cost cover of the equation, up to 'max' of them, as
+// copies of the equation, reporting the phases as minimize_with does.  When
+// the budget runs out first, the covers are the cheapest found so far but
+// need not be minimal, nor all of them.
+pub fn minimize_all(eqn: &Equation, nbits: usize, weights: &[usize],
+                    budget: &mut Budget, progress: &mut dyn Progress, max: usize) ->
+	Vec<Equation> {
+	if eqn.terms.is_empty() {
+		return vec![eqn.clone()];
+	}
+	let names = eqn.terms[0].names.clone();
+	progress.phase("prime implicants");
+	let (primes, candidates, covers, lits) = match chart(eqn, nbits, weights, budget) {
+		Some(chart) => chart,
+		None => {
+			let mut copy = eqn.clone();
+			copy.terms = fallback(eqn, budget, progress).terms;
+			return vec![copy];
+		},
+	};
+	let nitems = candidates.len();
+	progress.phase("cover search");
+	let mut search = Search::new(candidates, covers, lits, budget, progress, max.max(1));
+	search.best = search.greedy();
+	search.best_cost = search.cost(&search.best);
+	search.progress.update(search.best_cost.0, 0);
+	let mut greedy = search.best.clone();
+	greedy.sort();
+	search.alternatives.insert(greedy);
+	search.search(&mut vec![], &BitSet::new(nitems));
+	search.alternatives.iter().map(|sel| {
+		let mut copy = eqn.clone();
+		copy.terms = sel.iter().map(|&p| cube_to_term(&primes[p], &names)).collect();
+		copy
+	}).collect()
+}
+
+type Chart = (Vec<Cube>, Vec<Vec<usize>>, Vec<BitSet>, Vec<usize>);
+
+// The prime implicant chart of an equation: the primes (which may use its
+// don't-cares), and for 'cover' which primes cover each ON-set minterm, which
+// minterms each prime covers, and each prime's weighted literal count.  None
+// when the primes or the chart would outgrow the budget's memory limit.
+fn chart(eqn: &Equation, nbits: usize, weights: &[usize], budget: &mut Budget) ->
+	Option<Chart> {
+	let minterms: Vec<Vec<bool>> = eqn.terms.iter()
+		.flat_map(|t| expand(t, nbits))
+		.collect::<BTreeSet<_>>().into_iter().collect();
+	let mut care = minterms.clone();
+	care.extend(eqn.dc.iter().flat_map(|t| expand(t, nbits))
+		.filter(|m| !minterms.contains(m)));
+	let primes: Vec<Cube> = prime_implicants_within(&care, budget)?.into_iter()
+		.filter(|p| minterms.iter().any(|m| cube_covers(p, m)))
+		.collect();
+
+	// each pair of a prime and a minterm it covers is in the minterm's list,
+	// and a bit of the prime's set.
+	let set_bytes = minterms.len().div_ceil(64) * mem::size_of::<u64>();
+	let mut bytes = cube_bytes(primes.len(), nbits) +
+		minterms.len() * mem::size_of::<Vec<usize>>() +
+		primes.len() * (mem::size_of::<BitSet>() + set_bytes);
+	let mut candidates: Vec<Vec<usize>> = vec![vec![]; minterms.len()];
+	let mut covers: Vec<BitSet> = Vec::with_capacity(primes.len());
+	for (p, prime) in primes.iter().enumerate() {
+		covers.push(covered(prime, &minterms));
+		for m in covers[p].iter() {
+			candidates[m].push(p);
+		}
+		bytes += covers[p].count() * mem::size_of::<usize>();
+		if !budget.fits(bytes) {
+			return None;
+		}
+	}
+	let lits = primes.iter().map(|p| weighted(p, weights)).collect();
+	Some((primes, candidates, covers, lits))
+}
+
+// The minterms, which are sorted, that the cube covers: those of its
+// expansion, when it has fewer than there are minterms, and otherwise those
+// it is found to cover one by one.
+fn covered(cube: &Cube, minterms: &[Vec<bool>]) -> BitSet {
+	let mut rv = BitSet::new(minterms.len());
+	let free = cube.iter().filter(|c| c.is_none()).count();
+	if free < 64 && 1u64 << free < minterms.len() as u64 {
+		for m in expand_cube(cube) {
+			if let Ok(i) = minterms.binary_search(&m) {
+				rv.insert(i);
+			}
+		}
+	} else {
+		for (i, m) in minterms.iter().enumerate() {
+			if cube_covers(cube, m) {
+				rv.insert(i);
+			}
+		}
+	}
+	rv
+}
+
+// Solves a covering problem: 'candidates' lists, for each item to cover, the
+// terms covering it, 'covers' the items covered by each term and 'lits' each
+// term's literal count.  Returns the chosen terms in order, and whether they
+// were proven to be a minimum.
+fn cover(candidates: Vec<Vec<usize>>, covers: Vec<BitSet>, lits: Vec<usize>,
+         budget: &mut Budget, progress: &mut dyn Progress) -> (Vec<usize>, bool) {
+	progress.phase("cover search");
+	let reduced = reduce(&candidates, &covers, &lits);
+	// the items and terms left, numbered anew for the search.
+	let items: Vec<usize> = reduced.items.iter().collect();
+	let terms: Vec<usize> = (0..covers.len()).filter(|&p| reduced.terms[p]).collect();
+	let mut index = vec![0; covers.len()];
+	for (k, &p) in terms.iter().enumerate() {
+		index[p] = k;
+	}
+	let mut left_candidates: Vec<Vec<usize>> = vec![vec![]; items.len()];
+	let mut left_covers = vec![BitSet::new(items.len()); terms.len()];
+	for (k, &m) in items.iter().enumerate() {
+		for &p in candidates[m].iter().filter(|&&p| reduced.terms[p]) {
+			left_candidates[k].push(index[p]);
+			left_covers[index[p]].insert(k);
+		}
+	}
+	let left_lits = terms.iter().map(|&p| lits[p]).collect();
+	let mut search = Search::new(left_candidates, left_covers, left_lits, budget, progress,
+	                             0);
+	search.taken = (reduced.essential.len(),
+	                reduced.essential.iter().map(|&p| lits[p]).sum());
+	search.best = search.greedy();
+	search.best_cost = search.cost(&search.best);
+	search.progress.update(search.best_cost.0, 0);
+	search.search(&mut vec![], &BitSet::new(items.len()));
+
+	let mut chosen = reduced.essential.clone();
+	chosen.extend(search.best.iter().map(|&k| terms[k]));
+	chosen.sort();
+	(chosen, !search.aborted)
+}
+
+// What the reductions leave of a covering problem: the terms found
+// essential, the items still to cover, and whether each term is still in the
+// running.
+struct Reduced {
+	essential: Vec<usize>,
+	items: BitSet,
+	terms: Vec<bool>,
+}
+
+// Applies the classical reductions to a covering problem, as cover takes it,
+// until none applies:
+//   - a term that is the only one left covering an item is essential: it is
+//     taken, and the items it covers need no other;
+//   - an item whose terms include all of another item's is dropped (row
+//     dominance), as the term covering the other covers it too;
+//   - a term whose items are all another's, which has no more literals, is
+//     dropped (column dominance), as the other does at least as well in its
+//     place.
+// Of two items, or two terms, that are alike, the later is dropped.  A cover
+// of what is left, with the essential terms, is a cover of the whole; and a
+// minimum one, as dropping terms only loses covers that tie with one kept.
+// That is also why minimize_all, which wants the covers that tie, doesn't
+// reduce.
+fn reduce(candidates: &[Vec<usize>], covers: &[BitSet], lits: &[usize]) -> Reduced {
+	let mut items = BitSet::full(candidates.len());
+	let mut terms = vec![true; covers.len()];
+	let mut essential: Vec<usize> = vec![];
+	let mut changed = true;
+	while changed {
+		changed = false;
+		for (m, cands) in candidates.iter().enumerate() {
+			if !items.contains(m) {
+				continue;
+			}
+			let live: Vec<usize> = cands.iter().cloned().filter(|&p| terms[p]).collect();
+			if live.len() == 1 {
+				essential.push(live[0]);
+				items.difference_with(&covers[live[0]]);
+				terms[live[0]] = false;
+				changed = true;
+			}
+		}
+		// the items whose terms include all of m's are those each of m's terms
+		// covers.
+		for (m, cands) in candidates.iter().enumerate() {
+			let live: Vec<usize> = cands.iter().cloned().filter(|&p| terms[p]).collect();
+			if !items.contains(m) || live.is_empty() {
+				continue;
+			}
+			let mut dominated = items.clone();
+			for &p in live.iter() {
+				dominated.intersect_with(&covers[p]);
+			}
+			for i in dominated.iter() {
+				let alike = candidates[i].iter().filter(|&&p| terms[p]).count() ==
+					live.len();
+				if i != m && !(alike && i < m) {
+					items.remove(i);
+					changed = true;
+				}
+			}
+		}
+		// the terms covering all of p's items are in the list of each of them.
+		let left: Vec<BitSet> = covers.iter().map(|c| {
+			let mut c = c.clone();
+			c.intersect_with(&items);
+			c
+		}).collect();
+		for p in 0..covers.len() {
+			if !terms[p] {
+				continue;
+			}
+			let first = match left[p].iter().next() {
+				Some(m) => m,
+				None => {
+					terms[p] = false;
+					changed = true;
+					continue;
+				},
+			};
+			let better = candidates[first].iter().any(|&q| {
+				q != p && terms[q] && lits[q] <= lits[p] && left[p].is_subset(&left[q]) &&
+					(lits[q] < lits[p] || q < p || !left[q].is_subset(&left[p]))
+			});
+			if better {
+				terms[p] = false;
+				changed = true;
+			}
+		}
+	}
+	Reduced{essential, items, terms}
+}
+
+// A condition of a shared cover: a product term, and the outputs (indices
+// into the equations given) that it raises.
+pub struct Condition {
+	pub term: Term,
+	pub outputs: Vec<usize>,
+}
+
+// The result of minimize_shared.
+pub struct SharedSolution {
+	pub conditions: Vec<Condition>,
+	pub minimal: bool,
+}
+
+// The multi-output prime implicants of the functions whose ON- and DC-sets
+// are 'care': each cube is tagged with the set of functions (as a bit mask)
+// it is an implicant of.  A cube is prime when no larger cube is an implicant
+// of all of the same functions.  None when they would outgrow the budget's
+// memory limit.
+fn shared_primes(care: &[BTreeSet<Vec<bool>>], budget: &mut Budget) ->
+	Option<Vec<(Cube, u64)>> {
+	let nbits = care.iter().flat_map(|ms| ms.iter()).next().map_or(0, |m| m.len());
+	let bytes = |n: usize| cube_bytes(n, nbits) + n * mem::size_of::<u64>();
+	let mut level: BTreeMap<Cube, u64> = BTreeMap::new();
+	for (f, minterms) in care.iter().enumerate() {
+		for m in minterms.iter() {
+			*level.entry(m.iter().map(|&b| Some(b)).collect()).or_insert(0) |= 1 << f;
+		}
+	}
+	let mut primes: Vec<(Cube, u64)> = vec![];
+	while !level.is_empty() {
+		let cubes: Vec<(Cube, u64)> = level.into_iter().collect();
+		let mut merged = vec![false; cubes.len()];
+		let mut next: BTreeMap<Cube, u64> = BTreeMap::new();
+		for i in 0..cubes.len() {
+			for j in i+1..cubes.len() {
+				let tag = cubes[i].1 & cubes[j].1;
+				if tag == 0 {
+					continue;
+				}
+				if let Some(c) = combine(&cubes[i].0, &cubes[j].0) {
+					merged[i] = merged[i] || tag == cubes[i].1;
+					merged[j] = merged[j] || tag == cubes[j].1;
+					next.insert(c, tag);
+				}
+			}
+			if !budget.fits(bytes(cubes.len() + next.len() + primes.len())) {
+				return None;
+			}
+		}
+		for (i, c) in cubes.into_iter().enumerate() {
+			if !merged[i] {
+				primes.push(c);
+			}
+		}
+		level = next;
+	}
+	primes.sort_by_key(|&(ref c, tag)| (literals(c), c.clone(), tag));
+	Some(primes)
+}
+
+// Finds a cover of all of the equations together that uses the fewest
+// distinct terms, a term raising several outputs counting once, then the
+// fewest literals in those terms.  This is the number of 'if' statements
+// needed when one 'if' may assign several outputs.  At most 64 equations.
+pub fn minimize_shared(eqns: &[Equation], nbits: usize, weights: &[usize],
+                       budget: &mut Budget, progress: &mut dyn Progress) ->
+	SharedSolution {
+	assert!(eqns.len() <= 64);
+	let names = match eqns.iter().flat_map(|e| e.terms.iter()).next() {
+		None => return SharedSolution{conditions: vec![], minimal: true},
+		Some(t) => t.names.clone(),
+	};
+	let on: Vec<BTreeSet<Vec<bool>>> = eqns.iter()
+		.map(|e| e.terms.iter().flat_map(|t| expand(t, nbits)).collect())
+		.collect();
+	let care: Vec<BTreeSet<Vec<bool>>> = eqns.iter().zip(on.iter())
+		.map(|(e, on)| {
+			let mut care = on.clone();
+			care.extend(e.dc.iter().flat_map(|t| expand(t, nbits)));
+			care
+		}).collect();
+	progress.phase("prime implicants");
+	let primes = match shared_primes(&care, budget) {
+		Some(primes) => primes,
+		None => return shared_fallback(eqns, budget, progress),
+	};
+
+	// the items to cover are the (output, minterm) pairs of the ON-sets.
+	let items: Vec<(usize, &Vec<bool>)> = on.iter().enumerate()
+		.flat_map(|(f, ms)| ms.iter().map(move |m| (f, m)))
+		.collect();
+	let mut candidates: Vec<Vec<usize>> = vec![vec![]; items.len()];
+	let mut covers: Vec<BitSet> = vec![BitSet::new(items.len()); primes.len()];
+	for (p, &(ref prime, tag)) in primes.iter().enumerate() {
+		for (i, &(f, m)) in items.iter().enumerate() {
+			if tag & (1 << f) != 0 && cube_covers(prime, m) {
+				candidates[i].push(p);
+				covers[p].insert(i);
+			}
+		}
+	}
+	let lits = primes.iter().map(|p| weighted(&p.0, weights)).collect();
+	let (chosen, minimal) = cover(candidates, covers.clone(), lits, budget,
+	                              progress);
+	// a condition raises the outputs it covers some ON-set minterm of.
+	let conditions = chosen.iter().map(|&p| {
+		let mut outputs: Vec<usize> = covers[p].iter().map(|i| items[i].0).collect();
+		outputs.dedup();
+		Condition{term: cube_to_term(&primes[p].0, &names), outputs}
+	}).collect();
+	SharedSolution{conditions, minimal}
+}
+
+// The greedy simplifier's cover of each equation, a term several have raising
+// all of them, for equations whose primes would outgrow the budget's memory
+// limit.
+fn shared_fallback(eqns: &[Equation], budget: &mut Budget, progress: &mut dyn Progress) ->
+	SharedSolution {
+	let mut conditions: Vec<Condition> = vec![];
+	for (e, eqn) in eqns.iter().enumerate() {
+		for t in fallback(eqn, budget, progress).terms {
+			match conditions.iter().position(|c| c.term == t) {
+				Some(k) => conditions[k].outputs.push(e),
+				None => conditions.push(Condition{term: t, outputs: vec![e]}),
+			}
+		}
+	}
+	SharedSolution{conditions, minimal: false}
+}
+
+// The terms to add to the equation's cover to make it free of static-1
+// hazards: every pair of adjacent minterms of the cover (differing in one
+// input) must lie in a single term, or the output may glitch to 0 while that
+// input changes.  The added terms are prime, but logically redundant; each
+// is picked to cover the most pairs still lacking a common term.
+pub fn hazard_terms(eqn: &Equation, nbits: usize) -> Vec<Term> {
+	if eqn.terms.is_empty() {
+		return vec![];
+	}
+	let names = eqn.terms[0].names.clone();
+	let on: BTreeSet<Vec<bool>> = eqn.terms.iter()
+		.flat_map(|t| expand(t, nbits)).collect();
+	let mut pairs: Vec<(Vec<bool>, Vec<bool>)> = vec![];
+	for m in on.iter() {
+		for i in (0..nbits).filter(|&i| !m[i]) {
+			let mut n = m.clone();
+			n[i] = true;
+			if on.contains(&n) {
+				pairs.push((m.clone(), n));
+			}
+		}
+	}
+	// the pair is the term of m without the input it differs from n in.
+	pairs.retain(|(m, n)| {
+		let mut pair = Term::compute(m);
+		pair.bits.retain(|&(i, b)| n[i] == b);
+		!eqn.terms.iter().any(|t| t.contains(&pair))
+	});
+
+	let mut care: Vec<Vec<bool>> = on.iter().cloned().collect();
+	care.extend(eqn.dc.iter().flat_map(|t| expand(t, nbits))
+		.filter(|m| !on.contains(m)));
+	let primes = prime_implicants(&care);
+	let spans: Vec<BitSet> = primes.iter().map(|p| {
+		let mut span = BitSet::new(pairs.len());
+		for (i, (m, n)) in pairs.iter().enumerate() {
+			if cube_covers(p, m) && cube_covers(p, n) {
+				span.insert(i);
+			}
+		}
+		span
+	}).collect();
+	let all = BitSet::full(pairs.len());
+	let mut spanned = BitSet::new(pairs.len());
+	let mut rv: Vec<Term> = vec![];
+	while !all.is_subset(&spanned) {
+		let gain = |p: usize| spans[p].count_without(&spanned);
+		// a pair is a cube of the function, so some prime contains it.
+		let pick = (0..primes.len()).filter(|&p| gain(p) > 0)
+			.min_by_key(|&p| (usize::MAX - gain(p), literals(&primes[p])))
+			.expect("every adjacent pair lies in some prime");
+		spanned.union_with(&spans[pick]);
+		rv.push(cube_to_term(&primes[pick], &names));
+	}
+	rv
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, gray_code, parse, testutil, Truth};
+	use super::super::bits::bit_pack;
+	use super::super::progress::test::Recorder;
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|e| e.to_string()).collect()
+	}
+
+	fn verify(eqn: &Equation, terms: &[Term], tbl: &Truth) -> bool {
+		let mut copy = eqn.clone();
+		copy.terms = terms.to_vec();
+		tbl.table.iter().all(|ent| copy.evaluate(&ent.input) == ent.output[eqn.index])
+	}
+
+	fn small() -> Truth {
+		let s = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		         1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		parse(s.as_bytes(), 0, 3, 2)
+	}
+
+	// f = sum(0,1,2,5,6,7): every minterm is covered by exactly two primes, so
+	// nothing is essential and the search has to branch.
+	fn cyclic() -> Truth {
+		let inputs = gray_code(3);
+		let on = [0, 1, 2, 5, 6, 7];
+		let outputs = inputs.iter().map(|i| {
+			vec![on.contains(&bit_pack(i, BitOrder::Msb))]
+		}).collect();
+		Truth::new(inputs, outputs)
+	}
+
+	#[test]
+	fn small_exact() {
+		let tbl = small();
+		let eqns = equations(&tbl, vec!["x", "y"], names());
+		let x = minimize(&eqns[0], 3, &mut Budget::unlimited());
+		assert!(x.minimal);
+		assert_eq!(x.terms.len(), 3);
+		assert!(verify(&eqns[0], &x.terms, &tbl));
+		let y = minimize(&eqns[1], 3, &mut Budget::unlimited());
+		assert!(y.minimal);
+		let strs: Vec<String> = y.terms.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["c'", "ab'"]);
+	}
+
+	#[test]
+	fn cyclic_exact() {
+		let tbl = cyclic();
+		let eqns = equations(&tbl, vec!["f"], names());
+		let sol = minimize(&eqns[0], 3, &mut Budget::unlimited());
+		assert!(sol.minimal);
+		assert_eq!(sol.terms.len(), 3);
+		assert!(verify(&eqns[0], &sol.terms, &tbl));
+	}
+
+	#[test]
+	fn tiny_budget_still_correct() {
+		// the reductions alone solve the small table's outputs, with no search
+		// to stop; the cyclic one has to branch.
+		for (tbl, reduced) in [(small(), true), (cyclic(), false)] {
+			let nout = tbl.table[0].output.len();
+			let outs = vec!["x", "y"].into_iter().take(nout).collect();
+			for eqn in equations(&tbl, outs, names()).iter() {
+				let mut budget = Budget::new(None, Some(1));
+				let sol = minimize(eqn, 3, &mut budget);
+				assert_eq!(sol.minimal, reduced);
+				assert!(verify(eqn, &sol.terms, &tbl));
+			}
+		}
+	}
+
+	#[test]
+	fn memory_limit_falls_back() {
+		for tbl in [small(), cyclic()] {
+			let nout = tbl.table[0].output.len();
+			let outs = vec!["x", "y"].into_iter().take(nout).collect();
+			let eqns = equations(&tbl, outs, names());
+			for eqn in eqns.iter() {
+				let mut budget = Budget::unlimited();
+				budget.limit_memory(1);
+				let sol = minimize(eqn, 3, &mut budget);
+				assert!(!sol.minimal);
+				assert!(budget.out_of_memory());
+				assert!(verify(eqn, &sol.terms, &tbl));
+			}
+			let mut budget = Budget::unlimited();
+			budget.limit_memory(1);
+			let sol = minimize_shared(&eqns, 3, &[1, 1, 1], &mut budget, &mut Silent);
+			assert!(!sol.minimal);
+			for (e, eqn) in eqns.iter().enumerate() {
+				let terms: Vec<Term> = sol.conditions.iter()
+					.filter(|c| c.outputs.contains(&e))
+					.map(|c| c.term.clone()).collect();
+				assert!(verify(eqn, &terms, &tbl));
+			}
+		}
+		// a limit the expansion reaches part way through.
+		let f = testutil::dense(7, 2, 3);
+		for idx in 0..f.outvars.len() {
+			let eqn = Equation::new(&f.truth, idx, &f.outvars[idx], &f.invars);
+			let mut budget = Budget::unlimited();
+			budget.limit_memory(4096);
+			let sol = minimize(&eqn, 7, &mut budget);
+			assert!(budget.out_of_memory());
+			assert!(verify(&eqn, &sol.terms, &f.truth));
+		}
+		// nothing is accounted against no limit.
+		let mut budget = Budget::unlimited();
+		minimize(&equations(&small(), vec!["x", "y"], names())[0], 3, &mut budget);
+		assert!(!budget.out_of_memory());
+	}
+
+	#[test]
+	fn reports_improvements() {
+		let tbl = cyclic();
+		let eqns = equations(&tbl, vec!["f"], names());
+		let mut rec = Recorder::default();
+		let sol = minimize_with(&eqns[0], 3, &mut Budget::unlimited(), &mut rec);
+		assert_eq!(rec.phases, vec!["prime implicants", "cover search"]);
+		assert!(!rec.updates.is_empty());
+		assert!(rec.updates.windows(2).all(|w| w[1].0 <= w[0].0));
+		assert_eq!(rec.updates.last().unwrap().0, sol.terms.len());
+	}
+
+	#[test]
+	fn dont_cares() {
+		// f = a'b'c' + a'bc' with abc' and ab'c' free: c' covers it all.
+		let mut eqn = Equation::parse("f = a'b'c' + a'bc'", &names()).unwrap();
+		eqn.dc = Equation::parse("f = abc' + ab'c'", &names()).unwrap().terms;
+		let sol = minimize(&eqn, 3, &mut Budget::unlimited());
+		let strs: Vec<String> = sol.terms.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["c'"]);
+		// and a don't-care that doesn't help is not covered.
+		eqn.dc = Equation::parse("f = abc", &names()).unwrap().terms;
+		let sol = minimize(&eqn, 3, &mut Budget::unlimited());
+		let strs: Vec<String> = sol.terms.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["a'c'"]);
+	}
+
+	#[test]
+	fn all_minimal() {
+		let tbl = cyclic();
+		let eqns = equations(&tbl, vec!["f"], names());
+		let all = minimize_all(&eqns[0], 3, &[1, 1, 1], &mut Budget::unlimited(),
+		                       &mut Silent, 10);
+		let strs: Vec<String> = all.iter().map(|e| format!("{}", e)).collect();
+		assert_eq!(strs, vec!["f = b'c + a'c' + ab + ;", "f = bc' + a'b' + ac + ;"]);
+		for eqn in all.iter() {
+			assert!(verify(&eqns[0], &eqn.terms, &tbl));
+		}
+		let mut rec = Recorder::default();
+		let one = minimize_all(&eqns[0], 3, &[1, 1, 1], &mut Budget::unlimited(), &mut rec,
+		                       1);
+		assert_eq!(one.len(), 1);
+		// the phases are reported, as they are by minimize_with.
+		assert_eq!(rec.phases, vec!["prime implicants", "cover search"]);
+		assert!(!rec.updates.is_empty());
+		// a function with a unique minimum cover.
+		let eqns = equations(&small(), vec!["x", "y"], names());
+		assert_eq!(minimize_all(&eqns[1], 3, &[1, 1, 1], &mut Budget::unlimited(),
+		                        &mut Silent, 10).len(), 1);
+	}
+
+	#[test]
+	fn weights() {
+		// two minimum covers: b'c' + a'b + ab' and a'c' + a'b + ab'.  The first
+		// has two literals of a, the second three.
+		let eqn = Equation::parse("f = a'b'c' + a'b + ab'", &names()).unwrap();
+		let pick = |weights: &[usize]| -> Vec<String> {
+			let sol = minimize_weighted(&eqn, 3, weights, &mut Budget::unlimited(),
+			                            &mut Silent);
+			sol.terms.iter().map(|t| format!("{}", t)).collect()
+		};
+		assert_eq!(pick(&[5, 1, 1]), vec!["b'c'", "a'b", "ab'"]);
+		assert_eq!(pick(&[1, 5, 1]), vec!["a'c'", "a'b", "ab'"]);
+	}
+
+	// The chart of terms covering the given items, out of 'nitems'.
+	fn hand_chart(nitems: usize, terms: &[&[usize]]) -> (Vec<Vec<usize>>, Vec<BitSet>) {
+		let mut candidates = vec![vec![]; nitems];
+		let mut covers = vec![BitSet::new(nitems); terms.len()];
+		for (p, items) in terms.iter().enumerate() {
+			for &m in items.iter() {
+				candidates[m].push(p);
+				covers[p].insert(m);
+			}
+		}
+		(candidates, covers)
+	}
+
+	#[test]
+	fn reductions() {
+		let left = |r: &Reduced| -> (Vec<usize>, Vec<usize>) {
+			let terms = (0..r.terms.len()).filter(|&p| r.terms[p]).collect();
+			(r.items.iter().collect(), terms)
+		};
+		// essential: item 0 has term 0 alone, and item 2 term 1 once term 0
+		// covers item 1.
+		let (c, v) = hand_chart(3, &[&[0, 1], &[1, 2]]);
+		let r = reduce(&c, &v, &[2, 2]);
+		assert_eq!((r.essential.clone(), left(&r)), (vec![0, 1], (vec![], vec![])));
+		// a cycle of six, term k covering items k and k+1, which nothing
+		// reduces; item 6's terms include item 1's, so it goes.
+		let cycle: Vec<Vec<usize>> = (0..6).map(|k| vec![k, (k + 1) % 6]).collect();
+		let mut terms: Vec<&[usize]> = cycle.iter().map(|t| &t[..]).collect();
+		let (c, v) = hand_chart(6, &terms);
+		let r = reduce(&c, &v, &[2; 6]);
+		assert!(r.essential.is_empty());
+		assert_eq!(left(&r), ((0..6).collect(), (0..6).collect()));
+		let (c, v) = hand_chart(7, &[&[0, 1, 6], &[1, 2, 6], &[2, 3, 6], &[3, 4], &[4, 5],
+		                             &[5, 0]]);
+		let r = reduce(&c, &v, &[2; 6]);
+		assert!(r.essential.is_empty());
+		assert_eq!(left(&r), ((0..6).collect(), (0..6).collect()));
+		// term 6 covers item 0, which term 0 also does: with as many literals
+		// or more it goes, with fewer it stays.
+		terms.push(&[0]);
+		let (c, v) = hand_chart(6, &terms);
+		let r = reduce(&c, &v, &[2, 2, 2, 2, 2, 2, 2]);
+		assert_eq!(left(&r), ((0..6).collect(), (0..6).collect()));
+		let r = reduce(&c, &v, &[2, 2, 2, 2, 2, 2, 1]);
+		assert_eq!(left(&r), ((0..6).collect(), (0..7).collect()));
+		// of two alike terms the later goes, which makes term 0 essential.
+		let (c, v) = hand_chart(2, &[&[0, 1], &[0, 1]]);
+		let r = reduce(&c, &v, &[1, 1]);
+		assert_eq!((r.essential.clone(), left(&r)), (vec![0], (vec![], vec![])));
+	}
+
+	#[test]
+	fn reduced_covers_stay_minimal() {
+		let mut corpus: Vec<testutil::Fixture> = (0..6).map(|s| testutil::dense(5, 1, s))
+			.collect();
+		corpus.extend((0..4).map(|s| testutil::sparse(6, 1, 4, s)));
+		corpus.extend((0..2).map(|s| testutil::noisy(6, 6, s)));
+		for f in corpus {
+			let eqn = Equation::new(&f.truth, 0, &f.outvars[0], &f.invars);
+			let nbits = f.invars.len();
+			let weights = vec![1; nbits];
+			let mut budget = Budget::unlimited();
+			let (_, candidates, covers, lits) = chart(&eqn, nbits, &weights, &mut budget)
+				.unwrap();
+			let (chosen, minimal) = cover(candidates.clone(), covers.clone(), lits.clone(),
+			                              &mut budget, &mut Silent);
+			assert!(minimal);
+			let mut covered = BitSet::new(candidates.len());
+			for &p in chosen.iter() {
+				covered.union_with(&covers[p]);
+			}
+			assert_eq!(covered.count(), candidates.len(), "{}", f.name);
+			// the search over the whole chart.
+			let nitems = candidates.len();
+			let mut silent = Silent;
+			let mut search = Search::new(candidates, covers, lits, &mut budget,
+			                             &mut silent, 0);
+			search.best = search.greedy();
+			search.best_cost = search.cost(&search.best);
+			search.search(&mut vec![], &BitSet::new(nitems));
+			assert!(!search.aborted);
+			assert_eq!(search.cost(&chosen), search.best_cost, "{}", f.name);
+		}
+	}
+
+	#[test]
+	fn shared_conditions() {
+		let tbl = small();
+		let eqns = equations(&tbl, vec!["x", "y"], names());
+		let sol = minimize_shared(&eqns, 3, &[1, 1, 1], &mut Budget::unlimited(),
+		                          &mut Silent);
+		assert!(sol.minimal);
+		// the hand solution in main.rs needs 6.
+		assert_eq!(sol.conditions.len(), 5);
+		for (e, eqn) in eqns.iter().enumerate() {
+			let terms: Vec<Term> = sol.conditions.iter()
+				.filter(|c| c.outputs.contains(&e))
+				.map(|c| c.term.clone()).collect();
+			assert!(verify(eqn, &terms, &tbl));
+		}
+		// bc' and ac' raise both outputs.
+		let both: Vec<String> = sol.conditions.iter().filter(|c| c.outputs.len() == 2)
+			.map(|c| format!("{}", c.term)).collect();
+		assert_eq!(both, vec!["bc'", "ac'"]);
+	}
+
+	#[test]
+	fn hazards() {
+		let eqn = Equation::parse("f = ab + a'c", &names()).unwrap();
+		let sol = minimize(&eqn, 3, &mut Budget::unlimited());
+		let strs: Vec<String> = sol.terms.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["a'c", "ab"]);
+		// abc and a'bc are adjacent but in different terms: bc bridges them.
+		let mut min = eqn.clone();
+		min.terms = sol.terms;
+		let added = hazard_terms(&min, 3);
+		let strs: Vec<String> = added.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["bc"]);
+		min.terms.extend(added);
+		assert!(hazard_terms(&min, 3).is_empty());
+		// a single term has no hazards.
+		let eqn = Equation::parse("f = ab", &names()).unwrap();
+		assert!(hazard_terms(&eqn, 3).is_empty());
+	}
+
+	#[test]
+	fn expired_deadline() {
+		let tbl = cyclic();
+		let eqns = equations(&tbl, vec!["f"], names());
+		let mut budget = Budget::new(Some(Duration::from_secs(0)), None);
+		let sol = minimize(&eqns[0], 3, &mut budget);
+		assert!(!sol.minimal);
+		assert!(verify(&eqns[0], &sol.terms, &tbl));
+	}
+}
@@ -0,0 +1,163 @@
+// What --explain prints under each equation: the rows of the table each term
+// covers, and the two cubes it was merged from.  The merges are recorded from
+// the cube expansion the greedy simplifier does (exact::expand_cubes), run
+// over the output's minterms and don't-cares, which makes every implicant of
+// the output whichever search chose the terms.
+use std::collections::{BTreeSet, HashMap};
+use super::{Entry, Equation, Term, Truth, describe_sources};
+use super::exact::{self, Cube};
+use super::notation::Notation;
+use super::progress::Progress;
+
+// For each cube the expansion made, the first two it was merged from.
+#[derive(Default)]
+pub struct Derivations {
+	pub merges: HashMap<Cube, (Cube, Cube)>,
+}
+
+impl Progress for Derivations {
+	fn merged(&mut self, a: &Cube, b: &Cube, into: &Cube) {
+		self.merges.entry(into.clone()).or_insert_with(|| (a.clone(), b.clone()));
+	}
+}
+
+impl Derivations {
+	// Records the merges that expand the equation's terms and don't-cares,
+	// taken apart into minterms, into the largest cubes they make.
+	pub fn new(eqn: &Equation, nbits: usize) -> Self {
+		let minterms: BTreeSet<Cube> = eqn.terms.iter().chain(eqn.dc.iter())
+			.flat_map(|t| exact::expand_cube(&exact::term_cube(t, nbits)))
+			.map(|m| m.into_iter().map(Some).collect())
+			.collect();
+		let mut rv = Derivations::default();
+		exact::expand_cubes(minterms.into_iter().collect(), &mut rv);
+		rv
+	}
+
+	// The minterms at the leaves of the cube's derivation tree.
+	pub fn leaves(&self, cube: &Cube) -> Vec<Vec<bool>> {
+		match self.merges.get(cube) {
+			Some((a, b)) => {
+				let mut rv = self.leaves(a);
+				rv.extend(self.leaves(b));
+				rv
+			},
+			// a minterm; nothing was merged into it.
+			None => exact::expand_cube(cube),
+		}
+	}
+}
+
+// A term of an equation, the rows of the table it covers (indices into the
+// table) and the two terms it was merged from, None when it is a minterm.
+pub struct Explanation {
+	pub term: Term,
+	pub rows: Vec<usize>,
+	pub from: Option<(Term, Term)>,
+}
+
+// Explains each term of 'result', the equation 'start' minimized.
+pub fn explain(start: &Equation, result: &Equation, tbl: &Truth, nbits: usize) ->
+	Vec<Explanation> {
+	let derivations = Derivations::new(start, nbits);
+	let rows: HashMap<&[bool], usize> = tbl.table.iter().enumerate()
+		.map(|(i, e)| (e.input.as_slice(), i))
+		.collect();
+	result.terms.iter().map(|t| {
+		let cube = exact::term_cube(t, nbits);
+		let mut covered: Vec<usize> = derivations.leaves(&cube).iter()
+			.filter_map(|m| rows.get(m.as_slice()).cloned())
+			.collect();
+		covered.sort();
+		let from = derivations.merges.get(&cube).map(|(a, b)| {
+			(exact::cube_to_term(a, &t.names), exact::cube_to_term(b, &t.names))
+		});
+		Explanation{term: t.clone(), rows: covered, from}
+	}).collect()
+}
+
+// "bc' covers rows 3,7 (lines 5,9): merged from a'bc' + abc'", the rows
+// counted from 1.  The lines are those the rows were read from, when each was
+// read from one; a row merged from several tables lists them all, as in
+// "(a.csv line 5, b.csv line 2)", and a row that wasn't read from a file
+// leaves them out.
+pub fn describe(ex: &Explanation, tbl: &Truth, n: &Notation) -> String {
+	let list = |ns: Vec<usize>| -> String {
+		let strs: Vec<String> = ns.iter().map(|n| n.to_string()).collect();
+		strs.join(",")
+	};
+	let term = n.term(&ex.term);
+	let mut s = match ex.rows.len() {
+		0 => format!("{} covers no row of the table", term),
+		1 => format!("{} covers row {}", term, ex.rows[0] + 1),
+		_ => format!("{} covers rows {}", term,
+		             list(ex.rows.iter().map(|r| r + 1).collect())),
+	};
+	let covered: Vec<&Entry> = ex.rows.iter().map(|&r| &tbl.table[r]).collect();
+	let lines: Option<Vec<usize>> = covered.iter().map(|e| e.line()).collect();
+	match lines {
+		_ if covered.is_empty() => {},
+		Some(lines) => {
+			let plural = if lines.len() == 1 { "" } else { "s" };
+			s += &format!(" (line{} {})", plural, list(lines));
+		},
+		None if covered.iter().all(|e| !e.sources.is_empty()) => {
+			let strs: Vec<String> = covered.iter().map(|e| describe_sources(&e.sources))
+				.collect();
+			s += &format!(" ({})", strs.join("; "));
+		},
+		None => {},
+	}
+	match ex.from {
+		Some((ref a, ref b)) =>
+			s += &format!(": merged from {} + {}", n.term(a), n.term(b)),
+		None => s += ": not merged",
+	}
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse, HEADER_LINES};
+	use super::super::testutil;
+
+	#[test]
+	fn small_example() {
+		let csv = "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n\
+		           0,1,1,,0,0\n1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		let tbl = parse(csv.as_bytes(), HEADER_LINES, 3, 2);
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string())
+			.collect();
+		let start = equations(&tbl, vec!["x", "y"], names).remove(0);
+		let mut result = start.clone();
+		result.simplify();
+		result.canonicalize();
+		let strs: Vec<String> = explain(&start, &result, &tbl, 3).iter()
+			.map(|ex| describe(ex, &tbl, &Notation::classic())).collect();
+		assert_eq!(strs, vec!["ac' covers rows 5,7 (lines 7,9): merged from ab'c' + \
+		                       abc'",
+		                      "bc' covers rows 3,7 (lines 5,9): merged from a'bc' + \
+		                       abc'",
+		                      "a'b'c covers row 2 (line 4): not merged"]);
+	}
+
+	#[test]
+	fn rows_are_those_covered() {
+		for f in [testutil::dense(5, 2, 7), testutil::sparse(6, 2, 4, 3),
+		              testutil::capabilities()] {
+			let nbits = f.invars.len();
+			for idx in 0..f.outvars.len() {
+				let start = Equation::new(&f.truth, idx, &f.outvars[idx], &f.invars);
+				let mut result = start.clone();
+				result.simplify();
+				for ex in explain(&start, &result, &f.truth, nbits) {
+					let evaluated: Vec<usize> = (0..f.truth.len())
+						.filter(|&i| ex.term.evaluate(&f.truth.table[i].input))
+						.collect();
+					assert_eq!(ex.rows, evaluated, "{} in {}", ex.term, f.name);
+				}
+			}
+		}
+	}
+}
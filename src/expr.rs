@@ -0,0 +1,486 @@
+// Boolean expressions over the input variables (or, for invariants, the
+// inputs and outputs), as written by people:
+//    a & !b | c          C-like operators
+//    a'b + c             the notation this program prints
+// Both styles may be mixed, '^' is exclusive or and '->' implication.
+// Precedence is NOT > AND > XOR > OR, as in C, then '->', which groups to the
+// right; parentheses group as usual.  Juxtaposition is AND, so with
+// single-letter variable names "ab'c" is a AND NOT b AND c; identifiers that
+// are not variable names are split into a sequence of variable names where
+// that is possible.
+use std::fmt;
+use super::{Term, Truth, Variable};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+	Const(bool),
+	// index of an input variable.
+	Var(usize),
+	Not(Box<Expr>),
+	And(Box<Expr>, Box<Expr>),
+	Xor(Box<Expr>, Box<Expr>),
+	Or(Box<Expr>, Box<Expr>),
+	Implies(Box<Expr>, Box<Expr>),
+}
+
+// A syntax error, located by its (0-based, in characters) position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+	pub pos: usize,
+	pub msg: String,
+}
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "at position {}: {}", self.pos, self.msg)
+	}
+}
+
+fn error<T>(pos: usize, msg: String) -> Result<T, ParseError> {
+	Err(ParseError{pos, msg})
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Ident(String),
+	Const(bool),
+	Not,      // ! or ~ before an operand
+	Prime,    // ' after an operand
+	And,      // & or * or .
+	Xor,      // ^
+	Or,       // | or +
+	Implies,  // ->
+	Open,
+	Close,
+	End,
+}
+
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+	let chars: Vec<char> = s.chars().collect();
+	let mut rv = vec![];
+	let mut i = 0;
+	while i < chars.len() {
+		let c = chars[i];
+		let tok = match c {
+			_ if c.is_whitespace() => { i += 1; continue; },
+			'!' | '~' => Token::Not,
+			'\'' => Token::Prime,
+			'&' | '*' | '.' => Token::And,
+			'^' => Token::Xor,
+			'|' | '+' => Token::Or,
+			'-' if chars.get(i + 1) == Some(&'>') => { i += 1; Token::Implies },
+			'(' => Token::Open,
+			')' => Token::Close,
+			'0' => Token::Const(false),
+			'1' => Token::Const(true),
+			_ if c.is_alphabetic() || c == '_' => {
+				let start = i;
+				while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+					i += 1;
+				}
+				rv.push((Token::Ident(chars[start..i].iter().collect()), start));
+				continue;
+			},
+			_ => return error(i, format!("unexpected character '{}'", c)),
+		};
+		rv.push((tok, i));
+		i += 1;
+	}
+	rv.push((Token::End, chars.len()));
+	Ok(rv)
+}
+
+struct Parser<'a> {
+	toks: Vec<(Token, usize)>,
+	at: usize,
+	names: &'a [String],
+}
+
+impl<'a> Parser<'a> {
+	fn peek(&self) -> &Token { &self.toks[self.at].0 }
+	fn pos(&self) -> usize { self.toks[self.at].1 }
+
+	fn implies(&mut self) -> Result<Expr, ParseError> {
+		let lhs = self.or()?;
+		if *self.peek() != Token::Implies {
+			return Ok(lhs);
+		}
+		self.at += 1;
+		let rhs = self.implies()?;
+		Ok(Expr::Implies(Box::new(lhs), Box::new(rhs)))
+	}
+
+	fn or(&mut self) -> Result<Expr, ParseError> {
+		let mut lhs = self.xor()?;
+		while *self.peek() == Token::Or {
+			self.at += 1;
+			let rhs = self.xor()?;
+			lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+		}
+		Ok(lhs)
+	}
+
+	fn xor(&mut self) -> Result<Expr, ParseError> {
+		let mut lhs = self.and()?;
+		while *self.peek() == Token::Xor {
+			self.at += 1;
+			let rhs = self.and()?;
+			lhs = Expr::Xor(Box::new(lhs), Box::new(rhs));
+		}
+		Ok(lhs)
+	}
+
+	fn and(&mut self) -> Result<Expr, ParseError> {
+		let mut lhs = self.unary()?;
+		loop {
+			match *self.peek() {
+				Token::And => { self.at += 1; },
+				// juxtaposition: "ab" is "a & b".
+				Token::Ident(_) | Token::Const(_) | Token::Not | Token::Open => {},
+				_ => return Ok(lhs),
+			}
+			let rhs = self.unary()?;
+			lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+		}
+	}
+
+	fn unary(&mut self) -> Result<Expr, ParseError> {
+		if *self.peek() == Token::Not {
+			self.at += 1;
+			let e = self.unary()?;
+			return Ok(Expr::Not(Box::new(e)));
+		}
+		let mut e = self.atom()?;
+		while *self.peek() == Token::Prime {
+			self.at += 1;
+			e = Expr::Not(Box::new(e));
+		}
+		Ok(e)
+	}
+
+	fn atom(&mut self) -> Result<Expr, ParseError> {
+		let pos = self.pos();
+		match self.peek().clone() {
+			Token::Const(v) => { self.at += 1; Ok(Expr::Const(v)) },
+			Token::Ident(name) => { self.at += 1; self.variable(&name, pos) },
+			Token::Open => {
+				self.at += 1;
+				let e = self.implies()?;
+				if *self.peek() != Token::Close {
+					return error(self.pos(), format!("expected ')' to close the '(' at \
+					                                  position {}", pos));
+				}
+				self.at += 1;
+				Ok(e)
+			},
+			Token::End => error(pos, "unexpected end of expression".to_string()),
+			_ => error(pos, "expected a variable, constant or '('".to_string()),
+		}
+	}
+
+	fn variable(&self, ident: &str, pos: usize) -> Result<Expr, ParseError> {
+		match self.names.iter().position(|n| n == ident) {
+			None => error(pos, format!("unknown variable '{}'", ident)),
+			Some(i) => Ok(Expr::Var(i)),
+		}
+	}
+}
+
+// Replaces identifiers that are a run of variable names written without
+// separators ("abc" for a, b and c) by one identifier per variable, so that
+// "ab'" means "a b'" just as it would with spaces.
+fn expand_runs(toks: Vec<(Token, usize)>, names: &[String]) -> Vec<(Token, usize)> {
+	let mut rv = vec![];
+	for (tok, pos) in toks {
+		let vars = match tok {
+			Token::Ident(ref ident) => split(ident, names),
+			_ => None,
+		};
+		match vars {
+			Some(ref vars) if vars.len() > 1 => {
+				let mut at = pos;
+				for &v in vars.iter() {
+					rv.push((Token::Ident(names[v].clone()), at));
+					at += names[v].chars().count();
+				}
+			},
+			_ => rv.push((tok, pos)),
+		}
+	}
+	rv
+}
+
+// Splits 'ident' into a sequence of variable names, preferring an exact
+// match and otherwise the longest names first.
+fn split(ident: &str, names: &[String]) -> Option<Vec<usize>> {
+	if let Some(i) = names.iter().position(|n| n == ident) {
+		return Some(vec![i]);
+	}
+	let mut order: Vec<usize> = (0..names.len()).filter(|&i| !names[i].is_empty())
+		.collect();
+	order.sort_by_key(|&i| std::cmp::Reverse(names[i].len()));
+	for i in order {
+		if ident.starts_with(names[i].as_str()) {
+			if let Some(mut rest) = split(&ident[names[i].len()..], names) {
+				rest.insert(0, i);
+				return Some(rest);
+			}
+		}
+	}
+	if ident.is_empty() { Some(vec![]) } else { None }
+}
+
+impl Expr {
+	// Parses an expression over the variables with the given names.
+	pub fn parse(s: &str, names: &[String]) -> Result<Expr, ParseError> {
+		let toks = expand_runs(tokenize(s)?, names);
+		let mut p = Parser{toks, at: 0, names};
+		let e = p.implies()?;
+		match *p.peek() {
+			Token::End => Ok(e),
+			Token::Close => error(p.pos(), "unbalanced ')'".to_string()),
+			_ => error(p.pos(), "expected an operator".to_string()),
+		}
+	}
+
+	pub fn evaluate(&self, inp: &[bool]) -> bool {
+		match *self {
+			Expr::Const(v) => v,
+			Expr::Var(i) => inp[i],
+			Expr::Not(ref e) => !e.evaluate(inp),
+			Expr::And(ref a, ref b) => a.evaluate(inp) && b.evaluate(inp),
+			Expr::Xor(ref a, ref b) => a.evaluate(inp) != b.evaluate(inp),
+			Expr::Or(ref a, ref b) => a.evaluate(inp) || b.evaluate(inp),
+			Expr::Implies(ref a, ref b) => !a.evaluate(inp) || b.evaluate(inp),
+		}
+	}
+
+	// The indices of the variables the expression uses, in order.
+	pub fn variables(&self) -> Vec<usize> {
+		let mut rv = match *self {
+			Expr::Const(_) => vec![],
+			Expr::Var(i) => vec![i],
+			Expr::Not(ref e) => e.variables(),
+			Expr::And(ref a, ref b) | Expr::Xor(ref a, ref b) | Expr::Or(ref a, ref b) |
+			Expr::Implies(ref a, ref b) => {
+				let mut vs = a.variables();
+				vs.extend(b.variables());
+				vs
+			},
+		};
+		rv.sort();
+		rv.dedup();
+		rv
+	}
+
+	// The expression as a sum of products.  Each product is a sorted list of
+	// literals; contradictory products (a a') are dropped, so an empty list
+	// means 0 and a list holding an empty product means 1.
+	pub fn products(&self) -> Vec<Vec<Variable>> {
+		self.dnf(false)
+	}
+
+	// DNF of this expression, or of its complement when 'neg' is set; the
+	// negation is pushed down to the variables with De Morgan's laws.
+	fn dnf(&self, neg: bool) -> Vec<Vec<Variable>> {
+		match (self, neg) {
+			(&Expr::Const(v), _) => if v != neg { vec![vec![]] } else { vec![] },
+			(&Expr::Var(i), _) => vec![vec![(i, !neg)]],
+			(Expr::Not(e), _) => e.dnf(!neg),
+			// a -> b is a' + b.
+			(Expr::Implies(a, b), _) =>
+				Expr::Or(Box::new(Expr::Not(a.clone())), b.clone()).dnf(neg),
+			// a^b is ab' + a'b, and its complement is ab + a'b'.
+			(Expr::Xor(a, b), _) => {
+				let nb = Expr::Not(b.clone());
+				let (p, q) = if neg { (b.as_ref(), &nb) } else { (&nb, b.as_ref()) };
+				let lhs = Expr::And(a.clone(), Box::new(p.clone()));
+				let rhs = Expr::And(Box::new(Expr::Not(a.clone())), Box::new(q.clone()));
+				Expr::Or(Box::new(lhs), Box::new(rhs)).dnf(false)
+			},
+			(&Expr::Or(ref a, ref b), false) | (&Expr::And(ref a, ref b), true) => {
+				let mut rv = a.dnf(neg);
+				for p in b.dnf(neg) {
+					if !rv.contains(&p) {
+						rv.push(p);
+					}
+				}
+				rv
+			},
+			(&Expr::And(ref a, ref b), false) | (&Expr::Or(ref a, ref b), true) => {
+				let lhs = a.dnf(neg);
+				let rhs = b.dnf(neg);
+				let mut rv: Vec<Vec<Variable>> = vec![];
+				for p in lhs.iter() {
+					for q in rhs.iter() {
+						if let Some(prod) = conjoin(p, q) {
+							if !rv.contains(&prod) {
+								rv.push(prod);
+							}
+						}
+					}
+				}
+				rv
+			},
+		}
+	}
+}
+
+// The product of two products, or None if it is contradictory.
+pub fn conjoin(p: &[Variable], q: &[Variable]) -> Option<Vec<Variable>> {
+	let mut rv: Vec<Variable> = p.to_vec();
+	for &(idx, val) in q.iter() {
+		match rv.iter().find(|&&(i, _)| i == idx) {
+			Some(&(_, v)) if v != val => return None,
+			Some(_) => {},
+			None => rv.push((idx, val)),
+		}
+	}
+	rv.sort();
+	Some(rv)
+}
+
+// The terms of a sum of products over the given variable names.
+pub fn terms(products: Vec<Vec<Variable>>, names: &[String]) -> Vec<Term> {
+	products.into_iter().map(|bits| {
+		Term{bits, names: names.to_vec()}
+	}).collect()
+}
+
+// Builds the complete table of the given expressions over 'nbits' inputs,
+// with one output per expression.  The rows are in counting order, first
+// input most significant.  Fails above 'limit' inputs.
+pub fn table(exprs: &[Expr], nbits: usize, limit: usize) -> Result<Truth, String> {
+	Truth::from_fn_limited(nbits, exprs.len(), limit,
+	                       |inp| Some(exprs.iter().map(|e| e.evaluate(inp)).collect()))
+}
+
+// Splits "x = <expr>" into the output name and the expression.  Without an
+// '=' the whole string is the expression and the name is empty.
+pub fn split_assignment(s: &str) -> (&str, &str, usize) {
+	match s.find('=') {
+		None => ("", s, 0),
+		Some(eq) => (s[..eq].trim(), &s[eq+1..], s[..eq+1].chars().count()),
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|e| e.to_string()).collect()
+	}
+
+	fn all_inputs() -> Vec<Vec<bool>> {
+		(0..8).map(|i| vec![i & 4 != 0, i & 2 != 0, i & 1 != 0]).collect()
+	}
+
+	fn same(x: &str, y: &str) {
+		let ex = Expr::parse(x, &names()).unwrap();
+		let ey = Expr::parse(y, &names()).unwrap();
+		for inp in all_inputs() {
+			assert_eq!(ex.evaluate(&inp), ey.evaluate(&inp), "{} vs {} on {:?}",
+			           x, y, inp);
+		}
+	}
+
+	#[test]
+	fn precedence() {
+		let e = Expr::parse("a & !b | c", &names()).unwrap();
+		assert_eq!(e, Expr::Or(
+			Box::new(Expr::And(Box::new(Expr::Var(0)),
+			                   Box::new(Expr::Not(Box::new(Expr::Var(1)))))),
+			Box::new(Expr::Var(2))));
+		same("a'b + c", "(!a & b) | c");
+		same("a + b c", "a | (b & c)");
+		same("!a | b", "(!a) | b");
+		same("(a + b)'", "!a & !b");
+		same("ab'c", "a & !b & c");
+		same("a.b * c", "abc");
+		same("~(a | b) + 1", "1");
+		same("a ^ b", "ab' + a'b");
+		same("a ^ b c", "a ^ (b & c)");
+		same("a ^ b | c", "(a ^ b) | c");
+		same("a ^ b ^ c", "(a ^ b) ^ c");
+		same("a -> b", "a' + b");
+		same("a -> b -> c", "a -> (b -> c)");
+		same("a + b -> c", "(a + b) -> c");
+		same("!(a -> b) | c", "ab' + c");
+		assert_eq!(Expr::parse("(a->b)c", &names()).unwrap().variables(), vec![0, 1, 2]);
+		assert!(Expr::parse("a - b", &names()).is_err());
+	}
+
+	#[test]
+	fn xor_products() {
+		let e = Expr::parse("a ^ b", &names()).unwrap();
+		assert_eq!(e.products(), vec![vec![(0, true), (1, false)],
+		                              vec![(0, false), (1, true)]]);
+		let e = Expr::parse("!(a ^ b)", &names()).unwrap();
+		assert_eq!(e.products(), vec![vec![(0, true), (1, true)],
+		                              vec![(0, false), (1, false)]]);
+		let e = Expr::parse("a ^ b ^ c", &names()).unwrap();
+		for inp in all_inputs() {
+			let sop = e.products().iter()
+				.any(|p| p.iter().all(|&(i, v)| inp[i] == v));
+			assert_eq!(sop, e.evaluate(&inp));
+		}
+	}
+
+	#[test]
+	fn build_table() {
+		let exprs = vec![Expr::parse("a'b + c", &names()).unwrap(),
+		                 Expr::parse("a ^ b", &names()).unwrap()];
+		let t = table(&exprs, 3, 3).unwrap();
+		assert_eq!(t.len(), 8);
+		assert_eq!(t.table[3].input, vec![false, true, true]);
+		assert_eq!(t.table[3].output, vec![true, true]);
+		assert_eq!(t.table[6].output, vec![false, false]);
+	}
+
+	#[test]
+	fn products() {
+		let e = Expr::parse("(a + b)(a' + c)", &names()).unwrap();
+		assert_eq!(e.products(), vec![vec![(0, true), (2, true)],
+		                              vec![(0, false), (1, true)],
+		                              vec![(1, true), (2, true)]]);
+		let e = Expr::parse("!(a | b'c)", &names()).unwrap();
+		assert_eq!(e.products(), vec![vec![(0, false), (1, true)],
+		                              vec![(0, false), (2, false)]]);
+		assert_eq!(Expr::parse("a a'", &names()).unwrap().products(),
+		           Vec::<Vec<Variable>>::new());
+		assert_eq!(Expr::parse("1", &names()).unwrap().products(), vec![vec![]]);
+	}
+
+	#[test]
+	fn long_names() {
+		let names: Vec<String> = ["OGL", "GLX", "GL"].iter()
+			.map(|e| e.to_string()).collect();
+		let e = Expr::parse("OGL & !GLX | GL", &names).unwrap();
+		assert!(e.evaluate(&[true, false, false]));
+		assert!(!e.evaluate(&[true, true, false]));
+		// run together, the longest names are tried first.
+		assert_eq!(Expr::parse("GLXGL", &names).unwrap(),
+		           Expr::And(Box::new(Expr::Var(1)), Box::new(Expr::Var(2))));
+	}
+
+	#[test]
+	fn errors() {
+		let err = Expr::parse("a & (b | c", &names()).unwrap_err();
+		assert_eq!(err.pos, 10);
+		let err = Expr::parse("a & d", &names()).unwrap_err();
+		assert_eq!((err.pos, err.msg.as_str()), (4, "unknown variable 'd'"));
+		let err = Expr::parse("a | | b", &names()).unwrap_err();
+		assert_eq!(err.pos, 4);
+		let err = Expr::parse("a # b", &names()).unwrap_err();
+		assert_eq!(err.pos, 2);
+		let err = Expr::parse("a)", &names()).unwrap_err();
+		assert_eq!((err.pos, err.msg.as_str()), (1, "unbalanced ')'"));
+		assert_eq!(Expr::parse("", &names()).unwrap_err().pos, 0);
+	}
+
+	#[test]
+	fn assignment() {
+		assert_eq!(split_assignment("x = a'b"), ("x", " a'b", 3));
+		assert_eq!(split_assignment("a + b"), ("", "a + b", 0));
+	}
+}
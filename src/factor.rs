@@ -0,0 +1,418 @@
+// The choice the comment at the top of lib.rs leaves open: whether to merge
+// "ab'c + ab'c'" into "ab'" in one output when another output has "ab'c'"
+// too.  The greedy simplifier minimizes each output on its own, and there the
+// merge always pays.  Here the outputs are merged together, one term at a
+// time, and a merge is made only where the whole system costs less after it
+// than before, a term several outputs have counting once.  A term is merged
+// by dropping one of its inputs, for the output it is in or for every output
+// the larger term is an implicant of.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use super::{Equation, Term};
+use super::exact::{self, Cube};
+use super::notation::Notation;
+
+// What a system of equations costs, a term several have counting once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tally {
+	pub terms: usize,
+	pub literals: usize,
+}
+
+impl fmt::Display for Tally {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} terms, {} literals", self.terms, self.literals)
+	}
+}
+
+// Which of a tally's counts comes first.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Model {
+	// the literals of the distinct terms, as --share-terms writes them.
+	Literals,
+	// the distinct terms, the 'if' statements of the conditions.
+	Conditions,
+}
+
+impl Model {
+	fn key(&self, t: &Tally) -> (usize, usize) {
+		match *self {
+			Model::Literals => (t.literals, t.terms),
+			Model::Conditions => (t.terms, t.literals),
+		}
+	}
+}
+
+// A merge that was considered: 'from' and the term next to it, in the output
+// 'outputs[0]', make 'into', which replaces the terms it contains in each of
+// 'outputs'.  It was made when the system's tally after it, 'merge', was
+// below the tally keeping the terms apart, 'keep'.
+#[derive(Clone, Debug)]
+pub struct Decision {
+	pub outputs: Vec<usize>,
+	pub from: (Term, Term),
+	pub into: Term,
+	pub merge: Tally,
+	pub keep: Tally,
+	pub merged: bool,
+}
+
+// "ab'c + ab'c' merged into ab' (5 terms, 13 literals; kept apart: 5 terms, 14
+// literals)", the outputs named when several took the merged term.
+pub fn describe(d: &Decision, eqns: &[Equation], n: &Notation) -> String {
+	let also = if d.outputs.len() < 2 {
+		String::new()
+	} else {
+		let names: Vec<&str> = d.outputs.iter().map(|&e| eqns[e].varname.as_str())
+			.collect();
+		format!(" for {}", names.join(", "))
+	};
+	let (a, b, into) = (n.term(&d.from.0), n.term(&d.from.1), n.term(&d.into));
+	if d.merged {
+		format!("{} + {} merged into {}{} ({}; kept apart: {})", a, b, into, also, d.merge,
+		        d.keep)
+	} else {
+		format!("{} + {} kept apart ({}; merged into {}{}: {})", a, b, d.keep, into, also,
+		        d.merge)
+	}
+}
+
+fn literals(cube: &Cube) -> usize {
+	cube.iter().filter(|b| b.is_some()).count()
+}
+
+// The system's tally, from how many equations have each term.
+fn tally(uses: &HashMap<Cube, usize>) -> Tally {
+	Tally{terms: uses.len(), literals: uses.keys().map(literals).sum()}
+}
+
+// The tally of the equations, 'now' before, with 'into' replacing the terms
+// it contains in each of 'outputs'.
+fn tally_after(uses: &HashMap<Cube, usize>, now: Tally, terms: &[Vec<Cube>],
+               outputs: &[usize], into: &Cube) -> Tally {
+	// how many of each term's equations lose it, 'into' gaining them back.
+	let mut lost: HashMap<&Cube, usize> = HashMap::new();
+	for &e in outputs.iter() {
+		for t in terms[e].iter().filter(|t| exact::cube_contains(into, t)) {
+			*lost.entry(t).or_insert(0) += 1;
+		}
+	}
+	let mut rv = now;
+	for (t, n) in lost {
+		if t != into && uses[t] == n {
+			rv.terms -= 1;
+			rv.literals -= literals(t);
+		}
+	}
+	if !uses.contains_key(into) {
+		rv.terms += 1;
+		rv.literals += literals(into);
+	}
+	rv
+}
+
+// What the equations cost.
+pub fn cost(eqns: &[Equation], nbits: usize) -> Tally {
+	let terms: Vec<Vec<Cube>> = eqns.iter()
+		.map(|e| e.terms.iter().map(|t| exact::term_cube(t, nbits)).collect())
+		.collect();
+	tally(&usage(&terms))
+}
+
+fn usage(terms: &[Vec<Cube>]) -> HashMap<Cube, usize> {
+	let mut uses: HashMap<Cube, usize> = HashMap::new();
+	for ts in terms.iter() {
+		let distinct: HashSet<&Cube> = ts.iter().collect();
+		for t in distinct {
+			*uses.entry(t.clone()).or_insert(0) += 1;
+		}
+	}
+	uses
+}
+
+// The equations' terms and don't-cares, as cubes and as the minterms each
+// may raise.
+struct System {
+	terms: Vec<Vec<Cube>>,
+	care: Vec<HashSet<Vec<bool>>>,
+}
+
+impl System {
+	fn new(eqns: &[Equation], nbits: usize) -> Self {
+		let terms: Vec<Vec<Cube>> = eqns.iter().map(|e| {
+			let mut cubes: Vec<Cube> = vec![];
+			for t in e.terms.iter() {
+				let cube = exact::term_cube(t, nbits);
+				if !cubes.contains(&cube) {
+					cubes.push(cube);
+				}
+			}
+			cubes
+		}).collect();
+		let care = eqns.iter()
+			.map(|e| e.terms.iter().chain(e.dc.iter())
+				.flat_map(|t| exact::expand_cube(&exact::term_cube(t, nbits)))
+				.collect())
+			.collect();
+		System{terms, care}
+	}
+
+	fn implies(&self, e: usize, cube: &Cube) -> bool {
+		exact::expand_cube(cube).iter().all(|m| self.care[e].contains(m))
+	}
+
+	// The merges of the equation's term 't' dropping input 'v': the term it is
+	// next to and the one they make, or None where that isn't an implicant.
+	fn neighbour(&self, e: usize, t: &Cube, v: usize) -> Option<(Cube, Cube)> {
+		let mut next = t.clone();
+		next[v] = next[v].map(|b| !b);
+		let mut into = t.clone();
+		into[v] = None;
+		if t[v].is_none() || !self.implies(e, &next) {
+			return None;
+		}
+		Some((next, into))
+	}
+
+	// The best of merging 't' for 'e' alone or for every output 'into' is an
+	// implicant of and contains a term of: the outputs and the tally after.
+	fn best(&self, e: usize, into: &Cube, uses: &HashMap<Cube, usize>, now: Tally,
+	        model: Model) -> (Vec<usize>, Tally) {
+		let alone = vec![e];
+		let mut all = alone.clone();
+		all.extend((0..self.terms.len()).filter(|&o| o != e)
+			.filter(|&o| self.terms[o].iter().any(|t| exact::cube_contains(into, t)))
+			.filter(|&o| self.implies(o, into)));
+		let after_alone = tally_after(uses, now, &self.terms, &alone, into);
+		if all.len() == 1 {
+			return (alone, after_alone);
+		}
+		let after_all = tally_after(uses, now, &self.terms, &all, into);
+		if model.key(&after_all) < model.key(&after_alone) {
+			(all, after_all)
+		} else {
+			(alone, after_alone)
+		}
+	}
+
+	fn apply(&mut self, outputs: &[usize], into: &Cube) {
+		for &e in outputs.iter() {
+			let at = self.terms[e].iter().position(|t| exact::cube_contains(into, t))
+				.unwrap_or(self.terms[e].len());
+			self.terms[e].retain(|t| !exact::cube_contains(into, t));
+			self.terms[e].insert(at, into.clone());
+		}
+	}
+
+	// Every merge of a term in the equations: the output, the term, the one
+	// next to it and the one they make.
+	fn merges(&self) -> Vec<(usize, Cube, Cube, Cube)> {
+		let mut rv = vec![];
+		for (e, ts) in self.terms.iter().enumerate() {
+			for t in ts.iter() {
+				for v in 0..t.len() {
+					if let Some((next, into)) = self.neighbour(e, t, v) {
+						rv.push((e, t.clone(), next, into));
+					}
+				}
+			}
+		}
+		rv
+	}
+}
+
+// Weighs one merge: the term 'from' of equation 'e' and the one next to it
+// in input 'v', against keeping them apart.  None when the two don't make an
+// implicant of the equation.
+pub fn decide(eqns: &[Equation], nbits: usize, e: usize, from: &Term, v: usize,
+              model: Model) -> Option<Decision> {
+	let sys = System::new(eqns, nbits);
+	let uses = usage(&sys.terms);
+	let t = exact::term_cube(from, nbits);
+	let (next, into) = sys.neighbour(e, &t, v)?;
+	let keep = tally(&uses);
+	let (outputs, merge) = sys.best(e, &into, &uses, keep, model);
+	Some(decision(outputs, (&t, &next, &into), merge, keep, model, &from.names))
+}
+
+fn decision(outputs: Vec<usize>, cubes: (&Cube, &Cube, &Cube), merge: Tally, keep: Tally,
+            model: Model, names: &[String]) -> Decision {
+	let (t, next, into) = cubes;
+	Decision{outputs,
+	         from: (exact::cube_to_term(t, names), exact::cube_to_term(next, names)),
+	         into: exact::cube_to_term(into, names),
+	         merge, keep, merged: model.key(&merge) < model.key(&keep)}
+}
+
+// Merges terms of the equations, output by output and term by term, wherever
+// that lowers the system's cost under the model, until no merge does.
+// Returns the merges made, in order, and then those left because keeping the
+// terms apart costs no more: merges of terms other outputs share.
+pub fn merge(eqns: &mut [Equation], nbits: usize, model: Model) -> Vec<Decision> {
+	let names = match eqns.iter().flat_map(|e| e.terms.iter()).next() {
+		None => return vec![],
+		Some(t) => t.names.clone(),
+	};
+	let mut sys = System::new(eqns, nbits);
+	let mut decisions: Vec<Decision> = vec![];
+	let mut uses = usage(&sys.terms);
+	let mut keep = tally(&uses);
+	loop {
+		let made = decisions.len();
+		for (e, t, next, into) in sys.merges() {
+			// an earlier merge may have taken the term.
+			if !sys.terms[e].contains(&t) {
+				continue;
+			}
+			let (outputs, after) = sys.best(e, &into, &uses, keep, model);
+			if model.key(&after) < model.key(&keep) {
+				sys.apply(&outputs, &into);
+				decisions.push(decision(outputs, (&t, &next, &into), after, keep, model,
+				                        &names));
+				uses = usage(&sys.terms);
+				keep = tally(&uses);
+			}
+		}
+		if decisions.len() == made {
+			break;
+		}
+	}
+	let mut declined: HashSet<(usize, Cube)> = HashSet::new();
+	for (e, t, next, into) in sys.merges() {
+		if sys.terms[e].contains(&into) || !declined.insert((e, into.clone())) {
+			continue;
+		}
+		let (outputs, after) = sys.best(e, &into, &uses, keep, model);
+		decisions.push(decision(outputs, (&t, &next, &into), after, keep, model, &names));
+	}
+	for (eqn, ts) in eqns.iter_mut().zip(sys.terms.iter()) {
+		eqn.terms = ts.iter().map(|c| exact::cube_to_term(c, &names)).collect();
+	}
+	decisions
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse, Truth};
+	use super::super::testutil;
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|s| s.to_string()).collect()
+	}
+
+	// The system the comment at the top of lib.rs works through.
+	fn documented() -> Truth {
+		let s = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		         1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+		parse(s.as_bytes(), 0, 3, 2)
+	}
+
+	fn system(x: &str, y: &str) -> Vec<Equation> {
+		let mut eqns = vec![Equation::parse(x, &names()).unwrap(),
+		                    Equation::parse(y, &names()).unwrap()];
+		eqns[1].index = 1;
+		eqns
+	}
+
+	fn correct(eqns: &[Equation], tbl: &Truth) -> bool {
+		eqns.iter().enumerate().all(|(o, e)| {
+			tbl.table.iter().all(|ent| e.evaluate(&ent.input) == ent.output[o])
+		})
+	}
+
+	#[test]
+	fn documented_choice() {
+		// where the comment stops: merge ab'c + ab'c' in y, or keep ab'c' in
+		// common with x?
+		let x = "x = a'b'c + ab'c' + bc'";
+		let y = "y = a'b'c' + ab'c + ab'c' + bc'";
+		let merged = cost(&system(x, "y = a'b'c' + ab' + bc'"), 3);
+		let kept = cost(&system(x, y), 3);
+		assert_eq!(kept, Tally{terms: 5, literals: 14});
+		assert_eq!(merged, Tally{terms: 5, literals: 13});
+		let ab_c = Equation::parse("y = ab'c", &names()).unwrap().terms.remove(0);
+		for &model in [Model::Literals, Model::Conditions].iter() {
+			let d = decide(&system(x, y), 3, 1, &ab_c, 2, model).unwrap();
+			assert_eq!((d.merge, d.keep), (merged, kept));
+			assert_eq!(d.into.to_string(), "ab'");
+			assert_eq!(d.merged, model.key(&merged) < model.key(&kept));
+			assert_eq!(describe(&d, &system(x, y), &Notation::classic()),
+			           "ab'c + ab'c' merged into ab' (5 terms, 13 literals; kept apart: 5 \
+			            terms, 14 literals)");
+		}
+	}
+
+	#[test]
+	fn shared_terms_kept() {
+		// ab'c and ab'c' each raise another output too: merging them in y only
+		// adds ab'.
+		let x = "x = ab'c + ab'c'";
+		let y = "y = ab'c + ab'c' + a'bc";
+		let mut eqns = system(x, y);
+		eqns.push(Equation::parse("z = ab'c", &names()).unwrap());
+		let ab_c = Equation::parse("y = ab'c", &names()).unwrap().terms.remove(0);
+		let d = decide(&eqns, 3, 1, &ab_c, 2, Model::Conditions).unwrap();
+		// x takes it too; z can't.
+		assert_eq!(d.outputs, vec![1, 0]);
+		assert!(d.merged);
+		let mut lone = system("x = ab'c'", y);
+		lone.push(Equation::parse("z = ab'c", &names()).unwrap());
+		for &model in [Model::Literals, Model::Conditions].iter() {
+			let d = decide(&lone, 3, 1, &ab_c, 2, model).unwrap();
+			assert_eq!(d.outputs, vec![1]);
+			assert_eq!(d.keep, Tally{terms: 3, literals: 9});
+			assert_eq!(d.merge, Tally{terms: 4, literals: 11});
+			assert!(!d.merged);
+		}
+	}
+
+	#[test]
+	fn documented_system() {
+		let tbl = documented();
+		for &model in [Model::Literals, Model::Conditions].iter() {
+			let mut eqns = equations(&tbl, vec!["x", "y"], names());
+			let start = cost(&eqns, 3);
+			let decisions = merge(&mut eqns, 3, model);
+			assert!(correct(&eqns, &tbl));
+			let end = cost(&eqns, 3);
+			assert!(model.key(&end) < model.key(&start));
+			// each merge made lowered the cost, each declined wouldn't have.
+			let mut last = start;
+			for d in decisions.iter() {
+				if d.merged {
+					assert_eq!(d.keep, last);
+					assert!(model.key(&d.merge) < model.key(&d.keep));
+					last = d.merge;
+				} else {
+					assert_eq!(d.keep, end);
+					assert!(model.key(&d.merge) >= model.key(&d.keep));
+				}
+			}
+			assert_eq!(last, end);
+			// as few conditions as the exact search finds.
+			assert_eq!(end.terms, 5, "{:?}", model);
+		}
+	}
+
+	#[test]
+	fn fixtures_stay_correct() {
+		for f in [testutil::dense(5, 3, 7), testutil::sparse(6, 2, 4, 3),
+		              testutil::capabilities()] {
+			let nbits = f.invars.len();
+			let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+			for &model in [Model::Literals, Model::Conditions].iter() {
+				let mut eqns = equations(&f.truth, outvars.clone(), f.invars.clone());
+				let start = cost(&eqns, nbits);
+				merge(&mut eqns, nbits, model);
+				assert!(model.key(&cost(&eqns, nbits)) <= model.key(&start));
+				for (o, eqn) in eqns.iter().enumerate() {
+					for ent in f.truth.table.iter() {
+						assert_eq!(eqn.evaluate(&ent.input), ent.output[o], "{} {}",
+						           f.name, eqn.varname);
+					}
+				}
+			}
+		}
+	}
+}
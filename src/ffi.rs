@@ -0,0 +1,276 @@
+// A C interface for embedding the minimizer, built with '--features ffi' and
+// declared in include/minterm.h.  A program builds a table row by row, then
+// asks for its minimized outputs written in one of the command line's
+// --format languages.  The functions return the command line's exit statuses,
+// or MINTERM_PANIC when something panicked; no panic unwinds into C.
+//
+// A static library to link against comes from
+//   cargo rustc --release --features ffi --lib --crate-type staticlib
+//
+// The pointer arguments must be valid as the header describes.
+#![allow(clippy::missing_safety_doc)]
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use super::{Entry, MAX_ENUMERATED_BITS, Truth};
+use super::embed::{self, Error};
+
+pub const MINTERM_OK: c_int = 0;
+pub const MINTERM_USAGE: c_int = 2;
+pub const MINTERM_INVALID: c_int = 3;
+pub const MINTERM_UNPROVEN: c_int = 5;
+pub const MINTERM_PANIC: c_int = -1;
+
+// A table under construction; opaque to C.
+pub struct Table {
+	invars: Vec<String>,
+	outvars: Vec<String>,
+	tbl: Truth,
+	// the inputs of every row so far, to refuse a second row for one.
+	seen: HashSet<Vec<bool>>,
+}
+
+#[repr(C)]
+pub struct Options {
+	// as --format; null for "text".
+	pub format: *const c_char,
+	// as --default-output; null when the table lists every input.
+	pub default_output: *const c_char,
+	// nonzero for --algorithm exact.
+	pub exact: c_int,
+	// as --max-steps for the exact search; 0 for no limit.
+	pub max_steps: u64,
+}
+
+// Runs 'f', reporting a panic as MINTERM_PANIC.
+fn guard<F: FnOnce() -> c_int>(f: F) -> c_int {
+	panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(MINTERM_PANIC)
+}
+
+unsafe fn string(p: *const c_char) -> Option<String> {
+	if p.is_null() {
+		return None;
+	}
+	CStr::from_ptr(p).to_str().ok().map(|s| s.to_string())
+}
+
+unsafe fn strings(ps: *const *const c_char, n: usize) -> Option<Vec<String>> {
+	if ps.is_null() {
+		return None;
+	}
+	slice::from_raw_parts(ps, n).iter().map(|&p| string(p)).collect()
+}
+
+// 'n' bytes that are each 0 or 1.
+unsafe fn bits(p: *const u8, n: usize) -> Option<Vec<bool>> {
+	if p.is_null() {
+		return None;
+	}
+	slice::from_raw_parts(p, n).iter().map(|&b| match b {
+		0 => Some(false),
+		1 => Some(true),
+		_ => None,
+	}).collect()
+}
+
+// Makes an empty table with the given input and output names, for
+// minterm_table_free.  MINTERM_USAGE when a name is null or not UTF-8, when
+// there are no inputs or outputs, or more than MAX_ENUMERATED_BITS inputs.
+#[no_mangle]
+pub unsafe extern "C" fn minterm_table_new(invars: *const *const c_char, nin: usize,
+                                           outvars: *const *const c_char, nout: usize,
+                                           table: *mut *mut Table) -> c_int {
+	guard(|| {
+		if table.is_null() {
+			return MINTERM_USAGE;
+		}
+		*table = ptr::null_mut();
+		if nin == 0 || nin > MAX_ENUMERATED_BITS || nout == 0 {
+			return MINTERM_USAGE;
+		}
+		let (invars, outvars) = match (strings(invars, nin), strings(outvars, nout)) {
+			(Some(i), Some(o)) => (i, o),
+			_ => return MINTERM_USAGE,
+		};
+		*table = Box::into_raw(Box::new(Table{invars, outvars, tbl: Truth::default(),
+		                                      seen: HashSet::new()}));
+		MINTERM_OK
+	})
+}
+
+// Adds a row: one byte per input and per output, each 0 or 1.  Null outputs
+// make the row's outputs don't-cares.  MINTERM_INVALID when a byte is neither
+// or the table already has a row for the inputs.
+#[no_mangle]
+pub unsafe extern "C" fn minterm_table_add_row(table: *mut Table, inputs: *const u8,
+                                               outputs: *const u8) -> c_int {
+	guard(|| {
+		if table.is_null() || inputs.is_null() {
+			return MINTERM_USAGE;
+		}
+		let t = &mut *table;
+		let input = match bits(inputs, t.invars.len()) {
+			Some(input) => input,
+			None => return MINTERM_INVALID,
+		};
+		let output = if outputs.is_null() {
+			None
+		} else {
+			match bits(outputs, t.outvars.len()) {
+				Some(output) => Some(output),
+				None => return MINTERM_INVALID,
+			}
+		};
+		if !t.seen.insert(input.clone()) {
+			return MINTERM_INVALID;
+		}
+		match output {
+			Some(output) => t.tbl.table.push(Entry::new(input, output)),
+			None => t.tbl.dc.push(input),
+		}
+		MINTERM_OK
+	})
+}
+
+// Minimizes every output of the table and stores the equations, written in
+// options->format, in '*result'; null options are all defaults.  On failure
+// '*result' is the error message instead.  Either way it is for
+// minterm_string_free.  MINTERM_UNPROVEN when the exact search ran out of
+// steps; the equations are still correct.
+#[no_mangle]
+pub unsafe extern "C" fn minterm_minimize(table: *const Table, options: *const Options,
+                                          result: *mut *mut c_char) -> c_int {
+	guard(|| {
+		if table.is_null() || result.is_null() {
+			return MINTERM_USAGE;
+		}
+		*result = ptr::null_mut();
+		let mut opts = embed::Options::default();
+		if !options.is_null() {
+			let given = &*options;
+			if !given.format.is_null() {
+				opts.format = match string(given.format) {
+					Some(f) => f,
+					None => return MINTERM_USAGE,
+				};
+			}
+			if !given.default_output.is_null() {
+				opts.default_output = match string(given.default_output) {
+					Some(d) => Some(d),
+					None => return MINTERM_USAGE,
+				};
+			}
+			opts.exact = given.exact != 0;
+			opts.max_steps = if given.max_steps == 0 { None } else { Some(given.max_steps) };
+		}
+		let t = &*table;
+		let (status, text) = match embed::generate(&t.tbl, &t.invars, &t.outvars, &opts) {
+			Ok((text, true)) => (MINTERM_OK, text),
+			Ok((text, false)) => (MINTERM_UNPROVEN, text),
+			Err(Error::Usage(msg)) => (MINTERM_USAGE, msg),
+			Err(Error::Invalid(msg)) => (MINTERM_INVALID, msg),
+		};
+		// the names came from C strings, so nothing written has a NUL in it.
+		*result = CString::new(text).unwrap().into_raw();
+		status
+	})
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn minterm_string_free(s: *mut c_char) {
+	if !s.is_null() {
+		drop(CString::from_raw(s));
+	}
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn minterm_table_free(table: *mut Table) {
+	if !table.is_null() {
+		drop(Box::from_raw(table));
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const SMALL: [([u8; 3], [u8; 2]); 8] = [
+		([0, 0, 0], [0, 1]), ([0, 0, 1], [1, 0]), ([0, 1, 0], [1, 1]),
+		([0, 1, 1], [0, 0]), ([1, 0, 0], [1, 1]), ([1, 0, 1], [0, 1]),
+		([1, 1, 0], [1, 1]), ([1, 1, 1], [0, 0]),
+	];
+
+	// The table of the small example, made through the C interface.
+	unsafe fn small(rows: usize) -> *mut Table {
+		let names = [CString::new("a").unwrap(), CString::new("b").unwrap(),
+		             CString::new("c").unwrap(), CString::new("x").unwrap(),
+		             CString::new("y").unwrap()];
+		let ptrs: Vec<*const c_char> = names.iter().map(|n| n.as_ptr()).collect();
+		let mut table = ptr::null_mut();
+		assert_eq!(minterm_table_new(ptrs.as_ptr(), 3, ptrs[3..].as_ptr(), 2, &mut table),
+		           MINTERM_OK);
+		for &(inp, out) in SMALL[..rows].iter() {
+			assert_eq!(minterm_table_add_row(table, inp.as_ptr(), out.as_ptr()),
+			           MINTERM_OK);
+		}
+		table
+	}
+
+	unsafe fn run(table: *const Table, options: *const Options) -> (c_int, String) {
+		let mut result = ptr::null_mut();
+		let status = minterm_minimize(table, options, &mut result);
+		let text = CStr::from_ptr(result).to_str().unwrap().to_string();
+		minterm_string_free(result);
+		(status, text)
+	}
+
+	#[test]
+	fn small_example() {
+		unsafe {
+			let table = small(8);
+			assert_eq!(run(table, ptr::null()),
+			           (MINTERM_OK, "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n"
+			                        .to_string()));
+			let format = CString::new("switch-c").unwrap();
+			let opts = Options{format: format.as_ptr(), default_output: ptr::null(),
+			                   exact: 1, max_steps: 0};
+			let (status, text) = run(table, &opts);
+			assert_eq!(status, MINTERM_OK);
+			assert!(text.contains("switch ("), "{}", text);
+			minterm_table_free(table);
+		}
+	}
+
+	#[test]
+	fn errors() {
+		unsafe {
+			let table = small(7);
+			// 111 is missing.
+			let (status, msg) = run(table, ptr::null());
+			assert_eq!(status, MINTERM_INVALID);
+			assert!(msg.contains("no row for 1 input(s)"), "{}", msg);
+			let dc = CString::new("dc").unwrap();
+			let opts = Options{format: ptr::null(), default_output: dc.as_ptr(),
+			                   exact: 0, max_steps: 0};
+			assert_eq!(run(table, &opts).0, MINTERM_OK);
+			let bad = CString::new("perl").unwrap();
+			let opts = Options{format: bad.as_ptr(), ..opts};
+			assert_eq!(run(table, &opts).0, MINTERM_USAGE);
+			// a second row for 000, and a byte that isn't 0 or 1.
+			let (inp, out) = SMALL[0];
+			assert_eq!(minterm_table_add_row(table, inp.as_ptr(), out.as_ptr()),
+			           MINTERM_INVALID);
+			assert_eq!(minterm_table_add_row(table, [1, 1, 1].as_ptr(), [2, 0].as_ptr()),
+			           MINTERM_INVALID);
+			assert_eq!(minterm_table_add_row(table, [1, 1, 1].as_ptr(), ptr::null()),
+			           MINTERM_OK);
+			assert_eq!(run(table, ptr::null()).0, MINTERM_OK);
+			minterm_table_free(table);
+			assert_eq!(minterm_minimize(ptr::null(), ptr::null(), ptr::null_mut()),
+			           MINTERM_USAGE);
+		}
+	}
+}
@@ -0,0 +1,222 @@
+// The driver every pass over the covers runs under.  The passes -- the
+// minimization, --improve's polishing, and whatever comes after them -- are
+// registered here in order and run round after round until a round changes
+// nothing.  Two guards keep the rounds from going on for ever: each pass is
+// run as guarded runs it, so no pass raises the cost, and the covers after
+// each pass are remembered, a pass that would bring back covers the rounds
+// have already had being undone and the rounds stopped there.  With the cost
+// never rising and no covers coming back, the rounds end however the passes
+// behave, as there are only so many covers.
+use std::collections::BTreeSet;
+use super::{Equation, Term};
+use super::embed::{Cost, guarded};
+
+pub trait Pass {
+	fn name(&self) -> &str;
+	// Runs over the covers; false when running again could change nothing,
+	// so that later rounds leave the pass out.
+	fn run(&mut self, eqns: &mut [Equation]) -> bool;
+}
+
+// A pass run in the first round alone, such as a minimization, which
+// starts from the table and would only do the same again.
+pub struct Once<F> {
+	name: &'static str,
+	run: Option<F>,
+}
+
+impl<F: FnOnce(&mut [Equation])> Once<F> {
+	pub fn new(name: &'static str, run: F) -> Self {
+		Once{name, run: Some(run)}
+	}
+}
+
+impl<F: FnOnce(&mut [Equation])> Pass for Once<F> {
+	fn name(&self) -> &str {
+		self.name
+	}
+
+	fn run(&mut self, eqns: &mut [Equation]) -> bool {
+		if let Some(run) = self.run.take() {
+			run(eqns);
+		}
+		false
+	}
+}
+
+// How the rounds ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stop {
+	// a round changed nothing, or no pass had more to do.
+	Converged,
+	// the named pass would have brought back earlier covers.
+	Cycle(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Outcome {
+	pub rounds: usize,
+	pub stop: Stop,
+	// guarded's, for each pass undone for raising the cost.
+	pub warnings: Vec<String>,
+}
+
+impl Outcome {
+	// What to log when the guard, rather than convergence, stopped the rounds.
+	pub fn note(&self) -> Option<String> {
+		match self.stop {
+			Stop::Converged => None,
+			Stop::Cycle(ref pass) => Some(format!(
+				"The {} pass would have brought back covers of an earlier round; the \
+				 passes were stopped after {} round(s).", pass, self.rounds)),
+		}
+	}
+}
+
+// The covers, whatever order their terms are in.
+fn state(eqns: &[Equation]) -> Vec<BTreeSet<Term>> {
+	eqns.iter().map(|e| e.terms.iter().cloned().collect()).collect()
+}
+
+// The passes of a minimization, registered in the order they run: the
+// covers are minimized from the table, then polished.  A pass added to the
+// pipeline is added here.
+pub fn minimize<M, P>(eqns: &mut [Equation], nbits: usize, cost: Cost, minimize: M,
+                      polish: P) -> Outcome
+	where M: FnOnce(&mut [Equation]), P: FnOnce(&mut [Equation]) {
+	let mut minimizing = Once::new("minimize", minimize);
+	let mut polishing = Once::new("improve", polish);
+	run(eqns, nbits, cost, &mut [&mut minimizing, &mut polishing])
+}
+
+// Runs the passes, in order, round after round until they converge or the
+// guard stops them.
+pub fn run(eqns: &mut [Equation], nbits: usize, cost: Cost,
+           passes: &mut [&mut dyn Pass]) -> Outcome {
+	let mut last = state(eqns);
+	let mut seen: BTreeSet<Vec<BTreeSet<Term>>> = BTreeSet::new();
+	seen.insert(last.clone());
+	let mut active = vec![true; passes.len()];
+	let mut rv = Outcome{rounds: 0, stop: Stop::Converged, warnings: vec![]};
+	while active.contains(&true) {
+		rv.rounds += 1;
+		let mut changed = false;
+		for (p, pass) in passes.iter_mut().enumerate() {
+			if !active[p] {
+				continue;
+			}
+			let before: Vec<Vec<Term>> = eqns.iter().map(|e| e.terms.clone()).collect();
+			let name = pass.name().to_string();
+			let (again, warning) = guarded(&name, eqns, nbits, cost, |e| pass.run(e));
+			rv.warnings.extend(warning);
+			active[p] = again;
+			let now = state(eqns);
+			if now == last {
+				continue;
+			}
+			if seen.contains(&now) {
+				for (eqn, terms) in eqns.iter_mut().zip(before) {
+					eqn.terms = terms;
+				}
+				rv.stop = Stop::Cycle(name);
+				return rv;
+			}
+			seen.insert(now.clone());
+			last = now;
+			changed = true;
+		}
+		if !changed {
+			break;
+		}
+	}
+	rv
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::time::Duration;
+	use super::super::{equations, testutil};
+	use super::super::embed::{MinimizeOptions, improve};
+	use super::super::exact::Budget;
+	use super::super::progress::Silent;
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|s| s.to_string()).collect()
+	}
+
+	// Sets the cover of x to the one it was made with, every time.
+	struct Set(&'static str);
+
+	impl Pass for Set {
+		fn name(&self) -> &str {
+			self.0
+		}
+
+		fn run(&mut self, eqns: &mut [Equation]) -> bool {
+			eqns[0] = Equation::parse(self.0, &names()).unwrap();
+			true
+		}
+	}
+
+	#[test]
+	fn cycle_guard() {
+		let start = || vec![Equation::parse("x = a'bc + abc + ab'c", &names()).unwrap()];
+		// two covers of the same cost, each pass undoing the other's.
+		let (mut one, mut other) = (Set("x = bc + ab'c"), Set("x = ac + a'bc"));
+		let mut eqns = start();
+		let out = run(&mut eqns, 3, Cost::Terms, &mut [&mut one, &mut other]);
+		assert_eq!(out.stop, Stop::Cycle("x = bc + ab'c".to_string()));
+		assert_eq!(out.rounds, 2);
+		assert_eq!(eqns[0].to_string(), "x = ac + a'bc + ;");
+		assert_eq!(out.note().unwrap(), "The x = bc + ab'c pass would have brought back \
+		                                 covers of an earlier round; the passes were \
+		                                 stopped after 2 round(s).");
+		// a pass that keeps setting the same covers converges.
+		let mut eqns = start();
+		let out = run(&mut eqns, 3, Cost::Terms, &mut [&mut one]);
+		assert_eq!((out.stop, out.rounds), (Stop::Converged, 2));
+		// a costlier pass is undone, and the rounds converge on what was before.
+		let mut costly = Set("x = a'bc + abc + ab'c + abc'");
+		let mut eqns = start();
+		let out = run(&mut eqns, 3, Cost::Terms, &mut [&mut costly]);
+		assert_eq!((out.stop, out.rounds, out.warnings.len()), (Stop::Converged, 1, 1));
+		assert_eq!(eqns, start());
+	}
+
+	// Simplifies the covers greedily again, which may always be run again.
+	struct Simplify;
+
+	impl Pass for Simplify {
+		fn name(&self) -> &str {
+			"simplify"
+		}
+
+		fn run(&mut self, eqns: &mut [Equation]) -> bool {
+			eqns.iter_mut().for_each(Equation::simplify);
+			true
+		}
+	}
+
+	#[test]
+	fn pipeline_converges() {
+		let fixtures = [testutil::dense(5, 2, 7), testutil::sparse(6, 2, 4, 3),
+		                testutil::parity(3), testutil::capabilities()];
+		for f in fixtures.iter() {
+			let nbits = f.invars.len();
+			let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+			let start = equations(&f.truth, outvars, f.invars.clone());
+			let opts = MinimizeOptions::new().improve(Duration::from_millis(10));
+			let mut eqns = start.clone();
+			let out = minimize(&mut eqns, nbits, Cost::Terms, |eqns| {
+				super::super::minimize(eqns, nbits, false, &vec![1; nbits],
+				                       &mut Budget::unlimited(), &mut Silent);
+			}, |eqns| { improve(eqns, &start, nbits, &opts); });
+			assert_eq!(out, Outcome{rounds: 1, stop: Stop::Converged, warnings: vec![]},
+			           "{}", f.name);
+			// a pass that may always run again still comes to rest.
+			let out = run(&mut eqns, nbits, Cost::Terms, &mut [&mut Simplify]);
+			assert_eq!((out.stop, out.rounds), (Stop::Converged, 1), "{}", f.name);
+		}
+	}
+}
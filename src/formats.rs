@@ -0,0 +1,506 @@
+// Table files other than our CSV: Berkeley PLA files, as espresso reads and
+// writes them, and JSON.  Both name their variables and may hold comments,
+// and both describe rows by cubes, '-' standing for an input that may be
+// either value, so a minimized cover can be written as a table too.
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use serde_json::{self, Value};
+use super::{Entry, Equation, ParseError, Truth, MAX_ENUMERATED_BITS};
+use super::bits::{BitOrder, bit_unpack};
+
+// A row given by an input cube: None for an input that may be either value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cube {
+	pub input: Vec<Option<bool>>,
+	pub output: Vec<bool>,
+}
+
+// A table as a file holds it: its rows, with the names of its variables and
+// any comments.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sheet {
+	pub invars: Vec<String>,
+	pub outvars: Vec<String>,
+	pub comments: Vec<String>,
+	pub rows: Vec<Cube>,
+	// input cubes whose outputs are all don't-cares.
+	pub dc: Vec<Vec<Option<bool>>>,
+	// whether inputs no row covers give 0, as in a cover, rather than being
+	// missing from the table.
+	pub unlisted_zero: bool,
+}
+
+fn full(input: &[bool]) -> Vec<Option<bool>> {
+	input.iter().map(|&b| Some(b)).collect()
+}
+
+// '0', '1', or '-' for an input of a cube.
+pub fn bit_char(bit: Option<bool>) -> char {
+	match bit {
+		Some(true) => '1',
+		Some(false) => '0',
+		None => '-',
+	}
+}
+
+// The input a character of a cube stands for, None if it stands for none.
+// Strictly only '-' is either value; otherwise 'x', 'X' and '?' are too, as
+// other tools write them.
+pub fn parse_bit(c: char, strict: bool) -> Option<Option<bool>> {
+	match c {
+		'0' => Some(Some(false)),
+		'1' => Some(Some(true)),
+		'-' => Some(None),
+		'x' | 'X' | '?' if !strict => Some(None),
+		_ => None,
+	}
+}
+
+// "01-" for a cube.
+pub fn cube_string(cube: &[Option<bool>]) -> String {
+	cube.iter().map(|&b| bit_char(b)).collect()
+}
+
+// The cube "01-" is, as parse_bit reads its characters.  Fails on an empty
+// string, and on a bad character with its position, counted from 1.
+pub fn parse_cube(s: &str, strict: bool) -> Result<Vec<Option<bool>>, String> {
+	if s.is_empty() {
+		return Err("the cube is empty".to_string());
+	}
+	s.chars().enumerate().map(|(k, c)| parse_bit(c, strict)
+		.ok_or_else(|| format!("bad input '{}' at position {}", c, k + 1))).collect()
+}
+
+fn bit_string(bits: &[bool]) -> String {
+	bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+// Every input pattern in the cube.
+pub fn expand(cube: &[Option<bool>]) -> Result<Vec<Vec<bool>>, String> {
+	let free: Vec<usize> = (0..cube.len()).filter(|&i| cube[i].is_none()).collect();
+	if free.len() > MAX_ENUMERATED_BITS {
+		return Err(format!("Cube {} covers 2^{} inputs; at most 2^{} are supported.",
+		                   cube_string(cube), free.len(), MAX_ENUMERATED_BITS));
+	}
+	Ok((0..1u64 << free.len()).map(|i| {
+		let mut inp: Vec<bool> = cube.iter().map(|b| b.unwrap_or(false)).collect();
+		for (&f, b) in free.iter().zip(bit_unpack(i, free.len(), BitOrder::Lsb)) {
+			inp[f] = b;
+		}
+		inp
+	}).collect())
+}
+
+impl Sheet {
+	pub fn from_truth(tbl: &Truth, invars: &[String], outvars: &[String]) -> Self {
+		Sheet{invars: invars.to_vec(), outvars: outvars.to_vec(), comments: vec![],
+		      rows: tbl.table.iter().map(|e| Cube{input: full(&e.input),
+		                                          output: e.output.clone()}).collect(),
+		      dc: tbl.dc.iter().map(|inp| full(inp)).collect(), unlisted_zero: false}
+	}
+
+	// The cover the equations give: a row for each distinct term, setting the
+	// outputs whose equations have it.  Inputs no term covers give 0.
+	pub fn from_equations(eqns: &[Equation], invars: &[String], outvars: &[String]) ->
+		Self {
+		let mut terms: BTreeMap<Vec<Option<bool>>, Vec<bool>> = BTreeMap::new();
+		for eqn in eqns.iter() {
+			for t in eqn.terms.iter() {
+				let mut input = vec![None; invars.len()];
+				for &(idx, val) in t.bits.iter() {
+					input[idx] = Some(val);
+				}
+				terms.entry(input).or_insert_with(|| vec![false; outvars.len()])
+					[eqn.index] = true;
+			}
+		}
+		Sheet{invars: invars.to_vec(), outvars: outvars.to_vec(), comments: vec![],
+		      rows: terms.into_iter().map(|(i, o)| Cube{input: i, output: o}).collect(),
+		      dc: vec![], unlisted_zero: true}
+	}
+
+	// The table of the input patterns the rows cover.  Rows that overlap set
+	// the outputs either sets, as in a cover; without unlisted_zero, where
+	// rows list outputs explicitly, they must agree.
+	pub fn to_truth(&self) -> Result<Truth, String> {
+		let nout = self.outvars.len();
+		let mut rows: BTreeMap<Vec<bool>, Vec<bool>> = BTreeMap::new();
+		for cube in self.rows.iter() {
+			for inp in expand(&cube.input)? {
+				let out = rows.entry(inp.clone()).or_insert_with(|| cube.output.clone());
+				if *out == cube.output {
+					continue;
+				}
+				if !self.unlisted_zero {
+					return Err(format!("Input {} has two rows, with outputs {} and {}.",
+					                   bit_string(&inp), bit_string(out),
+					                   bit_string(&cube.output)));
+				}
+				for (o, &b) in out.iter_mut().zip(cube.output.iter()) {
+					*o |= b;
+				}
+			}
+		}
+		let mut dc: Vec<Vec<bool>> = vec![];
+		for cube in self.dc.iter() {
+			dc.extend(expand(cube)?.into_iter().filter(|inp| !rows.contains_key(inp)));
+		}
+		if self.unlisted_zero {
+			let nin = self.invars.len();
+			let every = expand(&vec![None; nin])?;
+			for inp in every {
+				if !dc.contains(&inp) {
+					rows.entry(inp).or_insert_with(|| vec![false; nout]);
+				}
+			}
+		}
+		Ok(Truth{table: rows.into_iter().map(|(i, o)| Entry::new(i, o)).collect(), dc})
+	}
+}
+
+fn error(line: usize, invalid: bool, msg: String) -> ParseError {
+	ParseError{line, msg, invalid}
+}
+
+// The most inputs or outputs a PLA may declare: far more than a table could
+// be minimized over, but few enough to name each of them.
+const MAX_DECLARED: usize = 1 << 16;
+
+// Reads a PLA file: '.i' and '.o' give the numbers of inputs and outputs,
+// '.ilb' and '.ob' their names, '.type' whether unlisted inputs are 0 ('f',
+// 'fd') or not, and each other line a row such as "01- 10".  Outputs '-' make
+// a row don't-care, which has to hold for all its outputs; '~' is 0.
+pub fn read_pla<R: Read>(data: R) -> Result<Sheet, ParseError> {
+	let mut sheet = Sheet{invars: vec![], outvars: vec![], comments: vec![], rows: vec![],
+	                      dc: vec![], unlisted_zero: true};
+	let (mut nin, mut nout): (Option<usize>, Option<usize>) = (None, None);
+	for (n, line) in BufReader::new(data).lines().enumerate() {
+		let line = line.map_err(|e| error(n + 1, false, e.to_string()))?;
+		let line = line.trim();
+		if let Some(comment) = line.strip_prefix('#') {
+			sheet.comments.push(comment.trim().to_string());
+			continue;
+		}
+		let words: Vec<&str> = line.split_whitespace().collect();
+		// the rows so far have the cells the first count gave.
+		let count = |words: &[&str], old: Option<usize>| {
+			match words.get(1).map(|w| w.parse::<usize>()) {
+				Some(Ok(c)) if c > MAX_DECLARED => Err(error(n + 1, true, format!(
+					"'{} {}' declares more than {} variables.", words[0], c,
+					MAX_DECLARED))),
+				Some(Ok(c)) if old.is_some_and(|o| o != c) => Err(error(n + 1, true,
+					format!("'{} {}' contradicts the earlier '{} {}'.", words[0], c,
+					        words[0], old.unwrap()))),
+				Some(Ok(c)) => Ok(Some(c)),
+				_ => Err(error(n + 1, false, format!("'{}' needs a number.", words[0]))),
+			}
+		};
+		match words.first().cloned() {
+			None => {},
+			Some(".i") => nin = count(&words, nin)?,
+			Some(".o") => nout = count(&words, nout)?,
+			Some(".ilb") => sheet.invars = words[1..].iter().map(|w| w.to_string()).collect(),
+			Some(".ob") => sheet.outvars = words[1..].iter().map(|w| w.to_string()).collect(),
+			Some(".type") => sheet.unlisted_zero = match words.get(1).cloned() {
+				Some("f") | Some("fd") => true,
+				Some("fr") | Some("fdr") => false,
+				t => return Err(error(n + 1, false, format!("Unknown PLA type '{}'.",
+				                                            t.unwrap_or("")))),
+			},
+			Some(".e") | Some(".end") => break,
+			// the row count and other directives don't matter here.
+			Some(w) if w.starts_with('.') => {},
+			Some(_) => {
+				let (ni, no) = match (nin, nout) {
+					(Some(i), Some(o)) => (i, o),
+					_ => return Err(error(n + 1, true, "A row before '.i' and '.o'."
+					                                    .to_string())),
+				};
+				// by characters: a cell that isn't ASCII is still one bad cell.
+				let cells: Vec<char> = words.concat().chars().collect();
+				if cells.len() != ni + no {
+					return Err(error(n + 1, true, format!(
+						"expected {} inputs and {} outputs, found {} cells", ni, no,
+						cells.len())));
+				}
+				let ins: String = cells[..ni].iter().collect();
+				let input = if ni == 0 { vec![] } else {
+					parse_cube(&ins, false).map_err(|e| error(n + 1, false, e))?
+				};
+				let outs: String = cells[ni..].iter().collect();
+				if outs.chars().all(|c| c == '-') && no > 0 {
+					sheet.dc.push(input);
+					continue;
+				}
+				let mut output: Vec<bool> = vec![];
+				for c in outs.chars() {
+					output.push(match c {
+						'1' | '4' => true,
+						'0' | '~' | '3' => false,
+						'-' | '2' => return Err(error(n + 1, true, format!(
+							"don't-cares for some outputs of a row ('{}') aren't supported",
+							outs))),
+						_ => return Err(error(n + 1, false, format!("bad output '{}'", c))),
+					});
+				}
+				sheet.rows.push(Cube{input, output});
+			},
+		}
+	}
+	let (ni, no) = match (nin, nout) {
+		(Some(i), Some(o)) => (i, o),
+		_ => return Err(error(0, true, "A PLA needs '.i' and '.o'.".to_string())),
+	};
+	if sheet.invars.is_empty() {
+		sheet.invars = (0..ni).map(|i| format!("i{}", i)).collect();
+	}
+	if sheet.outvars.is_empty() {
+		sheet.outvars = (0..no).map(|o| format!("o{}", o)).collect();
+	}
+	if sheet.invars.len() != ni || sheet.outvars.len() != no {
+		return Err(error(0, true, format!("'.ilb' and '.ob' name {} and {} variables for \
+		                                   '.i {}' and '.o {}'.", sheet.invars.len(),
+		                                  sheet.outvars.len(), ni, no)));
+	}
+	Ok(sheet)
+}
+
+pub fn write_pla<W: Write>(wrt: &mut W, sheet: &Sheet) -> Result<(), String> {
+	let mut lines: Vec<String> = sheet.comments.iter().map(|c| format!("# {}", c))
+		.collect();
+	lines.push(format!(".i {}", sheet.invars.len()));
+	lines.push(format!(".o {}", sheet.outvars.len()));
+	lines.push(format!(".ilb {}", sheet.invars.join(" ")));
+	lines.push(format!(".ob {}", sheet.outvars.join(" ")));
+	lines.push(format!(".type {}", if sheet.unlisted_zero { "fd" } else { "fr" }));
+	lines.push(format!(".p {}", sheet.rows.len() + sheet.dc.len()));
+	for cube in sheet.rows.iter() {
+		lines.push(format!("{} {}", cube_string(&cube.input), bit_string(&cube.output)));
+	}
+	let dashes: String = sheet.outvars.iter().map(|_| '-').collect();
+	for cube in sheet.dc.iter() {
+		lines.push(format!("{} {}", cube_string(cube), dashes));
+	}
+	lines.push(".e".to_string());
+	for line in lines {
+		writeln!(wrt, "{}", line).map_err(|e| e.to_string())?;
+	}
+	Ok(())
+}
+
+// Reads a JSON table, as write_json writes it.
+pub fn read_json<R: Read>(data: R) -> Result<Sheet, ParseError> {
+	let doc: Value = serde_json::from_reader(data)
+		.map_err(|e| error(e.line(), false, e.to_string()))?;
+	let invalid = |msg: &str| error(0, true, msg.to_string());
+	let strings = |key: &str| -> Result<Vec<String>, ParseError> {
+		match doc.get(key) {
+			None => Ok(vec![]),
+			Some(Value::Array(vs)) => vs.iter().map(|v| match v.as_str() {
+				Some(s) => Ok(s.to_string()),
+				None => Err(invalid(&format!("'{}' must list strings.", key))),
+			}).collect(),
+			Some(_) => Err(invalid(&format!("'{}' must be a list.", key))),
+		}
+	};
+	let (invars, outvars) = (strings("inputs")?, strings("outputs")?);
+	let cube = |s: &str| -> Result<Vec<Option<bool>>, ParseError> {
+		if s.len() != invars.len() {
+			return Err(invalid(&format!("Input cube '{}' should have {} inputs.", s,
+			                            invars.len())));
+		}
+		parse_cube(s, true).map_err(|e| invalid(&format!("Input cube '{}': {}.", s, e)))
+	};
+	let mut rows: Vec<Cube> = vec![];
+	for row in doc.get("rows").and_then(|r| r.as_array()).unwrap_or(&vec![]) {
+		let (i, o) = match (row.get("in").and_then(|v| v.as_str()),
+		                    row.get("out").and_then(|v| v.as_str())) {
+			(Some(i), Some(o)) => (i, o),
+			_ => return Err(invalid("Each row needs 'in' and 'out' strings.")),
+		};
+		if o.len() != outvars.len() || !o.chars().all(|c| c == '0' || c == '1') {
+			return Err(invalid(&format!("Outputs '{}' should be {} 0/1 digits.", o,
+			                            outvars.len())));
+		}
+		rows.push(Cube{input: cube(i)?, output: o.chars().map(|c| c == '1').collect()});
+	}
+	let mut dc: Vec<Vec<Option<bool>>> = vec![];
+	for s in strings("dont_cares")? {
+		dc.push(cube(&s)?);
+	}
+	let unlisted_zero = match doc.get("unlisted").and_then(|v| v.as_str()) {
+		None | Some("missing") => false,
+		Some("zero") => true,
+		Some(u) => return Err(invalid(&format!("Unknown 'unlisted' value '{}'; expected \
+		                                        'missing' or 'zero'.", u))),
+	};
+	Ok(Sheet{invars, outvars, comments: strings("comments")?, rows, dc, unlisted_zero})
+}
+
+// Writes the table as a JSON object, a row per line:
+//   {"inputs": [...], "outputs": [...], "comments": [...],
+//    "unlisted": "missing" or "zero",
+//    "rows": [{"in": "01-", "out": "10"}, ...], "dont_cares": ["1-1", ...]}
+pub fn write_json<W: Write>(wrt: &mut W, sheet: &Sheet) -> Result<(), String> {
+	let json = |v: Value| v.to_string();
+	let strings = |ss: &[String]| json(Value::from(ss.to_vec()));
+	let rows: Vec<String> = sheet.rows.iter().map(|c| {
+		format!("    {{\"in\": \"{}\", \"out\": \"{}\"}}", cube_string(&c.input),
+		        bit_string(&c.output))
+	}).collect();
+	let dc: Vec<String> = sheet.dc.iter().map(|c| cube_string(c)).collect();
+	let s = format!("{{\n  \"inputs\": {},\n  \"outputs\": {},\n  \"comments\": {},\n  \
+	                 \"unlisted\": \"{}\",\n  \"rows\": [\n{}\n  ],\n  \
+	                 \"dont_cares\": {}\n}}\n", strings(&sheet.invars),
+	                strings(&sheet.outvars), strings(&sheet.comments),
+	                if sheet.unlisted_zero { "zero" } else { "missing" }, rows.join(",\n"),
+	                strings(&dc));
+	wrt.write_all(s.as_bytes()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{InputEncoding, OutputEncoding, equations, parse_encoded,
+	                   write_encoded, HEADER_LINES};
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	// the small example, with 111 a don't-care.
+	fn example() -> Sheet {
+		let csv = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+		           1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n";
+		let tbl = parse_encoded(csv.as_bytes(), 0, 3, 2, InputEncoding::Binary,
+		                        OutputEncoding::Binary).unwrap();
+		let mut sheet = Sheet::from_truth(&tbl, &names(&["a", "b", "c"]),
+		                                  &names(&["x", "y"]));
+		sheet.dc.push(vec![Some(true), Some(true), Some(true)]);
+		sheet.comments.push("decoder, rev 2".to_string());
+		sheet
+	}
+
+	fn write(sheet: &Sheet, fmt: &str) -> Vec<u8> {
+		let mut out: Vec<u8> = vec![];
+		match fmt {
+			"csv" => write_encoded(&mut out, &sheet.to_truth().unwrap(), &sheet.invars,
+			                       &sheet.outvars, InputEncoding::Binary,
+			                       OutputEncoding::Binary).unwrap(),
+			"pla" => write_pla(&mut out, sheet).unwrap(),
+			_ => write_json(&mut out, sheet).unwrap(),
+		}
+		out
+	}
+
+	fn read(data: &[u8], fmt: &str) -> Sheet {
+		match fmt {
+			"csv" => {
+				let tbl = parse_encoded(data, HEADER_LINES, 3, 2, InputEncoding::Binary,
+				                        OutputEncoding::Binary).unwrap();
+				Sheet::from_truth(&tbl, &names(&["a", "b", "c"]), &names(&["x", "y"]))
+			},
+			"pla" => read_pla(data).unwrap(),
+			_ => read_json(data).unwrap(),
+		}
+	}
+
+	#[test]
+	fn round_trips() {
+		let formats = ["csv", "pla", "json"];
+		let orig = example();
+		let table = orig.to_truth().unwrap();
+		for from in formats.iter() {
+			for to in formats.iter() {
+				let sheet = read(&write(&read(&write(&orig, from), from), to), to);
+				let tbl = sheet.to_truth().unwrap();
+				assert_eq!(tbl.table, table.table, "{} -> {}", from, to);
+				assert_eq!((&sheet.invars, &sheet.outvars), (&orig.invars, &orig.outvars));
+				// CSV has no way to keep don't-cares or comments.
+				if *from != "csv" && *to != "csv" {
+					assert_eq!(tbl.dc, table.dc, "{} -> {}", from, to);
+					assert_eq!(sheet.comments, orig.comments);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn cube_strings() {
+		// every cube of up to 4 inputs comes back as it was written.
+		for n in 1..5 {
+			for i in 0..3usize.pow(n) {
+				let cube: Vec<Option<bool>> = (0..n).map(|k| match i / 3usize.pow(k) % 3 {
+					0 => Some(false),
+					1 => Some(true),
+					_ => None,
+				}).collect();
+				assert_eq!(parse_cube(&cube_string(&cube), true), Ok(cube.clone()));
+				assert_eq!(parse_cube(&cube_string(&cube), false), Ok(cube));
+			}
+		}
+		assert_eq!(parse_cube("1x?X", false), Ok(vec![Some(true), None, None, None]));
+		let err = |s: &str, strict: bool| parse_cube(s, strict).unwrap_err();
+		assert_eq!(err("1x?X", true), "bad input 'x' at position 2");
+		assert_eq!(err("01-2", false), "bad input '2' at position 4");
+		assert_eq!(err("", false), "the cube is empty");
+		assert_eq!(parse_bit('?', true), None);
+		assert_eq!(bit_char(None), '-');
+	}
+
+	#[test]
+	fn pla_files() {
+		let pla = "# from espresso\n.i 3\n.o 2\n.ilb a b c\n.ob x y\n.p 3\n\
+		           1-- 10\n-1- 01\n000 --\n.e\n";
+		let sheet = read_pla(pla.as_bytes()).unwrap();
+		assert_eq!(sheet.comments, vec!["from espresso"]);
+		assert!(sheet.unlisted_zero);
+		let tbl = sheet.to_truth().unwrap();
+		assert_eq!(tbl.dc, vec![vec![false, false, false]]);
+		assert_eq!(tbl.solution(vec![true, true, false]), vec![true, true]);
+		assert_eq!(tbl.solution(vec![false, false, true]), vec![false, false]);
+		assert_eq!(tbl.len(), 7);
+		let bad = ".i 2\n.o 2\n01 1-\n";
+		assert!(read_pla(bad.as_bytes()).unwrap_err().invalid);
+		assert_eq!(read_pla(".i 2\n.o 1\n011 1\n".as_bytes()).unwrap_err().line, 3);
+	}
+
+	// Files that once made read_pla panic.
+	#[test]
+	fn malformed_pla_files() {
+		// a two-byte character where one cell is expected.
+		let err = read_pla(".i 1\n.o 1\n\u{e9}\n".as_bytes()).unwrap_err();
+		assert_eq!(err.msg, "expected 1 inputs and 1 outputs, found 1 cells");
+		let err = read_pla(".i 1\n.o 1\n\u{e9}1\n".as_bytes()).unwrap_err();
+		assert_eq!(err.msg, "bad input '\u{e9}' at position 1");
+		// .i + .o overflowed, and naming the inputs ran out of memory.
+		let err = read_pla(".i 18446744073709551615\n.o 1\n01\n".as_bytes()).unwrap_err();
+		assert_eq!((err.line, err.invalid), (1, true));
+		assert!(read_pla(".i 100000000\n.o 1\n.e\n".as_bytes()).is_err());
+		// rows of two lengths, which to_truth mixed into one table.
+		let err = read_pla(".i 2\n.o 1\n01 1\n.i 3\n011 1\n".as_bytes()).unwrap_err();
+		assert_eq!(err.to_string(), "line 4: '.i 3' contradicts the earlier '.i 2'.");
+		assert!(read_pla(".i 2\n.o 1\n.i 2\n01 1\n".as_bytes()).is_ok());
+		// bytes that aren't UTF-8.
+		assert!(read_pla(&b".i 1\n.o 1\n\xff 1\n"[..]).is_err());
+		assert!(read_pla(&b""[..]).unwrap_err().msg.contains("needs '.i' and '.o'"));
+	}
+
+	#[test]
+	fn covers() {
+		let sheet = example();
+		let tbl = sheet.to_truth().unwrap();
+		let mut eqns = equations(&tbl, vec!["x", "y"], sheet.invars.clone());
+		for eqn in eqns.iter_mut() {
+			eqn.simplify();
+		}
+		let cover = Sheet::from_equations(&eqns, &sheet.invars, &sheet.outvars);
+		assert!(cover.rows.len() < tbl.len());
+		let mut pla: Vec<u8> = vec![];
+		write_pla(&mut pla, &cover).unwrap();
+		let back = read_pla(&pla[..]).unwrap().to_truth().unwrap();
+		for ent in tbl.table.iter() {
+			assert_eq!(back.solution(ent.input.clone()), ent.output);
+		}
+	}
+}
@@ -0,0 +1,117 @@
+// --freq-col: how often each input occurs, from a column of the table, and
+// the terms of each equation ordered by it.  Code that evaluates the terms in
+// turn and stops at the first that holds, as C's '||' and Python's 'or' do,
+// then tests the terms of the hot inputs first.  Ordering the terms for the
+// fewest tests on average is a min-sum set cover; each term is taken greedily,
+// the one holding for the most weight of the rows no term before it holds for.
+use std::collections::HashMap;
+use super::{Equation, Term, Truth};
+
+// What ordering an equation's terms did for it: the terms tested on average
+// before and after.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reordering {
+	pub before: f64,
+	pub after: f64,
+}
+
+// How often each row of the table occurs, by the frequency of the line it was
+// read from; a row without one counts once.
+pub fn row_weights(tbl: &Truth, frequencies: &HashMap<usize, f64>) -> Vec<f64> {
+	tbl.table.iter()
+		.map(|e| e.line().and_then(|l| frequencies.get(&l)).cloned().unwrap_or(1.0))
+		.collect()
+}
+
+// The number of terms tested on average over the rows of the table, weighted:
+// up to the first that holds, or all of them.
+pub fn expected_tests(eqn: &Equation, tbl: &Truth, weights: &[f64]) -> f64 {
+	let total: f64 = weights.iter().sum();
+	let tested: f64 = tbl.table.iter().zip(weights.iter()).map(|(e, &w)| {
+		let n = eqn.terms.iter().position(|t| t.evaluate(&e.input))
+			.map_or(eqn.terms.len(), |k| k + 1);
+		w * n as f64
+	}).sum();
+	if total > 0.0 { tested / total } else { 0.0 }
+}
+
+// Orders the terms hottest first, ties in the order they had.  Leaves them as
+// they were if that tests no fewer on average.
+pub fn hottest_first(eqn: &mut Equation, tbl: &Truth, weights: &[f64]) -> Reordering {
+	let before = expected_tests(eqn, tbl, weights);
+	let mut left: Vec<Term> = eqn.terms.clone();
+	// the rows no term taken so far holds for.
+	let mut open = vec![true; tbl.table.len()];
+	let mut ordered: Vec<Term> = vec![];
+	while !left.is_empty() {
+		let heat: Vec<f64> = left.iter().map(|t| {
+			tbl.table.iter().zip(weights.iter()).zip(open.iter())
+				.filter(|&((e, _), &o)| o && t.evaluate(&e.input))
+				.map(|((_, &w), _)| w).sum()
+		}).collect();
+		let mut hottest = 0;
+		for k in 1..left.len() {
+			if heat[k] > heat[hottest] {
+				hottest = k;
+			}
+		}
+		let t = left.remove(hottest);
+		for (o, e) in open.iter_mut().zip(tbl.table.iter()) {
+			if t.evaluate(&e.input) {
+				*o = false;
+			}
+		}
+		ordered.push(t);
+	}
+	let given = std::mem::replace(&mut eqn.terms, ordered);
+	let after = expected_tests(eqn, tbl, weights);
+	if after >= before {
+		eqn.terms = given;
+		return Reordering{before, after: before};
+	}
+	Reordering{before, after}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{Cells, InputEncoding, Layout, OutputEncoding, ParseOutcome, equations,
+	                   parse_layout};
+
+	fn read(csv: &str, freq: &str) -> Result<ParseOutcome, String> {
+		let layout = Layout{freq: Some(freq.to_string()), ..Layout::default()};
+		parse_layout(csv.as_bytes(), 1, 3, 1, InputEncoding::Binary, OutputEncoding::Binary,
+		             Cells::Strict, None, &layout).map_err(|e| e.to_string())
+	}
+
+	#[test]
+	fn hot_term_first() {
+		// x = a + b, and the inputs with b but not a occur ten times as often as
+		// the others, which have no frequency or 0.
+		let csv = "a,b,c,n,x\n0,0,0,,0\n0,0,1,0,0\n0,1,0,10,1\n0,1,1,10,1\n1,0,0,,1\n\
+		           1,0,1,1,1\n1,1,0,,1\n1,1,1,,1\n";
+		let outcome = read(csv, "n").unwrap();
+		let tbl = outcome.truth;
+		let weights = row_weights(&tbl, &outcome.frequencies);
+		assert_eq!(weights, vec![1.0, 1.0, 10.0, 10.0, 1.0, 1.0, 1.0, 1.0]);
+		assert_eq!(read(csv, "3").unwrap().frequencies, outcome.frequencies);
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let mut eqn = equations(&tbl, vec!["x"], names).remove(0);
+		eqn.simplify();
+		eqn.canonicalize();
+		assert_eq!(eqn.to_string(), "x = a + b + ;");
+		// of 26 in all, a holds for 4 and b for 22 more.
+		let r = hottest_first(&mut eqn, &tbl, &weights);
+		assert_eq!(eqn.to_string(), "x = b + a + ;");
+		assert_eq!(((r.before * 26.0).round(), (r.after * 26.0).round()), (48.0, 30.0));
+		// once ordered, there is nothing to gain.
+		let r = hottest_first(&mut eqn, &tbl, &weights);
+		assert_eq!((r.before, eqn.to_string()), (r.after, "x = b + a + ;".to_string()));
+		assert_eq!(read(csv, "x").unwrap_err(),
+		           "line 2: the --freq-col column is one of the outputs");
+		assert_eq!(read(csv, "b").unwrap_err(),
+		           "line 1: the --freq-col column is one of the inputs.");
+		assert_eq!(read(&csv.replace(",10,", ",-1,"), "n").unwrap_err(),
+		           "line 4: frequency '-1' in column 3 is not a non-negative number");
+	}
+}
@@ -0,0 +1,138 @@
+// --given: conditions the code already computes for other reasons, such as
+// "cond1 = a & !b", which the covers may use for free.  Each given becomes an
+// input after the table's own, whose value on a row is that of its
+// expression; the input patterns where it has the other value can't occur,
+// so they are don't-cares, and a minimizer takes the given, in either
+// polarity, wherever its one literal does the work of several.  The
+// equations then name the givens, and expand turns them back into equations
+// over the inputs alone.
+use std::collections::BTreeSet;
+use super::{Entry, Equation, Truth, Variable};
+use super::enumerate::Inputs;
+use super::expr::{Expr, conjoin, split_assignment, terms};
+
+#[derive(Clone, Debug)]
+pub struct Given {
+	pub name: String,
+	// over the inputs of the table.
+	pub expr: Expr,
+}
+
+// Parses the --given declarations, "<name> = <expr>".
+pub fn parse(specs: Vec<&str>, invars: &[String]) -> Result<Vec<Given>, String> {
+	let mut rv: Vec<Given> = vec![];
+	for s in specs {
+		let (name, text, _) = split_assignment(s);
+		if name.is_empty() || name.contains(char::is_whitespace) {
+			return Err(format!("Invalid --given '{}'; expected '<name> = <expr>'.", s));
+		}
+		if invars.iter().any(|n| n == name) {
+			return Err(format!("--given '{}' names an input.", s));
+		}
+		if rv.iter().any(|g| g.name == name) {
+			return Err(format!("--given names '{}' twice.", name));
+		}
+		let expr = Expr::parse(text, invars)
+			.map_err(|e| format!("Cannot parse --given '{}' {}.", s, e))?;
+		rv.push(Given{name: name.to_string(), expr});
+	}
+	Ok(rv)
+}
+
+// The input pattern followed by the value of each given.
+pub fn extend(inp: &[bool], givens: &[Given]) -> Vec<bool> {
+	let mut rv = inp.to_vec();
+	rv.extend(givens.iter().map(|g| g.expr.evaluate(inp)));
+	rv
+}
+
+pub fn extend_table(tbl: &Truth, givens: &[Given]) -> Truth {
+	let mut rv = Truth::default();
+	for ent in tbl.table.iter() {
+		rv.table.push(Entry{input: extend(&ent.input, givens), output: ent.output.clone(),
+		                    dc: ent.dc.clone(), sources: ent.sources.clone()});
+	}
+	rv.dc = tbl.dc.iter().map(|inp| extend(inp, givens)).collect();
+	rv
+}
+
+// Every pattern of the 'nbits' inputs and the givens that can't occur: those
+// a given disagrees with, and those whose inputs are already 'impossible'.
+pub fn impossible(nbits: usize, givens: &[Given], impossible: &BTreeSet<Vec<bool>>) ->
+	BTreeSet<Vec<bool>> {
+	let n = nbits + givens.len();
+	Inputs::every(n)
+		.filter(|p| impossible.contains(&p[..nbits]) || extend(&p[..nbits], givens) != *p)
+		.collect()
+}
+
+// The names of the givens the equation's terms use.
+pub fn used<'a>(eqn: &Equation, nbits: usize, givens: &'a [Given]) -> Vec<&'a str> {
+	givens.iter().enumerate()
+		.filter(|&(k, _)| eqn.terms.iter().any(|t| t.bits.iter().any(|b| b.0 == nbits + k)))
+		.map(|(_, g)| g.name.as_str()).collect()
+}
+
+// The equation over the inputs alone: each term's literals of givens are
+// replaced by their expressions as sums of products, and multiplied out.
+pub fn expand(eqn: &Equation, givens: &[Given], invars: &[String]) -> Equation {
+	let nbits = invars.len();
+	let mut products: Vec<Vec<Variable>> = vec![];
+	for t in eqn.terms.iter() {
+		let own: Vec<Variable> = t.bits.iter().cloned().filter(|b| b.0 < nbits).collect();
+		let mut expanded = vec![own];
+		for &(idx, val) in t.bits.iter().filter(|b| b.0 >= nbits) {
+			let expr = &givens[idx - nbits].expr;
+			let sop = if val { expr.products() }
+			          else { Expr::Not(Box::new(expr.clone())).products() };
+			expanded = expanded.iter()
+				.flat_map(|p| sop.iter().filter_map(move |q| conjoin(p, q))).collect();
+		}
+		for p in expanded {
+			if !products.contains(&p) {
+				products.push(p);
+			}
+		}
+	}
+	Equation{index: eqn.index, terms: terms(products, invars), dc: vec![],
+	         varname: eqn.varname.clone()}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{Term, exact};
+
+	#[test]
+	fn cheaper_with_a_given() {
+		// x = ab'c + ab'd, which is cond c + cond d with cond = ab'.
+		let invars: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string())
+			.collect();
+		let tbl = Truth::from_fn(4, 1, |i| vec![i[0] && !i[1] && (i[2] || i[3])]).unwrap();
+		let givens = parse(vec!["cond = a & !b"], &invars).unwrap();
+		let literals = |e: &Equation| e.terms.iter().map(Term::len).sum::<usize>();
+		let plain = Equation::new(&tbl, 0, "x", &invars);
+		let plain = exact::minimize(&plain, 4, &mut exact::Budget::unlimited()).terms;
+		assert_eq!(plain.iter().map(Term::len).sum::<usize>(), 6);
+		let mut names = invars.clone();
+		names.push("cond".to_string());
+		let mut eqn = Equation::new(&extend_table(&tbl, &givens), 0, "x", &names);
+		for p in impossible(4, &givens, &BTreeSet::new()) {
+			let mut t = Term::compute(&p);
+			t.names = names.clone();
+			eqn.dc.push(t);
+		}
+		eqn.terms = exact::minimize(&eqn, 5, &mut exact::Budget::unlimited()).terms;
+		eqn.canonicalize();
+		assert_eq!(eqn.to_string(), "x = ccond + dcond + ;");
+		assert_eq!(literals(&eqn), 4);
+		assert_eq!(used(&eqn, 4, &givens), vec!["cond"]);
+		let back = expand(&eqn, &givens, &invars);
+		assert!(back.counterexamples(&tbl).is_empty(), "{}", back);
+		assert_eq!(literals(&back), 6);
+		assert!(parse(vec!["a = b"], &invars).is_err());
+		assert!(parse(vec!["b & c"], &invars).is_err());
+		assert!(parse(vec!["g = a", "g = b"], &invars).is_err());
+		assert!(parse(vec!["g = a & e"], &invars).is_err());
+	}
+}
@@ -0,0 +1,254 @@
+// --emit-tests: a test of the C code --format switch-c or if-chain-c writes,
+// in the firmware's own framework, calling 'minterm' on a set of inputs and
+// asserting each output it should give them.  The inputs are the test
+// vectors of 'minterm vectors', or every row of the table should those not
+// check out, and optionally some inputs the table doesn't list, picked at
+// random.  An output is asserted only where the table, or --default-output
+// for an unlisted input, says what it is: never on a don't-care, and an
+// input for which the code aborts isn't tested at all.
+//
+// The cases are one table shared by the tests, each row the inputs followed
+// by the outputs expected of them, -1 for one left open; there is a test per
+// output, which goes through the rows asserting that output.
+use std::collections::BTreeSet;
+use super::{Equation, RowIndex, Truth};
+use super::emit::DefaultOutput;
+use super::rng::Rng;
+use super::switch::c_name;
+use super::vectors;
+
+// The most rows tested when the vectors don't check out and every row of the
+// table is tested instead.
+pub const MAX_ROWS: usize = 65536;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Framework {
+	GTest,
+	Catch2,
+	CUnit,
+}
+
+impl Framework {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"gtest" => Ok(Framework::GTest),
+			"catch2" => Ok(Framework::Catch2),
+			"cunit" => Ok(Framework::CUnit),
+			_ => Err(format!("Invalid --emit-tests '{}'; expected 'gtest', 'catch2' or \
+			                  'cunit'.", s)),
+		}
+	}
+}
+
+// An input and the outputs expected of it, in the equations' order, None for
+// one the test doesn't assert.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Case {
+	pub input: Vec<bool>,
+	pub output: Vec<Option<bool>>,
+}
+
+// What the table, or 'policy' for an input it doesn't list, says each output
+// is; None if the code aborts on the input.
+fn expected(inp: &[bool], eqns: &[Equation], tbl: &Truth, rows: &RowIndex,
+            dc: &BTreeSet<&Vec<bool>>, policy: &DefaultOutput) ->
+	Option<Vec<Option<bool>>> {
+	if let Some(e) = rows.position(inp).map(|r| &tbl.table[r]) {
+		let cells = e.cells();
+		return Some(eqns.iter().map(|q| cells[q.index]).collect());
+	}
+	if dc.contains(&inp.to_vec()) {
+		return Some(vec![None; eqns.len()]);
+	}
+	match *policy {
+		DefaultOutput::Zero => Some(vec![Some(false); eqns.len()]),
+		DefaultOutput::Bits(ref b) =>
+			Some(eqns.iter().map(|q| Some(b[q.index])).collect()),
+		DefaultOutput::DontCare => Some(vec![None; eqns.len()]),
+		DefaultOutput::Panic => None,
+	}
+}
+
+// The cases to test the equations' code with: the test vectors, or every
+// row of the table if they don't check out, and 'sample' inputs the table
+// doesn't list, picked at random from 'seed', that have an output to assert.
+// Cases asserting nothing are left out.
+pub fn cases(eqns: &[Equation], tbl: &Truth, nbits: usize, policy: &DefaultOutput,
+             sample: usize, seed: u64) -> Result<Vec<Case>, String> {
+	let inputs: Vec<Vec<bool>> = match vectors::select(eqns, tbl, nbits) {
+		Ok(vs) => vs.into_iter().map(|v| v.input).collect(),
+		Err(_) if tbl.table.len() <= MAX_ROWS =>
+			tbl.table.iter().map(|e| e.input.clone()).collect(),
+		Err(e) => return Err(format!("{} The table has more than {} rows to test \
+		                              instead.", e, MAX_ROWS)),
+	};
+	let rows = tbl.index();
+	let dc: BTreeSet<&Vec<bool>> = tbl.dc.iter().collect();
+	let case = |inp: Vec<bool>| expected(&inp, eqns, tbl, &rows, &dc, policy)
+		.filter(|out| out.iter().any(|o| o.is_some()))
+		.map(|out| Case{input: inp, output: out});
+	let mut rv: Vec<Case> = inputs.into_iter().filter_map(&case).collect();
+	let mut rng = Rng::new(seed);
+	let mut tested: BTreeSet<Vec<bool>> = rv.iter().map(|c| c.input.clone()).collect();
+	let mut picked = 0;
+	// give up after so many draws, for a table listing nearly every input.
+	for _ in 0..sample * 64 {
+		if picked == sample {
+			break;
+		}
+		let inp: Vec<bool> = (0..nbits).map(|_| rng.bit()).collect();
+		if rows.position(&inp).is_some() || tested.contains(&inp) {
+			continue;
+		}
+		if let Some(c) = case(inp.clone()) {
+			tested.insert(inp);
+			rv.push(c);
+			picked += 1;
+		}
+	}
+	Ok(rv)
+}
+
+// The cases as a C array, and 'call', running 'minterm' on the inputs of a
+// case and storing the outputs in an array.
+fn table(cases: &[Case], invars: &[String], outvars: &[String]) -> String {
+	let value = |b: Option<bool>| match b {
+		Some(true) => "1",
+		Some(false) => "0",
+		None => "-1",
+	};
+	let mut s = format!("/* The inputs {} followed by the outputs {} expected of \
+	                     them,\n   -1 where the table leaves an output open. */\n",
+	                    invars.join(", "), outvars.join(", "));
+	s += &format!("static const signed char minterm_cases[{}][{}] = {{\n", cases.len(),
+	              invars.len() + outvars.len());
+	for c in cases.iter() {
+		let values: Vec<&str> = c.input.iter().map(|&b| value(Some(b)))
+			.chain(c.output.iter().map(|&b| value(b))).collect();
+		s += &format!("\t{{{}}},\n", values.join(", "));
+	}
+	s += "};\n";
+	s += "#define MINTERM_CASES (sizeof minterm_cases / sizeof minterm_cases[0])\n\n";
+	let args: Vec<String> = (0..invars.len()).map(|i| format!("c[{}] != 0", i))
+		.chain((0..outvars.len()).map(|o| format!("&out[{}]", o))).collect();
+	s += "static void call(const signed char *c, bool *out) {\n";
+	s += &format!("\tminterm({});\n", args.join(", "));
+	s += "}\n";
+	s
+}
+
+// A test of 'minterm' for each output, including it by 'include', in the
+// framework; the tests are named by the outputs as the C code has them.
+pub fn tests(framework: Framework, cases: &[Case], invars: &[String],
+             outvars: &[String], include: &str) -> String {
+	let (nin, nout) = (invars.len(), outvars.len());
+	let names: Vec<String> = outvars.iter().map(|o| c_name(o)).collect();
+	let mut s = match framework {
+		Framework::GTest => format!("#include <gtest/gtest.h>\n\nextern \"C\" {{\n\
+		                             #include \"{}\"\n}}\n\n", include),
+		Framework::Catch2 => format!("#include <catch2/catch_test_macros.hpp>\n\n\
+		                              extern \"C\" {{\n#include \"{}\"\n}}\n\n", include),
+		Framework::CUnit => format!("#include <CUnit/Basic.h>\n#include \"{}\"\n\n",
+		                            include),
+	};
+	s += &table(cases, invars, outvars);
+	for (o, name) in names.iter().enumerate() {
+		let check = format!("out[{}] == (c[{}] != 0)", o, nin + o);
+		s += &match framework {
+			Framework::GTest => format!("\nTEST(Minterm, {}) {{\n", name),
+			Framework::Catch2 => format!("\nTEST_CASE(\"minterm {}\", \"[minterm]\") {{\n",
+			                             name),
+			Framework::CUnit => format!("\nstatic void test_{}(void) {{\n", name),
+		};
+		s += "\tunsigned k;\n\tfor (k = 0; k < MINTERM_CASES; k++) {\n";
+		s += "\t\tconst signed char *c = minterm_cases[k];\n";
+		s += &format!("\t\tbool out[{}];\n", nout);
+		s += &format!("\t\tif (c[{}] < 0)\n\t\t\tcontinue;\n", nin + o);
+		s += "\t\tcall(c, out);\n";
+		s += &match framework {
+			Framework::GTest => format!("\t\tEXPECT_TRUE({}) << \"case \" << k;\n", check),
+			Framework::Catch2 => format!("\t\tINFO(\"case \" << k);\n\t\tCHECK({});\n",
+			                             check),
+			Framework::CUnit => format!("\t\tCU_ASSERT({});\n", check),
+		};
+		s += "\t}\n}\n";
+	}
+	if framework == Framework::CUnit {
+		s += "\nint main(void) {\n\tCU_pSuite suite;\n\tunsigned failures;\n";
+		s += "\tif (CU_initialize_registry() != CUE_SUCCESS)\n";
+		s += "\t\treturn CU_get_error();\n";
+		s += "\tsuite = CU_add_suite(\"minterm\", NULL, NULL);\n";
+		let adds: Vec<String> = names.iter()
+			.map(|n| format!("\t    CU_add_test(suite, \"{}\", test_{}) == NULL", n, n))
+			.collect();
+		s += &format!("\tif (suite == NULL ||\n{}) {{\n", adds.join(" ||\n"));
+		s += "\t\tCU_cleanup_registry();\n\t\treturn CU_get_error();\n\t}\n";
+		s += "\tCU_basic_set_mode(CU_BRM_VERBOSE);\n\tCU_basic_run_tests();\n";
+		s += "\tfailures = CU_get_number_of_failures();\n\tCU_cleanup_registry();\n";
+		s += "\treturn failures != 0;\n}\n";
+	}
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse};
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	// Whether the brackets of each kind pair up, outside strings.
+	fn balanced(code: &str) -> bool {
+		let mut open: Vec<char> = vec![];
+		let mut quoted = false;
+		for c in code.chars() {
+			match c {
+				'"' => quoted = !quoted,
+				_ if quoted => {},
+				'(' | '{' | '[' => open.push(c),
+				')' | '}' | ']' => {
+					let want = match c { ')' => '(', '}' => '{', _ => '[' };
+					if open.pop() != Some(want) {
+						return false;
+					}
+				},
+				_ => {},
+			}
+		}
+		open.is_empty() && !quoted
+	}
+
+	#[test]
+	fn frameworks() {
+		// 11 is unlisted, 00 a don't-care.
+		let mut tbl = parse("0,1,,1,0\n1,0,,0,1\n".as_bytes(), 0, 2, 2);
+		tbl.dc.push(vec![false, false]);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names(&["a", "b"]));
+		eqns.iter_mut().for_each(Equation::simplify);
+		let cs = cases(&eqns, &tbl, 2, &DefaultOutput::Bits(vec![true, false]), 4, 1)
+			.unwrap();
+		let inputs: Vec<&Vec<bool>> = cs.iter().map(|c| &c.input).collect();
+		assert_eq!(inputs,
+		           vec![&vec![false, true], &vec![true, false], &vec![true, true]]);
+		assert_eq!(cs[2].output, vec![Some(true), Some(false)]);
+		// with dc, nothing unlisted is asserted; with panic, nothing is tested.
+		for policy in [DefaultOutput::DontCare, DefaultOutput::Panic].iter() {
+			assert_eq!(cases(&eqns, &tbl, 2, policy, 4, 1).unwrap().len(), 2);
+		}
+		let (invars, outvars) = (names(&["a", "b"]), names(&["x", "if"]));
+		for &(fw, test) in [(Framework::GTest, "TEST(Minterm, "),
+		                    (Framework::Catch2, "TEST_CASE(\"minterm "),
+		                    (Framework::CUnit, "CU_add_test(suite, ")].iter() {
+			let code = tests(fw, &cs, &invars, &outvars, "minterm.h");
+			assert_eq!(code.matches(test).count(), 2, "{}", code);
+			assert!(balanced(&code), "{}", code);
+			assert!(code.contains("\t{1, 1, 1, 0},\n"), "{}", code);
+			assert!(code.contains("\tminterm(c[0] != 0, c[1] != 0, &out[0], &out[1]);\n"));
+		}
+		let cunit = tests(Framework::CUnit, &cs, &invars, &outvars, "minterm.h");
+		assert!(cunit.contains("static void test_if_(void) {\n"), "{}", cunit);
+		assert!(Framework::parse("unity").is_err());
+	}
+}
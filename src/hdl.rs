@@ -0,0 +1,398 @@
+// Hardware description output: the minimized equations written out as
+// synthesizable source, in Verilog or VHDL.
+//
+// A Verilog 'casez' tests its arms in order and takes the first that matches,
+// with '?' matching either value of an input.  Each arm is a cube of the
+// inputs and assigns every output at once, so the per-output covers are first
+// converted into a list of joint cubes with one value per output.
+use super::{Equation, Term};
+use super::bits::BitOrder;
+use super::emit::{DefaultOutput, Shared, Syntax, Unlisted, product, substitute,
+                  sum_of_products};
+use super::exact::{Cube, cube_covers, expand_cube, term_cube};
+
+// One arm of a casez: the inputs it matches and the value of every output
+// for those inputs.
+#[derive(Debug, PartialEq)]
+pub struct Arm {
+	pub cube: Cube,
+	pub outputs: Vec<bool>,
+}
+
+// Converts the covers of the equations into casez arms.  Every distinct term
+// becomes an arm (terms shared between outputs are only listed once).  An arm
+// only sees the inputs that no earlier arm matched; when an output is 1 for
+// some of those and 0 for others, the arm is split on its first free input
+// until each part is constant.
+//
+// Inputs matched by no arm go to the default arm.  With a complete table, or
+// unlisted inputs that are 0, that gives 0 in every output.  Otherwise the
+// listed inputs whose outputs are all 0 get arms of their own, and unlisted
+// inputs are kept out of the arms unless they are don't-cares.
+pub fn casez_arms(eqns: &[Equation], nbits: usize, unlisted: &Unlisted) ->
+	Vec<Arm> {
+	let mut cubes: Vec<Cube> = vec![];
+	let mut terms: Vec<&Term> = eqns.iter().flat_map(|e| e.terms.iter()).collect();
+	if unlisted.policy != DefaultOutput::Zero {
+		if let Some(ref cover) = unlisted.listed {
+			terms.extend(cover.terms.iter());
+		}
+	}
+	for term in terms {
+		let cube = term_cube(term, nbits);
+		if !cubes.contains(&cube) {
+			cubes.push(cube);
+		}
+	}
+	let mut arms: Vec<Arm> = vec![];
+	for cube in cubes {
+		resolve(cube, eqns, unlisted, &mut arms);
+	}
+	arms
+}
+
+fn resolve(cube: Cube, eqns: &[Equation], unlisted: &Unlisted,
+           arms: &mut Vec<Arm>) {
+	let reached: Vec<Vec<bool>> = expand_cube(&cube).into_iter()
+		.filter(|m| !arms.iter().any(|a| cube_covers(&a.cube, m)))
+		.collect();
+	let strict = unlisted.branch().is_some();
+	let relevant: Vec<&Vec<bool>> = if unlisted.policy == DefaultOutput::Zero {
+		reached.iter().collect()
+	} else {
+		reached.iter().filter(|m| unlisted.is_listed(m)).collect()
+	};
+	if relevant.is_empty() { // shadowed by earlier arms, or all unlisted.
+		return;
+	}
+	let outputs: Vec<bool> = eqns.iter().map(|e| e.evaluate(relevant[0]))
+		.collect();
+	let constant = relevant.iter().all(|m| {
+		eqns.iter().zip(outputs.iter()).all(|(e, &v)| e.evaluate(m) == v)
+	});
+	// unlisted inputs must reach the default arm.
+	if constant && !(strict && relevant.len() != reached.len()) {
+		arms.push(Arm{cube, outputs});
+		return;
+	}
+	// a constant cube always exists at the level of single minterms, so
+	// there is a free input left to split on.
+	let free = cube.iter().position(|c| c.is_none()).unwrap();
+	for &v in [false, true].iter() {
+		let mut half = cube.clone();
+		half[free] = Some(v);
+		resolve(half, eqns, unlisted, arms);
+	}
+}
+
+// The output of the casez for an input pattern: the first matching arm's
+// outputs, or None for the default arm.
+#[cfg(test)]
+pub fn casez_evaluate(arms: &[Arm], inp: &[bool]) -> Option<Vec<bool>> {
+	arms.iter().find(|a| cube_covers(&a.cube, inp)).map(|a| a.outputs.clone())
+}
+
+const VERILOG_KEYWORDS: &[&str] = &[
+	"always", "and", "assign", "begin", "buf", "case", "casex", "casez", "default",
+	"else", "end", "endcase", "endfunction", "endmodule", "for", "function", "if",
+	"initial", "inout", "input", "integer", "logic", "module", "nand", "nor",
+	"not", "or", "output", "parameter", "reg", "signed", "wire", "xnor", "xor",
+];
+
+// A name as a Verilog identifier.  Names that are not plain identifiers, such
+// as "x[0]", or that are keywords use the escaped form, which is ended by
+// whitespace.
+pub fn verilog_name(name: &str) -> String {
+	let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() ||
+	                                                 c == '_') &&
+		name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+	if plain && !VERILOG_KEYWORDS.contains(&name) {
+		name.to_string()
+	} else {
+		format!("\\{} ", name)
+	}
+}
+
+fn verilog_bit(b: bool) -> &'static str { if b { "1'b1" } else { "1'b0" } }
+
+// Writes a module whose outputs are assigned by a single casez over all of
+// the inputs, concatenated so that the selector is the inputs packed in the
+// given order.
+pub fn verilog_casez(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                     order: BitOrder) -> String {
+	let inputs: Vec<String> = invars.iter().map(|n| verilog_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| verilog_name(&e.varname))
+		.collect();
+	let assign = |values: &[&str], extra: &str| -> String {
+		let stmts: Vec<String> = outputs.iter().zip(values.iter())
+			.map(|(o, v)| format!("{} = {};", o, v)).collect();
+		format!("begin {}{} end", stmts.join(" "), extra)
+	};
+	let bits = |values: &[bool]| -> Vec<&str> {
+		values.iter().map(|&v| verilog_bit(v)).collect()
+	};
+	let default = match (unlisted.listed.is_some(), &unlisted.policy) {
+		(false, _) | (true, &DefaultOutput::Zero) =>
+			assign(&bits(&vec![false; eqns.len()]), ""),
+		(true, DefaultOutput::Bits(b)) =>
+			assign(&bits(&eqns.iter().map(|e| b[e.index]).collect::<Vec<_>>()), ""),
+		(true, &DefaultOutput::DontCare) => assign(&vec!["1'bx"; eqns.len()], ""),
+		(true, &DefaultOutput::Panic) =>
+			assign(&vec!["1'bx"; eqns.len()],
+			       " $fatal(1, \"input not in the table\");"),
+	};
+
+	let mut s = String::new();
+	s += "module minterm(\n";
+	s += &format!("\tinput wire {},\n", inputs.join(", "));
+	s += &format!("\toutput reg {}\n", outputs.join(", "));
+	s += ");\n";
+	s += "\talways @* begin\n";
+	let selector: Vec<&str> = order.msb_down(&inputs).iter().map(|n| n.as_str()).collect();
+	s += &format!("\t\tcasez ({{{}}})\n", selector.join(", "));
+	for arm in casez_arms(eqns, invars.len(), unlisted) {
+		let pattern: String = order.msb_down(&arm.cube).iter().map(|c| match **c {
+			Some(true) => '1',
+			Some(false) => '0',
+			None => '?',
+		}).collect();
+		s += &format!("\t\t\t{}'b{}: {}\n", invars.len(), pattern,
+		              assign(&bits(&arm.outputs), ""));
+	}
+	s += &format!("\t\t\tdefault: {}\n", default);
+	s += "\t\tendcase\n";
+	s += "\tend\n";
+	s += "endmodule\n";
+	s
+}
+
+const VHDL: Syntax = Syntax{not: "not ", and: " and ", or: " or ", zero: "'0'",
+                           one: "'1'"};
+
+const VHDL_KEYWORDS: &[&str] = &[
+	"abs", "access", "after", "alias", "all", "and", "architecture", "array",
+	"assert", "attribute", "begin", "block", "body", "buffer", "bus", "case",
+	"component", "configuration", "constant", "disconnect", "downto", "else",
+	"elsif", "end", "entity", "exit", "file", "for", "function", "generate",
+	"generic", "group", "guarded", "if", "impure", "in", "inertial", "inout",
+	"is", "label", "library", "linkage", "literal", "loop", "map", "mod", "nand",
+	"new", "next", "nor", "not", "null", "of", "on", "open", "or", "others",
+	"out", "package", "port", "postponed", "procedure", "process", "pure",
+	"range", "record", "register", "reject", "rem", "report", "return", "rol",
+	"ror", "select", "severity", "shared", "signal", "sla", "sll", "sra", "srl",
+	"subtype", "then", "to", "transport", "type", "unaffected", "units", "until",
+	"use", "variable", "wait", "when", "while", "with", "xnor", "xor",
+];
+
+// A name as a VHDL identifier.  Basic identifiers are letters, digits and
+// single underscores, starting with a letter; keywords are not case
+// sensitive.  Anything else becomes an extended identifier, \like this\.
+pub fn vhdl_name(name: &str) -> String {
+	let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) &&
+		name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') &&
+		!name.ends_with('_') && !name.contains("__");
+	if plain && !VHDL_KEYWORDS.contains(&name.to_ascii_lowercase().as_str()) {
+		name.to_string()
+	} else {
+		format!("\\{}\\", name.replace('\\', "\\\\"))
+	}
+}
+
+// Writes an entity with a std_logic port per input and output, and an
+// architecture assigning each output its sum of products.  Unlisted inputs
+// get the default bits through a conditional assignment, or fail a
+// concurrent assertion.
+pub fn vhdl(eqns: &[Equation], invars: &[String], unlisted: &Unlisted) -> String {
+	vhdl_shared(eqns, invars, unlisted, &[])
+}
+
+// As vhdl, with the shared products as signals of their own that the
+// outputs' equations use.
+pub fn vhdl_shared(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                   shared: &[Shared]) -> String {
+	let inputs: Vec<String> = invars.iter().map(|n| vhdl_name(n)).collect();
+	let (eqns, names) = substitute(eqns, shared, invars);
+	let names: Vec<String> = names.iter().map(|n| vhdl_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| vhdl_name(&e.varname))
+		.collect();
+	let mut s = String::new();
+	s += "library ieee;\n";
+	s += "use ieee.std_logic_1164.all;\n";
+	s += "\n";
+	s += "entity minterm is\n";
+	s += "\tport (\n";
+	s += &format!("\t\t{} : in std_logic;\n", inputs.join(", "));
+	s += &format!("\t\t{} : out std_logic\n", outputs.join(", "));
+	s += "\t);\n";
+	s += "end entity minterm;\n";
+	s += "\n";
+	s += "architecture rtl of minterm is\n";
+	if !shared.is_empty() {
+		s += &format!("\tsignal {} : std_logic;\n", names[inputs.len()..].join(", "));
+	}
+	s += "begin\n";
+	for (sh, name) in shared.iter().zip(names[inputs.len()..].iter()) {
+		s += &format!("\t{} <= {};\n", name, product(&sh.term, &inputs, &VHDL));
+	}
+	let listed = unlisted.branch().map(|c| sum_of_products(c, &inputs, &VHDL));
+	if let (Some(ref cond), &DefaultOutput::Panic) = (&listed, &unlisted.policy) {
+		s += &format!("\tassert ({}) = '1' report \"input not in the table\" \
+		               severity failure;\n", cond);
+	}
+	for (eqn, name) in eqns.iter().zip(outputs.iter()) {
+		let sop = sum_of_products(eqn, &names, &VHDL);
+		match (&listed, &unlisted.policy) {
+			(Some(cond), DefaultOutput::Bits(b)) => {
+				let other = if b[eqn.index] { VHDL.one } else { VHDL.zero };
+				s += &format!("\t{} <= {} when ({}) = '1' else {};\n", name, sop,
+				              cond, other);
+			},
+			_ => s += &format!("\t{} <= {};\n", name, sop),
+		}
+	}
+	s += "end architecture rtl;\n";
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{Equation, Truth, gray_code};
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|n| n.to_string()).collect()
+	}
+
+	fn eqn(s: &str) -> Equation { Equation::parse(s, &names()).unwrap() }
+
+	fn complete() -> Unlisted {
+		Unlisted{policy: DefaultOutput::Zero, listed: None}
+	}
+
+	// Only the rows of 'listed' are in the table.
+	fn sparse(policy: DefaultOutput, listed: &str) -> Unlisted {
+		let cover = Equation::parse(listed, &names()).unwrap();
+		let rows: Vec<Vec<bool>> = gray_code(3).into_iter()
+			.filter(|i| cover.evaluate(i)).collect();
+		let outputs = vec![vec![]; rows.len()];
+		Unlisted::new(policy, &Truth::new(rows, outputs), 3, &names())
+	}
+
+	// every input must get the equations' value from the casez, with the
+	// default arm giving 0.
+	fn check(eqns: &[Equation], arms: &[Arm]) {
+		for inp in gray_code(3) {
+			let want: Vec<bool> = eqns.iter().map(|e| e.evaluate(&inp)).collect();
+			let got = casez_evaluate(arms, &inp).unwrap_or(vec![false; eqns.len()]);
+			assert_eq!(got, want);
+		}
+	}
+
+	#[test]
+	fn shared_terms() {
+		let eqns = vec![eqn("x = a'b'c + ab'c' + bc'"),
+		                eqn("y = a'b'c' + ab' + bc'")];
+		let arms = casez_arms(&eqns, 3, &complete());
+		check(&eqns, &arms);
+		// bc' is common to both outputs and listed once.
+		let bc = arms.iter().filter(|a| a.cube == vec![None, Some(true), Some(false)])
+			.count();
+		assert_eq!(bc, 1);
+	}
+
+	#[test]
+	fn shared_signals() {
+		let eqns = vec![eqn("x = a'b'c + ab'c' + bc'"),
+		                eqn("y = a'b'c' + ab' + bc'")];
+		let shared = ::emit::shared_products(&eqns, "t", 2);
+		let src = vhdl_shared(&eqns, &names(), &complete(), &shared);
+		assert!(src.contains("architecture rtl of minterm is\n\tsignal t0 : std_logic;\n\
+		                      begin\n\tt0 <= b and not c;\n"), "{}", src);
+		assert!(src.contains("\tx <= (not a and not b and c) or (a and not b and not c) \
+		                      or t0;\n"), "{}", src);
+		assert!(src.contains("\ty <= (not a and not b and not c) or (a and not b) or t0;\n"),
+		        "{}", src);
+	}
+
+	#[test]
+	fn overlapping_terms() {
+		// 'a' and 'b' overlap at ab, where x must stay 1 and y must be 1 too.
+		let eqns = vec![eqn("x = a"), eqn("y = b + a'c")];
+		let arms = casez_arms(&eqns, 3, &complete());
+		check(&eqns, &arms);
+		assert_eq!(arms[0], Arm{cube: vec![Some(true), Some(false), None],
+		                        outputs: vec![true, false]});
+		assert_eq!(arms[1], Arm{cube: vec![Some(true), Some(true), None],
+		                        outputs: vec![true, true]});
+	}
+
+	#[test]
+	fn casez_defaults() {
+		// only a'-rows are listed; x = b on them.
+		let eqns = vec![eqn("x = a'b")];
+		for policy in [DefaultOutput::Zero, DefaultOutput::Panic] {
+			let unlisted = sparse(policy.clone(), "x = a'");
+			let arms = casez_arms(&eqns, 3, &unlisted);
+			for inp in gray_code(3) {
+				let got = casez_evaluate(&arms, &inp);
+				if policy == DefaultOutput::Panic && inp[0] {
+					assert_eq!(got, None);
+				} else {
+					let want = vec![eqns[0].evaluate(&inp)];
+					assert_eq!(got.unwrap_or(vec![false]), want);
+				}
+			}
+		}
+		let src = verilog_casez(&eqns, &names(), &sparse(DefaultOutput::Zero, "x = a'"),
+		                        BitOrder::Msb);
+		assert!(src.contains("\t\t\tdefault: begin x = 1'b0; end\n"));
+		assert!(!src.contains("3'b00?"));
+		let src = verilog_casez(&eqns, &names(), &sparse(DefaultOutput::Panic, "x = a'"),
+		                        BitOrder::Msb);
+		assert!(src.contains("\t\t\t3'b01?: begin x = 1'b1; end\n\
+		                      \t\t\t3'b0??: begin x = 1'b0; end\n"));
+		assert!(src.contains("\t\t\tdefault: begin x = 1'bx; \
+		                      $fatal(1, \"input not in the table\"); end\n"));
+		let src = verilog_casez(&eqns, &names(),
+		                        &sparse(DefaultOutput::Bits(vec![true]), "x = a'"),
+		                        BitOrder::Msb);
+		assert!(src.contains("\t\t\tdefault: begin x = 1'b1; end\n"));
+	}
+
+	#[test]
+	fn vhdl_defaults() {
+		let eqns = vec![eqn("x = a'b")];
+		let src = vhdl(&eqns, &names(), &sparse(DefaultOutput::Zero, "x = a'"));
+		assert!(src.contains("\tx <= not a and b;\n"));
+		assert!(!src.contains("assert"));
+		let src = vhdl(&eqns, &names(), &sparse(DefaultOutput::Panic, "x = a'"));
+		assert!(src.contains("\tassert (not a) = '1' report \"input not in the table\" \
+		                      severity failure;\n"));
+		assert!(src.contains("\tx <= not a and b;\n"));
+		let src = vhdl(&eqns, &names(),
+		               &sparse(DefaultOutput::Bits(vec![true]), "x = a'"));
+		assert!(src.contains("\tx <= not a and b when (not a) = '1' else '1';\n"));
+	}
+
+	#[test]
+	fn escaped_names() {
+		assert_eq!(verilog_name("enable"), "enable");
+		assert_eq!(verilog_name("x[0]"), "\\x[0] ");
+		assert_eq!(verilog_name("output"), "\\output ");
+		assert_eq!(verilog_name("2x"), "\\2x ");
+		assert_eq!(vhdl_name("enable"), "enable");
+		assert_eq!(vhdl_name("x[0]"), "\\x[0]\\");
+		assert_eq!(vhdl_name("a__b"), "\\a__b\\");
+		assert_eq!(vhdl_name("a\\b"), "\\a\\\\b\\");
+	}
+
+	#[test]
+	fn vhdl_keywords() {
+		let eqns = vec![eqn("out = a'b"), eqn("Signal = 0"), eqn("z = 1")];
+		let src = vhdl(&eqns, &names(), &complete());
+		assert!(src.contains("\t\t\\out\\, \\Signal\\, z : out std_logic\n"));
+		assert!(src.contains("\t\\out\\ <= not a and b;\n"));
+		assert!(src.contains("\t\\Signal\\ <= '0';\n"));
+		assert!(src.contains("\tz <= '1';\n"));
+	}
+}
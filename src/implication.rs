@@ -0,0 +1,216 @@
+// Implications between outputs: "if x is 1 then y is 1", or any polarity of
+// either, holding on every row of the table.  'minterm implications' lists
+// them, and --use-implications has the code compute y from x where one
+// holds: y's rows on which x settles it become don't-cares for y, as
+// --observe makes them, and y's cover is then combined with x: y = x + cover
+// where x settles y at 1, and y the cover's terms each and-ed with x' where it
+// settles it at 0, x' standing for x when the implication is from x'.
+//
+// Only the rows the table specifies count.  A don't-care, or an input the
+// table leaves open, neither makes nor breaks an implication, since the code
+// may give it anything; and one that never applies, its x never taking the
+// value, or that says nothing, its y never taking the other, isn't one.
+use std::cmp::Reverse;
+use super::{Equation, Term, Truth};
+use super::expr::Expr;
+use super::notation::Notation;
+use super::observe::Observe;
+
+// On every row where output 'from' is 'from_value', output 'to' is
+// 'to_value'.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Implication {
+	pub from: usize,
+	pub from_value: bool,
+	pub to: usize,
+	pub to_value: bool,
+}
+
+impl Implication {
+	// The same implication the other way round: y' -> x' for x -> y.
+	pub fn contrapositive(&self) -> Self {
+		Implication{from: self.to, from_value: !self.to_value, to: self.from,
+		            to_value: !self.from_value}
+	}
+
+	// "x -> y'", in the notation.
+	pub fn describe(&self, outvars: &[String], n: &Notation) -> String {
+		format!("{} -> {}", n.literal(&outvars[self.from], self.from_value),
+		        n.literal(&outvars[self.to], self.to_value))
+	}
+
+	// The rows of the table the implication settles the output 'to' on, and of
+	// those the ones it is 1 on, which then no longer need covering.
+	pub fn rows(&self, tbl: &Truth) -> (usize, usize) {
+		let settled = tbl.table.iter().filter(|e| e.output[self.from] == self.from_value);
+		let ones = settled.clone().filter(|e| e.output[self.to]).count();
+		(settled.count(), ones)
+	}
+
+	// The --observe declaration making those rows don't-cares for 'to': it is
+	// used only while 'from' isn't 'from_value'.
+	pub fn unsettled(&self, nbits: usize) -> Observe {
+		let from = Expr::Var(nbits + self.from);
+		Observe{output: self.to,
+		        when: if self.from_value { Expr::Not(Box::new(from)) } else { from }}
+	}
+}
+
+// Every implication between two outputs that holds on the table's rows, and
+// applies on some of them and says something on some: one of each pair of
+// contrapositives, that from the earlier output.  Outputs that are don't-cares
+// on some row are left out, as the rows can't settle them.
+pub fn mine(tbl: &Truth) -> Vec<Implication> {
+	let nout = tbl.table.first().map_or(0, |e| e.output.len());
+	let given: Vec<usize> = (0..nout).filter(|&o| tbl.table.iter().all(|e| e.cares(o)))
+		.collect();
+	let mut rv = vec![];
+	for &from in given.iter() {
+		for &to in given.iter().filter(|&&to| to > from) {
+			for &(from_value, to_value) in [(true, true), (true, false), (false, true),
+			                                (false, false)].iter() {
+				let i = Implication{from, from_value, to, to_value};
+				let applies = tbl.table.iter().any(|e| e.output[from] == from_value);
+				let says = tbl.table.iter().any(|e| e.output[to] != to_value);
+				let holds = tbl.table.iter()
+					.all(|e| e.output[from] != from_value || e.output[to] == to_value);
+				if applies && says && holds {
+					rv.push(i);
+				}
+			}
+		}
+	}
+	rv
+}
+
+// Whether following the outputs each is computed from leads from 'from' to
+// 'to'.
+fn uses(used: &[Option<Implication>], from: usize, to: usize) -> bool {
+	let mut o = from;
+	loop {
+		if o == to {
+			return true;
+		}
+		match used[o] {
+			Some(i) => o = i.from,
+			None => return false,
+		}
+	}
+}
+
+// For each of the table's 'nout' outputs, the implication to compute it
+// from: of those the mined ones and their contrapositives give, the one
+// taking the most 1s out of its cover, then settling the most rows, but
+// never from an output computed from it.
+pub fn choose(mined: &[Implication], tbl: &Truth, nout: usize) ->
+	Vec<Option<Implication>> {
+	let mut all: Vec<Implication> = mined.iter()
+		.flat_map(|i| vec![*i, i.contrapositive()]).collect();
+	all.sort_by_key(|i| {
+		let (settled, ones) = i.rows(tbl);
+		(Reverse(ones), Reverse(settled), i.to, i.from)
+	});
+	let mut rv: Vec<Option<Implication>> = vec![None; nout];
+	for i in all {
+		if rv[i.to].is_none() && !uses(&rv, i.from, i.to) {
+			rv[i.to] = Some(i);
+		}
+	}
+	rv
+}
+
+// The equations with each covered with the rows 'used' settles as don't-cares
+// combined with the output it is computed from, the output a variable after
+// the inputs; reordered so that each comes after the output it uses.
+pub fn compose(eqns: &[Equation], used: &[Option<Implication>], nbits: usize) ->
+	Vec<Equation> {
+	let rewritten: Vec<Equation> = eqns.iter().map(|eqn| {
+		let i = match used[eqn.index] {
+			Some(i) => i,
+			None => return eqn.clone(),
+		};
+		let from = eqns.iter().find(|e| e.index == i.from).expect("a minimized output");
+		let mut names = match eqn.terms.iter().chain(from.terms.iter()).next() {
+			Some(t) => t.names.clone(),
+			None => (0..nbits).map(|b| format!("i{}", b)).collect(),
+		};
+		names.truncate(nbits);
+		names.push(from.varname.clone());
+		let lit = (nbits, i.from_value == i.to_value);
+		let mut rv = eqn.clone();
+		rv.terms = eqn.terms.iter().map(|t| {
+			let mut bits = t.bits.clone();
+			if !i.to_value {
+				bits.push(lit);
+			}
+			Term{bits, names: names.clone()}
+		}).collect();
+		if i.to_value {
+			rv.terms.insert(0, Term{bits: vec![lit], names});
+		}
+		rv
+	}).collect();
+	let mut out = vec![false; eqns.len()];
+	let position = |o: usize| eqns.iter().position(|e| e.index == o);
+	let mut rv: Vec<Equation> = vec![];
+	while rv.len() < eqns.len() {
+		let e = (0..eqns.len()).find(|&e| {
+			!out[e] && used[eqns[e].index].and_then(|i| position(i.from))
+				.is_none_or(|p| out[p])
+		}).expect("no output uses one using it");
+		out[e] = true;
+		rv.push(rewritten[e].clone());
+	}
+	rv
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, exact, parse};
+	use super::super::observe::relax;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn mined_and_used() {
+		// x = abc + a'b'c', and y is 1 wherever x is and on ab'c too: x -> y
+		// holds, y -> x doesn't.
+		let csv = "0,0,0,,1,1\n0,0,1,,0,0\n0,1,0,,0,0\n0,1,1,,0,0\n1,0,0,,0,0\n\
+		           1,0,1,,0,1\n1,1,0,,0,0\n1,1,1,,1,1\n";
+		let tbl = parse(csv.as_bytes(), 0, 3, 2);
+		let (invars, outvars) = (names(&["a", "b", "c"]), names(&["x", "y"]));
+		let mined = mine(&tbl);
+		let n = Notation::classic();
+		let described: Vec<String> = mined.iter().map(|i| i.describe(&outvars, &n))
+			.collect();
+		assert_eq!(described, vec!["x -> y"]);
+		assert_eq!(mined[0].contrapositive().describe(&outvars, &n), "y' -> x'");
+		let used = choose(&mined, &tbl, 2);
+		assert_eq!(used, vec![None, Some(mined[0])]);
+		// y minimized with the rows x settles don't-cares, then given x.
+		let mut eqns = equations(&tbl, vec!["x", "y"], invars.clone());
+		let mut plain = eqns[1].clone();
+		plain.terms = exact::minimize(&plain, 3, &mut exact::Budget::unlimited()).terms;
+		assert_eq!(plain.to_string(), "y = ac + a'b'c' + ;");
+		assert_eq!(relax(&mut eqns[1], &tbl, &[mined[0].unsettled(3)]), 2);
+		for eqn in eqns.iter_mut() {
+			eqn.terms = exact::minimize(eqn, 3, &mut exact::Budget::unlimited()).terms;
+		}
+		let composed = compose(&eqns, &used, 3);
+		let strs: Vec<String> = composed.iter().map(|e| e.to_string()).collect();
+		assert_eq!(strs, vec!["x = a'b'c' + abc + ;", "y = x + ac + ;"]);
+		// x -> y' combines as a product, and the contrapositive the other way.
+		let neg = Implication{from: 1, from_value: false, to: 0, to_value: false};
+		let composed = compose(&eqns, &[Some(neg), None], 3);
+		assert_eq!(composed[1].to_string(), "x = a'b'c'y + abcy + ;");
+		// outputs that are always equal aren't computed from each other both ways.
+		let same = parse("0,,0,0\n1,,1,1\n".as_bytes(), 0, 1, 2);
+		let mined = mine(&same);
+		assert_eq!(mined.len(), 2);
+		let used = choose(&mined, &same, 2);
+		assert_eq!(used.iter().filter(|u| u.is_some()).count(), 1);
+	}
+}
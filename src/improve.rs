@@ -0,0 +1,222 @@
+// --improve: local search on the greedy simplifier's cover, for as long as it
+// is given.  Each pass perturbs the cover and repairs it, as espresso does:
+// REDUCE shrinks some terms to the minterms no other term covers, EXPAND
+// grows each term a literal at a time while it stays within the ON- and
+// DC-sets, and absorption and IRREDUNDANT drop the terms that another
+// contains or that the others cover together.  The terms and literals are
+// taken in an order drawn from --seed, and the cheapest cover any pass ends
+// with, fewest terms and then fewest literals, is kept once it is checked
+// against the table.
+use std::time::Instant;
+use super::{Equation, Term};
+use super::bits::{BitOrder, bit_pack};
+use super::coverage::BitSet;
+use super::exact::{Cube, cube_contains, cube_to_term, expand_cube, term_cube};
+use super::rng::Rng;
+
+// The most inputs --improve works on: it keeps a bit per input pattern.
+pub const MAX_IMPROVE_BITS: usize = 20;
+
+// A cover's cost, as the exact search weighs it: terms, then literals.
+pub type Cost = (usize, usize);
+
+// What the passes made of an equation's cover.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Improvement {
+	pub before: Cost,
+	pub after: Cost,
+	pub passes: usize,
+}
+
+fn cost(cubes: &[Cube]) -> Cost {
+	(cubes.len(), cubes.iter().map(|c| c.iter().filter(|b| b.is_some()).count()).sum())
+}
+
+// The indices of the input patterns of the cube.
+fn patterns(cube: &Cube) -> Vec<usize> {
+	expand_cube(cube).iter().map(|m| bit_pack(m, BitOrder::Msb) as usize).collect()
+}
+
+fn pattern_set(terms: &[Term], nbits: usize) -> BitSet {
+	let mut rv = BitSet::new(1 << nbits);
+	for t in terms.iter() {
+		for m in patterns(&term_cube(t, nbits)) {
+			rv.insert(m);
+		}
+	}
+	rv
+}
+
+// For each ON-set pattern, how many of the cubes cover it.
+fn counts(cubes: &[Cube], on: &BitSet, npatterns: usize) -> Vec<usize> {
+	let mut rv = vec![0; npatterns];
+	for c in cubes.iter() {
+		for m in patterns(c).into_iter().filter(|&m| on.contains(m)) {
+			rv[m] += 1;
+		}
+	}
+	rv
+}
+
+// The smallest cube holding every one of the patterns, which isn't empty.
+fn supercube(patterns: &[usize], nbits: usize) -> Cube {
+	(0..nbits).map(|k| {
+		let bit = |m: usize| m >> (nbits - 1 - k) & 1 == 1;
+		let first = bit(patterns[0]);
+		if patterns.iter().all(|&m| bit(m) == first) { Some(first) } else { None }
+	}).collect()
+}
+
+// Shrinks about half of the cubes, in a random order, to the smallest cube
+// holding the ON-set patterns only they cover, dropping those that have none.
+fn reduce(cubes: &mut Vec<Cube>, on: &BitSet, nbits: usize, rng: &mut Rng) {
+	let mut count = counts(cubes, on, 1 << nbits);
+	let mut order: Vec<usize> = (0..cubes.len()).collect();
+	rng.shuffle(&mut order);
+	let mut dropped = vec![false; cubes.len()];
+	for i in order {
+		if !rng.bit() {
+			continue;
+		}
+		let mine: Vec<usize> = patterns(&cubes[i]).into_iter().filter(|&m| on.contains(m))
+			.collect();
+		let unique: Vec<usize> = mine.iter().cloned().filter(|&m| count[m] == 1).collect();
+		let reduced = if unique.is_empty() { None } else { Some(supercube(&unique, nbits)) };
+		let mut inside = reduced.as_ref().map_or(vec![], patterns);
+		inside.sort();
+		for &m in mine.iter().filter(|m| inside.binary_search(m).is_err()) {
+			count[m] -= 1;
+		}
+		match reduced {
+			Some(r) => cubes[i] = r,
+			None => dropped[i] = true,
+		}
+	}
+	let mut k = 0;
+	cubes.retain(|_| { k += 1; !dropped[k - 1] });
+}
+
+// Takes out the literals of each cube, in a random order, while the cube
+// stays within the allowed patterns.
+fn expand(cubes: &mut [Cube], allowed: &BitSet, rng: &mut Rng) {
+	for cube in cubes.iter_mut() {
+		let mut lits: Vec<usize> = (0..cube.len()).filter(|&k| cube[k].is_some()).collect();
+		rng.shuffle(&mut lits);
+		for k in lits {
+			// the half the cube would grow by.
+			let mut other = cube.clone();
+			other[k] = cube[k].map(|b| !b);
+			if patterns(&other).iter().all(|&m| allowed.contains(m)) {
+				cube[k] = None;
+			}
+		}
+	}
+}
+
+// Drops the cubes another contains, keeping the first of equal ones.
+fn absorb(cubes: &mut Vec<Cube>) {
+	let mut kept: Vec<Cube> = vec![];
+	for (i, c) in cubes.iter().enumerate() {
+		let absorbed = cubes.iter().enumerate().any(|(j, d)| {
+			j != i && cube_contains(d, c) && (d != c || j < i)
+		});
+		if !absorbed {
+			kept.push(c.clone());
+		}
+	}
+	*cubes = kept;
+}
+
+// Drops, in a random order, the cubes whose ON-set patterns the others all
+// cover.
+fn irredundant(cubes: &mut Vec<Cube>, on: &BitSet, nbits: usize, rng: &mut Rng) {
+	let mut count = counts(cubes, on, 1 << nbits);
+	let mut order: Vec<usize> = (0..cubes.len()).collect();
+	rng.shuffle(&mut order);
+	let mut dropped = vec![false; cubes.len()];
+	for i in order {
+		let mine: Vec<usize> = patterns(&cubes[i]).into_iter().filter(|&m| on.contains(m))
+			.collect();
+		if mine.iter().all(|&m| count[m] > 1) {
+			for &m in mine.iter() {
+				count[m] -= 1;
+			}
+			dropped[i] = true;
+		}
+	}
+	let mut k = 0;
+	cubes.retain(|_| { k += 1; !dropped[k - 1] });
+}
+
+// Whether the cubes cover every ON-set pattern and only allowed ones.
+fn verify(cubes: &[Cube], on: &BitSet, allowed: &BitSet, nbits: usize) -> bool {
+	let mut covered = BitSet::new(1 << nbits);
+	for c in cubes.iter() {
+		for m in patterns(c) {
+			covered.insert(m);
+		}
+	}
+	on.is_subset(&covered) && covered.is_subset(allowed)
+}
+
+// Improves 'eqn', a cover of 'start', the equation before minimizing it,
+// over 'nbits' inputs, with passes until the deadline or 'max_passes' of
+// them.  None when there are more than MAX_IMPROVE_BITS inputs.
+pub fn improve(eqn: &mut Equation, start: &Equation, nbits: usize, deadline: Instant,
+               max_passes: usize, rng: &mut Rng) -> Option<Improvement> {
+	if nbits > MAX_IMPROVE_BITS {
+		return None;
+	}
+	let mut cubes: Vec<Cube> = eqn.terms.iter().map(|t| term_cube(t, nbits)).collect();
+	let before = cost(&cubes);
+	let names = match eqn.terms.first() {
+		Some(t) => t.names.clone(),
+		None => return Some(Improvement{before, after: before, passes: 0}),
+	};
+	let on = pattern_set(&start.terms, nbits);
+	let mut allowed = pattern_set(&start.dc, nbits);
+	allowed.union_with(&on);
+	let mut best = (before, cubes.clone());
+	let mut passes = 0;
+	while passes < max_passes && Instant::now() < deadline {
+		reduce(&mut cubes, &on, nbits, rng);
+		expand(&mut cubes, &allowed, rng);
+		absorb(&mut cubes);
+		irredundant(&mut cubes, &on, nbits, rng);
+		passes += 1;
+		let c = cost(&cubes);
+		if c < best.0 && verify(&cubes, &on, &allowed, nbits) {
+			best = (c, cubes.clone());
+		}
+	}
+	eqn.terms = best.1.iter().map(|c| cube_to_term(c, &names)).collect();
+	Some(Improvement{before, after: best.0, passes})
+}
+
+#[cfg(test)]
+mod test {
+	use std::time::Duration;
+	use super::*;
+	use super::super::{equations, testutil};
+
+	#[test]
+	fn fewer_literals_than_greedy() {
+		// the greedy cover of this one has 8 terms and 29 literals; the
+		// minimum, 6 and 22.
+		let f = testutil::dense(5, 1, 57);
+		let start = equations(&f.truth, vec!["x"], f.invars.clone()).remove(0);
+		let mut eqn = start.clone();
+		eqn.simplify();
+		let deadline = Instant::now() + Duration::from_secs(60);
+		let imp = improve(&mut eqn, &start, 5, deadline, 50, &mut Rng::new(1)).unwrap();
+		assert_eq!(imp.before, (8, 29));
+		assert!(imp.after.1 < imp.before.1, "{:?}", imp);
+		assert_eq!(imp.passes, 50);
+		let cubes: Vec<Cube> = eqn.terms.iter().map(|t| term_cube(t, 5)).collect();
+		assert_eq!(cost(&cubes), imp.after);
+		let on = pattern_set(&start.terms, 5);
+		let mut allowed = pattern_set(&start.dc, 5);
+		allowed.union_with(&on);
+		assert!(verify(&cubes, &on, &allowed, 5), "{}", eqn);
+	}
+}
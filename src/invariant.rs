@@ -0,0 +1,125 @@
+// Invariants of a table: expressions over its inputs and outputs that must
+// hold on every row, such as "x | y" (x and y are never both 0) or "GL -> x".
+// A row with outputs that are don't-cares holds only if the invariant holds
+// for every value they could take.
+use super::{RowSource, Table, Truth, sources_note};
+use super::bits::{BitOrder, bit_unpack};
+use super::expr::{Expr, ParseError};
+
+// A row to check: an input pattern, its outputs, None for those that are
+// don't-cares, and where it was read from.
+pub type Row = (Vec<bool>, Vec<Option<bool>>, Vec<RowSource>);
+
+// The rows of the table: its listed rows and its don't-care inputs.
+pub fn rows(tbl: &Truth) -> Vec<Row> {
+	let nout = tbl.outputs();
+	tbl.table.iter().map(|e| (e.input.clone(), e.cells(), e.sources.clone()))
+		.chain(tbl.dc.iter().map(|inp| (inp.clone(), vec![None; nout], vec![])))
+		.collect()
+}
+
+// Parses an invariant; its variables are the inputs followed by the outputs.
+pub fn parse(s: &str, invars: &[String], outvars: &[String]) -> Result<Expr, ParseError> {
+	let mut names = invars.to_vec();
+	names.extend(outvars.iter().cloned());
+	Expr::parse(s, &names)
+}
+
+// Whether the invariant holds on the row.  For don't-care outputs, every
+// value of those it uses is tried.
+pub fn holds(e: &Expr, row: &Row, nout: usize) -> bool {
+	let nin = row.0.len();
+	let mut values = row.0.clone();
+	values.extend((0..nout).map(|o| row.1.get(o).and_then(|&b| b).unwrap_or(false)));
+	let open: Vec<usize> = e.variables().into_iter()
+		.filter(|&v| v >= nin && row.1.get(v - nin).is_none_or(|b| b.is_none()))
+		.collect();
+	(0..1u64 << open.len()).all(|i| {
+		for (&v, b) in open.iter().zip(bit_unpack(i, open.len(), BitOrder::Lsb)) {
+			values[v] = b;
+		}
+		e.evaluate(&values)
+	})
+}
+
+// The rows on which the invariant fails.
+pub fn violations<'a>(e: &Expr, rows: &'a [Row], nout: usize) -> Vec<&'a Row> {
+	rows.iter().filter(|r| !holds(e, r, nout)).collect()
+}
+
+// Describes the failing rows, the first few of them in full.
+pub fn report(s: &str, bad: &[&Row], invars: &[String], outvars: &[String]) -> String {
+	let mut msg = format!("Invariant fails on {} row(s): {}", bad.len(), s);
+	for &(inp, outs, sources) in bad.iter().take(5) {
+		let inputs: Vec<String> = inp.iter().zip(invars.iter())
+			.map(|(&b, n)| format!("{}={}", n, b as u8)).collect();
+		let outputs = if outs.iter().all(|b| b.is_none()) && !outs.is_empty() {
+			"outputs are don't-cares, and some values break it".to_string()
+		} else {
+			let vals: Vec<String> = outs.iter().zip(outvars.iter()).map(|(&b, n)| match b {
+				Some(b) => format!("{}={}", n, b as u8),
+				None => format!("{}=x", n),
+			}).collect();
+			vals.join(" ")
+		};
+		msg += &format!("\n  {}{}: {}", inputs.join(" "), sources_note(sources), outputs);
+	}
+	if bad.len() > 5 {
+		msg += &format!("\n  ... and {} more", bad.len() - 5);
+	}
+	msg
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse as parse_table;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	fn check(s: &str, tbl: &Truth) -> Result<(), String> {
+		let (invars, outvars) = (names(&["a", "b", "c"]), names(&["x", "y"]));
+		let e = parse(s, &invars, &outvars).unwrap();
+		let rows = rows(tbl);
+		let bad = violations(&e, &rows, 2);
+		if bad.is_empty() { Ok(()) } else { Err(report(s, &bad, &invars, &outvars)) }
+	}
+
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n";
+
+	#[test]
+	fn passing() {
+		let tbl = parse_table(SMALL.as_bytes(), 0, 3, 2);
+		assert_eq!(check("c' -> y", &tbl), Ok(()));
+		assert_eq!(check("b c' -> x y", &tbl), Ok(()));
+	}
+
+	#[test]
+	fn failing() {
+		let tbl = parse_table(SMALL.as_bytes(), 0, 3, 2);
+		assert_eq!(check("x | y", &tbl),
+		           Err("Invariant fails on 1 row(s): x | y\n  a=0 b=1 c=1 (line 4): \
+		                x=0 y=0".to_string()));
+		assert!(check("a -> x", &tbl).unwrap_err()
+			.contains("a=1 b=0 c=1 (line 6): x=0 y=1"));
+		assert!(parse("a -> z", &names(&["a"]), &names(&["x"])).is_err());
+	}
+
+	#[test]
+	fn dont_cares() {
+		let mut tbl = parse_table(SMALL.as_bytes(), 0, 3, 2);
+		tbl.dc.push(vec![true, true, true]);
+		// x and y could both be 0 on 111, or not.
+		assert_eq!(check("x | y", &tbl).unwrap_err().lines().last(),
+		           Some("  a=1 b=1 c=1: outputs are don't-cares, and some values break \
+		                 it"));
+		assert!(check("a -> x | y", &tbl).unwrap_err()
+			.contains("a=1 b=1 c=1: outputs are don't-cares"));
+		// invariants that no values of the outputs break hold there.
+		assert_eq!(check("a b c -> a", &tbl), Ok(()));
+		assert_eq!(check("x y' + x' y + x y + x' y' ", &tbl), Ok(()));
+	}
+}
@@ -0,0 +1,4033 @@
+// Minimize the number of 'if' statements to map between sets of variables.
+//
+// We are given a truth table with 'b' input bits and 'o' output bits.  The
+// task of the program is to minimize the number of if statements required to
+// generate that mapping.
+// Consider this b=3 o=2 system:
+//    000 => 01
+//    001 => 10
+//    010 => 11
+//    011 => 00
+//    100 => 11
+//    101 => 01
+//    110 => 11
+//    111 => 00
+// Let us call the inputs 'a', 'b', and 'c', and the output 'x' and 'y'.
+// Hereafter "a" corresponds to a==1, and "a'" corresponds to a==0.
+// In this case, the program should generate something like:
+//    if(a'b'):
+//      if(c): x = 1
+//      else if(c'): y = 1
+//    if(ab'):
+//      y = 1
+//      if(c'): x = 1
+//    if(bc'): x = y = 1
+// The naive minterms for each this system's outputs would be:
+//    x = a'b'c + a'bc' + ab'c' + abc'
+//    y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
+// Consider a reordering of terms:
+//    x = a'b'c + a'bc' + ab'c' + abc'
+//    y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
+//    =>
+//    x = a'b'c         + ab'c' + abc' + a'bc'
+//    y = a'b'c' + ab'c + ab'c' + abc' + a'bc'
+// Now it is clear that
+//    1) both of the 3 final terms are identical for x and y
+//    2) both "abc'" and "a'bc'" appear.  The "bc'" is identical here, and
+//       because both a and a' appear, we can merge these two, dropping "a".
+// Thus:
+//    x = a'b'c         + ab'c' + bc'
+//    y = a'b'c' + ab'c + ab'c' + bc'
+// Now we have a choice.  Note that y includes the two terms "ab'c" and
+// "ab'c'"; by argument (2) above, we could merge those two terms.  However, it
+// may be more profitable to keep the common subexpression "ab'" so that we can
+// merge the solutions for "x" and "y".
+extern crate csv;
+extern crate flate2;
+#[cfg(feature = "pyo3")]
+extern crate pyo3;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+// pyo3's and wasm_bindgen's macros name ::core, which this edition doesn't have
+// in scope.
+#[cfg(any(feature = "pyo3", feature = "wasm"))]
+extern crate core;
+extern crate serde_json;
+#[cfg(feature = "zstd")]
+extern crate zstd;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use bits::{BitOrder, bit_pack, bit_unpack};
+pub mod anf;
+pub mod bdd;
+pub mod bits;
+pub mod breadth;
+pub mod chain;
+pub mod complement;
+pub mod complete;
+pub mod completion;
+pub mod compressed;
+pub mod compose;
+pub mod counterexample;
+pub mod cover;
+pub mod coverage;
+pub mod diff;
+pub mod embed;
+pub mod emit;
+pub mod enumerate;
+pub mod estimate;
+pub mod exact;
+pub mod explain;
+pub mod expr;
+pub mod factor;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixpoint;
+pub mod formats;
+pub mod frequency;
+pub mod given;
+pub mod harness;
+pub mod hdl;
+pub mod implication;
+pub mod improve;
+pub mod invariant;
+pub mod lut;
+pub mod merge;
+pub mod notation;
+pub mod observe;
+pub mod pregroup;
+pub mod prelude;
+pub mod onehot;
+pub mod packed;
+pub mod progress;
+#[cfg(feature = "pyo3")]
+pub mod pybind;
+pub mod python;
+pub mod rng;
+pub mod schema;
+pub mod sets;
+pub mod share;
+pub mod switch;
+pub mod symbolic;
+// Public only for the integration tests and benchmarks; not part of the API.
+#[doc(hidden)]
+pub mod testutil;
+pub mod trace;
+pub mod vectors;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Where a row of a table was read from: the line, counting from 1, and the
+// file, which is empty until the caller names it (parse only sees the data).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RowSource {
+	pub file: String,
+	pub line: usize,
+}
+
+impl fmt::Display for RowSource {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.file.as_str() {
+			"" => write!(f, "line {}", self.line),
+			file => write!(f, "{} line {}", file, self.line),
+		}
+	}
+}
+
+// Lists the sources as "a.csv line 3, b.csv line 5".
+pub fn describe_sources(sources: &[RowSource]) -> String {
+	let strs: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+	strs.join(", ")
+}
+
+// " (a.csv line 3)", to follow the description of a row, or nothing for a row
+// that wasn't read from a file.
+pub fn sources_note(sources: &[RowSource]) -> String {
+	match sources.len() {
+		0 => String::new(),
+		_ => format!(" ({})", describe_sources(sources)),
+	}
+}
+
+// A single entry in a truth table.  Its sources are the rows it was read
+// from: none for an entry that was computed, several for one merged from
+// several tables.  The outputs listed in dc are don't-cares on this row
+// alone; their bits in output are 0.  Entries compare by their inputs and
+// outputs alone.
+#[derive(Clone, Debug)]
+pub struct Entry {
+	pub input: Vec<bool>,
+	pub output: Vec<bool>,
+	pub dc: Vec<usize>,
+	pub sources: Vec<RowSource>,
+}
+impl Entry {
+	pub fn new(inp: Vec<bool>, outp: Vec<bool>) -> Self {
+		Entry{input: inp.clone(), output: outp.clone(), dc: vec![], sources: vec![]}
+	}
+
+	// The entry for output cells read from a row, None being a don't-care.
+	pub fn from_cells(inp: Vec<bool>, cells: &[Option<bool>]) -> Self {
+		Entry{input: inp, output: cells.iter().map(|c| c.unwrap_or(false)).collect(),
+		      dc: (0..cells.len()).filter(|&o| cells[o].is_none()).collect(),
+		      sources: vec![]}
+	}
+
+	// Whether output 'idx' is given on this row, rather than a don't-care.
+	pub fn cares(&self, idx: usize) -> bool {
+		!self.dc.contains(&idx)
+	}
+
+	// The outputs, None for the don't-cares.
+	pub fn cells(&self) -> Vec<Option<bool>> {
+		self.output.iter().enumerate()
+			.map(|(o, &b)| if self.cares(o) { Some(b) } else { None }).collect()
+	}
+
+	// The line the entry was read from, when it was read from just one.
+	pub fn line(&self) -> Option<usize> {
+		match self.sources.len() {
+			1 => Some(self.sources[0].line),
+			_ => None,
+		}
+	}
+}
+
+impl PartialEq for Entry {
+	fn eq(&self, other: &Entry) -> bool {
+		self.input == other.input && self.output == other.output && self.dc == other.dc
+	}
+}
+
+// A Term is a product that is each state of the input bits.  For example, in
+// the system: 00 -> 1, 01 -> 1, 10 -> 0, 11 -> 1, the output equation is:
+//   a'b' + a'b + ab
+// a'b', a'b, and ab are all terms.  We don't have symbolic names in a program,
+// of course, so we just say we have a list where each element is an index and
+// a boolean.  So (0, false) means "a'", whereas (1, true) means "b".  The
+// terms the table's rows give list their variables sorted by index, and
+// merging keeps that order, but a term built otherwise may list them in any.
+//
+// Terms compare by their literals alone, whatever their order: a'b and ba'
+// are the same term.  They are ordered by length, then by their variables,
+// then by the variables' polarities.
+pub type Variable = (usize, bool);
+#[derive(Clone, Debug)]
+pub struct Term {
+	pub bits: Vec<Variable>,
+	pub names: Vec<String>,
+}
+impl PartialEq for Term {
+	fn eq(&self, other: &Term) -> bool { self.key() == other.key() }
+}
+impl Eq for Term {}
+impl std::hash::Hash for Term {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.key().hash(state) }
+}
+impl PartialOrd for Term {
+	fn partial_cmp(&self, other: &Term) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for Term {
+	fn cmp(&self, other: &Term) -> std::cmp::Ordering { self.key().cmp(&other.key()) }
+}
+impl fmt::Display for Term {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		assert!(self.names.len() >= self.bits.len());
+		if self.is_universal() {
+			return write!(f, "1");
+		}
+		for var in self.bits.iter() {
+			// completely valid to extend the list of names... but this quickly gets
+			// larger than something that would be computable in finite time.
+			assert!(var.0 < self.names.len()); // so we can convert to a "simple" name
+			if var.1 {
+				try!(write!(f, "{}", self.names[var.0]));
+			} else {
+				try!(write!(f, "{}'", self.names[var.0]));
+			}
+		}
+		write!(f, "")
+	}
+}
+
+impl Term {
+	#[cfg(test)]
+	pub fn new(vals: Vec<Variable>) -> Self {
+		// just used as symbolic names, to avoid calling them "index 7" etc.
+		let nms = ["a","b","c","d","e","f","g","h","i","j","k","l","m","n","o",
+		           "p","q","r","s","t","u","v","w","x","y","z"];
+		let copy = nms.iter().map(|elt| elt.to_string()).collect();
+		Term{bits: vals, names: copy}
+	}
+	pub fn compute(bits: &Vec<bool>) -> Self {
+		let mut rv = vec![];
+		for (i, bit) in bits.iter().enumerate() {
+			rv.push((i, *bit));
+		}
+		let nms = ["a","b","c","d","e","f","g","h","i","j","k","l","m","n","o",
+		           "p","q","r","s","t","u","v","w","x","y","z"];
+		let copy = nms.iter().map(|elt| elt.to_string()).collect();
+		Term{bits: rv, names: copy}
+	}
+	pub fn len(&self) -> usize { self.bits.len() }
+	pub fn is_empty(&self) -> bool { self.bits.is_empty() }
+	// Whether the term has no literals: it is true for every input, and an
+	// equation having it is constant 1.
+	pub fn is_universal(&self) -> bool { self.bits.is_empty() }
+	// (length, indices, polarities) with the variables sorted, for comparing.
+	fn key(&self) -> (usize, Vec<usize>, Vec<bool>) {
+		let mut bits = self.bits.clone();
+		bits.sort();
+		(bits.len(), bits.iter().map(|b| b.0).collect(),
+		 bits.iter().map(|b| b.1).collect())
+	}
+	// true when the given input pattern satisfies every literal of the term.
+	pub fn evaluate(&self, inp: &[bool]) -> bool {
+		self.bits.iter().all(|&(idx, val)| inp[idx] == val)
+	}
+	// true when the terms merge; see merge.
+	pub fn mergeable(&self, other: &Term) -> bool {
+		self.differing(other).is_some()
+	}
+
+	// The term covering both terms, when they have the same variables and
+	// differ in the polarity of just one, which it lacks: a'b' and ab' merge
+	// into b'.  The variables are matched by index, not by position, so they
+	// may be in any order (a'b' and b'a merge too); the merged term keeps this
+	// term's order.
+	pub fn merge(&self, other: &Term) -> Option<Term> {
+		self.differing(other).map(|idx| {
+			let mut rv = self.clone();
+			rv.remove_index(idx);
+			rv
+		})
+	}
+
+	// The index of the variable merging the terms drops; see merge.
+	fn differing(&self, other: &Term) -> Option<usize> {
+		if self.len() != other.len() { // mismatched terms cannot be merged.
+			return None;
+		}
+		// terms both sorted by index line up; others are matched by search.
+		let sorted = |t: &Term| t.bits.windows(2).all(|w| w[0].0 < w[1].0);
+		let in_step = sorted(self) && sorted(other);
+		let mut differing = None;
+		for (k, &(idx, val)) in self.bits.iter().enumerate() {
+			let b = if in_step { Some(&other.bits[k]).filter(|b| b.0 == idx) }
+			        else { other.bits.iter().find(|b| b.0 == idx) };
+			match b {
+				None => return None,
+				Some(b) if b.1 == val => {},
+				Some(_) if differing.is_some() => return None,
+				Some(_) => differing = Some(idx),
+			}
+		}
+		differing
+	}
+
+	// true when some input satisfies both terms, i.e. no variable appears in
+	// them with opposite polarity.
+	pub fn intersects(&self, other: &Term) -> bool {
+		self.bits.iter().all(|a| !other.bits.iter().any(|b| a.0 == b.0 && a.1 != b.1))
+	}
+
+	// true when every input satisfying 'other' satisfies this term: each of its
+	// literals is one of other's.  Every term contains itself, and the
+	// universal term every other.
+	pub fn contains(&self, other: &Term) -> bool {
+		self.bits.iter().all(|a| other.bits.contains(a))
+	}
+
+	// The number of variables the terms both test, in opposite polarities: 0
+	// when they intersect, and 1 when they are adjacent, as a'b and ac are,
+	// sharing the border their consensus bc spans.  For terms of single inputs
+	// it is how many bits the inputs differ in.  None when the terms are over
+	// different variables: a variable either tests is named differently, or not
+	// at all, by the other's names.
+	pub fn distance(&self, other: &Term) -> Option<usize> {
+		let reconciled = self.bits.iter().chain(other.bits.iter()).all(|b| {
+			self.names.get(b.0).is_some() && self.names.get(b.0) == other.names.get(b.0)
+		});
+		if !reconciled {
+			return None;
+		}
+		Some(self.bits.iter()
+			.filter(|a| other.bits.iter().any(|b| a.0 == b.0 && a.1 != b.1)).count())
+	}
+
+	// The term over other indices: the variable of index i becomes that of
+	// index mapping[i], keeping its name, so the term can move between the
+	// indices of a caller's own variables and a table's.  The mapping must be
+	// an injection: two variables onto one index would make another term.
+	pub fn remap(&self, mapping: &[usize]) -> Result<Term, RemapError> {
+		check_mapping(mapping)?;
+		let mut bits: Vec<Variable> = vec![];
+		for &(idx, val) in self.bits.iter() {
+			match mapping.get(idx) {
+				Some(&to) => bits.push((to, val)),
+				None => return Err(RemapError::Unmapped{index: idx, len: mapping.len()}),
+			}
+		}
+		bits.sort();
+		let size = mapping.iter().map(|&to| to + 1).max().unwrap_or(0)
+			.max(self.names.len());
+		let mut names = self.names.clone();
+		names.resize(size, String::new());
+		// an index no variable moves to has no name any more.
+		for name in names.iter_mut().take(mapping.len()) {
+			name.clear();
+		}
+		for (name, &to) in self.names.iter().zip(mapping.iter()) {
+			names[to] = name.clone();
+		}
+		Ok(Term{bits, names})
+	}
+
+	fn remove_index(&mut self, idx: usize) {
+		self.bits.retain(|&b| b.0 != idx);
+	}
+}
+
+// Why variables can't be moved to the indices a mapping gives.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RemapError {
+	// a variable's index is beyond the mapping.
+	Unmapped{index: usize, len: usize},
+	// two variables map to the same index.
+	Collision{first: usize, second: usize, index: usize},
+	// a variable maps beyond the inputs of the table.
+	OutOfRange{variable: usize, index: usize, nbits: usize},
+}
+
+impl fmt::Display for RemapError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			RemapError::Unmapped{index, len} =>
+				write!(f, "Variable {} is not mapped; the mapping has {} indices.", index,
+				       len),
+			RemapError::Collision{first, second, index} =>
+				write!(f, "Variables {} and {} both map to index {}.", first, second,
+				       index),
+			RemapError::OutOfRange{variable, index, nbits} =>
+				write!(f, "Variable {} maps to index {}, but the table has {} inputs.",
+				       variable, index, nbits),
+		}
+	}
+}
+
+// Checks that no two variables of the mapping share an index.
+fn check_mapping(mapping: &[usize]) -> Result<(), RemapError> {
+	for (second, &index) in mapping.iter().enumerate() {
+		if let Some(first) = mapping[..second].iter().position(|&to| to == index) {
+			return Err(RemapError::Collision{first, second, index});
+		}
+	}
+	Ok(())
+}
+
+/// An equation is a collection of Terms, where the OR of Terms gives the
+/// result.  Outside the crate its fields are read and changed through methods.
+///
+/// ```compile_fail
+/// use minterm::prelude::*;
+/// let tbl = Truth::from_fn(1, 1, |i| vec![i[0]]).unwrap();
+/// let eqn = Equation::new(&tbl, 0, "x", &vec!["a".to_string()]);
+/// assert_eq!(eqn.terms.len(), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Equation {
+	pub(crate) index: usize,
+	pub(crate) terms: Vec<Term>,
+	// inputs for which the output may be either value: the minimizers may
+	// cover them or not, whichever gives the simpler equation.
+	pub(crate) dc: Vec<Term>,
+	pub(crate) varname: String,
+}
+impl Equation {
+	// The fields, for code outside the crate.
+	pub fn index(&self) -> usize { self.index }
+	pub fn varname(&self) -> &str { &self.varname }
+	pub fn terms(&self) -> &[Term] { &self.terms }
+	pub fn dont_cares(&self) -> &[Term] { &self.dc }
+	// Replaces the terms, or adds to them or to the don't-cares; nothing checks
+	// that a term is over the equation's inputs.
+	pub fn set_terms(&mut self, terms: Vec<Term>) { self.terms = terms }
+	pub fn add_terms<I: IntoIterator<Item = Term>>(&mut self, terms: I) {
+		self.terms.extend(terms)
+	}
+	pub fn add_dont_cares<I: IntoIterator<Item = Term>>(&mut self, terms: I) {
+		self.dc.extend(terms)
+	}
+	pub fn clear_dont_cares(&mut self) { self.dc.clear() }
+
+	// The equation for output 'idx', named 'vn', that is 1 for the inputs of
+	// 'terms' and either value for those of 'dc'.
+	pub fn from_terms(idx: usize, vn: &str, terms: Vec<Term>, dc: Vec<Term>) -> Self {
+		Equation{index: idx, terms, dc, varname: vn.to_string()}
+	}
+
+	/// @param tbl the truth table to compute this from
+	/// @param idx the index of the output variable we're creating
+	/// @param vn the variable name of the output variable
+	/// @param invars the names of the input variables
+	// Takes a truth table and the index of the output variable to compute
+	// equations for.
+	pub fn new<T: Table>(tbl: &T, idx: usize, vn: &str, invars: &Vec<String>) -> Self {
+		let mut rv: Vec<Term> = vec![];
+		// 0 bits don't contribute terms.
+		for input in tbl.minterm_inputs(idx) {
+			// compute the term and add it to our list ...
+			let mut term = Term::compute(&input);
+			term.names = invars.clone();
+			rv.push(term);
+		}
+		let dc = tbl.dont_care_inputs(idx).map(|inp| {
+			let mut term = Term::compute(&inp);
+			term.names = invars.clone();
+			term
+		}).collect();
+		Equation{index: idx, terms: rv, dc, varname: vn.to_string()}
+	}
+
+	/// @param s the equation, "x = a'b + c", or just the expression
+	/// @param invars the names of the input variables
+	// Parses a hand-written equation; see expr.rs for the syntax.  The output
+	// index can't be known from the text alone, so it is left at 0.
+	pub fn parse(s: &str, invars: &[String]) -> Result<Self, expr::ParseError> {
+		let (name, rhs, offset) = expr::split_assignment(s);
+		let e = match expr::Expr::parse(rhs, invars) {
+			Ok(e) => e,
+			Err(mut err) => {
+				err.pos += offset;
+				return Err(err);
+			},
+		};
+		Ok(Equation{index: 0, terms: expr::terms(e.products(), invars), dc: vec![],
+		            varname: name.to_string()})
+	}
+
+	// Whether the equations have the same terms, in whatever order and however
+	// often they are listed.
+	pub fn eq_semantic(&self, other: &Equation) -> bool {
+		let terms = |e: &Equation| e.terms.iter().cloned().collect::<BTreeSet<Term>>();
+		terms(self) == terms(other)
+	}
+
+	// Evaluates the equation (the OR of its terms) for an input pattern.
+	pub fn evaluate(&self, inp: &[bool]) -> bool {
+		self.terms.iter().any(|t| t.evaluate(inp))
+	}
+
+	// The rows of the table on which this equation disagrees with its output,
+	// where that isn't a don't-care.
+	pub fn counterexamples<'a>(&self, tbl: &'a Truth) -> Vec<&'a Entry> {
+		tbl.table.iter()
+			.filter(|ent| ent.cares(self.index) &&
+			              self.evaluate(&ent.input) != ent.output[self.index])
+			.collect()
+	}
+
+	// Adjusts the (minimized) equation to the table after the rows for the
+	// 'changed' input patterns changed, rather than minimizing it again from
+	// scratch: terms covering a pattern that is now 0 are dropped and the 1s
+	// they covered put back as minterms, patterns that are now 1 are added, and
+	// the result is merged again.  Unlisted patterns count as 0.
+	pub fn update(&mut self, tbl: &Truth, changed: &[Vec<bool>]) {
+		if changed.is_empty() {
+			return;
+		}
+		let nbits = changed[0].len();
+		let rows: HashMap<&[bool], bool> = tbl.table.iter()
+			.map(|e| (e.input.as_slice(), e.output[self.index]))
+			.collect();
+		let value = |inp: &[bool]| rows.get(inp).cloned().unwrap_or(false);
+		let names = match self.terms.iter().chain(self.dc.iter()).next() {
+			Some(t) => t.names.clone(),
+			None => Term::compute(&changed[0]).names,
+		};
+		let cleared: Vec<&Vec<bool>> = changed.iter().filter(|p| !value(p)).collect();
+		let (keep, dropped): (Vec<Term>, Vec<Term>) = self.terms.drain(..)
+			.partition(|t| !cleared.iter().any(|p| t.evaluate(p)));
+		let mut ones: BTreeSet<Vec<bool>> = BTreeSet::new();
+		for t in dropped.iter() {
+			ones.extend(exact::expand_cube(&exact::term_cube(t, nbits)).into_iter()
+				.filter(|m| value(m)));
+		}
+		ones.extend(changed.iter().filter(|p| value(p)).cloned());
+		self.terms = keep;
+		for m in ones {
+			if !self.evaluate(&m) {
+				let mut term = Term::compute(&m);
+				term.names = names.clone();
+				self.terms.push(term);
+			}
+		}
+		self.simplify();
+	}
+
+	// Tries to minimize this equation.
+	pub fn simplify(&mut self) {
+		self.simplify_with(&mut progress::Silent);
+	}
+
+	// Puts the terms in their canonical order (Term's Ord), which depends only
+	// on which terms there are.  The greedy and exact searches give them in
+	// orders that follow the table's rows or the search, which a shuffled
+	// table or a different search could change.
+	pub fn canonicalize(&mut self) {
+		self.terms.sort();
+	}
+
+	// This equation computed from 'other''s output, for code that computes
+	// 'other' first: when other is 1 only where this is, the terms within other
+	// give way to its output (y = ab + c with x = ab is y = x + c), and when
+	// other is a single product that every term has, the product does
+	// (y = abc + abd' is y = cx + d'x).  The output is a variable after the
+	// inputs, named for it.  Both equations must be over the inputs alone.  None
+	// when neither holds or the rewrite doesn't have fewer literals.
+	pub fn substitute(&self, other: &Equation) -> Option<Equation> {
+		let mut names = match self.terms.first() {
+			Some(t) if other.index != self.index && !other.terms.is_empty() =>
+				t.names.clone(),
+			_ => return None,
+		};
+		let nbits = names.len();
+		names.push(other.varname.clone());
+		let within = |t: &Term, eqn: &Equation| {
+			exact::expand_cube(&exact::term_cube(t, nbits)).iter().all(|m| eqn.evaluate(m))
+		};
+		let renamed = |bits: Vec<Variable>| Term{bits, names: names.clone()};
+		let terms: Vec<Term> = if other.terms.iter().all(|t| within(t, self)) {
+			let mut terms = vec![renamed(vec![(nbits, true)])];
+			terms.extend(self.terms.iter().filter(|t| !within(t, other))
+				.map(|t| renamed(t.bits.clone())));
+			terms
+		} else if other.terms.len() == 1 &&
+		          self.terms.iter().all(|t| other.terms[0].bits.iter()
+		                                .all(|b| t.bits.contains(b))) {
+			let factor = &other.terms[0].bits;
+			self.terms.iter().map(|t| {
+				let mut bits: Vec<Variable> = t.bits.iter()
+					.filter(|b| !factor.contains(b)).cloned().collect();
+				bits.push((nbits, true));
+				renamed(bits)
+			}).collect()
+		} else {
+			return None;
+		};
+		let literals = |terms: &[Term]| terms.iter().map(|t| t.len()).sum::<usize>();
+		if literals(&terms) >= literals(&self.terms) {
+			return None;
+		}
+		let mut rv = self.clone();
+		rv.terms = terms;
+		Some(rv)
+	}
+
+	// The equation with its terms and don't-cares remapped; see Term::remap.
+	pub fn remap(&self, mapping: &[usize]) -> Result<Equation, RemapError> {
+		let remap = |terms: &[Term]| -> Result<Vec<Term>, RemapError> {
+			terms.iter().map(|t| t.remap(mapping)).collect()
+		};
+		Ok(Equation{index: self.index, terms: remap(&self.terms)?, dc: remap(&self.dc)?,
+		            varname: self.varname.clone()})
+	}
+
+	// As simplify, reporting the number of cubes after every level of merging.
+	//
+	// The terms, and the don't-cares, are expanded level by level into the
+	// largest cubes they merge into (see exact::expand_cubes), so that no
+	// order of merging can miss one; then as few of those as a greedy choice
+	// finds cover the terms, those covering nothing but don't-cares never
+	// being chosen.
+	pub fn simplify_with(&mut self, progress: &mut dyn progress::Progress) {
+		self.simplify_within(progress, &mut exact::Budget::unlimited());
+	}
+
+	// As simplify_with, the expansion stopping at the budget's memory limit
+	// and its limits on passes and merges; the terms are then chosen from the
+	// cubes it got to.  Returns false when the pass or merge limit stopped it,
+	// leaving the equation partially simplified.
+	pub fn simplify_within(&mut self, progress: &mut dyn progress::Progress,
+	                       budget: &mut exact::Budget) -> bool {
+		let names = match self.terms.first() {
+			Some(t) => t.names.clone(),
+			None => return true,
+		};
+		// constant 1; nothing merges any further.
+		if let Some(one) = self.terms.iter().find(|t| t.is_universal()).cloned() {
+			self.terms = vec![one];
+			return true;
+		}
+		let nbits = self.terms.iter().chain(self.dc.iter())
+			.flat_map(|t| t.bits.iter().map(|b| b.0 + 1)).max().unwrap_or(0);
+		let mut on: Vec<exact::Cube> = vec![];
+		for t in self.terms.iter() {
+			let cube = exact::term_cube(t, nbits);
+			if !on.contains(&cube) {
+				on.push(cube);
+			}
+		}
+		let mut cubes = on.clone();
+		cubes.extend(self.dc.iter().map(|t| exact::term_cube(t, nbits)));
+		let (primes, expanded) = exact::expand_cubes_within(cubes, progress, budget, true);
+		let covers: Vec<Vec<usize>> = primes.iter()
+			.map(|p| (0..on.len()).filter(|&t| exact::cube_contains(p, &on[t])).collect())
+			.collect();
+		// the prime covering the most terms still uncovered, ties going to the
+		// fewest literals.
+		let mut covered = vec![false; on.len()];
+		let mut chosen: Vec<usize> = vec![];
+		while covered.iter().any(|c| !c) {
+			let gain = |p: usize| covers[p].iter().filter(|&&t| !covered[t]).count();
+			let pick = (0..primes.len()).filter(|&p| gain(p) > 0)
+				.min_by_key(|&p| (usize::MAX - gain(p),
+				                  primes[p].iter().filter(|b| b.is_some()).count(), p))
+				.expect("every term is in some merged cube");
+			for &t in covers[pick].iter() {
+				covered[t] = true;
+			}
+			chosen.push(pick);
+		}
+		// an early pick may be covered by later ones.
+		for i in (0..chosen.len()).rev() {
+			let others: BTreeSet<usize> = chosen.iter().enumerate()
+				.filter(|&(j, _)| j != i).flat_map(|(_, &p)| covers[p].iter().cloned())
+				.collect();
+			if covers[chosen[i]].iter().all(|t| others.contains(t)) {
+				chosen.remove(i);
+			}
+		}
+		// in the order of the first term each covers.
+		chosen.sort_by_key(|&p| covers[p][0]);
+		self.terms = chosen.iter().map(|&p| exact::cube_to_term(&primes[p], &names))
+			.collect();
+		expanded != exact::Expansion::Limited
+	}
+}
+
+impl std::fmt::Display for Equation {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		try!(write!(f, "{} = ", self.varname));
+		for t in self.terms.iter() {
+			try!(write!(f, "{} + ", t));
+		}
+		write!(f, ";")
+	}
+}
+
+pub fn equations<T: Table>(truth: &T, outvars: Vec<&str>, invars: Vec<String>) ->
+	Vec<Equation> {
+	assert!(truth.len() > 0);
+	assert!(truth.outputs() == outvars.len());
+	let mut rv: Vec<Equation> = vec![];
+	for b in 0..truth.outputs() {
+		rv.push(Equation::new(truth, b, outvars[b], &invars));
+	}
+	rv
+}
+
+// What building equations needs from a table, whichever way its rows are
+// stored.
+pub trait Table {
+	// the number of rows.
+	fn len(&self) -> usize;
+	fn is_empty(&self) -> bool { self.len() == 0 }
+	// the number of outputs of each row.
+	fn outputs(&self) -> usize;
+	// the input patterns of the rows where output 'idx' is 1.
+	fn minterm_inputs<'a>(&'a self, idx: usize) -> Box<dyn Iterator<Item = Vec<bool>> + 'a>;
+	// the input patterns for which output 'idx' is a don't-care.
+	fn dont_care_inputs<'a>(&'a self, idx: usize) ->
+		Box<dyn Iterator<Item = Vec<bool>> + 'a>;
+}
+
+/// The rows of a table, and the inputs for which every output is a
+/// don't-care.  Outside the crate the rows are read through `rows` and added
+/// with `push`.
+///
+/// ```compile_fail
+/// use minterm::prelude::*;
+/// let mut tbl = Truth::default();
+/// tbl.table.push(Entry::new(vec![true], vec![false]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Truth {
+	pub(crate) table: Vec<Entry>,
+	// input patterns for which every output may be either value; see
+	// Entry for rows with only some outputs don't-cares.
+	pub(crate) dc: Vec<Vec<bool>>,
+}
+
+impl Table for Truth {
+	fn len(&self) -> usize { self.table.len() }
+	fn outputs(&self) -> usize { self.table.first().map_or(0, |e| e.output.len()) }
+	fn minterm_inputs<'a>(&'a self, idx: usize) ->
+		Box<dyn Iterator<Item = Vec<bool>> + 'a> {
+		Box::new(self.minterms(idx).map(|e| e.input.clone()))
+	}
+	fn dont_care_inputs<'a>(&'a self, idx: usize) ->
+		Box<dyn Iterator<Item = Vec<bool>> + 'a> {
+		Box::new(self.dont_cares_of(idx).cloned())
+	}
+}
+
+// The most inputs a table can be enumerated over unless --max-enumerate says
+// otherwise; beyond this it won't fit in memory.
+pub const MAX_ENUMERATED_BITS: usize = 24;
+
+impl Truth {
+	// Records the file every row was read from.
+	pub fn name_sources(&mut self, file: &str) {
+		for src in self.table.iter_mut().flat_map(|e| e.sources.iter_mut()) {
+			src.file = file.to_string();
+		}
+	}
+
+	/// Builds the complete table over `nbits` inputs, the rows in counting
+	/// order with the first input most significant, taking each row's `nout`
+	/// outputs from `f`.  Fails above `MAX_ENUMERATED_BITS` inputs, or when `f`
+	/// gives the wrong number of outputs.
+	///
+	/// ```
+	/// use minterm::{Truth, equations, exact};
+	/// let names: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+	/// let mut budget = exact::Budget::unlimited();
+	///
+	/// // majority: set when at least two inputs are.
+	/// let maj = Truth::from_fn(3, 1, |inp| {
+	///     vec![inp.iter().filter(|&&b| b).count() >= 2]
+	/// }).unwrap();
+	/// let mut eqn = equations(&maj, vec!["m"], names.clone()).remove(0);
+	/// eqn.set_terms(exact::minimize(&eqn, 3, &mut budget).terms);
+	/// assert_eq!(eqn.to_string(), "m = bc + ac + ab + ;");
+	///
+	/// // parity: no two minterms are adjacent, so nothing merges.
+	/// let par = Truth::from_fn(3, 1, |inp| {
+	///     vec![inp.iter().filter(|&&b| b).count() % 2 == 1]
+	/// }).unwrap();
+	/// let mut eqn = equations(&par, vec!["p"], names).remove(0);
+	/// eqn.simplify();
+	/// assert_eq!(eqn.terms().len(), 4);
+	/// ```
+	pub fn from_fn<F: Fn(&[bool]) -> Vec<bool>>(nbits: usize, nout: usize, f: F) ->
+		Result<Truth, String> {
+		Truth::from_fn_partial(nbits, nout, |inp| Some(f(inp)))
+	}
+
+	/// As `from_fn`, but `f` gives `None` for inputs whose outputs are
+	/// don't-cares, which `dont_cares` gives rather than the rows.
+	///
+	/// ```
+	/// use minterm::{Truth, equations, exact};
+	/// // whether a BCD digit is 5 or more; 10 to 15 aren't digits.
+	/// let tbl = Truth::from_fn_partial(4, 1, |inp| {
+	///     let v = inp.iter().fold(0, |v, &b| 2*v + b as u8);
+	///     if v > 9 { None } else { Some(vec![v >= 5]) }
+	/// }).unwrap();
+	/// assert_eq!((tbl.len(), tbl.dont_cares().count()), (10, 6));
+	/// let names: Vec<String> = vec!["a".into(), "b".into(), "c".into(), "d".into()];
+	/// let mut eqn = equations(&tbl, vec!["ge5"], names).remove(0);
+	/// eqn.set_terms(exact::minimize(&eqn, 4, &mut exact::Budget::unlimited()).terms);
+	/// assert_eq!(eqn.to_string(), "ge5 = a + bd + bc + ;");
+	/// ```
+	pub fn from_fn_partial<F: Fn(&[bool]) -> Option<Vec<bool>>>(nbits: usize,
+	                                                           nout: usize, f: F) ->
+		Result<Truth, String> {
+		Truth::from_fn_limited(nbits, nout, MAX_ENUMERATED_BITS, f)
+	}
+
+	// As from_fn_partial, failing above 'limit' inputs rather than
+	// MAX_ENUMERATED_BITS.
+	pub fn from_fn_limited<F: Fn(&[bool]) -> Option<Vec<bool>>>(nbits: usize,
+	                                                           nout: usize, limit: usize,
+	                                                           f: F) ->
+		Result<Truth, String> {
+		let mut rv = Truth::default();
+		for input in enumerate::inputs(nbits, limit)? {
+			match f(&input) {
+				Some(ref output) if output.len() != nout => {
+					return Err(format!("{} outputs for input {:?}; expected {}.",
+					                   output.len(), input, nout));
+				},
+				Some(output) => rv.table.push(Entry::new(input, output)),
+				None => rv.dc.push(input),
+			}
+		}
+		Ok(rv)
+	}
+
+	/// The complete table the equations compute over `nbits` inputs, output
+	/// `k` being `eqns[k]`, whatever its `index`; the equations' names are
+	/// the table's.  An equation with no terms is 0 everywhere and one with
+	/// an empty term 1, and inputs no term tests just don't matter.  The
+	/// equations' don't-cares are ignored, so the table has none.  Fails
+	/// above `MAX_ENUMERATED_BITS` inputs, or when a term tests an input past
+	/// `nbits`.
+	///
+	/// ```
+	/// use minterm::{Truth, equations};
+	/// let tbl = Truth::from_fn(2, 1, |inp| vec![inp[0] != inp[1]]).unwrap();
+	/// let names: Vec<String> = vec!["a".into(), "b".into()];
+	/// let mut eqns = equations(&tbl, vec!["x"], names);
+	/// eqns[0].simplify();
+	/// assert_eq!(Truth::from_equations(&eqns, 2).unwrap().rows(), tbl.rows());
+	/// ```
+	pub fn from_equations(eqns: &[Equation], nbits: usize) -> Result<Truth, String> {
+		let past = eqns.iter().flat_map(|e| e.terms.iter().map(move |t| (e, t)))
+			.find(|&(_, t)| t.bits.iter().any(|b| b.0 >= nbits));
+		if let Some((eqn, t)) = past {
+			return Err(format!("The term {} of {} tests an input past the {}.", t,
+			                   eqn.varname, nbits));
+		}
+		Truth::from_fn(nbits, eqns.len(),
+		               |inp| eqns.iter().map(|e| e.evaluate(inp)).collect())
+	}
+
+	pub fn new(inp: Vec<Vec<bool>>, outp: Vec<Vec<bool>>) -> Self {
+		assert_eq!(inp.len(), outp.len());
+		let mut entlist: Vec<Entry> = vec![];
+		for i in 0..inp.len() {
+			entlist.push(Entry::new(inp[i].clone(), outp[i].clone()));
+		}
+		Truth{table: entlist, dc: vec![]}
+	}
+
+	// Each call is a pass over the rows; for looking up many patterns, see
+	// index.
+	pub fn solution(&self, inp: Vec<bool>) -> Vec<bool> {
+		// find the entry for which the input bit pattern matches.
+		let foo = self.table.iter().find(|tbl| { tbl.input == inp });
+		match foo {
+			None => panic!("cannot find bit pattern {:?}", inp),
+			Some(x) => x.output.clone(), // return the output part of the Entry.
+		}
+	}
+
+	pub fn len(&self) -> usize { return self.table.len() }
+	pub fn is_empty(&self) -> bool { self.table.is_empty() }
+
+	/// Every row with outputs, in the table's order.
+	pub fn rows(&self) -> &[Entry] { &self.table }
+
+	// Exchanges rows 'a' and 'b'.
+	pub fn swap_rows(&mut self, a: usize, b: usize) { self.table.swap(a, b) }
+
+	// Adds a row after the others; nothing checks that its input isn't
+	// already listed.
+	pub fn push(&mut self, ent: Entry) { self.table.push(ent) }
+
+	// Makes every output a don't-care for the input patterns.
+	pub fn add_dont_cares<I: IntoIterator<Item = Vec<bool>>>(&mut self, inps: I) {
+		self.dc.extend(inps)
+	}
+
+	/// The rows where output `idx` is 1, and not a don't-care.
+	///
+	/// ```
+	/// let tbl = minterm::Truth::from_fn(2, 1, |inp| vec![inp[0] || inp[1]]).unwrap();
+	/// assert_eq!(tbl.minterms(0).count(), 3);
+	/// assert_eq!(tbl.minterm_indices(0).collect::<Vec<u64>>(), vec![1, 2, 3]);
+	/// assert_eq!(tbl.maxterm_indices(0).collect::<Vec<u64>>(), vec![0]);
+	/// ```
+	pub fn minterms<'a>(&'a self, idx: usize) -> impl Iterator<Item = &'a Entry> + 'a {
+		self.table.iter().filter(move |e| e.output[idx] && e.cares(idx))
+	}
+
+	/// The rows where output `idx` is 0, and not a don't-care.
+	pub fn maxterms<'a>(&'a self, idx: usize) -> impl Iterator<Item = &'a Entry> + 'a {
+		self.table.iter().filter(move |e| !e.output[idx] && e.cares(idx))
+	}
+
+	/// The input patterns for which the outputs are don't-cares.
+	pub fn dont_cares<'a>(&'a self) -> impl Iterator<Item = &'a Vec<bool>> + 'a {
+		self.dc.iter()
+	}
+
+	/// The input patterns for which output `idx` is a don't-care: those of
+	/// `dont_cares`, then the rows where just some outputs are, `idx` among
+	/// them.
+	pub fn dont_cares_of<'a>(&'a self, idx: usize) ->
+		impl Iterator<Item = &'a Vec<bool>> + 'a {
+		self.dc.iter()
+			.chain(self.table.iter().filter(move |e| !e.cares(idx)).map(|e| &e.input))
+	}
+
+	/// The ON-, DC- and OFF-sets of output `idx`: the inputs of the rows where
+	/// it is 1, the inputs where it is a don't-care, and those where it is
+	/// 0, each as cubes.  Inputs the table doesn't list are in none of them.
+	///
+	/// ```
+	/// let tbl = minterm::Truth::from_fn(2, 1, |inp| vec![inp[0] && inp[1]]).unwrap();
+	/// let (on, dc, off) = tbl.partition(0);
+	/// assert_eq!(on, vec![vec![Some(true), Some(true)]]);
+	/// assert_eq!((dc.len(), off.len()), (0, 3));
+	/// assert!(minterm::sets::check(&on, &dc, &off, 2, true).is_ok());
+	/// ```
+	pub fn partition(&self, idx: usize) -> (sets::Cover, sets::Cover, sets::Cover) {
+		let cube = |inp: &Vec<bool>| inp.iter().map(|&b| Some(b)).collect();
+		(self.minterms(idx).map(|e| cube(&e.input)).collect(),
+		 self.dont_cares_of(idx).map(&cube).collect(),
+		 self.maxterms(idx).map(|e| cube(&e.input)).collect())
+	}
+
+	/// As `minterms`, but the input patterns packed into integers, the first
+	/// input most significant.
+	pub fn minterm_indices<'a>(&'a self, idx: usize) -> impl Iterator<Item = u64> + 'a {
+		self.minterms(idx).map(|e| bit_pack(&e.input, BitOrder::Msb))
+	}
+
+	/// As `maxterms`, but the input patterns packed into integers.
+	pub fn maxterm_indices<'a>(&'a self, idx: usize) -> impl Iterator<Item = u64> + 'a {
+		self.maxterms(idx).map(|e| bit_pack(&e.input, BitOrder::Msb))
+	}
+
+	/// As `dont_cares`, but the input patterns packed into integers.
+	pub fn dont_care_indices<'a>(&'a self) -> impl Iterator<Item = u64> + 'a {
+		self.dc.iter().map(|inp| bit_pack(inp, BitOrder::Msb))
+	}
+
+	// Sets output 'idx' of the row for the input pattern 'inp', adding the row
+	// with all other of the 'nout' outputs 0 if the table doesn't list it, as
+	// an empty table can't say how many there are.  Returns the old value, 0
+	// for a don't-care.
+	pub fn set_output(&mut self, inp: &[bool], idx: usize, value: bool, nout: usize) ->
+		bool {
+		if let Some(ent) = self.table.iter_mut().find(|e| e.input == inp) {
+			let old = ent.output[idx];
+			ent.output[idx] = value;
+			ent.dc.retain(|&o| o != idx);
+			return old;
+		}
+		let mut output = vec![false; nout];
+		output[idx] = value;
+		self.table.push(Entry::new(inp.to_vec(), output));
+		false
+	}
+
+	// The input patterns whose outputs differ between the two tables, in
+	// order, an output that is a don't-care differing from 0 and 1; a pattern
+	// only one of them lists has all outputs 0 in the other.
+	pub fn changed_rows(&self, other: &Truth) -> Vec<Vec<bool>> {
+		let rows = |t: &Truth| -> HashMap<Vec<bool>, Vec<Option<bool>>> {
+			t.table.iter().map(|e| (e.input.clone(), e.cells())).collect()
+		};
+		let (old, new) = (rows(self), rows(other));
+		let patterns: BTreeSet<&Vec<bool>> = old.keys().chain(new.keys()).collect();
+		patterns.into_iter().filter(|p| {
+			let zero = |o: &Vec<Option<bool>>| o.iter().all(|&b| b == Some(false));
+			match (old.get(*p), new.get(*p)) {
+				(Some(a), Some(b)) => a != b,
+				(Some(o), None) | (None, Some(o)) => !zero(o),
+				(None, None) => false,
+			}
+		}).cloned().collect()
+	}
+
+	// The table with its input columns reordered, column i becoming column
+	// mapping[i], as Term::remap moves the variables of terms over it.  The
+	// mapping must be a permutation of the inputs.
+	pub fn permute_inputs(&self, mapping: &[usize]) -> Result<Truth, RemapError> {
+		let nbits = self.table.first().map(|e| e.input.len())
+			.or_else(|| self.dc.first().map(|inp| inp.len())).unwrap_or(0);
+		check_mapping(mapping)?;
+		if mapping.len() < nbits {
+			return Err(RemapError::Unmapped{index: mapping.len(), len: mapping.len()});
+		}
+		if let Some((k, &to)) = mapping.iter().enumerate().find(|&(_, &to)| to >= nbits) {
+			return Err(RemapError::OutOfRange{variable: k, index: to, nbits});
+		}
+		let permute = |inp: &Vec<bool>| -> Vec<bool> {
+			let mut rv = vec![false; inp.len()];
+			for (&b, &to) in inp.iter().zip(mapping.iter()) {
+				rv[to] = b;
+			}
+			rv
+		};
+		let mut rv = self.clone();
+		for ent in rv.table.iter_mut() {
+			ent.input = permute(&ent.input);
+		}
+		rv.dc = self.dc.iter().map(permute).collect();
+		Ok(rv)
+	}
+
+	/// The don't-care inputs as cubes, for listing them compactly: a pair of
+	/// patterns differing only in the first input merge into a cube with
+	/// None for it, then pairs of those differing only in the second input,
+	/// and so on.  Every don't-care input is in exactly one cube.
+	///
+	/// ```
+	/// let tbl = minterm::Truth::from_fn_partial(2, 1, |inp| {
+	///     if inp[0] { None } else { Some(vec![inp[1]]) }
+	/// }).unwrap();
+	/// assert_eq!(tbl.dont_care_cubes(), vec![vec![Some(true), None]]);
+	/// ```
+	pub fn dont_care_cubes(&self) -> sets::Cover {
+		let mut cubes: sets::Cover = vec![];
+		for inp in self.dc.iter() {
+			let cube: exact::Cube = inp.iter().map(|&b| Some(b)).collect();
+			if !cubes.contains(&cube) {
+				cubes.push(cube);
+			}
+		}
+		let nbits = cubes.first().map_or(0, |c| c.len());
+		for k in 0..nbits {
+			let present: HashSet<exact::Cube> = cubes.iter().cloned().collect();
+			let mut merged: HashSet<exact::Cube> = HashSet::new();
+			let mut next: sets::Cover = vec![];
+			for mut cube in cubes.into_iter() {
+				if merged.contains(&cube) {
+					continue;
+				}
+				if let Some(b) = cube[k] {
+					let mut other = cube.clone();
+					other[k] = Some(!b);
+					if present.contains(&other) {
+						merged.insert(other);
+						cube[k] = None;
+					}
+				}
+				next.push(cube);
+			}
+			cubes = next;
+		}
+		cubes
+	}
+
+	/// The table with a header naming its columns, for printing; see the
+	/// `Display` of `Truth`.
+	///
+	/// ```
+	/// let tbl = minterm::Truth::new(vec![vec![false, true]], vec![vec![true]]);
+	/// let names = |ns: &[&str]| ns.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+	/// let (invars, outvars) = (names(&["sel", "b"]), names(&["out"]));
+	/// assert_eq!(tbl.display(&invars, &outvars).to_string(),
+	///            "sel b    out\n0   1 -> 1\n");
+	/// ```
+	pub fn display<'a>(&'a self, invars: &'a [String], outvars: &'a [String]) ->
+		Listing<'a> {
+		Listing{tbl: self, invars, outvars}
+	}
+
+	/// An index of the rows by their input patterns, for looking many of them
+	/// up.  It is of the rows as they are now: build another after changing
+	/// them.
+	///
+	/// ```
+	/// let tbl = minterm::Truth::from_fn(12, 1, |inp| vec![inp[0] ^ inp[11]]).unwrap();
+	/// let rows = tbl.index();
+	/// let inp = vec![true; 12];
+	/// assert_eq!(rows.position(&inp), Some(4095));
+	/// assert_eq!(rows.solution(&tbl, &inp), &[false]);
+	/// assert_eq!(rows.position(&[true]), None);
+	/// ```
+	pub fn index(&self) -> RowIndex {
+		let nbits = self.table.first().map_or(0, |e| e.input.len());
+		let counting = nbits <= MAX_ENUMERATED_BITS && self.table.len() == 1 << nbits &&
+			self.table.iter().enumerate()
+				.all(|(i, e)| bit_pack(&e.input, BitOrder::Msb) == i as u64);
+		if counting {
+			return RowIndex{nbits, rows: None};
+		}
+		let mut rows: HashMap<Vec<bool>, usize> = HashMap::new();
+		for (i, e) in self.table.iter().enumerate() {
+			// the first of repeated patterns, as solution finds.
+			rows.entry(e.input.clone()).or_insert(i);
+		}
+		RowIndex{nbits, rows: Some(rows)}
+	}
+}
+
+/// The positions of a table's rows by input pattern, from `Truth::index`.
+/// Finding a row otherwise takes a pass over the rows, so checking every
+/// input of a complete table takes time growing as the square of its size.
+/// A complete table with its rows in counting order, as `from_fn` builds
+/// them, needs no map at all: the row of a pattern is its number.  The rows
+/// are whole input patterns, never cubes, so a map of them is all it takes.
+pub struct RowIndex {
+	nbits: usize,
+	// None when the rows are in counting order.
+	rows: Option<HashMap<Vec<bool>, usize>>,
+}
+
+impl RowIndex {
+	/// The position in the table of the row for the pattern, if it has one.
+	pub fn position(&self, inp: &[bool]) -> Option<usize> {
+		match self.rows {
+			None if inp.len() == self.nbits => Some(bit_pack(inp, BitOrder::Msb) as usize),
+			None => None,
+			Some(ref rows) => rows.get(inp).cloned(),
+		}
+	}
+
+	/// The outputs of the row for the pattern, as `Truth::solution` gives them
+	/// for the table indexed; panics if it has no such row.
+	pub fn solution<'a>(&self, tbl: &'a Truth, inp: &[bool]) -> &'a [bool] {
+		match self.position(inp) {
+			None => panic!("cannot find bit pattern {:?}", inp),
+			Some(r) => &tbl.table[r].output,
+		}
+	}
+}
+
+// A cell of a printed table: 0, 1, or x for a don't-care.
+fn cell(bit: Option<bool>) -> &'static str {
+	match bit {
+		Some(true) => "1",
+		Some(false) => "0",
+		None => "x",
+	}
+}
+
+// A line of a printed table: the cells, the inputs and outputs separated by
+// "->", each padded to the width of its column (1 where 'widths' has none),
+// without trailing spaces.
+fn table_line(cells: &[&str], widths: &[usize]) -> String {
+	let padded: Vec<String> = cells.iter().enumerate()
+		.map(|(k, c)| format!("{:<1$}", c, widths.get(k).cloned().unwrap_or(1)))
+		.collect();
+	padded.join(" ").trim_end().to_string()
+}
+
+// The cells of a line for the inputs and outputs, None being don't-cares.
+fn row_cells<I: Iterator<Item = Option<bool>>, O: Iterator<Item = Option<bool>>>(
+	input: I, output: O) -> Vec<&'static str> {
+	input.map(cell).chain(Some("->")).chain(output.map(cell)).collect()
+}
+
+// An entry as its inputs and outputs: "0 1 1 -> 1 0".
+impl fmt::Display for Entry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let cells = row_cells(self.input.iter().map(|&b| Some(b)),
+		                      self.cells().into_iter());
+		write!(f, "{}", table_line(&cells, &[]))
+	}
+}
+
+// A table with its columns named, as Truth::display gives it.
+pub struct Listing<'a> {
+	tbl: &'a Truth,
+	invars: &'a [String],
+	outvars: &'a [String],
+}
+
+// The table's rows, a line each as Entry prints them, followed by its
+// don't-cares, merged into cubes, with x for the inputs a cube doesn't test
+// and for every output.  With names, a header line names the columns, each
+// as wide as its name.
+impl<'a> fmt::Display for Listing<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let nout = self.tbl.outputs();
+		let named = !self.invars.is_empty() || !self.outvars.is_empty();
+		let mut widths: Vec<usize> = vec![];
+		if named {
+			let header: Vec<&str> = self.invars.iter().map(|s| s.as_str())
+				.chain(Some(""))
+				.chain(self.outvars.iter().map(|s| s.as_str()))
+				.collect();
+			widths = header.iter().map(|h| h.len().max(1)).collect();
+			widths[self.invars.len()] = 2;
+			writeln!(f, "{}", table_line(&header, &widths))?;
+		}
+		for e in self.tbl.table.iter() {
+			let cells = row_cells(e.input.iter().map(|&b| Some(b)), e.cells().into_iter());
+			writeln!(f, "{}", table_line(&cells, &widths))?;
+		}
+		for cube in self.tbl.dont_care_cubes() {
+			let cells = row_cells(cube.into_iter(), (0..nout).map(|_| None));
+			writeln!(f, "{}", table_line(&cells, &widths))?;
+		}
+		Ok(())
+	}
+}
+
+// The table without a header, as Listing prints it.
+impl fmt::Display for Truth {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", self.display(&[], &[]))
+	}
+}
+
+// The names of the output bits.  Normally these are just the --ovar names; with
+// an explicit --ovar-width a single name 'x' expands to 'x[0]', 'x[1]', ...
+pub fn output_names(ovars: Vec<&str>, width: &str) -> Result<Vec<String>, String> {
+	let names: Vec<String> = ovars.iter().map(|s| s.to_string()).collect();
+	if width.is_empty() {
+		return Ok(names);
+	}
+	let width = match width.parse::<usize>() {
+		Ok(w) if w > 0 => w,
+		_ => return Err(format!("Invalid --ovar-width '{}'; expected a positive \
+		                         integer.", width)),
+	};
+	if names.len() == width {
+		Ok(names)
+	} else if names.len() == 1 {
+		Ok((0..width).map(|b| format!("{}[{}]", names[0], b)).collect())
+	} else {
+		Err(format!("--ovar-width is {} but {} --ovar names were given; give \
+		             either one name or {}.", width, names.len(), width))
+	}
+}
+
+// Checks that no two inputs or outputs have the same name, which would make
+// equations over them ambiguous.
+pub fn check_names(invars: &[String], outvars: &[String]) -> Result<(), String> {
+	let all: Vec<(&str, &str, usize)> = invars.iter().enumerate()
+		.map(|(i, n)| (n.as_str(), "--ivar", i + 1))
+		.chain(outvars.iter().enumerate().map(|(o, n)| (n.as_str(), "--ovar", o + 1)))
+		.collect();
+	for (k, &(name, opt, i)) in all.iter().enumerate() {
+		if let Some(&(_, first, j)) = all[..k].iter().find(|&&(n, _, _)| n == name) {
+			return Err(format!("'{}' names both {} {} and {} {}; every variable needs a \
+			                    name of its own.", name, first, j, opt, i));
+		}
+	}
+	Ok(())
+}
+
+// The most rows after the header that columns reads.
+const SAMPLED_ROWS: usize = 1000;
+
+// How the rows of a CSV table with a column per input and per output are laid
+// out: how many columns the first row has, and the first run of columns that
+// are empty on every row, which usually parts the inputs from the outputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Columns {
+	pub width: usize,
+	// the first and one past the last of the empty columns.
+	pub blank: Option<(usize, usize)>,
+}
+
+// The layout of the rows of the table, from its first SAMPLED_ROWS rows after
+// the 'nheader' header lines, the comment and blank lines of 'layout' left
+// out; None when it has none.
+pub fn columns<T: std::io::Read>(data: T, nheader: usize, layout: &Layout) ->
+	Option<Columns> {
+	let mut rdr = csv::ReaderBuilder::new()
+		.has_headers(false)
+		.flexible(true)
+		.from_reader(data);
+	let mut width = None;
+	let mut filled: Vec<bool> = vec![];
+	let records = rdr.records().take_while(|r| r.is_ok()).filter_map(|r| r.ok())
+		.filter(|r| !layout.skips(r));
+	for record in records.skip(nheader).take(SAMPLED_ROWS) {
+		width = width.or(Some(record.len()));
+		for (j, cell) in record.iter().enumerate() {
+			if filled.len() <= j {
+				filled.resize(j + 1, false);
+			}
+			filled[j] |= !clean_cell(cell).is_empty();
+		}
+	}
+	let width = width?;
+	// a run at either end parts nothing.
+	let blank = filled.iter().take(width).position(|&f| !f)
+		.filter(|&s| s > 0)
+		.map(|s| (s, s + filled[s..].iter().take_while(|&&f| !f).count()))
+		.filter(|&(_, e)| e < width);
+	Some(Columns{width, blank})
+}
+
+// The cells of the first header line of a CSV table, the comment and blank
+// lines of 'layout' left out; None when it has no line.
+pub fn header<T: std::io::Read>(data: T, layout: &Layout) -> Option<Vec<String>> {
+	let mut rdr = csv::ReaderBuilder::new()
+		.has_headers(false)
+		.flexible(true)
+		.from_reader(data);
+	let record = rdr.records().take_while(|r| r.is_ok()).filter_map(|r| r.ok())
+		.find(|r| !layout.skips(r))?;
+	Some(record.iter().map(|cell| clean_cell(cell).to_string()).collect())
+}
+
+impl Columns {
+	// Why 'nin' inputs and 'nout' outputs don't fit the rows, and the counts
+	// that would; None when they fit.
+	pub fn mismatch(&self, nin: usize, nout: usize) -> Option<String> {
+		match self.blank {
+			Some((s, e)) if (s, self.width - e) == (nin, nout) => None,
+			Some((s, e)) => {
+				let empty = if e == s + 1 { format!("column {} is", s + 1) }
+				            else { format!("columns {} to {} are", s + 1, e) };
+				Some(format!("{} input(s) and {} output(s) were declared, but {} empty \
+				              on every row, which reads as {} input column(s) before and \
+				              {} output column(s) after: did you mean {} --ivar and {} \
+				              --ovar?", nin, nout, empty, s, self.width - e, s,
+				             self.width - e))
+			},
+			None if nin + nout == self.width => None,
+			None if nout >= self.width =>
+				Some(format!("{} output(s) were declared, but the rows have only {} \
+				              column(s).", nout, self.width)),
+			None => Some(format!("{} input(s) were declared, but the rows have {} \
+			                      columns; with {} output(s) that leaves {} leading \
+			                      column(s): did you mean {} --ivar?", nin, self.width,
+			                     nout, self.width - nout, self.width - nout)),
+		}
+	}
+}
+
+// Parses an --assert-eq equation and checks it against the table.  Returns
+// the equation if it holds, otherwise a message listing (the first few of)
+// the rows where it does not.
+pub fn check_assertion(s: &str, tbl: &Truth, outvars: &[&str], invars: &[String]) ->
+	Result<Equation, String> {
+	verify_assertion(parse_assertion(s, outvars, invars)?, s, tbl, invars)
+}
+// The parsing half of check_assertion: the equation, with the index of the
+// output it assigns.
+pub fn parse_assertion(s: &str, outvars: &[&str], invars: &[String]) ->
+	Result<Equation, String> {
+	let mut eqn = match Equation::parse(s, invars) {
+		Ok(e) => e,
+		Err(e) => return Err(format!("Cannot parse '{}' {}.", s, e)),
+	};
+	eqn.index = match outvars.iter().position(|&o| o == eqn.varname) {
+		Some(i) => i,
+		None => return Err(format!("'{}' does not assign an output: '{}' is not \
+		                            an --ovar.{}", s, eqn.varname,
+		                           did_you_mean(&eqn.varname, outvars))),
+	};
+	Ok(eqn)
+}
+// The checking half of check_assertion, for the equation parsed from 's'.
+pub fn verify_assertion(eqn: Equation, s: &str, tbl: &Truth, invars: &[String]) ->
+	Result<Equation, String> {
+	let bad = eqn.counterexamples(tbl);
+	if bad.is_empty() {
+		return Ok(eqn);
+	}
+	let mut msg = format!("Assertion fails on {} row(s): {}", bad.len(), s);
+	for ent in bad.iter().take(5) {
+		let inputs: Vec<String> = ent.input.iter().zip(invars.iter())
+			.map(|(&b, n)| format!("{}={}", n, b as u8)).collect();
+		msg += &format!("\n  {}{}: table has {}={}, equation gives {}",
+		                inputs.join(" "), sources_note(&ent.sources), eqn.varname,
+		                ent.output[eqn.index] as u8, !ent.output[eqn.index] as u8);
+	}
+	if bad.len() > 5 {
+		msg += &format!("\n  ... and {} more", bad.len() - 5);
+	}
+	Err(msg)
+}
+// Builds the table for 'minterm from-expr' by evaluating each "x = <expr>"
+// over all input patterns.  Returns the table and the names of its outputs.
+pub fn from_expressions(exprs: Vec<&str>, invars: &[String], limit: usize) ->
+	Result<(Truth, Vec<String>), String> {
+	// everything is enumerated, so fail before parsing if that's too much.
+	enumerate::check(invars.len(), limit, "")?;
+	let mut names: Vec<String> = vec![];
+	let mut parsed: Vec<expr::Expr> = vec![];
+	for s in exprs {
+		let (name, rhs, offset) = expr::split_assignment(s);
+		if name.is_empty() {
+			return Err(format!("'{}' must name its output, as in 'x = {}'.", s,
+			                   s.trim()));
+		}
+		if names.iter().any(|n| n == name) {
+			return Err(format!("Output '{}' is defined more than once.", name));
+		}
+		match expr::Expr::parse(rhs, invars) {
+			Ok(e) => parsed.push(e),
+			Err(mut e) => {
+				e.pos += offset;
+				return Err(format!("Cannot parse '{}' {}.", s, e));
+			},
+		}
+		names.push(name.to_string());
+	}
+	Ok((expr::table(&parsed, invars.len(), limit)?, names))
+}
+
+// Lines at the top of a table file that name the columns.
+pub const HEADER_LINES: usize = 2;
+
+// The input patterns over 'nbits' inputs that have no row in the table, in
+// counting order.  Finding them means going through every pattern, so this
+// fails above 'limit' inputs unless the table lists them all.
+pub fn missing_inputs(tbl: &Truth, nbits: usize, limit: usize) ->
+	Result<Vec<Vec<bool>>, String> {
+	let rows: HashSet<&Vec<bool>> =
+		tbl.table.iter().map(|e| &e.input).chain(tbl.dc.iter()).collect();
+	if nbits < 64 && rows.len() as u64 == 1 << nbits {
+		return Ok(vec![]);
+	}
+	Ok(enumerate::inputs(nbits, limit)?.filter(|inp| !rows.contains(inp)).collect())
+}
+
+// Parses the --constraint expressions over the inputs.
+pub fn parse_constraints(specs: Vec<&str>, invars: &[String]) ->
+	Result<Vec<expr::Expr>, String> {
+	specs.iter().map(|s| {
+		expr::Expr::parse(s, invars)
+			.map_err(|e| format!("Cannot parse constraint '{}' {}.", s, e))
+	}).collect()
+}
+
+// Removes the rows whose inputs aren't 'possible' from the table: those
+// inputs can't occur, so whatever the table says for them is a don't-care.
+// Returns the number of rows removed, and every input pattern over 'nbits'
+// inputs that is impossible, listed or not; fails above 'limit' inputs.
+pub fn apply_constraints(tbl: &mut Truth, possible: &dyn Fn(&[bool]) -> bool,
+                         nbits: usize, limit: usize) ->
+	Result<(usize, BTreeSet<Vec<bool>>), String> {
+	let every = enumerate::inputs(nbits, limit)?;
+	let before = tbl.len();
+	tbl.table.retain(|e| possible(&e.input));
+	let impossible = every.filter(|inp| !possible(inp)).collect();
+	Ok((before - tbl.len(), impossible))
+}
+
+// Parses the --weight options, "a=3", into a weight per input; inputs not
+// mentioned weigh 1.
+pub fn input_weights(specs: Vec<&str>, invars: &[String]) -> Result<Vec<usize>, String> {
+	let mut rv = vec![1; invars.len()];
+	for spec in specs {
+		let (name, cost) = match spec.find('=') {
+			Some(eq) => (&spec[..eq], &spec[eq+1..]),
+			None => return Err(format!("Invalid --weight '{}'; expected \
+			                            <ivar>=<cost>.", spec)),
+		};
+		let names: Vec<&str> = invars.iter().map(|s| s.as_str()).collect();
+		let idx = match names.iter().position(|&n| n == name) {
+			Some(idx) => idx,
+			None => return Err(format!("Unknown input '{}' in --weight.{}", name,
+			                           did_you_mean(name, &names))),
+		};
+		rv[idx] = match cost.parse::<usize>() {
+			Ok(c) => c,
+			Err(_) => return Err(format!("Invalid --weight '{}'; the cost must be \
+			                              a non-negative integer.", spec)),
+		};
+	}
+	Ok(rv)
+}
+
+// Why a table leaves nothing to minimize.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum EmptyTable {
+	// not one row has outputs: the file has only a header, or only don't-cares.
+	NoRows,
+	// the table has no outputs, or its rows none.
+	NoOutputs,
+	// the rows have outputs, but --only and --skip leave none of them.
+	NoneSelected{only: Vec<String>, skip: Vec<String>},
+}
+
+impl fmt::Display for EmptyTable {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			EmptyTable::NoRows => write!(f, "The table has no rows with outputs."),
+			EmptyTable::NoOutputs => write!(f, "The table has no outputs; give at least \
+			                                    one --ovar."),
+			EmptyTable::NoneSelected{ref only, ref skip} => {
+				let mut opts: Vec<String> = only.iter().map(|o| format!("--only {}", o))
+					.collect();
+				opts.extend(skip.iter().map(|o| format!("--skip {}", o)));
+				write!(f, "No output is left to minimize by {}.", opts.join(" "))
+			},
+		}
+	}
+}
+
+// Checks that the table, with 'nout' outputs declared, has something to
+// minimize, as equations() and the ANF need.
+pub fn check_outputs<T: Table>(truth: &T, nout: usize) -> Result<(), EmptyTable> {
+	if nout == 0 {
+		Err(EmptyTable::NoOutputs)
+	} else if truth.len() == 0 {
+		Err(EmptyTable::NoRows)
+	} else if truth.outputs() == 0 {
+		Err(EmptyTable::NoOutputs)
+	} else {
+		Ok(())
+	}
+}
+
+// Checks that --only and --skip selected at least one output.
+pub fn check_selection(selected: &[usize], only: &[&str], skip: &[&str]) ->
+	Result<(), EmptyTable> {
+	if !selected.is_empty() {
+		return Ok(());
+	}
+	let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+	Err(EmptyTable::NoneSelected{only: names(only), skip: names(skip)})
+}
+
+// Resolves --only and --skip into the indices of the outputs to work on.
+pub fn select_outputs(outvars: &[&str], only: &[&str], skip: &[&str]) ->
+	Result<Vec<usize>, String> {
+	for name in only.iter().chain(skip.iter()) {
+		if !outvars.contains(name) {
+			return Err(format!("Unknown output '{}'.{}", name,
+			                   did_you_mean(name, outvars)));
+		}
+	}
+	Ok((0..outvars.len())
+		.filter(|&b| only.is_empty() || only.contains(&outvars[b]))
+		.filter(|&b| !skip.contains(&outvars[b]))
+		.collect())
+}
+
+// A " Did you mean 'x'?" suggestion naming the candidate closest to 'name',
+// or nothing if no candidate is reasonably close.
+pub fn did_you_mean(name: &str, candidates: &[&str]) -> String {
+	let best = candidates.iter()
+		.map(|c| (edit_distance(name, c), c))
+		.min();
+	match best {
+		Some((d, c)) if d <= std::cmp::max(1, name.chars().count() / 3) =>
+			format!(" Did you mean '{}'?", c),
+		_ => String::new(),
+	}
+}
+
+// The Levenshtein distance between two strings.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut prev: Vec<usize> = (0..b.len()+1).collect();
+	for (i, ca) in a.chars().enumerate() {
+		let mut cur = vec![i+1; b.len()+1];
+		for j in 0..b.len() {
+			let subst = prev[j] + if ca == b[j] { 0 } else { 1 };
+			cur[j+1] = std::cmp::min(subst, std::cmp::min(prev[j+1], cur[j]) + 1);
+		}
+		prev = cur;
+	}
+	prev[b.len()]
+}
+
+// Minimizes each of the equations, greedily or exactly.  Returns, per
+// equation, whether the result is known to be minimal.
+pub fn minimize(eqns: &mut [Equation], nbits: usize, exact: bool, weights: &[usize],
+            budget: &mut exact::Budget, progress: &mut dyn progress::Progress) ->
+	Vec<bool> {
+	let algorithm = if exact { Algorithm::Exact } else { Algorithm::Greedy };
+	let algorithms = vec![algorithm; eqns.len()];
+	minimize_each(eqns, nbits, &algorithms, weights, budget, progress).iter()
+		.map(Ran::minimal).collect()
+}
+
+// Minimizes each of the equations with the algorithm at the same place in
+// 'algorithms'.  Returns what each came to.
+pub fn minimize_each(eqns: &mut [Equation], nbits: usize, algorithms: &[Algorithm],
+                     weights: &[usize], budget: &mut exact::Budget,
+                     progress: &mut dyn progress::Progress) -> Vec<Ran> {
+	eqns.iter_mut().zip(algorithms.iter()).map(|(eqn, &alg)| {
+		progress.equation(eqn.index, &eqn.varname);
+		if alg == Algorithm::Greedy {
+			progress.phase("simplify");
+			return if eqn.simplify_within(progress, budget) { Ran::Greedy }
+			       else { Ran::GreedyStopped };
+		}
+		let sol = exact::minimize_weighted(eqn, nbits, weights, budget, progress);
+		eqn.terms = sol.terms;
+		match (sol.minimal, sol.simplified) {
+			(_, true) => Ran::GreedyForMemory,
+			(true, _) => Ran::Exact,
+			(false, _) => Ran::ExactStopped,
+		}
+	}).collect()
+}
+
+// How to minimize an equation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Algorithm {
+	// the greedy simplifier, merging terms pairwise.
+	Greedy,
+	// the exact search for a minimum cover.
+	Exact,
+}
+
+impl Algorithm {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"greedy" => Ok(Algorithm::Greedy),
+			"exact" => Ok(Algorithm::Exact),
+			_ => Err(format!("Unknown algorithm '{}'; expected 'greedy' or 'exact'.", s)),
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Algorithm::Greedy => "greedy",
+			Algorithm::Exact => "exact",
+		}
+	}
+}
+
+// The algorithm that minimized an equation in the end, which falls short of
+// the exact search when the budget runs out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Ran {
+	Greedy,
+	// the exact search, which finished.
+	Exact,
+	// the exact search, stopped by the time or step limit: its best cover yet.
+	ExactStopped,
+	// the greedy simplifier, the exact search's chart outgrowing the memory
+	// limit.
+	GreedyForMemory,
+	// the greedy simplifier, stopped by the limit on its passes or merges: a
+	// correct cover, partially simplified.
+	GreedyStopped,
+}
+
+impl Ran {
+	// Whether the cover is known to be minimal, as the greedy simplifier's is
+	// taken to be.
+	pub fn minimal(&self) -> bool {
+		*self == Ran::Greedy || *self == Ran::Exact
+	}
+
+	// Whether the algorithm ran to the end, rather than the simplifier
+	// stopping at its limits.
+	pub fn completed(&self) -> bool {
+		*self != Ran::GreedyStopped
+	}
+}
+
+impl fmt::Display for Ran {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match *self {
+			Ran::Greedy => "greedy",
+			Ran::Exact => "exact",
+			Ran::ExactStopped => "exact, stopped at the budget",
+			Ran::GreedyForMemory => "greedy, exact outgrowing the memory limit",
+			Ran::GreedyStopped => "greedy, partially simplified",
+		})
+	}
+}
+
+// Parses the --algorithm-for specs, '<ovar>=<alg>', into the outputs they
+// name and their algorithms.
+pub fn parse_algorithm_for(specs: Vec<&str>) -> Result<Vec<(String, Algorithm)>, String> {
+	specs.iter().map(|spec| {
+		let (name, alg) = match spec.find('=') {
+			Some(eq) => (spec[..eq].trim(), spec[eq+1..].trim()),
+			None => return Err(format!("Invalid --algorithm-for '{}'; expected \
+			                            <ovar>=<alg>.", spec)),
+		};
+		let alg = Algorithm::parse(alg)
+			.map_err(|e| format!("Invalid --algorithm-for '{}': {}", spec, e))?;
+		Ok((name.to_string(), alg))
+	}).collect()
+}
+
+// The algorithm for each output of the table: 'default', but for the outputs
+// 'overrides' names.
+pub fn algorithm_options(overrides: &[(String, Algorithm)], outvars: &[String],
+                         default: Algorithm) -> Result<Vec<Algorithm>, String> {
+	let mut rv = vec![default; outvars.len()];
+	let mut given: Vec<usize> = vec![];
+	let names: Vec<&str> = outvars.iter().map(|s| s.as_str()).collect();
+	for &(ref name, alg) in overrides {
+		let idx = match names.iter().position(|n| n == name) {
+			Some(idx) => idx,
+			None => return Err(format!("Unknown output '{}' in --algorithm-for.{}", name,
+			                           did_you_mean(name, &names))),
+		};
+		if given.contains(&idx) {
+			return Err(format!("--algorithm-for gives '{}' twice.", name));
+		}
+		given.push(idx);
+		rv[idx] = alg;
+	}
+	Ok(rv)
+}
+
+// really this returns a Vec<[usize; nbits]>, but Rust's variable-length arrays
+// are vectors.
+pub fn gray_code(nbits: usize) -> Vec<Vec<bool>> {
+	let gray1: Vec<Vec<bool>> = vec![vec![false], vec![true]];
+	let mut cur = gray1;
+	for _ in 1..nbits {
+		cur = gray_code_r(cur);
+	}
+	cur
+}
+
+// takes an 'n' bit gray code and computes the gray code for n+1 bits
+fn gray_code_r(gray: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
+	// prepend 0's (false) to the original list
+	let list0: Vec<Vec<bool>> =	gray.iter().map(|bitstring| {
+		let mut copy = bitstring.clone();
+		copy.insert(0, false);
+		copy
+	}).collect();
+	// prepend 1's (true) to the reversed original list
+	let mut list1: Vec<Vec<bool>> =	gray.iter().rev().map(|bitstring| {
+		let mut copy = bitstring.clone();
+		copy.insert(0, true);
+		copy
+	}).collect();
+	// return the concatenation of the old and new lists.
+	let mut concat = list0;
+	concat.append(&mut list1);
+	concat
+}
+
+// How the output bits of a row are stored in the CSV.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputEncoding {
+	// one 0/1 column per output bit.
+	Binary,
+	// a single non-negative integer column holding all output bits, packed in
+	// the given order.
+	Decimal{order: BitOrder},
+}
+
+// How the input bits of a row are stored in the CSV.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputEncoding {
+	// one 0/1 column per input bit.
+	Binary,
+	// a single integer column (the leftmost) holding the input pattern.  Rows
+	// may come in any order, but each pattern may only appear once.
+	Index{order: BitOrder},
+	// no input columns at all: the position of the row is the input pattern.
+	Row{order: BitOrder},
+}
+
+// What a cell of a binary column holding something other than 0 or 1 means.
+// Cells are read as clean_cell leaves them, so ' "1"' is 1; TRUE and FALSE,
+// as spreadsheets write them, are 1 and 0.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cells {
+	// a warning, and the row is a don't-care: a bad output makes its input a
+	// don't-care, and a row with a bad input is skipped.
+	Warn,
+	// an error.
+	Strict,
+	// any integer but 0 is 1, and anything else is 0 with a warning.  This is
+	// how cells used to be read, when a stray 2 was quietly taken as 1.
+	Lenient,
+}
+
+// What an empty cell of a binary column means, when it isn't taken as Cells
+// says of any other cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Empty {
+	// the row says nothing: a row with an empty input is skipped, and one with
+	// an empty output has don't-care outputs.
+	DontCare,
+	Zero,
+	Error,
+}
+
+impl Empty {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"dc" => Ok(Empty::DontCare),
+			"zero" => Ok(Empty::Zero),
+			"error" => Ok(Empty::Error),
+			_ => Err(format!("Invalid --empty '{}'; expected 'dc', 'zero' or 'error'.",
+			                 s)),
+		}
+	}
+}
+
+// A --filter or --filter-out: the rows whose cell in the named column is the
+// value are the only ones read, or with 'keep' false the ones left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+	pub column: String,
+	pub value: String,
+	pub keep: bool,
+}
+
+impl Filter {
+	// Parses "status=final".
+	pub fn parse(s: &str, keep: bool) -> Result<Self, String> {
+		let opt = if keep { "--filter" } else { "--filter-out" };
+		match s.find('=') {
+			Some(eq) if !s[..eq].trim().is_empty() =>
+				Ok(Filter{column: s[..eq].trim().to_string(),
+				          value: s[eq + 1..].trim().to_string(), keep}),
+			_ => Err(format!("Invalid {} '{}'; expected 'column=value'.", opt, s)),
+		}
+	}
+}
+
+// A --map: a cell in the named column, or any 0/1 column for '*', holding
+// exactly the value -- case and all -- is read as 'to', None for a
+// don't-care, before it is read as 0 or 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellMap {
+	pub column: String,
+	pub value: String,
+	pub to: Option<bool>,
+}
+
+impl CellMap {
+	// Parses "status:N/A=x", the column up to the first ':' and the value up to
+	// the last '='.
+	pub fn parse(s: &str) -> Result<Self, String> {
+		let bad = || format!("Invalid --map '{}'; expected 'column:value=0', '=1' or \
+		                      '=x'.", s);
+		let (colon, eq) = match (s.find(':'), s.rfind('=')) {
+			(Some(c), Some(e)) if c < e && !s[..c].trim().is_empty() => (c, e),
+			_ => return Err(bad()),
+		};
+		let to = match s[eq + 1..].trim() {
+			"0" => Some(false),
+			"1" => Some(true),
+			"x" | "X" => None,
+			_ => return Err(bad()),
+		};
+		Ok(CellMap{column: s[..colon].trim().to_string(),
+		           value: s[colon + 1..eq].trim().to_string(), to})
+	}
+}
+
+// What the maps read 'text', a cell of column 'col', as: a map naming the
+// column before one for every column.  None when none of them maps it.
+fn mapped(maps: &[(Option<usize>, &CellMap)], col: usize, text: &str) ->
+	Option<Option<bool>> {
+	let find = |c: Option<usize>| maps.iter()
+		.find(|&&(mc, m)| mc == c && m.value == text).map(|&(_, m)| m.to);
+	find(Some(col)).or_else(|| find(None))
+}
+
+// Which rows of a table to read and which of its columns are the outputs, by
+// the names in its first header line.  The default reads every row, the
+// outputs in the rightmost columns; with 'ocols', the outputs are the columns
+// named, or counted from 0, in order, and any columns after them are left
+// alone.  'freq' is a
+// column, by name or counting from 0, giving how often each row's input
+// occurs; it can't be one of the inputs or outputs.  Lines whose first cell
+// starts with 'comment', and lines with nothing in any cell, are skipped
+// unless it is None, as if the table didn't have them.  'maps' are applied
+// to the 0/1 cells before anything else reads them; only those naming a
+// column need the header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+	pub filters: Vec<Filter>,
+	pub ocols: Vec<String>,
+	pub freq: Option<String>,
+	pub comment: Option<char>,
+	pub maps: Vec<CellMap>,
+}
+
+impl Default for Layout {
+	fn default() -> Self {
+		Layout{filters: vec![], ocols: vec![], freq: None, comment: Some('#'),
+		       maps: vec![]}
+	}
+}
+
+// The columns a Layout names, found in the header.
+struct Resolved<'a> {
+	filters: Vec<(usize, &'a Filter)>,
+	ocols: Option<Vec<usize>>,
+	freq: Option<usize>,
+	// None for the maps of every column.
+	maps: Vec<(Option<usize>, &'a CellMap)>,
+}
+
+impl Layout {
+	// Whether the layout names no columns, and so needs no header.
+	fn is_default(&self) -> bool {
+		self.filters.is_empty() && self.ocols.is_empty() && self.freq.is_none() &&
+			self.maps.iter().all(|m| m.column == "*")
+	}
+
+	// The maps for every column, all there are when none names a column.
+	fn wildcards(&self) -> Vec<(Option<usize>, &CellMap)> {
+		self.maps.iter().filter(|m| m.column == "*").map(|m| (None, m)).collect()
+	}
+
+	// Whether the record is a comment or blank line, which isn't read.
+	fn skips(&self, record: &csv::StringRecord) -> bool {
+		self.comment.is_some_and(|c| {
+			record.iter().all(|cell| clean_cell(cell).is_empty()) ||
+			record.get(0).is_some_and(|cell| clean_cell(cell).starts_with(c))
+		})
+	}
+
+	// The filters and columns as indices into the header's columns.
+	fn resolve(&self, header: &csv::StringRecord, incols: usize, outcols: usize) ->
+		Result<Resolved<'_>, String> {
+		let names: Vec<&str> = header.iter().map(clean_cell).collect();
+		let find = |name: &str| names.iter().position(|&n| n == name).ok_or_else(|| {
+			// not the blank columns between the inputs and the outputs.
+			let named: Vec<&str> = names.iter().cloned().filter(|n| !n.is_empty())
+				.collect();
+			format!("no column named '{}' in the header.{}", name,
+			        did_you_mean(name, &named))
+		});
+		let mut filters = vec![];
+		for f in self.filters.iter() {
+			filters.push((find(&f.column)?, f));
+		}
+		let mut maps = self.wildcards();
+		for m in self.maps.iter().filter(|m| m.column != "*") {
+			maps.push((Some(find(&m.column)?), m));
+		}
+		let freq = match self.freq {
+			None => None,
+			Some(ref c) => match (find(c), c.parse::<usize>()) {
+				(Ok(col), _) | (Err(_), Ok(col)) => Some(col),
+				(Err(e), Err(_)) => return Err(e),
+			},
+		};
+		if freq.is_some_and(|col| col < incols) {
+			return Err("the --freq-col column is one of the inputs.".to_string());
+		}
+		if self.ocols.is_empty() {
+			return Ok(Resolved{filters, ocols: None, freq, maps});
+		}
+		if self.ocols.len() != outcols {
+			return Err(format!("--ocol names {} column(s), but the outputs take {}.",
+			                   self.ocols.len(), outcols));
+		}
+		let ocols: Vec<usize> = self.ocols.iter()
+			.map(|c| find(c).or_else(|e| c.parse::<usize>().map_err(|_| e)))
+			.collect::<Result<_, _>>()?;
+		if freq.is_some_and(|col| ocols.contains(&col)) {
+			return Err("the --freq-col column is one of the outputs.".to_string());
+		}
+		Ok(Resolved{filters, ocols: Some(ocols), freq, maps})
+	}
+}
+
+// An error in the table contents, located by its (1-based) line number, or 0
+// when it isn't on any one line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+	pub line: usize,
+	pub msg: String,
+	// whether the line could be read but doesn't make a valid row (too few
+	// columns, a repeated or out of range input, an output too wide), rather
+	// than a cell being unreadable.
+	pub invalid: bool,
+}
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.line {
+			0 => write!(f, "{}", self.msg),
+			n => write!(f, "line {}: {}", n, self.msg),
+		}
+	}
+}
+
+// parses a truth table in a CSV file with
+//   NHEADER header (ignored) rows
+//   NIN inputs as the leftmost NIN columns
+//   NOUT outputs as the rightmost NOUT columns
+pub fn parse<T: std::io::Read>(data: T, nheader: usize, nin: usize, nout: usize) ->
+	Truth {
+	// the tests' tables are well-formed, and binary cells only ever warn.
+	parse_encoded(data, nheader, nin, nout, InputEncoding::Binary,
+	              OutputEncoding::Binary).unwrap()
+}
+
+// What a cell that a table's reader warned about was taken as.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum WarningKind {
+	// an input cell other than 0 or 1: the row was skipped.
+	BadInput,
+	// an output cell other than 0 or 1: the row's input is a don't-care.
+	BadOutput,
+	// with Cells::Lenient, a cell that isn't an integer: it was taken as 0.
+	IgnoredInput,
+	IgnoredOutput,
+}
+
+// A cell the reader took some meaning for, rather than failing: where it is,
+// with the column counted from 0, and what it held.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Warning {
+	pub kind: WarningKind,
+	pub line: usize,
+	pub column: usize,
+	pub text: String,
+}
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let (what, problem, taken) = match self.kind {
+			WarningKind::BadInput => ("input", "0 or 1", "skipping the row"),
+			WarningKind::BadOutput =>
+				("output", "0 or 1", "taking it as a don't-care on the row"),
+			WarningKind::IgnoredInput => ("input", "an integer", "taking it as 0"),
+			WarningKind::IgnoredOutput => ("output", "an integer", "taking it as 0"),
+		};
+		write!(f, "line {}: {} '{}' in column {} is not {}; {}.", self.line, what,
+		       self.text, self.column, problem, taken)
+	}
+}
+
+// A table read, and the warnings about it.
+#[derive(Clone, Debug)]
+pub struct ParseOutcome {
+	pub truth: Truth,
+	pub warnings: Vec<Warning>,
+	// the line each don't-care was read from; the table's rows carry their
+	// own.
+	pub dc_lines: Vec<usize>,
+	// with a --freq-col, how often the input of each row occurs, by its line.
+	pub frequencies: HashMap<usize, f64>,
+}
+
+// as parse, but with the inputs and outputs stored according to 'inenc' and
+// 'outenc'.  NIN and NOUT are always the number of bits, even when they are
+// packed into a single column.  Any warnings are dropped; parse_cells returns
+// them.
+pub fn parse_encoded<T: std::io::Read>(data: T, nheader: usize, nin: usize,
+                                   nout: usize, inenc: InputEncoding,
+                                   outenc: OutputEncoding) ->
+	Result<Truth, ParseError> {
+	parse_cells(data, nheader, nin, nout, inenc, outenc, Cells::Warn, None)
+		.map(|o| o.truth)
+}
+
+// as parse_encoded, with cells other than 0 and 1 taken as 'cells' says, and
+// a warning for each of them.  Empty cells are taken as 'empty' says, without
+// a warning, or when it is None as 'cells' says.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_cells<T: std::io::Read>(data: T, nheader: usize, nin: usize,
+                                 nout: usize, inenc: InputEncoding,
+                                 outenc: OutputEncoding, cells: Cells,
+                                 empty: Option<Empty>) ->
+	Result<ParseOutcome, ParseError> {
+	parse_layout(data, nheader, nin, nout, inenc, outenc, cells, empty, &Layout::default())
+}
+
+// as parse_cells, reading only the rows the layout's filters let through,
+// and the outputs from the columns it names.  The rows left out are as if
+// the table didn't have them, so their inputs are missing.
+#[allow(clippy::too_many_arguments)]
+pub fn parse_layout<T: std::io::Read>(data: T, nheader: usize, nin: usize,
+                                  nout: usize, inenc: InputEncoding,
+                                  outenc: OutputEncoding, cells: Cells,
+                                  empty: Option<Empty>, layout: &Layout) ->
+	Result<ParseOutcome, ParseError> {
+	let mut tbl = Truth::default();
+	let mut warnings: Vec<Warning> = vec![];
+	let mut dc_lines: Vec<usize> = vec![];
+	let mut frequencies: HashMap<usize, f64> = HashMap::new();
+	read_rows(data, nheader, nin, nout, inenc, outenc, cells, empty, layout, &mut warnings,
+	          |line, input, output, freq| {
+		if let Some(f) = freq {
+			frequencies.insert(line, f);
+		}
+		if dont_care_row(&output) {
+			tbl.dc.push(input);
+			dc_lines.push(line);
+			return;
+		}
+		let mut ent = Entry::from_cells(input, &output);
+		ent.sources.push(RowSource{file: String::new(), line});
+		tbl.table.push(ent);
+	})?;
+	Ok(ParseOutcome{truth: tbl, warnings, dc_lines, frequencies})
+}
+
+// as parse_encoded, but into the packed representation.
+pub fn parse_packed<T: std::io::Read>(data: T, nheader: usize, nin: usize,
+                                  nout: usize, inenc: InputEncoding,
+                                  outenc: OutputEncoding) ->
+	Result<packed::PackedTruth, ParseError> {
+	let mut tbl = packed::PackedTruth::new(nin, nout);
+	read_rows(data, nheader, nin, nout, inenc, outenc, Cells::Warn, None,
+	          &Layout::default(), &mut vec![], |_, input, output, _| {
+		if dont_care_row(&output) {
+			tbl.dc.push(input);
+		} else {
+			tbl.push_entry(&Entry::from_cells(input, &output));
+		}
+	})?;
+	tbl.shrink_to_fit();
+	Ok(tbl)
+}
+
+// Whether a row's output cells leave every output a don't-care, so that its
+// input belongs with the table's don't-cares rather than among its rows.  A
+// row of a table without outputs is a row.
+fn dont_care_row(cells: &[Option<bool>]) -> bool {
+	!cells.is_empty() && cells.iter().all(|c| c.is_none())
+}
+
+// Reads a cell of a binary column as 'cells' says, adding to 'warnings' if
+// need be.  None when the cell makes its output a don't-care on the row, or
+// for an input cell, makes the row skipped.
+fn read_cell(text: &str, output: bool, line: usize, col: usize, cells: Cells,
+             empty: Option<Empty>, warnings: &mut Vec<Warning>) ->
+	Result<Option<bool>, ParseError> {
+	let text = clean_cell(text);
+	let what = if output { "output" } else { "input" };
+	match (text, empty) {
+		("", Some(Empty::DontCare)) => return Ok(None),
+		("", Some(Empty::Zero)) => return Ok(Some(false)),
+		("", Some(Empty::Error)) => return Err(ParseError{line, msg: format!(
+			"{} cell in column {} is empty", what, col), invalid: false}),
+		_ => {},
+	}
+	if text.eq_ignore_ascii_case("true") {
+		return Ok(Some(true));
+	} else if text.eq_ignore_ascii_case("false") {
+		return Ok(Some(false));
+	}
+	let mut warn = |kind| warnings.push(Warning{kind, line, column: col,
+	                                            text: text.to_string()});
+	if cells == Cells::Lenient {
+		return Ok(Some(match text.parse::<i32>() {
+			Ok(b) => b != 0,
+			Err(_) => {
+				warn(if output { WarningKind::IgnoredOutput }
+				     else { WarningKind::IgnoredInput });
+				false
+			},
+		}));
+	}
+	match text {
+		"0" => return Ok(Some(false)),
+		"1" => return Ok(Some(true)),
+		_ => {},
+	}
+	if cells == Cells::Strict {
+		return Err(ParseError{line, msg: format!(
+			"{} '{}' in column {} is not 0 or 1", what, text, col), invalid: false});
+	}
+	warn(if output { WarningKind::BadOutput } else { WarningKind::BadInput });
+	Ok(None)
+}
+
+// A cell as spreadsheet exports write it, without the whitespace around it,
+// a UTF-8 byte order mark, or the quotes around it the CSV reader leaves when
+// they don't start the field, as in ' "1"'.
+pub fn clean_cell(text: &str) -> &str {
+	let text = text.trim_start_matches('\u{feff}').trim();
+	if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+		text[1..text.len() - 1].trim()
+	} else {
+		text
+	}
+}
+
+// The data with a space in each empty line, when 'spaced': the CSV reader
+// skips empty lines but gives the next record the line of the first of them,
+// and a line with a space is a record, which the layout then skips.
+struct Spaced<T> {
+	data: T,
+	spaced: bool,
+	buf: Vec<u8>,
+	pos: usize,
+	// whether the next byte starts a line.
+	line_start: bool,
+}
+
+impl<T: std::io::Read> std::io::Read for Spaced<T> {
+	fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+		if !self.spaced {
+			return self.data.read(out);
+		}
+		if self.pos == self.buf.len() {
+			let mut chunk = [0u8; 8192];
+			let n = self.data.read(&mut chunk)?;
+			self.buf.clear();
+			self.pos = 0;
+			for &b in chunk[..n].iter() {
+				if self.line_start && (b == b'\n' || b == b'\r') {
+					self.buf.push(b' ');
+				}
+				self.buf.push(b);
+				self.line_start = b == b'\n';
+			}
+		}
+		let n = std::cmp::min(out.len(), self.buf.len() - self.pos);
+		out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+		self.pos += n;
+		Ok(n)
+	}
+}
+
+// Reads the rows of a table, handing each row's line, inputs and outputs to
+// 'row' as it goes, each output None when it is a don't-care.  The rows
+// the layout filters out aren't read at all.
+#[allow(clippy::too_many_arguments)]
+fn read_rows<T: std::io::Read, F: FnMut(usize, Vec<bool>, Vec<Option<bool>>, Option<f64>)>(
+	data: T, nheader: usize, nin: usize, nout: usize, inenc: InputEncoding,
+	outenc: OutputEncoding, cells: Cells, empty: Option<Empty>, layout: &Layout,
+	warnings: &mut Vec<Warning>, mut row: F) -> Result<(), ParseError> {
+	// rows of the wrong length are reported below, with what was expected.
+	let mut rdr = csv::ReaderBuilder::new()
+		.has_headers(false)
+		.flexible(true)
+		.from_reader(Spaced{data, spaced: layout.comment.is_some(), buf: vec![],
+		                    pos: 0, line_start: true});
+	// one record, reused for every line.
+	let mut record = csv::StringRecord::new();
+	// the line of the file, counting from 1, and how many of those up to it
+	// aren't comments or blank.
+	let mut line: usize = 0;
+	let mut read: usize = 0;
+	let incols = match inenc {
+		InputEncoding::Binary => nin,
+		InputEncoding::Index{..} => 1,
+		InputEncoding::Row{..} => 0,
+	};
+	let outcols = match outenc {
+		OutputEncoding::Binary => nout,
+		OutputEncoding::Decimal{..} => 1,
+	};
+	if nheader == 0 && !layout.is_default() {
+		let msg = "--filter, --filter-out, --ocol, --freq-col and --map of a column \
+		           need a header line naming the columns";
+		return Err(ParseError{line: 0, msg: msg.to_string(), invalid: false});
+	}
+	let mut filters: Vec<(usize, &Filter)> = vec![];
+	let mut ocols: Option<Vec<usize>> = None;
+	let mut fcol: Option<usize> = None;
+	let mut maps = layout.wildcards();
+	let mut ncols = incols + outcols;
+	loop {
+		match rdr.read_record(&mut record) {
+			Ok(true) => {},
+			Ok(false) => break,
+			Err(e) => {
+				let line = e.position().map_or(line + 1, |p| p.line() as usize);
+				return Err(ParseError{line, msg: e.to_string(), invalid: false});
+			},
+		}
+		// the reader skips empty lines itself, but knows where it is.
+		line = record.position().map_or(line + 1, |p| p.line() as usize);
+		if layout.skips(&record) {
+			continue;
+		}
+		read += 1;
+		if read == 1 && !layout.is_default() {
+			let r = layout.resolve(&record, incols, outcols)
+				.map_err(|msg| ParseError{line, msg, invalid: false})?;
+			filters = r.filters;
+			let least = if r.ocols.is_some() { incols } else { incols + outcols };
+			ncols = r.ocols.iter().flat_map(|o| o.iter()).chain(r.freq.iter())
+				.map(|&c| c + 1).fold(least, std::cmp::max);
+			ocols = r.ocols;
+			fcol = r.freq;
+			maps = r.maps;
+		}
+		if read <= nheader { // skip header lines.
+			continue;
+		}
+		if !filters.iter().all(|&(col, f)| {
+			record.get(col).is_some_and(|cell| clean_cell(cell) == f.value) == f.keep
+		}) {
+			continue;
+		}
+		if record.len() < ncols {
+			return Err(ParseError{line, msg: format!(
+				"expected at least {} columns, found {}", ncols, record.len()),
+				invalid: true});
+		}
+		let index = match inenc {
+			InputEncoding::Binary => None,
+			InputEncoding::Index{order} => match clean_cell(&record[0]).parse::<u64>() {
+				Ok(v) => Some((v, order)),
+				Err(e) => return Err(ParseError{line, msg: format!(
+					"input index '{}' is not a non-negative integer ({})", &record[0], e), invalid: false}),
+			},
+			InputEncoding::Row{order} =>
+				Some(((read - nheader - 1) as u64, order)),
+		};
+		// the row stands for every input its cells mapped to don't-cares give.
+		let inputs = match index {
+			None => {
+				let mut cube: Vec<Option<bool>> = Vec::with_capacity(nin);
+				let mut skipped = false;
+				for i in 0..nin {
+					if let Some(b) = mapped(&maps, i, clean_cell(&record[i])) {
+						cube.push(b);
+						continue;
+					}
+					match read_cell(&record[i], false, line, i, cells, empty, warnings)? {
+						Some(b) => cube.push(Some(b)),
+						None => skipped = true,
+					}
+				}
+				if skipped {
+					continue;
+				}
+				let open = cube.iter().filter(|b| b.is_none()).count();
+				if open > MAX_ENUMERATED_BITS {
+					return Err(ParseError{line, msg: format!(
+						"--map makes {} input cells don't-cares; at most {} are supported",
+						open, MAX_ENUMERATED_BITS), invalid: true});
+				}
+				formats::expand(&cube).expect("few enough don't-care inputs")
+			},
+			Some((idx, order)) => {
+				if nin < 64 && idx >> nin != 0 {
+					return Err(ParseError{line, msg: format!(
+						"input index {} is out of range for {} input bits", idx, nin), invalid: true});
+				}
+				vec![bit_unpack(idx, nin, order)]
+			},
+		};
+
+		let output = match outenc {
+			// we take the right*most* NOUT columns for the outputs, unless the
+			// layout names them.  Note that this is not columns nin through
+			// nin+nout: there could be "spacer" columns between the inputs and
+			// outputs.
+			OutputEncoding::Binary => {
+				let mut bits: Vec<Option<bool>> = Vec::with_capacity(nout);
+				for k in 0..nout {
+					let j = ocols.as_ref().map_or(record.len() - nout + k, |cols| cols[k]);
+					bits.push(match mapped(&maps, j, clean_cell(&record[j])) {
+						Some(b) => b,
+						None =>
+							read_cell(&record[j], true, line, j, cells, empty, warnings)?,
+					});
+				}
+				bits
+			},
+			OutputEncoding::Decimal{order} => {
+				let j = ocols.as_ref().map_or(record.len() - 1, |cols| cols[0]);
+				let value = match clean_cell(&record[j]).parse::<u64>() {
+					Ok(v) => v,
+					Err(e) => return Err(ParseError{line, msg: format!(
+						"output value '{}' in column {} is not a non-negative integer ({})",
+						&record[j], j, e), invalid: false}),
+				};
+				if nout < 64 && value >> nout != 0 {
+					return Err(ParseError{line, msg: format!(
+						"output value {} does not fit in {} bits", value, nout), invalid: true});
+				}
+				bit_unpack(value, nout, order).into_iter().map(Some).collect()
+			},
+		};
+		// missing and zero frequencies count once.
+		let freq = match fcol {
+			None => None,
+			Some(j) if ocols.is_none() && j + outcols >= record.len() =>
+				return Err(ParseError{line, msg: "the --freq-col column is one of \
+				                                        the outputs".to_string(),
+				                      invalid: false}),
+			Some(j) => match clean_cell(&record[j]) {
+				"" => Some(1.0),
+				text => match text.parse::<f64>() {
+					Ok(0.0) => Some(1.0),
+					Ok(f) if f > 0.0 && f.is_finite() => Some(f),
+					_ => return Err(ParseError{line, msg: format!(
+						"frequency '{}' in column {} is not a non-negative number", text,
+						j), invalid: false}),
+				},
+			},
+		};
+		for input in inputs {
+			row(line, input, output.clone(), freq);
+		}
+	}
+	Ok(())
+}
+
+// Writes the table as a CSV that parse_encoded reads back with the same
+// encodings: a line of column names and a line giving each column's role,
+// then the rows, inputs and outputs separated by an empty column.  With
+// InputEncoding::Row the table must list every input pattern.
+pub fn write_encoded<W: std::io::Write>(wrt: &mut W, tbl: &Truth, invars: &[String],
+                                        outvars: &[String], inenc: InputEncoding,
+                                        outenc: OutputEncoding) -> Result<(), String> {
+	let nin = invars.len();
+	let mut rows: Vec<&Entry> = tbl.table.iter().collect();
+	if let InputEncoding::Row{order} = inenc {
+		if nin > MAX_ENUMERATED_BITS || rows.len() != 1 << nin {
+			return Err(format!("Only a table listing all {} input patterns can be \
+			                    written by row.", 1u64 << std::cmp::min(nin, 63)));
+		}
+		rows.sort_by_key(|e| bit_pack(&e.input, order));
+		if rows.windows(2).any(|w| w[0].input == w[1].input) {
+			return Err("The table lists an input pattern more than once.".to_string());
+		}
+	}
+	write_header(wrt, invars, outvars, inenc, outenc)?;
+	write_rows(wrt, rows.into_iter(), inenc, outenc)
+}
+
+// The two header lines of write_encoded, for writers giving the rows
+// themselves.
+pub fn write_header<W: std::io::Write>(wrt: &mut W, invars: &[String],
+                                       outvars: &[String], inenc: InputEncoding,
+                                       outenc: OutputEncoding) -> Result<(), String> {
+	let nin = invars.len();
+	let (mut names, mut roles): (Vec<String>, Vec<&str>) = match inenc {
+		InputEncoding::Binary => (invars.to_vec(), vec!["in"; nin]),
+		InputEncoding::Index{..} => (vec![invars.join(" ")], vec!["in"]),
+		InputEncoding::Row{..} => (vec![], vec![]),
+	};
+	if !names.is_empty() {
+		names.push(String::new());
+		roles.push("");
+	}
+	match outenc {
+		OutputEncoding::Binary => {
+			names.extend(outvars.iter().cloned());
+			roles.extend(vec!["out"; outvars.len()]);
+		},
+		OutputEncoding::Decimal{..} if outvars.len() > 64 =>
+			return Err(format!("{} outputs don't fit in a decimal column; at most 64 do.",
+			                   outvars.len())),
+		OutputEncoding::Decimal{..} => {
+			names.push(outvars.join(" "));
+			roles.push("out");
+		},
+	}
+	writeln!(wrt, "{}\n{}", names.join(","), roles.join(",")).map_err(|e| e.to_string())
+}
+
+// Writes the rows as write_encoded does, after write_header; with
+// InputEncoding::Row they must be every input pattern in order.
+pub fn write_rows<'a, W: std::io::Write, I: Iterator<Item = &'a Entry>>(
+	wrt: &mut W, rows: I, inenc: InputEncoding, outenc: OutputEncoding) ->
+	Result<(), String> {
+	let bits = |values: &[bool]| -> Vec<String> {
+		values.iter().map(|&b| if b { "1" } else { "0" }.to_string()).collect()
+	};
+	for ent in rows {
+		let mut cells: Vec<String> = match inenc {
+			InputEncoding::Binary => bits(&ent.input),
+			InputEncoding::Index{order} => vec![bit_pack(&ent.input, order).to_string()],
+			InputEncoding::Row{..} => vec![],
+		};
+		if !cells.is_empty() {
+			cells.push(String::new());
+		}
+		match outenc {
+			OutputEncoding::Binary => cells.extend(bits(&ent.output)),
+			OutputEncoding::Decimal{order} =>
+				cells.push(bit_pack(&ent.output, order).to_string()),
+		}
+		writeln!(wrt, "{}", cells.join(",")).map_err(|e| e.to_string())?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use std::time::Instant;
+
+	fn example_head() -> String {
+		let s = ",COMPONENTS,,,HAVE,,,,,REQUIRED_VARS includes,,,\n".to_string() +
+			"REQUIRED,OGL,GLX,EGL,OGL,GLX,EGL,GL,,OGL,GLX,EGL,GL\n" +
+			"0,0,0,0,0,0,0,0,,1,1,0,0\n" +
+			"0,0,0,0,0,0,0,1,,0,0,0,1\n";
+		s
+	}
+
+	// a faux example with just 3 inputs and 2 outputs, for validation against.
+	// if the inputs are 'a','b','c' and the outputs are 'x','y', then the
+	// basic solution is:
+	//   x = a'b'c + a'bc' + ab'c' + abc'
+	//   y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
+	// i.e. a solution of:
+	//   x = y = 0
+	//   if(a'bc'): x = y = 1
+	//   if(abc'): x = y = 1
+
+	//   if(ab'c'): x = y = 1
+	//   if(ab'c): y = 1
+
+	//   if(a'b'c): x = 1
+	//   if(a'b'c'): y = 1
+	// that can be simplified to:
+	//   if(a'b'):
+	//    if(c): x = 1
+	//    else if(c'): y = 1
+	//   if(ab'):
+	//    y = 1
+	//    if(c'): x = 1
+	//   if(bc'): x = y = 1
+	fn small_example() -> String {
+		let s =
+			"0,0,0,,0,1\n".to_string() +
+			"0,0,1,,1,0\n" +
+			"0,1,0,,1,1\n" +
+			"0,1,1,,0,0\n" +
+			"1,0,0,,1,1\n" +
+			"1,0,1,,0,1\n" +
+			"1,1,0,,1,1\n" +
+			"1,1,1,,0,0\n";
+		s
+	}
+
+	#[test]
+	fn read_test() {
+		let eg = example_head();
+		let tbl = parse(eg.as_bytes(), 2, 8, 4);
+		// should be the same number of lines:
+		assert_eq!(tbl.len(), 2);
+	}
+
+	#[test]
+	fn parse_small() {
+		let small = small_example();
+		let truth = parse(small.as_bytes(), 0, 3, 2);
+		assert_eq!(truth.len(), 8);
+	}
+
+	// small_example with x and y packed LSB-first into one decimal column.
+	fn small_decimal() -> String {
+		"0,0,0,2\n0,0,1,1\n0,1,0,3\n0,1,1,0\n\
+		 1,0,0,3\n1,0,1,2\n1,1,0,3\n1,1,1,0\n".to_string()
+	}
+
+	#[test]
+	fn parse_decimal_outputs() {
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let binary = parse(small_example().as_bytes(), 0, 3, 2);
+		let lsb = parse_encoded(small_decimal().as_bytes(), 0, 3, 2,
+		                        InputEncoding::Binary,
+		                        OutputEncoding::Decimal{order: BitOrder::Lsb}).unwrap();
+		assert_eq!(lsb.table, binary.table);
+		let mut expect = equations(&binary, vec!["x", "y"], ivar.clone());
+		let mut got = equations(&lsb, vec!["x", "y"], ivar.clone());
+		for (e, g) in expect.iter_mut().zip(got.iter_mut()) {
+			e.simplify();
+			g.simplify();
+			assert_eq!(format!("{}", e), format!("{}", g));
+		}
+
+		// MSB-first swaps the roles of the two bits.
+		let msb = parse_encoded(small_decimal().as_bytes(), 0, 3, 2,
+		                        InputEncoding::Binary,
+		                        OutputEncoding::Decimal{order: BitOrder::Msb}).unwrap();
+		for (m, b) in msb.table.iter().zip(binary.table.iter()) {
+			assert_eq!(m.output, vec![b.output[1], b.output[0]]);
+		}
+	}
+
+	#[test]
+	fn decimal_output_too_wide() {
+		let csv = "0,0,0,1\n0,0,1,4\n";
+		let err = parse_encoded(csv.as_bytes(), 0, 3, 2, InputEncoding::Binary,
+		                        OutputEncoding::Decimal{order: BitOrder::Lsb});
+		assert_eq!(err.unwrap_err().line, 2);
+		let err = parse_encoded("0,0,0,-1\n".as_bytes(), 0, 3, 2,
+		                        InputEncoding::Binary,
+		                        OutputEncoding::Decimal{order: BitOrder::Lsb});
+		assert_eq!(err.unwrap_err().line, 1);
+	}
+
+	// small_example with the inputs given as an index column, shuffled.
+	fn small_indexed() -> String {
+		"4,1,1\n0,0,1\n7,0,0\n1,1,0\n2,1,1\n5,0,1\n3,0,0\n6,1,1\n".to_string()
+	}
+
+	#[test]
+	fn parse_index_inputs() {
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let binary = parse(small_example().as_bytes(), 0, 3, 2);
+		let indexed = parse_encoded(small_indexed().as_bytes(), 0, 3, 2,
+		                            InputEncoding::Index{order: BitOrder::Msb},
+		                            OutputEncoding::Binary).unwrap();
+		for ent in binary.table.iter() {
+			assert_eq!(indexed.solution(ent.input.clone()), ent.output);
+		}
+		let mut expect = equations(&binary, vec!["x", "y"], ivar.clone());
+		let mut got = equations(&indexed, vec!["x", "y"], ivar.clone());
+		for (e, g) in expect.iter_mut().zip(got.iter_mut()) {
+			e.simplify();
+			g.simplify();
+			assert!(binary.table.iter().all(|ent| {
+				e.evaluate(&ent.input) == g.evaluate(&ent.input)
+			}));
+		}
+
+		// with implicit row positions the outputs alone suffice.
+		let outputs: String = small_example().lines()
+			.map(|l| l[6..].to_string() + "\n").collect();
+		let rows = parse_encoded(outputs.as_bytes(), 0, 3, 2,
+		                         InputEncoding::Row{order: BitOrder::Msb},
+		                         OutputEncoding::Binary).unwrap();
+		assert_eq!(rows.table, binary.table);
+	}
+
+	#[test]
+	fn bad_index_inputs() {
+		// a repeated index is read; complete::resolve_duplicates reports it.
+		let dup = "0,1\n1,0\n1,1\n";
+		let mut outcome = parse_cells(dup.as_bytes(), 1, 2, 1,
+		                              InputEncoding::Index{order: BitOrder::Msb},
+		                              OutputEncoding::Binary, Cells::Warn, None).unwrap();
+		let lines: Vec<Option<usize>> = outcome.truth.table.iter().map(|e| e.line())
+			.collect();
+		assert_eq!(lines, vec![Some(2), Some(3)]);
+		let err = complete::resolve_duplicates(&mut outcome, complete::Duplicates::Error)
+			.unwrap_err();
+		assert!(err.contains("01 on lines 2, 3"), "{}", err);
+		let range = "0,1\n4,0\n";
+		let err = parse_encoded(range.as_bytes(), 0, 2, 1,
+		                        InputEncoding::Index{order: BitOrder::Lsb},
+		                        OutputEncoding::Binary).unwrap_err();
+		assert_eq!(err.line, 2);
+		let rows = "1\n0\n1\n";
+		let err = parse_encoded(rows.as_bytes(), 0, 1, 1,
+		                        InputEncoding::Row{order: BitOrder::Lsb},
+		                        OutputEncoding::Binary).unwrap_err();
+		assert_eq!(err.line, 3);
+	}
+
+	#[test]
+	fn non_binary_cells() {
+		let read = |csv: &str, cells| parse_cells(csv.as_bytes(), 0, 2, 1,
+		                                          InputEncoding::Binary,
+		                                          OutputEncoding::Binary, cells, None);
+		// a 2 or -1 output is a don't-care on its row; it is the only output, so
+		// the row's input is a don't-care.
+		for bad in ["2", "-1"].iter() {
+			let csv = format!("0,0,,1\n0,1,,{}\n1,0,,0\n", bad);
+			let tbl = read(&csv, Cells::Warn).unwrap().truth;
+			assert_eq!(tbl.len(), 2);
+			assert_eq!(tbl.dc, vec![vec![false, true]]);
+			assert_eq!(read(&csv, Cells::Strict).unwrap_err(),
+			           ParseError{line: 2, msg: format!("output '{}' in column 3 is not \
+			                                             0 or 1", bad), invalid: false});
+			// as they used to be, they are 1.
+			let tbl = read(&csv, Cells::Lenient).unwrap().truth;
+			assert_eq!(tbl.solution(vec![false, true]), vec![true]);
+			assert!(tbl.dc.is_empty());
+		}
+		// a row with a bad input is skipped.
+		let tbl = read("0,0,,1\n2,1,,1\n", Cells::Warn).unwrap().truth;
+		assert_eq!((tbl.len(), tbl.dc.len()), (1, 0));
+		assert_eq!(read("0,0,,1\n2,1,,1\n", Cells::Strict).unwrap_err().msg,
+		           "input '2' in column 0 is not 0 or 1");
+		// whitespace around a cell doesn't matter.
+		for &cells in [Cells::Warn, Cells::Strict, Cells::Lenient].iter() {
+			let got = read(" 1 ,0,, 1 \n", cells).unwrap();
+			assert_eq!(got.truth.table, vec![Entry::new(vec![true, false], vec![true])]);
+			assert!(got.warnings.is_empty());
+		}
+	}
+
+	// A bad output cell is a don't-care for its own output alone.
+	#[test]
+	fn bad_cell_leaves_other_outputs() {
+		let csv = "0,0,,0,0\n0,1,,2,1\n1,0,,0,0\n1,1,,1,0\n";
+		let got = parse_cells(csv.as_bytes(), 0, 2, 2, InputEncoding::Binary,
+		                      OutputEncoding::Binary, Cells::Warn, None).unwrap();
+		let tbl = got.truth;
+		assert_eq!(got.warnings.len(), 1);
+		assert!(tbl.dc.is_empty());
+		assert_eq!(tbl.table[1].cells(), vec![None, Some(true)]);
+		assert_eq!(tbl.minterm_indices(1).collect::<Vec<u64>>(), vec![1]);
+		assert_eq!(tbl.maxterm_indices(0).collect::<Vec<u64>>(), vec![0, 2]);
+		assert_eq!(tbl.dont_cares_of(0).collect::<Vec<&Vec<bool>>>(),
+		           vec![&vec![false, true]]);
+		assert_eq!(tbl.dont_cares_of(1).count(), 0);
+		let mut eqns = equations(&tbl, vec!["x", "y"], vec!["a".into(), "b".into()]);
+		eqns.iter_mut().for_each(Equation::simplify);
+		assert_eq!(eqns[0].to_string(), "x = b + ;");
+		assert_eq!(eqns[1].to_string(), "y = a'b + ;");
+		assert!(eqns.iter().all(|e| e.counterexamples(&tbl).is_empty()));
+		// the row prints with x for the don't-care.
+		assert_eq!(tbl.table[1].to_string(), "0 1 -> x 1");
+	}
+
+	// Tables as spreadsheets export them read as the clean table does.
+	#[test]
+	fn spreadsheet_exports() {
+		let clean = parse(small_example().as_bytes(), 0, 3, 2);
+		let read = |csv: &str, nheader, empty| parse_cells(csv.as_bytes(), nheader, 3, 2,
+		                                                   InputEncoding::Binary,
+		                                                   OutputEncoding::Binary,
+		                                                   Cells::Strict, empty);
+		// padded and quoted cells, and TRUE and FALSE.
+		let got = read(include_str!("../tests/fixtures/spreadsheet-padded.csv"), 2, None)
+			.unwrap();
+		assert!(got.warnings.is_empty());
+		assert_eq!((got.truth.table, got.truth.dc), (clean.table.clone(), vec![]));
+		// a byte order mark before the first row.
+		let got = read(include_str!("../tests/fixtures/spreadsheet-bom.csv"), 0, None)
+			.unwrap();
+		assert_eq!(got.truth.table, clean.table);
+		// empty cells: x on 000 and b on 101.
+		let empty = include_str!("../tests/fixtures/spreadsheet-empty.csv");
+		let got = read(empty, 2, Some(Empty::Zero)).unwrap();
+		assert_eq!(got.truth.table, clean.table);
+		let got = read(empty, 2, Some(Empty::DontCare)).unwrap();
+		// y is still given on 000.
+		let listed: Vec<Entry> = clean.table.iter()
+			.filter(|e| e.input != vec![true, false, true])
+			.map(|e| match e.input == vec![false; 3] {
+				true => Entry::from_cells(e.input.clone(), &[None, Some(e.output[1])]),
+				false => e.clone(),
+			}).collect();
+		assert_eq!((got.truth.table, got.truth.dc), (listed, vec![]));
+		assert_eq!(read(empty, 2, Some(Empty::Error)).unwrap_err(),
+		           ParseError{line: 3, msg: "output cell in column 4 is empty".to_string(),
+		                      invalid: false});
+		assert_eq!(read(empty, 2, None).unwrap_err().msg,
+		           "output '' in column 4 is not 0 or 1");
+		assert!(Empty::parse("one").is_err());
+	}
+
+	// the small example with comment and blank lines among the header lines
+	// and the rows.
+	#[test]
+	fn comment_lines() {
+		let clean = parse(small_example().as_bytes(), 0, 3, 2);
+		let commented = include_str!("../tests/fixtures/spreadsheet-comments.csv");
+		let read = |csv: &str, inenc, comment| {
+			let layout = Layout{comment, ..Layout::default()};
+			parse_layout(csv.as_bytes(), 2, 3, 2, inenc, OutputEncoding::Binary,
+			             Cells::Strict, None, &layout)
+		};
+		let got = read(commented, InputEncoding::Binary, Some('#')).unwrap();
+		assert_eq!(got.truth.table, clean.table);
+		assert!(got.truth.dc.is_empty() && got.warnings.is_empty());
+		// the lines are those of the file.
+		let lines: Vec<usize> = got.truth.table.iter().map(|e| e.sources[0].line)
+			.collect();
+		assert_eq!(lines, vec![6, 7, 9, 10, 13, 14, 16, 17]);
+		// without them, the first comment is the first header line.
+		assert_eq!(read(commented, InputEncoding::Binary, None).unwrap_err(),
+		           ParseError{line: 3, msg: "input 'in' in column 0 is not 0 or 1"
+		                      .to_string(), invalid: false});
+		// nor with another character.
+		let semi = commented.replace('#', ";");
+		assert_eq!(read(&semi, InputEncoding::Binary, Some(';')).unwrap().truth.table,
+		           clean.table);
+		assert_eq!(read(&semi, InputEncoding::Binary, Some('#')).unwrap_err().line, 3);
+		let bad = commented.replace("1,0,1,,0,1", "1,0,1,,0,2");
+		assert_eq!(read(&bad, InputEncoding::Binary, Some('#')).unwrap_err().line, 14);
+		// a row's position is counted without them.
+		let rows = "x,y
+out,out
+# a = 0
+0,1
+1,0
+
+1,1
+0,0
+# a = 1
+1,1
+0,1
+1,1
+0,0
+";
+		let got = read(rows, InputEncoding::Row{order: BitOrder::Msb}, Some('#')).unwrap();
+		assert_eq!(got.truth.table, clean.table);
+		let cols = columns(commented.as_bytes(), 2, &Layout::default()).unwrap();
+		assert_eq!(cols, Columns{width: 6, blank: Some((3, 4))});
+	}
+
+	// a status column after the outputs, and notes after that.
+	#[test]
+	fn filtered_rows() {
+		let clean = parse(small_example().as_bytes(), 0, 3, 2);
+		let status = include_str!("../tests/fixtures/spreadsheet-status.csv");
+		let read = |layout: &Layout| parse_layout(status.as_bytes(), 2, 3, 2,
+		                                          InputEncoding::Binary,
+		                                          OutputEncoding::Binary, Cells::Strict,
+		                                          None, layout);
+		let layout = |filter: &str, keep| Layout{
+			filters: vec![Filter::parse(filter, keep).unwrap()],
+			ocols: vec!["x".to_string(), "y".to_string()], ..Layout::default()
+		};
+		// 100 is a draft, and so is the second row for 111.
+		let finals: Vec<Entry> = clean.table.iter()
+			.filter(|e| e.input != vec![true, false, false])
+			.cloned().collect();
+		assert_eq!(read(&layout("status=final", true)).unwrap().truth.table, finals);
+		assert_eq!(read(&layout("status = DRAFT", false)).unwrap().truth.table, finals);
+		assert_eq!(read(&layout("status=DRAFT", true)).unwrap().truth.len(), 2);
+		// without --ocol, the notes are taken as an output.
+		assert!(read(&Layout::default()).is_err());
+		assert_eq!(read(&layout("staus=final", true)).unwrap_err().to_string(),
+		           "line 1: no column named 'staus' in the header. Did you mean \
+		            'status'?");
+		// the blank column between the inputs and the outputs is no suggestion.
+		let named = "left,right,,sum\n-,-,,-\n0,1,,1\n";
+		let q = parse_layout(named.as_bytes(), 2, 2, 1, InputEncoding::Binary,
+		                     OutputEncoding::Binary, Cells::Strict, None,
+		                     &layout("q=1", true));
+		assert_eq!(q.unwrap_err().to_string(),
+		           "line 1: no column named 'q' in the header.");
+		let one = Layout{ocols: vec!["x".to_string()], ..Layout::default()};
+		assert_eq!(read(&one).unwrap_err().msg,
+		           "--ocol names 1 column(s), but the outputs take 2.");
+		assert!(parse_layout(small_example().as_bytes(), 0, 3, 2, InputEncoding::Binary,
+		                     OutputEncoding::Binary, Cells::Strict, None,
+		                     &layout("status=final", true)).is_err());
+		assert!(Filter::parse("final", true).is_err());
+	}
+
+	#[test]
+	fn mapped_cells() {
+		let csv = "OGL,GLX,,R\nYES,ON,,1\nNO,N/A,,0\nYES,OFF,,N/A\nyes,ON,,1\n";
+		let maps = |ms: &[&str]| Layout{
+			maps: ms.iter().map(|m| CellMap::parse(m).unwrap()).collect(),
+			..Layout::default()
+		};
+		let read = |cells, layout: &Layout| parse_layout(csv.as_bytes(), 1, 2, 1,
+		                                                 InputEncoding::Binary,
+		                                                 OutputEncoding::Binary, cells,
+		                                                 None, layout);
+		// the column's own N/A map wins over the wildcard; 'yes' isn't 'YES'.
+		let layout = maps(&["OGL:YES=1", "OGL:NO=0", "*:ON=1", "*:OFF=0", "*:N/A=0",
+		                    "R:N/A=x"]);
+		let got = read(Cells::Warn, &layout).unwrap();
+		let rows: Vec<(&Vec<bool>, &Vec<bool>)> = got.truth.table.iter()
+			.map(|e| (&e.input, &e.output)).collect();
+		assert_eq!(rows, vec![(&vec![true, true], &vec![true]),
+		                      (&vec![false, false], &vec![false])]);
+		assert_eq!(got.truth.dc, vec![vec![true, false]]);
+		assert_eq!(got.warnings,
+		           vec![Warning{kind: WarningKind::BadInput, line: 5, column: 0,
+		                        text: "yes".into()}]);
+		assert_eq!(read(Cells::Strict, &layout).unwrap_err().to_string(),
+		           "line 5: input 'yes' in column 0 is not 0 or 1");
+		// a map for every case passes --strict.
+		let mut all = layout.clone();
+		all.maps.push(CellMap::parse("OGL:yes=1").unwrap());
+		assert!(read(Cells::Strict, &all).unwrap().warnings.is_empty());
+		// a column map needs the header; wildcards don't.
+		let err = parse_layout("YES,1\n".as_bytes(), 0, 1, 1, InputEncoding::Binary,
+		                       OutputEncoding::Binary, Cells::Strict, None,
+		                       &maps(&["a:YES=1"])).unwrap_err();
+		assert!(err.msg.starts_with("--filter, --filter-out, --ocol, --freq-col and \
+		                             --map"));
+		// an x input stands for both values.
+		let got = parse_layout("-,1,,1\n0,0,,0\n".as_bytes(), 0, 2, 1,
+		                       InputEncoding::Binary, OutputEncoding::Binary,
+		                       Cells::Strict, None, &maps(&["*:-=x"])).unwrap();
+		let inputs: Vec<&Vec<bool>> = got.truth.table.iter().map(|e| &e.input).collect();
+		assert_eq!(inputs,
+		           vec![&vec![false, true], &vec![true, true], &vec![false, false]]);
+		assert_eq!(CellMap::parse(" a:b:c = X"),
+		           Ok(CellMap{column: "a".into(), value: "b:c".into(), to: None}));
+		assert!(CellMap::parse(":YES=1").is_err());
+		assert!(CellMap::parse("a:YES=yes").is_err());
+		assert!(CellMap::parse("a=1").is_err());
+	}
+
+	#[test]
+	fn parse_warnings() {
+		let csv = "a,b,,x\n0,0,,1\n0,?,,1\n1,0,,2\n1,1,,0\n";
+		let got = parse_cells(csv.as_bytes(), 1, 2, 1, InputEncoding::Binary,
+		                      OutputEncoding::Binary, Cells::Warn, None).unwrap();
+		assert_eq!(got.warnings, vec![
+			Warning{kind: WarningKind::BadInput, line: 3, column: 1, text: "?".into()},
+			Warning{kind: WarningKind::BadOutput, line: 4, column: 3, text: "2".into()},
+		]);
+		assert_eq!(got.warnings[0].to_string(),
+		           "line 3: input '?' in column 1 is not 0 or 1; skipping the row.");
+		assert_eq!((got.truth.len(), got.truth.dc.len()), (2, 1));
+		let got = parse_cells(csv.as_bytes(), 1, 2, 1, InputEncoding::Binary,
+		                      OutputEncoding::Binary, Cells::Lenient, None).unwrap();
+		assert_eq!(got.warnings, vec![
+			Warning{kind: WarningKind::IgnoredInput, line: 3, column: 1, text: "?".into()},
+		]);
+	}
+
+	#[test]
+	fn short_rows() {
+		let short = "0,0,0,,1,0\n0,0,1,1\n";
+		let err = parse_encoded(short.as_bytes(), 0, 3, 2, InputEncoding::Binary,
+		                        OutputEncoding::Binary).unwrap_err();
+		assert_eq!(err, ParseError{line: 2, msg: "expected at least 5 columns, \
+		                                          found 4".to_string(),
+		                              invalid: true});
+		let err = parse_encoded("1\n".as_bytes(), 0, 3, 2,
+		                        InputEncoding::Index{order: BitOrder::Lsb},
+		                        OutputEncoding::Decimal{order: BitOrder::Lsb}).unwrap_err();
+		assert_eq!(err.msg, "expected at least 2 columns, found 1");
+	}
+
+	#[test]
+	fn written_tables() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let invars: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+		let outvars: Vec<String> = vec!["x".into(), "y".into()];
+		let mut csv: Vec<u8> = vec![];
+		write_encoded(&mut csv, &truth, &invars, &outvars, InputEncoding::Binary,
+		              OutputEncoding::Binary).unwrap();
+		assert!(String::from_utf8_lossy(&csv).starts_with("a,b,c,,x,y\nin,in,in,,out,out\n\
+		                                                   0,0,0,,0,1\n"));
+		let encodings = [InputEncoding::Binary, InputEncoding::Index{order: BitOrder::Msb},
+		                 InputEncoding::Row{order: BitOrder::Lsb}];
+		let mut reversed = truth.clone();
+		reversed.table.reverse();
+		for &inenc in encodings.iter() {
+			for &outenc in [OutputEncoding::Binary,
+			                OutputEncoding::Decimal{order: BitOrder::Lsb}].iter() {
+				let mut csv: Vec<u8> = vec![];
+				write_encoded(&mut csv, &reversed, &invars, &outvars, inenc, outenc).unwrap();
+				let back = parse_encoded(&csv[..], HEADER_LINES, 3, 2, inenc, outenc).unwrap();
+				assert!(back.changed_rows(&truth).is_empty(), "{:?} {:?}", inenc, outenc);
+			}
+		}
+		// by row, every pattern needs a row.
+		let mut short = truth.clone();
+		short.table.pop();
+		assert!(write_encoded(&mut vec![], &short, &invars, &outvars,
+		                      InputEncoding::Row{order: BitOrder::Msb},
+		                      OutputEncoding::Binary).is_err());
+	}
+
+	// Parses a generated table of 2^20 rows; run with --ignored, preferably
+	// in release mode, to see the time it takes.
+	#[test]
+	#[ignore]
+	fn parse_million_rows() {
+		let nin = 20;
+		let mut binary = String::new();
+		let mut rows = String::new();
+		for i in 0..1u64 << nin {
+			for b in bit_unpack(i, nin, BitOrder::Msb) {
+				binary += if b { "1," } else { "0," };
+			}
+			let out = if i.count_ones() % 2 == 1 { "1\n" } else { "0\n" };
+			binary += out;
+			rows += out;
+		}
+		for &(csv, inenc) in [(&binary, InputEncoding::Binary),
+		                      (&rows, InputEncoding::Row{order: BitOrder::Msb})].iter() {
+			let start = Instant::now();
+			let tbl = parse_encoded(csv.as_bytes(), 0, nin, 1, inenc,
+			                        OutputEncoding::Binary).unwrap();
+			println!("{:?}: {} rows in {:?}", inenc, tbl.len(), start.elapsed());
+			assert_eq!(tbl.len(), 1 << nin);
+			assert_eq!(tbl.table[5].input, bit_unpack(5, nin, BitOrder::Msb));
+			assert!(tbl.table.iter().all(|e| e.input.capacity() == nin));
+		}
+	}
+
+	#[test]
+	fn minterms_and_maxterms() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		assert_eq!(truth.minterms(0).count(), 4);
+		assert_eq!(truth.minterms(1).count(), 5);
+		assert_eq!(truth.maxterms(1).count(), 3);
+		assert!(truth.minterms(1).all(|e| e.output[1]));
+		assert_eq!(truth.minterm_indices(0).collect::<Vec<u64>>(), vec![1, 2, 4, 6]);
+		assert_eq!(truth.maxterm_indices(1).collect::<Vec<u64>>(), vec![1, 3, 7]);
+		assert_eq!(truth.dont_cares().count(), 0);
+		let partial = Truth::from_fn_partial(2, 1, |inp| {
+			if inp[0] && inp[1] { None } else { Some(vec![inp[0]]) }
+		}).unwrap();
+		assert_eq!(partial.dont_care_indices().collect::<Vec<u64>>(), vec![3]);
+		assert_eq!(bit_pack(&[true, false, false], BitOrder::Msb), 4);
+	}
+
+	#[test]
+	fn tables_from_fn() {
+		let tbl = Truth::from_fn(2, 2, |inp| vec![inp[0] && inp[1], inp[0] || inp[1]])
+			.unwrap();
+		assert_eq!(tbl.table[1], Entry::new(vec![false, true], vec![false, true]));
+		let err = Truth::from_fn(2, 2, |inp| vec![inp[0]]).unwrap_err();
+		assert_eq!(err, "1 outputs for input [false, false]; expected 2.");
+		assert!(Truth::from_fn(MAX_ENUMERATED_BITS + 1, 1, |_| vec![true]).is_err());
+		// the don't-cares reach the equations.
+		let tbl = Truth::from_fn_partial(2, 1, |inp| {
+			if inp[0] { None } else { Some(vec![inp[1]]) }
+		}).unwrap();
+		let names = vec!["a".to_string(), "b".to_string()];
+		let mut eqns = equations(&tbl, vec!["x"], names);
+		assert_eq!(eqns[0].dc.len(), 2);
+		eqns[0].simplify();
+		assert_eq!(format!("{}", eqns[0]), "x = b + ;");
+	}
+
+	#[test]
+	fn indexed_rows() {
+		// in counting order, and then not: reversed, with a row missing and
+		// another repeated.
+		let tbl = Truth::from_fn(4, 1, |inp| vec![inp[1] && !inp[3]]).unwrap();
+		let mut shuffled = tbl.clone();
+		shuffled.table.reverse();
+		shuffled.table.remove(3);
+		shuffled.table.push(Entry::new(vec![true; 4], vec![true]));
+		for t in [&tbl, &shuffled].iter() {
+			let rows = t.index();
+			for i in 0..16 {
+				let inp = bit_unpack(i, 4, BitOrder::Msb);
+				let found = t.table.iter().position(|e| e.input == inp);
+				assert_eq!(rows.position(&inp), found, "{:?}", inp);
+				if found.is_some() {
+					assert_eq!(rows.solution(t, &inp), t.solution(inp.clone()).as_slice());
+				}
+			}
+			assert_eq!(rows.position(&[true, false]), None);
+		}
+	}
+
+	#[test]
+	fn printed_tables() {
+		let mut tbl = Truth::new(vec![vec![false, false, false], vec![false, false, true]],
+		                         vec![vec![false, true], vec![true, false]]);
+		for i in [3, 4, 5, 6, 7].iter() {
+			tbl.dc.push(bit_unpack(*i, 3, BitOrder::Msb));
+		}
+		assert_eq!(tbl.table[1].to_string(), "0 0 1 -> 1 0");
+		// 011 and 111 merge on the first input, then 100 and 110 on the second.
+		assert_eq!(tbl.to_string(), "0 0 0 -> 0 1\n0 0 1 -> 1 0\nx 1 1 -> x x\n\
+		                             1 x 0 -> x x\n1 0 1 -> x x\n");
+		let invars: Vec<String> = vec!["a".into(), "bus".into(), "c".into()];
+		let outvars: Vec<String> = vec!["x".into(), "valid".into()];
+		assert_eq!(tbl.display(&invars, &outvars).to_string(),
+		           "a bus c    x valid\n\
+		            0 0   0 -> 0 1\n\
+		            0 0   1 -> 1 0\n\
+		            x 1   1 -> x x\n\
+		            1 x   0 -> x x\n\
+		            1 0   1 -> x x\n");
+		assert_eq!(Truth::default().to_string(), "");
+	}
+
+	#[test]
+	fn output_name_expansion() {
+		assert_eq!(output_names(vec!["x", "y"], ""), Ok(vec!["x".to_string(),
+		                                                     "y".to_string()]));
+		assert_eq!(output_names(vec!["v"], "3").unwrap(),
+		           vec!["v[0]", "v[1]", "v[2]"]);
+		assert!(output_names(vec!["x", "y"], "3").is_err());
+		assert!(output_names(vec!["x"], "zero").is_err());
+	}
+
+	#[test]
+	fn declared_variables() {
+		let names = |ns: &[&str]| ns.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+		assert!(check_names(&names(&["a", "b"]), &names(&["x", "y"])).is_ok());
+		assert_eq!(check_names(&names(&["a", "b", "a"]), &names(&["x"])).unwrap_err(),
+		           "'a' names both --ivar 1 and --ivar 3; every variable needs a name of \
+		            its own.");
+		assert!(check_names(&names(&["a", "b"]), &names(&["x", "b"])).unwrap_err()
+			.starts_with("'b' names both --ivar 2 and --ovar 2;"));
+
+		// 3 inputs and 2 outputs, parted by an empty column.
+		let cols = columns(small_example().as_bytes(), 0, &Layout::default()).unwrap();
+		assert_eq!(cols, Columns{width: 6, blank: Some((3, 4))});
+		assert_eq!(cols.mismatch(3, 2), None);
+		let off = cols.mismatch(2, 2).unwrap();
+		assert!(off.starts_with("2 input(s) and 2 output(s) were declared, but column 4 \
+		                         is empty on every row"), "{}", off);
+		assert!(off.ends_with("did you mean 3 --ivar and 2 --ovar?"), "{}", off);
+		// without one, the outputs are the last columns.
+		let csv = "a,b,c,x\n0,0,1,1\n1,0,1,0\n";
+		let cols = columns(csv.as_bytes(), 1, &Layout::default()).unwrap();
+		assert_eq!(cols, Columns{width: 4, blank: None});
+		assert_eq!(cols.mismatch(3, 1), None);
+		assert_eq!(cols.mismatch(4, 1).unwrap(),
+		           "4 input(s) were declared, but the rows have 4 columns; with 1 \
+		            output(s) that leaves 3 leading column(s): did you mean 3 --ivar?");
+		assert_eq!(cols.mismatch(1, 4).unwrap(),
+		           "4 output(s) were declared, but the rows have only 4 column(s).");
+		assert_eq!(columns("a,b\n".as_bytes(), 1, &Layout::default()), None);
+	}
+
+	#[test]
+	fn output_selection() {
+		let outs = vec!["x", "y", "enable"];
+		assert_eq!(select_outputs(&outs, &[], &[]), Ok(vec![0, 1, 2]));
+		assert_eq!(select_outputs(&outs, &["enable", "x"], &[]), Ok(vec![0, 2]));
+		assert_eq!(select_outputs(&outs, &[], &["y"]), Ok(vec![0, 2]));
+		assert_eq!(select_outputs(&outs, &["x", "y"], &["y"]), Ok(vec![0]));
+		assert_eq!(select_outputs(&outs, &["enabel"], &[]),
+		           Err("Unknown output 'enabel'. Did you mean 'enable'?".to_string()));
+		assert_eq!(select_outputs(&outs, &[], &["zzz"]),
+		           Err("Unknown output 'zzz'.".to_string()));
+	}
+
+	#[test]
+	fn nothing_to_minimize() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		assert_eq!(check_outputs(&truth, 2), Ok(()));
+		assert_eq!(check_outputs(&truth, 0), Err(EmptyTable::NoOutputs));
+		// a header alone, as of a CSV file with no rows.
+		let header = parse("a,b,c,,x,y\n".as_bytes(), 1, 3, 2);
+		assert_eq!(check_outputs(&header, 2), Err(EmptyTable::NoRows));
+		assert_eq!(check_outputs(&Truth::default(), 2), Err(EmptyTable::NoRows));
+		// rows whose outputs are zero wide.
+		let narrow = Truth::from_fn(2, 0, |_| vec![]).unwrap();
+		assert_eq!(check_outputs(&narrow, 1), Err(EmptyTable::NoOutputs));
+		let outs = vec!["x", "y"];
+		let sel = select_outputs(&outs, &["x"], &["x"]).unwrap();
+		let err = check_selection(&sel, &["x"], &["x"]).unwrap_err();
+		assert_eq!(err, EmptyTable::NoneSelected{only: vec!["x".to_string()],
+		                                         skip: vec!["x".to_string()]});
+		assert_eq!(err.to_string(), "No output is left to minimize by --only x --skip x.");
+		assert_eq!(check_selection(&[1], &[], &["x"]), Ok(()));
+	}
+
+	#[test]
+	fn only_selected_minimized() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let sel = select_outputs(&["x", "y"], &["y"], &[]).unwrap();
+		let mut eqns: Vec<Equation> = sel.iter().map(|&b| {
+			Equation::new(&truth, b, ["x", "y"][b], &ivar)
+		}).collect();
+		let mut rec = progress::test::Recorder::default();
+		minimize(&mut eqns, 3, false, &[1, 1, 1], &mut exact::Budget::new(None, None),
+		         &mut rec);
+		assert_eq!(rec.equations, vec!["y"]);
+		assert_eq!(eqns.len(), 1);
+		assert_eq!(format!("{}", eqns[0]), "y = c' + ab' + ;");
+	}
+
+	#[test]
+	fn parse_equation() {
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let eqn = Equation::parse("x = a'b + c", &ivar).unwrap();
+		assert_eq!(eqn.varname, "x");
+		assert_eq!(format!("{}", eqn), "x = a'b + c + ;");
+		assert!(eqn.evaluate(&[false, true, false]));
+		assert!(!eqn.evaluate(&[true, true, false]));
+		let err = Equation::parse("x = a + (b", &ivar).unwrap_err();
+		assert_eq!(err.pos, 10);
+	}
+
+	#[test]
+	fn assertions() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let eqn = check_assertion("y = c' + a & !b", &truth, &["x", "y"], &ivar)
+			.unwrap();
+		assert_eq!(eqn.index, 1);
+		// a'b'c is missing from x, which is 1 there.
+		let err = check_assertion("x = bc' + ab'c'", &truth, &["x", "y"], &ivar)
+			.unwrap_err();
+		assert_eq!(err, "Assertion fails on 1 row(s): x = bc' + ab'c'\n  \
+		                 a=0 b=0 c=1 (line 2): table has x=1, equation gives 0");
+		let err = check_assertion("z = a", &truth, &["x", "y"], &ivar).unwrap_err();
+		assert!(err.contains("'z' is not an --ovar"), "{}", err);
+	}
+
+	#[test]
+	fn expressions_roundtrip() {
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let defs = vec!["x = a'b + c", "y = a ^ b", "z = (a ^ b ^ c) & !(ab)"];
+		let (truth, outs) = from_expressions(defs.clone(), &ivar, 3).unwrap();
+		assert_eq!(outs, vec!["x", "y", "z"]);
+		let outs: Vec<&str> = outs.iter().map(|s| s.as_str()).collect();
+		let mut eqns = equations(&truth, outs, ivar.clone());
+		for (eqn, def) in eqns.iter_mut().zip(defs.iter()) {
+			eqn.simplify();
+			let e = expr::Expr::parse(expr::split_assignment(def).1, &ivar).unwrap();
+			for ent in truth.table.iter() {
+				assert_eq!(eqn.evaluate(&ent.input), e.evaluate(&ent.input));
+			}
+		}
+		let err = from_expressions(vec!["x = a + d"], &ivar, 3).unwrap_err();
+		assert_eq!(err, "Cannot parse 'x = a + d' at position 8: unknown variable \
+		                 'd'.");
+		assert!(from_expressions(vec!["a + b"], &ivar, 3).is_err());
+		assert!(from_expressions(vec!["x = a", "x = b"], &ivar, 3).is_err());
+	}
+
+	#[test]
+	fn small_verilog_casez() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		for e in eqns.iter_mut() {
+			e.simplify();
+		}
+		let complete = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		let arms = hdl::casez_arms(&eqns, 3, &complete);
+		for ent in truth.table.iter() {
+			let got = hdl::casez_evaluate(&arms, &ent.input).unwrap_or(vec![false; 2]);
+			assert_eq!(got, ent.output);
+		}
+		let golden =
+			"module minterm(\n".to_string() +
+			"\tinput wire a, b, c,\n" +
+			"\toutput reg x, y\n" +
+			");\n" +
+			"\talways @* begin\n" +
+			"\t\tcasez ({a, b, c})\n" +
+			"\t\t\t3'b001: begin x = 1'b1; y = 1'b0; end\n" +
+			"\t\t\t3'b?10: begin x = 1'b1; y = 1'b1; end\n" +
+			"\t\t\t3'b1?0: begin x = 1'b1; y = 1'b1; end\n" +
+			"\t\t\t3'b??0: begin x = 1'b0; y = 1'b1; end\n" +
+			"\t\t\t3'b10?: begin x = 1'b0; y = 1'b1; end\n" +
+			"\t\t\tdefault: begin x = 1'b0; y = 1'b0; end\n" +
+			"\t\tendcase\n" +
+			"\tend\n" +
+			"endmodule\n";
+		assert_eq!(hdl::verilog_casez(&eqns, &ivar, &complete, BitOrder::Msb), golden);
+		// with a the least significant bit, the selector and patterns reverse.
+		let lsb = hdl::verilog_casez(&eqns, &ivar, &complete, BitOrder::Lsb);
+		assert_eq!(lsb, golden.replace("{a, b, c}", "{c, b, a}").replace("b001", "b100")
+		                      .replace("b?10", "b01?").replace("b1?0", "b0?1")
+		                      .replace("b??0", "b0??").replace("b10?", "b?01"));
+	}
+
+	#[test]
+	fn packed_formats_agree() {
+		// the lookup table, the jump table and the casez selector all take the
+		// same value for the same inputs.
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+		let mut eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		eqns.iter_mut().for_each(|e| e.simplify());
+		let complete = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		for &order in [BitOrder::Lsb, BitOrder::Msb].iter() {
+			let entries = lut::entries(&eqns, 3, &complete, order);
+			let cases = switch::cases(&eqns, 3, &complete, order, false);
+			let casez = hdl::verilog_casez(&eqns, &ivar, &complete, order);
+			// each arm's pattern and outputs, as "3'b1?0: begin x = 1'b1; ...".
+			let arms: Vec<(&str, Vec<bool>)> = casez.lines()
+				.filter_map(|l| l.trim().strip_prefix("3'b"))
+				.map(|l| (&l[..3], l.split("1'b").skip(1).map(|v| v.starts_with('1'))
+				                     .collect()))
+				.collect();
+			for v in 0..8u64 {
+				let inp = bit_unpack(v, 3, order);
+				let want: Vec<bool> = eqns.iter().map(|e| e.evaluate(&inp)).collect();
+				assert_eq!(bit_unpack(entries[v as usize] as u64, 2, order), want,
+				           "{:?} {}", order, v);
+				let case = cases.iter().find(|c| c.values.contains(&v)).unwrap();
+				assert_eq!(case.outputs, want, "{:?} {}", order, v);
+				// the selector's value as a binary number, most significant first.
+				let digits = format!("{:03b}", v);
+				let arm = arms.iter().find(|&&(p, _)| {
+					p.chars().zip(digits.chars()).all(|(p, d)| p == '?' || p == d)
+				});
+				assert_eq!(arm.map_or(vec![false; 2], |a| a.1.clone()), want,
+				           "{:?} {}", order, v);
+			}
+		}
+	}
+
+	#[test]
+	fn small_vhdl() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		for e in eqns.iter_mut() {
+			e.simplify();
+		}
+		let golden =
+			"library ieee;\n".to_string() +
+			"use ieee.std_logic_1164.all;\n" +
+			"\n" +
+			"entity minterm is\n" +
+			"\tport (\n" +
+			"\t\ta, b, c : in std_logic;\n" +
+			"\t\tx, y : out std_logic\n" +
+			"\t);\n" +
+			"end entity minterm;\n" +
+			"\n" +
+			"architecture rtl of minterm is\n" +
+			"begin\n" +
+			"\tx <= (not a and not b and c) or (b and not c) or (a and not c);\n" +
+			"\ty <= not c or (a and not b);\n" +
+			"end architecture rtl;\n";
+		let complete = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		assert_eq!(hdl::vhdl(&eqns, &ivar, &complete), golden);
+	}
+
+	// Runs a Python program, returning its output; None when there is no
+	// python3 to run it with.
+	fn run_python(program: &str) -> Option<String> {
+		let out = match std::process::Command::new("python3").arg("-c")
+			.arg(program).output() {
+			Ok(out) => out,
+			Err(_) => return None,
+		};
+		assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+		Some(String::from_utf8(out.stdout).unwrap())
+	}
+
+	#[test]
+	fn small_python() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["x", "in"], ivar.clone());
+		for e in eqns.iter_mut() {
+			e.simplify();
+		}
+		let complete = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		let src = python::function(&eqns, &ivar, &complete, false);
+		assert!(src.starts_with("def map(a, b, c):\n"));
+		assert!(src.contains("    in_ = bool(not c or (a and not b))\n"), "{}", src);
+		assert!(src.ends_with("    return (x, in_)\n"));
+		// the same check, in Python: every row of the table.
+		let mut check = src.clone();
+		for ent in truth.table.iter() {
+			let inp: Vec<String> = ent.input.iter().map(|&b| (b as u8).to_string())
+				.collect();
+			let out: Vec<&str> = ent.output.iter()
+				.map(|&b| if b { "True" } else { "False" }).collect();
+			check += &format!("assert map({}) == ({})\n", inp.join(", "),
+			                  out.join(", "));
+		}
+		check += "print('ok')\n";
+		if let Some(out) = run_python(&check) {
+			assert_eq!(out, "ok\n");
+		}
+
+		let src = python::function(&eqns, &ivar, &complete, true);
+		assert!(src.ends_with("    return dict(zip(('x', 'in'), (x, in_)))\n"));
+		if let Some(out) = run_python(&(src + "print(map(0, 0, 1)['in'])\n")) {
+			assert_eq!(out, "False\n");
+		}
+	}
+
+	#[test]
+	fn small_python_lookup() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let names = vec!["y".to_string()];
+		let complete = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		let src = python::lookup(&truth, &[1], &names, &ivar, &complete, false);
+		// one entry per row of the CSV, holding just the selected output.
+		for ent in truth.table.iter() {
+			let inp: Vec<String> = ent.input.iter().map(|&b| (b as u8).to_string())
+				.collect();
+			let entry = format!("    ({}): ({},),\n", inp.join(", "),
+			                    ent.output[1] as u8);
+			assert!(src.contains(&entry), "{} not in {}", entry, src);
+		}
+		assert!(src.ends_with("def map(a, b, c):\n    return TABLE[(a, b, c)]\n"));
+		if let Some(out) = run_python(&(src + "print(map(1, 0, 1))\n")) {
+			assert_eq!(out, "(1,)\n");
+		}
+	}
+
+	// small_example without the rows for inputs 011 and 111.
+	fn small_sparse() -> Truth {
+		let mut truth = parse(small_example().as_bytes(), 0, 3, 2);
+		truth.table.retain(|e| !(e.input[1] && e.input[2]));
+		truth
+	}
+
+	#[test]
+	fn sparse_python() {
+		let truth = small_sparse();
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		assert_eq!(missing_inputs(&truth, 3, 3).unwrap(), vec![vec![false, true, true],
+		                                                       vec![true, true, true]]);
+		// finding them needs every pattern, which the limit may not allow.
+		assert!(missing_inputs(&truth, 3, 2).is_err());
+		let eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		let zero = emit::Unlisted::new(emit::DefaultOutput::Zero, &truth, 3, &ivar);
+		let src = python::function(&eqns, &ivar, &zero, false);
+		assert!(!src.contains("    if "));
+		if let Some(out) = run_python(&(src + "print(map(1, 1, 1))\n")) {
+			assert_eq!(out, "(False, False)\n");
+		}
+		let panic = emit::Unlisted::new(emit::DefaultOutput::Panic, &truth, 3, &ivar);
+		let src = python::function(&eqns, &ivar, &panic, false);
+		assert!(src.contains("    if not (not c or not b):\n        raise ValueError("));
+		let check = src + "try:\n    map(0, 1, 1)\nexcept ValueError:\n    print('raised')\n";
+		if let Some(out) = run_python(&(check + "print(map(1, 0, 1))\n")) {
+			assert_eq!(out, "raised\n(False, True)\n");
+		}
+
+		let names = vec!["x".to_string(), "y".to_string()];
+		let src = python::lookup(&truth, &[0, 1], &names, &ivar, &zero, false);
+		assert!(src.ends_with("    return TABLE.get((a, b, c), (0, 0))\n"));
+		let src = python::lookup(&truth, &[0, 1], &names, &ivar, &panic, false);
+		assert!(src.ends_with("    if (a, b, c) not in TABLE:\n        raise \
+		                       ValueError('input not in the table')\n    \
+		                       return TABLE[(a, b, c)]\n"));
+	}
+
+	#[test]
+	fn sparse_dont_cares() {
+		let truth = small_sparse();
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		for eqn in eqns.iter_mut() {
+			for inp in missing_inputs(&truth, 3, 3).unwrap() {
+				let mut term = Term::compute(&inp);
+				term.names = ivar.clone();
+				eqn.dc.push(term);
+			}
+		}
+		let mut budget = exact::Budget::unlimited();
+		for eqn in eqns.iter() {
+			let mut greedy = eqn.clone();
+			greedy.simplify();
+			let mut exact = eqn.clone();
+			exact.terms = exact::minimize(eqn, 3, &mut budget).terms;
+			for ent in truth.table.iter() {
+				assert_eq!(greedy.evaluate(&ent.input), ent.output[eqn.index]);
+				assert_eq!(exact.evaluate(&ent.input), ent.output[eqn.index]);
+			}
+		}
+		// with 111 free, y = c' + ab' becomes y = c' + a.
+		let y = exact::minimize(&eqns[1], 3, &mut budget);
+		let strs: Vec<String> = y.terms.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["c'", "a"]);
+	}
+
+	#[test]
+	fn weight_options() {
+		let ivar: Vec<String> = ["a", "sel", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		assert_eq!(input_weights(vec![], &ivar), Ok(vec![1, 1, 1]));
+		assert_eq!(input_weights(vec!["sel=3", "c=0"], &ivar), Ok(vec![1, 3, 0]));
+		assert_eq!(input_weights(vec!["sell=3"], &ivar),
+		           Err("Unknown input 'sell' in --weight. Did you mean 'sel'?".to_string()));
+		assert!(input_weights(vec!["sel"], &ivar).is_err());
+		assert!(input_weights(vec!["sel=-1"], &ivar).is_err());
+	}
+
+	#[test]
+	fn algorithm_per_output() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()).collect();
+		let ovar: Vec<String> = vec!["x".to_string(), "y".to_string()];
+		let options = |specs: Vec<&str>| {
+			algorithm_options(&parse_algorithm_for(specs)?, &ovar, Algorithm::Greedy)
+		};
+		let opts = options(vec!["y=exact"]).unwrap();
+		assert_eq!(opts, vec![Algorithm::Greedy, Algorithm::Exact]);
+		let fresh = || -> Vec<Equation> {
+			(0..2).map(|b| Equation::new(&truth, b, &ovar[b], &ivar)).collect()
+		};
+		let mut eqns = fresh();
+		let ran = minimize_each(&mut eqns, 3, &opts, &[1, 1, 1],
+		                        &mut exact::Budget::unlimited(), &mut progress::Silent);
+		assert_eq!(ran, vec![Ran::Greedy, Ran::Exact]);
+		// a chart can't fit in 10 bytes; y's primes are all essential, so a
+		// budget of one step is enough.
+		let mut budget = exact::Budget::unlimited();
+		budget.limit_memory(10);
+		let ran = minimize_each(&mut fresh(), 3, &opts, &[1, 1, 1], &mut budget,
+		                        &mut progress::Silent);
+		assert_eq!((ran[1], ran[1].minimal()), (Ran::GreedyForMemory, false));
+		let mut budget = exact::Budget::new(None, Some(1));
+		let ran = minimize_each(&mut fresh(), 3, &opts, &[1, 1, 1], &mut budget,
+		                        &mut progress::Silent);
+		assert_eq!(ran[1].to_string(), "exact");
+		assert_eq!(options(vec!["yy=exact"]),
+		           Err("Unknown output 'yy' in --algorithm-for. Did you mean 'y'?"
+		               .to_string()));
+		assert!(options(vec!["y=espresso"]).is_err());
+		assert!(options(vec!["y"]).is_err());
+		assert!(options(vec!["y=exact", "y=greedy"]).is_err());
+	}
+
+	#[test]
+	fn rebuilt_from_equations() {
+		let truth = parse(small_example().as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(|e| e.to_string())
+			.collect();
+		let mut eqns = equations(&truth, vec!["x", "y"], ivar.clone());
+		minimize(&mut eqns, 3, true, &[1, 1, 1], &mut exact::Budget::unlimited(),
+		         &mut progress::Silent);
+		let rebuilt = Truth::from_equations(&eqns, 3).unwrap();
+		assert_eq!(rebuilt.table, truth.table);
+		assert!(rebuilt.dc.is_empty());
+		// the outputs follow the slice, not the equations' indices.
+		eqns.reverse();
+		let swapped = Truth::from_equations(&eqns, 3).unwrap();
+		assert!(swapped.table.iter().zip(truth.table.iter())
+			.all(|(s, t)| s.output[0] == t.output[1] && s.output[1] == t.output[0]));
+		// constants, and an equation of one input in four.
+		let zero = Equation{index: 0, terms: vec![], dc: vec![], varname: "z".to_string()};
+		let mut one = zero.clone();
+		one.terms.push(Term{bits: vec![], names: ivar.clone()});
+		let mut b = zero.clone();
+		b.terms.push(Term{bits: vec![(1, true)], names: ivar.clone()});
+		let t = Truth::from_equations(&[zero, one, b.clone()], 4).unwrap();
+		assert_eq!(t.len(), 16);
+		assert!(t.table.iter()
+			.all(|e| !e.output[0] && e.output[1] && e.output[2] == e.input[1]));
+		assert!(Truth::from_equations(&[b], 1).is_err());
+		for seed in 0..4 {
+			let f = testutil::dense(5, 3, seed);
+			let outvars: Vec<&str> = f.outvars.iter().map(|s| s.as_str()).collect();
+			let mut eqns = equations(&f.truth, outvars, f.invars.clone());
+			eqns.iter_mut().for_each(Equation::simplify);
+			assert_eq!(Truth::from_equations(&eqns, 5).unwrap().table, f.truth.table);
+		}
+	}
+
+	#[test]
+	fn constraints() {
+		// x is 1 for b or c; a, b and c are one-hot, so x is just a'.
+		let s = "0,0,0,,1\n0,0,1,,1\n0,1,0,,1\n0,1,1,,0\n\
+		         1,0,0,,0\n1,0,1,,1\n1,1,0,,0\n1,1,1,,1\n";
+		let mut truth = parse(s.as_bytes(), 0, 3, 1);
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let literals = |eqn: &Equation| -> usize {
+			eqn.terms.iter().map(|t| t.len()).sum()
+		};
+		let mut budget = exact::Budget::unlimited();
+		let eqns = equations(&truth, vec!["x"], ivar.clone());
+		let free = exact::minimize(&eqns[0], 3, &mut budget).terms;
+
+		let onehot = parse_constraints(vec!["a'b'c + a'bc' + ab'c'"], &ivar).unwrap();
+		let possible = |inp: &[bool]| onehot.iter().all(|c| c.evaluate(inp));
+		let (removed, impossible) = apply_constraints(&mut truth, &possible, 3, 3)
+			.unwrap();
+		assert_eq!((removed, impossible.len()), (5, 5));
+		assert_eq!(truth.len(), 3);
+		let mut eqns = equations(&truth, vec!["x"], ivar.clone());
+		for inp in impossible.iter() {
+			let mut term = Term::compute(inp);
+			term.names = ivar.clone();
+			eqns[0].dc.push(term);
+		}
+		let mut constrained = eqns[0].clone();
+		constrained.terms = exact::minimize(&eqns[0], 3, &mut budget).terms;
+		assert_eq!(format!("{}", constrained), "x = a' + ;");
+		let mut unconstrained = eqns[0].clone();
+		unconstrained.terms = free;
+		assert!(literals(&constrained) < literals(&unconstrained));
+		// a constraint that can't be parsed.
+		let err = parse_constraints(vec!["a & d"], &ivar).unwrap_err();
+		assert_eq!(err, "Cannot parse constraint 'a & d' at position 4: unknown \
+		                 variable 'd'.");
+	}
+
+	#[test]
+	fn canonical_terms() {
+		let t1 = Term::new(vec![(0,false), (1,true)]);
+		let t2 = Term::new(vec![(1,true), (0,false)]);
+		assert_eq!(t1, t2);
+		assert_eq!(t1.cmp(&t2), std::cmp::Ordering::Equal);
+		let hash = |t: &Term| {
+			use std::hash::{Hash, Hasher};
+			let mut h = std::collections::hash_map::DefaultHasher::new();
+			t.hash(&mut h);
+			h.finish()
+		};
+		assert_eq!(hash(&t1), hash(&t2));
+		let set: HashSet<Term> = vec![t1.clone(), t2.clone()].into_iter().collect();
+		assert_eq!(set.len(), 1);
+		// shorter first, then by variables, then by polarity.
+		let mut terms = [Term::new(vec![(0,true), (2,false)]),
+		                     Term::new(vec![(1,false)]),
+		                     Term::new(vec![(0,false), (2,false)]),
+		                     Term::new(vec![(0,true), (1,true)])];
+		terms.sort();
+		let strs: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+		assert_eq!(strs, vec!["b'", "ab", "a'c'", "ac'"]);
+
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(|e| e.to_string())
+			.collect();
+		let e1 = Equation::parse("x = a'b + c", &ivar).unwrap();
+		let e2 = Equation::parse("x = c + b & !a + c", &ivar).unwrap();
+		assert!(e1.eq_semantic(&e2));
+		assert!(e1 != e2);
+		let mut dup = e2.clone();
+		dup.simplify();
+		assert_eq!(dup.terms.len(), 2);
+	}
+
+	#[test]
+	fn substitution() {
+		let ivar: Vec<String> = ["a", "b", "c", "d"].iter().map(|e| e.to_string())
+			.collect();
+		let parse = |s: &str, idx: usize| {
+			let mut eqn = Equation::parse(s, &ivar).unwrap();
+			eqn.index = idx;
+			eqn
+		};
+		let x = parse("x = ab + c'd", 0);
+		let y = parse("y = ab + c'd + a'bd", 1);
+		let z = parse("z = abc + abd'", 2);
+		let y_x = y.substitute(&x).unwrap();
+		assert_eq!(y_x.to_string(), "y = x + a'bd + ;");
+		let w = parse("w = ab", 0);
+		let z_w = z.substitute(&w).unwrap();
+		assert_eq!(z_w.to_string(), "z = cw + d'w + ;");
+		// computing x first, its value is the variable after the inputs.
+		for i in 0..16 {
+			let inp = bit_unpack(i, 4, BitOrder::Msb);
+			let mut with_x = inp.clone();
+			with_x.push(x.evaluate(&inp));
+			assert_eq!(y_x.evaluate(&with_x), y.evaluate(&inp), "{:?}", inp);
+			let mut with_w = inp.clone();
+			with_w.push(w.evaluate(&inp));
+			assert_eq!(z_w.evaluate(&with_w), z.evaluate(&inp), "{:?}", inp);
+		}
+		// x isn't within z, nor y within x; nor does a single literal save any.
+		assert!(z.substitute(&x).is_none());
+		assert!(x.substitute(&y).is_none());
+		assert!(x.substitute(&x).is_none());
+		assert!(parse("v = a + b", 1).substitute(&parse("u = a", 0)).is_none());
+	}
+
+	#[test]
+	fn term_merge() {
+		let t1 = Term::new(vec![(0,false), (1,false), (2,false)]);
+		let t2 = Term::new(vec![(0,false), (1,true), (2,false)]);
+		let t3 = Term::new(vec![(0,false), (1,true), (2,false), (3,true)]);
+		let t4 = Term::new(vec![(0,false), (1,true), (2,false), (3,false)]);
+		assert!(t1.mergeable(&t2));
+		assert!(!t1.mergeable(&t3));
+		assert!(!t1.mergeable(&t4));
+		assert!(t2.mergeable(&t1));
+		assert!(!t2.mergeable(&t3));
+		assert!(!t2.mergeable(&t4));
+		assert!(!t3.mergeable(&t1));
+		assert!(!t3.mergeable(&t2));
+		assert!(t3.mergeable(&t4));
+		assert!(!t4.mergeable(&t1));
+		assert!(!t4.mergeable(&t2));
+		assert!(t4.mergeable(&t3));
+		assert_eq!(t1.merge(&t2), Some(Term::new(vec![(0,false), (2,false)])));
+		// the variables are matched by index, in whatever order they come.
+		let ab = Term::new(vec![(0,false), (1,false)]);
+		let ba = Term::new(vec![(1,false), (0,true)]);
+		assert_eq!(ab.merge(&ba), Some(Term::new(vec![(1,false)])));
+		assert_eq!(ba.merge(&ab).map(|t| t.bits), Some(vec![(1,false)]));
+		assert!(!ab.mergeable(&Term::new(vec![(0,true), (2,false)])));
+		assert!(!ab.mergeable(&Term::new(vec![(2,false), (0,true)])));
+	}
+
+	#[test]
+	fn remapping() {
+		// x = a'b + c over a, b, c; moved to c, a, b and back.
+		let invars: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let tbl = Truth::from_fn(3, 1, |i| vec![!i[0] && i[1] || i[2]]).unwrap();
+		let mut eqn = Equation::new(&tbl, 0, "x", &invars);
+		eqn.simplify();
+		eqn.canonicalize();
+		assert_eq!(eqn.to_string(), "x = c + a'b + ;");
+		let (there, back) = ([2, 0, 1], [1, 2, 0]);
+		let moved = eqn.remap(&there).unwrap();
+		// the same equation, its variables now ordered b, c, a.
+		assert_eq!(moved.to_string(), "x = c + ba' + ;");
+		assert_eq!(moved.terms[1].bits, vec![(0, true), (2, false)]);
+		let permuted = tbl.permute_inputs(&there).unwrap();
+		assert!(moved.counterexamples(&permuted).is_empty(), "{}", moved);
+		assert!(!eqn.counterexamples(&permuted).is_empty());
+		assert_eq!(moved.remap(&back).unwrap(), eqn);
+		let restored = permuted.permute_inputs(&back).unwrap();
+		assert_eq!(restored.table, tbl.table);
+		// into a wider space, and out of it again.
+		let t = Term{bits: vec![(0, false), (1, true)], names: invars.clone()};
+		let wide = t.remap(&[3, 1, 4]).unwrap();
+		assert_eq!((wide.bits.clone(), wide.to_string()), (vec![(1, true), (3, false)],
+		                                                    "ba'".to_string()));
+		assert_eq!(wide.names, vec!["", "b", "", "a", "c"]);
+		assert_eq!(wide.remap(&[0, 1, 2, 0, 2]),
+		           Err(RemapError::Collision{first: 0, second: 3, index: 0}));
+		assert_eq!(wide.remap(&[9, 1, 8, 0, 2]).unwrap(), t);
+		assert_eq!(t.remap(&[1]), Err(RemapError::Unmapped{index: 1, len: 1}));
+		assert_eq!(tbl.permute_inputs(&[0, 1]).unwrap_err().to_string(),
+		           "Variable 2 is not mapped; the mapping has 2 indices.");
+		assert_eq!(tbl.permute_inputs(&[0, 1, 3]).unwrap_err(),
+		           RemapError::OutOfRange{variable: 2, index: 3, nbits: 3});
+		assert_eq!(tbl.permute_inputs(&[1, 1, 0]).unwrap_err().to_string(),
+		           "Variables 0 and 1 both map to index 1.");
+	}
+
+	#[test]
+	fn term_queries() {
+		// every term over three inputs, each absent or in either polarity.
+		let mut all: Vec<Term> = vec![];
+		for code in 0..27 {
+			let bits = (0..3).map(|i| (i, code / 3usize.pow(i as u32) % 3))
+				.filter(|&(_, v)| v > 0).map(|(i, v)| (i, v == 2)).collect();
+			all.push(Term::new(bits));
+		}
+		let inputs: Vec<Vec<bool>> = (0..8).map(|k| bit_unpack(k, 3, BitOrder::Msb))
+			.collect();
+		let ones = |t: &Term| -> Vec<&Vec<bool>> {
+			inputs.iter().filter(|i| t.evaluate(i)).collect()
+		};
+		for s in all.iter() {
+			for t in all.iter() {
+				let both = ones(s).iter().any(|i| t.evaluate(i));
+				assert_eq!(s.intersects(t), both, "{} {}", s, t);
+				let within = ones(t).iter().all(|i| s.evaluate(i));
+				assert_eq!(s.contains(t), within, "{} {}", s, t);
+				assert!(!s.contains(t) || s.intersects(t), "{} {}", s, t);
+				assert_eq!(s.contains(t) && t.contains(s), s == t, "{} {}", s, t);
+				// as far apart as their closest inputs.
+				let apart = |i: &Vec<bool>, j: &Vec<bool>| {
+					i.iter().zip(j.iter()).filter(|&(a, b)| a != b).count()
+				};
+				let closest = ones(s).iter()
+					.flat_map(|i| ones(t).into_iter().map(move |j| apart(i, j))).min();
+				assert_eq!(s.distance(t), closest, "{} {}", s, t);
+				assert_eq!(s.distance(t), t.distance(s), "{} {}", s, t);
+				assert_eq!(s.distance(t) == Some(0), both, "{} {}", s, t);
+			}
+		}
+		// ab over a, b and over x, b can't be matched up; over a, b, c they can.
+		let xb = Term{bits: vec![(0, true), (1, true)],
+		              names: vec!["x".to_string(), "b".to_string()]};
+		let ab = Term::new(vec![(0, true), (1, true)]);
+		assert_eq!(ab.distance(&xb), None);
+		let mut abc = ab.clone();
+		abc.names = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+		assert_eq!(abc.distance(&Term::new(vec![(0, false), (2, true)])), Some(1));
+		abc.names.truncate(1);
+		assert_eq!(abc.distance(&ab), None);
+	}
+
+	#[test]
+	fn merge_matches_variables() {
+		// the same variables in another order: matching them by position would
+		// see two differences, or delete the wrong one.
+		let ba = Term::new(vec![(1,false), (0,true)]);
+		let ab = Term::new(vec![(0,false), (1,false)]);
+		assert_eq!(ba.merge(&ab), Some(Term::new(vec![(1,false)])));
+		assert_eq!(ab.merge(&ba), Some(Term::new(vec![(1,false)])));
+		// as many variables, but not the same: ab and bc don't merge, nor do
+		// ab' and a'b.
+		let bc = Term::new(vec![(1,true), (2,true)]);
+		assert_eq!(Term::new(vec![(0,true), (1,true)]).merge(&bc), None);
+		assert_eq!(Term::new(vec![(0,true), (1,false)]).merge(&Term::new(
+			vec![(1,true), (0,false)])), None);
+		// merging such terms pair by pair still gives a cover of the table.
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(|e| e.to_string())
+			.collect();
+		let mut eqn = Equation::parse("x = b'a' + a'b + cb + bc'a", &ivar).unwrap();
+		let truth = Truth::from_fn(3, 1, |inp| vec![eqn.evaluate(inp)]).unwrap();
+		while merge_pair_all_pairs(&mut eqn) {}
+		assert!(eqn.counterexamples(&truth).is_empty(), "{}", eqn);
+		assert_eq!(eqn.to_string(), "x = a' + bc + abc' + ;");
+	}
+
+	// The merge step as it was before cube expansion, and before terms were
+	// bucketed: every pair is compared, and the last term that merges with any
+	// wins.
+	fn merge_pair_all_pairs(eqn: &mut Equation) -> bool {
+		let mut found = None;
+		for (t1_loc, t1) in eqn.terms.iter().enumerate() {
+			for (t2_loc, t2) in eqn.terms.iter().enumerate() {
+				if let Some(merged) = t1.merge(t2) {
+					found = Some((t1_loc, merged, t2_loc));
+					break;
+				}
+			}
+		}
+		if let Some((t1_loc, merged, t2_loc)) = found {
+			eqn.terms[t1_loc] = merged;
+			eqn.terms.remove(t2_loc);
+			true
+		} else {
+			false
+		}
+	}
+
+	// Tables with random outputs, 'density' in 256 of them 1.
+	fn random_table(nin: usize, nout: usize, density: u64, seed: u64) -> Truth {
+		let mut state = seed;
+		let mut tbl = Truth::default();
+		for i in 0..1u64 << nin {
+			let outputs = (0..nout).map(|_| {
+				// xorshift64
+				state ^= state << 13;
+				state ^= state >> 7;
+				state ^= state << 17;
+				state % 256 < density
+			}).collect();
+			tbl.table.push(Entry::new(bit_unpack(i, nin, BitOrder::Msb), outputs));
+		}
+		tbl
+	}
+
+	#[test]
+	fn bucketed_merges() {
+		for (seed, &density) in [32, 128, 224].iter().enumerate() {
+			let tbl = random_table(6, 4, density, seed as u64 + 1);
+			let ivar: Vec<String> = (0..6).map(|i| format!("i{}", i)).collect();
+			// the packed representation gives the same equations.
+			let packed = packed::PackedTruth::from_truth(&tbl);
+			let eqns = equations(&tbl, vec!["w", "x", "y", "z"], ivar.clone());
+			assert_eq!(eqns, equations(&packed, vec!["w", "x", "y", "z"], ivar));
+			for mut eqn in eqns {
+				let mut old = eqn.clone();
+				while merge_pair_all_pairs(&mut old) {}
+				eqn.simplify();
+				assert!(eqn.counterexamples(&tbl).is_empty(), "{}", eqn);
+				assert!(eqn.terms.len() <= old.terms.len(), "{} vs {}", eqn, old);
+				// every term is prime: without any one of its literals it would
+				// cover a 0.
+				for t in eqn.terms.iter() {
+					for &lit in t.bits.iter() {
+						let mut wider = t.clone();
+						wider.remove_index(lit.0);
+						assert!(tbl.table.iter().any(|e| {
+							wider.evaluate(&e.input) && !e.output[eqn.index]
+						}), "{} is not prime in {}", t, eqn);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn merge_order() {
+		// pairwise merging could stop short of a' depending on which pairs
+		// merged first.
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(|e| e.to_string())
+			.collect();
+		for order in [[0, 1, 2, 3], [0, 3, 1, 2], [3, 0, 2, 1]].iter() {
+			let minterms = ["a'b'c'", "a'b'c", "a'bc'", "a'bc"];
+			let text: Vec<&str> = order.iter().map(|&i| minterms[i]).collect();
+			let mut eqn = Equation::parse(&format!("x = {}", text.join(" + ")), &ivar)
+				.unwrap();
+			eqn.simplify();
+			assert_eq!(eqn.to_string(), "x = a' + ;");
+		}
+	}
+
+	#[test]
+	fn incremental_updates() {
+		let mut tbl = random_table(6, 2, 128, 3);
+		let ivar: Vec<String> = (0..6).map(|i| format!("i{}", i)).collect();
+		let mut eqns = equations(&tbl, vec!["x", "y"], ivar.clone());
+		for eqn in eqns.iter_mut() {
+			eqn.simplify();
+		}
+		let mut state: u64 = 12345;
+		for _ in 0..40 {
+			state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+			let inp = bit_unpack(state >> 58, 6, BitOrder::Msb);
+			let idx = (state >> 40) as usize % 2;
+			let value = (state >> 20) & 1 == 1;
+			let old = tbl.solution(inp.clone())[idx];
+			assert_eq!(tbl.set_output(&inp, idx, value, 2), old);
+			for eqn in eqns.iter_mut() {
+				eqn.update(&tbl, std::slice::from_ref(&inp));
+				assert!(eqn.counterexamples(&tbl).is_empty(), "{}", eqn);
+				let mut scratch = Equation::new(&tbl, eqn.index, &eqn.varname, &ivar);
+				scratch.simplify();
+				for i in 0..64 {
+					let inp = bit_unpack(i, 6, BitOrder::Msb);
+					assert_eq!(eqn.evaluate(&inp), scratch.evaluate(&inp));
+				}
+			}
+		}
+		// a row the table didn't list.
+		let mut sparse = small_sparse();
+		let before = sparse.clone();
+		assert!(!sparse.set_output(&[true, true, true], 1, true, 2));
+		assert_eq!(before.changed_rows(&sparse), vec![vec![true, true, true]]);
+		// and a table without any.
+		let mut empty = Truth::default();
+		assert!(!empty.set_output(&[false, true], 0, true, 2));
+		assert_eq!(empty.table[0].output, vec![true, false]);
+	}
+
+	// Times the cube expansion and the old all-pairs merging on a dense table;
+	// run with --ignored, preferably in release mode.
+	#[test]
+	#[ignore]
+	fn merge_timing() {
+		let tbl = random_table(10, 1, 192, 7);
+		let ivar: Vec<String> = (0..10).map(|i| format!("i{}", i)).collect();
+		let mut eqn = equations(&tbl, vec!["x"], ivar).pop().unwrap();
+		let mut old = eqn.clone();
+		let start = Instant::now();
+		eqn.simplify();
+		println!("expanded: {:?}", start.elapsed());
+		let start = Instant::now();
+		while merge_pair_all_pairs(&mut old) {}
+		println!("all pairs: {:?}", start.elapsed());
+		assert!(eqn.terms.len() <= old.terms.len());
+	}
+
+	#[test]
+	fn simplify_progress() {
+		let small = small_example();
+		let truth = parse(small.as_bytes(), 0, 3, 2);
+		let ivar: Vec<String> = ["A", "B", "C"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["foo", "bar"], ivar);
+		let mut rec = progress::test::Recorder::default();
+		eqns[1].simplify_with(&mut rec);
+		// a level per literal merged away, and then the cubes left to pick from.
+		assert_eq!(rec.updates.first(), Some(&(5, 0)));
+		assert_eq!(rec.updates.last(), Some(&(2, 3)));
+		for w in rec.updates.windows(2) {
+			assert_eq!(w[1].1, w[0].1 + 1);
+		}
+		assert_eq!(eqns[1].terms.len(), 2);
+	}
+
+	#[test]
+	fn constant_one() {
+		let ivar: Vec<String> = ["a", "b", "c"].iter().map(|e| e.to_string())
+			.collect();
+		let start = Equation::parse("x = a + a' + bc", &ivar).unwrap();
+		let complete = gray_code(3);
+		let mut eqn = start.clone();
+		let mut rec = progress::test::Recorder::default();
+		eqn.simplify_with(&mut rec);
+		assert_eq!(eqn.terms.len(), 1);
+		assert!(eqn.terms[0].is_universal());
+		assert_eq!(eqn.to_string(), "x = 1 + ;");
+		assert!(complete.iter().all(|inp| eqn.evaluate(inp)));
+		// the expansion stopped at the first level.
+		assert_eq!(rec.updates.last(), Some(&(1, 1)));
+		// simplifying again, or exactly, keeps it.
+		eqn.simplify();
+		assert_eq!(eqn.to_string(), "x = 1 + ;");
+		let sol = exact::minimize(&start, 3, &mut exact::Budget::unlimited());
+		assert!(sol.minimal);
+		assert_eq!(sol.terms, eqn.terms);
+		let unlisted = emit::Unlisted::new(emit::DefaultOutput::Zero,
+		                                   &Truth::new(complete.clone(),
+		                                               vec![vec![true]; 8]),
+		                                   3, &ivar);
+		let src = python::function(&[eqn.clone()], &ivar, &unlisted, false);
+		assert!(src.contains("x = bool(True)\n"), "{}", src);
+		let src = switch::switch_rust(&[eqn.clone()], &ivar, &unlisted, BitOrder::Lsb,
+		                              true);
+		assert!(src.contains("\t\t0..=7 => true,\n"), "{}", src);
+		let src = hdl::vhdl(&[eqn], &ivar, &unlisted);
+		assert!(src.contains("\tx <= '1';\n"), "{}", src);
+	}
+
+	#[test]
+	fn small_simplify() {
+		let small = small_example();
+		let truth = parse(small.as_bytes(), 0, 3, 2);
+		assert_eq!(truth.len(), 8);
+		let ivar: Vec<String> = ["A", "B", "C"].iter().map(
+			|e| e.to_string()
+		).collect();
+		let mut eqns = equations(&truth, vec!["foo", "bar"], ivar);
+		assert_eq!(eqns.len(), truth.table[0].output.len());
+		for e in 0..eqns.len() {
+			println!("{}", eqns[e]);
+			eqns[e].simplify();
+		}
+	}
+}
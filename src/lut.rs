@@ -0,0 +1,280 @@
+// Lookup-table output for embedding in Rust, e.g. in firmware: a const array
+// with an entry per input pattern, indexed by the packed inputs as for the
+// jump tables (see switch.rs), each entry holding every output as one bit of
+// the narrowest unsigned integer that fits them; or a macro_rules! expanding
+// to the equations themselves.  Both are 2018-edition Rust.
+use super::Equation;
+use super::bits::{BitOrder, bit_pack_wide};
+use super::enumerate::Inputs;
+use super::emit::{DefaultOutput, Syntax, Unlisted, sum_of_products};
+use super::switch::{packed, packing, rust_name};
+
+const RUST: Syntax = Syntax{not: "!", and: " && ", or: " || ", zero: "false",
+                            one: "true"};
+
+// The most outputs an entry holds.
+pub const MAX_OUTPUTS: usize = 128;
+
+// The integer type of the entries for that many outputs.
+pub fn width(nout: usize) -> Option<&'static str> {
+	match nout {
+		0..=8 => Some("u8"),
+		9..=16 => Some("u16"),
+		17..=32 => Some("u32"),
+		33..=64 => Some("u64"),
+		65..=128 => Some("u128"),
+		_ => None,
+	}
+}
+
+// The entry for each packed value of the inputs: the outputs the equations
+// give, packed in the same order as the inputs, or the default bits for
+// inputs the table doesn't list.  Unlisted inputs that should panic get 0;
+// the function reading the table checks for them.
+pub fn entries(eqns: &[Equation], nbits: usize, unlisted: &Unlisted, order: BitOrder) ->
+	Vec<u128> {
+	let default = match (unlisted.branch().is_some(), &unlisted.policy) {
+		(true, DefaultOutput::Bits(b)) =>
+			Some(eqns.iter().map(|e| b[e.index]).collect()),
+		(true, _) => Some(vec![false; eqns.len()]),
+		_ => None,
+	};
+	Inputs::every(nbits).ordered(order).map(|inp| {
+		let outputs: Vec<bool> = match default {
+			Some(ref d) if !unlisted.is_listed(&inp) => d.clone(),
+			_ => eqns.iter().map(|e| e.evaluate(&inp)).collect(),
+		};
+		bit_pack_wide(&outputs, order)
+	}).collect()
+}
+
+// "x as bit 0 and y as bit 1".
+fn bits(names: &[String], order: BitOrder) -> String {
+	let bits: Vec<String> = names.iter().enumerate()
+		.map(|(k, n)| format!("{} as bit {}", n, order.shift(k, names.len())))
+		.collect();
+	match bits.split_last() {
+		Some((last, rest)) if !rest.is_empty() =>
+			format!("{} and {}", rest.join(", "), last),
+		_ => bits.join(""),
+	}
+}
+
+// What the entries of inputs the table doesn't list hold.
+fn unlisted_note(eqns: &[Equation], outputs: &[String], unlisted: &Unlisted) -> String {
+	match (unlisted.branch().is_some(), &unlisted.policy) {
+		(false, _) => "Inputs the table doesn't list hold what the equations give \
+		               them.".to_string(),
+		(true, DefaultOutput::Bits(b)) => {
+			let values: Vec<String> = eqns.iter().zip(outputs.iter())
+				.map(|(e, n)| format!("{} = {}", n, b[e.index] as u8)).collect();
+			format!("Inputs the table doesn't list hold {}.", values.join(", "))
+		},
+		_ => "Inputs the table doesn't list hold 0, and minterm() panics for \
+		      them.".to_string(),
+	}
+}
+
+// A tuple of the outputs, or the one output alone.
+fn tuple(elts: &[String]) -> String {
+	if elts.len() == 1 { elts[0].clone() } else { format!("({})", elts.join(", ")) }
+}
+
+// The panic for unlisted inputs, when the policy asks for one.
+fn check(unlisted: &Unlisted, inputs: &[String], indent: &str) -> String {
+	match (unlisted.branch(), &unlisted.policy) {
+		(Some(cover), &DefaultOutput::Panic) =>
+			format!("{}assert!({}, \"input not in the table\");\n", indent,
+			        sum_of_products(cover, inputs, &RUST)),
+		_ => String::new(),
+	}
+}
+
+// Writes a const array MAP of the packed outputs for each packed value of the
+// inputs, and a function 'minterm' taking the inputs and returning the outputs
+// read from it.  At most MAX_OUTPUTS outputs.
+pub fn rust_const(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                  order: BitOrder) -> String {
+	let ty = width(eqns.len()).expect("too many outputs for an entry");
+	let inputs: Vec<String> = invars.iter().map(|n| rust_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| rust_name(&e.varname)).collect();
+	let nbits = inputs.len();
+	let mut s = format!("// {}  MAP holds the entry for each packed value, {} of \
+	                     the {}.  {}\n", packing(&inputs, order), bits(&outputs, order),
+	                    ty, unlisted_note(eqns, &outputs, unlisted));
+	s += &format!("pub const MAP: [{}; 1 << {}] = [\n", ty, nbits);
+	// as few digits as show every output, eight entries a line.
+	let digits = eqns.len().div_ceil(4);
+	let strs: Vec<String> = entries(eqns, nbits, unlisted, order).iter()
+		.map(|&v| if eqns.len() <= 8 { format!("0b{:01$b}", v, eqns.len().max(1)) }
+		          else { format!("0x{:01$x}", v, digits) })
+		.collect();
+	for line in strs.chunks(8) {
+		s += &format!("\t{},\n", line.join(", "));
+	}
+	s += "];\n\n";
+	let params: Vec<String> = inputs.iter().map(|n| format!("{}: bool", n)).collect();
+	let ret = tuple(&vec!["bool".to_string(); eqns.len()]);
+	s += &format!("// Returns {}.\n", tuple(&outputs));
+	s += &format!("pub fn minterm({}) -> {} {{\n", params.join(", "), ret);
+	s += &check(unlisted, &inputs, "\t");
+	let cast = |n: &str| format!("({} as usize)", n);
+	let index = if nbits == 0 { "0".to_string() }
+	            else { packed(&inputs, order, &cast) };
+	s += &format!("\tlet entry = MAP[{}];\n", index);
+	let nout = eqns.len();
+	let values: Vec<String> = (0..nout).map(|k| match order.shift(k, nout) {
+		0 => "entry & 1 != 0".to_string(),
+		shift => format!("(entry >> {}) & 1 != 0", shift),
+	}).collect();
+	s += &format!("\t{}\n", tuple(&values));
+	s += "}\n";
+	s
+}
+
+// Writes a macro 'minterm!' taking an expression per input, in the order of
+// the inputs, and expanding to the outputs' equations over them.  Unlisted
+// inputs give the default bits or panic.
+pub fn rust_macro(eqns: &[Equation], invars: &[String], unlisted: &Unlisted) -> String {
+	let inputs: Vec<String> = invars.iter().map(|n| rust_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| rust_name(&e.varname)).collect();
+	let mut s = format!("// minterm!({}) is {}.\n", inputs.join(", "), tuple(&outputs));
+	s += "#[macro_export]\nmacro_rules! minterm {\n";
+	let metas: Vec<String> = inputs.iter().map(|n| format!("${}:expr", n)).collect();
+	s += &format!("\t({}) => {{{{\n", metas.join(", "));
+	if !inputs.is_empty() {
+		let vars: Vec<String> = inputs.iter().map(|n| format!("${}", n)).collect();
+		// an input the equations don't test is still evaluated, once.
+		s += "\t\t#[allow(unused_variables)]\n";
+		s += &format!("\t\tlet {}: {} = {};\n", tuple(&inputs),
+		              tuple(&vec!["bool".to_string(); inputs.len()]), tuple(&vars));
+	}
+	s += &check(unlisted, &inputs, "\t\t");
+	let values: Vec<String> = eqns.iter()
+		.map(|e| sum_of_products(e, &inputs, &RUST)).collect();
+	match (unlisted.branch(), &unlisted.policy) {
+		(Some(cover), DefaultOutput::Bits(b)) => {
+			let defaults: Vec<String> = eqns.iter()
+				.map(|e| if b[e.index] { RUST.one } else { RUST.zero }.to_string())
+				.collect();
+			s += &format!("\t\tif {} {{ {} }} else {{ {} }}\n",
+			              sum_of_products(cover, &inputs, &RUST), tuple(&values),
+			              tuple(&defaults));
+		},
+		_ => s += &format!("\t\t{}\n", tuple(&values)),
+	}
+	s += "\t}};\n";
+	s += "}\n";
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse, Truth};
+	use std::fs;
+	use std::process::Command;
+
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	fn names(n: usize) -> Vec<String> {
+		vec!["a", "b", "c"][..n].iter().map(|n| n.to_string()).collect()
+	}
+
+	// Compiles the code, as 'name', with a main asserting that each row of the
+	// table gives its outputs through 'call', and runs it; false when there is
+	// no rustc to compile it with.
+	fn run_rust(name: &str, src: &str, tbl: &Truth, call: &str) -> bool {
+		let mut program = src.to_string();
+		program += "\nfn main() {\n";
+		for ent in tbl.table.iter() {
+			let inp: Vec<String> = ent.input.iter().map(|b| b.to_string()).collect();
+			let out: Vec<String> = ent.output.iter().map(|b| b.to_string()).collect();
+			program += &format!("\tassert_eq!({}({}), {});\n", call, inp.join(", "),
+			                    tuple(&out));
+		}
+		program += "\tprintln!(\"ok\");\n}\n";
+		let dir = std::env::temp_dir().join(format!("minterm-lut-{}",
+		                                            std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		let file = dir.join(format!("{}.rs", name));
+		fs::write(&file, program).unwrap();
+		let exe = dir.join(name);
+		let out = match Command::new("rustc").args(["--edition", "2018", "-o"])
+			.arg(&exe).arg(&file).output() {
+			Ok(out) => out,
+			Err(_) => return false,
+		};
+		assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+		let out = Command::new(&exe).output().unwrap();
+		assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+		assert_eq!(String::from_utf8(out.stdout).unwrap(), "ok\n");
+		true
+	}
+
+	#[test]
+	fn widths() {
+		assert_eq!(width(1), Some("u8"));
+		assert_eq!(width(9), Some("u16"));
+		assert_eq!(width(64), Some("u64"));
+		assert_eq!(width(65), Some("u128"));
+		assert_eq!(width(MAX_OUTPUTS + 1), None);
+	}
+
+	#[test]
+	fn small_table() {
+		let tbl = parse(SMALL.as_bytes(), 0, 3, 2);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names(3));
+		eqns.iter_mut().for_each(|e| e.simplify());
+		let unlisted = Unlisted::new(DefaultOutput::Zero, &tbl, 3, &names(3));
+		assert_eq!(entries(&eqns, 3, &unlisted, BitOrder::Lsb),
+		           vec![0b10, 0b11, 0b11, 0b11, 0b01, 0b10, 0b00, 0b00]);
+		let src = rust_const(&eqns, &names(3), &unlisted, BitOrder::Lsb);
+		assert!(src.starts_with("// Packs a as bit 0, b as bit 1 and c as bit 2.  MAP \
+		                         holds the entry for each packed value, x as bit 0 and \
+		                         y as bit 1 of the u8."), "{}", src);
+		assert!(src.contains("pub const MAP: [u8; 1 << 3] = [\n\t0b10, 0b11, 0b11, 0b11, \
+		                      0b01, 0b10, 0b00, 0b00,\n];\n"), "{}", src);
+		assert!(src.contains("\tlet entry = MAP[(a as usize) | (b as usize) << 1 | \
+		                      (c as usize) << 2];\n\t(entry & 1 != 0, (entry >> 1) & 1 \
+		                      != 0)\n"), "{}", src);
+		run_rust("small", &src, &tbl, "minterm");
+		let src = rust_const(&eqns, &names(3), &unlisted, BitOrder::Msb);
+		run_rust("small-msb", &src, &tbl, "minterm");
+		let src = rust_macro(&eqns, &names(3), &unlisted);
+		assert!(src.contains("\t($a:expr, $b:expr, $c:expr) => {{\n\
+		                      \t\t#[allow(unused_variables)]\n\
+		                      \t\tlet (a, b, c): (bool, bool, bool) = ($a, $b, $c);\n"),
+		        "{}", src);
+		run_rust("small-macro", &src, &tbl, "minterm!");
+	}
+
+	#[test]
+	fn defaults() {
+		// x = a, y = b', but 11 is missing.
+		let tbl = parse("0,0,,0,1\n0,1,,0,0\n1,0,,1,1\n".as_bytes(), 0, 2, 2);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names(2));
+		eqns.iter_mut().for_each(|e| e.simplify());
+		let unlisted = Unlisted::new(DefaultOutput::Bits(vec![false, true]), &tbl, 2,
+		                             &names(2));
+		// x is the most significant bit of an entry, as a is of the index.
+		assert_eq!(entries(&eqns, 2, &unlisted, BitOrder::Msb),
+		           vec![0b01, 0b00, 0b11, 0b01]);
+		let src = rust_const(&eqns, &names(2), &unlisted, BitOrder::Msb);
+		assert!(src.contains("Inputs the table doesn't list hold x = 0, y = 1.\n"), "{}",
+		        src);
+		let mut full = tbl.clone();
+		full.table.push(super::super::Entry::new(vec![true, true], vec![false, true]));
+		run_rust("bits", &src, &full, "minterm");
+		run_rust("bits-macro", &rust_macro(&eqns, &names(2), &unlisted), &full,
+		         "minterm!");
+		let unlisted = Unlisted::new(DefaultOutput::Panic, &tbl, 2, &names(2));
+		let src = rust_const(&eqns, &names(2), &unlisted, BitOrder::Msb);
+		assert!(src.contains("\tassert!(!b || !a, \"input not in the table\");\n"), "{}",
+		        src);
+		run_rust("panic", &src, &tbl, "minterm");
+		run_rust("panic-macro", &rust_macro(&eqns, &names(2), &unlisted), &tbl,
+		         "minterm!");
+	}
+}
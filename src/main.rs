@@ -1,528 +1,2678 @@
-// Minimize the number of 'if' statements to map between sets of variables.
+// The minterm command line: reads the options and the table, and prints the
+// minimized equations in the chosen format.  The work itself is done by the
+// library.
 //
-// We are given a truth table with 'b' input bits and 'o' output bits.  The
-// task of the program is to minimize the number of if statements required to
-// generate that mapping.
-// Consider this b=3 o=2 system:
-//    000 => 01
-//    001 => 10
-//    010 => 11
-//    011 => 00
-//    100 => 11
-//    101 => 01
-//    110 => 11
-//    111 => 00
-// Let us call the inputs 'a', 'b', and 'c', and the output 'x' and 'y'.
-// Hereafter "a" corresponds to a==1, and "a'" corresponds to a==0.
-// In this case, the program should generate something like:
-//    if(a'b'):
-//      if(c): x = 1
-//      else if(c'): y = 1
-//    if(ab'):
-//      y = 1
-//      if(c'): x = 1
-//    if(bc'): x = y = 1
-// The naive minterms for each this system's outputs would be:
-//    x = a'b'c + a'bc' + ab'c' + abc'
-//    y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
-// Consider a reordering of terms:
-//    x = a'b'c + a'bc' + ab'c' + abc'
-//    y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
-//    =>
-//    x = a'b'c         + ab'c' + abc' + a'bc'
-//    y = a'b'c' + ab'c + ab'c' + abc' + a'bc'
-// Now it is clear that
-//    1) both of the 3 final terms are identical for x and y
-//    2) both "abc'" and "a'bc'" appear.  The "bc'" is identical here, and
-//       because both a and a' appear, we can merge these two, dropping "a".
-// Thus:
-//    x = a'b'c         + ab'c' + bc'
-//    y = a'b'c' + ab'c + ab'c' + bc'
-// Now we have a choice.  Note that y includes the two terms "ab'c" and
-// "ab'c'"; by argument (2) above, we could merge those two terms.  However, it
-// may be more profitable to keep the common subexpression "ab'" so that we can
-// merge the solutions for "x" and "y".
-extern crate csv;
+// Each command has a usage of its own, parsed by docopt once the command is
+// known.  Options without a command are minimize's, as before there were
+// commands.
 extern crate docopt;
+extern crate minterm;
 use docopt::Docopt;
-use std::fmt;
+use minterm::*;
+use minterm::notation::{Conjunction, Negation, Notation};
+use minterm::pregroup::Pregroup;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{BufRead, BufReader, IsTerminal, Write};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use std::fs::File;
 use std::path::Path;
 
-const USAGE: &'static str = "
-Usage: minterm --table <truth> --ivar=<foo>... --ovar=<bar>...
+const USAGE: &str = "
+Usage: minterm <command> [<args>...]
+       minterm (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [options]
+       minterm (-h | --help)
 
-Options:
+Commands:
+  minimize  Minimize a table's outputs and print the equations or code for
+            them.  Options given without a command, as in the second form
+            above, are minimize's.
+  compose   Minimize two tables chained, the first's outputs feeding the
+            second's inputs; short for 'minimize compose'.
+  check     Validate a table and check equations against it.
+  assert    Check invariants over a table's inputs and outputs.
+  diff      Compare two versions of a table by what they compute.
+  vectors   Print a small set of test vectors for a table's equations.
+  estimate  Estimate how hard a table is to minimize, and suggest how.
+  implications  List the implications between a table's outputs.
+  profile   Count how often each term of the equations holds on a trace.
+  template  Write a table listing every input pattern, to be filled in.
+  convert   Rewrite a table in another format or with other encodings.
+  repl      Load a table and query it and its equations interactively.
+  batch     Minimize every table of a directory, each into a file.
+
+'minterm <command> --help' describes the command and its options.
+
+Exit status:
+  0  Success.
+  2  Bad options, or a table file that can't be read.
+  3  The table is not valid: rows with too few columns, repeated rows
+     without --duplicates, missing inputs without --default-output or
+     --missing, or rows breaking a --onehot group.
+  4  An --assert-eq or invariant does not hold, or an equation does not
+     reproduce the table.
+  5  The search stopped at --timeout or --max-steps before proving every
+     equation minimal, fell back on the greedy simplifier at
+     --memory-limit, or the simplifier stopped at --max-passes or
+     --max-merges; the equations found are still printed.
+Messages other than the equations go to stderr.
 ";
 
-// A single entry in a truth table.
-#[derive(Clone, Debug, PartialEq)]
-struct Entry {
-	input: Vec<bool>,
-	output: Vec<bool>,
-}
-impl Entry {
-	fn default() -> Self { Entry{input: vec![], output: vec![]} }
-	#[allow(dead_code)]
-	fn new(inp: Vec<bool>, outp: Vec<bool>) -> Self {
-		Entry{input: inp.clone(), output: outp.clone()}
-	}
-
-	fn clear(&mut self) {
-		self.input.clear();
-		self.output.clear();
-	}
-}
-
-// A Term is a product that is each state of the input bits.  For example, in
-// the system: 00 -> 1, 01 -> 1, 10 -> 0, 11 -> 1, the output equation is:
-//   a'b' + a'b + ab
-// a'b', a'b, and ab are all terms.  We don't have symbolic names in a program,
-// of course, so we just say we have a list where each element is an index and
-// a boolean.  So (0, false) means "a'", whereas (1, true) means "b".
-type Variable = (usize, bool);
-#[derive(Clone, Debug, PartialEq)]
-struct Term {
-	bits: Vec<Variable>,
-	pub names: Vec<String>,
-}
-impl fmt::Display for Term {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		assert!(self.names.len() >= self.bits.len());
-		for var in self.bits.iter() {
-			// completely valid to extend the list of names... but this quickly gets
-			// larger than something that would be computable in finite time.
-			assert!(var.0 < self.names.len()); // so we can convert to a "simple" name
-			if var.1 {
-				try!(write!(f, "{}", self.names[var.0]));
-			} else {
-				try!(write!(f, "{}'", self.names[var.0]));
-			}
-		}
-		write!(f, "")
-	}
-}
-
-impl Term {
-	#[cfg(test)]
-	pub fn new(vals: Vec<Variable>) -> Self {
-		// just used as symbolic names, to avoid calling them "index 7" etc.
-		let nms = ["a","b","c","d","e","f","g","h","i","j","k","l","m","n","o",
-		           "p","q","r","s","t","u","v","w","x","y","z"];
-		let copy = nms.iter().map(|elt| elt.to_string()).collect();
-		Term{bits: vals, names: copy}
-	}
-	pub fn compute(bits: &Vec<bool>) -> Self {
-		let mut rv = vec![];
-		for (i, bit) in bits.iter().enumerate() {
-			rv.push((i, *bit));
-		}
-		let nms = ["a","b","c","d","e","f","g","h","i","j","k","l","m","n","o",
-		           "p","q","r","s","t","u","v","w","x","y","z"];
-		let copy = nms.iter().map(|elt| elt.to_string()).collect();
-		Term{bits: rv, names: copy}
-	}
-	pub fn len(&self) -> usize { self.bits.len() }
-	// true when:
-	//   - these are the same terms sans one variable is opposite (a'b' and ab').
-	pub fn mergeable(&self, other: &Term) -> bool {
-		if self.len() != other.len() { // mismatched terms cannot be merged.
-			return false;
-		}
-		// they must have the same indices (represent the same vars)
-		// we should probably just enforce that the ordering is monotonic; we don't
-		// right now, which forces us to do a linear search every iteration.
-		for t in self.bits.iter() {
-			if let None = other.bits.iter().find(|&o| o.0 == t.0) {
-				return false;
-			}
+// How a table file is stored, for every command that reads or writes one.
+macro_rules! table_options { () => ("
+  --input-encoding=<enc>  How inputs are stored: 'binary' is one 0/1 column
+                     per input, 'index' a single integer column holding the
+                     input pattern, and 'row' means there are no input columns
+                     and a row's position is its input pattern.
+                     [default: binary]
+  --output-encoding=<enc>  How outputs are stored: 'binary' is one 0/1
+                     column per output, 'decimal' a single integer column
+                     holding all output bits.  [default: binary]
+  --ovar-width=<n>   Number of output bits in a decimal output column.  A
+                     single --ovar name 'x' then expands to 'x[0]'...'x[n-1]'.
+  --bit-order=<order>  Which bit of a packed value the first variable is,
+                     wherever variables are packed into a number: an index
+                     of the inputs, a decimal value of the outputs, the rows
+                     of a template, and the code of the verilog-casez,
+                     switch-c, switch-rust and rust-const formats.  'lsb'
+                     makes it the least significant bit, 'msb' the most.
+                     [default: lsb]
+  --msb-first        Short for --bit-order msb.
+  --strict           Fail on a cell of a 0/1 column holding anything else,
+                     such as 2 or -1, rather than warning and going on: a
+                     bad output is a don't-care for that output on its row,
+                     and a row with a bad input is skipped.
+  --lenient-ints     Read 0/1 cells as integers, anything but 0 being 1, as
+                     earlier versions did.
+  --empty=<policy>   How to read an empty 0/1 cell: 'dc' skips a row with an
+                     empty input and makes an empty output a don't-care on
+                     its row, 'zero' reads it as 0, and 'error' fails.
+                     Without it an empty cell is like any other bad cell.
+  --filter=<cond>    Only read the rows of a CSV table whose cell in a column
+                     named in the first header line holds a value, e.g.
+                     'status=final'.  The rows left out are as if the table
+                     didn't have them: their inputs are missing, and go as
+                     the --missing policy says.  May be repeated.
+  --filter-out=<cond>  Likewise leave out the rows holding the value, e.g.
+                     'status=DRAFT'.  May be repeated.
+  --map=<m>          Read a value of a 0/1 column as 0, 1 or x, e.g.
+                     'GLX:YES=1', 'GLX:N/A=x', or for any column '*:OFF=0'.
+                     The value is matched exactly, case and all, before the
+                     cell is read as 0 or 1, and a map for the column wins
+                     over one for '*'.  An x output is a don't-care for that
+                     output on its row; an x input makes the row stand for both
+                     values of it.  Values no map gives are read as without
+                     it, so --strict still rejects them.  May be repeated.
+  --ocol=<col>       The column, named in the first header line or counted
+                     from 0, holding the next output, rather than the outputs
+                     being the rightmost columns; columns after them, such as
+                     notes, are then ignored.  Give one per output column.
+  --comment-char=<c>  Skip the lines of a CSV table whose first cell starts
+                     with this character, and the lines with every cell
+                     empty.  The header lines are counted without them, and
+                     messages give the lines of the file.  [default: #]
+  --no-comments      Read every line of a CSV table as a row, comments and
+                     blank lines included, as earlier versions did.
+  --deny-warnings    Fail, after listing them, if there are any warnings
+                     about the table, such as cells --strict would reject.
+  --quiet            Print nothing but the command's output and errors.
+  -h, --help         Show this text.
+") }
+
+// The table's inputs and outputs, and what the inputs can be, for the
+// commands that check or minimize a table.
+macro_rules! input_options { () => ("
+  --constraint=<expr>  An expression over the inputs that holds for every
+                     input that can occur, e.g. \"!(a & b)\".  Inputs
+                     violating it are don't-cares, whatever the table says,
+                     and assertions are only checked on the others.  May be
+                     repeated.
+  --onehot=<cols>    A comma separated group of inputs of which exactly one
+                     is set, e.g. 'idle,run,stop'.  Every row of the table
+                     must set exactly one of them, and other patterns are
+                     don't-cares.  May be repeated.
+  --assert-eq=<eqn>  Check that a hand-written equation such as
+                     \"x = a'b + c\" or \"x = a & !b | c\" matches the table's
+                     column for x, reporting the rows where it does not.  An
+                     equation that holds is used as the starting point for
+                     minimizing x.  May be repeated.
+  --default-output=<d>  What inputs missing from the table give: 'zero',
+                  one 0/1 per output (e.g. '01'), 'dc' for don't-care, so the
+                  minimizer may choose, or 'panic' for an assertion in the
+                  generated code.  Without it the table must list every
+                  input.
+  --missing=<m>      What to do about inputs missing from the table:
+                     'error' unless --default-output allows them, 'warn'
+                     listing them and then taking them as --default-output
+                     says (or 0), or take them as 'dc' or 'zero' outputs
+                     like --default-output.  [default: error]
+  --duplicates=<d>   What to do about an input the table gives more than
+                     once: 'error', 'merge' the rows if their outputs agree
+                     (a row of don't-cares giving way), or 'last-wins'.
+                     [default: error]
+  --max-enumerate=<n>  The most inputs whose every pattern may be gone
+                     through: to find the inputs the table doesn't list,
+                     those --constraint and --onehot rule out, and for the
+                     likes of from-expr, --given, --emit-complements and the
+                     formats listing every pattern.  Above it these fail
+                     rather than run out of memory or time.  [default: 24]
+  --dump-table       Print the table on stderr as it is minimized or checked,
+                     after --constraint, --onehot and the missing inputs
+                     have their say: the rows, then the don't-cares merged
+                     into patterns with x for the inputs they don't depend
+                     on, their outputs x.
+") }
+
+const MINIMIZE_USAGE: &str = concat!("
+Usage: minterm minimize (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--only=<ovar>]... [--skip=<ovar>]... [--assert-eq=<eqn>]... [--weight=<w>]... [--algorithm-for=<decl>]... [--given=<decl>]... [--constraint=<expr>]... [--onehot=<cols>]... [--observe=<decl>]... [--group=<decl>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm minimize from-expr --ivar=<foo>... (--expr=<eqn>)... [--only=<ovar>]... [--skip=<ovar>]... [--weight=<w>]... [--algorithm-for=<decl>]... [--given=<decl>]... [--constraint=<expr>]... [--onehot=<cols>]... [--observe=<decl>]... [--group=<decl>]... [options]
+       minterm minimize compose (--table <truth>)... --ivar=<foo>... --ovar=<bar>... (--wire=<w>)... [--only=<ovar>]... [--skip=<ovar>]... [--weight=<w>]... [--algorithm-for=<decl>]... [--given=<decl>]... [--constraint=<expr>]... [--onehot=<cols>]... [--observe=<decl>]... [--group=<decl>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm minimize --from-cover=<cov> [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm minimize --help
+
+Minimizes each output of the table and prints the equations.
+
+A table file ending in .gz, or starting as gzip data does, is decompressed
+first, as is one ending in .zst with the zstd feature built in; '--table -'
+reads the table from stdin, which may be compressed too.
+
+The table may be split across several files with the same input columns and
+their own output columns.  Each --table then takes the --ovar names that
+follow it, e.g. '--table a.csv --ovar x --table b.csv --ovar y --ovar z'.
+
+When the first header line of a single table names every --ovar, each output
+is read from the column of its name, whatever order they are given in.
+Otherwise the outputs are the rightmost columns in order, with a warning for
+more than one.  '--ovar x@col' reads the output from the column named, or
+counted from 0, instead; give every --ovar so, or none.
+
+A single table may start with a schema line, such as
+'#! minterm inputs=a,b,c outputs=x,y sparse=dc duplicates=merge', standing
+for the --ivar, --ovar, --missing and --duplicates options the command line
+leaves out.  An option given anyway wins, with a warning if it differs.
+
+With from-expr there is no table file: each --expr \"x = a'b + c\" defines an
+output by an expression over the --ivar inputs (see --assert-eq for the
+syntax; '^' is also allowed), and the table is built by evaluating them.
+
+With compose, 'minterm compose' for short, there are two tables, each with
+the --ivar and --ovar names following its --table, and --wire feeds outputs
+of the first into inputs of the second.  The table minimized is the
+composite: its inputs are the first table's and the second's that no wire
+feeds, its outputs the second's.  An input the first table doesn't list,
+or that leads to one the second doesn't, is a don't-care.
+
+With --from-cover there is no table to minimize: the equations are those of
+a cover file that --emit-cover wrote, and are only written in the --format
+asked for, so that a slow minimization need run once.
+
+Options:
+  --algorithm=<alg>  Minimization algorithm: 'greedy' merges terms pairwise,
+                     'exact' searches for a minimum cover.  [default: greedy]
+  --algorithm-for=<decl>  '<ovar>=<alg>': minimize the output with this
+                     algorithm instead, e.g. 'y=greedy' for one too large to
+                     search.  Reports the algorithm each output came to,
+                     noting exact searches stopped at the time or step limit
+                     or outgrowing the memory limit.  May be repeated.
+  --given=<decl>     '<name> = <expr>': a condition over the inputs that the
+                     code computes anyway, e.g. 'cond1 = a & !b', which the
+                     equations may then test like an input, in either
+                     polarity, where that makes them cheaper.  The exact
+                     search finds those places best.  May be repeated.
+  --timeout=<secs>   Stop the exact search after this many seconds and use the
+                     best cover found so far.
+  --max-steps=<n>    Stop the exact search after this many search nodes.
+  --memory-limit=<MB>  Keep the tables of merged terms and the exact search's
+                     chart of each output under about this many megabytes,
+                     simplifying greedily where they would be larger.
+  --max-passes=<n>   Stop the greedy simplifier of each output after this
+                     many levels of merging terms, covering the output with
+                     the terms merged so far: correctly, but only partially
+                     simplified.
+  --max-merges=<n>   Likewise, after this many merges of two terms.
+  --improve=<secs>   After the greedy simplifier, spend up to this many seconds
+                     reshaping each output's terms at random, shrinking and
+                     regrowing them and dropping those the rest cover, and
+                     keep the cheapest cover found.  Reports what it gained.
+                     Outputs of at most 20 inputs.
+  --seed=<n>         Seeds the random choices of --improve, which given the
+                     same seed and time make the same cover.  [default: 0]
+  --onehot-encode    Minimize with each --onehot group of k inputs encoded as
+                     a ceil(log2 k) bit number, printing the encoding, and
+                     write the equations over the original inputs.
+  --weight=<w>       Make literals of an input more (or less) costly for the
+                     exact search, e.g. 'sel=3' if 'sel' is expensive to
+                     compute.  Inputs weigh 1 by default.  May be repeated.
+  --all-minimal      Find every minimum cover of each output with the exact
+                     search, listing the others as alternatives ('or ...').
+  --max-solutions=<n>  The most covers --all-minimal lists per output.
+                     [default: 16]
+  --cost=<cost>      What to minimize: 'terms' minimizes each output on its
+                     own, 'conditions' the number of distinct terms across all
+                     outputs, a term shared by several outputs counting once,
+                     and 'literals' the literals of those distinct terms.
+                     'conditions' always uses the exact search and prints each
+                     condition with the outputs it sets.  'literals' merges
+                     terms greedily, all outputs together, merging a term
+                     another output shares only where that costs fewer
+                     literals than keeping it.  [default: terms]
+  --pregroup=<p>     What the covers are minimized from: 'none' the rows
+                     of the table, a minterm each; 'rows' the table
+                     compressed into cubes first, merging rows whose inputs
+                     differ in one place and whose outputs are alike; 'auto'
+                     both, keeping whichever cover is cheaper under --cost
+                     and noting which.  Only the greedy simplifier can come
+                     to different covers from the two.  [default: none]
+  --only=<ovar>      Only minimize the named output; may be repeated.  The
+                     whole table is still parsed and validated.
+  --skip=<ovar>      Do not minimize the named output; may be repeated.
+  --progress         Report progress on stderr even when it is not a terminal.
+  --canonical        Write each equation's terms in canonical order, which
+                     depends only on which terms there are: fewest literals
+                     first, then by the inputs they test, then by the values
+                     they test them for.  Shuffling the table's rows doesn't
+                     change the output.  This is the default.
+  --table-order      Write the terms in the order the search gives them
+                     instead: for greedy, the order of the first row of the
+                     table each covers.
+  --form=<form>  Algebraic form of the output equations: 'sop' for a sum of
+                 products, 'anf' for the XOR-of-ANDs (Reed-Muller) form.
+                 [default: sop]
+  --format=<fmt>  How to write the equations: 'text' as above,
+                  'verilog-casez' for a Verilog module assigning all outputs
+                  from one casez over the inputs, 'vhdl' for a VHDL
+                  entity and architecture, 'python' for a Python
+                  function 'map' returning a tuple of the outputs, or
+                  'sets' for each output's ON-, DC- and OFF-sets of the
+                  table, unminimized, 'bdd' for the size of each output's
+                  binary decision diagram with the inputs in their order
+                  and in the best order sifting finds, or 'bdd-dot' for
+                  the latter diagrams in Graphviz's DOT language.  The
+                  diagrams take don't-cares as 0.  'switch-c' writes a C
+                  function switching over the inputs packed into an
+                  integer, with a case per input pattern setting every
+                  output, and 'switch-rust' a Rust function matching on
+                  them likewise.  'if-chain-c' writes a C function setting
+                  the outputs from an if/else-if chain, for outputs of which
+                  at most one is 1 for any input, as a decoder's are; each
+                  condition leaves out what the branches before it rule
+                  out.  'rust-const' writes a Rust const array
+                  of an entry per packed input pattern, holding the outputs
+                  as the bits of the narrowest integer type fitting them,
+                  and a function reading it.  The inputs, and the outputs
+                  of an entry, are packed as --bit-order says.
+                  'share-dot' draws the products of the equations and the
+                  outputs having them as a Graphviz graph, showing which
+                  products several outputs share.  'coverage' lists every
+                  term, broadest first, with how many input patterns it
+                  covers, how many of those are rows of the table and how
+                  many don't-cares, and its share of the input space.
+                  [default: text]
+  --broad=<pct>   With --format coverage, flag the terms covering more than
+                  this percentage of the input space with more don't-cares
+                  than rows.  [default: 25]
+  --macro         With --format rust-const, write a macro_rules! expanding
+                  to the equations instead of the array.
+  --collapse-unshared  With --format share-dot, draw the products only one
+                  output has as a single node per output.
+  --neg-style=<s>  How the equations write a negated input: 'prime' a',
+                  'bang' !a, 'overline-unicode' a\u{305} or 'not' NOT a, in the
+                  text, --explain, --format coverage and share-dot.
+                  [default: prime]
+  --and-style=<s>  How the equations write a product: 'concat' ab, 'dot'
+                  a\u{b7}b, 'amp' a & b or 'word' a AND b.  Unless given, concat
+                  while every input is named by one character and amp
+                  otherwise.  An input name the styles would misread is
+                  written in double quotes.
+  --compress      With --format switch-c or switch-rust, give the input
+                  patterns that set the outputs alike a single case:
+                  fallthrough labels in C, ranges and alternatives in Rust.
+  --share-terms   Name each product that several outputs' equations have,
+                  define it once and use the name in the equations: a
+                  line 't0 = bc';' in text, a signal in VHDL, a local
+                  variable in Python.  Most useful with --cost conditions.
+  --share-prefix=<p>  The names of the shared products are this and a
+                  number.  [default: t]
+  --min-reuse=<n>  How many outputs must have a product for it to be
+                  shared.  [default: 2]
+  --emit-complements  Also minimize and write the complement of each
+                  output, 1 where it is 0, named with '_n' after it.  Each
+                  complement is minimized from its output's cover, so the
+                  two are complementary on every input, and checked to be.
+                  With --share-terms they share products as any outputs do.
+  --loose-complements  With --emit-complements, minimize each complement
+                  from the table alongside the outputs instead, so that an
+                  output and its complement may take a don't-care alike;
+                  they are checked on the table's rows.  --cost conditions
+                  then searches for the products of both together.
+  --reuse-outputs  Compute an output from another where that takes fewer
+                  literals: from x = ab, y = ab + c becomes 'y = x + c',
+                  and y = abc + abd' 'y = cx + d'x'.  The equations are
+                  written in an order computing each output before those
+                  using it.  Only --format text and python.
+  --use-implications  Compute an output from another where an implication
+                  between them holds on every row, as 'minterm implications'
+                  lists them: with x -> y, y's rows where x is 1 are
+                  don't-cares for its cover, and 'y = x + cover'; with
+                  x -> y', each term of the cover is given x'.  Each output
+                  uses the implication taking the most 1s out of its cover.
+                  The equations are written in an order computing each
+                  output before those using it.  Only --format text and
+                  python.
+  --freq-col=<col>  A column of the table, named in its first header line or
+                  counted from 0, giving how often each row's input occurs,
+                  such as counts from a profile; an empty or 0 cell counts
+                  once.  Each equation's terms are then written with the
+                  terms of the frequent inputs first, rather than in the
+                  canonical order, so that code testing them in turn and
+                  stopping at the first that holds tests few on average.
+                  Reports the terms tested on average in either order.
+                  Only --format text and python.
+  --explain       Under each equation, list the rows of the table each term
+                  covers, with their lines in the table file, and the two
+                  terms it was merged from.  With --cost literals, also the
+                  merges weighed for it, made or not, and the terms and
+                  literals of all outputs with and without each.  Only for
+                  text output.
+  --hazard-free   Add redundant terms so that every pair of adjacent inputs
+                  giving 1 shares a term, so an output cannot glitch to 0
+                  while a single input changes.  The added terms are
+                  reported.
+  --return-dict   With --format python, return a dict keyed by output name
+                  instead of a tuple.
+  --dict-lookup   With --format python, emit the table as a dict literal and
+                  have 'map' look its inputs up in it, instead of the
+                  equations.
+  --show-completion  List the table's don't-care rows on stderr, as a CSV
+                  table, with the outputs the covers give them.  Among
+                  them are the inputs --constraint or --onehot rule out,
+                  and with --missing dc the inputs the table lacks.  The
+                  generated code gives the rows those outputs rather than
+                  --default-output's.  Where the conditions of a --group
+                  hold for several of its values on a row, or for none, the
+                  row takes the value the group's match gives it, with a
+                  warning.
+  --watch         Keep running after printing the equations, and whenever
+                  the table file changes update them for the rows that
+                  changed, printing the equations that differ.  Changes
+                  are merged into the covers greedily, so it can't be
+                  combined with --algorithm exact or --weight.
+  --emit-cover=<file>  Also write the equations to this file, with the
+                  inputs and outputs and what the table doesn't list, so
+                  that --from-cover can write them later in any format.
+                  Not with --group, --form anf, or the formats sets, bdd
+                  and bdd-dot.
+  --emit-tests=<fw>  With --format switch-c or if-chain-c, also write a test
+                  of the C code for the framework 'gtest', 'catch2' or
+                  'cunit' to --tests-file.  It runs the code on the test
+                  vectors of 'minterm vectors', or on every row should they
+                  not check out, asserting each output the table gives.
+  --tests-file=<file>  The file --emit-tests writes.
+  --tests-include=<file>  The header the test includes the C code by.
+                  [default: minterm.h]
+  --tests-sample=<n>  Also test n inputs the table doesn't list, picked at
+                  random by --seed, asserting the outputs --default-output
+                  gives them, and none with dc.
+  --from-cover=<file>  Write the equations of a file --emit-cover wrote,
+                  instead of minimizing a table.
+  --verify-table=<csv>  With --from-cover, first check the equations
+                  against this table, its inputs and outputs those of the
+                  cover, and fail with the rows they get wrong.
+  --wire=<w>      With compose, outputs of the first table feeding inputs
+                  of the second, as 'aout=bin,...'.  May be repeated.
+  --carry         With compose, also output the first table's outputs that
+                  feed no input.
+  --observe=<decl>  '<ovar> when <expr>': the output is only used on rows
+                  where the expression over the inputs and the other
+                  outputs holds, and is a don't-care on the others.  Reports
+                  how many rows that relaxes.  --assert-eq only checks the
+                  rows where it is used.  May be repeated.
+  --group=<decl>  'name=o1,o2,...': the outputs together hold one value,
+                  such as the encoding of an enum.  In place of their
+                  equations, the condition on the inputs giving each value
+                  they take is minimized: with --format text a line per
+                  value, 'sel = 01 when a'b + c;', and with --format
+                  switch-rust a function named for the group matching on
+                  the inputs, an arm per value.  Encodings no row has are
+                  never asked for.  May be repeated.",
+input_options!(), table_options!());
+
+const CHECK_USAGE: &str = concat!("
+Usage: minterm check (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--assert-eq=<eqn>]... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm check --help
+
+Reads and validates the table as minimize does, and checks each --assert-eq
+against it, without minimizing anything.  Prints a summary of the table if
+all is well.
+
+Options:
+  --report           Print a JSON report of each --assert-eq instead: the
+                     rows it gets wrong, the first few with the table's
+                     output and the equation's, and the cube with the fewest
+                     literals, grown from the first, whose rows it all gets
+                     wrong alike.  The exit status is 4 if any fails.
+  --max-counterexamples=<k>  The most wrong rows the report lists per
+                     assertion.  [default: 5]", input_options!(), table_options!());
+
+const ASSERT_USAGE: &str = concat!("
+Usage: minterm assert (--table <truth>)... --ivar=<foo>... --ovar=<bar>... (--expr=<inv>)... [--assert-eq=<eqn>]... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm assert --help
+
+Checks invariants of the table: expressions over its inputs and outputs that
+must hold on every row, such as 'x | y' (x and y are never both 0) or
+'GL -> x' (x is 1 whenever GL is).  Besides the operators of equations, '->'
+is implication, binding more loosely than any of them.  A row whose outputs
+are don't-cares, in the table or by --default-output, must satisfy an
+invariant whatever their values.  When one fails, the first few rows
+breaking it are listed and the exit status is 4.
+
+Options:
+  --expr=<inv>       An invariant to check.  May be repeated.", input_options!(),
+table_options!());
+
+const DIFF_USAGE: &str = concat!("
+Usage: minterm diff <old> <new> --ivar=<foo>... --ovar=<bar>... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm diff --help
+
+Compares two versions of a table by what they compute: lists the input
+patterns whose outputs changed and the old and new equation of each changed
+output, and exits with 1 if anything changed, otherwise 0.  An input missing
+from a table gives all 0s.
+
+Options:
+  --neg-style=<s>  How the equations write a negated input, as minimize
+                   does.  [default: prime]
+  --and-style=<s>  How the equations write a product, as minimize does.",
+table_options!());
+
+const VECTORS_USAGE: &str = concat!("
+Usage: minterm vectors (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--assert-eq=<eqn>]... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm vectors --help
+
+Minimizes the table's outputs as minimize does and prints test vectors for
+the equations: a few inputs, each with the outputs the equations give it,
+that make every term of every equation true at least once and every output
+0 at least once, unless it is 1 on every input.  Inputs come from the
+table's rows where they can.  The vectors are checked against the equations
+and the table before they are printed; if they fall short the exit status
+is 4.
+
+Options:
+  --format=<fmt>     How to write the vectors: 'csv', a table in minterm's
+                     format; 'rust', a const array of (inputs, outputs)
+                     pairs; or 'c', an array of rows of the inputs followed
+                     by the outputs, as 0s and 1s.  [default: csv]",
+input_options!(), table_options!());
+
+const ESTIMATE_USAGE: &str = concat!("
+Usage: minterm estimate (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm estimate --help
+
+Estimates how hard the table is to minimize, without minimizing it: for each
+output its minterms, don't-cares and maxterms, an upper bound on its prime
+implicants, and whether it is constant or unate; then which --algorithm to
+use, and the order of magnitude of the time it will take.  Above 65536 rows,
+unateness is checked on a sample of them.
+
+Options:
+  --json             Print the estimate as a JSON object.", input_options!(),
+table_options!());
+
+const IMPLICATIONS_USAGE: &str = concat!("
+Usage: minterm implications (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm implications --help
+
+Lists the implications between pairs of outputs that hold on every row of
+the table, such as 'x -> y' (y is 1 whenever x is) or 'x -> y\'' (y is 0
+whenever x is 1), one of each pair and its contrapositive.  Only the rows
+the table, or --default-output, gives outputs count: a don't-care neither
+makes nor breaks an implication.  One whose left side never holds, or whose
+right side always does, isn't listed.  minimize --use-implications computes
+an output from another by them.
+
+Options:
+  --neg-style=<s>  How to write a negated output, as minimize writes a
+                   negated input.  [default: prime]",
+input_options!(), table_options!());
+
+const PROFILE_USAGE: &str = concat!("
+Usage: minterm profile (--table <truth>)... --ivar=<foo>... --ovar=<bar>... --trace=<file> [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm profile --help
+
+Minimizes the table's outputs as vectors does, and counts how often each
+term of each equation holds on a trace: the input patterns a system was seen
+to take at runtime.  Prints, for each output, how often it was 1, then each
+term with its count, marking those never activated.  The trace has a line
+per input pattern, its 0/1 cells separated by commas or written together as
+'0110', then optionally the times it was seen, else once.  Empty cells, a
+first line naming the inputs, blank lines and lines starting with '#' are
+skipped.
+
+Options:
+  --trace=<file>     The trace, which may be gzip or zstd compressed as a
+                     table may.
+  --prune-unactivated  Also minimize the table with the rows whose inputs
+                     the trace never has as don't-cares, and print those
+                     equations after the profile, under a comment saying
+                     so.  They depend on the trace: they agree with the
+                     table only on the inputs it has.
+  --neg-style=<s>  How the equations write a negated input, as minimize
+                   does.  [default: prime]
+  --and-style=<s>  How the equations write a product, as minimize does.",
+input_options!(), table_options!());
+
+// Why minterm stops without printing equations, or with equations that fall
+// short; each gives its own exit status, as the usage describes.
+enum Failure {
+	Usage(String),
+	Invalid(String),
+	Verification(String),
+	// the equations are printed, but not all are proven minimal.
+	Unproven,
+	// diff found differences; the report is the output.
+	Changed,
+}
+impl Failure {
+	fn code(&self) -> i32 {
+		match *self {
+			Failure::Changed => 1,
+			Failure::Usage(_) => 2,
+			Failure::Invalid(_) => 3,
+			Failure::Verification(_) => 4,
+			Failure::Unproven => 5,
 		}
+	}
+}
+// A table with nothing to minimize is not one minterm can use.
+impl From<EmptyTable> for Failure {
+	fn from(e: EmptyTable) -> Failure {
+		Failure::Invalid(e.to_string())
+	}
+}
+// Reports the failure on stderr and exits with its status.
+fn stop(f: Failure) -> ! {
+	match f {
+		Failure::Usage(ref msg) | Failure::Invalid(ref msg) |
+		Failure::Verification(ref msg) => eprintln!("{}", msg),
+		Failure::Unproven | Failure::Changed => {},
+	}
+	std::process::exit(f.code())
+}
+// Returns the kind of failure with a message, formatted as by format!.
+macro_rules! fail {
+	($kind:ident, $($arg:tt)*) => (return Err(Failure::$kind(format!($($arg)*))))
+}
 
-		// now count the number of bits that differ.
-		let mut n_different: usize = 0;
-		for t1 in self.bits.iter() {
-			if let Some(bit) = other.bits.iter().find(|&o| o.0 == t1.0) {
-				if bit.1 != t1.1 {
-					n_different = n_different + 1;
-				}
-			}
+mod batch;
+mod convert;
+mod repl;
+mod template;
+mod watch;
+
+// Each command and its usage.
+const COMMANDS: [(&str, &str); 12] = [
+	("minimize", MINIMIZE_USAGE),
+	("check", CHECK_USAGE),
+	("assert", ASSERT_USAGE),
+	("diff", DIFF_USAGE),
+	("vectors", VECTORS_USAGE),
+	("estimate", ESTIMATE_USAGE),
+	("implications", IMPLICATIONS_USAGE),
+	("profile", PROFILE_USAGE),
+	("template", template::USAGE),
+	("convert", convert::USAGE),
+	("repl", repl::USAGE),
+	("batch", batch::USAGE),
+];
+
+// Parses the command line against a command's usage.  An option the command
+// doesn't have is reported with the closest one it does have, rather than
+// docopt's bare usage line.
+fn parse_args(usage: &str, argv: &[String]) -> Result<docopt::ArgvMap, Failure> {
+	let err = match Docopt::new(usage).and_then(|d| d.argv(argv.iter()).parse()) {
+		Ok(args) => return Ok(args),
+		Err(e) => e,
+	};
+	// --help is not an error.
+	if !err.fatal() {
+		err.exit();
+	}
+	let known: Vec<&str> = usage.split(|c: char| !(c.is_alphanumeric() || c == '-'))
+		.filter(|w| w.starts_with("--") && w.len() > 2)
+		.collect();
+	let unknown = argv.iter().skip(1)
+		.filter(|a| a.starts_with("--"))
+		.map(|a| a.split('=').next().unwrap())
+		.find(|a| !known.contains(a));
+	match unknown {
+		Some(opt) => fail!(Usage, "Unknown option '{}'.{}  See 'minterm {} --help'.", opt,
+		                   did_you_mean(opt, &known), argv[1]),
+		None => Err(Failure::Usage(err.to_string())),
+	}
+}
+
+// The value of the option, which must be one of 'known'.
+fn choice<'a>(args: &docopt::ArgvMap, opt: &str, known: &[&'a str]) ->
+	Result<&'a str, Failure> {
+	let value = args.get_str(opt);
+	match known.iter().find(|&&k| k == value) {
+		Some(k) => Ok(k),
+		None => {
+			let quoted: Vec<String> = known.iter().map(|k| format!("'{}'", k)).collect();
+			let (last, rest) = quoted.split_last().unwrap();
+			fail!(Usage, "Unknown {} '{}'; expected {} or {}.{}", opt, value,
+			      rest.join(", "), last, did_you_mean(value, known))
+		},
+	}
+}
+
+// The --bit-order, or --msb-first.
+fn bit_order(args: &docopt::ArgvMap) -> Result<bits::BitOrder, Failure> {
+	if args.get_bool("--msb-first") {
+		return Ok(bits::BitOrder::Msb);
+	}
+	bits::BitOrder::parse(args.get_str("--bit-order")).map_err(Failure::Usage)
+}
+
+// The --neg-style and --and-style for inputs named 'invars'.
+fn notation(args: &docopt::ArgvMap, invars: &[String]) -> Result<Notation, Failure> {
+	let neg = Negation::parse(args.get_str("--neg-style")).map_err(Failure::Usage)?;
+	let and = match args.get_str("--and-style") {
+		"" => None,
+		s => Some(Conjunction::parse(s).map_err(Failure::Usage)?),
+	};
+	Ok(Notation::new(invars, Some(neg), and))
+}
+
+// The --max-enumerate: the most inputs whose every pattern may be listed.
+fn max_enumerate(args: &docopt::ArgvMap) -> Result<usize, Failure> {
+	match args.get_str("--max-enumerate") {
+		"" => Ok(MAX_ENUMERATED_BITS),
+		s => match s.parse::<usize>() {
+			Ok(n) if n <= enumerate::MAX_BITS => Ok(n),
+			_ => fail!(Usage, "Invalid --max-enumerate '{}'; expected a number of inputs \
+			                   up to {}.", s, enumerate::MAX_BITS),
+		},
+	}
+}
+
+// A failure to enumerate, saying how to allow it.
+fn too_many(msg: String) -> Failure {
+	Failure::Usage(format!("{} Give a larger --max-enumerate to allow more.", msg))
+}
+
+// The --input-encoding and --output-encoding.
+fn encodings(args: &docopt::ArgvMap) -> Result<(InputEncoding, OutputEncoding), Failure> {
+	let order = bit_order(args)?;
+	let inenc = match choice(args, "--input-encoding", &["binary", "index", "row"])? {
+		"binary" => InputEncoding::Binary,
+		"index" => InputEncoding::Index{order},
+		_ => InputEncoding::Row{order},
+	};
+	let outenc = match choice(args, "--output-encoding", &["binary", "decimal"])? {
+		"binary" => OutputEncoding::Binary,
+		_ => OutputEncoding::Decimal{order},
+	};
+	Ok((inenc, outenc))
+}
+
+// How to take cells of 0/1 columns holding something else.
+fn cells(args: &docopt::ArgvMap) -> Result<Cells, Failure> {
+	match (args.get_bool("--strict"), args.get_bool("--lenient-ints")) {
+		(true, true) => fail!(Usage, "--strict can't be combined with --lenient-ints."),
+		(true, false) => Ok(Cells::Strict),
+		(false, true) => Ok(Cells::Lenient),
+		(false, false) => Ok(Cells::Warn),
+	}
+}
+
+// How to take empty cells of 0/1 columns, when not as cells() says.
+fn empty(args: &docopt::ArgvMap) -> Result<Option<Empty>, Failure> {
+	match args.get_str("--empty") {
+		"" => Ok(None),
+		s => Empty::parse(s).map(Some).map_err(Failure::Usage),
+	}
+}
+
+// Which rows to read and where the outputs and frequencies are, by --filter,
+// --filter-out, --ocol and --freq-col.  The columns are those of a single
+// table, whose contents are 'data'.
+fn layout(args: &docopt::ArgvMap, data: &[u8]) -> Result<Layout, Failure> {
+	let mut filters: Vec<Filter> = vec![];
+	for (opt, keep) in [("--filter", true), ("--filter-out", false)].iter() {
+		for s in args.get_vec(opt) {
+			filters.push(Filter::parse(s, *keep).map_err(Failure::Usage)?);
+		}
+	}
+	if !args.get_vec("--ocol").is_empty() && args.get_vec("<truth>").len() > 1 {
+		fail!(Usage, "--ocol only applies to a single --table.");
+	}
+	let freq = match args.get_str("--freq-col") {
+		"" => None,
+		_ if args.get_vec("<truth>").len() > 1 =>
+			fail!(Usage, "--freq-col only applies to a single --table."),
+		col => Some(col.to_string()),
+	};
+	let comment = match args.get_str("--comment-char") {
+		_ if args.get_bool("--no-comments") => None,
+		c if c.chars().count() == 1 && c != "," => c.chars().next(),
+		c => fail!(Usage, "Invalid --comment-char '{}'; expected one character other \
+		                   than a comma.", c),
+	};
+	let mut maps: Vec<CellMap> = vec![];
+	for s in args.get_vec("--map") {
+		let m = CellMap::parse(s).map_err(Failure::Usage)?;
+		if maps.iter().any(|o| o.column == m.column && o.value == m.value) {
+			fail!(Usage, "--map gives '{}:{}' twice.", m.column, m.value);
 		}
-		return n_different == 1;
+		maps.push(m);
 	}
+	let mut layout = Layout{filters, ocols: vec![], freq, comment, maps};
+	layout.ocols = output_columns(args, &layout, data)?.0;
+	Ok(layout)
+}
 
-	fn remove_index(&mut self, idx: usize) {
-		self.bits.retain(|&b| b.0 != idx);
+// An --ovar's name, and the column it names with 'name@col'.
+fn split_ovar(s: &str) -> (&str, Option<&str>) {
+	match s.rfind('@') {
+		Some(at) => (&s[..at], Some(&s[at + 1..])),
+		None => (s, None),
 	}
 }
 
-// An equation is a collection of Terms, where the OR of Terms gives the
-// result.
-#[derive(Clone, Debug, PartialEq)]
-struct Equation {
-	index: usize,
-	terms: Vec<Term>,
-	varname: String,
+// The --ovar names without their columns.
+fn ovar_names(args: &docopt::ArgvMap) -> Vec<&str> {
+	args.get_vec("--ovar").into_iter().map(|s| split_ovar(s).0).collect()
 }
-impl Equation {
-	/// @param tbl the truth table to compute this from
-	/// @param idx the index of the output variable we're creating
-	/// @param vn the variable name of the output variable
-	/// @param invars the names of the input variables
-	// Takes a truth table and the index of the output variable to compute
-	// equations for.
-	fn new(tbl: &Truth, idx: usize, vn: &str, invars: &Vec<String>) -> Self {
-		let mut rv: Vec<Term> = vec![];
-		for ent in tbl.table.iter() {
-			assert!(idx < ent.output.len());
-			// 0 bits don't contribute terms.
-			if ent.output[idx] == false {
-				continue;
-			}
-			// compute the term and add it to our list ...
-			let mut term = Term::compute(&ent.input);
-			term.names = invars.clone();
-			rv.push(term);
-		}
-		Equation{index: idx, terms: rv, varname: vn.to_string()}
-	}
-
-	// Tries to minimize this equation.
-	fn simplify(&mut self) {
-		// Essentially the only option we have is identifying opposite
-		// subexpressions: a'b' + a'b simplifies to a'.
-		let mut idx_remove: (usize, usize) = Default::default(); // index, bit.
-		let mut term_remove: usize = Default::default();
-		let mut found = false;
-		for (t1_loc, t1) in self.terms.iter().enumerate() {
-			for (t2_loc, t2) in self.terms.iter().enumerate() {
-				if t1 == t2 { continue; }
-				if t1.mergeable(&t2) {
-					// Then we can drop the bit that differs.
-					found = true;
-					assert!(t1.len() == t2.len());
-
-					let mut iter = t1.bits.iter().zip(t2.bits.iter());
-					// Which bit is it?  The indices are the same, bit itself differs.
-					let index = iter.find(|&(b1, b2)| b1.0 == b2.0 && b1.1 != b2.1);
-					match index {
-						None => panic!("mergeable but no opposite bits?"),
-						Some((idx, _)) => {
-							idx_remove = (t1_loc, idx.0);
-							term_remove = t2_loc;
-							break;
-						}
-					};
-				}
-			}
+
+// The columns holding the outputs, for Layout::ocols: those --ocol or
+// 'name@col' give, or with neither, the columns of the --ovar names when the
+// first header line of the single --table, whose contents are 'data', names
+// them all.  Empty when the outputs are the rightmost columns, in order; the
+// flag is then whether that binds more than one output by position alone, as
+// nothing names them.
+fn output_columns(args: &docopt::ArgvMap, layout: &Layout, data: &[u8]) ->
+	Result<(Vec<String>, bool), Failure> {
+	let ovars: Vec<(&str, Option<&str>)> = args.get_vec("--ovar").into_iter()
+		.map(split_ovar).collect();
+	let ocols: Vec<String> = args.get_vec("--ocol").iter().map(|s| s.to_string())
+		.collect();
+	let files = args.get_vec("<truth>");
+	if ovars.iter().any(|&(_, col)| col.is_some()) {
+		if let Some(&(name, _)) = ovars.iter().find(|&&(_, col)| col.is_none()) {
+			fail!(Usage, "--ovar {} names no column; give every --ovar as name@col, or \
+			              none.", name);
+		}
+		if let Some(&(name, _)) = ovars.iter().find(|&&(_, col)| col == Some("")) {
+			fail!(Usage, "--ovar {}@ names no column; expected name@col.", name);
+		}
+		if !ocols.is_empty() {
+			fail!(Usage, "--ocol and --ovar name@col both bind the outputs; give one.");
+		}
+		if files.len() != 1 {
+			fail!(Usage, "--ovar name@col only applies to a single --table.");
 		}
-		if found {
-			self.terms[idx_remove.0].remove_index(idx_remove.1);
-			self.terms.remove(term_remove);
-			self.simplify();
+		if !args.get_str("--ovar-width").is_empty() {
+			fail!(Usage, "--ovar name@col can't be combined with --ovar-width.");
 		}
+		return Ok((ovars.iter().map(|&(_, col)| col.unwrap().to_string()).collect(),
+		           false));
+	}
+	let binary = choice(args, "--output-encoding", &["binary", "decimal"])? == "binary";
+	if !ocols.is_empty() || files.len() != 1 || !binary ||
+	   !args.get_str("--ovar-width").is_empty() {
+		return Ok((ocols, false));
+	}
+	let header = header(data, layout).unwrap_or_default();
+	let named: Vec<&str> = ovars.iter().map(|&(name, _)| name)
+		.filter(|&name| header.iter().any(|h| h == name)).collect();
+	if named.is_empty() {
+		return Ok((vec![], ovars.len() > 1));
+	}
+	if let Some(&(name, _)) = ovars.iter().find(|&&(name, _)| !named.contains(&name)) {
+		let cells: Vec<&str> = header.iter().map(|h| h.as_str()).filter(|h| !h.is_empty())
+			.collect();
+		fail!(Usage, "--ovar {} names no column of the header of {}, which names the \
+		              other outputs; give the name of its column or --ovar {}@<col>.{}",
+		      name, files[0], name, did_you_mean(name, &cells));
 	}
+	Ok((named.iter().map(|n| n.to_string()).collect(), false))
 }
 
-impl std::fmt::Display for Equation {
-	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		try!(write!(f, "{} = ", self.varname));
-		for t in self.terms.iter() {
-			try!(write!(f, "{} + ", t));
+// A table, the names of its outputs and the frequencies of its lines.
+type Tables = (Truth, Vec<String>, HashMap<usize, f64>);
+
+// Reads the --table file(s) into a single table.  Returns the table, whose
+// rows know the lines they were read from, the names of its outputs, and with
+// --freq-col the frequencies of those lines.
+fn load_tables(args: &docopt::ArgvMap, argv: &[String], input_bits: usize,
+               inenc: InputEncoding, outenc: OutputEncoding) -> Result<Tables, Failure> {
+	let files = args.get_vec("<truth>");
+	let groups = if files.len() == 1 {
+		vec![ovar_names(args).iter().map(|s| s.to_string()).collect()]
+	} else {
+		merge::ovar_groups(argv)
+	};
+	if groups.len() != files.len() || groups.iter().any(|g| g.is_empty()) {
+		fail!(Usage, "Every --table needs at least one --ovar following it.");
+	}
+	let mut names: Vec<Vec<String>> = vec![];
+	for group in groups.iter() {
+		names.push(output_names(group.iter().map(|s| s.as_str()).collect(),
+		                        args.get_str("--ovar-width")).map_err(Failure::Usage)?);
+	}
+	let outvars: Vec<String> = names.iter().flat_map(|n| n.iter().cloned()).collect();
+	let invars: Vec<String> = args.get_vec("--ivar").iter().map(|s| s.to_string())
+		.collect();
+	check_names(&invars, &outvars).map_err(Failure::Usage)?;
+	let mut sources: Vec<merge::Source> = vec![];
+	// only a single table has them.
+	let mut frequencies = HashMap::new();
+	for (file, names) in files.iter().zip(names.iter()) {
+		let data = table_data(file)?;
+		if files.len() == 1 && outvars.len() > 1 &&
+		   output_columns(args, &layout(args, &data)?, &data)?.1 {
+			warn(args, &[format!("The header of {} names none of the outputs, so the \
+			                      --ovar names are bound to its output columns in \
+			                      order; give --ovar name@col to bind them by column.",
+			                     file)])?;
 		}
-		write!(f, ";")
+		let outcome = parse_table(args, file, &data, input_bits, names.len(), inenc,
+		                          outenc)?;
+		sources.push(merge::Source{file, truth: outcome.truth});
+		frequencies = outcome.frequencies;
+	}
+	if sources.len() == 1 {
+		return Ok((sources.pop().unwrap().truth, outvars, frequencies));
+	}
+	match merge::merge(&sources) {
+		Ok(t) => Ok((t, outvars, HashMap::new())),
+		Err(msg) => fail!(Invalid, "Cannot merge tables: {}", msg),
 	}
 }
 
-fn equations(truth: &Truth, outvars: Vec<&str>, invars: Vec<String>) ->
-	Vec<Equation> {
-	assert!(!truth.table.is_empty());
-	for i in truth.table.iter() { // verify lengths are okay.
-		assert!(i.input.len() == truth.table[0].input.len());
-		assert!(i.output.len() == truth.table[0].output.len());
+// Writes the warnings to stderr, or with --deny-warnings fails after doing
+// so.
+fn warn(args: &docopt::ArgvMap, warnings: &[String]) -> Result<(), Failure> {
+	for w in warnings.iter() {
+		eprintln!("WARNING: {}", w);
 	}
-	assert!(truth.table[0].output.len() == outvars.len());
-	let mut rv: Vec<Equation> = vec![];
-	for b in 0..truth.table[0].output.len() {
-		rv.push(Equation::new(truth, b, outvars[b], &invars));
+	if args.get_bool("--deny-warnings") && !warnings.is_empty() {
+		fail!(Usage, "{} warning(s), denied by --deny-warnings.", warnings.len());
 	}
-	rv
+	Ok(())
 }
 
-struct Truth {
-	table: Vec<Entry>,
+// The contents of a table file, or of stdin for '-', decompressed.  Stdin is
+// read once, and every reader of the table given the same data.
+fn table_data(file: &str) -> Result<Vec<u8>, Failure> {
+	static STDIN: OnceLock<Result<Vec<u8>, String>> = OnceLock::new();
+	if file == "-" {
+		return STDIN.get_or_init(|| compressed::read_all("stdin", std::io::stdin()))
+			.clone().map_err(Failure::Usage);
+	}
+	let fp = match File::open(Path::new(file)) {
+		Ok(f) => f,
+		Err(e) => fail!(Usage, "Cannot open {}: {}", file, e),
+	};
+	compressed::read_all(file, fp).map_err(Failure::Usage)
+}
+
+// Reads one table file.  A file that can't be read at all is a usage error,
+// one whose rows don't make a valid table a validation error.
+fn read_table(args: &docopt::ArgvMap, file: &str, nin: usize, nout: usize,
+              inenc: InputEncoding, outenc: OutputEncoding) -> Result<Truth, Failure> {
+	read_table_lines(args, file, nin, nout, inenc, outenc).map(|o| o.truth)
+}
+// As read_table, with the warnings and the lines of the don't-cares.  Inputs
+// given more than once are resolved by --duplicates, which commands without
+// it take as 'error'.
+fn read_table_lines(args: &docopt::ArgvMap, file: &str, nin: usize, nout: usize,
+                    inenc: InputEncoding, outenc: OutputEncoding) ->
+	Result<ParseOutcome, Failure> {
+	parse_table(args, file, &table_data(file)?, nin, nout, inenc, outenc)
 }
 
-impl Truth {
-	fn default() -> Self { Truth{table: vec![]} }
+// As read_table_lines, with the file's contents already read.
+fn parse_table(args: &docopt::ArgvMap, file: &str, data: &[u8], nin: usize, nout: usize,
+               inenc: InputEncoding, outenc: OutputEncoding) ->
+	Result<ParseOutcome, Failure> {
+	let duplicates = match args.get_str("--duplicates") {
+		"" => complete::Duplicates::Error,
+		s => complete::Duplicates::parse(s).map_err(Failure::Usage)?,
+	};
+	let layout = layout(args, data)?;
+	// with a column per input and per output, and no other in use, whether the
+	// counts fit the rows.
+	let mismatch = match (inenc, outenc) {
+		(InputEncoding::Binary, OutputEncoding::Binary) if args.get_vec("--ocol")
+			.is_empty() && args.get_str("--freq-col").is_empty() =>
+			columns(data, HEADER_LINES, &layout).and_then(|c| c.mismatch(nin, nout)),
+		_ => None,
+	};
+	let hint = mismatch.as_ref().map_or(String::new(), |m| format!("\n{}", m));
+	let mut outcome = match parse_layout(data, HEADER_LINES, nin, nout, inenc,
+	                                     outenc, cells(args)?, empty(args)?, &layout) {
+		Ok(outcome) => outcome,
+		Err(ref e) if e.invalid => fail!(Invalid, "Error parsing {}: {}{}", file, e, hint),
+		Err(e) => fail!(Usage, "Error parsing {}: {}{}", file, e, hint),
+	};
+	let warnings: Vec<String> = mismatch.into_iter().chain(outcome.warnings.iter()
+		.map(|w| w.to_string())).map(|w| format!("{}: {}", file, w)).collect();
+	warn(args, &warnings)?;
+	if let Err(msg) = complete::resolve_duplicates(&mut outcome, duplicates) {
+		fail!(Invalid, "Error in {}: {}", file, msg);
+	}
+	outcome.truth.name_sources(file);
+	Ok(outcome)
+}
 
-	#[allow(dead_code)]
-	fn new(inp: Vec<Vec<bool>>, outp: Vec<Vec<bool>>) -> Self {
-		assert_eq!(inp.len(), outp.len());
-		let mut entlist: Vec<Entry> = vec![];
-		for i in 0..inp.len() {
-			entlist.push(Entry::new(inp[i].clone(), outp[i].clone()));
+// A table read and validated, with what the options say about its inputs.
+struct Loaded {
+	tbl: Truth,
+	invars: Vec<String>,
+	outvars: Vec<String>,
+	inenc: InputEncoding,
+	outenc: OutputEncoding,
+	groups: Vec<Vec<usize>>,
+	// inputs the constraints and one-hot groups rule out, and the possible
+	// inputs the table doesn't list.
+	impossible: BTreeSet<Vec<bool>>,
+	missing: Vec<Vec<bool>>,
+	policy: emit::DefaultOutput,
+	// with --freq-col, how often the input of each line of the table occurs.
+	frequencies: HashMap<usize, f64>,
+}
+
+// Reads the two --table files of compose, each with the --ivar and --ovar
+// names that follow it, and composes them as --wire says.
+fn compose_tables(args: &docopt::ArgvMap, argv: &[String], inenc: InputEncoding,
+                  outenc: OutputEncoding, note: &dyn Fn(String)) ->
+	Result<compose::Composite, Failure> {
+	let files = args.get_vec("<truth>");
+	let invars = merge::option_groups(argv, "--ivar");
+	let ovars = merge::ovar_groups(argv);
+	if files.len() != 2 || invars.len() != 2 || ovars.len() != 2 ||
+		invars.iter().chain(ovars.iter()).any(|g| g.is_empty()) {
+		fail!(Usage, "compose takes two --table files, each followed by its --ivar and \
+		              --ovar names.");
+	}
+	let mut truths: Vec<Truth> = vec![];
+	let mut outvars: Vec<Vec<String>> = vec![];
+	for k in 0..2 {
+		let names = output_names(ovars[k].iter().map(|s| s.as_str()).collect(),
+		                         args.get_str("--ovar-width")).map_err(Failure::Usage)?;
+		truths.push(read_table(args, files[k], invars[k].len(), names.len(), inenc,
+		                       outenc)?);
+		outvars.push(names);
+	}
+	let a = compose::Stage{truth: &truths[0], invars: &invars[0], outvars: &outvars[0]};
+	let b = compose::Stage{truth: &truths[1], invars: &invars[1], outvars: &outvars[1]};
+	let wires = compose::parse_wires(args.get_vec("--wire"), &a, &b)
+		.map_err(Failure::Usage)?;
+	let c = compose::compose(&a, &b, &wires, args.get_bool("--carry"))
+		.map_err(Failure::Usage)?;
+	note(format!("Composed {} and {}: {} inputs -> {} outputs.", files[0], files[1],
+	             c.invars.len(), c.outvars.len()));
+	Ok(c)
+}
+
+// Reads the table, or builds it with from-expr or compose, and applies the
+// constraints and one-hot groups and the --default-output policy to it.
+fn load(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<Loaded, Failure> {
+	let (inenc, outenc) = encodings(args)?;
+	let limit = max_enumerate(args)?;
+	let mut invars: Vec<String> = args.get_vec("--ivar").iter().map(
+		|elt| elt.to_string()
+	).collect();
+	if !args.get_bool("compose") &&
+		(!args.get_vec("--wire").is_empty() || args.get_bool("--carry")) {
+		fail!(Usage, "--wire and --carry only apply to compose.");
+	}
+	let (mut tbl, outvars, frequencies) = if args.get_bool("from-expr") {
+		enumerate::check(invars.len(), limit, "").map_err(too_many)?;
+		let (tbl, outvars) = from_expressions(args.get_vec("--expr"), &invars, limit)
+			.map_err(Failure::Usage)?;
+		check_names(&invars, &outvars).map_err(Failure::Usage)?;
+		(tbl, outvars, HashMap::new())
+	} else if args.get_bool("compose") {
+		let c = compose_tables(args, argv, inenc, outenc, note)?;
+		invars = c.invars;
+		(c.truth, c.outvars, HashMap::new())
+	} else {
+		load_tables(args, argv, invars.len(), inenc, outenc)?
+	};
+	let input_bits = invars.len();
+	let output_bits = outvars.len();
+	let groups = onehot::parse_groups(args.get_vec("--onehot"), &invars)
+		.map_err(Failure::Usage)?;
+	for group in groups.iter() {
+		onehot::check(&tbl, group, &invars).map_err(Failure::Invalid)?;
+	}
+	let constraints = parse_constraints(args.get_vec("--constraint"), &invars)
+		.map_err(Failure::Usage)?;
+	let (reclassified, impossible) = if constraints.is_empty() && groups.is_empty() {
+		(0, BTreeSet::new())
+	} else {
+		let possible = |inp: &[bool]| {
+			groups.iter().all(|g| onehot::is_onehot(inp, g)) &&
+				constraints.iter().all(|c| c.evaluate(inp))
+		};
+		enumerate::check(input_bits, limit, "; --constraint and --onehot go through them \
+		                                     all").map_err(too_many)?;
+		apply_constraints(&mut tbl, &possible, input_bits, limit).map_err(Failure::Usage)?
+	};
+	if !constraints.is_empty() {
+		note(format!("Constraints make {} row(s) of the table impossible; they are \
+		              don't-cares now.", reclassified));
+		if tbl.rows().is_empty() {
+			fail!(Invalid, "No row of the table satisfies the constraints.");
 		}
-		Truth{table: entlist}
 	}
+	// an empty file, or one of don't-cares, says nothing of the outputs.
+	check_outputs(&tbl, output_bits)?;
+	let missing = missing_inputs(&tbl, input_bits, limit).map_err(|e| {
+		too_many(format!("{} The table doesn't list them all, and finding those it \
+		                  lacks means going through every one.", e))
+	})?;
+	let missing: Vec<Vec<bool>> = missing.into_iter()
+		.filter(|inp| !impossible.contains(inp))
+		.collect();
+	let missing_policy = match args.get_str("--missing") {
+		"" => complete::Missing::Error,
+		s => complete::Missing::parse(s).map_err(Failure::Usage)?,
+	};
+	let default_output = args.get_str("--default-output");
+	let listed = complete::examples(&missing, missing.len() as u64);
+	let policy = match (missing_policy, default_output) {
+		(complete::Missing::DontCare, "") => emit::DefaultOutput::DontCare,
+		(complete::Missing::Zero, "") => emit::DefaultOutput::Zero,
+		(complete::Missing::DontCare, _) | (complete::Missing::Zero, _) =>
+			fail!(Usage, "--missing {} can't be combined with --default-output.",
+			      args.get_str("--missing")),
+		(complete::Missing::Error, "") if !missing.is_empty() => {
+			fail!(Invalid, "Table is too short: no row for {} of the {} input patterns \
+			                ({}); give --default-output or --missing to allow missing \
+			                inputs.", missing.len(), 1u64 << input_bits, listed);
+		},
+		(complete::Missing::Warn, _) if !missing.is_empty() => {
+			warn(args, &[format!("No row for {} of the {} input patterns: {}.",
+			                     missing.len(), 1u64 << input_bits, listed)])?;
+			match default_output {
+				"" => emit::DefaultOutput::Zero,
+				s => emit::DefaultOutput::parse(s, output_bits).map_err(Failure::Usage)?,
+			}
+		},
+		(_, "") => emit::DefaultOutput::Zero,
+		(_, s) => emit::DefaultOutput::parse(s, output_bits).map_err(Failure::Usage)?,
+	};
+	note(format!("Parsed truth table with {} input bits -> {} output bits",
+	             input_bits, output_bits));
+	note(format!("({} input lines.)", tbl.len()));
+	let ld = Loaded{tbl, invars, outvars, inenc, outenc, groups, impossible, missing,
+	                policy, frequencies};
+	if args.get_bool("--dump-table") {
+		eprint!("{}", every_input(&ld).display(&ld.invars, &ld.outvars));
+	}
+	Ok(ld)
+}
 
-	#[allow(dead_code)]
-	fn solution(&self, inp: Vec<bool>) -> Vec<bool> {
-		// find the entry for which the input bit pattern matches.
-		let foo = self.table.iter().find(|tbl| { tbl.input == inp });
-		match foo {
-			None => panic!("cannot find bit pattern {:?}", inp),
-			Some(x) => x.output.clone(), // return the output part of the Entry.
+// Checks every --assert-eq, reporting all that fail together.  Returns the
+// equations, which hold.
+fn check_assertions(args: &docopt::ArgvMap, tbl: &Truth, outvars: &[&str],
+                    invars: &[String], observe: &[observe::Observe],
+                    note: &dyn Fn(String)) -> Result<Vec<Equation>, Failure> {
+	let mut seeds: Vec<Equation> = vec![];
+	let mut failures: Vec<String> = vec![];
+	for s in args.get_vec("--assert-eq") {
+		let eqn = parse_assertion(s, outvars, invars).map_err(Failure::Usage)?;
+		let checked = observe::observed_table(tbl, eqn.index(), observe);
+		match verify_assertion(eqn, s, &checked, invars) {
+			Ok(eqn) => {
+				note(format!("Assertion holds: {}", s));
+				seeds.push(eqn);
+			},
+			Err(msg) => failures.push(msg),
 		}
 	}
+	if !failures.is_empty() {
+		return Err(Failure::Verification(failures.join("\n")));
+	}
+	Ok(seeds)
+}
 
-	fn len(&self) -> usize { return self.table.len() }
+// The equations of the selected outputs, before minimizing: a seed from
+// --assert-eq where there is one, with the impossible inputs as don't-cares
+// and the missing ones treated as the policy says.
+fn initial_equations(ld: &Loaded, tbl: &Truth, invars: &Vec<String>,
+                     missing: &[Vec<bool>], impossible: &BTreeSet<Vec<bool>>,
+                     selected: &[usize], seeds: &[Equation]) -> Vec<Equation> {
+	let term = |inp: &Vec<bool>| {
+		let mut term = Term::compute(inp);
+		term.names = invars.clone();
+		term
+	};
+	selected.iter().map(|&b| {
+		let mut eqn = match seeds.iter().find(|e| e.index() == b) {
+			Some(seed) => seed.clone(),
+			None => Equation::new(tbl, b, &ld.outvars[b], invars),
+		};
+		eqn.add_dont_cares(impossible.iter().map(&term));
+		match ld.policy {
+			emit::DefaultOutput::Zero => {},
+			emit::DefaultOutput::Bits(ref bits) => if bits[b] {
+				eqn.add_terms(missing.iter().map(&term));
+			},
+			// panicking inputs never get as far as the equations.
+			emit::DefaultOutput::DontCare | emit::DefaultOutput::Panic =>
+				eqn.add_dont_cares(missing.iter().map(&term)),
+		}
+		eqn
+	}).collect()
+}
 
-	#[allow(dead_code)]
-	fn print(&self, wrt: &mut std::io::Write) {
-		for elem in self.table.iter() {
-			for i in elem.input.iter() {
-				write!(wrt, "{}", *i).unwrap();
-			}
-			write!(wrt, " -> ").unwrap();
-			for o in elem.output.iter() {
-				if *o {
-					write!(wrt, "{}", 1).unwrap();
-				} else {
-					write!(wrt, "{}", 0).unwrap();
-				}
+// Shows minimization progress on stderr, rewriting a single line in place.
+struct StatusLine {
+	phase: String,
+	output: String,
+	last: Option<Instant>,
+}
+impl progress::Progress for StatusLine {
+	fn phase(&mut self, name: &str) { self.phase = name.to_string(); }
+	fn equation(&mut self, _idx: usize, name: &str) {
+		self.output = name.to_string();
+		self.last = None;
+	}
+	fn update(&mut self, terms: usize, merges: usize) {
+		// redrawing on every merge would dominate the runtime.
+		if let Some(t) = self.last {
+			if t.elapsed() < Duration::from_millis(100) {
+				return;
 			}
-			write!(wrt, "\n").unwrap();
+		}
+		self.last = Some(Instant::now());
+		eprint!("\r\x1b[K{}: {} ({} terms, {} merges)", self.phase, self.output,
+		        terms, merges);
+	}
+	fn done(&mut self) {
+		if self.last.is_some() {
+			eprint!("\r\x1b[K");
 		}
 	}
 }
 
 fn main() {
-	let args = Docopt::new(USAGE)
-		.unwrap_or_else(|e| e.exit())
-		.parse()
-		.unwrap_or_else(|e| e.exit());
-	println!("map: '{:?}'", args);
-	let input_bits = args.get_count("--ivar") as usize;
-	let output_bits = args.get_count("--ovar") as usize;
-	let header_lines = 2;
-	let csvtable = Path::new(args.get_str("<truth>"));
-	let fp = match File::open(&csvtable) {
-		Err(e) => panic!("error {} opening {}", e, args.get_str("<truth>")),
-		Ok(f) => f,
+	let mut argv: Vec<String> = std::env::args().collect();
+	let first = argv.get(1).cloned().unwrap_or_default();
+	// the options of minimize, and from-expr, come without a command in
+	// scripts older than the commands.
+	if first == "from-expr" || first == "compose" ||
+		(first.starts_with("--") && first != "--help") {
+		argv.insert(1, "minimize".to_string());
+	}
+	let cmd = argv.get(1).cloned().unwrap_or_default();
+	let usage = match COMMANDS.iter().find(|&&(name, _)| name == cmd) {
+		Some(&(_, usage)) => usage,
+		None if cmd == "-h" || cmd == "--help" => {
+			// as docopt prints help, which may go to a pipe closed early.
+			let _ = writeln!(std::io::stdout(), "{}", USAGE.trim());
+			return;
+		},
+		None if cmd.is_empty() => stop(Failure::Usage(USAGE.trim().to_string())),
+		None => {
+			let names: Vec<&str> = COMMANDS.iter().map(|&(name, _)| name).collect();
+			stop(Failure::Usage(format!("Unknown command '{}'.{}  See 'minterm --help'.",
+			                            cmd, did_you_mean(&cmd, &names))));
+		},
 	};
-	let tbl = parse(fp, header_lines, input_bits, output_bits);
-	for ent in tbl.table.iter() {
-		if ent.input.len() != input_bits {
-			println!("Incorrect number of bits ({}, should be {}) for elem {:?}.",
-			         ent.input.len(), input_bits, ent.input);
-			std::process::exit(1);
+	// the commands reading a table with the --ivar and --ovar names.
+	let named = ["minimize", "check", "assert", "vectors", "estimate", "implications",
+	             "profile", "repl"];
+	if named.contains(&cmd.as_str()) {
+		argv = match schema_args(argv) {
+			Ok(a) => a,
+			Err(f) => stop(f),
+		};
+	}
+	let result = parse_args(usage, &argv).and_then(|args| {
+		// everything but the output proper goes to stderr, and --quiet drops
+		// what isn't an error.
+		let quiet = args.get_bool("--quiet");
+		let note = move |msg: String| if !quiet { eprintln!("{}", msg) };
+		match cmd.as_str() {
+			"check" => check(&args, &argv, &note),
+			"assert" => assert(&args, &argv, &note),
+			"diff" => diff(&args),
+			"vectors" => vectors(&args, &argv, &note),
+			"estimate" => estimate(&args, &argv, &note),
+			"implications" => implications(&args, &argv, &note),
+			"profile" => profile(&args, &argv, &note),
+			"template" => template::run(&args),
+			"convert" => convert::run(&args, &note),
+			"repl" => repl::run(&args, &argv, &note),
+			"batch" => batch::run(&args),
+			_ => minimize(&args, &argv, &note),
 		}
+	});
+	if let Err(f) = result {
+		stop(f);
 	}
-	let two: i32 = 2;
-	if tbl.len() < two.pow(input_bits as u32) as usize {
-		println!("Table is too short ({} elems) for {} bits.", tbl.len(),
-		         input_bits);
-		std::process::exit(1);
+}
+
+// Fills in, from the schema line a single --table file may start with, the
+// --ivar, --ovar, --missing and --duplicates options the command line leaves
+// out.  One given both ways is the command line's, with a warning when the two
+// differ; --default-output likewise overrides a 'sparse' of dc or zero, which
+// it can't be combined with.
+fn schema_args(mut argv: Vec<String>) -> Result<Vec<String>, Failure> {
+	let files: Vec<String> = argv.windows(2).filter(|w| w[0] == "--table")
+		.map(|w| w[1].clone()).collect();
+	if files.len() != 1 {
+		return Ok(argv);
+	}
+	let file = &files[0];
+	let mut first = String::new();
+	// a file that can't be read is reported when the table is.
+	if let Ok(data) = table_data(file) {
+		let _ = BufReader::new(&data[..]).read_line(&mut first);
+	}
+	let schema = match schema::parse(first.trim_end()) {
+		Ok(Some(s)) => s,
+		Ok(None) => return Ok(argv),
+		Err(e) => fail!(Usage, "Invalid schema line in {}: {}", file, e),
+	};
+	let given = |opt: &str| merge::option_groups(&argv, opt).concat();
+	let mut added: Vec<String> = vec![];
+	let mut warnings: Vec<String> = vec![];
+	for &(opt, key, names) in [("--ivar", "inputs", &schema.inputs),
+	                           ("--ovar", "outputs", &schema.outputs)].iter() {
+		let flags = given(opt);
+		if flags.is_empty() {
+			added.extend(names.iter().map(|n| format!("{}={}", opt, n)));
+		} else if !names.is_empty() && flags != *names {
+			warnings.push(format!("{} overrides {}={} of the schema line in {}.", opt, key,
+			                      names.join(","), file));
+		}
 	}
-	println!("Parsed truth table with {} input bits -> {} output bits",
-	         input_bits, output_bits);
-	println!("({} input lines.)", tbl.len());
+	let overridden = |v: &str| v == "dc" || v == "zero";
+	for &(opt, key, value) in [("--missing", "sparse", schema.sparse.as_ref()),
+	                           ("--duplicates", "duplicates", schema.duplicates.as_ref())]
+		.iter() {
+		let value = match value {
+			Some(v) => v,
+			None => continue,
+		};
+		let by = match given(opt).last() {
+			Some(v) if v != value => opt,
+			Some(_) => continue,
+			None if opt == "--missing" && overridden(value) &&
+			        !given("--default-output").is_empty() => "--default-output",
+			None => {
+				added.push(format!("{}={}", opt, value));
+				continue;
+			},
+		};
+		warnings.push(format!("{} overrides {}={} of the schema line in {}.", by, key,
+		                      value, file));
+	}
+	// a --map of the same column and value replaces the schema's.
+	let maps: Vec<CellMap> = given("--map").iter().filter_map(|m| CellMap::parse(m).ok())
+		.collect();
+	for m in schema.maps.iter() {
+		let sm = CellMap::parse(m).expect("a checked map");
+		match maps.iter().find(|o| o.column == sm.column && o.value == sm.value) {
+			None => added.push(format!("--map={}", m)),
+			Some(o) if o.to != sm.to =>
+				warnings.push(format!("--map overrides map={} of the schema line in {}.",
+				                      m, file)),
+			Some(_) => {},
+		}
+	}
+	for w in warnings.iter() {
+		eprintln!("WARNING: {}", w);
+	}
+	if argv.iter().any(|a| a == "--deny-warnings") && !warnings.is_empty() {
+		fail!(Usage, "{} warning(s), denied by --deny-warnings.", warnings.len());
+	}
+	argv.extend(added);
+	Ok(argv)
+}
 
-	let as_strings = args.get_vec("--ivar").iter().map(
-		|elt| elt.to_string()
-	).collect();
-	let mut eqns = equations(&tbl, args.get_vec("--ovar"), as_strings);
-	assert_eq!(eqns.len(), tbl.table[0].output.len());
-	for e in 0..eqns.len() {
-		eqns[e].simplify();
-		println!("{}", eqns[e]);
-	}
-}
-
-// really this returns a Vec<[usize; nbits]>, but Rust's variable-length arrays
-// are vectors.
-#[allow(dead_code)]
-fn gray_code(nbits: usize) -> Vec<Vec<bool>> {
-	let gray1: Vec<Vec<bool>> = vec![vec![false], vec![true]];
-	let mut cur = gray1;
-	for _ in 1..nbits {
-		cur = gray_code_r(cur);
-	}
-	cur
-}
-
-// takes an 'n' bit gray code and computes the gray code for n+1 bits
-fn gray_code_r(gray: Vec<Vec<bool>>) -> Vec<Vec<bool>> {
-	// prepend 0's (false) to the original list
-	let list0: Vec<Vec<bool>> =	gray.iter().map(|bitstring| {
-		let mut copy = bitstring.clone();
-		copy.insert(0, false);
-		copy
-	}).collect();
-	// prepend 1's (true) to the reversed original list
-	let mut list1: Vec<Vec<bool>> =	gray.iter().rev().map(|bitstring| {
-		let mut copy = bitstring.clone();
-		copy.insert(0, true);
-		copy
-	}).collect();
-	// return the concatenation of the old and new lists.
-	let mut concat = list0;
-	concat.append(&mut list1);
-	concat
-}
-
-// parses a truth table in a CSV file with
-//   NHEADER header (ignored) rows
-//   NIN inputs as the leftmost NIN columns
-//   NOUT outputs as the rightmost NOUT columns
-fn parse<T: std::io::Read>(data: T, nheader: usize, nin: usize, nout: usize) ->
-	Truth {
-	let mut rdr = csv::ReaderBuilder::new()
-		.has_headers(false)
-		.from_reader(data);
-	let mut iter = rdr.records();
-	let mut line: usize = 0;
-	for _ in 0..nheader { // skip header lines.
-		iter.next();
-		line = line + 1;
-	}
-	let mut tbl = Truth::default();
-	let mut ent = Entry::default();
-
-	for result in iter {
-		ent.clear();
-
-		let record = result.expect("a CSV record");
-		line = line + 1;
-		for i in 0..nin {
-			let on: bool = match record[i].parse::<i32>() {
-				Ok(b) => b != 0,
-				Err(e) => {
-					println!("WARNING: ignoring input '{}' ({}) on line {}:{}",
-					         record[i].to_string(), e, line, i);
-					false
-				},
+fn check(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let ld = load(args, argv, note)?;
+	let outvars: Vec<&str> = ld.outvars.iter().map(|s| s.as_str()).collect();
+	if args.get_bool("--report") {
+		let max = match args.get_str("--max-counterexamples").parse::<usize>() {
+			Ok(n) => n,
+			Err(_) => fail!(Usage, "Invalid --max-counterexamples '{}'; expected an \
+			                        integer.", args.get_str("--max-counterexamples")),
+		};
+		let mut reports: Vec<counterexample::Report> = vec![];
+		for s in args.get_vec("--assert-eq") {
+			let eqn = parse_assertion(s, &outvars, &ld.invars).map_err(Failure::Usage)?;
+			reports.push(counterexample::report(&eqn, s, &ld.tbl, &ld.invars, max));
+		}
+		println!("{}", counterexample::json(&reports, &ld.invars));
+		let failed = reports.iter().filter(|r| r.mismatches > 0).count();
+		if failed > 0 {
+			fail!(Verification, "{} of {} assertion(s) fail.", failed, reports.len());
+		}
+		return Ok(());
+	}
+	let asserted = check_assertions(args, &ld.tbl, &outvars, &ld.invars, &[], note)?;
+	println!("{} rows, {} inputs -> {} outputs; {} input(s) missing, {} impossible, \
+	          {} assertion(s) hold.", ld.tbl.len(), ld.invars.len(), outvars.len(),
+	         ld.missing.len(), ld.impossible.len(), asserted.len());
+	Ok(())
+}
+
+fn assert(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let ld = load(args, argv, note)?;
+	let outvars: Vec<&str> = ld.outvars.iter().map(|s| s.as_str()).collect();
+	check_assertions(args, &ld.tbl, &outvars, &ld.invars, &[], note)?;
+	let mut rows = invariant::rows(&ld.tbl);
+	let nout = outvars.len();
+	rows.extend(ld.missing.iter().map(|inp| (inp.clone(), match ld.policy {
+		emit::DefaultOutput::Zero => vec![Some(false); nout],
+		emit::DefaultOutput::Bits(ref b) => b.iter().cloned().map(Some).collect(),
+		emit::DefaultOutput::DontCare | emit::DefaultOutput::Panic => vec![None; nout],
+	}, vec![])));
+	let mut failures: Vec<String> = vec![];
+	for s in args.get_vec("--expr") {
+		let e = match invariant::parse(s, &ld.invars, &ld.outvars) {
+			Ok(e) => e,
+			Err(e) => fail!(Usage, "Invalid invariant '{}' {}", s, e),
+		};
+		let bad = invariant::violations(&e, &rows, nout);
+		if bad.is_empty() {
+			note(format!("Invariant holds: {}", s));
+		} else {
+			failures.push(invariant::report(s, &bad, &ld.invars, &ld.outvars));
+		}
+	}
+	if !failures.is_empty() {
+		return Err(Failure::Verification(failures.join("\n")));
+	}
+	println!("{} invariant(s) hold on {} row(s).", args.get_vec("--expr").len(),
+	         rows.len());
+	Ok(())
+}
+
+fn diff(args: &docopt::ArgvMap) -> Result<(), Failure> {
+	let (inenc, outenc) = encodings(args)?;
+	let invars: Vec<String> = args.get_vec("--ivar").iter().map(|s| s.to_string())
+		.collect();
+	let names = output_names(ovar_names(args), args.get_str("--ovar-width"))
+		.map_err(Failure::Usage)?;
+	check_names(&invars, &names).map_err(Failure::Usage)?;
+	let read = |file: &str| read_table(args, file, invars.len(), names.len(), inenc,
+	                                   outenc);
+	let notation = notation(args, &invars)?;
+	let (report, same) = diff::diff(&read(args.get_str("<old>"))?,
+	                                &read(args.get_str("<new>"))?, &names, &invars,
+	                                &notation);
+	print!("{}", report);
+	if same { Ok(()) } else { Err(Failure::Changed) }
+}
+
+fn vectors(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let format = choice(args, "--format", &["csv", "rust", "c"])?;
+	let ld = load(args, argv, note)?;
+	let nin = ld.invars.len();
+	let outvars: Vec<&str> = ld.outvars.iter().map(|s| s.as_str()).collect();
+	let seeds = check_assertions(args, &ld.tbl, &outvars, &ld.invars, &[], note)?;
+	let selected: Vec<usize> = (0..outvars.len()).collect();
+	let mut eqns = initial_equations(&ld, &ld.tbl, &ld.invars, &ld.missing,
+	                                 &ld.impossible, &selected, &seeds);
+	minterm::minimize(&mut eqns, nin, false, &vec![1; nin],
+	                  &mut exact::Budget::unlimited(), &mut progress::Silent);
+	// the rows --default-output gives are checked like the table's own.
+	let tbl = completed(&ld);
+	let vs = vectors::select(&eqns, &tbl, nin).map_err(Failure::Verification)?;
+	note(format!("{} vector(s) cover the {} term(s) of {} equation(s).", vs.len(),
+	             eqns.iter().map(|e| e.terms().len()).sum::<usize>(), eqns.len()));
+	print!("{}", match format {
+		"rust" => vectors::rust(&vs, &ld.invars, &ld.outvars),
+		"c" => vectors::c(&vs, &ld.invars, &ld.outvars),
+		_ => vectors::csv(&vs, &ld.invars, &ld.outvars),
+	});
+	Ok(())
+}
+
+fn estimate(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let ld = load(args, argv, note)?;
+	let profile = estimate::profile(&every_input(&ld), ld.invars.len(), &ld.outvars);
+	let rec = estimate::recommend(&profile);
+	if args.get_bool("--json") {
+		println!("{}", estimate::json(&profile, &rec));
+	} else {
+		print!("{}", estimate::report(&profile, &rec));
+	}
+	Ok(())
+}
+
+fn implications(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let ld = load(args, argv, note)?;
+	let notation = notation(args, &ld.outvars)?;
+	// the rows --default-output gives count like the table's own.
+	let mined = implication::mine(&completed(&ld));
+	for i in mined.iter() {
+		println!("{}", i.describe(&ld.outvars, &notation));
+	}
+	note(format!("{} implication(s) between {} output(s).", mined.len(),
+	             ld.outvars.len()));
+	Ok(())
+}
+
+fn profile(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let file = args.get_str("--trace");
+	let ld = load(args, argv, note)?;
+	let nin = ld.invars.len();
+	let notation = notation(args, &ld.invars)?;
+	let text = match String::from_utf8(table_data(file)?) {
+		Ok(text) => text,
+		Err(_) => fail!(Usage, "{} is not a text file.", file),
+	};
+	let trace = trace::Trace::parse(&text, &ld.invars)
+		.map_err(|e| Failure::Usage(format!("{}, {}", file, e)))?;
+	if trace.total() == 0 {
+		fail!(Usage, "{} has no input patterns.", file);
+	}
+	// the equations as minimize has them, missing inputs and all.
+	let tbl = every_input(&ld);
+	let outvars: Vec<&str> = ld.outvars.iter().map(|s| s.as_str()).collect();
+	let minimized = |tbl: &Truth| {
+		let mut eqns = equations(tbl, outvars.clone(), ld.invars.clone());
+		minterm::minimize(&mut eqns, nin, false, &vec![1; nin],
+		                  &mut exact::Budget::unlimited(), &mut progress::Silent);
+		eqns.iter_mut().for_each(Equation::canonicalize);
+		eqns
+	};
+	let eqns = minimized(&tbl);
+	let acts: Vec<trace::Activations> = eqns.iter().map(|e| trace::activations(e, &trace))
+		.collect();
+	print!("{}", trace::report(&eqns, &acts, trace.total(), &notation));
+	let seen = trace.counts.keys().filter(|inp| trace.has(inp)).count();
+	let listed = tbl.rows().iter().filter(|e| trace.has(&e.input)).count();
+	note(format!("{} of {} term(s) never activated; the trace has {} input pattern(s), {} \
+	              of them rows of the table.",
+	             acts.iter().map(|a| a.never().len()).sum::<usize>(),
+	             eqns.iter().map(|e| e.terms().len()).sum::<usize>(), seen, listed));
+	if !args.get_bool("--prune-unactivated") {
+		return Ok(());
+	}
+	let pruned = trace::untraced(&tbl, &trace);
+	if pruned.rows().is_empty() {
+		fail!(Invalid, "No input of {} is a row of the table; there is nothing to \
+		                minimize for.", file);
+	}
+	println!("\n# Trace-dependent: minimized with the {} row(s) whose inputs the trace \
+	          never has\n# as don't-cares; these equations agree with the table only on \
+	          the traced inputs.", tbl.rows().len() - pruned.rows().len());
+	for eqn in minimized(&pruned).iter() {
+		println!("{}", notation.equation(eqn));
+	}
+	Ok(())
+}
+
+// The table with a complement after each of its outputs, as --emit-complements
+// minimizes it.
+fn with_complements(mut ld: Loaded) -> Result<Loaded, Failure> {
+	let outvars = complement::names(&ld.outvars);
+	check_names(&ld.invars, &outvars).map_err(Failure::Usage)?;
+	ld.policy = complement::extend_policy(&ld.policy, ld.outvars.len());
+	ld.tbl = complement::extend_table(&ld.tbl);
+	ld.outvars = outvars;
+	Ok(ld)
+}
+
+// The table as minimizing sees it: completed, and the inputs the constraints
+// and --onehot rule out don't-cares.
+fn every_input(ld: &Loaded) -> Truth {
+	let mut tbl = completed(ld);
+	tbl.add_dont_cares(ld.impossible.iter().cloned());
+	tbl
+}
+
+// The table with the missing inputs as the policy has them: rows of
+// --default-output's bits or 0s, or don't-cares.
+fn completed(ld: &Loaded) -> Truth {
+	match ld.policy {
+		emit::DefaultOutput::Zero | emit::DefaultOutput::Bits(_) =>
+			filled(&ld.tbl, &ld.missing, &ld.policy, ld.outvars.len()),
+		emit::DefaultOutput::DontCare | emit::DefaultOutput::Panic => {
+			let mut tbl = ld.tbl.clone();
+			tbl.add_dont_cares(ld.missing.iter().cloned());
+			tbl
+		},
+	}
+}
+
+// The table with a row for each missing input, whose outputs are those of
+// --default-output when it gives bits and 0 otherwise, for the forms that need
+// a value for every input.  Don't-cares are left out, so they are 0 too.
+fn filled(tbl: &Truth, missing: &[Vec<bool>], policy: &emit::DefaultOutput,
+          nout: usize) -> Truth {
+	let mut full = tbl.clone();
+	let fill = match *policy {
+		emit::DefaultOutput::Bits(ref b) => b.clone(),
+		_ => vec![false; nout],
+	};
+	for inp in missing.iter() {
+		full.push(Entry::new(inp.clone(), fill.clone()));
+	}
+	full
+}
+
+fn minimize(args: &docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	if !args.get_str("--from-cover").is_empty() {
+		return from_cover(args, note);
+	}
+	if !args.get_str("--verify-table").is_empty() {
+		fail!(Usage, "--verify-table only applies to --from-cover.");
+	}
+	let complements = args.get_bool("--emit-complements");
+	let loose = args.get_bool("--loose-complements");
+	if loose && !complements {
+		fail!(Usage, "--loose-complements only applies to --emit-complements.");
+	}
+	let ld = load(args, argv, note)?;
+	let limit = max_enumerate(args)?;
+	// the outputs of the table, which any complements follow.
+	let nout = ld.outvars.len();
+	let ld = if complements { with_complements(ld)? } else { ld };
+	let (inenc, outenc) = (ld.inenc, ld.outenc);
+	let outvars: Vec<&str> = ld.outvars.iter().map(|s| s.as_str()).collect();
+	let output_bits = outvars.len();
+	let groups = &ld.groups;
+	let policy = ld.policy.clone();
+	let observed = args.get_vec("--observe").iter()
+		.map(|s| observe::parse(s, &ld.invars, &ld.outvars))
+		.collect::<Result<Vec<_>, _>>().map_err(Failure::Usage)?;
+
+	// with --onehot-encode, everything up to the equations' decoding works on
+	// the encoded inputs.
+	let encoding = if !args.get_bool("--onehot-encode") {
+		None
+	} else {
+		if groups.is_empty() {
+			fail!(Usage, "--onehot-encode needs at least one --onehot group.");
+		}
+		let unsupported = [("--assert-eq", !args.get_vec("--assert-eq").is_empty()),
+		                   ("--observe", !args.get_vec("--observe").is_empty()),
+		                   ("--weight", !args.get_vec("--weight").is_empty()),
+		                   ("--hazard-free", args.get_bool("--hazard-free")),
+		                   ("--form anf", args.get_str("--form") == "anf")];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--onehot-encode can't be combined with {}.", opt);
+			}
+		}
+		let enc = onehot::Encoding::new(&ld.invars, groups);
+		note(format!("Encoding one-hot inputs {}", enc));
+		Some(enc)
+	};
+	let (tbl, impossible, missing, as_strings, input_bits) = match encoding {
+		None => (ld.tbl.clone(), ld.impossible.clone(), ld.missing.clone(),
+		         ld.invars.clone(), ld.invars.len()),
+		Some(ref enc) => {
+			// codes no input of their group has are impossible too.
+			let nbits = enc.names.len();
+			let impossible: BTreeSet<Vec<bool>> = enumerate::Inputs::every(nbits)
+				.filter(|c| enc.decode(c).is_none_or(|inp| ld.impossible.contains(&inp)))
+				.collect();
+			let missing = ld.missing.iter().map(|inp| enc.encode(inp)).collect();
+			(enc.encode_table(&ld.tbl), impossible, missing, enc.names.clone(), nbits)
+		},
+	};
+	// with --given, the givens follow the inputs.
+	let givens = given::parse(args.get_vec("--given"), &ld.invars)
+		.map_err(Failure::Usage)?;
+	let (tbl, impossible, missing, as_strings, input_bits) = if givens.is_empty() {
+		(tbl, impossible, missing, as_strings, input_bits)
+	} else {
+		if encoding.is_some() {
+			fail!(Usage, "--given can't be combined with --onehot-encode.");
+		}
+		enumerate::check(input_bits + givens.len(), limit,
+		                 "; --given goes through the patterns of the inputs and givens \
+		                  together").map_err(too_many)?;
+		let mut names = as_strings;
+		names.extend(givens.iter().map(|g| g.name.clone()));
+		(given::extend_table(&tbl, &givens),
+		 given::impossible(input_bits, &givens, &impossible),
+		 missing.iter().map(|inp| given::extend(inp, &givens)).collect(), names,
+		 input_bits + givens.len())
+	};
+	let notation = notation(args, &as_strings)?;
+
+	let only = args.get_vec("--only");
+	let skip = args.get_vec("--skip");
+	let mut selected = select_outputs(&outvars[..nout], &only, &skip)
+		.map_err(Failure::Usage)?;
+	check_selection(&selected, &only, &skip)?;
+	if complements {
+		let comps: Vec<usize> = selected.iter().map(|&b| b + nout).collect();
+		selected.extend(comps);
+	}
+	let format = choice(args, "--format", &["text", "verilog-casez", "vhdl", "python",
+	                                        "sets", "bdd", "bdd-dot", "switch-c",
+	                                        "switch-rust", "if-chain-c", "rust-const",
+	                                        "share-dot", "coverage"])?;
+	let symbols = symbolic::parse_groups(args.get_vec("--group"), &ld.outvars)
+		.map_err(Failure::Usage)?;
+	if !symbols.is_empty() {
+		let unsupported = [("--format other than text or switch-rust",
+		                    format != "text" && format != "switch-rust"),
+		                   ("--form anf", args.get_str("--form") == "anf"),
+		                   ("--onehot-encode", encoding.is_some()),
+		                   ("--given", !givens.is_empty()),
+		                   ("--default-output panic",
+		                    policy == emit::DefaultOutput::Panic),
+		                   ("--cost other than terms", args.get_str("--cost") != "terms"),
+		                   ("--all-minimal", args.get_bool("--all-minimal")),
+		                   ("--share-terms", args.get_bool("--share-terms")),
+		                   ("--reuse-outputs", args.get_bool("--reuse-outputs")),
+		                   ("--explain", args.get_bool("--explain")),
+		                   ("--watch", args.get_bool("--watch"))];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--group can't be combined with {}.", opt);
+			}
+		}
+		// the grouped outputs are minimized value by value instead.
+		selected.retain(|b| !symbols.iter().any(|g| g.outputs.contains(b)));
+	}
+	let emit_cover = args.get_str("--emit-cover");
+	if !emit_cover.is_empty() {
+		let unsupported = [("--group", !symbols.is_empty()),
+		                   ("--form anf", args.get_str("--form") == "anf"),
+		                   ("--format sets, bdd or bdd-dot",
+		                    format == "sets" || format == "bdd" || format == "bdd-dot")];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--emit-cover can't be combined with {}.", opt);
+			}
+		}
+	}
+	match choice(args, "--form", &["sop", "anf"])? {
+		"anf" if format != "text" =>
+			fail!(Usage, "--form anf only applies to --format text."),
+		"anf" if args.get_bool("--share-terms") =>
+			fail!(Usage, "--share-terms needs sum-of-products equations."),
+		"anf" if args.get_bool("--explain") =>
+			fail!(Usage, "--explain needs sum-of-products equations."),
+		"anf" if !args.get_vec("--observe").is_empty() =>
+			fail!(Usage, "--observe needs sum-of-products equations."),
+		"anf" => {
+			let full = filled(&tbl, &missing, &policy, output_bits);
+			for &b in selected.iter() {
+				let e = anf::XorEquation::new(&full, b, outvars[b], &as_strings, limit)
+					.map_err(too_many)?;
+				if !e.verify(&full) {
+					fail!(Verification, "ANF for '{}' does not reproduce the table.", e);
+				}
+				println!("{}", e);
+			}
+			return Ok(());
+		},
+		_ => {},
+	}
+	if format == "sets" {
+		for &b in selected.iter() {
+			let sets = minterm::sets::describe(&tbl, b, outvars[b], input_bits, &policy)
+				.map_err(|e| Failure::Invalid(format!("{}: {}", outvars[b], e)))?;
+			print!("{}", sets);
+		}
+		return Ok(());
+	}
+	if format == "bdd" || format == "bdd-dot" {
+		let instead = format!("; --format {} goes through them all", format);
+		enumerate::check(input_bits, limit, &instead).map_err(too_many)?;
+		let full = filled(&tbl, &missing, &policy, output_bits);
+		let natural: Vec<usize> = (0..input_bits).collect();
+		let names = |order: &[usize]| -> String {
+			let names: Vec<&str> = order.iter().map(|&v| as_strings[v].as_str()).collect();
+			names.join(" ")
+		};
+		for &b in selected.iter() {
+			let size = bdd::Bdd::from_truth(&full, b, &natural, limit)
+				.map_err(too_many)?.size();
+			let best = bdd::sift(&full, b, &natural, limit).map_err(too_many)?;
+			if format == "bdd-dot" {
+				note(format!("{}: {} nodes in the natural order, {} after sifting.",
+				             outvars[b], size, best.size()));
+				print!("{}", best.to_dot(outvars[b], &as_strings));
+			} else if best.size() < size {
+				println!("{}: {} nodes in the order {}; {} in the order {} after sifting.",
+				         outvars[b], size, names(&natural), best.size(),
+				         names(best.order()));
+			} else {
+				println!("{}: {} nodes in the order {}, which sifting doesn't improve on.",
+				         outvars[b], size, names(&natural));
+			}
+		}
+		return Ok(());
+	}
+	let exact = choice(args, "--algorithm", &["greedy", "exact"])? == "exact";
+	let overridden = !args.get_vec("--algorithm-for").is_empty();
+	let default = if exact { Algorithm::Exact } else { Algorithm::Greedy };
+	let algorithm_for = parse_algorithm_for(args.get_vec("--algorithm-for"))
+		.map_err(Failure::Usage)?;
+	let timeout = match args.get_str("--timeout") {
+		"" => None,
+		s => match s.parse::<f64>() {
+			Ok(secs) if secs >= 0.0 => Some(Duration::from_millis((secs*1000.0) as u64)),
+			_ => fail!(Usage, "Invalid --timeout '{}'; expected a number of seconds.",
+			           s),
+		},
+	};
+	let max_steps = match args.get_str("--max-steps") {
+		"" => None,
+		s => match s.parse::<u64>() {
+			Ok(n) => Some(n),
+			Err(_) => fail!(Usage, "Invalid --max-steps '{}'; expected an integer.", s),
+		},
+	};
+	let simplify_limit = |opt: &str| match args.get_str(opt) {
+		"" => Ok(None),
+		s => match s.parse::<usize>() {
+			Ok(n) if n > 0 => Ok(Some(n)),
+			_ => fail!(Usage, "Invalid {} '{}'; expected a positive integer.", opt, s),
+		},
+	};
+	let (max_passes, max_merges) = (simplify_limit("--max-passes")?,
+	                                simplify_limit("--max-merges")?);
+	let mut budget = exact::Budget::new(timeout, max_steps);
+	budget.limit_simplify(max_passes, max_merges);
+	match args.get_str("--memory-limit") {
+		"" => {},
+		s => match s.parse::<f64>() {
+			Ok(mb) if mb > 0.0 => budget.limit_memory((mb * 1048576.0) as usize),
+			_ => fail!(Usage, "Invalid --memory-limit '{}'; expected a positive number \
+			                   of megabytes.", s),
+		},
+	}
+	let weights = input_weights(args.get_vec("--weight"), &as_strings)
+		.map_err(Failure::Usage)?;
+	let all_minimal = if !args.get_bool("--all-minimal") {
+		None
+	} else {
+		match args.get_str("--max-solutions").parse::<usize>() {
+			Ok(n) if n > 0 => Some(n),
+			_ => {
+				fail!(Usage, "Invalid --max-solutions '{}'; expected a positive \
+				              integer.", args.get_str("--max-solutions"));
+			},
+		}
+	};
+	let cost = embed::Cost::parse(choice(args, "--cost",
+	                                     &["terms", "conditions", "literals"])?)
+		.map_err(Failure::Usage)?;
+	let shared = cost == embed::Cost::Conditions;
+	let factored = cost == embed::Cost::Literals;
+	if all_minimal.is_some() && overridden {
+		fail!(Usage, "--algorithm-for can't be combined with --all-minimal.");
+	}
+	if all_minimal.is_some() && factored {
+		fail!(Usage, "--cost literals merges terms greedily; it can't be combined with \
+		              --all-minimal.");
+	}
+	let improve_for = match args.get_str("--improve") {
+		"" => None,
+		s => match s.parse::<f64>() {
+			Ok(secs) if secs >= 0.0 => Some(Duration::from_millis((secs*1000.0) as u64)),
+			_ => fail!(Usage, "Invalid --improve '{}'; expected a number of seconds.",
+			           s),
+		},
+	};
+	if improve_for.is_some() && all_minimal.is_some() {
+		fail!(Usage, "--improve polishes the greedy covers; it can't be combined with \
+		              --all-minimal.");
+	}
+	let seed = match args.get_str("--seed").parse::<u64>() {
+		Ok(n) => n,
+		Err(_) => fail!(Usage, "Invalid --seed '{}'; expected an integer.",
+		                args.get_str("--seed")),
+	};
+	let share_terms = args.get_bool("--share-terms");
+	let pregroup = Pregroup::parse(args.get_str("--pregroup")).map_err(Failure::Usage)?;
+	let mut opts = embed::MinimizeOptions::new().algorithm(default).cost(cost)
+		.share_terms(share_terms).canonical(!args.get_bool("--table-order")).seed(seed)
+		.pregroup(pregroup);
+	for &(ref output, algorithm) in algorithm_for.iter() {
+		opts = opts.algorithm_for(output, algorithm);
+	}
+	if let Some(t) = timeout {
+		opts = opts.timeout(t);
+	}
+	if let Some(n) = max_steps {
+		opts = opts.max_steps(n);
+	}
+	if let Some(n) = max_passes {
+		opts = opts.max_passes(n);
+	}
+	if let Some(n) = max_merges {
+		opts = opts.max_merges(n);
+	}
+	if let Some(t) = improve_for {
+		opts = opts.improve(t);
+	}
+	let options = opts.algorithms(&ld.outvars).map_err(Failure::Usage)?;
+	let any_exact = selected.iter().any(|&b| options[b] == Algorithm::Exact);
+	let min_reuse = min_reuse(args)?;
+	check_generator_options(args, format, input_bits, selected.len())?;
+	let emit_tests = test_harness(args)?;
+	let reuse = args.get_bool("--reuse-outputs");
+	if reuse {
+		let unsupported = [("--cost conditions", shared),
+		                   ("--all-minimal", all_minimal.is_some())];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--reuse-outputs can't be combined with {}.", opt);
+			}
+		}
+	}
+	let implied = args.get_bool("--use-implications");
+	if implied {
+		let unsupported = [("--format other than text or python",
+		                    format != "text" && format != "python"),
+		                   ("--dict-lookup", args.get_bool("--dict-lookup")),
+		                   ("--reuse-outputs", reuse),
+		                   ("--share-terms", share_terms),
+		                   ("--emit-cover", !emit_cover.is_empty()),
+		                   ("--explain", args.get_bool("--explain")),
+		                   ("--cost conditions", shared),
+		                   ("--all-minimal", all_minimal.is_some()),
+		                   ("--group", !symbols.is_empty()),
+		                   ("--onehot-encode", encoding.is_some()),
+		                   ("--given", !givens.is_empty()),
+		                   ("--observe", !observed.is_empty()),
+		                   ("--emit-complements", complements),
+		                   ("--hazard-free", args.get_bool("--hazard-free")),
+		                   ("--freq-col", !args.get_str("--freq-col").is_empty()),
+		                   ("--form anf", args.get_str("--form") == "anf"),
+		                   ("--watch", args.get_bool("--watch"))];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--use-implications can't be combined with {}.", opt);
+			}
+		}
+	}
+	let explain = args.get_bool("--explain");
+	if explain {
+		let unsupported = [("--format other than text", format != "text"),
+		                   ("--onehot-encode", encoding.is_some()),
+		                   ("--share-terms", share_terms),
+		                   ("--reuse-outputs", reuse),
+		                   ("--cost conditions", shared)];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--explain can't be combined with {}.", opt);
+			}
+		}
+	}
+	let broad = match args.get_str("--broad").parse::<f64>() {
+		Ok(pct) if (0.0..=100.0).contains(&pct) => pct,
+		_ => fail!(Usage, "Invalid --broad '{}'; expected a percentage from 0 to 100.",
+		           args.get_str("--broad")),
+	};
+	if format == "coverage" {
+		let unsupported = [("--given", !givens.is_empty()),
+		                   ("--share-terms", share_terms),
+		                   ("--reuse-outputs", reuse),
+		                   ("--cost conditions", shared)];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--format coverage can't be combined with {}.", opt);
+			}
+		}
+	}
+	if complements {
+		let unsupported = [("--group", !symbols.is_empty()),
+		                   ("--all-minimal", all_minimal.is_some()),
+		                   ("--watch", args.get_bool("--watch")),
+		                   ("--cost conditions unless --loose-complements",
+		                    shared && !loose)];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--emit-complements can't be combined with {}.", opt);
+			}
+		}
+		if !loose {
+			enumerate::check(input_bits, limit, "; give --loose-complements to check the \
+			                                     complements on the table's rows only")
+				.map_err(too_many)?;
+		}
+	}
+	if args.get_bool("--canonical") && args.get_bool("--table-order") {
+		fail!(Usage, "--canonical and --table-order are opposites; give one.");
+	}
+	let searched = any_exact || all_minimal.is_some() || shared;
+	let watch = args.get_bool("--watch");
+	if watch {
+		let unsupported = [
+			("from-expr", args.get_bool("from-expr")),
+			("compose", args.get_bool("compose")),
+			("several --table", args.get_count("--table") > 1),
+			("--constraint", !args.get_vec("--constraint").is_empty()),
+			("--onehot", !groups.is_empty()),
+			("--observe", !observed.is_empty()),
+			("--default-output", policy != emit::DefaultOutput::Zero),
+			("--form anf", args.get_str("--form") == "anf"),
+			("--format other than text", format != "text"),
+			("--cost conditions", shared),
+			("--cost literals", factored),
+			// an edit is merged into the covers greedily, which would leave
+			// them no longer exact.
+			("--algorithm exact", exact),
+			("--weight", !args.get_vec("--weight").is_empty()),
+			("--all-minimal", all_minimal.is_some()),
+			("--hazard-free", args.get_bool("--hazard-free")),
+			("--share-terms", share_terms),
+			("--table-order", args.get_bool("--table-order")),
+			("--reuse-outputs", reuse),
+			("--explain", explain),
+			("--memory-limit", !args.get_str("--memory-limit").is_empty()),
+			("--improve", improve_for.is_some()),
+			("--algorithm-for", overridden),
+			("--given", !givens.is_empty()),
+		];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--watch can't be combined with {}.", opt);
+			}
+		}
+	}
+	let by_frequency = !args.get_str("--freq-col").is_empty();
+	if by_frequency {
+		let unsupported = [("from-expr", args.get_bool("from-expr")),
+		                   ("--format other than text or python",
+		                    format != "text" && format != "python"),
+		                   ("--dict-lookup", args.get_bool("--dict-lookup")),
+		                   ("--table-order", args.get_bool("--table-order")),
+		                   ("--cost conditions", shared),
+		                   ("--reuse-outputs", reuse),
+		                   ("--watch", watch)];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--freq-col can't be combined with {}.", opt);
+			}
+		}
+	}
+	if !args.get_vec("--weight").is_empty() && !searched {
+		fail!(Usage, "--weight only affects the exact search; add --algorithm exact.");
+	}
+
+	let mut silent = progress::Silent;
+	let mut status = StatusLine{phase: String::new(), output: String::new(),
+	                            last: None};
+	let quiet = args.get_bool("--quiet");
+	let progress: &mut dyn progress::Progress =
+		if args.get_bool("--progress") || (!quiet && std::io::stderr().is_terminal()) {
+			&mut status
+		} else {
+			&mut silent
+		};
+
+	let seeds = check_assertions(args, &tbl, &outvars, &as_strings, &observed, note)?;
+
+	assert_eq!(output_bits, tbl.rows()[0].output.len());
+	let mut eqns = initial_equations(&ld, &tbl, &as_strings, &missing, &impossible,
+	                                 &selected, &seeds);
+	for eqn in eqns.iter_mut() {
+		let relaxed = observe::relax(eqn, &tbl, &observed);
+		if relaxed > 0 {
+			note(format!("--observe makes {} row(s) don't-cares for {}.", relaxed,
+			             eqn.varname()));
+		}
+	}
+	// with --use-implications, the output each is computed from, and the rows
+	// that settles don't-cares for it.
+	let used = if !implied { vec![] } else {
+		let rows = completed(&ld);
+		let mined: Vec<implication::Implication> = implication::mine(&rows).into_iter()
+			.filter(|i| selected.contains(&i.from) && selected.contains(&i.to)).collect();
+		let used = implication::choose(&mined, &rows, nout);
+		for eqn in eqns.iter_mut() {
+			if let Some(i) = used[eqn.index()] {
+				let relaxed = observe::relax(eqn, &rows, &[i.unsettled(input_bits)]);
+				note(format!("--use-implications: {} holds, making {} row(s) don't-cares \
+				              for {}.", i.describe(&ld.outvars, &notation), relaxed,
+				             eqn.varname()));
+			}
+		}
+		used
+	};
+	// with --explain or --improve, what each equation was minimized from.
+	let start = if explain || improve_for.is_some() { eqns.clone() } else { vec![] };
+	// with --all-minimal, the other minimum covers of each equation.
+	let mut alternatives: Vec<Vec<Equation>> = vec![];
+	// otherwise, the covers the options' cost and algorithms came to.
+	let mut covers = embed::Covers{minimal: vec![], proven: vec![], ran: vec![],
+	                               conditions: None, decisions: vec![], pregrouped: vec![],
+	                               warnings: vec![]};
+	let mut improved = vec![];
+	let outcome = fixpoint::minimize(&mut eqns, input_bits, cost, |eqns| {
+		if let Some(max) = all_minimal {
+			for eqn in eqns.iter_mut() {
+				progress.equation(eqn.index(), eqn.varname());
+				let mut all = exact::minimize_all(eqn, input_bits, &weights, &mut budget,
+				                                  progress, max);
+				eqn.set_terms(all[0].terms().to_vec());
+				alternatives.push(all.split_off(1));
+			}
+			covers.minimal = vec![!budget.exhausted() && !budget.out_of_memory();
+			                      eqns.len()];
+			covers.proven = covers.minimal.clone();
+		} else {
+			covers = embed::minimize_equations(eqns, input_bits, &opts, &options,
+			                                   &weights, &mut budget, progress);
+		}
+	}, |eqns| improved = embed::improve(eqns, &start, input_bits, &opts));
+	let embed::Covers{mut minimal, mut proven, ran, mut conditions, decisions, pregrouped,
+	                  warnings} = covers;
+	progress.done();
+	warn(args, &warnings)?;
+	warn(args, &outcome.warnings)?;
+	if let Some(stopped) = outcome.note() {
+		note(stopped);
+	}
+	if overridden {
+		let strs: Vec<String> = eqns.iter().zip(ran.iter())
+			.map(|(e, r)| format!("{} {}", e.varname(), r)).collect();
+		note(format!("Algorithms: {}.", strs.join("; ")));
+	}
+	if pregroup == Pregroup::Auto && !pregrouped.is_empty() {
+		let strs: Vec<String> = eqns.iter().zip(pregrouped.iter())
+			.map(|(e, &p)| format!("{} {}", e.varname(), if p { "rows" } else { "none" }))
+			.collect();
+		note(format!("Pregrouping: {}.", strs.join("; ")));
+	}
+	// by name, as --reuse-outputs reorders the equations.
+	let partial: Vec<String> = eqns.iter().zip(ran.iter()).filter(|&(_, r)| !r.completed())
+		.map(|(e, _)| e.varname().to_string()).collect();
+	if !partial.is_empty() {
+		note(format!("The simplifier stopped at --max-passes or --max-merges for {}; \
+		              the covers are correct but partially simplified.",
+		             partial.join(", ")));
+	}
+	if budget.out_of_memory() {
+		let how = if searched { "simplified greedily" }
+		          else { "simplified from partly merged terms" };
+		note(format!("Some outputs outgrew --memory-limit and were {}.", how));
+	}
+	for (eqn, imp) in eqns.iter().zip(improved) {
+		match imp {
+			None => note(format!("--improve: {} has more than {} inputs; left as it \
+			                      is.", eqn.varname(), improve::MAX_IMPROVE_BITS)),
+			Some(imp) if imp.after < imp.before =>
+				note(format!("--improve: {} went from {} terms and {} literals to {} \
+				              and {} in {} passes.", eqn.varname(), imp.before.0,
+				             imp.before.1, imp.after.0, imp.after.1, imp.passes)),
+			Some(imp) =>
+				note(format!("--improve: {} passes found nothing cheaper for {}.",
+				             imp.passes, eqn.varname())),
+		}
+	}
+	if complements && !loose {
+		// each complement again, from its output's cover.
+		let at: Vec<usize> = (0..eqns.len()).filter(|&k| eqns[k].index() >= nout)
+			.collect();
+		let mut fixed: Vec<Equation> = at.iter().map(|&k| {
+			let cover = eqns.iter().find(|e| e.index() + nout == eqns[k].index()).unwrap();
+			complement::fixed(cover, &eqns[k], input_bits, &impossible)
+		}).collect();
+		let covers = embed::minimize_equations(&mut fixed, input_bits, &opts, &options,
+		                                       &weights, &mut budget,
+		                                       &mut progress::Silent);
+		warn(args, &covers.warnings)?;
+		for (i, (&k, eqn)) in at.iter().zip(fixed).enumerate() {
+			eqns[k] = eqn;
+			minimal[k] = covers.minimal[i];
+			proven[k] = covers.proven[i];
+		}
+	}
+	if args.get_bool("--hazard-free") {
+		for (eqn, proven) in eqns.iter_mut().zip(proven.iter_mut()) {
+			let added = exact::hazard_terms(eqn, input_bits);
+			if added.is_empty() {
+				continue;
+			}
+			// no longer a minimum cover.
+			*proven = false;
+			let strs: Vec<String> = added.iter().map(|t| notation.term(t)).collect();
+			note(format!("Added for hazard coverage in {}: {}", eqn.varname(),
+			             strs.join(" + ")));
+			eqn.add_terms(added);
+		}
+	}
+	if complements {
+		// strict complements are checked on every possible input, loose ones on
+		// those the table or the policy gives an output.
+		let filled = match ld.policy {
+			emit::DefaultOutput::Zero | emit::DefaultOutput::Bits(_) => &missing[..],
+			emit::DefaultOutput::DontCare | emit::DefaultOutput::Panic => &[],
+		};
+		for comp in eqns.iter().filter(|e| e.index() >= nout) {
+			let eqn = eqns.iter().find(|e| e.index() + nout == comp.index()).unwrap();
+			let clash = if loose {
+				let listed = complement::rows(&tbl).chain(filled.iter()).cloned();
+				complement::clash(eqn, comp, listed)
+			} else {
+				let possible = enumerate::Inputs::every(input_bits)
+					.filter(|inp| !impossible.contains(inp));
+				complement::clash(eqn, comp, possible)
 			};
-			ent.input.push(on);
-		}
-
-		// we take the right*most* NOUT columns for the outputs.  Note that this is
-		// not columns nin through nin+nout: there could be "spacer" columns
-		// between the inputs and outputs.
-		let mincol = record.len() - nout;
-		for j in mincol .. record.len() {
-			let on: bool = match record[j].parse::<i32>() {
-				Ok(b) => b != 0,
-				Err(e) => {
-					println!("WARNING: ignoring output '{}' ({}) on line {}:{}",
-					         record[j].to_string(), e, line, j);
-					false
-				},
+			if let Some((inp, v)) = clash {
+				fail!(Verification, "{} and {} are both {} for {}.", eqn.varname(),
+				      comp.varname(), v as u8, complete::pattern(&inp));
+			}
+		}
+	}
+	if !args.get_bool("--table-order") {
+		eqns.iter_mut().for_each(Equation::canonicalize);
+		alternatives.iter_mut().flat_map(|a| a.iter_mut()).for_each(Equation::canonicalize);
+	}
+	let (tbl, as_strings, input_bits) = match encoding {
+		None => (tbl, as_strings, input_bits),
+		Some(ref enc) => {
+			// the don't-cares are patterns of the encoded inputs; the generators
+			// don't need them.
+			let decode = |eqn: &mut Equation| {
+				let decoded = eqn.terms().iter().map(|t| enc.decode_term(t)).collect();
+				eqn.set_terms(decoded);
+				eqn.clear_dont_cares();
+			};
+			eqns.iter_mut().for_each(&decode);
+			alternatives.iter_mut().flat_map(|a| a.iter_mut()).for_each(&decode);
+			for c in conditions.iter_mut().flat_map(|cs| cs.iter_mut()) {
+				c.term = enc.decode_term(&c.term);
+			}
+			(enc.decode_table(&tbl), ld.invars.clone(), ld.invars.len())
+		},
+	};
+	if !givens.is_empty() {
+		let nbits = ld.invars.len();
+		for eqn in eqns.iter() {
+			let expanded = given::expand(eqn, &givens, &ld.invars);
+			let differs = |e: &&Entry| {
+				expanded.evaluate(&e.input[..nbits]) != eqn.evaluate(&e.input)
 			};
-			ent.output.push(on);
-		}
-		tbl.table.push(ent.clone());
-		ent.clear()
-	}
-	return tbl;
-}
-
-#[cfg(test)]
-mod test {
-	use super::*;
-
-	fn example_head() -> String {
-		let s = ",COMPONENTS,,,HAVE,,,,,REQUIRED_VARS includes,,,\n".to_string() +
-			"REQUIRED,OGL,GLX,EGL,OGL,GLX,EGL,GL,,OGL,GLX,EGL,GL\n" +
-			"0,0,0,0,0,0,0,0,,1,1,0,0\n" +
-			"0,0,0,0,0,0,0,1,,0,0,0,1\n";
-		s
-	}
-
-	// a faux example with just 3 inputs and 2 outputs, for validation against.
-	// if the inputs are 'a','b','c' and the outputs are 'x','y', then the
-	// basic solution is:
-	//   x = a'b'c + a'bc' + ab'c' + abc'
-	//   y = a'b'c' + a'bc' + ab'c' + ab'c + abc'
-	// i.e. a solution of:
-	//   x = y = 0
-	//   if(a'bc'): x = y = 1
-	//   if(abc'): x = y = 1
-
-	//   if(ab'c'): x = y = 1
-	//   if(ab'c): y = 1
-
-	//   if(a'b'c): x = 1
-	//   if(a'b'c'): y = 1
-	// that can be simplified to:
-	//   if(a'b'):
-	//    if(c): x = 1
-	//    else if(c'): y = 1
-	//   if(ab'):
-	//    y = 1
-	//    if(c'): x = 1
-	//   if(bc'): x = y = 1
-	fn small_example() -> String {
-		let s =
-			"0,0,0,,0,1\n".to_string() +
-			"0,0,1,,1,0\n" +
-			"0,1,0,,1,1\n" +
-			"0,1,1,,0,0\n" +
-			"1,0,0,,1,1\n" +
-			"1,0,1,,0,1\n" +
-			"1,1,0,,1,1\n" +
-			"1,1,1,,0,0\n";
-		s
-	}
-
-	#[test]
-	fn read_test() {
-		let eg = example_head();
-		let tbl = parse(eg.as_bytes(), 2, 8, 4);
-		// should be the same number of lines:
-		assert_eq!(tbl.len(), 2);
-	}
-
-	#[test]
-	fn parse_small() {
-		let small = small_example();
-		let truth = parse(small.as_bytes(), 0, 3, 2);
-		assert_eq!(truth.len(), 8);
-	}
-
-	#[test]
-	fn term_merge() {
-		let t1 = Term::new(vec![(0,false), (1,false), (2,false)]);
-		let t2 = Term::new(vec![(0,false), (1,true), (2,false)]);
-		let t3 = Term::new(vec![(0,false), (1,true), (2,false), (3,true)]);
-		let t4 = Term::new(vec![(0,false), (1,true), (2,false), (3,false)]);
-		assert!(t1.mergeable(&t2));
-		assert!(!t1.mergeable(&t3));
-		assert!(!t1.mergeable(&t4));
-		assert!(t2.mergeable(&t1));
-		assert!(!t2.mergeable(&t3));
-		assert!(!t2.mergeable(&t4));
-		assert!(!t3.mergeable(&t1));
-		assert!(!t3.mergeable(&t2));
-		assert!(t3.mergeable(&t4));
-		assert!(!t4.mergeable(&t1));
-		assert!(!t4.mergeable(&t2));
-		assert!(t4.mergeable(&t3));
-	}
-
-	#[test]
-	fn small_simplify() {
-		let small = small_example();
-		let truth = parse(small.as_bytes(), 0, 3, 2);
-		assert_eq!(truth.len(), 8);
-		let ivar: Vec<String> = vec!["A", "B", "C"].iter().map(
-			|e| e.to_string()
-		).collect();
-		let mut eqns = equations(&truth, vec!["foo", "bar"], ivar);
-		assert_eq!(eqns.len(), truth.table[0].output.len());
-		for e in 0..eqns.len() {
-			println!("{}", eqns[e]);
-			eqns[e].simplify();
+			if let Some(ent) = tbl.rows().iter().find(differs) {
+				fail!(Verification, "{} with the givens expanded, {}, differs from it{}.",
+				      eqn.varname(), expanded, sources_note(&ent.sources));
+			}
+		}
+		let uses: Vec<String> = eqns.iter().filter_map(|e| {
+			let used = given::used(e, nbits, &givens);
+			if used.is_empty() { None }
+			else { Some(format!("{} uses {}", e.varname(), used.join(" and "))) }
+		}).collect();
+		if uses.is_empty() {
+			note("--given: no output is cheaper with the givens.".to_string());
+		} else {
+			note(format!("--given: {}.", uses.join("; ")));
+		}
+	}
+	if by_frequency {
+		let weights = frequency::row_weights(&tbl, &ld.frequencies);
+		for eqn in eqns.iter_mut().filter(|e| e.terms().len() > 1) {
+			let r = frequency::hottest_first(eqn, &tbl, &weights);
+			if r.after < r.before {
+				note(format!("--freq-col: {} tests {:.2} terms on average in canonical \
+				              order, {:.2} with the frequent first.", eqn.varname(),
+				             r.before, r.after));
+			} else {
+				note(format!("--freq-col: {} tests {:.2} terms on average, and no fewer \
+				              with the frequent first.", eqn.varname(), r.before));
+			}
+		}
+	}
+	// each group's values, and the condition giving each.
+	let mut symbol_covers: Vec<(Vec<Vec<bool>>, Vec<Equation>)> = vec![];
+	let full = if symbols.is_empty() { Truth::default() } else { every_input(&ld) };
+	for g in symbols.iter() {
+		let values = symbolic::values(g, &full);
+		let mut conds = symbolic::equations(g, &values, &full, &as_strings);
+		minterm::minimize(&mut conds, input_bits, exact, &weights, &mut budget,
+		                  &mut progress::Silent);
+		conds.iter_mut().for_each(Equation::canonicalize);
+		symbol_covers.push((values, conds));
+	}
+	// the table's don't-care rows completed as the covers have them, which the
+	// generated code then gives them rather than its default branch: those it
+	// lists, the inputs --constraint and --onehot rule out and, with --missing
+	// dc or --default-output dc, those it lacks.  With --default-output panic the
+	// missing inputs still panic.
+	let mut minimized = tbl.clone();
+	{
+		let (impossible, missing) = if encoding.is_some() { (&ld.impossible, &ld.missing) }
+		                            else { (&impossible, &missing) };
+		minimized.add_dont_cares(impossible.iter().cloned());
+		if policy == emit::DefaultOutput::DontCare {
+			minimized.add_dont_cares(missing.iter().cloned());
+		}
+	}
+	let (mut whole, from) = completion::complete(&minimized, &eqns, output_bits);
+	let mut conflicts: Vec<String> = vec![];
+	for (g, (values, conds)) in symbols.iter().zip(symbol_covers.iter()) {
+		conflicts.extend(completion::resolve(&mut whole, from, g, values, conds)
+			.iter().map(|c| c.describe(g, values)));
+	}
+	warn(args, &conflicts)?;
+	if args.get_bool("--show-completion") {
+		let mut shown: Vec<usize> = eqns.iter().map(|e| e.index())
+			.chain(symbols.iter().flat_map(|g| g.outputs.iter().cloned())).collect();
+		shown.sort_unstable();
+		if whole.rows().len() == from {
+			note("--show-completion: there are no don't-care rows.".to_string());
+		} else {
+			eprint!("{}", completion::csv(&whole, from, &as_strings, &ld.outvars, &shown));
+		}
+	}
+	let unlisted = emit::Unlisted::new(policy, &whole, input_bits, &as_strings);
+	if !emit_cover.is_empty() {
+		let cov = cover::Cover{invars: as_strings.clone(), outvars: ld.outvars.clone(),
+		                       algorithm: if overridden { "mixed" }
+		                                  else if searched { "exact" }
+		                                  else { "greedy" }.to_string(),
+		                       cost: args.get_str("--cost").to_string(),
+		                       unlisted: unlisted.clone(), eqns: eqns.clone(),
+		                       minimal: minimal.clone(), proven};
+		write_cover(&cov, emit_cover)?;
+	}
+	let (eqns, minimal) = if !reuse { (eqns, minimal) } else {
+		reuse_outputs(eqns, minimal)
+	};
+	let (eqns, minimal) = if !implied { (eqns, minimal) } else {
+		use_implications(&ld, eqns, minimal, &used, input_bits)?
+	};
+	let products = if share_terms {
+		shared_products(args, &eqns, &as_strings, &outvars, min_reuse)?
+	} else {
+		vec![]
+	};
+	let explained: Vec<Vec<explain::Explanation>> = start.iter().zip(eqns.iter())
+		.filter(|_| explain)
+		.map(|(s, e)| explain::explain(s, e, &tbl, input_bits))
+		.collect();
+	let order = bit_order(args)?;
+	if format == "switch-rust" {
+		for (g, (values, conds)) in symbols.iter().zip(symbol_covers.iter()) {
+			print!("{}", symbolic::rust(g, values, conds, &as_strings, &ld.outvars, order)
+				.map_err(Failure::Usage)?);
+		}
+		if selected.is_empty() {
+			return Ok(());
+		}
+	}
+	if format == "if-chain-c" {
+		exclusive_outputs(&eqns, &as_strings, &unlisted, &notation, note)?;
+	}
+	match generated(args, format, &eqns, &as_strings, &unlisted, &products, order,
+	                &notation) {
+		Some(code) => print!("{}", code),
+		None if format == "coverage" => {
+			let terms = breadth::breadth(&eqns, &tbl, input_bits, broad, &notation);
+			print!("{}", breadth::report(&terms));
+		},
+		None if format == "python" => {
+			let names: Vec<String> = eqns.iter().map(|e| e.varname().to_string())
+				.collect();
+			print!("{}", python::lookup(&whole, &selected, &names, &as_strings,
+			                            &unlisted, args.get_bool("--return-dict")));
+		},
+		None if share_terms =>
+			print!("{}", emit::text(&eqns, &products, &as_strings, &notation)),
+		None if conditions.is_some() => {
+			let conditions = conditions.unwrap();
+			for c in conditions.iter() {
+				let outs: Vec<&str> = c.outputs.iter()
+					.map(|&e| eqns[e].varname()).collect();
+				println!("if({}): {} = 1", notation.term(&c.term), outs.join(" = "));
+			}
+			let proven = if minimal.iter().all(|&m| m) { "" }
+			             else { " (not proven minimal)" };
+			println!("{} conditions{}.", conditions.len(), proven);
+		},
+		None => for e in 0..eqns.len() {
+			let partly = partial.iter().any(|p| p == eqns[e].varname());
+			let proven = if partly { " (partially simplified)" }
+			             else if minimal[e] { "" }
+			             else { " (not proven minimal)" };
+			println!("{}{}", notation.equation(&eqns[e]), proven);
+			for alt in alternatives.get(e).into_iter().flat_map(|a| a.iter()) {
+				println!("  or {}", notation.equation(alt));
+			}
+			for ex in explained.get(e).into_iter().flat_map(|x| x.iter()) {
+				println!("  {}", explain::describe(ex, &tbl, &notation));
+			}
+			for d in decisions.iter().filter(|d| explain && d.outputs[0] == e) {
+				println!("  {}", factor::describe(d, &eqns, &notation));
+			}
+		},
+	}
+	if format == "text" {
+		for (g, (values, conds)) in symbols.iter().zip(symbol_covers.iter()) {
+			print!("{}", symbolic::text(g, values, conds, &notation));
+		}
+	}
+	if let Some((framework, sample)) = emit_tests {
+		let cases = harness::cases(&eqns, &tbl, input_bits, &unlisted.policy, sample, seed)
+			.map_err(Failure::Verification)?;
+		if cases.is_empty() {
+			fail!(Usage, "--emit-tests has nothing to assert: the table leaves every \
+			              output open on the inputs it would test.");
+		}
+		let names: Vec<String> = eqns.iter().map(|e| e.varname().to_string()).collect();
+		let code = harness::tests(framework, &cases, &as_strings, &names,
+		                          args.get_str("--tests-include"));
+		let file = args.get_str("--tests-file");
+		let written = File::create(file).and_then(|mut fp| fp.write_all(code.as_bytes()));
+		if let Err(e) = written {
+			fail!(Usage, "Cannot create {}: {}", file, e);
+		}
+		note(format!("--emit-tests: {} cases written to {}.", cases.len(), file));
+	}
+	if watch {
+		let file = args.get_vec("<truth>")[0];
+		let src = watch::Source{file, nheader: HEADER_LINES,
+		                        nin: input_bits, nout: output_bits, inenc,
+		                        outenc, cells: cells(args)?, empty: empty(args)?,
+		                        layout: layout(args, &table_data(file)?)?};
+		watch::run(&src, tbl, eqns);
+	}
+	let proven = minimal.iter().all(|&m| m);
+	// the covers are still correct, but scripts may want to know they could
+	// be improved upon with a larger budget.
+	if !proven {
+		return Err(Failure::Unproven);
+	}
+	Ok(())
+}
+
+// Fails on options of the code generators that don't apply to --format, or
+// that it can't honour for 'nbits' inputs and 'nout' outputs.
+fn check_generator_options(args: &docopt::ArgvMap, format: &str, nbits: usize,
+                           nout: usize) -> Result<(), Failure> {
+	let jump_table = format == "switch-c" || format == "switch-rust";
+	let lookup = format == "rust-const" && !args.get_bool("--macro");
+	let share_terms = args.get_bool("--share-terms");
+	if share_terms && (format == "verilog-casez" || jump_table ||
+	                   format == "rust-const" || args.get_bool("--dict-lookup")) {
+		fail!(Usage, "--share-terms needs equations to write; --format {} has none.",
+		      if format == "python" { "python --dict-lookup" } else { format });
+	}
+	if share_terms && format == "if-chain-c" {
+		fail!(Usage, "--format if-chain-c tests each output's own cover; it can't be \
+		              combined with --share-terms.");
+	}
+	if args.get_bool("--reuse-outputs") {
+		let unsupported = [("--format other than text or python",
+		                    format != "text" && format != "python"),
+		                   ("--dict-lookup", args.get_bool("--dict-lookup")),
+		                   ("--share-terms", share_terms)];
+		for &(opt, given) in unsupported.iter() {
+			if given {
+				fail!(Usage, "--reuse-outputs can't be combined with {}.", opt);
+			}
 		}
 	}
+	if args.get_bool("--compress") && !jump_table {
+		fail!(Usage, "--compress only applies to --format switch-c and switch-rust.");
+	}
+	if args.get_bool("--collapse-unshared") && format != "share-dot" {
+		fail!(Usage, "--collapse-unshared only applies to --format share-dot.");
+	}
+	if args.get_bool("--macro") && format != "rust-const" {
+		fail!(Usage, "--macro only applies to --format rust-const.");
+	}
+	if !args.get_str("--emit-tests").is_empty() {
+		if format != "switch-c" && format != "if-chain-c" {
+			fail!(Usage, "--emit-tests only applies to --format switch-c and if-chain-c.");
+		}
+		if args.get_str("--tests-file").is_empty() {
+			fail!(Usage, "--emit-tests needs --tests-file to write the test to.");
+		}
+	} else if !args.get_str("--tests-file").is_empty() ||
+		!args.get_str("--tests-sample").is_empty() {
+		fail!(Usage, "--tests-file and --tests-sample only apply to --emit-tests.");
+	}
+	if format == "coverage" && nbits > breadth::MAX_BITS {
+		fail!(Usage, "--format coverage handles at most {} inputs.", breadth::MAX_BITS);
+	}
+	if jump_table || lookup {
+		let instead = format!("; --format {} has an entry for each of them", format);
+		enumerate::check(nbits, max_enumerate(args)?, &instead).map_err(too_many)?;
+	}
+	if lookup && nout > lut::MAX_OUTPUTS {
+		fail!(Usage, "--format rust-const handles at most {} outputs; give --macro or \
+		              fewer --only.", lut::MAX_OUTPUTS);
+	}
+	Ok(())
+}
+
+// --emit-tests: the framework to write the test in and how many unlisted
+// inputs to add to its cases, or None without it.
+fn test_harness(args: &docopt::ArgvMap) ->
+	Result<Option<(harness::Framework, usize)>, Failure> {
+	let framework = match args.get_str("--emit-tests") {
+		"" => return Ok(None),
+		s => harness::Framework::parse(s).map_err(Failure::Usage)?,
+	};
+	let sample = match args.get_str("--tests-sample") {
+		"" => 0,
+		s => match s.parse::<usize>() {
+			Ok(n) => n,
+			Err(_) => fail!(Usage, "Invalid --tests-sample '{}'; expected a non-negative \
+			                        integer.", s),
+		},
+	};
+	Ok(Some((framework, sample)))
+}
+
+fn min_reuse(args: &docopt::ArgvMap) -> Result<usize, Failure> {
+	match args.get_str("--min-reuse").parse::<usize>() {
+		Ok(n) if n > 0 => Ok(n),
+		_ => fail!(Usage, "Invalid --min-reuse '{}'; expected a positive integer.",
+		           args.get_str("--min-reuse")),
+	}
+}
+
+// The products at least 'min_reuse' of the equations have, named for
+// --share-terms.
+fn shared_products(args: &docopt::ArgvMap, eqns: &[Equation], invars: &[String],
+                   outvars: &[&str], min_reuse: usize) ->
+	Result<Vec<emit::Shared>, Failure> {
+	let prefix = args.get_str("--share-prefix");
+	let products = emit::shared_products(eqns, prefix, min_reuse);
+	let taken: Vec<&str> = invars.iter().map(|s| s.as_str())
+		.chain(outvars.iter().cloned()).collect();
+	if let Some(p) = products.iter().find(|p| taken.contains(&p.name.as_str())) {
+		fail!(Usage, "The shared product name '{}' is already a variable; choose \
+		              another --share-prefix.", p.name);
+	}
+	Ok(products)
+}
+
+// The equations as --reuse-outputs rewrites and orders them, with whether
+// each was proven minimal.
+fn reuse_outputs(eqns: Vec<Equation>, minimal: Vec<bool>) -> (Vec<Equation>, Vec<bool>) {
+	let reused = emit::reuse_outputs(&eqns);
+	let minimal = reused.iter()
+		.map(|r| minimal[eqns.iter().position(|e| e.index() == r.index()).unwrap()])
+		.collect();
+	(reused, minimal)
+}
+
+// The equations with --use-implications combining each with the output it is
+// computed from, and reordered as --reuse-outputs reorders them; each is
+// checked against the rows, the output it uses taking the table's value.
+fn use_implications(ld: &Loaded, eqns: Vec<Equation>, minimal: Vec<bool>,
+                    used: &[Option<implication::Implication>], nbits: usize) ->
+	Result<(Vec<Equation>, Vec<bool>), Failure> {
+	let composed = implication::compose(&eqns, used, nbits);
+	let rows = completed(ld);
+	for eqn in composed.iter() {
+		let i = match used[eqn.index()] {
+			Some(i) => i,
+			None => continue,
+		};
+		let differs = |e: &&Entry| {
+			let mut values = e.input.clone();
+			values.push(e.output[i.from]);
+			eqn.evaluate(&values) != e.output[eqn.index()]
+		};
+		if let Some(ent) = rows.rows().iter().find(differs) {
+			fail!(Verification, "{} computed from {} differs from the table{}.",
+			      eqn.varname(), ld.outvars[i.from], sources_note(&ent.sources));
+		}
+	}
+	let minimal = composed.iter()
+		.map(|c| minimal[eqns.iter().position(|e| e.index() == c.index()).unwrap()])
+		.collect();
+	Ok((composed, minimal))
+}
+
+// The code of the --format writing the equations alone; None for text and
+// python --dict-lookup, which need more than the equations.
+#[allow(clippy::too_many_arguments)]
+fn generated(args: &docopt::ArgvMap, format: &str, eqns: &[Equation],
+             invars: &[String], unlisted: &emit::Unlisted, products: &[emit::Shared],
+             order: bits::BitOrder, notation: &Notation) -> Option<String> {
+	Some(match format {
+		"verilog-casez" => hdl::verilog_casez(eqns, invars, unlisted, order),
+		"vhdl" => hdl::vhdl_shared(eqns, invars, unlisted, products),
+		"switch-c" => switch::switch_c(eqns, invars, unlisted, order,
+		                               args.get_bool("--compress")),
+		"switch-rust" => switch::switch_rust(eqns, invars, unlisted, order,
+		                                     args.get_bool("--compress")),
+		"if-chain-c" => chain::if_chain_c(eqns, invars),
+		"share-dot" => share::dot(eqns, args.get_bool("--collapse-unshared"), notation),
+		"rust-const" if args.get_bool("--macro") =>
+			lut::rust_macro(eqns, invars, unlisted),
+		"rust-const" => lut::rust_const(eqns, invars, unlisted, order),
+		"python" if !args.get_bool("--dict-lookup") =>
+			python::function_shared(eqns, invars, unlisted, args.get_bool("--return-dict"),
+			                        products),
+		_ => return None,
+	})
+}
+
+// --format if-chain-c: fails unless the covers are never 1 together and give
+// unlisted inputs what the code will, and notes the literals the chain saves.
+fn exclusive_outputs(eqns: &[Equation], invars: &[String], unlisted: &emit::Unlisted,
+                     notation: &Notation, note: &dyn Fn(String)) -> Result<(), Failure> {
+	if unlisted.branch().is_some() {
+		fail!(Usage, "--format if-chain-c has no default branch; give --default-output \
+		              zero or dc.");
+	}
+	if let Some((i, j, cube)) = chain::overlap(eqns, invars.len()) {
+		let term = exact::cube_to_term(&cube, invars);
+		let inputs = if term.is_universal() { "every input".to_string() }
+		             else { notation.term(&term) };
+		fail!(Usage, "--format if-chain-c needs outputs of which at most one is 1 for any \
+		              input, but {} and {} are both 1 for {}.", eqns[i].varname(),
+		      eqns[j].varname(), inputs);
+	}
+	let chain = chain::chain(eqns, invars.len());
+	let names: Vec<&str> = eqns.iter().map(|e| e.varname()).collect();
+	note(format!("Outputs {} are mutually exclusive; the chain tests {} literals where \
+	              separate ifs would test {}.", names.join(", "), chain.chained,
+	             chain.separate));
+	Ok(())
+}
+
+fn write_cover(cov: &cover::Cover, file: &str) -> Result<(), Failure> {
+	let text = cover::write(cov).map_err(Failure::Usage)?;
+	match File::create(file).and_then(|mut fp| fp.write_all(text.as_bytes())) {
+		Ok(()) => Ok(()),
+		Err(e) => fail!(Usage, "Cannot create {}: {}", file, e),
+	}
+}
+
+// minimize --from-cover: writes the equations of a cover file as --format
+// says, once --verify-table, if given, has checked them.
+fn from_cover(args: &docopt::ArgvMap, note: &dyn Fn(String)) -> Result<(), Failure> {
+	let file = args.get_str("--from-cover");
+	let cov = match std::fs::read_to_string(file) {
+		Ok(text) => cover::read(&text)
+			.map_err(|e| Failure::Usage(format!("{}: {}", file, e)))?,
+		Err(e) => fail!(Usage, "Cannot open {}: {}", file, e),
+	};
+	let format = choice(args, "--format", &["text", "verilog-casez", "vhdl", "python",
+	                                        "switch-c", "switch-rust", "if-chain-c",
+	                                        "rust-const", "share-dot"])?;
+	// what needs the table, or is decided before the equations are.
+	let unsupported = [("--emit-cover", !args.get_str("--emit-cover").is_empty()),
+	                   ("--emit-tests", !args.get_str("--emit-tests").is_empty()),
+	                   ("--only", !args.get_vec("--only").is_empty()),
+	                   ("--skip", !args.get_vec("--skip").is_empty()),
+	                   ("--group", !args.get_vec("--group").is_empty()),
+	                   ("--form anf", args.get_str("--form") == "anf"),
+	                   ("--onehot-encode", args.get_bool("--onehot-encode")),
+	                   ("--improve", !args.get_str("--improve").is_empty()),
+	                   ("--freq-col", !args.get_str("--freq-col").is_empty()),
+	                   ("--hazard-free", args.get_bool("--hazard-free")),
+	                   ("--dict-lookup", args.get_bool("--dict-lookup")),
+	                   ("--explain", args.get_bool("--explain")),
+	                   ("--watch", args.get_bool("--watch"))];
+	for &(opt, given) in unsupported.iter() {
+		if given {
+			fail!(Usage, "--from-cover can't be combined with {}.", opt);
+		}
+	}
+	check_generator_options(args, format, cov.invars.len(), cov.eqns.len())?;
+	let share_terms = args.get_bool("--share-terms");
+	let min_reuse = min_reuse(args)?;
+	let order = bit_order(args)?;
+	let verify_table = args.get_str("--verify-table");
+	if !verify_table.is_empty() {
+		let (inenc, outenc) = encodings(args)?;
+		let tbl = read_table(args, verify_table, cov.invars.len(), cov.outvars.len(),
+		                     inenc, outenc)?;
+		if let Err(e) = cover::verify(&cov, &tbl) {
+			fail!(Verification, "The equations of {} don't reproduce {}:\n{}", file,
+			      verify_table, e);
+		}
+		note(format!("The equations of {} reproduce {}.", file, verify_table));
+	}
+	let outvars: Vec<&str> = cov.outvars.iter().map(|s| s.as_str()).collect();
+	let (eqns, minimal) = if args.get_bool("--reuse-outputs") {
+		reuse_outputs(cov.eqns.clone(), cov.minimal.clone())
+	} else {
+		(cov.eqns.clone(), cov.minimal.clone())
+	};
+	let products = if share_terms {
+		shared_products(args, &eqns, &cov.invars, &outvars, min_reuse)?
+	} else {
+		vec![]
+	};
+	let notation = notation(args, &cov.invars)?;
+	if format == "if-chain-c" {
+		exclusive_outputs(&eqns, &cov.invars, &cov.unlisted, &notation, note)?;
+	}
+	match generated(args, format, &eqns, &cov.invars, &cov.unlisted, &products, order,
+	                &notation) {
+		Some(code) => print!("{}", code),
+		None if share_terms =>
+			print!("{}", emit::text(&eqns, &products, &cov.invars, &notation)),
+		None => for (eqn, &m) in eqns.iter().zip(minimal.iter()) {
+			let proven = if m { "" } else { " (not proven minimal)" };
+			println!("{}{}", notation.equation(eqn), proven);
+		},
+	}
+	if !minimal.iter().all(|&m| m) {
+		return Err(Failure::Unproven);
+	}
+	Ok(())
 }
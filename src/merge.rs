@@ -0,0 +1,219 @@
+// Combining truth tables that were split across several files.  Each file has
+// the same input columns but its own output columns; the merged table has all
+// outputs side by side, so the shared-term minimizer can work across them.
+//
+// Files need not list their rows in the same order: rows are matched up by
+// their input pattern.
+use std::collections::HashMap;
+use super::{Truth, describe_sources};
+
+// A parsed table along with where it came from, for error messages.
+pub struct Source<'a> {
+	pub file: &'a str,
+	pub truth: Truth,
+}
+
+impl<'a> Source<'a> {
+	// Where the row was read from: the lines its entry records, or for a
+	// table that wasn't read from text, its position in the table.
+	fn at(&self, row: usize) -> String {
+		match self.truth.table[row].sources.as_slice() {
+			&[] => format!("{} row {}", self.file, row + 1),
+			sources => describe_sources(sources),
+		}
+	}
+}
+
+fn pattern(bits: &[bool]) -> String {
+	bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+// Indexes a table's rows by input pattern.  A pattern may only be given once
+// with any particular outputs; giving it twice with different outputs is a
+// conflict.
+fn index(src: &Source) -> Result<HashMap<Vec<bool>, usize>, String> {
+	let mut rv: HashMap<Vec<bool>, usize> = HashMap::new();
+	for (row, ent) in src.truth.table.iter().enumerate() {
+		if let Some(&prev) = rv.get(&ent.input) {
+			if src.truth.table[prev] != *ent {
+				return Err(format!("{} and {} give different outputs for input {}",
+				                   src.at(prev), src.at(row), pattern(&ent.input)));
+			}
+			continue;
+		}
+		rv.insert(ent.input.clone(), row);
+	}
+	Ok(rv)
+}
+
+// Merges the tables into one whose rows are those of the first table, and
+// whose outputs are the outputs of every table in order.  Each merged row
+// keeps the sources of the rows it was made from.  Every input
+// pattern must appear in every table.
+pub fn merge(sources: &[Source]) -> Result<Truth, String> {
+	assert!(!sources.is_empty());
+	let first = &sources[0];
+	let indices = sources.iter().map(index).collect::<Result<Vec<_>, _>>()?;
+
+	// an input that is a don't-care in any file is one in the merged table,
+	// whatever the other files give for it.
+	let mut rv = Truth::default();
+	for src in sources.iter() {
+		for inp in src.truth.dc.iter() {
+			if !rv.dc.contains(inp) {
+				rv.dc.push(inp.clone());
+			}
+		}
+	}
+	for (row, ent) in first.truth.table.iter().enumerate() {
+		if rv.dc.contains(&ent.input) {
+			continue;
+		}
+		let mut merged = ent.clone();
+		for (src, idx) in sources.iter().zip(indices.iter()).skip(1) {
+			match idx.get(&ent.input) {
+				None => return Err(format!("input {} ({}) has no row in {}",
+				                           pattern(&ent.input), first.at(row), src.file)),
+				Some(&r) => {
+					let other = &src.truth.table[r];
+					let offset = merged.output.len();
+					merged.dc.extend(other.dc.iter().map(|&o| o + offset));
+					merged.output.extend(other.output.iter());
+					merged.sources.extend(other.sources.iter().cloned());
+				},
+			}
+		}
+		rv.table.push(merged);
+	}
+	// the reverse direction: rows that only exist in a later file.
+	for src in sources.iter().skip(1) {
+		for (row, ent) in src.truth.table.iter().enumerate() {
+			if !indices[0].contains_key(&ent.input) && !rv.dc.contains(&ent.input) {
+				return Err(format!("input {} ({}) has no row in {}",
+				                   pattern(&ent.input), src.at(row), first.file));
+			}
+		}
+	}
+	Ok(rv)
+}
+
+// Splits the --ovar names among the --table files: each file gets the --ovar
+// options that follow it on the command line, up to the next --table.  Names
+// given before the first --table belong to the first file.
+pub fn ovar_groups(argv: &[String]) -> Vec<Vec<String>> {
+	option_groups(argv, "--ovar")
+}
+
+// As ovar_groups, for the values of any option taking one.
+pub fn option_groups(argv: &[String], opt: &str) -> Vec<Vec<String>> {
+	let joined = format!("{}=", opt);
+	let mut leading: Vec<String> = vec![];
+	let mut groups: Vec<Vec<String>> = vec![];
+	let mut iter = argv.iter().skip(1);
+	while let Some(arg) = iter.next() {
+		let name = if arg == "--table" {
+			groups.push(vec![]);
+			iter.next();
+			continue;
+		} else if arg == opt {
+			match iter.next() {
+				Some(v) => v.clone(),
+				None => break,
+			}
+		} else if arg.starts_with(&joined) {
+			arg[joined.len()..].to_string()
+		} else {
+			continue;
+		};
+		match groups.last_mut() {
+			None => leading.push(name),
+			Some(g) => g.push(name),
+		}
+	}
+	if groups.is_empty() {
+		groups.push(vec![]);
+	}
+	leading.append(&mut groups[0]);
+	groups[0] = leading;
+	groups
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	fn args(s: &str) -> Vec<String> {
+		s.split_whitespace().map(|a| a.to_string()).collect()
+	}
+
+	#[test]
+	fn groups() {
+		let g = ovar_groups(&args("minterm --table a.csv --ivar i --ovar x \
+		                           --table b.csv --ovar=y --ovar z"));
+		assert_eq!(g, vec![vec!["x"], vec!["y", "z"]]);
+		let g = ovar_groups(&args("minterm --ovar x --table a.csv --ovar y"));
+		assert_eq!(g, vec![vec!["x", "y"]]);
+		let g = ovar_groups(&args("minterm --ovar x --table a.csv --table b.csv \
+		                           --ovar y"));
+		assert_eq!(g, vec![vec!["x"], vec!["y"]]);
+		let g = option_groups(&args("minterm compose --table a.csv --ivar i --ovar x \
+		                             --table b.csv --ivar=x --ivar j"), "--ivar");
+		assert_eq!(g, vec![vec!["i"], vec!["x", "j"]]);
+	}
+
+	fn source<'a>(file: &'a str, csv: &str, nout: usize) -> Source<'a> {
+		let mut truth = parse(csv.as_bytes(), 1, 2, nout);
+		truth.name_sources(file);
+		Source{file, truth}
+	}
+
+	#[test]
+	fn reordered_rows() {
+		let a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n1,0,0\n1,1,1\n", 1);
+		let b = source("b.csv", "a,b,y,z\n1,1,0,1\n0,0,1,1\n1,0,1,0\n0,1,0,0\n", 2);
+		let m = merge(&[a, b]).unwrap();
+		assert_eq!(m.solution(vec![false, false]), vec![true, true, true]);
+		assert_eq!(m.solution(vec![false, true]), vec![false, false, false]);
+		assert_eq!(m.solution(vec![true, false]), vec![false, true, false]);
+		assert_eq!(m.solution(vec![true, true]), vec![true, false, true]);
+		assert_eq!(m.len(), 4);
+	}
+
+	#[test]
+	fn missing_rows() {
+		let a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n1,0,0\n", 1);
+		let b = source("b.csv", "a,b,y\n0,0,1\n0,1,0\n1,1,1\n", 1);
+		let err = merge(&[a, b]).err().unwrap();
+		assert_eq!(err, "input 10 (a.csv line 4) has no row in b.csv");
+		let a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n", 1);
+		let b = source("b.csv", "a,b,y\n0,0,1\n0,1,0\n1,1,1\n", 1);
+		let err = merge(&[a, b]).err().unwrap();
+		assert_eq!(err, "input 11 (b.csv line 4) has no row in a.csv");
+		// unless it is a don't-care in one of them.
+		let mut a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n", 1);
+		a.truth.dc.push(vec![true, true]);
+		let b = source("b.csv", "a,b,y\n0,0,1\n0,1,0\n1,1,1\n", 1);
+		let m = merge(&[a, b]).unwrap();
+		assert_eq!((m.len(), m.dc.clone()), (2, vec![vec![true, true]]));
+	}
+
+	#[test]
+	fn conflicting_rows() {
+		let a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n", 1);
+		let b = source("b.csv", "a,b,y\n0,0,1\n0,1,0\n0,0,0\n", 1);
+		let err = merge(&[a, b]).err().unwrap();
+		assert_eq!(err, "b.csv line 2 and b.csv line 4 give different outputs for \
+		                 input 00");
+	}
+
+	#[test]
+	fn merged_sources() {
+		let a = source("a.csv", "a,b,x\n0,0,1\n0,1,0\n", 1);
+		let b = source("b.csv", "a,b,y\n0,1,1\n0,0,0\n", 1);
+		let m = merge(&[a, b]).unwrap();
+		let at: Vec<String> = m.table.iter().map(|e| describe_sources(&e.sources))
+			.collect();
+		assert_eq!(at, vec!["a.csv line 2, b.csv line 3", "a.csv line 3, b.csv line 2"]);
+	}
+}
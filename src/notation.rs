@@ -0,0 +1,214 @@
+// How the text output spells a product: --neg-style for a negated input and
+// --and-style for the conjunction of several.
+//
+//     prime     a'        concat  ab
+//     bang      !a        dot     a·b
+//     overline  a̅         amp     a & b
+//     not       NOT a     word    a AND b
+//
+// Writing products by concatenation only reads right while every input is
+// named by one character, so unless --and-style is given, names any longer
+// switch it to amp.  A name that the notation would misread is written in
+// double quotes, with any '"' or '\' in it escaped by a '\': one containing
+// white space or any of '"+=;()', and besides
+//
+//     prime     a '           concat  more than one character, in a
+//     bang      a '!'                 product of several literals
+//     overline  a U+0305      dot     a '·'
+//     not       NOT itself    amp     a '&'
+//                             word    AND itself
+//
+// NOT and AND in any case; an overline goes over the name inside the quotes.
+// Has'GL negated is "Has'GL"' with prime, and !Has'GL with bang.
+use super::{Equation, Term};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Negation {
+	Prime,
+	Bang,
+	Overline,
+	Not,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Conjunction {
+	Concat,
+	Dot,
+	Amp,
+	Word,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Notation {
+	pub neg: Negation,
+	pub and: Conjunction,
+}
+
+impl Negation {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"prime" => Ok(Negation::Prime),
+			"bang" => Ok(Negation::Bang),
+			"overline-unicode" => Ok(Negation::Overline),
+			"not" => Ok(Negation::Not),
+			_ => Err(format!("Invalid --neg-style '{}'; expected 'prime', 'bang', \
+			                  'overline-unicode' or 'not'.", s)),
+		}
+	}
+}
+
+impl Conjunction {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"concat" => Ok(Conjunction::Concat),
+			"dot" => Ok(Conjunction::Dot),
+			"amp" => Ok(Conjunction::Amp),
+			"word" => Ok(Conjunction::Word),
+			_ => Err(format!("Invalid --and-style '{}'; expected 'concat', 'dot', 'amp' \
+			                  or 'word'.", s)),
+		}
+	}
+}
+
+// U+0305, drawn over the character before it.
+const OVERLINE: char = '\u{305}';
+
+impl Notation {
+	// The notation of Term's and Equation's Display: a'b.
+	pub fn classic() -> Self {
+		Notation{neg: Negation::Prime, and: Conjunction::Concat}
+	}
+
+	// The notation for inputs named 'names', each style as given or, for one
+	// that isn't, prime and concat, unless a name is too long to concatenate.
+	pub fn new(names: &[String], neg: Option<Negation>, and: Option<Conjunction>) ->
+		Self {
+		let long = names.iter().any(|n| n.chars().count() > 1);
+		let and = and.unwrap_or(if long { Conjunction::Amp } else { Conjunction::Concat });
+		Notation{neg: neg.unwrap_or(Negation::Prime), and}
+	}
+
+	// Whether the name has to be quoted to be read back as one name, in a
+	// product of several literals when 'joined'.
+	fn ambiguous(&self, name: &str, joined: bool) -> bool {
+		let special = |c: char| c.is_whitespace() || "\"+=;()".contains(c) ||
+			match self.neg {
+				Negation::Prime => c == '\'',
+				Negation::Bang => c == '!',
+				Negation::Overline => c == OVERLINE,
+				Negation::Not => false,
+			} || match self.and {
+				Conjunction::Dot => c == '·',
+				Conjunction::Amp => c == '&',
+				_ => false,
+			};
+		name.is_empty() || name.chars().any(special) ||
+			self.neg == Negation::Not && name.eq_ignore_ascii_case("not") ||
+			self.and == Conjunction::Word && name.eq_ignore_ascii_case("and") ||
+			joined && self.and == Conjunction::Concat && name.chars().count() > 1
+	}
+
+	// The name as the notation writes it in a product of several literals.
+	pub fn name(&self, name: &str) -> String {
+		self.quote(name, true, false)
+	}
+
+	// The name, each character overlined when 'lined', quoted if need be.
+	fn quote(&self, name: &str, joined: bool, lined: bool) -> String {
+		let quoted = self.ambiguous(name, joined);
+		let mut rv = if quoted { "\"".to_string() } else { String::new() };
+		for c in name.chars() {
+			if quoted && (c == '"' || c == '\\') {
+				rv.push('\\');
+			}
+			rv.push(c);
+			if lined {
+				rv.push(OVERLINE);
+			}
+		}
+		if quoted {
+			rv.push('"');
+		}
+		rv
+	}
+
+	// The input 'name', negated unless 'value', as a product of several
+	// literals has it.
+	pub fn literal(&self, name: &str, value: bool) -> String {
+		self.joined_literal(name, value, true)
+	}
+
+	fn joined_literal(&self, name: &str, value: bool, joined: bool) -> String {
+		let lined = !value && self.neg == Negation::Overline;
+		let name = self.quote(name, joined, lined);
+		match self.neg {
+			_ if value => name,
+			Negation::Prime => format!("{}'", name),
+			Negation::Bang => format!("!{}", name),
+			Negation::Overline => name,
+			Negation::Not => format!("NOT {}", name),
+		}
+	}
+
+	// The product, 1 when it has no literals.
+	pub fn term(&self, t: &Term) -> String {
+		if t.is_universal() {
+			return "1".to_string();
+		}
+		let joined = t.bits.len() > 1;
+		let lits: Vec<String> = t.bits.iter()
+			.map(|&(v, on)| self.joined_literal(&t.names[v], on, joined)).collect();
+		lits.join(match self.and {
+			Conjunction::Concat => "",
+			Conjunction::Dot => "·",
+			Conjunction::Amp => " & ",
+			Conjunction::Word => " AND ",
+		})
+	}
+
+	// The equation as Display writes it, "x = a'b + c + ;", in the notation.
+	pub fn equation(&self, eqn: &Equation) -> String {
+		let mut s = format!("{} = ", eqn.varname);
+		for t in eqn.terms.iter() {
+			s += &self.term(t);
+			s += " + ";
+		}
+		s + ";"
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::Term;
+
+	#[test]
+	fn styles() {
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let t = Term{bits: vec![(0, true), (1, false), (2, false)], names: names.clone()};
+		let styles = |neg, and| Notation{neg, and}.term(&t);
+		assert_eq!(Notation::classic().term(&t), t.to_string());
+		assert_eq!(styles(Negation::Bang, Conjunction::Amp), "a & !b & !c");
+		assert_eq!(styles(Negation::Overline, Conjunction::Dot), "a·b\u{305}·c\u{305}");
+		assert_eq!(styles(Negation::Not, Conjunction::Word), "a AND NOT b AND NOT c");
+		assert_eq!(Notation::new(&names, None, None), Notation::classic());
+		// a long name, or one with an apostrophe, is quoted where it would
+		// misread.
+		let names: Vec<String> = ["Has'GL", "EGL", "not"].iter().map(|s| s.to_string())
+			.collect();
+		let t = Term{bits: vec![(0, false), (1, true), (2, false)], names: names.clone()};
+		let auto = Notation::new(&names, None, None);
+		assert_eq!(auto, Notation{neg: Negation::Prime, and: Conjunction::Amp});
+		assert_eq!(auto.term(&t), "\"Has'GL\"' & EGL & not'");
+		let concat = Notation::new(&names, None, Some(Conjunction::Concat));
+		assert_eq!(concat.term(&t), "\"Has'GL\"'\"EGL\"\"not\"'");
+		// alone, a literal has nothing to run into.
+		let egl = Term{bits: vec![(1, false)], names: names.clone()};
+		assert_eq!(concat.term(&egl), "EGL'");
+		let word = Notation::new(&names, Some(Negation::Not), Some(Conjunction::Word));
+		assert_eq!(word.term(&t), "NOT Has'GL AND EGL AND NOT \"not\"");
+		assert_eq!(Notation::classic().name("a\"b"), "\"a\\\"b\"");
+		assert!(Negation::parse("tilde").is_err());
+		assert_eq!(Conjunction::parse("word"), Ok(Conjunction::Word));
+	}
+}
@@ -0,0 +1,146 @@
+// Observability: an output that is only used while some condition holds, such
+// as data bits that only matter when an enable output is set.  "mode when
+// enable" makes every row on which enable is 0 a don't-care for mode, which
+// the minimizers are then free to cover or not.
+//
+// Conditions are expressions over the inputs followed by the outputs, as for
+// invariants; the outputs they use take their values from the table.
+use std::collections::HashSet;
+use super::{Entry, Equation, Term, Truth};
+use super::expr::Expr;
+
+// An --observe declaration: 'output' matters only on rows where 'when' holds.
+#[derive(Clone, Debug)]
+pub struct Observe {
+	pub output: usize,
+	pub when: Expr,
+}
+
+impl Observe {
+	// Whether the output is used on the row.
+	pub fn observed(&self, e: &Entry) -> bool {
+		let mut values = e.input.clone();
+		values.extend(e.output.iter().cloned());
+		self.when.evaluate(&values)
+	}
+}
+
+// Parses "<ovar> when <expr>".
+pub fn parse(s: &str, invars: &[String], outvars: &[String]) -> Result<Observe, String> {
+	let (name, cond) = match s.find(" when ") {
+		Some(at) => (s[..at].trim(), &s[at + " when ".len()..]),
+		None => return Err(format!("Invalid --observe '{}'; expected '<ovar> when \
+		                            <expr>'.", s)),
+	};
+	let output = match outvars.iter().position(|n| n == name) {
+		Some(o) => o,
+		None => return Err(format!("'{}' in --observe '{}' is not an --ovar.", name, s)),
+	};
+	let mut names = invars.to_vec();
+	names.extend(outvars.iter().cloned());
+	let when = Expr::parse(cond, &names)
+		.map_err(|e| format!("Invalid --observe '{}' {}", s, e))?;
+	if when.variables().contains(&(invars.len() + output)) {
+		return Err(format!("The condition of --observe '{}' can't depend on {} itself.",
+		                   s, name));
+	}
+	Ok(Observe{output, when})
+}
+
+// The rows of the table on which the output is not used: those where any of
+// its declarations' conditions fails.
+pub fn unobserved<'a>(tbl: &'a Truth, output: usize, observe: &[Observe]) ->
+	Vec<&'a Entry> {
+	let mine: Vec<&Observe> = observe.iter().filter(|o| o.output == output).collect();
+	tbl.table.iter().filter(|e| mine.iter().any(|o| !o.observed(e))).collect()
+}
+
+// Makes the rows where the equation's output isn't used don't-cares, taking
+// their minterms out of its terms.  Returns how many rows that relaxed.
+pub fn relax(eqn: &mut Equation, tbl: &Truth, observe: &[Observe]) -> usize {
+	let rows = unobserved(tbl, eqn.index, observe);
+	let names = eqn.terms.first().or_else(|| eqn.dc.first()).map(|t| t.names.clone())
+		.unwrap_or_default();
+	let relaxed: HashSet<Term> = rows.iter().map(|e| {
+		let mut term = Term::compute(&e.input);
+		term.names = names.clone();
+		term
+	}).collect();
+	eqn.terms.retain(|t| !relaxed.contains(t));
+	eqn.dc.extend(relaxed);
+	rows.len()
+}
+
+// The table with only the rows where the output is used, to check equations
+// for it against.
+pub fn observed_table(tbl: &Truth, output: usize, observe: &[Observe]) -> Truth {
+	let table = tbl.table.iter().filter(|e| {
+		observe.iter().all(|o| o.output != output || o.observed(e))
+	}).cloned().collect();
+	Truth{table, dc: tbl.dc.clone()}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, exact, parse as parse_table};
+	use super::super::testutil;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn declarations() {
+		let (invars, outvars) = (names(&["a", "b"]), names(&["en", "d"]));
+		let o = parse("d when en", &invars, &outvars).unwrap();
+		assert_eq!(o.output, 1);
+		assert!(parse("d if en", &invars, &outvars).unwrap_err().contains("expected"));
+		assert_eq!(parse("q when en", &invars, &outvars).unwrap_err(),
+		           "'q' in --observe 'q when en' is not an --ovar.");
+		assert!(parse("d when d | a", &invars, &outvars).unwrap_err()
+			.contains("can't depend on d itself"));
+		assert!(parse("d when z", &invars, &outvars).is_err());
+	}
+
+	#[test]
+	fn relaxed_cells() {
+		// d is only used when en is: of its 1s, 01 and 11 are moot.
+		let csv = "0,0,,1,0\n0,1,,0,1\n1,0,,1,1\n1,1,,0,1\n";
+		let tbl = parse_table(csv.as_bytes(), 0, 2, 2);
+		let (invars, outvars) = (names(&["a", "b"]), names(&["en", "d"]));
+		let obs = vec![parse("d when en", &invars, &outvars).unwrap()];
+		let mut eqn = equations(&tbl, vec!["en", "d"], invars.clone()).remove(1);
+		assert_eq!(relax(&mut eqn, &tbl, &obs), 2);
+		assert_eq!((eqn.terms.len(), eqn.dc.len()), (1, 2));
+		eqn.terms = exact::minimize(&eqn, 2, &mut exact::Budget::unlimited()).terms;
+		assert_eq!(eqn.to_string(), "d = a + ;");
+		// which holds on every row where d is used, though not on 01.
+		assert!(eqn.counterexamples(&observed_table(&tbl, 1, &obs)).is_empty());
+		assert_eq!(eqn.counterexamples(&tbl).len(), 1);
+	}
+
+	#[test]
+	fn gated_data() {
+		// an enable gating three data outputs, which are noise while it is 0.
+		let f = testutil::gated(5, 3, 11);
+		let (invars, outvars) = (&f.invars, &f.outvars);
+		let obs: Vec<Observe> = outvars[1..].iter()
+			.map(|o| format!("{} when {}", o, outvars[0]))
+			.map(|s| parse(&s, invars, outvars).unwrap())
+			.collect();
+		let names: Vec<&str> = outvars.iter().map(|s| s.as_str()).collect();
+		let mut before = 0;
+		let mut after = 0;
+		for mut eqn in equations(&f.truth, names, invars.clone()).into_iter().skip(1) {
+			let mut budget = exact::Budget::unlimited();
+			before += exact::minimize(&eqn, 5, &mut budget).terms.len();
+			relax(&mut eqn, &f.truth, &obs);
+			eqn.terms = exact::minimize(&eqn, 5, &mut budget).terms;
+			after += eqn.terms.len();
+			let checked = observed_table(&f.truth, eqn.index, &obs);
+			assert!(eqn.counterexamples(&checked).is_empty(), "{}", eqn);
+		}
+		assert!(after < before, "{} terms relaxed, {} before", after, before);
+	}
+}
@@ -0,0 +1,306 @@
+// One-hot input groups: several input columns of which exactly one is set,
+// together representing a single enumerated value.  Patterns with none or
+// several of a group's inputs set can't occur, so they are don't-cares.
+//
+// A group of k inputs may also be re-encoded into ceil(log2 k) binary inputs
+// before minimizing, which usually gives much smaller equations, and the
+// resulting terms decoded back into terms over the original inputs.
+use std::fmt;
+use super::{Entry, Term, Truth, describe_sources, did_you_mean};
+
+// Parses the --onehot options, each a comma separated list of input names,
+// into the indices of each group's inputs.
+pub fn parse_groups(specs: Vec<&str>, invars: &[String]) ->
+	Result<Vec<Vec<usize>>, String> {
+	let names: Vec<&str> = invars.iter().map(|s| s.as_str()).collect();
+	let mut groups: Vec<Vec<usize>> = vec![];
+	for spec in specs {
+		let mut group: Vec<usize> = vec![];
+		for name in spec.split(',').map(|n| n.trim()) {
+			let idx = match names.iter().position(|&n| n == name) {
+				Some(idx) => idx,
+				None => return Err(format!("Unknown input '{}' in --onehot.{}", name,
+				                           did_you_mean(name, &names))),
+			};
+			if group.contains(&idx) || groups.iter().any(|g| g.contains(&idx)) {
+				return Err(format!("Input '{}' is in more than one --onehot group.",
+				                   name));
+			}
+			group.push(idx);
+		}
+		if group.len() < 2 {
+			return Err(format!("--onehot {} needs at least two inputs.", spec));
+		}
+		groups.push(group);
+	}
+	Ok(groups)
+}
+
+// Whether exactly one of the group's inputs is set.
+pub fn is_onehot(inp: &[bool], group: &[usize]) -> bool {
+	group.iter().filter(|&&i| inp[i]).count() == 1
+}
+
+// Checks that every row of the table sets exactly one input of the group.
+pub fn check(tbl: &Truth, group: &[usize], invars: &[String]) -> Result<(), String> {
+	for (row, ent) in tbl.table.iter().enumerate() {
+		if !is_onehot(&ent.input, group) {
+			let names: Vec<&str> = group.iter().map(|&i| invars[i].as_str()).collect();
+			let set = group.iter().filter(|&&i| ent.input[i]).count();
+			let pattern: String = ent.input.iter().map(|&b| if b { '1' } else { '0' })
+				.collect();
+			let at = match ent.sources.len() {
+				0 => String::new(),
+				_ => format!(", {}", describe_sources(&ent.sources)),
+			};
+			return Err(format!("Row {} (input {}{}) sets {} of the one-hot inputs {}.",
+			                   row + 1, pattern, at, set, names.join(",")));
+		}
+	}
+	Ok(())
+}
+
+// Where an encoded input comes from.
+#[derive(Clone, Debug, PartialEq)]
+enum Source {
+	// an input that is not in a group, by its original index.
+	Plain(usize),
+	// bit 'bit' (0 is the least significant) of the code of group 'group'.
+	Code(usize, usize),
+}
+
+// The inputs after re-encoding each group as a binary number: member j of a
+// group gets code j.  A group's code bits take the place of its first input,
+// most significant bit first.
+pub struct Encoding {
+	groups: Vec<Vec<usize>>,
+	sources: Vec<Source>,
+	// names of the original inputs, and of the encoded ones.
+	original: Vec<String>,
+	pub names: Vec<String>,
+}
+
+// The number of bits needed for codes 0..k-1.
+fn code_bits(k: usize) -> usize {
+	let mut bits = 0;
+	while (1 << bits) < k {
+		bits += 1;
+	}
+	bits
+}
+
+impl Encoding {
+	pub fn new(invars: &[String], groups: &[Vec<usize>]) -> Self {
+		let mut sources: Vec<Source> = vec![];
+		let mut names: Vec<String> = vec![];
+		for i in 0..invars.len() {
+			match groups.iter().position(|g| g.contains(&i)) {
+				None => {
+					sources.push(Source::Plain(i));
+					names.push(invars[i].clone());
+				},
+				Some(g) if groups[g][0] == i => {
+					let last = &invars[*groups[g].last().unwrap()];
+					for bit in (0..code_bits(groups[g].len())).rev() {
+						sources.push(Source::Code(g, bit));
+						names.push(format!("{}..{}[{}]", invars[i], last, bit));
+					}
+				},
+				Some(_) => {},
+			}
+		}
+		Encoding{groups: groups.to_vec(), sources, original: invars.to_vec(), names}
+	}
+
+	// Encodes an input pattern in which every group is one-hot.
+	pub fn encode(&self, inp: &[bool]) -> Vec<bool> {
+		self.sources.iter().map(|src| match *src {
+			Source::Plain(i) => inp[i],
+			Source::Code(g, bit) => {
+				let code = self.groups[g].iter().position(|&i| inp[i]).unwrap();
+				code & (1 << bit) != 0
+			},
+		}).collect()
+	}
+
+	// Decodes an encoded input pattern; None when a code is not that of any
+	// input of its group.
+	pub fn decode(&self, enc: &[bool]) -> Option<Vec<bool>> {
+		let mut rv = vec![false; self.original.len()];
+		let mut codes = vec![0; self.groups.len()];
+		for (src, &b) in self.sources.iter().zip(enc.iter()) {
+			match *src {
+				Source::Plain(i) => rv[i] = b,
+				Source::Code(g, bit) => if b { codes[g] |= 1 << bit },
+			}
+		}
+		for (g, &code) in codes.iter().enumerate() {
+			if code >= self.groups[g].len() {
+				return None;
+			}
+			rv[self.groups[g][code]] = true;
+		}
+		Some(rv)
+	}
+
+	pub fn encode_table(&self, tbl: &Truth) -> Truth {
+		let mut rv = Truth::default();
+		for ent in tbl.table.iter() {
+			rv.table.push(Entry{input: self.encode(&ent.input), output: ent.output.clone(),
+			                    dc: ent.dc.clone(), sources: ent.sources.clone()});
+		}
+		rv
+	}
+
+	pub fn decode_table(&self, tbl: &Truth) -> Truth {
+		let mut rv = Truth::default();
+		for ent in tbl.table.iter() {
+			let inp = self.decode(&ent.input).expect("a table row with an unused code");
+			rv.table.push(Entry{input: inp, output: ent.output.clone(), dc: ent.dc.clone(),
+			                    sources: ent.sources.clone()});
+		}
+		rv
+	}
+
+	// Translates a term over the encoded inputs into one over the original
+	// inputs.  A term's code literals select a set of a group's inputs; one
+	// input is written as that input, several as the product of the group's
+	// other inputs, negated, since exactly one input of a group is set.
+	pub fn decode_term(&self, term: &Term) -> Term {
+		let mut bits: Vec<(usize, bool)> = vec![];
+		for &(idx, val) in term.bits.iter() {
+			if let Source::Plain(i) = self.sources[idx] {
+				bits.push((i, val));
+			}
+		}
+		for (g, group) in self.groups.iter().enumerate() {
+			let selected: Vec<usize> = (0..group.len()).filter(|&code| {
+				term.bits.iter().all(|&(idx, val)| match self.sources[idx] {
+					Source::Code(cg, bit) if cg == g => (code & (1 << bit) != 0) == val,
+					_ => true,
+				})
+			}).collect();
+			if selected.len() == 1 {
+				bits.push((group[selected[0]], true));
+			} else if selected.len() < group.len() {
+				bits.extend((0..group.len()).filter(|c| !selected.contains(c))
+					.map(|c| (group[c], false)));
+			}
+		}
+		bits.sort();
+		Term{bits, names: self.original.clone()}
+	}
+}
+
+impl fmt::Display for Encoding {
+	// e.g. "G0,G1,G2 as G0..G2[1] G0..G2[0]: G0=00 G1=01 G2=10"
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (g, group) in self.groups.iter().enumerate() {
+			if g > 0 {
+				writeln!(f)?;
+			}
+			let members: Vec<&str> = group.iter().map(|&i| self.original[i].as_str())
+				.collect();
+			let bits: Vec<&str> = self.sources.iter().zip(self.names.iter())
+				.filter(|&(src, _)| match *src { Source::Code(cg, _) => cg == g, _ => false })
+				.map(|(_, n)| n.as_str()).collect();
+			write!(f, "{} as {}:", members.join(","), bits.join(" "))?;
+			let width = bits.len();
+			for (code, name) in members.iter().enumerate() {
+				let pattern: String = (0..width).rev()
+					.map(|b| if code & (1 << b) != 0 { '1' } else { '0' }).collect();
+				write!(f, " {}={}", name, pattern)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse, exact};
+	use super::super::bits::{BitOrder, bit_unpack};
+
+	fn names() -> Vec<String> {
+		["s", "G0", "G1", "G2"].iter().map(|n| n.to_string()).collect()
+	}
+
+	// y = s when G0, 1 when G1, 0 when G2.
+	fn table() -> Truth {
+		let s = "0,1,0,0,,0\n0,0,1,0,,1\n0,0,0,1,,0\n\
+		         1,1,0,0,,1\n1,0,1,0,,1\n1,0,0,1,,0\n";
+		parse(s.as_bytes(), 0, 4, 1)
+	}
+
+	#[test]
+	fn groups() {
+		assert_eq!(parse_groups(vec!["G0,G1,G2"], &names()), Ok(vec![vec![1, 2, 3]]));
+		assert!(parse_groups(vec!["G0,G9"], &names()).is_err());
+		assert!(parse_groups(vec!["G0"], &names()).is_err());
+		assert!(parse_groups(vec!["G0,G1", "G1,G2"], &names()).is_err());
+	}
+
+	#[test]
+	fn validation() {
+		let mut tbl = table();
+		assert_eq!(check(&tbl, &[1, 2, 3], &names()), Ok(()));
+		tbl.table[4].input[1] = true;
+		assert_eq!(check(&tbl, &[1, 2, 3], &names()),
+		           Err("Row 5 (input 1110, line 5) sets 2 of the one-hot inputs G0,G1,G2."
+		               .to_string()));
+	}
+
+	#[test]
+	fn dont_cares() {
+		let tbl = table();
+		let eqns = equations(&tbl, vec!["y"], names());
+		let mut eqn = eqns[0].clone();
+		for i in 0..16u64 {
+			let inp = bit_unpack(i, 4, BitOrder::Msb);
+			if !is_onehot(&inp, &[1, 2, 3]) {
+				let mut t = Term::compute(&inp);
+				t.names = names();
+				eqn.dc.push(t);
+			}
+		}
+		let mut budget = exact::Budget::unlimited();
+		let plain = exact::minimize(&eqns[0], 4, &mut budget).terms;
+		let free = exact::minimize(&eqn, 4, &mut budget).terms;
+		let lits = |ts: &Vec<Term>| ts.iter().map(|t| t.len()).sum::<usize>();
+		assert!(lits(&free) < lits(&plain));
+		let strs: Vec<String> = free.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["G1", "sG2'"]);
+	}
+
+	#[test]
+	fn reencoded() {
+		let tbl = table();
+		let enc = Encoding::new(&names(), &[vec![1, 2, 3]]);
+		assert_eq!(enc.names, vec!["s", "G0..G2[1]", "G0..G2[0]"]);
+		assert_eq!(format!("{}", enc), "G0,G1,G2 as G0..G2[1] G0..G2[0]: G0=00 G1=01 \
+		                                G2=10");
+		let coded = enc.encode_table(&tbl);
+		assert_eq!(coded.table[1].input, vec![false, false, true]);
+		assert_eq!(enc.decode(&[true, true, true]), None);
+		assert_eq!(enc.decode_table(&coded).table, tbl.table);
+
+		// code 11 is unused, so a don't-care.
+		let mut eqns = equations(&coded, vec!["y"], enc.names.clone());
+		let mut t = Term::compute(&vec![false, true, true]);
+		t.names = enc.names.clone();
+		eqns[0].dc.push(t.clone());
+		t.bits[0].1 = true;
+		eqns[0].dc.push(t);
+		let sol = exact::minimize(&eqns[0], 3, &mut exact::Budget::unlimited());
+		let decoded: Vec<Term> = sol.terms.iter().map(|t| enc.decode_term(t)).collect();
+		let strs: Vec<String> = decoded.iter().map(|t| format!("{}", t)).collect();
+		assert_eq!(strs, vec!["G1", "sG2'"]);
+		for ent in tbl.table.iter() {
+			assert_eq!(decoded.iter().any(|t| t.evaluate(&ent.input)), ent.output[0]);
+		}
+		// a term selecting two of the three inputs: not G2.
+		let two = Term{bits: vec![(1, false)], names: enc.names.clone()};
+		assert_eq!(format!("{}", enc.decode_term(&two)), "G2'");
+	}
+}
@@ -0,0 +1,218 @@
+// A table stored as bits: each row's inputs, its outputs, and whether each
+// output is a don't-care on the row, one bit each, packed into 64-bit words.
+// A row of Vec<bool>s costs two vectors and a byte per bit, which is far too
+// much for tables of millions of rows.
+//
+// It is for code embedding the library, which can read a table with
+// parse_packed and build its equations with equations(), as from a Truth.
+// The command line still reads tables into Truth: the rest of its pipeline
+// works on those, and a minterm of an equation, a term with its own input
+// names, costs more than a row of either, so packing the table alone
+// wouldn't let the minimizer take tables of millions of rows.
+//
+// The inputs are plain booleans throughout, so unlike a tri-state cell a bit
+// per input is enough.
+use super::{Entry, Table, Truth};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PackedTruth {
+	nin: usize,
+	nout: usize,
+	rows: usize,
+	words: Vec<u64>,
+	pub dc: Vec<Vec<bool>>,
+}
+
+impl PackedTruth {
+	pub fn new(nin: usize, nout: usize) -> Self {
+		PackedTruth{nin, nout, rows: 0, words: vec![], dc: vec![]}
+	}
+
+	pub fn from_truth(tbl: &Truth) -> Self {
+		let (nin, nout) = match tbl.table.first() {
+			Some(e) => (e.input.len(), e.output.len()),
+			None => (0, 0),
+		};
+		let mut rv = PackedTruth::new(nin, nout);
+		rv.words.reserve(tbl.len() * rv.row_words());
+		for ent in tbl.table.iter() {
+			rv.push_entry(ent);
+		}
+		rv.dc = tbl.dc.clone();
+		rv
+	}
+
+	pub fn to_truth(&self) -> Truth {
+		Truth{table: self.iter().collect(), dc: self.dc.clone()}
+	}
+
+	fn row_words(&self) -> usize {
+		(self.nin + 2 * self.nout).div_ceil(64)
+	}
+
+	fn bit(&self, row: usize, b: usize) -> bool {
+		self.words[row * self.row_words() + b / 64] >> (b % 64) & 1 == 1
+	}
+
+	pub fn push(&mut self, input: &[bool], output: &[bool]) {
+		assert_eq!((input.len(), output.len()), (self.nin, self.nout));
+		let start = self.words.len();
+		self.words.resize(start + self.row_words(), 0);
+		for (b, &on) in input.iter().chain(output.iter()).enumerate() {
+			if on {
+				self.words[start + b / 64] |= 1 << (b % 64);
+			}
+		}
+		self.rows += 1;
+	}
+
+	// As push, with the outputs the entry doesn't care about marked.
+	pub fn push_entry(&mut self, ent: &Entry) {
+		self.push(&ent.input, &ent.output);
+		let start = self.words.len() - self.row_words();
+		for &o in ent.dc.iter() {
+			let b = self.nin + self.nout + o;
+			self.words[start + b / 64] |= 1 << (b % 64);
+		}
+	}
+
+	// Releases the memory reserved for rows beyond the last.
+	pub fn shrink_to_fit(&mut self) {
+		self.words.shrink_to_fit();
+	}
+
+	pub fn len(&self) -> usize { self.rows }
+	pub fn is_empty(&self) -> bool { self.rows == 0 }
+
+	pub fn input(&self, row: usize) -> Vec<bool> {
+		(0..self.nin).map(|b| self.bit(row, b)).collect()
+	}
+
+	pub fn output(&self, row: usize, idx: usize) -> bool {
+		self.bit(row, self.nin + idx)
+	}
+
+	// Whether output 'idx' is given on the row, rather than a don't-care.
+	pub fn cares(&self, row: usize, idx: usize) -> bool {
+		!self.bit(row, self.nin + self.nout + idx)
+	}
+
+	pub fn entry(&self, row: usize) -> Entry {
+		let mut rv = Entry::new(self.input(row),
+		                        (0..self.nout).map(|o| self.output(row, o)).collect());
+		rv.dc = (0..self.nout).filter(|&o| !self.cares(row, o)).collect();
+		rv
+	}
+
+	pub fn iter<'a>(&'a self) -> impl Iterator<Item = Entry> + 'a {
+		(0..self.rows).map(move |r| self.entry(r))
+	}
+
+	// The outputs for the input pattern; panics if the table doesn't list it.
+	pub fn solution(&self, inp: Vec<bool>) -> Vec<bool> {
+		match (0..self.rows).find(|&r| (0..self.nin).all(|b| self.bit(r, b) == inp[b])) {
+			None => panic!("cannot find bit pattern {:?}", inp),
+			Some(r) => self.entry(r).output,
+		}
+	}
+
+	// The rows where output 'idx' is 1, and not a don't-care.
+	pub fn minterms<'a>(&'a self, idx: usize) -> impl Iterator<Item = Entry> + 'a {
+		(0..self.rows).filter(move |&r| self.output(r, idx) && self.cares(r, idx))
+			.map(move |r| self.entry(r))
+	}
+
+	// Bytes of memory held for the rows.
+	pub fn heap_size(&self) -> usize {
+		self.words.capacity() * 8
+	}
+}
+
+impl Table for PackedTruth {
+	fn len(&self) -> usize { self.rows }
+	fn outputs(&self) -> usize { self.nout }
+	fn minterm_inputs<'a>(&'a self, idx: usize) ->
+		Box<dyn Iterator<Item = Vec<bool>> + 'a> {
+		Box::new((0..self.rows).filter(move |&r| self.output(r, idx) && self.cares(r, idx))
+			.map(move |r| self.input(r)))
+	}
+	fn dont_care_inputs<'a>(&'a self, idx: usize) ->
+		Box<dyn Iterator<Item = Vec<bool>> + 'a> {
+		Box::new(self.dc.iter().cloned()
+			.chain((0..self.rows).filter(move |&r| !self.cares(r, idx))
+				.map(move |r| self.input(r))))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{InputEncoding, OutputEncoding, equations, parse, parse_packed};
+	use super::super::bits::{BitOrder, bit_unpack};
+
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	#[test]
+	fn same_rows() {
+		let truth = parse(SMALL.as_bytes(), 0, 3, 2);
+		let packed = parse_packed(SMALL.as_bytes(), 0, 3, 2, InputEncoding::Binary,
+		                          OutputEncoding::Binary).unwrap();
+		assert_eq!(packed, PackedTruth::from_truth(&truth));
+		assert_eq!(packed.to_truth().table, truth.table);
+		assert_eq!(packed.len(), 8);
+		assert_eq!(packed.solution(vec![false, true, false]), vec![true, true]);
+		assert_eq!(packed.minterms(1).count(), 5);
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|n| n.to_string())
+			.collect();
+		assert_eq!(equations(&packed, vec!["x", "y"], names.clone()),
+		           equations(&truth, vec!["x", "y"], names));
+	}
+
+	#[test]
+	fn wide_rows() {
+		// 70 inputs and 3 outputs span two words.
+		let mut packed = PackedTruth::new(70, 3);
+		let input: Vec<bool> = (0..70).map(|b| b % 3 == 0).collect();
+		packed.push(&input, &[true, false, true]);
+		packed.push(&[true; 70], &[false, false, true]);
+		assert_eq!(packed.input(0), input);
+		assert_eq!(packed.entry(1).output, vec![false, false, true]);
+		assert_eq!(packed.heap_size(), 2 * 2 * 8);
+	}
+
+	#[test]
+	fn dont_care_outputs() {
+		let csv = "0,0,,0,0\n0,1,,2,1\n1,0,,0,0\n1,1,,1,0\n";
+		let truth = parse(csv.as_bytes(), 0, 2, 2);
+		let packed = parse_packed(csv.as_bytes(), 0, 2, 2, InputEncoding::Binary,
+		                          OutputEncoding::Binary).unwrap();
+		assert!(packed.cares(1, 1) && !packed.cares(1, 0));
+		assert_eq!(packed.to_truth().table, truth.table);
+		let names: Vec<String> = vec!["a".to_string(), "b".to_string()];
+		assert_eq!(equations(&packed, vec!["x", "y"], names.clone()),
+		           equations(&truth, vec!["x", "y"], names));
+	}
+
+	#[test]
+	fn memory() {
+		let nin = 12;
+		let mut csv = String::new();
+		for i in 0..1u64 << nin {
+			for b in bit_unpack(i, nin, BitOrder::Msb) {
+				csv += if b { "1," } else { "0," };
+			}
+			csv += "1,0\n";
+		}
+		let truth = parse(csv.as_bytes(), 0, nin, 2);
+		let packed = parse_packed(csv.as_bytes(), 0, nin, 2, InputEncoding::Binary,
+		                          OutputEncoding::Binary).unwrap();
+		// two vectors per row, and their contents.
+		let unpacked = truth.table.capacity() * std::mem::size_of::<Entry>() +
+			truth.table.iter().map(|e| e.input.capacity() + e.output.capacity())
+			.sum::<usize>();
+		assert!(packed.heap_size() <= 8 << nin);
+		assert!(packed.heap_size() * 4 < unpacked, "{} vs {}", packed.heap_size(),
+		        unpacked);
+	}
+}
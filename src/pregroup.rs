@@ -0,0 +1,162 @@
+// --pregroup: whether the equations are minimized from the table's rows, a
+// minterm each, or from the table compressed into cubes first.  Compressing
+// merges two rows whose inputs differ in one place and whose outputs are all
+// alike, don't-cares included, level by level for as long as any merge, and
+// each output then starts from the cubes it is 1 on.  The greedy simplifier
+// covers every term it starts from with a single merged cube, so the two
+// starts can come to different covers, and either may be the cheaper; the
+// exact search expands the cubes to minterms again and finds the same
+// minimum from both.  With auto, both are minimized and each output keeps
+// the cheaper cover under the cost, once it is checked to compute what the
+// output's rows say.
+use std::collections::{BTreeMap, BTreeSet};
+use super::{Equation, Term};
+use super::exact::{self, Cube};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pregroup {
+	// the minterms of the rows, as the table lists them.
+	None,
+	// the cubes the rows compress into.
+	Rows,
+	// whichever of the two gives the cheaper cover.
+	Auto,
+}
+
+impl Pregroup {
+	pub fn parse(s: &str) -> Result<Self, String> {
+		match s {
+			"none" => Ok(Pregroup::None),
+			"rows" => Ok(Pregroup::Rows),
+			"auto" => Ok(Pregroup::Auto),
+			_ => Err(format!("Unknown --pregroup '{}'; expected 'rows', 'none' or \
+			                  'auto'.", s)),
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Pregroup::None => "none",
+			Pregroup::Rows => "rows",
+			Pregroup::Auto => "auto",
+		}
+	}
+}
+
+// The minterms of the terms.
+fn minterms(terms: &[Term], nbits: usize) -> BTreeSet<Vec<bool>> {
+	terms.iter().flat_map(|t| exact::expand_cube(&exact::term_cube(t, nbits))).collect()
+}
+
+// The equations starting from the compressed table: each input pattern some
+// equation is 1 on is keyed by what every equation is on it, 1, 0 or a
+// don't-care, and the patterns alike in that are merged into cubes.
+pub fn compress(eqns: &[Equation], nbits: usize) -> Vec<Equation> {
+	let on: Vec<BTreeSet<Vec<bool>>> = eqns.iter().map(|e| minterms(&e.terms, nbits))
+		.collect();
+	let dc: Vec<BTreeSet<Vec<bool>>> = eqns.iter().map(|e| minterms(&e.dc, nbits))
+		.collect();
+	let mut groups: BTreeMap<Vec<Option<bool>>, BTreeSet<Cube>> = BTreeMap::new();
+	for inp in on.iter().flat_map(|s| s.iter()) {
+		let key = (0..eqns.len()).map(|e| {
+			if on[e].contains(inp) { Some(true) }
+			else if dc[e].contains(inp) { None }
+			else { Some(false) }
+		}).collect();
+		groups.entry(key).or_default().insert(inp.iter().map(|&b| Some(b)).collect());
+	}
+	let merged: Vec<(Vec<Option<bool>>, BTreeSet<Cube>)> = groups.into_iter()
+		.map(|(key, cubes)| (key, merge(cubes))).collect();
+	eqns.iter().enumerate().map(|(e, eqn)| {
+		let names = match eqn.terms.first() {
+			Some(t) => t.names.clone(),
+			None => return eqn.clone(),
+		};
+		let mut rv = eqn.clone();
+		rv.terms = merged.iter().filter(|&(key, _)| key[e] == Some(true))
+			.flat_map(|(_, cubes)| cubes.iter())
+			.map(|c| exact::cube_to_term(c, &names)).collect();
+		rv
+	}).collect()
+}
+
+// The cubes with each pair differing in one literal merged, level by level,
+// a cube merging with the first other it can; the cubes cover the same
+// patterns as before, and none overlap.
+fn merge(mut cubes: BTreeSet<Cube>) -> BTreeSet<Cube> {
+	loop {
+		let mut rv: BTreeSet<Cube> = BTreeSet::new();
+		let mut used: BTreeSet<Cube> = BTreeSet::new();
+		for c in cubes.iter() {
+			if used.contains(c) {
+				continue;
+			}
+			let partner = (0..c.len()).filter(|&i| c[i] == Some(false)).map(|i| {
+				let mut p = c.clone();
+				p[i] = Some(true);
+				(i, p)
+			}).find(|(_, p)| cubes.contains(p) && !used.contains(p));
+			match partner {
+				Some((i, p)) => {
+					let mut m = c.clone();
+					m[i] = None;
+					used.insert(p);
+					rv.insert(m);
+				},
+				None => { rv.insert(c.clone()); },
+			}
+			used.insert(c.clone());
+		}
+		if rv.len() == cubes.len() {
+			return rv;
+		}
+		cubes = rv;
+	}
+}
+
+// Whether 'cover' computes what 'start' says: 1 on all its terms' patterns,
+// and only on those and its don't-cares.
+pub fn verified(cover: &Equation, start: &Equation, nbits: usize) -> bool {
+	let on = minterms(&start.terms, nbits);
+	let dc = minterms(&start.dc, nbits);
+	on.iter().all(|m| cover.evaluate(m)) && cover.terms.iter().all(|t| {
+		// a cube larger than the patterns there are can't be within them.
+		let free = nbits - t.len();
+		free < 64 && 1u64 << free <= (on.len() + dc.len()) as u64 &&
+			exact::expand_cube(&exact::term_cube(t, nbits)).iter()
+				.all(|m| on.contains(m) || dc.contains(m))
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse};
+
+	#[test]
+	fn compressed_rows() {
+		// x is 1 on 00- and 11-, y on 00- and 010: the rows 000 and 001 agree on
+		// both and merge, 110 and 111 only agree if y is alike.
+		let csv = "0,0,0,,1,1\n0,0,1,,1,1\n0,1,0,,0,1\n0,1,1,,0,0\n1,0,0,,0,0\n\
+		           1,0,1,,0,0\n1,1,0,,1,0\n1,1,1,,1,1\n";
+		let tbl = parse(csv.as_bytes(), 0, 3, 2);
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+		let eqns = equations(&tbl, vec!["x", "y"], names);
+		let grouped = compress(&eqns, 3);
+		let strs: Vec<String> = grouped.iter().map(|e| e.to_string()).collect();
+		assert_eq!(strs, vec!["x = abc' + a'b' + abc + ;", "y = a'bc' + a'b' + abc + ;"]);
+		for (g, e) in grouped.iter().zip(eqns.iter()) {
+			assert!(verified(g, e, 3));
+			let mut cover = g.clone();
+			cover.simplify();
+			assert!(verified(&cover, e, 3));
+		}
+		// covering a 0, or missing a 1, fails.
+		let mut wrong = eqns[1].clone();
+		wrong.terms.pop();
+		assert!(!verified(&wrong, &eqns[1], 3));
+		assert!(!verified(&eqns[0], &eqns[1], 3));
+		assert_eq!(Pregroup::parse("auto"), Ok(Pregroup::Auto));
+		assert!(Pregroup::parse("cubes").is_err());
+	}
+}
@@ -0,0 +1,17 @@
+// The types and functions a program embedding minterm needs, re-exported in
+// one place: 'use minterm::prelude::*' brings them in.  This is the surface
+// kept stable across releases; the modules behind it may be rearranged.
+// tests/prelude.rs pins what it exports, and the signatures; the public items
+// of the whole library are in tests/golden/public-api.txt.
+//
+// The enums that will grow are #[non_exhaustive], as are the structs a
+// minimization returns, and MinimizeOptions is set through its builder, so
+// that a new error, cost, algorithm, statistic or setting breaks no caller.
+pub use super::{Algorithm, EmptyTable, Entry, Equation, ParseError, Ran, Table, Term,
+                Truth, Variable, Warning, WarningKind};
+pub use super::bits::BitOrder;
+pub use super::cover::Cover;
+pub use super::embed::{Cost, Error, MinimizeOptions, MinimizeResult, Names, Provenance,
+                       Stats, minimize, render};
+pub use super::emit::DefaultOutput;
+pub use super::pregroup::Pregroup;
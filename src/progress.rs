@@ -0,0 +1,45 @@
+// Progress notifications from the minimizers.  Minimizing a wide table can take
+// minutes; the minimizers report what they are doing through this trait and
+// leave it to the caller to decide whether and how to show it.
+use super::exact::Cube;
+
+pub trait Progress {
+	// A new phase of the computation started, e.g. "simplify".
+	fn phase(&mut self, _name: &str) {}
+	// Work started on the output with the given index and name.
+	fn equation(&mut self, _idx: usize, _name: &str) {}
+	// The equation currently being worked on now has 'terms' terms (or
+	// cubes), after 'merges' levels of merging (or search improvements, for
+	// the exact algorithm).
+	fn update(&mut self, _terms: usize, _merges: usize) {}
+	// Cubes 'a' and 'b', differing in one input, merged into 'into'.
+	fn merged(&mut self, _a: &Cube, _b: &Cube, _into: &Cube) {}
+	// All work is done.
+	fn done(&mut self) {}
+}
+
+// Ignores all notifications.
+pub struct Silent;
+impl Progress for Silent {}
+
+#[cfg(test)]
+pub mod test {
+	use super::*;
+
+	// Records every notification, for asserting on in tests.
+	#[derive(Default)]
+	pub struct Recorder {
+		pub phases: Vec<String>,
+		pub equations: Vec<String>,
+		pub updates: Vec<(usize, usize)>,
+	}
+	impl Progress for Recorder {
+		fn phase(&mut self, name: &str) { self.phases.push(name.to_string()); }
+		fn equation(&mut self, _idx: usize, name: &str) {
+			self.equations.push(name.to_string());
+		}
+		fn update(&mut self, terms: usize, merges: usize) {
+			self.updates.push((terms, merges));
+		}
+	}
+}
@@ -0,0 +1,361 @@
+// Python bindings: the 'pyo3' feature builds a module named minterm, e.g.
+// with 'maturin develop' and the pyproject.toml at the top of the tree.  The
+// small example, minimized and checked from a notebook:
+//
+//   import minterm
+//   rows = [[0,0,0, 0,1], [0,0,1, 1,0], [0,1,0, 1,1], [0,1,1, 0,0],
+//           [1,0,0, 1,1], [1,0,1, 0,1], [1,1,0, 1,1], [1,1,1, 0,0]]
+//   t = minterm.Truth(rows, nin=3, invars=["a", "b", "c"], outvars=["x", "y"])
+//   eqns = minterm.minimize(t)
+//   assert all(t.verify(e) == [] for e in eqns), [str(e) for e in eqns]
+//
+// A Truth's rows are (inputs, outputs) pairs or, given 'nin', lists of the
+// inputs followed by the outputs, as pandas' df.values.tolist() makes them.
+// Cells are 0, 1, True or False, or None for a don't-care: an input that is
+// None stands for both values, and an output that is None may be either.
+// pyo3's macros convert each PyResult's error to itself.
+#![allow(clippy::useless_conversion)]
+use std::collections::{HashMap, HashSet};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use super::{Equation, MAX_ENUMERATED_BITS, Term, enumerate, exact, progress};
+use super::bits::{BitOrder, bit_unpack};
+use super::emit::{DefaultOutput, Syntax, sum_of_products};
+use super::formats::cube_string;
+use super::python::{PYTHON, python_name};
+use super::switch::c_name;
+
+const C: Syntax = Syntax{not: "!", and: " && ", or: " || ", zero: "false", one: "true"};
+
+// A row as given: its inputs and outputs, None where they are don't-cares.
+type Cells = (Vec<Option<bool>>, Vec<Option<bool>>);
+// A row of the table: an input pattern, and its outputs.
+type Row = (Vec<bool>, Vec<Option<bool>>);
+
+// Every input pattern the cube stands for.
+fn expand(cube: &[Option<bool>]) -> Vec<Vec<bool>> {
+	let free: Vec<usize> = (0..cube.len()).filter(|&i| cube[i].is_none()).collect();
+	(0..1u64 << free.len()).map(|k| {
+		let mut inp: Vec<bool> = cube.iter().map(|b| b.unwrap_or(false)).collect();
+		for (&i, b) in free.iter().zip(bit_unpack(k, free.len(), BitOrder::Lsb)) {
+			inp[i] = b;
+		}
+		inp
+	}).collect()
+}
+
+// The rows with their don't-care inputs expanded.  Fails on a row of the wrong
+// width, or one that gives an input other outputs than an earlier row did.
+fn rows(cells: Vec<Cells>, nin: usize, nout: usize) -> Result<Vec<Row>, String> {
+	let mut rv: Vec<Row> = vec![];
+	let mut at: HashMap<Vec<bool>, usize> = HashMap::new();
+	for (r, (inp, out)) in cells.into_iter().enumerate() {
+		if inp.len() != nin || out.len() != nout {
+			return Err(format!("Row {} has {} input(s) and {} output(s); expected {} and \
+			                    {}.", r, inp.len(), out.len(), nin, nout));
+		}
+		for input in expand(&inp) {
+			match at.get(&input) {
+				Some(&i) if rv[i].1 != out => {
+					return Err(format!("Row {} ({}) gives different outputs than an \
+					                    earlier row.", r, cube_string(&inp)));
+				},
+				Some(_) => {},
+				None => {
+					at.insert(input.clone(), rv.len());
+					rv.push((input, out.clone()));
+				},
+			}
+		}
+	}
+	Ok(rv)
+}
+
+// The equation of each output before minimizing, with the inputs the rows
+// don't list treated as the policy says.  Fails above MAX_ENUMERATED_BITS
+// inputs, as finding those goes through every pattern.
+fn equations(rows: &[Row], invars: &[String], outvars: &[String],
+             policy: &DefaultOutput) -> Result<Vec<Equation>, String> {
+	let nin = invars.len();
+	let patterns = enumerate::inputs(nin, MAX_ENUMERATED_BITS)?;
+	let listed: HashSet<&Vec<bool>> = rows.iter().map(|r| &r.0).collect();
+	let missing: Vec<Vec<bool>> = if listed.len() as u64 == 1u64 << nin {
+		vec![]
+	} else {
+		patterns.filter(|inp| !listed.contains(inp)).collect()
+	};
+	let term = |inp: &Vec<bool>| {
+		let mut term = Term::compute(inp);
+		term.names = invars.to_vec();
+		term
+	};
+	Ok(outvars.iter().enumerate().map(|(b, name)| {
+		let mut eqn = Equation{index: b, terms: vec![], dc: vec![], varname: name.clone()};
+		for (inp, out) in rows.iter() {
+			match out[b] {
+				Some(true) => eqn.terms.push(term(inp)),
+				Some(false) => {},
+				None => eqn.dc.push(term(inp)),
+			}
+		}
+		match *policy {
+			DefaultOutput::Zero => {},
+			DefaultOutput::Bits(ref bits) => if bits[b] {
+				eqn.terms.extend(missing.iter().map(&term));
+			},
+			DefaultOutput::DontCare | DefaultOutput::Panic =>
+				eqn.dc.extend(missing.iter().map(&term)),
+		}
+		eqn
+	}).collect())
+}
+
+// The inputs of the rows where the equation disagrees with the table.
+fn counterexamples(rows: &[Row], eqn: &Equation) -> Vec<Vec<bool>> {
+	rows.iter().filter(|r| match r.1[eqn.index] {
+		Some(v) => eqn.evaluate(&r.0) != v,
+		None => false,
+	}).map(|r| r.0.clone()).collect()
+}
+
+fn value_error(msg: String) -> PyErr {
+	PyValueError::new_err(msg)
+}
+
+// A cell: 0, 1, True, False or None.
+fn cell(v: &Bound<'_, PyAny>) -> PyResult<Option<bool>> {
+	if v.is_none() {
+		return Ok(None);
+	}
+	if let Ok(b) = v.extract::<bool>() {
+		return Ok(Some(b));
+	}
+	match v.extract::<i64>() {
+		Ok(0) => Ok(Some(false)),
+		Ok(1) => Ok(Some(true)),
+		_ => Err(value_error(format!("Cell {} is not 0, 1 or None.", v))),
+	}
+}
+
+fn cells(seq: &Bound<'_, PyAny>) -> PyResult<Vec<Option<bool>>> {
+	seq.iter()?.map(|v| cell(&v?)).collect()
+}
+
+fn names(prefix: &str, n: usize) -> Vec<String> {
+	(0..n).map(|i| format!("{}{}", prefix, i)).collect()
+}
+
+#[pyclass(name = "Truth", module = "minterm")]
+pub struct PyTruth {
+	#[pyo3(get)]
+	invars: Vec<String>,
+	#[pyo3(get)]
+	outvars: Vec<String>,
+	rows: Vec<Row>,
+}
+
+#[pymethods]
+impl PyTruth {
+	// Without names the inputs are i0, i1, ... and the outputs o0, o1, ...,
+	// as many as the first row has.
+	#[new]
+	#[pyo3(signature = (rows, nin=None, invars=None, outvars=None))]
+	fn new(rows: &Bound<'_, PyAny>, nin: Option<usize>, invars: Option<Vec<String>>,
+	       outvars: Option<Vec<String>>) -> PyResult<Self> {
+		let mut given: Vec<Cells> = vec![];
+		for row in rows.iter()? {
+			let row = row?;
+			given.push(match nin {
+				Some(n) => {
+					let mut inp = cells(&row)?;
+					let out = inp.split_off(n.min(inp.len()));
+					(inp, out)
+				},
+				None if row.len()? == 2 => (cells(&row.get_item(0)?)?,
+				                            cells(&row.get_item(1)?)?),
+				None => return Err(value_error(
+					"Rows are (inputs, outputs) pairs unless nin is given.".to_string())),
+			});
+		}
+		let first = given.first().map_or((0, 0), |r| (r.0.len(), r.1.len()));
+		let invars = invars.unwrap_or_else(|| names("i", nin.unwrap_or(first.0)));
+		let outvars = outvars.unwrap_or_else(|| names("o", first.1));
+		if invars.is_empty() || invars.len() > MAX_ENUMERATED_BITS || outvars.is_empty() {
+			return Err(value_error(format!("A table needs 1 to {} inputs and at least \
+			                                one output.", MAX_ENUMERATED_BITS)));
+		}
+		let rows = self::rows(given, invars.len(), outvars.len()).map_err(value_error)?;
+		Ok(PyTruth{invars, outvars, rows})
+	}
+
+	fn __len__(&self) -> usize {
+		self.rows.len()
+	}
+
+	// The inputs of the rows where the equation is wrong; empty when it holds.
+	fn verify(&self, eqn: &PyEquation) -> Vec<Vec<bool>> {
+		counterexamples(&self.rows, &eqn.eqn)
+	}
+
+	// As verify, for a hand-written equation such as "x = a'b + c".
+	fn check(&self, eqn: &str) -> PyResult<Vec<Vec<bool>>> {
+		let outvars: Vec<&str> = self.outvars.iter().map(|s| s.as_str()).collect();
+		let eqn = super::parse_assertion(eqn, &outvars, &self.invars)
+			.map_err(value_error)?;
+		Ok(counterexamples(&self.rows, &eqn))
+	}
+}
+
+#[pyclass(name = "Equation", module = "minterm")]
+pub struct PyEquation {
+	eqn: Equation,
+	// whether the equation is known to be minimal.
+	#[pyo3(get)]
+	minimal: bool,
+}
+
+#[pymethods]
+impl PyEquation {
+	#[getter]
+	fn name(&self) -> String {
+		self.eqn.varname.clone()
+	}
+
+	// Each product as a dict from the names of the inputs it tests to the
+	// values it tests them for.
+	#[getter]
+	fn terms<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+		self.eqn.terms.iter().map(|t| {
+			let d = PyDict::new_bound(py);
+			for &(idx, val) in t.bits.iter() {
+				d.set_item(&t.names[idx], val)?;
+			}
+			Ok(d)
+		}).collect()
+	}
+
+	// 'text' as the command line writes it, or an assignment in 'python' or 'c'.
+	#[pyo3(signature = (style="text"))]
+	fn to_string(&self, style: &str) -> PyResult<String> {
+		let names = |f: fn(&str) -> String| -> Vec<String> {
+			self.eqn.terms.first().map_or(vec![], |t| t.names.iter().map(|n| f(n)).collect())
+		};
+		match style {
+			"text" => Ok(self.eqn.to_string()),
+			"python" => Ok(format!("{} = {}", python_name(&self.eqn.varname),
+			                       sum_of_products(&self.eqn, &names(python_name), &PYTHON))),
+			"c" => Ok(format!("{} = {};", c_name(&self.eqn.varname),
+			                  sum_of_products(&self.eqn, &names(c_name), &C))),
+			_ => Err(value_error(format!("Unknown style '{}'; expected 'text', 'python' \
+			                              or 'c'.", style))),
+		}
+	}
+
+	// The output for a list of input values.
+	fn evaluate(&self, inputs: &Bound<'_, PyAny>) -> PyResult<bool> {
+		let inp: Option<Vec<bool>> = cells(inputs)?.into_iter().collect();
+		match inp {
+			Some(ref inp) if inp.len() == self.eqn.terms.first().map_or(inp.len(),
+			                                                          |t| t.names.len()) =>
+				Ok(self.eqn.evaluate(inp)),
+			_ => Err(value_error("Give one 0 or 1 per input.".to_string())),
+		}
+	}
+
+	fn __str__(&self) -> String {
+		self.eqn.to_string()
+	}
+
+	fn __repr__(&self) -> String {
+		format!("<Equation {}>", self.eqn)
+	}
+}
+
+// Minimizes every output of the table.  'default_output' is for the inputs
+// it has no row for, as the command line's --default-output takes it.
+#[pyfunction]
+#[pyo3(signature = (truth, exact=false, max_steps=None, default_output="zero"))]
+fn minimize(truth: &PyTruth, exact: bool, max_steps: Option<u64>, default_output: &str) ->
+	PyResult<Vec<PyEquation>> {
+	let nin = truth.invars.len();
+	let policy = DefaultOutput::parse(default_output, truth.outvars.len())
+		.map_err(value_error)?;
+	let mut eqns = equations(&truth.rows, &truth.invars, &truth.outvars, &policy)
+		.map_err(value_error)?;
+	let mut budget = exact::Budget::new(None, max_steps);
+	let minimal = super::minimize(&mut eqns, nin, exact, &vec![1; nin], &mut budget,
+	                              &mut progress::Silent);
+	eqns.iter_mut().for_each(Equation::canonicalize);
+	Ok(eqns.into_iter().zip(minimal).map(|(e, m)| PyEquation{eqn: e, minimal: m}).collect())
+}
+
+#[pymodule]
+#[pyo3(name = "minterm")]
+fn init(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add_class::<PyTruth>()?;
+	m.add_class::<PyEquation>()?;
+	m.add_function(wrap_pyfunction!(pybind::minimize, m)?)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::formats::parse_cube;
+
+	fn small() -> Vec<Cells> {
+		let rows = ["000 01", "001 10", "010 11", "011 00", "100 11", "101 01", "110 11",
+		            "111 00"];
+		rows.iter().map(|r| {
+			let bits = |s: &str| parse_cube(s, true).unwrap();
+			(bits(&r[..3]), bits(&r[4..]))
+		}).collect()
+	}
+
+	fn strings(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|s| s.to_string()).collect()
+	}
+
+	#[test]
+	fn conversion() {
+		let mut cells = small();
+		cells.truncate(4);
+		cells.push((vec![Some(true), None, None], vec![Some(true), None]));
+		let table = rows(cells.clone(), 3, 2).unwrap();
+		assert_eq!(table.len(), 8);
+		assert_eq!(table[7], (vec![true, true, true], vec![Some(true), None]));
+		// 1-1 again, but with other outputs.
+		cells.push((vec![Some(true), None, Some(true)], vec![Some(false), None]));
+		assert_eq!(rows(cells.clone(), 3, 2).unwrap_err(),
+		           "Row 5 (1-1) gives different outputs than an earlier row.");
+		assert!(rows(cells, 3, 1).unwrap_err().starts_with("Row 0 has 3 input(s) and 2"));
+	}
+
+	#[test]
+	fn small_example() {
+		let rows = rows(small(), 3, 2).unwrap();
+		let (invars, outvars) = (strings(&["a", "b", "c"]), strings(&["x", "y"]));
+		let mut eqns = equations(&rows, &invars, &outvars, &DefaultOutput::Zero).unwrap();
+		super::super::minimize(&mut eqns, 3, true, &[1, 1, 1],
+		                       &mut exact::Budget::unlimited(), &mut progress::Silent);
+		assert_eq!(eqns[1].to_string(), "y = c' + ab' + ;");
+		for eqn in eqns.iter() {
+			assert!(counterexamples(&rows, eqn).is_empty(), "{}", eqn);
+		}
+		let wrong = super::super::parse_assertion("y = c'", &["x", "y"], &invars).unwrap();
+		assert_eq!(counterexamples(&rows, &wrong), vec![vec![true, false, true]]);
+		// without 111, and with it a don't-care.
+		let partial = &rows[..7];
+		let eqns = equations(partial, &invars, &outvars, &DefaultOutput::DontCare)
+			.unwrap();
+		assert_eq!(eqns[0].dc.len(), 1);
+		let eqns = equations(partial, &invars, &outvars,
+		                     &DefaultOutput::Bits(vec![false, true])).unwrap();
+		assert_eq!((eqns[0].terms.len(), eqns[1].terms.len()), (4, 6));
+		// too many inputs to find those the rows don't list.
+		let wide: Vec<String> = (0..64).map(|i| format!("i{}", i)).collect();
+		assert!(equations(&[], &wide, &outvars, &DefaultOutput::Zero).unwrap_err()
+			.starts_with("Cannot enumerate the 2^64 patterns of 64 inputs"));
+	}
+}
@@ -0,0 +1,212 @@
+// Python output, for checking the equations from scripts.  Either the
+// minimized equations as a function, or the table itself as a dict literal
+// that the function looks its answer up in.
+use super::{Equation, Truth};
+use super::emit::{DefaultOutput, Shared, Syntax, Unlisted, product, substitute,
+                  sum_of_products};
+
+pub const PYTHON: Syntax = Syntax{not: "not ", and: " and ", or: " or ",
+                             zero: "False", one: "True"};
+
+const KEYWORDS: &[&str] = &[
+	"False", "None", "True", "and", "as", "assert", "async", "await", "break",
+	"class", "continue", "def", "del", "elif", "else", "except", "finally",
+	"for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+	"not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+// A name as a Python identifier: characters that can't appear in one become
+// '_', and keywords get a trailing '_', so "x[0]" is "x_0_" and "in" is
+// "in_".
+pub fn python_name(name: &str) -> String {
+	let mut rv: String = name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+		.collect();
+	if rv.is_empty() || rv.starts_with(|c: char| c.is_ascii_digit()) {
+		rv.insert(0, '_');
+	}
+	if KEYWORDS.contains(&rv.as_str()) {
+		rv.push('_');
+	}
+	rv
+}
+
+// Names the generated code itself uses, which no variable may shadow.
+const RESERVED: &[&str] = &["TABLE", "ValueError", "bool", "dict", "zip"];
+
+// Python identifiers for the names, made by python_name and then kept
+// distinct: one that an earlier name already took, or that is reserved, gets
+// a suffix "_2", "_3" and so on, skipping the other names' identifiers.  So
+// "x[0]" and "x_0_" are "x_0_" and "x_0__2".
+pub fn python_names(names: &[String]) -> Vec<String> {
+	let plain: Vec<String> = names.iter().map(|n| python_name(n)).collect();
+	let mut rv: Vec<String> = vec![];
+	for name in plain.iter() {
+		let (mut unique, mut k) = (name.clone(), 1);
+		while rv.contains(&unique) || RESERVED.contains(&unique.as_str()) ||
+		      k > 1 && plain.contains(&unique) {
+			k += 1;
+			unique = format!("{}_{}", name, k);
+		}
+		rv.push(unique);
+	}
+	rv
+}
+
+// A Python string literal holding 'name'.
+fn quote(name: &str) -> String {
+	format!("'{}'", name.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// "(a, b)", with the trailing comma a single element tuple needs.
+fn tuple(elts: &[String]) -> String {
+	if elts.len() == 1 {
+		format!("({},)", elts[0])
+	} else {
+		format!("({})", elts.join(", "))
+	}
+}
+
+// The function's closing return statement, returning the tuple or dict of
+// 'values' (Python expressions, in output order).
+fn ret(values: &str, outnames: &[String], dict: bool) -> String {
+	if dict {
+		let keys: Vec<String> = outnames.iter().map(|n| quote(n)).collect();
+		format!("\treturn dict(zip({}, {}))\n", tuple(&keys), values)
+	} else {
+		format!("\treturn {}\n", values)
+	}
+}
+
+const UNLISTED: &str = "raise ValueError('input not in the table')";
+
+// Writes 'def map(a, b, c):' computing each output from its equation.  The
+// result is a tuple of bools in output order, or with 'dict' a dict keyed by
+// output name.  Unlisted inputs return the default bits or raise ValueError.
+pub fn function(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                dict: bool) -> String {
+	function_shared(eqns, invars, unlisted, dict, &[])
+}
+
+// As function, with the shared products computed once into local variables
+// that the outputs' equations use.  The equations are computed in the order
+// given, so one using another output (see emit::reuse_outputs) must come after
+// it; the result is still in output order.
+pub fn function_shared(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                       dict: bool, shared: &[Shared]) -> String {
+	let (eqns, locals) = substitute(eqns, shared, invars);
+	let mut ordered: Vec<&Equation> = eqns.iter().collect();
+	ordered.sort_by_key(|e| e.index);
+	let names: Vec<String> = ordered.iter().map(|e| e.varname.clone()).collect();
+	// the inputs, the shared products and the outputs are all variables.
+	let ids = python_names(&[&locals[..], &names[..]].concat());
+	let (locals, outputs) = ids.split_at(locals.len());
+	let (locals, outputs) = (locals.to_vec(), outputs.to_vec());
+	let inputs = locals[..invars.len()].to_vec();
+	let output = |name: &str| {
+		outputs[names.iter().position(|n| n == name).unwrap()].clone()
+	};
+	let mut s = format!("def map({}):\n", inputs.join(", "));
+	if let Some(cover) = unlisted.branch() {
+		s += &format!("\tif not ({}):\n", sum_of_products(cover, &inputs, &PYTHON));
+		match unlisted.policy {
+			DefaultOutput::Bits(ref b) => {
+				let values: Vec<String> = ordered.iter()
+					.map(|e| if b[e.index] { PYTHON.one } else { PYTHON.zero }.to_string())
+					.collect();
+				s += &format!("\t{}", ret(&tuple(&values), &names, dict));
+			},
+			_ => s += &format!("\t\t{}\n", UNLISTED),
+		}
+	}
+	for (sh, name) in shared.iter().zip(locals[inputs.len()..].iter()) {
+		s += &format!("\t{} = {}\n", name, product(&sh.term, &inputs, &PYTHON));
+	}
+	for eqn in eqns.iter() {
+		// an output used by this one is a variable after the inputs.
+		let names: Vec<String> = match eqn.terms.first() {
+			Some(t) if t.names.len() > locals.len() => {
+				let used = t.names[invars.len()..].iter().map(|n| output(n));
+				inputs.iter().cloned().chain(used).collect()
+			},
+			_ => locals.clone(),
+		};
+		s += &format!("\t{} = bool({})\n", output(&eqn.varname),
+		              sum_of_products(eqn, &names, &PYTHON));
+	}
+	s += &ret(&tuple(&outputs), &names, dict);
+	s.replace('\t', "    ")
+}
+
+fn bits(values: &[bool]) -> String {
+	let elts: Vec<String> = values.iter().map(|&b| (b as u8).to_string())
+		.collect();
+	tuple(&elts)
+}
+
+// Writes the table's selected outputs as a dict from input tuples to output
+// tuples, and a 'map' function looking its inputs up in it.  Unlisted inputs
+// give the default bits, raise ValueError, or with don't-cares KeyError.
+pub fn lookup(tbl: &Truth, selected: &[usize], outnames: &[String],
+              invars: &[String], unlisted: &Unlisted, dict: bool) -> String {
+	let inputs = python_names(invars);
+	let mut s = "TABLE = {\n".to_string();
+	for ent in tbl.table.iter() {
+		let outputs: Vec<bool> = selected.iter().map(|&o| ent.output[o]).collect();
+		s += &format!("\t{}: {},\n", bits(&ent.input), bits(&outputs));
+	}
+	s += "}\n\n";
+	s += &format!("def map({}):\n", inputs.join(", "));
+	let key = tuple(&inputs);
+	let value = match (unlisted.listed.is_some(), &unlisted.policy) {
+		(true, &DefaultOutput::Zero) =>
+			format!("TABLE.get({}, {})", key, bits(&vec![false; selected.len()])),
+		(true, DefaultOutput::Bits(b)) => {
+			let b: Vec<bool> = selected.iter().map(|&o| b[o]).collect();
+			format!("TABLE.get({}, {})", key, bits(&b))
+		},
+		(true, &DefaultOutput::Panic) => {
+			s += &format!("\tif {} not in TABLE:\n\t\t{}\n", key, UNLISTED);
+			format!("TABLE[{}]", key)
+		},
+		_ => format!("TABLE[{}]", key),
+	};
+	s += &ret(&value, outnames, dict);
+	s.replace('\t', "    ")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::equations;
+
+	#[test]
+	fn names() {
+		assert_eq!(python_name("enable"), "enable");
+		assert_eq!(python_name("x[0]"), "x_0_");
+		assert_eq!(python_name("in"), "in_");
+		assert_eq!(python_name("2x"), "_2x");
+		assert_eq!(quote("it's"), "'it\\'s'");
+	}
+
+	#[test]
+	fn distinct_names() {
+		let names = |ns: &[&str]| -> Vec<String> {
+			ns.iter().map(|n| n.to_string()).collect()
+		};
+		assert_eq!(python_names(&names(&["x[0]", "x_0_", "x_0__2", "bool"])),
+		           names(&["x_0_", "x_0__3", "x_0__2", "bool_2"]));
+		// an output named like an input doesn't overwrite it.
+		let tbl = Truth::from_fn(2, 2, |i| vec![i[0], i[0] && i[1]]).unwrap();
+		let invars = names(&["q-1", "x[0]"]);
+		let mut eqns = equations(&tbl, vec!["q_1", "x_0_"], invars.clone());
+		eqns.iter_mut().for_each(Equation::simplify);
+		let zero = Unlisted::new(DefaultOutput::Zero, &tbl, 2, &invars);
+		let src = function(&eqns, &invars, &zero, false);
+		assert_eq!(src, "def map(q_1, x_0_):\n    q_1_2 = bool(q_1)\n    \
+		                 x_0__2 = bool(q_1 and x_0_)\n    return (q_1_2, x_0__2)\n");
+		let src = lookup(&tbl, &[0], &names(&["q_1"]), &names(&["x[0]", "x_0_"]), &zero,
+		                 false);
+		assert!(src.contains("def map(x_0_, x_0__2):\n"), "{}", src);
+	}
+}
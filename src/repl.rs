@@ -0,0 +1,137 @@
+// 'minterm repl': loads a table and minimizes its outputs, then answers
+// queries about them read one per line from stdin.
+use std::io::{BufRead, IsTerminal, Write};
+use minterm::{Equation, RowIndex, Truth, did_you_mean, exact, progress};
+use super::{Failure, initial_equations, load};
+
+pub const USAGE: &str = concat!("
+Usage: minterm repl (--table <truth>)... --ivar=<foo>... --ovar=<bar>... [--constraint=<expr>]... [--onehot=<cols>]... [--filter=<cond>]... [--filter-out=<cond>]... [--ocol=<col>]... [--map=<m>]... [options]
+       minterm repl --help
+
+Loads the table and minimizes its outputs, then reads commands from stdin:
+  eval <bits>   The outputs the equations give for an input pattern, e.g.
+                'eval 011', and whether the table lists the pattern.
+  eqn [<ovar>]  The minimized equation of the output, or of every output.
+  rows          The number of rows in the table.
+  help          This list.
+  quit          Leave; so does the end of the input.
+
+Options:", input_options!(), table_options!());
+
+const COMMANDS: &str = "eval <bits> | eqn [<ovar>] | rows | help | quit";
+
+pub struct Session {
+	tbl: Truth,
+	rows: RowIndex,
+	nin: usize,
+	eqns: Vec<Equation>,
+}
+
+impl Session {
+	pub fn new(tbl: Truth, nin: usize, eqns: Vec<Equation>) -> Self {
+		Session{rows: tbl.index(), tbl, nin, eqns}
+	}
+
+	// The answer to one line of input, or None to leave.
+	pub fn respond(&self, line: &str) -> Option<String> {
+		let words: Vec<&str> = line.split_whitespace().collect();
+		let answer = match (words.first().cloned().unwrap_or(""), words.len()) {
+			("", _) => String::new(),
+			("quit", _) | ("exit", _) => return None,
+			("help", _) => COMMANDS.to_string(),
+			("rows", _) => format!("{} rows.", self.tbl.len()),
+			("eqn", 1) => {
+				let all: Vec<String> = self.eqns.iter().map(|e| e.to_string()).collect();
+				all.join("\n")
+			},
+			("eqn", 2) => match self.eqns.iter().find(|e| e.varname() == words[1]) {
+				Some(eqn) => eqn.to_string(),
+				None => {
+					let names: Vec<&str> = self.eqns.iter().map(|e| e.varname())
+						.collect();
+					format!("Unknown output '{}'.{}", words[1], did_you_mean(words[1],
+					                                                         &names))
+				},
+			},
+			("eval", 2) => self.eval(words[1]),
+			(cmd, _) => format!("Cannot understand '{}'; expected {}.", cmd.trim(),
+			                    COMMANDS),
+		};
+		Some(answer)
+	}
+
+	fn eval(&self, bits: &str) -> String {
+		if bits.len() != self.nin || !bits.chars().all(|c| c == '0' || c == '1') {
+			return format!("'{}' is not an input pattern; expected {} 0/1 digits.", bits,
+			               self.nin);
+		}
+		let inp: Vec<bool> = bits.chars().map(|c| c == '1').collect();
+		let outs: Vec<String> = self.eqns.iter()
+			.map(|e| format!("{}={}", e.varname(), e.evaluate(&inp) as u8)).collect();
+		let listed = self.rows.position(&inp).is_some();
+		format!("{}{}", outs.join(" "), if listed { "" } else { " (not in the table)" })
+	}
+
+	// Answers each line of 'input' on 'output' until told to quit.
+	pub fn serve<R: BufRead, W: Write>(&self, input: R, output: &mut W, prompt: bool) ->
+		std::io::Result<()> {
+		if prompt {
+			write!(output, "> ")?;
+			output.flush()?;
+		}
+		for line in input.lines() {
+			let answer = match self.respond(&line?) {
+				Some(a) => a,
+				None => break,
+			};
+			if !answer.is_empty() {
+				writeln!(output, "{}", answer)?;
+			}
+			if prompt {
+				write!(output, "> ")?;
+				output.flush()?;
+			}
+		}
+		Ok(())
+	}
+}
+
+pub fn run(args: &::docopt::ArgvMap, argv: &[String], note: &dyn Fn(String)) ->
+	Result<(), Failure> {
+	let ld = load(args, argv, note)?;
+	let nin = ld.invars.len();
+	let selected: Vec<usize> = (0..ld.outvars.len()).collect();
+	let mut eqns = initial_equations(&ld, &ld.tbl, &ld.invars, &ld.missing,
+	                                 &ld.impossible, &selected, &[]);
+	::minterm::minimize(&mut eqns, nin, false, &vec![1; nin],
+	                    &mut exact::Budget::unlimited(), &mut progress::Silent);
+	let session = Session::new(ld.tbl, nin, eqns);
+	let stdin = std::io::stdin();
+	let prompt = stdin.is_terminal();
+	let stdout = std::io::stdout();
+	session.serve(stdin.lock(), &mut stdout.lock(), prompt)
+		.map_err(|e| Failure::Usage(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use minterm::{equations, parse};
+
+	#[test]
+	fn queries() {
+		// x = a + b, but a=1 b=1 is missing.
+		let tbl = parse("0,0,,0\n0,1,,1\n1,0,,1\n".as_bytes(), 0, 2, 1);
+		let mut eqns = equations(&tbl, vec!["x"], vec!["a".into(), "b".into()]);
+		eqns[0].simplify();
+		let session = Session::new(tbl, 2, eqns);
+		let mut out: Vec<u8> = vec![];
+		session.serve("eval 01\neval 11\n\neqn\neqn y\neval 2\nrows\nfoo\nquit\nrows\n"
+		              .as_bytes(), &mut out, false).unwrap();
+		assert_eq!(String::from_utf8(out).unwrap(), "x=1\nx=0 (not in the table)\n\
+		            x = a'b + ab' + ;\nUnknown output 'y'. Did you mean 'x'?\n\
+		            '2' is not an input pattern; expected 2 0/1 digits.\n3 rows.\n\
+		            Cannot understand 'foo'; expected eval <bits> | eqn [<ovar>] | rows | \
+		            help | quit.\n");
+	}
+}
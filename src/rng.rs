@@ -0,0 +1,29 @@
+// A small xorshift generator, for the tables of testutil and the moves of
+// --improve: neither needs more than numbers that look random, and both need
+// the same numbers from the same seed.
+pub struct Rng(u64);
+
+impl Rng {
+	pub fn new(seed: u64) -> Self {
+		Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	pub fn bit(&mut self) -> bool {
+		self.next_u64() >> 32 & 1 == 1
+	}
+
+	// Puts the items in a random order.
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		for i in (1..items.len()).rev() {
+			let j = (self.next_u64() % (i as u64 + 1)) as usize;
+			items.swap(i, j);
+		}
+	}
+}
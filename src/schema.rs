@@ -0,0 +1,244 @@
+// The schema line a CSV table may start with, so that the file says what the
+// command line otherwise must:
+//
+//     #! minterm inputs=OGL,GLX,EGL,GL outputs=ROGL,RGLX sparse=dc
+//
+// 'inputs' and 'outputs' give the --ivar and --ovar names in order, 'sparse'
+// the --missing policy, 'duplicates' the --duplicates one and 'map' the --map
+// options, separated by commas, as in map=GLX:YES=1,GLX:NO=0,*:N/A=x.  To the reader
+// the line is a comment like any other, so it changes nothing of how the rows
+// are read.  A line starting "#!" but not "#! minterm" isn't a schema line;
+// one that is must be right, as a mistake in it would otherwise only show as
+// a table read wrongly.
+use super::complete::{Duplicates, Missing};
+use super::{CellMap, did_you_mean};
+
+const KEYS: [&str; 5] = ["inputs", "outputs", "sparse", "duplicates", "map"];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+	pub inputs: Vec<String>,
+	pub outputs: Vec<String>,
+	// the values as --missing and --duplicates take them.
+	pub sparse: Option<String>,
+	pub duplicates: Option<String>,
+	pub maps: Vec<String>,
+}
+
+// The schema the line gives, None when it isn't a schema line.
+pub fn parse(line: &str) -> Result<Option<Schema>, String> {
+	let rest = match line.strip_prefix("#!") {
+		Some(r) => r,
+		None => return Ok(None),
+	};
+	let mut words = rest.split_whitespace();
+	if words.next() != Some("minterm") {
+		return Ok(None);
+	}
+	let mut rv = Schema::default();
+	let mut seen: Vec<String> = vec![];
+	for word in words {
+		let mut parts = word.splitn(2, '=');
+		let key = parts.next().unwrap();
+		match parts.next() {
+			Some(value) => rv.set(&mut seen, key, value)?,
+			None => return Err(format!("'{}' is not key=value.", word)),
+		}
+	}
+	Ok(Some(rv))
+}
+
+// The schema a sidecar file gives, in the little of TOML it needs: a line per
+// key, its value a string or, for the names and maps, an array of strings.
+//
+//     inputs = ["a", "b", "c"]
+//     outputs = ["x", "y"]
+//     sparse = "dc"
+//     map = ["*:YES=1", "*:NO=0"]
+//
+// Blank lines and comments, from a '#' outside a string, are skipped.
+pub fn parse_toml(text: &str) -> Result<Schema, String> {
+	let mut rv = Schema::default();
+	let mut seen: Vec<String> = vec![];
+	for (k, line) in text.lines().enumerate() {
+		let at = |e: String| format!("line {}: {}", k + 1, e);
+		let line = uncommented(line).trim();
+		if line.is_empty() {
+			continue;
+		}
+		let mut parts = line.splitn(2, '=');
+		let key = parts.next().unwrap().trim();
+		let value = match parts.next() {
+			Some(v) => v.trim(),
+			None => return Err(at(format!("'{}' is not key = value.", line))),
+		};
+		let names = key == "inputs" || key == "outputs" || key == "map";
+		let value = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+			Some(items) if names => {
+				let names: Result<Vec<String>, String> = items.split(',')
+					.map(|i| string(i.trim())).collect();
+				names.map_err(&at)?.join(",")
+			},
+			None if names && KEYS.contains(&key) =>
+				return Err(at(format!("{} must be an array of strings, such as \
+				                       [\"a\", \"b\"].", key))),
+			_ if KEYS.contains(&key) => string(value).map_err(&at)?,
+			// set says what is wrong with the key.
+			_ => value.to_string(),
+		};
+		rv.set(&mut seen, key, &value).map_err(&at)?;
+	}
+	Ok(rv)
+}
+
+// The line up to a '#' that isn't within a string.
+fn uncommented(line: &str) -> &str {
+	let mut quoted = false;
+	for (i, c) in line.char_indices() {
+		match c {
+			'"' => quoted = !quoted,
+			'#' if !quoted => return &line[..i],
+			_ => {},
+		}
+	}
+	line
+}
+
+// The contents of a basic TOML string without escapes.
+fn string(value: &str) -> Result<String, String> {
+	match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+		Some(s) if !s.contains('"') && !s.contains('\\') => Ok(s.to_string()),
+		_ => Err(format!("expected a string in double quotes, without escapes, not {}.",
+		                 value)),
+	}
+}
+
+impl Schema {
+	// Sets the key from its value as the schema line has it, the names joined
+	// by commas.
+	fn set(&mut self, seen: &mut Vec<String>, key: &str, value: &str) ->
+		Result<(), String> {
+		if !KEYS.contains(&key) {
+			return Err(format!("unknown key '{}'; expected inputs, outputs, sparse, \
+			                    duplicates or map.{}", key, did_you_mean(key, &KEYS)));
+		}
+		if seen.iter().any(|k| k == key) {
+			return Err(format!("'{}' is given twice.", key));
+		}
+		seen.push(key.to_string());
+		match key {
+			"inputs" => self.inputs = list(key, value)?,
+			"outputs" => self.outputs = list(key, value)?,
+			"sparse" => {
+				Missing::parse(value).map_err(|_| format!(
+					"unknown sparse '{}'; expected 'error', 'warn', 'dc' or 'zero'.", value))?;
+				self.sparse = Some(value.to_string());
+			},
+			"map" => {
+				let maps = list(key, value)?;
+				if let Some(m) = maps.iter().find(|m| CellMap::parse(m).is_err()) {
+					return Err(format!("malformed map '{}'; expected column:value=0, 1 \
+					                    or x.", m));
+				}
+				self.maps = maps;
+			},
+			_ => {
+				Duplicates::parse(value).map_err(|_| format!(
+					"unknown duplicates '{}'; expected 'error', 'merge' or 'last-wins'.",
+					value))?;
+				self.duplicates = Some(value.to_string());
+			},
+		}
+		Ok(())
+	}
+
+	// The command line options the schema stands for.
+	pub fn options(&self) -> Vec<String> {
+		let mut rv: Vec<String> = self.inputs.iter().map(|n| format!("--ivar={}", n))
+			.collect();
+		rv.extend(self.outputs.iter().map(|n| format!("--ovar={}", n)));
+		rv.extend(self.sparse.iter().map(|s| format!("--missing={}", s)));
+		rv.extend(self.duplicates.iter().map(|s| format!("--duplicates={}", s)));
+		rv.extend(self.maps.iter().map(|m| format!("--map={}", m)));
+		rv
+	}
+}
+
+// A comma separated list of names, none of them empty or given twice.
+fn list(key: &str, value: &str) -> Result<Vec<String>, String> {
+	let mut rv: Vec<String> = vec![];
+	for name in value.split(',') {
+		if name.is_empty() {
+			return Err(format!("malformed {} list '{}': a name is empty.", key, value));
+		}
+		if rv.iter().any(|n| n == name) {
+			return Err(format!("malformed {} list '{}': '{}' is given twice.", key, value,
+			                   name));
+		}
+		rv.push(name.to_string());
+	}
+	Ok(rv)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn schema_lines() {
+		let s = parse("#! minterm inputs=OGL,GLX,EGL,GL outputs=ROGL,RGLX sparse=dc")
+			.unwrap().unwrap();
+		assert_eq!(s.inputs, vec!["OGL", "GLX", "EGL", "GL"]);
+		assert_eq!(s.outputs, vec!["ROGL", "RGLX"]);
+		assert_eq!((s.sparse, s.duplicates), (Some("dc".to_string()), None));
+		assert_eq!(parse("#!/usr/bin/env minterm"), Ok(None));
+		assert_eq!(parse("# minterm inputs=a"), Ok(None));
+		assert_eq!(parse("a,b,x"), Ok(None));
+		assert_eq!(parse("#! minterm"), Ok(Some(Schema::default())));
+		let err = |line: &str| parse(line).unwrap_err();
+		assert_eq!(err("#! minterm input=a,b"),
+		           "unknown key 'input'; expected inputs, outputs, sparse, duplicates or \
+		            map. Did you mean 'inputs'?");
+		assert_eq!(err("#! minterm inputs=a,,b"),
+		           "malformed inputs list 'a,,b': a name is empty.");
+		assert_eq!(err("#! minterm outputs=x,"), "malformed outputs list 'x,': a name is empty.");
+		assert_eq!(err("#! minterm outputs=x,y,x"),
+		           "malformed outputs list 'x,y,x': 'x' is given twice.");
+		assert_eq!(err("#! minterm inputs a,b"), "'inputs' is not key=value.");
+		assert_eq!(err("#! minterm inputs=a inputs=b"), "'inputs' is given twice.");
+		assert!(err("#! minterm sparse=maybe").starts_with("unknown sparse 'maybe'"));
+		assert!(err("#! minterm duplicates=first").starts_with("unknown duplicates"));
+		let s = parse("#! minterm inputs=a map=a:YES=1,*:N/A=x").unwrap().unwrap();
+		assert_eq!(s.maps, vec!["a:YES=1", "*:N/A=x"]);
+		assert_eq!(s.options(), vec!["--ivar=a", "--map=a:YES=1", "--map=*:N/A=x"]);
+		assert_eq!(err("#! minterm map=a:YES=2"),
+		           "malformed map 'a:YES=2'; expected column:value=0, 1 or x.");
+	}
+
+	#[test]
+	fn sidecar_files() {
+		let s = parse_toml("# the decoder\ninputs = [\"a\", \"b#1\"]  # two\n\n\
+		                    outputs = [\"x\"]\nsparse = \"dc\"\nmap = [\"*:ON=1\"]\n")
+			.unwrap();
+		assert_eq!((s.inputs.clone(), s.outputs.clone()),
+		           (vec!["a".to_string(), "b#1".to_string()], vec!["x".to_string()]));
+		assert_eq!(s.options(),
+		           vec!["--ivar=a", "--ivar=b#1", "--ovar=x", "--missing=dc",
+		                "--map=*:ON=1"]);
+		assert_eq!(parse("#! minterm inputs=a,b#1 outputs=x sparse=dc map=*:ON=1"),
+		           Ok(Some(s)));
+		let err = |text: &str| parse_toml(text).unwrap_err();
+		assert_eq!(err("inputs = [\"a\"]\noutput = [\"x\"]"),
+		           "line 2: unknown key 'output'; expected inputs, outputs, sparse, \
+		            duplicates or map. Did you mean 'outputs'?");
+		assert_eq!(err("inputs = \"a\""),
+		           "line 1: inputs must be an array of strings, such as [\"a\", \"b\"].");
+		assert_eq!(err("inputs = [\"a\", b]"),
+		           "line 1: expected a string in double quotes, without escapes, not b.");
+		assert_eq!(err("inputs = [\"a\", \"\"]"),
+		           "line 1: malformed inputs list 'a,': a name is empty.");
+		assert_eq!(err("[table]"), "line 1: '[table]' is not key = value.");
+		assert_eq!(err("sparse = dc"),
+		           "line 1: expected a string in double quotes, without escapes, not dc.");
+	}
+}
@@ -0,0 +1,131 @@
+// An output's inputs split three ways, as espresso and similar tools reason
+// about them: the ON-set, where the output is 1, the DC-set, where it may be
+// either, and the OFF-set, where it is 0.  Each set is a cover, a list of
+// cubes.  In a table that doesn't list every input one of the sets is
+// implicit: it holds whatever the other two don't, as --default-output says.
+use std::collections::HashSet;
+use super::{Truth, MAX_ENUMERATED_BITS};
+use super::emit::DefaultOutput;
+use super::exact::{expand_cube, Cube};
+use super::formats::cube_string;
+
+pub type Cover = Vec<Cube>;
+
+// Whether some input is in both cubes.
+fn intersect(a: &Cube, b: &Cube) -> bool {
+	a.iter().zip(b.iter()).all(|(x, y)| x.is_none() || y.is_none() || x == y)
+}
+
+// Checks that no input is in two of the sets and, when the table is
+// 'complete', that every input over 'nin' bits is in one of them.
+pub fn check(on: &Cover, dc: &Cover, off: &Cover, nin: usize, complete: bool) ->
+	Result<(), String> {
+	let sets = [("ON", on), ("DC", dc), ("OFF", off)];
+	for (i, &(name_a, a)) in sets.iter().enumerate() {
+		for &(name_b, b) in sets[i+1..].iter() {
+			for ca in a.iter() {
+				if let Some(cb) = b.iter().find(|cb| intersect(ca, cb)) {
+					return Err(format!("{} cube {} and {} cube {} overlap.", name_a,
+					                   cube_string(ca), name_b, cube_string(cb)));
+				}
+			}
+		}
+	}
+	if !complete {
+		return Ok(());
+	}
+	if nin > MAX_ENUMERATED_BITS {
+		return Err(format!("Cannot check that the sets cover 2^{} inputs.", nin));
+	}
+	let covered: HashSet<Vec<bool>> = on.iter().chain(dc.iter()).chain(off.iter())
+		.flat_map(expand_cube).collect();
+	if covered.len() != 1 << nin {
+		return Err(format!("The sets cover {} of the {} inputs.", covered.len(),
+		                   1u64 << nin));
+	}
+	Ok(())
+}
+
+// The set the inputs a table doesn't list are in, by the policy.
+fn implicit(policy: &DefaultOutput, idx: usize) -> &'static str {
+	match *policy {
+		DefaultOutput::Zero => "OFF",
+		DefaultOutput::Bits(ref b) if b[idx] => "ON",
+		DefaultOutput::Bits(_) => "OFF",
+		DefaultOutput::DontCare | DefaultOutput::Panic => "DC",
+	}
+}
+
+// The sets of output 'idx' of the table, checked and listed a line each:
+//   x:
+//     ON  001 010 100 110
+//     DC  111
+//     OFF 000 011 101
+// The implicit set of a table that doesn't list every input is listed as
+// 'every other input'.
+pub fn describe(tbl: &Truth, idx: usize, name: &str, nin: usize,
+                policy: &DefaultOutput) -> Result<String, String> {
+	let (on, dc, off) = tbl.partition(idx);
+	let complete = nin <= MAX_ENUMERATED_BITS &&
+		(tbl.len() + tbl.dc.len()) as u64 == 1u64 << nin;
+	check(&on, &dc, &off, nin, complete)?;
+	let mut rv = format!("{}:\n", name);
+	for &(set, cover) in [("ON", &on), ("DC", &dc), ("OFF", &off)].iter() {
+		let mut cubes: Vec<String> = cover.iter().map(|c| cube_string(c)).collect();
+		if !complete && set == implicit(policy, idx) {
+			cubes.push("every other input".to_string());
+		}
+		rv += format!("  {:<3} {}", set, cubes.join(" ")).trim_end();
+		rv += "\n";
+	}
+	Ok(rv)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	#[test]
+	fn complete() {
+		let mut tbl = parse(SMALL.as_bytes(), 0, 3, 2);
+		let (on, dc, off) = tbl.partition(0);
+		assert_eq!((on.len(), dc.len(), off.len()), (4, 0, 4));
+		assert_eq!(check(&on, &dc, &off, 3, true), Ok(()));
+		assert_eq!(describe(&tbl, 0, "x", 3, &DefaultOutput::Zero).unwrap(),
+		           "x:\n  ON  001 010 100 110\n  DC\n  OFF 000 011 101 111\n");
+		// 111 a don't-care rather than a row.
+		let last = tbl.table.pop().unwrap();
+		tbl.dc.push(last.input);
+		assert_eq!(describe(&tbl, 1, "y", 3, &DefaultOutput::Zero).unwrap(),
+		           "y:\n  ON  000 010 100 101 110\n  DC  111\n  OFF 001 011\n");
+		// which can't be a row too.
+		let (on, mut dc, off) = tbl.partition(1);
+		dc.push(vec![Some(false), None, Some(true)]);
+		assert_eq!(check(&on, &dc, &off, 3, true),
+		           Err("DC cube 0-1 and OFF cube 001 overlap.".to_string()));
+		let (on, dc, mut off) = tbl.partition(1);
+		off.pop();
+		assert_eq!(check(&on, &dc, &off, 3, true),
+		           Err("The sets cover 7 of the 8 inputs.".to_string()));
+	}
+
+	#[test]
+	fn sparse() {
+		let tbl = parse("0,0,0,,0,1\n0,0,1,,1,0\n1,1,1,,1,1\n".as_bytes(), 0, 3, 2);
+		let (on, dc, off) = tbl.partition(0);
+		// the unlisted inputs make the sets fall short, which is no error here.
+		assert!(check(&on, &dc, &off, 3, true).is_err());
+		assert_eq!(check(&on, &dc, &off, 3, false), Ok(()));
+		assert_eq!(describe(&tbl, 0, "x", 3, &DefaultOutput::Zero).unwrap(),
+		           "x:\n  ON  001 111\n  DC\n  OFF 000 every other input\n");
+		assert_eq!(describe(&tbl, 0, "x", 3, &DefaultOutput::DontCare).unwrap(),
+		           "x:\n  ON  001 111\n  DC  every other input\n  OFF 000\n");
+		let ones = DefaultOutput::Bits(vec![false, true]);
+		assert_eq!(describe(&tbl, 1, "y", 3, &ones).unwrap(),
+		           "y:\n  ON  000 111 every other input\n  DC\n  OFF 001\n");
+	}
+}
@@ -0,0 +1,95 @@
+// The products of a multi-output cover and the outputs using them, as a
+// bipartite graph in Graphviz's DOT language: a box per distinct product, an
+// ellipse per output, and an edge wherever the output's equation has the
+// product.  A product several outputs have is computed once for all of them,
+// which is what --cost conditions and --share-terms make the most of.
+use std::fmt::Write;
+use super::{Equation, Term};
+use super::notation::Notation;
+
+// The graph, its products in the order they first appear.  A product's box is
+// drawn as thick as the number of outputs having it, and the edges of a single
+// literal, which needs no gate, are dashed.  With 'collapse', the products
+// only one output has are drawn as a single node per output saying how many
+// there are.
+pub fn dot(eqns: &[Equation], collapse: bool, n: &Notation) -> String {
+	let mut terms: Vec<(&Term, Vec<usize>)> = vec![];
+	for (o, eqn) in eqns.iter().enumerate() {
+		for t in eqn.terms.iter() {
+			match terms.iter().position(|&(u, _)| u == t) {
+				Some(i) if !terms[i].1.contains(&o) => terms[i].1.push(o),
+				Some(_) => {},
+				None => terms.push((t, vec![o])),
+			}
+		}
+	}
+	let shared = terms.iter().filter(|&(_, outs)| outs.len() > 1).count();
+	let mut rv = "digraph \"sharing\" {\n\trankdir=LR;\n".to_string();
+	// write! to a String can't fail.
+	writeln!(rv, "\t// {} products, {} of them shared, for {} outputs", terms.len(),
+	         shared, eqns.len()).unwrap();
+	for (o, eqn) in eqns.iter().enumerate() {
+		writeln!(rv, "\to{} [label=\"{}\", shape=ellipse];", o, label(&eqn.varname))
+			.unwrap();
+	}
+	let mut unshared = vec![0; eqns.len()];
+	for (i, &(t, ref outs)) in terms.iter().enumerate() {
+		if collapse && outs.len() == 1 {
+			unshared[outs[0]] += 1;
+			continue;
+		}
+		writeln!(rv, "\tt{} [label=\"{}\", shape=box, penwidth={}];", i, label(&n.term(t)),
+		         outs.len())
+			.unwrap();
+		let style = if t.len() == 1 { " [style=dashed]" } else { "" };
+		for o in outs.iter() {
+			writeln!(rv, "\tt{} -> o{}{};", i, o, style).unwrap();
+		}
+	}
+	for (o, &n) in unshared.iter().enumerate().filter(|&(_, &n)| n > 0) {
+		writeln!(rv, "\tu{} [label=\"{} unshared\", shape=note];", o, n).unwrap();
+		writeln!(rv, "\tu{} -> o{};", o, o).unwrap();
+	}
+	rv += "}\n";
+	rv
+}
+
+// The text of a label, its quotes and backslashes escaped, as a quoted name
+// of the notation has them.
+fn label(s: &str) -> String {
+	s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn cover(eqns: &[&str]) -> Vec<Equation> {
+		let names: Vec<String> = ["a", "b", "c"].iter().map(|n| n.to_string())
+			.collect();
+		eqns.iter().enumerate().map(|(o, s)| {
+			let mut eqn = Equation::parse(s, &names).unwrap();
+			eqn.index = o;
+			eqn
+		}).collect()
+	}
+
+	#[test]
+	fn one_shared_product() {
+		let eqns = cover(&["x = bc' + a'b'c", "y = c' + ab' + bc'"]);
+		let dot = dot(&eqns, false, &Notation::classic());
+		assert!(dot.contains("// 4 products, 1 of them shared, for 2 outputs"), "{}", dot);
+		assert_eq!(dot.matches("penwidth=2").count(), 1, "{}", dot);
+		assert!(dot.contains("\tt0 [label=\"bc'\", shape=box, penwidth=2];\n\
+		                      \tt0 -> o0;\n\tt0 -> o1;\n"), "{}", dot);
+		assert_eq!(dot.matches("t0 -> ").count(), 2);
+		// c' is a single literal.
+		assert!(dot.contains("\tt2 -> o1 [style=dashed];\n"), "{}", dot);
+		let collapsed = super::dot(&eqns, true, &Notation::classic());
+		assert_eq!(collapsed.matches("shape=box").count(), 1, "{}", collapsed);
+		assert!(collapsed.contains("\tu0 [label=\"1 unshared\", shape=note];\n\
+		                            \tu0 -> o0;\n\
+		                            \tu1 [label=\"2 unshared\", shape=note];\n\
+		                            \tu1 -> o1;\n"), "{}", collapsed);
+	}
+}
@@ -0,0 +1,291 @@
+// Jump-table output: the inputs packed into one integer, and a switch in C or
+// a match in Rust over its values, each case assigning every output.  For a
+// dense table this is as fast as software gets, and the compiler turns it
+// into a lookup.
+//
+// The inputs are packed as --bit-order says (see bits.rs), and the generated
+// code says how in a comment.
+use super::Equation;
+use super::bits::BitOrder;
+use super::enumerate::Inputs;
+use super::emit::{DefaultOutput, Unlisted};
+
+// A case of the switch: the packed input values it is taken for, in
+// increasing order, and the outputs it assigns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Case {
+	pub values: Vec<u64>,
+	pub outputs: Vec<bool>,
+}
+
+// A case per input pattern, with the outputs the equations give, or with
+// 'compress' a case per distinct value of the outputs, taken for every pattern
+// giving it.  Patterns the table doesn't list have no case when the default
+// branch must handle them.
+pub fn cases(eqns: &[Equation], nbits: usize, unlisted: &Unlisted, order: BitOrder,
+             compress: bool) -> Vec<Case> {
+	let strict = unlisted.branch().is_some();
+	let mut cases: Vec<Case> = vec![];
+	for (value, inp) in Inputs::every(nbits).ordered(order).enumerate() {
+		let value = value as u64;
+		if strict && !unlisted.is_listed(&inp) {
+			continue;
+		}
+		let outputs: Vec<bool> = eqns.iter().map(|e| e.evaluate(&inp)).collect();
+		match cases.iter_mut().find(|c| compress && c.outputs == outputs) {
+			Some(c) => c.values.push(value),
+			None => cases.push(Case{values: vec![value], outputs}),
+		}
+	}
+	cases
+}
+
+// The runs of consecutive values, as (first, last) pairs.
+fn runs(values: &[u64]) -> Vec<(u64, u64)> {
+	let mut rv: Vec<(u64, u64)> = vec![];
+	for &v in values.iter() {
+		match rv.last_mut() {
+			Some(run) if run.1 + 1 == v => run.1 = v,
+			_ => rv.push((v, v)),
+		}
+	}
+	rv
+}
+
+// The outputs of the case taken for an input pattern, or None for the default
+// branch.
+#[cfg(test)]
+pub fn evaluate(cases: &[Case], inp: &[bool], order: BitOrder) -> Option<Vec<bool>> {
+	let value = super::bits::bit_pack(inp, order);
+	cases.iter().find(|c| c.values.contains(&value)).map(|c| c.outputs.clone())
+}
+
+const C_KEYWORDS: &[&str] = &[
+	"auto", "bool", "break", "case", "char", "const", "continue", "default", "do",
+	"double", "else", "enum", "extern", "false", "float", "for", "goto", "if",
+	"inline", "int", "long", "register", "restrict", "return", "short", "signed",
+	"sizeof", "static", "struct", "switch", "true", "typedef", "union",
+	"unsigned", "void", "volatile", "while",
+];
+
+const RUST_KEYWORDS: &[&str] = &[
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else",
+	"enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop",
+	"match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+	"static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+	"where", "while",
+];
+
+// A name as an identifier of a C-like language: characters that can't appear
+// in one become '_', and keywords get a trailing '_', as for Python.
+fn identifier(name: &str, keywords: &[&str]) -> String {
+	let mut rv: String = name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+		.collect();
+	if rv.is_empty() || rv.starts_with(|c: char| c.is_ascii_digit()) {
+		rv.insert(0, '_');
+	}
+	if keywords.contains(&rv.as_str()) {
+		rv.push('_');
+	}
+	rv
+}
+
+pub fn c_name(name: &str) -> String { identifier(name, C_KEYWORDS) }
+pub fn rust_name(name: &str) -> String { identifier(name, RUST_KEYWORDS) }
+
+// The packed value of the inputs, e.g. "a | b << 1 | c << 2", with each input
+// converted by 'cast'.
+pub fn packed(inputs: &[String], order: BitOrder, cast: &dyn Fn(&str) -> String) ->
+	String {
+	let n = inputs.len();
+	let parts: Vec<String> = inputs.iter().enumerate().map(|(i, name)| {
+		let shift = order.shift(i, n);
+		if shift == 0 { cast(name) } else { format!("{} << {}", cast(name), shift) }
+	}).collect();
+	parts.join(" | ")
+}
+
+// "Packs a as bit 0, b as bit 1 and c as bit 2."
+pub fn packing(inputs: &[String], order: BitOrder) -> String {
+	let n = inputs.len();
+	let bits: Vec<String> = inputs.iter().enumerate().map(|(i, name)| {
+		format!("{} as bit {}", name, order.shift(i, n))
+	}).collect();
+	match bits.split_last() {
+		Some((last, rest)) if !rest.is_empty() =>
+			format!("Packs {} and {}.", rest.join(", "), last),
+		_ => format!("Packs {}.", bits.join("")),
+	}
+}
+
+fn c_bool(b: bool) -> &'static str { if b { "true" } else { "false" } }
+
+// Writes a C function 'minterm' taking the inputs and a pointer per output,
+// which it sets from a switch over the packed inputs.  Unlisted inputs get the
+// default bits or abort().
+pub fn switch_c(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                order: BitOrder, compress: bool) -> String {
+	let inputs: Vec<String> = invars.iter().map(|n| c_name(n)).collect();
+	let outputs: Vec<String> = eqns.iter().map(|e| c_name(&e.varname)).collect();
+	let assign = |values: &[bool]| -> String {
+		let stmts: Vec<String> = outputs.iter().zip(values.iter())
+			.map(|(o, &v)| format!("*{} = {};", o, c_bool(v))).collect();
+		stmts.join(" ")
+	};
+	let params: Vec<String> = inputs.iter().map(|n| format!("bool {}", n))
+		.chain(outputs.iter().map(|n| format!("bool *{}", n))).collect();
+	let panics = unlisted.branch().is_some() && unlisted.policy == DefaultOutput::Panic;
+	let mut s = "#include <stdbool.h>\n".to_string();
+	if panics {
+		s += "#include <stdlib.h>\n";
+	}
+	s += "\n";
+	s += &format!("// {}\n", packing(&inputs, order));
+	s += &format!("void minterm({}) {{\n", params.join(", "));
+	let cast = |n: &str| format!("(unsigned){}", n);
+	s += &format!("\tswitch ({}) {{\n", packed(&inputs, order, &cast));
+	for case in cases(eqns, invars.len(), unlisted, order, compress) {
+		for v in case.values.iter() {
+			s += &format!("\tcase {}:\n", v);
+		}
+		s += &format!("\t\t{} break;\n", assign(&case.outputs));
+	}
+	match (unlisted.branch().is_some(), &unlisted.policy) {
+		(true, DefaultOutput::Bits(b)) => {
+			let b: Vec<bool> = eqns.iter().map(|e| b[e.index]).collect();
+			s += &format!("\tdefault:\n\t\t{} break;\n", assign(&b));
+		},
+		(true, _) => s += "\tdefault:\n\t\tabort(); /* input not in the table */\n",
+		_ => {},
+	}
+	s += "\t}\n";
+	s += "}\n";
+	s
+}
+
+// A tuple of the outputs, or the one output alone.
+pub fn rust_tuple(elts: &[String]) -> String {
+	if elts.len() == 1 { elts[0].clone() } else { format!("({})", elts.join(", ")) }
+}
+
+// Writes a Rust function 'minterm' taking the inputs and returning the
+// outputs, from a match over the packed inputs.  Runs of consecutive values
+// that share an arm are written as ranges.  Unlisted inputs give the default
+// bits or panic.
+pub fn switch_rust(eqns: &[Equation], invars: &[String], unlisted: &Unlisted,
+                   order: BitOrder, compress: bool) -> String {
+	let inputs: Vec<String> = invars.iter().map(|n| rust_name(n)).collect();
+	let params: Vec<String> = inputs.iter().map(|n| format!("{}: bool", n)).collect();
+	let values = |values: &[bool]| -> String {
+		let elts: Vec<String> = values.iter().map(|&v| c_bool(v).to_string()).collect();
+		rust_tuple(&elts)
+	};
+	let ret = rust_tuple(&vec!["bool".to_string(); eqns.len()]);
+	let mut s = format!("// {}  Returns {}.\n", packing(&inputs, order),
+	                    rust_tuple(&eqns.iter().map(|e| rust_name(&e.varname))
+	                               .collect::<Vec<_>>()));
+	s += &format!("pub fn minterm({}) -> {} {{\n", params.join(", "), ret);
+	let cast = |n: &str| format!("({} as u32)", n);
+	s += &format!("\tmatch {} {{\n", packed(&inputs, order, &cast));
+	for case in cases(eqns, invars.len(), unlisted, order, compress) {
+		let pats: Vec<String> = runs(&case.values).iter().map(|&(a, b)| match b - a {
+			0 => a.to_string(),
+			1 => format!("{} | {}", a, b),
+			_ => format!("{}..={}", a, b),
+		}).collect();
+		s += &format!("\t\t{} => {},\n", pats.join(" | "), values(&case.outputs));
+	}
+	match (unlisted.branch().is_some(), &unlisted.policy) {
+		(true, DefaultOutput::Bits(b)) => {
+			let b: Vec<bool> = eqns.iter().map(|e| b[e.index]).collect();
+			s += &format!("\t\t_ => {},\n", values(&b));
+		},
+		(true, _) => s += "\t\t_ => panic!(\"input not in the table\"),\n",
+		_ => s += "\t\t_ => unreachable!(),\n",
+	}
+	s += "\t}\n";
+	s += "}\n";
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse};
+	use super::super::bits::bit_unpack;
+
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	fn names() -> Vec<String> {
+		["a", "b", "c"].iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn small_arms() {
+		let tbl = parse(SMALL.as_bytes(), 0, 3, 2);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names());
+		eqns.iter_mut().for_each(|e| e.simplify());
+		let unlisted = Unlisted::new(DefaultOutput::Zero, &tbl, 3, &names());
+		for &order in [BitOrder::Lsb, BitOrder::Msb].iter() {
+			for &compress in [false, true].iter() {
+				let cases = cases(&eqns, 3, &unlisted, order, compress);
+				assert_eq!(cases.len(), if compress { 4 } else { 8 });
+				// every value of every arm gives the table's outputs.
+				let rows = tbl.index();
+				for case in cases.iter() {
+					for &v in case.values.iter() {
+						let inp = bit_unpack(v, 3, order);
+						assert_eq!(case.outputs, rows.solution(&tbl, &inp), "{} {:?}", v,
+						           order);
+					}
+				}
+				for ent in tbl.table.iter() {
+					assert_eq!(evaluate(&cases, &ent.input, order),
+					           Some(ent.output.clone()));
+				}
+			}
+		}
+		let src = switch_c(&eqns, &names(), &unlisted, BitOrder::Msb, false);
+		assert!(src.contains("// Packs a as bit 2, b as bit 1 and c as bit 0.\n"), "{}",
+		        src);
+		assert!(src.contains("\tswitch ((unsigned)a << 2 | (unsigned)b << 1 | (unsigned)c) \
+		                      {\n\tcase 0:\n\t\t*x = false; *y = true; break;\n"),
+		        "{}", src);
+		let src = switch_rust(&eqns, &names(), &unlisted, BitOrder::Lsb, true);
+		assert!(src.contains("\tmatch (a as u32) | (b as u32) << 1 | (c as u32) << 2 {\n\
+		                      \t\t0 | 5 => (false, true),\n"), "{}", src);
+		// 100, 010 and 110 give 1 1.
+		assert!(src.contains("\t\t1..=3 => (true, true),\n"), "{}", src);
+		assert!(src.ends_with("\t\t_ => unreachable!(),\n\t}\n}\n"), "{}", src);
+	}
+
+	#[test]
+	fn defaults() {
+		// x = a, but 11 is missing.
+		let tbl = parse("0,0,,0\n0,1,,0\n1,0,,1\n".as_bytes(), 0, 2, 1);
+		let invars: Vec<String> = vec!["a".into(), "b".into()];
+		let mut eqns = equations(&tbl, vec!["x"], invars.clone());
+		eqns[0].simplify();
+		let unlisted = Unlisted::new(DefaultOutput::Panic, &tbl, 2, &invars);
+		let cs = cases(&eqns, 2, &unlisted, BitOrder::Msb, false);
+		assert_eq!(evaluate(&cs, &[true, true], BitOrder::Msb), None);
+		let src = switch_c(&eqns, &invars, &unlisted, BitOrder::Msb, false);
+		assert!(src.starts_with("#include <stdbool.h>\n#include <stdlib.h>\n"));
+		assert!(src.contains("\tdefault:\n\t\tabort();"), "{}", src);
+		let unlisted = Unlisted::new(DefaultOutput::Bits(vec![true]), &tbl, 2, &invars);
+		let src = switch_rust(&eqns, &invars, &unlisted, BitOrder::Msb, true);
+		assert!(src.contains("-> bool {\n"), "{}", src);
+		assert!(src.contains("\t\t0 | 1 => false,\n\t\t2 => true,\n\t\t_ => true,\n"),
+		        "{}", src);
+	}
+
+	#[test]
+	fn identifiers() {
+		assert_eq!(c_name("x[0]"), "x_0_");
+		assert_eq!(c_name("int"), "int_");
+		assert_eq!(rust_name("match"), "match_");
+		assert_eq!(runs(&[1, 2, 3, 5, 7, 8]), vec![(1, 3), (5, 5), (7, 8)]);
+	}
+}
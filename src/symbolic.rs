@@ -0,0 +1,234 @@
+// Output groups: several outputs that together hold one value, such as 3 bits
+// selecting one of 6 backends, minimized as a single multi-valued symbol
+// rather than bit by bit.  For each value the group takes on some row, the
+// condition on the inputs giving it is an equation of its own: 1 on the rows
+// with that value, 0 on the rows with another, and a don't-care wherever the
+// table's outputs are.  A value no row has is never asked for, so the
+// encodings the group leaves unused cost nothing.
+use std::collections::BTreeSet;
+use super::{Entry, Equation, Truth, did_you_mean};
+use super::bits::{BitOrder, bit_pack};
+use super::lut::width;
+use super::notation::Notation;
+use super::switch::{packing, rust_name, rust_tuple};
+
+// A --group: its name and its outputs, in the order given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+	pub name: String,
+	pub outputs: Vec<usize>,
+}
+
+// Parses the --group options, "sel=s0,s1,s2", checking that no output is in
+// two groups.
+pub fn parse_groups(specs: Vec<&str>, outvars: &[String]) -> Result<Vec<Group>, String> {
+	let names: Vec<&str> = outvars.iter().map(|s| s.as_str()).collect();
+	let mut groups: Vec<Group> = vec![];
+	for spec in specs {
+		let (name, list) = match spec.find('=') {
+			Some(eq) if !spec[..eq].trim().is_empty() =>
+				(spec[..eq].trim(), &spec[eq + 1..]),
+			_ => return Err(format!("Invalid --group '{}'; expected \
+			                         'name=output,output,...'.", spec)),
+		};
+		let mut outputs: Vec<usize> = vec![];
+		for out in list.split(',').map(|n| n.trim()) {
+			let idx = match names.iter().position(|&n| n == out) {
+				Some(idx) => idx,
+				None => return Err(format!("Unknown output '{}' in --group {}.{}", out,
+				                           name, did_you_mean(out, &names))),
+			};
+			if outputs.contains(&idx) || groups.iter().any(|g| g.outputs.contains(&idx)) {
+				return Err(format!("Output '{}' is in more than one --group.", out));
+			}
+			outputs.push(idx);
+		}
+		if outputs.len() < 2 {
+			return Err(format!("--group {} needs at least two outputs.", name));
+		}
+		groups.push(Group{name: name.to_string(), outputs});
+	}
+	Ok(groups)
+}
+
+// Whether the row gives every output of the group.
+fn given(group: &Group, e: &Entry) -> bool {
+	group.outputs.iter().all(|&o| e.cares(o))
+}
+
+// The values the group takes on the table's rows, in counting order, the
+// first output most significant.  Rows where some output of the group is a
+// don't-care don't give it a value.
+pub fn values(group: &Group, tbl: &Truth) -> Vec<Vec<bool>> {
+	let values: BTreeSet<Vec<bool>> = tbl.table.iter().filter(|e| given(group, e))
+		.map(|e| group.outputs.iter().map(|&o| e.output[o]).collect())
+		.collect();
+	values.into_iter().collect()
+}
+
+// The condition for each of the values, unminimized, in the same order.  The
+// rows that don't give the group a value are don't-cares of every condition.
+pub fn equations(group: &Group, values: &[Vec<bool>], tbl: &Truth,
+                 invars: &Vec<String>) -> Vec<Equation> {
+	values.iter().map(|value| {
+		let mut indicator = Truth{dc: tbl.dc.clone(), ..Truth::default()};
+		for e in tbl.table.iter() {
+			if !given(group, e) {
+				indicator.dc.push(e.input.clone());
+				continue;
+			}
+			let is = group.outputs.iter().zip(value.iter())
+				.all(|(&o, &v)| e.output[o] == v);
+			indicator.table.push(Entry::new(e.input.clone(), vec![is]));
+		}
+		Equation::new(&indicator, 0, &format!("{}={}", group.name, bit_string(value)),
+		              invars)
+	}).collect()
+}
+
+fn bit_string(bits: &[bool]) -> String {
+	bits.iter().map(|&b| if b { '1' } else { '0' }).collect()
+}
+
+// The conditions a line each: "sel = 01 when a'b + c;".
+pub fn text(group: &Group, values: &[Vec<bool>], eqns: &[Equation], n: &Notation) ->
+	String {
+	let mut s = String::new();
+	for (value, eqn) in values.iter().zip(eqns.iter()) {
+		let terms: Vec<String> = eqn.terms.iter().map(|t| n.term(t)).collect();
+		s += &format!("{} = {} when {};\n", group.name, bit_string(value),
+		              terms.join(" + "));
+	}
+	s
+}
+
+// The value the catch-all arm of the match gives: the one whose condition has
+// the most terms, the last of those, so that the longest condition is left
+// out.
+fn fallback(eqns: &[Equation]) -> usize {
+	(0..eqns.len()).max_by_key(|&v| eqns[v].terms.len()).unwrap_or(0)
+}
+
+// The value the match gives the input: that of the first arm whose condition
+// holds, or the catch-all's.
+pub fn matched(eqns: &[Equation], inp: &[bool]) -> usize {
+	let last = fallback(eqns);
+	(0..eqns.len()).find(|&v| v != last && eqns[v].evaluate(inp)).unwrap_or(last)
+}
+
+// A Rust function named for the group, taking the inputs and returning the
+// group's outputs packed into an integer as --bit-order says, from a single
+// match over the inputs: an arm per value, each term of its condition a
+// pattern, and the value with the longest condition the catch-all.
+pub fn rust(group: &Group, values: &[Vec<bool>], eqns: &[Equation], invars: &[String],
+            outvars: &[String], order: BitOrder) -> Result<String, String> {
+	let ty = match width(group.outputs.len()) {
+		Some(ty) => ty,
+		None => return Err(format!("--group {} has too many outputs for an integer.",
+		                           group.name)),
+	};
+	let inputs: Vec<String> = invars.iter().map(|n| rust_name(n)).collect();
+	let outputs: Vec<String> = group.outputs.iter().map(|&o| rust_name(&outvars[o]))
+		.collect();
+	let params: Vec<String> = inputs.iter().map(|n| format!("{}: bool", n)).collect();
+	let literal = |value: &[bool]| format!("0b{:01$b}", bit_pack(value, order),
+	                                       value.len());
+	let mut s = format!("// Returns {} as one value.  {}\n", outputs.join(", "),
+	                    packing(&outputs, order));
+	s += &format!("pub fn {}({}) -> {} {{\n", rust_name(&group.name), params.join(", "),
+	              ty);
+	s += &format!("\tmatch {} {{\n", rust_tuple(&inputs));
+	let last = fallback(eqns);
+	for (v, eqn) in eqns.iter().enumerate().filter(|&(v, _)| v != last) {
+		let pats: Vec<String> = eqn.terms.iter().map(|t| {
+			let elts: Vec<String> = (0..inputs.len()).map(|i| {
+				match t.bits.iter().find(|b| b.0 == i) {
+					Some(&(_, true)) => "true".to_string(),
+					Some(&(_, false)) => "false".to_string(),
+					None => "_".to_string(),
+				}
+			}).collect();
+			rust_tuple(&elts)
+		}).collect();
+		s += &format!("\t\t{} => {},\n", pats.join(" | "), literal(&values[v]));
+	}
+	if let Some(value) = values.get(last) {
+		s += &format!("\t\t_ => {},\n", literal(value));
+	}
+	s += "\t}\n";
+	s += "}\n";
+	Ok(s)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::parse;
+
+	// a 2-bit group, s1 s0, taking 00, 01 and 10 but never 11, beside an
+	// output of its own.
+	const TABLE: &str = "0,0,0,,0,0,1\n0,0,1,,0,0,0\n0,1,0,,0,1,1\n\
+	                             0,1,1,,0,1,0\n1,0,0,,1,0,1\n1,0,1,,1,0,0\n\
+	                             1,1,0,,1,0,1\n1,1,1,,1,0,0\n";
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	fn minimized(group: &Group, tbl: &Truth) -> (Vec<Vec<bool>>, Vec<Equation>) {
+		let values = values(group, tbl);
+		let mut eqns = equations(group, &values, tbl, &names(&["a", "b", "c"]));
+		for eqn in eqns.iter_mut() {
+			eqn.simplify();
+			eqn.canonicalize();
+		}
+		(values, eqns)
+	}
+
+	#[test]
+	fn covers_per_value() {
+		let tbl = parse(TABLE.as_bytes(), 0, 3, 3);
+		let outvars = names(&["s1", "s0", "z"]);
+		let groups = parse_groups(vec!["sel=s1,s0"], &outvars).unwrap();
+		let (values, eqns) = minimized(&groups[0], &tbl);
+		assert_eq!(values, vec![vec![false, false], vec![false, true], vec![true, false]]);
+		assert_eq!(text(&groups[0], &values, &eqns, &Notation::classic()),
+		           "sel = 00 when a'b';\nsel = 01 when a'b;\nsel = 10 when a;\n");
+		// each condition holds exactly on the rows with its value.
+		for (value, eqn) in values.iter().zip(eqns.iter()) {
+			for e in tbl.table.iter() {
+				assert_eq!(eqn.evaluate(&e.input), e.output[..2] == value[..]);
+			}
+		}
+	}
+
+	#[test]
+	fn rust_match() {
+		let tbl = parse(TABLE.as_bytes(), 0, 3, 3);
+		let (invars, outvars) = (names(&["a", "b", "c"]), names(&["s1", "s0", "z"]));
+		let group = Group{name: "sel".to_string(), outputs: vec![0, 1]};
+		let (values, eqns) = minimized(&group, &tbl);
+		let src = rust(&group, &values, &eqns, &invars, &outvars, BitOrder::Msb).unwrap();
+		assert_eq!(src, "// Returns s1, s0 as one value.  Packs s1 as bit 1 and s0 as \
+		                 bit 0.\n\
+		                 pub fn sel(a: bool, b: bool, c: bool) -> u8 {\n\
+		                 \tmatch (a, b, c) {\n\
+		                 \t\t(false, false, _) => 0b00,\n\
+		                 \t\t(false, true, _) => 0b01,\n\
+		                 \t\t_ => 0b10,\n\
+		                 \t}\n}\n");
+		let lsb = rust(&group, &values, &eqns, &invars, &outvars, BitOrder::Lsb).unwrap();
+		assert!(lsb.contains("(false, true, _) => 0b10,"), "{}", lsb);
+	}
+
+	#[test]
+	fn bad_groups() {
+		let outvars = names(&["s1", "s0", "z"]);
+		assert_eq!(parse_groups(vec!["sel=s1,zz"], &outvars).unwrap_err(),
+		           "Unknown output 'zz' in --group sel. Did you mean 'z'?");
+		assert_eq!(parse_groups(vec!["sel=s1,s0", "t=s0,z"], &outvars).unwrap_err(),
+		           "Output 's0' is in more than one --group.");
+		assert!(parse_groups(vec!["s1,s0"], &outvars).is_err());
+		assert!(parse_groups(vec!["sel=s1"], &outvars).is_err());
+	}
+}
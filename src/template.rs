@@ -0,0 +1,92 @@
+// 'minterm template': writes a table listing every input pattern with all
+// outputs 0, in the layout minimize reads, as a start for a new table.  The
+// rows are written a chunk at a time as they are enumerated, so a template
+// within --max-enumerate never sits in memory whole; above it, --sample
+// lists a few patterns picked at random instead.
+use minterm::{Entry, InputEncoding, output_names, write_header, write_rows};
+use minterm::bits::bit_pack_wide;
+use minterm::enumerate::{self, Inputs};
+use super::{Failure, bit_order, encodings, max_enumerate, too_many};
+
+pub const USAGE: &str = concat!("
+Usage: minterm template (--bits=<n> | --ivar=<foo>...) --ovar=<bar>... [options]
+       minterm template --help
+
+Writes a table listing every input pattern, in counting order of the inputs
+packed as --bit-order says, with all outputs 0.
+
+Options:
+  --bits=<n>         The number of inputs, for inputs named i0, i1, ...
+                     instead of by --ivar.
+  --max-enumerate=<n>  The most inputs whose every pattern may be listed.
+                     [default: 24]
+  --sample=<n>       List only this many patterns, picked at random, for a
+                     table of too many inputs to list them all, whose other
+                     inputs are to be don't-cares or what the default output
+                     of minimize gives.
+  --seed=<n>         Seeds the picking of --sample.  [default: 1]", table_options!());
+
+// How many rows are made and written at a time.
+const CHUNK: usize = 4096;
+
+pub fn run(args: &::docopt::ArgvMap) -> Result<(), Failure> {
+	let invars: Vec<String> = match args.get_str("--bits") {
+		"" => args.get_vec("--ivar").iter().map(|s| s.to_string()).collect(),
+		s => match s.parse::<usize>() {
+			Ok(n) if n > 0 => (0..n).map(|i| format!("i{}", i)).collect(),
+			_ => fail!(Usage, "Invalid --bits '{}'; expected a positive integer.", s),
+		},
+	};
+	let nin = invars.len();
+	let sample = match args.get_str("--sample") {
+		"" => None,
+		s => match s.parse::<u64>() {
+			Ok(n) if n > 0 => Some(n),
+			_ => fail!(Usage, "Invalid --sample '{}'; expected a positive integer.", s),
+		},
+	};
+	let seed = match args.get_str("--seed").parse::<u64>() {
+		Ok(n) => n,
+		Err(_) => fail!(Usage, "Invalid --seed '{}'; expected an integer.",
+		                args.get_str("--seed")),
+	};
+	let outvars = output_names(args.get_vec("--ovar"), args.get_str("--ovar-width"))
+		.map_err(Failure::Usage)?;
+	let (inenc, outenc) = encodings(args)?;
+	let order = bit_order(args)?;
+	let zeros = vec![false; outvars.len()];
+	let row = |inp: Vec<bool>| Entry::new(inp, zeros.clone());
+	let stdout = ::std::io::stdout();
+	let mut out = stdout.lock();
+	match sample {
+		None => {
+			enumerate::check(nin, max_enumerate(args)?, "; give --sample=<n> to list n \
+			                                            patterns picked at random")
+				.map_err(too_many)?;
+			write_header(&mut out, &invars, &outvars, inenc, outenc)
+				.map_err(Failure::Usage)?;
+			for chunk in Inputs::every(nin).ordered(order).chunks(CHUNK) {
+				let rows: Vec<Entry> = chunk.into_iter().map(&row).collect();
+				write_rows(&mut out, rows.iter(), inenc, outenc).map_err(Failure::Usage)?;
+			}
+		},
+		Some(n) => {
+			if let InputEncoding::Row{..} = inenc {
+				fail!(Usage, "--sample can't be combined with --input-encoding row, \
+				              which lists every pattern.");
+			}
+			let most = if let InputEncoding::Index{..} = inenc { 64 } else { 128 };
+			if nin > most {
+				fail!(Usage, "--sample handles at most 128 inputs, or 64 with \
+				              --input-encoding index.");
+			}
+			let mut picked = enumerate::sample(nin, n, seed);
+			picked.sort_by_key(|inp| bit_pack_wide(inp, order));
+			write_header(&mut out, &invars, &outvars, inenc, outenc)
+				.map_err(Failure::Usage)?;
+			let rows: Vec<Entry> = picked.into_iter().map(&row).collect();
+			write_rows(&mut out, rows.iter(), inenc, outenc).map_err(Failure::Usage)?;
+		},
+	}
+	Ok(())
+}
@@ -0,0 +1,224 @@
+// Tables shared by the tests and the benchmarks, each with the names and
+// options the command line needs to read it.  The random ones come from a
+// fixed seed, so the same call always gives the same table.
+//
+//   dense         every input pattern, with random outputs.
+//   sparse        some of the input patterns, the others don't-cares.
+//   parity        the worst case for sums of products: no two minterms merge.
+//   decoder       an output per input pattern, 1 for that pattern alone.
+//   noisy         a function of a few inputs with some outputs flipped: a
+//                 few large primes and many small ones, a large chart.
+//   gated         an enable output and data outputs that are noise while it
+//                 is 0, for --observe.
+//   capabilities  which libraries a build of a GPU program must link, from
+//                 what it asks for and what was found, as in example_head.
+//
+// and mangle makes a table file malformed, to show the readers don't panic.
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::{Truth, InputEncoding, OutputEncoding, write_encoded};
+use super::bits::{BitOrder, bit_pack, bit_unpack};
+use super::rng::Rng;
+
+pub struct Fixture {
+	pub name: String,
+	pub truth: Truth,
+	pub invars: Vec<String>,
+	pub outvars: Vec<String>,
+	// the --default-output a table that doesn't list every input needs.
+	pub default_output: Option<&'static str>,
+}
+
+impl Fixture {
+	// The table as the command line reads it, with the two header lines.
+	pub fn csv(&self) -> String {
+		let mut out: Vec<u8> = vec![];
+		// all the fixtures are binary tables, which always write.
+		write_encoded(&mut out, &self.truth, &self.invars, &self.outvars,
+		              InputEncoding::Binary, OutputEncoding::Binary).unwrap();
+		String::from_utf8(out).unwrap()
+	}
+
+	// Writes the table to 'dir' as <name>.csv, returning its path.
+	pub fn write(&self, dir: &Path) -> PathBuf {
+		let path = dir.join(format!("{}.csv", self.name));
+		fs::write(&path, self.csv()).unwrap();
+		path
+	}
+
+	// The options naming the variables, and the --default-output if needed.
+	pub fn args(&self) -> Vec<String> {
+		let mut rv: Vec<String> = vec![];
+		for v in self.invars.iter() {
+			rv.push("--ivar".to_string());
+			rv.push(v.clone());
+		}
+		for v in self.outvars.iter() {
+			rv.push("--ovar".to_string());
+			rv.push(v.clone());
+		}
+		if let Some(d) = self.default_output {
+			rv.push("--default-output".to_string());
+			rv.push(d.to_string());
+		}
+		rv
+	}
+}
+
+fn names(prefix: &str, n: usize) -> Vec<String> {
+	(0..n).map(|i| format!("{}{}", prefix, i)).collect()
+}
+
+fn fixture(name: String, truth: Truth, invars: Vec<String>, outvars: Vec<String>) ->
+	Fixture {
+	Fixture{name, truth, invars, outvars, default_output: None}
+}
+
+pub fn dense(nin: usize, nout: usize, seed: u64) -> Fixture {
+	let mut rng = Rng::new(seed);
+	let mut truth = Truth::default();
+	for i in 0..1u64 << nin {
+		let output = (0..nout).map(|_| rng.bit()).collect();
+		truth.table.push(super::Entry::new(bit_unpack(i, nin, BitOrder::Msb), output));
+	}
+	fixture(format!("dense-{}x{}-{}", nin, nout, seed), truth, names("i", nin),
+	        names("o", nout))
+}
+
+// Lists about one input pattern in 'one_in' and makes the rest don't-cares,
+// which the command line gets from --default-output dc.
+pub fn sparse(nin: usize, nout: usize, one_in: u64, seed: u64) -> Fixture {
+	let mut rng = Rng::new(seed);
+	let mut truth = Truth::default();
+	for i in 0..1u64 << nin {
+		if !rng.next_u64().is_multiple_of(one_in) {
+			truth.dc.push(bit_unpack(i, nin, BitOrder::Msb));
+			continue;
+		}
+		let output = (0..nout).map(|_| rng.bit()).collect();
+		truth.table.push(super::Entry::new(bit_unpack(i, nin, BitOrder::Msb), output));
+	}
+	let mut f = fixture(format!("sparse-{}x{}-{}", nin, nout, seed), truth,
+	                    names("i", nin), names("o", nout));
+	f.default_output = Some("dc");
+	f
+}
+
+pub fn parity(nin: usize) -> Fixture {
+	let truth = Truth::from_fn(nin, 1, |inp| {
+		vec![inp.iter().filter(|&&b| b).count() % 2 == 1]
+	}).unwrap();
+	fixture(format!("parity-{}", nin), truth, names("i", nin), vec!["p".to_string()])
+}
+
+// Output k is 1 for the input pattern k alone, the first input the most
+// significant bit: the outputs are mutually exclusive, and one is always 1.
+pub fn decoder(nin: usize) -> Fixture {
+	let truth = Truth::from_fn(nin, 1 << nin, |inp| {
+		let k = bit_pack(inp, BitOrder::Msb) as usize;
+		(0..1 << nin).map(|o| o == k).collect()
+	}).unwrap();
+	fixture(format!("decoder-{}", nin), truth, names("i", nin), names("o", 1 << nin))
+}
+
+// The majority of the first five inputs, with about one output in 'one_in'
+// flipped.  At least 5 inputs.
+pub fn noisy(nin: usize, one_in: u64, seed: u64) -> Fixture {
+	assert!(nin >= 5);
+	let mut rng = Rng::new(seed);
+	let mut truth = Truth::default();
+	for i in 0..1u64 << nin {
+		let input = bit_unpack(i, nin, BitOrder::Msb);
+		let majority = input[..5].iter().filter(|&&b| b).count() >= 3;
+		let flipped = rng.next_u64().is_multiple_of(one_in);
+		truth.table.push(super::Entry::new(input, vec![majority ^ flipped]));
+	}
+	fixture(format!("noisy-{}-{}", nin, seed), truth, names("i", nin),
+	        vec!["m".to_string()])
+}
+
+// An enable output, the first input, and 'ndata' data outputs: while it is
+// set, data output k copies input k + 1, and while it isn't, random bits.
+pub fn gated(nin: usize, ndata: usize, seed: u64) -> Fixture {
+	assert!(ndata < nin);
+	let mut rng = Rng::new(seed);
+	let mut truth = Truth::default();
+	for i in 0..1u64 << nin {
+		let input = bit_unpack(i, nin, BitOrder::Msb);
+		let mut output = vec![input[0]];
+		output.extend((1..ndata + 1).map(|k| if input[0] { input[k] } else { rng.bit() }));
+		truth.table.push(super::Entry::new(input, output));
+	}
+	let mut outvars = vec!["en".to_string()];
+	outvars.extend(names("d", ndata));
+	fixture(format!("gated-{}x{}-{}", nin, ndata, seed), truth, names("i", nin), outvars)
+}
+
+// Whether a program needs to link each of the OpenGL libraries.  A program
+// that asks for no component gets OpenGL and GLX, unless only the legacy GL
+// library was found, which it then gets alone; components asked for are always
+// needed.  Whether the libraries are required at all doesn't change which.
+pub fn capabilities() -> Fixture {
+	let invars = ["REQUIRED", "WANT_OGL", "WANT_GLX", "WANT_EGL", "HAVE_OGL",
+	              "HAVE_GLX", "HAVE_EGL", "HAVE_GL"];
+	let outvars = ["NEED_OGL", "NEED_GLX", "NEED_EGL", "NEED_GL"];
+	let truth = Truth::from_fn(invars.len(), outvars.len(), |inp| {
+		let (want_ogl, want_glx, want_egl) = (inp[1], inp[2], inp[3]);
+		let (have_ogl, have_gl) = (inp[4], inp[7]);
+		let default = !want_ogl && !want_glx && !want_egl;
+		let legacy = default && have_gl && !have_ogl;
+		vec![want_ogl || default && !legacy, want_glx || default && !legacy, want_egl,
+		     legacy]
+	}).unwrap();
+	fixture("capabilities".to_string(), truth,
+	        invars.iter().map(|s| s.to_string()).collect(),
+	        outvars.iter().map(|s| s.to_string()).collect())
+}
+
+// The bytes mangle puts in: those that mean something to the readers, and
+// some that aren't UTF-8 or aren't ASCII.
+const MANGLING: &[u8] = b"01-~,. \n#io\xff\xc3\xa99";
+
+// The file with a few random edits: a byte changed, put in or taken out, or
+// the rest of the file cut off.
+pub fn mangle(data: &[u8], seed: u64) -> Vec<u8> {
+	let mut rng = Rng::new(seed);
+	let mut rv = data.to_vec();
+	for _ in 0..rng.next_u64() % 4 + 1 {
+		let at = if rv.is_empty() { 0 } else { rng.next_u64() as usize % rv.len() };
+		let byte = MANGLING[rng.next_u64() as usize % MANGLING.len()];
+		match rng.next_u64() % 8 {
+			0..=2 if !rv.is_empty() => rv[at] = byte,
+			3..=5 => rv.insert(at, byte),
+			6 if !rv.is_empty() => { rv.remove(at); },
+			7 => rv.truncate(at),
+			_ => {},
+		}
+	}
+	rv
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{parse, HEADER_LINES};
+
+	#[test]
+	fn fixtures() {
+		let d = dense(6, 2, 1);
+		assert_eq!(d.truth.len(), 64);
+		assert_eq!(d.csv(), dense(6, 2, 1).csv());
+		assert!(d.csv() != dense(6, 2, 2).csv());
+		let back = parse(d.csv().as_bytes(), HEADER_LINES, 6, 2);
+		assert_eq!(back.table, d.truth.table);
+		let s = sparse(8, 1, 4, 1);
+		assert!(s.truth.len() > 32 && s.truth.len() < 96, "{}", s.truth.len());
+		assert_eq!(s.args().last().map(|s| s.as_str()), Some("dc"));
+		// the first rows of example_head.
+		let c = capabilities();
+		assert_eq!(c.truth.table[0].output, vec![true, true, false, false]);
+		assert_eq!(c.truth.table[1].output, vec![false, false, false, true]);
+		assert_eq!(mangle(b"0,1,,1\n", 3), mangle(b"0,1,,1\n", 3));
+		assert!((0..8).any(|s| mangle(b"0,1,,1\n", s) != b"0,1,,1\n"));
+	}
+}
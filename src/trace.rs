@@ -0,0 +1,208 @@
+// Profiling the equations against a trace: the input patterns a system was
+// actually seen to take, each with how often.  Every term of every equation
+// is evaluated on them, and counts the traced inputs it holds for; a term
+// holding for none is logic the trace never exercises, to be reviewed first.
+//
+// Pruning goes further: the table's rows whose inputs the trace doesn't have
+// become don't-cares, and the table is minimized again.  The equations that
+// gives agree with the table only on the traced inputs, so they are only as
+// good as the trace; they are printed as such.
+use std::collections::BTreeMap;
+use super::{Equation, Truth, clean_cell};
+use super::notation::Notation;
+
+// The traced inputs and how often each was seen.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+	pub counts: BTreeMap<Vec<bool>, u64>,
+}
+
+impl Trace {
+	// Reads a trace of inputs named 'invars': a line per input pattern, its
+	// 0/1 cells separated by commas or written together as '0110', then
+	// optionally a count, the times it was seen, else once.  Empty cells are
+	// skipped, so that a count may follow an empty column as a table's outputs
+	// do.  A first line naming the inputs is skipped, as are blank lines and
+	// those starting with '#'.  A pattern given more than once has the counts
+	// of all its lines.
+	pub fn parse(text: &str, invars: &[String]) -> Result<Self, String> {
+		let nbits = invars.len();
+		let mut rv = Trace::default();
+		let mut first = true;
+		for (k, line) in text.lines().enumerate() {
+			let cells: Vec<&str> = line.split(',').map(clean_cell)
+				.filter(|c| !c.is_empty()).collect();
+			if cells.is_empty() || cells[0].starts_with('#') {
+				continue;
+			}
+			let header = first && cells.len() >= nbits &&
+				cells.iter().zip(invars.iter()).all(|(c, v)| c == v);
+			first = false;
+			if header {
+				continue;
+			}
+			let packed = cells[0].len() == nbits && nbits > 1;
+			let (bits, rest): (Vec<&str>, &[&str]) = if packed {
+				(cells[0].split("").filter(|c| !c.is_empty()).collect(), &cells[1..])
+			} else if cells.len() >= nbits {
+				(cells[..nbits].to_vec(), &cells[nbits..])
+			} else {
+				(vec![], &cells[..])
+			};
+			let input: Option<Vec<bool>> = bits.iter().map(|&c| match c {
+				"0" => Some(false),
+				"1" => Some(true),
+				_ => None,
+			}).collect();
+			let input = match input {
+				Some(ref inp) if inp.len() == nbits && rest.len() <= 1 => inp.clone(),
+				_ => return Err(format!("line {}: expected the {} input(s) as 0s and 1s, \
+				                         optionally followed by a count, not '{}'.", k + 1,
+				                        nbits, line.trim())),
+			};
+			let count = match rest.first() {
+				None => 1,
+				Some(c) => match c.parse::<u64>() {
+					Ok(n) => n,
+					Err(_) => return Err(format!("line {}: count '{}' is not a \
+					                              non-negative integer.", k + 1, c)),
+				},
+			};
+			*rv.counts.entry(input).or_insert(0) += count;
+		}
+		Ok(rv)
+	}
+
+	// The traced inputs, each as often as it was seen.
+	pub fn total(&self) -> u64 {
+		self.counts.values().sum()
+	}
+
+	// Whether the input was seen at all; one with a count of 0 wasn't.
+	pub fn has(&self, inp: &[bool]) -> bool {
+		self.counts.get(inp).is_some_and(|&n| n > 0)
+	}
+}
+
+// How often an equation's terms held on the trace, in the order of its
+// terms, and how often the equation did.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Activations {
+	pub terms: Vec<u64>,
+	pub ones: u64,
+}
+
+impl Activations {
+	// The terms that never held, by their position.
+	pub fn never(&self) -> Vec<usize> {
+		(0..self.terms.len()).filter(|&t| self.terms[t] == 0).collect()
+	}
+}
+
+pub fn activations(eqn: &Equation, trace: &Trace) -> Activations {
+	let mut rv = Activations{terms: vec![0; eqn.terms.len()], ones: 0};
+	for (inp, &n) in trace.counts.iter() {
+		let mut one = false;
+		for (t, term) in eqn.terms.iter().enumerate() {
+			if term.evaluate(inp) {
+				rv.terms[t] += n;
+				one = true;
+			}
+		}
+		if one {
+			rv.ones += n;
+		}
+	}
+	rv
+}
+
+// The profile as printed: for each equation how often it was 1, then a line
+// per term with its count, those never activated marked.
+pub fn report(eqns: &[Equation], acts: &[Activations], total: u64, n: &Notation) ->
+	String {
+	let mut s = String::new();
+	for (eqn, a) in eqns.iter().zip(acts.iter()) {
+		s += &format!("{}: 1 on {} of {} traced input(s)\n", n.name(&eqn.varname), a.ones,
+		              total);
+		let width = a.terms.iter().map(|c| c.to_string().len()).max().unwrap_or(1);
+		for (term, &c) in eqn.terms.iter().zip(a.terms.iter()) {
+			s += &format!("  {:>w$}  {}{}\n", c, n.term(term),
+			              if c == 0 { "  (never activated)" } else { "" }, w = width);
+		}
+	}
+	s
+}
+
+// The table with each row whose input the trace doesn't have a don't-care,
+// to minimize for the traced inputs alone.
+pub fn untraced(tbl: &Truth, trace: &Trace) -> Truth {
+	let mut rv = Truth{table: vec![], dc: tbl.dc.clone()};
+	for e in tbl.table.iter() {
+		if trace.has(&e.input) {
+			rv.table.push(e.clone());
+		} else {
+			rv.dc.push(e.input.clone());
+		}
+	}
+	rv
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, minimize, parse};
+	use super::super::exact::Budget;
+	use super::super::progress::Silent;
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn traced_terms() {
+		// x = ab + a'c and y = b'c' + ac; the trace has ab and b'c' hold, and
+		// neither a'c nor ac.
+		let csv = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,0,0\n0,1,1,,1,0\n1,0,0,,0,1\n\
+		           1,0,1,,0,1\n1,1,0,,1,0\n1,1,1,,1,1\n";
+		let tbl = parse(csv.as_bytes(), 0, 3, 2);
+		let invars = names(&["a", "b", "c"]);
+		let mut eqns = equations(&tbl, vec!["x", "y"], invars.clone());
+		minimize(&mut eqns, 3, true, &[1, 1, 1], &mut Budget::unlimited(), &mut Silent);
+		let strs: Vec<String> = eqns.iter().map(|e| e.to_string()).collect();
+		assert_eq!(strs, vec!["x = a'c + ab + ;", "y = b'c' + ac + ;"]);
+		let text = "a,b,c,,count\n# seen in the field\n110,,3\n0,0,0,,2\n\n010\n";
+		let trace = Trace::parse(text, &invars).unwrap();
+		assert_eq!(trace.total(), 6);
+		let acts: Vec<Activations> = eqns.iter().map(|e| activations(e, &trace)).collect();
+		assert_eq!(acts, vec![Activations{terms: vec![0, 3], ones: 3},
+		                      Activations{terms: vec![2, 0], ones: 2}]);
+		assert_eq!(acts[0].never(), vec![0]);
+		assert_eq!(report(&eqns, &acts, trace.total(), &Notation::classic()),
+		           "x: 1 on 3 of 6 traced input(s)\n  0  a'c  (never activated)\n  3  ab\n\
+		            y: 1 on 2 of 6 traced input(s)\n  2  b'c'\n  0  ac  (never \
+		            activated)\n");
+		// on the traced inputs alone, x is a and y is b'.
+		let pruned = untraced(&tbl, &trace);
+		assert_eq!((pruned.table.len(), pruned.dc.len()), (3, 5));
+		let mut eqns = equations(&pruned, vec!["x", "y"], invars.clone());
+		minimize(&mut eqns, 3, true, &[1, 1, 1], &mut Budget::unlimited(), &mut Silent);
+		let strs: Vec<String> = eqns.iter().map(|e| e.to_string()).collect();
+		assert_eq!(strs, vec!["x = a + ;", "y = b' + ;"]);
+	}
+
+	#[test]
+	fn bad_traces() {
+		let invars = names(&["a", "b"]);
+		assert_eq!(Trace::parse("0,1,2,3\n", &invars).unwrap_err(),
+		           "line 1: expected the 2 input(s) as 0s and 1s, optionally followed by \
+		            a count, not '0,1,2,3'.");
+		assert_eq!(Trace::parse("01\n0,2\n", &invars).unwrap_err(),
+		           "line 2: expected the 2 input(s) as 0s and 1s, optionally followed by \
+		            a count, not '0,2'.");
+		assert_eq!(Trace::parse("01,x\n", &invars).unwrap_err(),
+		           "line 1: count 'x' is not a non-negative integer.");
+		// a count of 0 doesn't count as seen.
+		let trace = Trace::parse("11,0\n", &invars).unwrap();
+		assert!(!trace.has(&[true, true]));
+	}
+}
@@ -0,0 +1,207 @@
+// Test vectors: a small set of input patterns, each with the outputs the
+// minimized equations give it, such that every term of every equation is
+// true for at least one of them and every output is 0 for at least one.  A
+// circuit or program built from the equations that passes them has none of
+// its products stuck at 0 and no output stuck at 1.
+//
+// The inputs are taken from the table's rows where one will do, so that the
+// expected outputs are the table's own; a term true on no listed row (it
+// covers only don't-cares and inputs the table doesn't list) gets the input
+// setting its literals and nothing else.
+use super::{Equation, Truth, write_encoded, InputEncoding, OutputEncoding};
+
+// An input pattern and the outputs expected of it, in the equations' order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vector {
+	pub input: Vec<bool>,
+	pub output: Vec<bool>,
+}
+
+// The inputs to test the equations with: the vectors already picked are
+// reused wherever they do, so that one input often serves several terms.
+fn inputs(eqns: &[Equation], tbl: &Truth, nbits: usize) -> Vec<Vec<bool>> {
+	let mut rv: Vec<Vec<bool>> = vec![];
+	for eqn in eqns.iter() {
+		for term in eqn.terms.iter() {
+			if rv.iter().any(|inp| term.evaluate(inp)) {
+				continue;
+			}
+			let inp = match tbl.table.iter().find(|e| term.evaluate(&e.input)) {
+				Some(e) => e.input.clone(),
+				None => {
+					let mut inp = vec![false; nbits];
+					for &(idx, val) in term.bits.iter() {
+						inp[idx] = val;
+					}
+					inp
+				},
+			};
+			rv.push(inp);
+		}
+	}
+	for eqn in eqns.iter() {
+		if rv.iter().any(|inp| !eqn.evaluate(inp)) {
+			continue;
+		}
+		let row = tbl.table.iter().map(|e| &e.input).chain(tbl.dc.iter())
+			.find(|inp| !eqn.evaluate(inp));
+		if let Some(inp) = row {
+			rv.push(inp.clone());
+		}
+	}
+	rv
+}
+
+// Picks the vectors for the equations and checks them; see verify.
+pub fn select(eqns: &[Equation], tbl: &Truth, nbits: usize) ->
+	Result<Vec<Vector>, String> {
+	let vectors: Vec<Vector> = inputs(eqns, tbl, nbits).into_iter().map(|inp| {
+		let output = eqns.iter().map(|e| e.evaluate(&inp)).collect();
+		Vector{input: inp, output}
+	}).collect();
+	verify(&vectors, eqns, tbl)?;
+	Ok(vectors)
+}
+
+// Checks that the vectors are distinct, that every term is true for one of
+// them, that every output is 0 for one of them unless it is 1 on every input
+// the table lists, and that the expected outputs are the table's on the rows
+// it lists, where they aren't don't-cares.
+pub fn verify(vectors: &[Vector], eqns: &[Equation], tbl: &Truth) -> Result<(), String> {
+	let rows = tbl.index();
+	for (k, v) in vectors.iter().enumerate() {
+		if vectors[..k].iter().any(|w| w.input == v.input) {
+			return Err(format!("Vector {} repeats an earlier input.", k + 1));
+		}
+		if let Some(e) = rows.position(&v.input).map(|r| &tbl.table[r]) {
+			for (o, eqn) in eqns.iter().enumerate() {
+				if e.cares(eqn.index) && e.output[eqn.index] != v.output[o] {
+					return Err(format!("Vector {} expects {} = {}, but the table gives \
+					                    {}.", k + 1, eqn.varname, v.output[o] as u8,
+					                   e.output[eqn.index] as u8));
+				}
+			}
+		}
+	}
+	for eqn in eqns.iter() {
+		let inactive = eqn.terms.iter()
+			.find(|t| !vectors.iter().any(|v| t.evaluate(&v.input)));
+		if let Some(t) = inactive {
+			return Err(format!("No vector activates the term {} of {}.", t, eqn.varname));
+		}
+		let falsified = vectors.iter().any(|v| !eqn.evaluate(&v.input));
+		let constant = tbl.table.iter().map(|e| &e.input).chain(tbl.dc.iter())
+			.all(|inp| eqn.evaluate(inp));
+		if !falsified && !constant {
+			return Err(format!("No vector sets {} to 0.", eqn.varname));
+		}
+	}
+	Ok(())
+}
+
+// The vectors as a table in minterm's own CSV format, binary encoded.
+pub fn csv(vectors: &[Vector], invars: &[String], outvars: &[String]) -> String {
+	let tbl = Truth::new(vectors.iter().map(|v| v.input.clone()).collect(),
+	                     vectors.iter().map(|v| v.output.clone()).collect());
+	let mut buf: Vec<u8> = vec![];
+	write_encoded(&mut buf, &tbl, invars, outvars, InputEncoding::Binary,
+	              OutputEncoding::Binary).expect("binary tables can always be written");
+	String::from_utf8(buf).unwrap()
+}
+
+fn list(bits: &[bool], one: &str, zero: &str) -> String {
+	let values: Vec<&str> = bits.iter().map(|&b| if b { one } else { zero }).collect();
+	values.join(", ")
+}
+
+// The vectors as a Rust const array of (inputs, outputs) pairs.
+pub fn rust(vectors: &[Vector], invars: &[String], outvars: &[String]) -> String {
+	let mut s = format!("// Test vectors: the inputs {} and the outputs {} expected of \
+	                     them.\n", invars.join(", "), outvars.join(", "));
+	s += &format!("pub const VECTORS: [([bool; {}], [bool; {}]); {}] = [\n", invars.len(),
+	              outvars.len(), vectors.len());
+	for v in vectors.iter() {
+		s += &format!("\t([{}], [{}]),\n", list(&v.input, "true", "false"),
+		              list(&v.output, "true", "false"));
+	}
+	s += "];\n";
+	s
+}
+
+// The vectors as a C array, each row the inputs followed by the outputs.
+pub fn c(vectors: &[Vector], invars: &[String], outvars: &[String]) -> String {
+	let mut s = format!("/* Test vectors: the inputs {} followed by the outputs {} \
+	                     expected of them. */\n", invars.join(", "), outvars.join(", "));
+	s += &format!("static const unsigned char minterm_vectors[{}][{}] = {{\n",
+	              vectors.len(), invars.len() + outvars.len());
+	for v in vectors.iter() {
+		s += &format!("\t{{{}, {}}},\n", list(&v.input, "1", "0"),
+		              list(&v.output, "1", "0"));
+	}
+	s += "};\n";
+	s
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use super::super::{equations, parse};
+
+	// the example at the top of lib.rs.
+	const SMALL: &str = "0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n0,1,1,,0,0\n\
+	                             1,0,0,,1,1\n1,0,1,,0,1\n1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	fn names(ns: &[&str]) -> Vec<String> {
+		ns.iter().map(|n| n.to_string()).collect()
+	}
+
+	#[test]
+	fn every_term_activated() {
+		let tbl = parse(SMALL.as_bytes(), 0, 3, 2);
+		let mut eqns = equations(&tbl, vec!["x", "y"], names(&["a", "b", "c"]));
+		for eqn in eqns.iter_mut() {
+			eqn.simplify();
+		}
+		let vectors = select(&eqns, &tbl, 3).unwrap();
+		for eqn in eqns.iter() {
+			for t in eqn.terms.iter() {
+				assert!(vectors.iter().any(|v| t.evaluate(&v.input)), "{} of {}", t, eqn);
+			}
+			assert!(vectors.iter().any(|v| !eqn.evaluate(&v.input)), "{}", eqn);
+		}
+		assert!(vectors.len() < tbl.len(), "{:?}", vectors);
+		for v in vectors.iter() {
+			assert_eq!(v.output, tbl.solution(v.input.clone()));
+		}
+	}
+
+	#[test]
+	fn unlisted_terms() {
+		// the table doesn't list 11, which x = ab' + ab covers: its term ab is
+		// true on no row, so gets the input 11 of its literals.
+		let tbl = parse("0,0,,0\n0,1,,0\n1,0,,1\n".as_bytes(), 0, 2, 1);
+		let eqn = Equation::parse("x = ab' + ab", &names(&["a", "b"])).unwrap();
+		let vectors = select(std::slice::from_ref(&eqn), &tbl, 2).unwrap();
+		let inputs: Vec<Vec<bool>> = vectors.iter().map(|v| v.input.clone()).collect();
+		assert_eq!(inputs, vec![vec![true, false], vec![true, true], vec![false, false]]);
+		// a vector the table contradicts.
+		let bad = vec![Vector{input: vec![true, false], output: vec![false]}];
+		assert_eq!(verify(&bad, &[eqn], &tbl).unwrap_err(),
+		           "Vector 1 expects x = 0, but the table gives 1.");
+	}
+
+	#[test]
+	fn formats() {
+		let v = vec![Vector{input: vec![true, false], output: vec![true]}];
+		let (invars, outvars) = (names(&["a", "b"]), names(&["x"]));
+		assert_eq!(rust(&v, &invars, &outvars),
+		           "// Test vectors: the inputs a, b and the outputs x expected of them.\n\
+		            pub const VECTORS: [([bool; 2], [bool; 1]); 1] = [\n\
+		            \t([true, false], [true]),\n];\n");
+		assert_eq!(c(&v, &invars, &outvars),
+		           "/* Test vectors: the inputs a, b followed by the outputs x expected of \
+		            them. */\nstatic const unsigned char minterm_vectors[1][3] = {\n\
+		            \t{1, 0, 1},\n};\n");
+		assert!(csv(&v, &invars, &outvars).ends_with("1,0,,1\n"));
+	}
+}
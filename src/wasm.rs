@@ -0,0 +1,145 @@
+// The minimizer for a web page, built with the 'wasm' feature:
+//
+//   wasm-pack build --target web -- --features wasm
+//
+// exports minimize_csv(csv, nin, nout, options), taking a table as the
+// command line reads it, with its two header lines, and options as JSON:
+//
+//   {"format": "text", "default_output": "dc", "algorithm": "exact",
+//    "max_steps": 100000, "bit_order": "msb", "invars": ["a", "b", "c"],
+//    "outvars": ["x", "y"]}
+//
+// all of which may be left out; the names default to those in the first
+// header line.  It returns JSON with the equations and any warnings about the
+// table:
+//
+//   {"output": "x = ...;\ny = ...;\n", "minimal": true, "warnings": []}
+//
+// or throws an Error with the message the command line would print.  There is
+// no --timeout: the browser has no clock the search could use.
+use std::panic;
+use serde_json::{self, Value};
+use wasm_bindgen::prelude::*;
+use super::{Cells, HEADER_LINES, InputEncoding, OutputEncoding, parse_cells};
+use super::bits::BitOrder;
+use super::embed::{self, Error};
+
+#[wasm_bindgen]
+pub fn minimize_csv(csv: &str, nin: usize, nout: usize, options: &str) ->
+	Result<String, JsError> {
+	// panics abort on wasm32-unknown-unknown, which JavaScript sees as a
+	// RuntimeError; where they unwind they're caught here.
+	match panic::catch_unwind(|| run(csv, nin, nout, options)) {
+		Ok(Ok(json)) => Ok(json),
+		Ok(Err(msg)) => Err(JsError::new(&msg)),
+		Err(_) => Err(JsError::new("minterm panicked; this is a bug.")),
+	}
+}
+
+// The names the options give under 'key', else the non-empty cells of the
+// first header line from 'skip' on.
+fn names(opts: &Value, key: &str, header: &[&str], skip: usize, n: usize) ->
+	Result<Vec<String>, String> {
+	let names: Vec<String> = match opts.get(key) {
+		Some(Value::Array(vs)) => {
+			vs.iter().map(|v| v.as_str().map(|s| s.to_string())).collect::<Option<_>>()
+				.ok_or(format!("Option '{}' must be a list of strings.", key))?
+		},
+		Some(_) => return Err(format!("Option '{}' must be a list of strings.", key)),
+		None => header.iter().skip(skip).take(n).map(|s| s.to_string()).collect(),
+	};
+	if names.len() != n {
+		return Err(format!("Expected {} {} names; got {}.", n, key, names.len()));
+	}
+	Ok(names)
+}
+
+fn string_option(opts: &Value, key: &str) -> Result<Option<String>, String> {
+	match opts.get(key) {
+		None | Some(&Value::Null) => Ok(None),
+		Some(Value::String(s)) => Ok(Some(s.clone())),
+		Some(_) => Err(format!("Option '{}' must be a string.", key)),
+	}
+}
+
+// The work of minimize_csv, with errors as messages.
+fn run(csv: &str, nin: usize, nout: usize, options: &str) -> Result<String, String> {
+	let opts: Value = match options.trim() {
+		"" => Value::Object(Default::default()),
+		s => serde_json::from_str(s).map_err(|e| format!("Invalid options: {}", e))?,
+	};
+	if !opts.is_object() {
+		return Err("The options must be a JSON object.".to_string());
+	}
+	let mut embedded = embed::Options::default();
+	if let Some(f) = string_option(&opts, "format")? {
+		embedded.format = f;
+	}
+	embedded.default_output = string_option(&opts, "default_output")?;
+	embedded.exact = match string_option(&opts, "algorithm")? {
+		None => false,
+		Some(ref a) if a == "greedy" => false,
+		Some(ref a) if a == "exact" => true,
+		Some(a) => return Err(format!("Unknown algorithm '{}'; expected 'greedy' or \
+		                               'exact'.", a)),
+	};
+	if let Some(o) = string_option(&opts, "bit_order")? {
+		embedded.bit_order = BitOrder::parse(&o)?;
+	}
+	embedded.max_steps = match opts.get("max_steps") {
+		None | Some(&Value::Null) => None,
+		Some(v) => Some(v.as_u64().ok_or("Option 'max_steps' must be a non-negative \
+		                                  integer.".to_string())?),
+	};
+	let header: Vec<&str> = csv.lines().next().unwrap_or("").split(',')
+		.map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+	let invars = names(&opts, "invars", &header, 0, nin)?;
+	let outvars = names(&opts, "outvars", &header, nin, nout)?;
+	let outcome = parse_cells(csv.as_bytes(), HEADER_LINES, nin, nout,
+	                          InputEncoding::Binary, OutputEncoding::Binary, Cells::Warn,
+	                          None)
+		.map_err(|e| format!("Error parsing the table: {}", e))?;
+	let (text, minimal) = embed::generate(&outcome.truth, &invars, &outvars, &embedded)
+		.map_err(|e| match e { Error::Usage(msg) | Error::Invalid(msg) => msg })?;
+	let warnings: Vec<String> = outcome.warnings.iter().map(|w| w.to_string()).collect();
+	let result = ::serde_json::json!({"output": text, "minimal": minimal,
+	                                  "warnings": warnings});
+	Ok(result.to_string())
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const SMALL: &str = "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n\
+	                             0,1,0,,1,1\n0,1,1,,0,0\n1,0,0,,1,1\n1,0,1,,0,1\n\
+	                             1,1,0,,1,1\n1,1,1,,0,0\n";
+
+	#[test]
+	fn small_example() {
+		let out: Value = serde_json::from_str(&run(SMALL, 3, 2, "").unwrap()).unwrap();
+		assert_eq!(out["output"], "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+		assert_eq!(out["minimal"], true);
+		let opts = r#"{"format": "python", "algorithm": "exact",
+		               "invars": ["p", "q", "r"]}"#;
+		let out: Value = serde_json::from_str(&run(SMALL, 3, 2, opts).unwrap()).unwrap();
+		assert!(out["output"].as_str().unwrap().starts_with("def map(p, q, r):"),
+		        "{}", out["output"]);
+	}
+
+	#[test]
+	fn errors() {
+		let short = &SMALL[..SMALL.len() - 11];
+		assert!(run(short, 3, 2, "").unwrap_err().contains("no row for 1 input(s)"));
+		let out = run(short, 3, 2, r#"{"default_output": "dc"}"#).unwrap();
+		let out: Value = serde_json::from_str(&out).unwrap();
+		assert_eq!(out["warnings"].as_array().unwrap().len(), 0);
+		assert!(run(SMALL, 3, 2, r#"{"format": "perl"}"#).unwrap_err()
+			.starts_with("Unknown format 'perl'"));
+		assert!(run(SMALL, 3, 2, "[1]").is_err());
+		let warned = SMALL.replace("1,1,1,,0,0", "1,1,1,,0,2");
+		let out: Value = serde_json::from_str(&run(&warned, 3, 2, "").unwrap()).unwrap();
+		assert_eq!(out["warnings"][0], "line 10: output '2' in column 5 is not 0 or 1; \
+		                                taking it as a don't-care on the row.");
+	}
+}
@@ -0,0 +1,92 @@
+// --watch: after the first run, re-read the table whenever the file changes
+// and update the equations for the rows that changed, printing only the
+// equations that came out different.
+use std::fs::File;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use minterm::{Cells, Empty, Equation, InputEncoding, Layout, OutputEncoding, Truth,
+              compressed, parse_layout};
+
+// How the watched file is read, as for the first run.
+pub struct Source<'a> {
+	pub file: &'a str,
+	pub nheader: usize,
+	pub nin: usize,
+	pub nout: usize,
+	pub inenc: InputEncoding,
+	pub outenc: OutputEncoding,
+	pub cells: Cells,
+	pub empty: Option<Empty>,
+	pub layout: Layout,
+}
+
+fn modified(file: &str) -> Option<SystemTime> {
+	std::fs::metadata(file).and_then(|m| m.modified()).ok()
+}
+
+// Updates 'eqns' from 'tbl' to 'new', returning the report: how many rows
+// changed and the old and new form of each equation that changed.
+pub fn update(tbl: &mut Truth, eqns: &mut [Equation], new: Truth) -> String {
+	let changed = tbl.changed_rows(&new);
+	let mut s = format!("{} row(s) changed.\n", changed.len());
+	for eqn in eqns.iter_mut() {
+		let old = eqn.clone();
+		eqn.update(&new, &changed);
+		eqn.canonicalize();
+		if !eqn.eq_semantic(&old) {
+			s += &format!("- {}\n+ {}\n", old, eqn);
+		}
+	}
+	*tbl = new;
+	s
+}
+
+// Polls the file for changes until the program is interrupted.  A table that
+// can't be parsed is reported and otherwise ignored, as it may be half saved.
+pub fn run(src: &Source, mut tbl: Truth, mut eqns: Vec<Equation>) -> ! {
+	eprintln!("Watching {} for changes.", src.file);
+	let mut last = modified(src.file);
+	loop {
+		thread::sleep(Duration::from_millis(500));
+		let now = modified(src.file);
+		if now.is_none() || now == last {
+			continue;
+		}
+		last = now;
+		let parsed = File::open(src.file).map_err(|e| e.to_string())
+			.and_then(|fp| compressed::read_all(src.file, fp))
+			.and_then(|data| parse_layout(&data[..], src.nheader, src.nin, src.nout,
+			                              src.inenc, src.outenc, src.cells, src.empty,
+			                              &src.layout).map_err(|e| e.to_string()));
+		match parsed {
+			Ok(new) => {
+				for w in new.warnings.iter() {
+					eprintln!("WARNING: {}: {}", src.file, w);
+				}
+				print!("{}: {}", src.file, update(&mut tbl, &mut eqns, new.truth));
+			},
+			Err(msg) => eprintln!("Error parsing {}: {}", src.file, msg),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use minterm::{equations, parse};
+
+	#[test]
+	fn report() {
+		let mut tbl = parse("0,0,,0\n0,1,,1\n1,0,,1\n1,1,,1\n".as_bytes(), 0, 2, 1);
+		let names = vec!["a".to_string(), "b".to_string()];
+		let mut eqns = equations(&tbl, vec!["x"], names);
+		eqns[0].simplify();
+		eqns[0].canonicalize();
+		assert_eq!(format!("{}", eqns[0]), "x = a + b + ;");
+		let same = parse("0,0,,0\n1,0,,1\n0,1,,1\n1,1,,1\n".as_bytes(), 0, 2, 1);
+		assert_eq!(update(&mut tbl, &mut eqns, same), "0 row(s) changed.\n");
+		let new = parse("0,0,,0\n0,1,,1\n1,0,,0\n1,1,,1\n".as_bytes(), 0, 2, 1);
+		assert_eq!(update(&mut tbl, &mut eqns, new),
+		           "1 row(s) changed.\n- x = a + b + ;\n+ x = b + ;\n");
+	}
+}
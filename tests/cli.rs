@@ -0,0 +1,1205 @@
+// The exit status and output of the command line for each kind of failure.
+extern crate assert_cmd;
+extern crate minterm;
+extern crate serde_json;
+use assert_cmd::Command;
+use minterm::testutil;
+use std::fs;
+use std::path::PathBuf;
+
+const TABLE: &str = "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n\
+                             0,1,0,,1,1\n0,1,1,,0,0\n1,0,0,,1,1\n1,0,1,,0,1\n\
+                             1,1,0,,1,1\n1,1,1,,0,0\n";
+
+// Writes the table to a file of its own for the test.
+fn table(name: &str, contents: &str) -> PathBuf {
+	let path = std::env::temp_dir().join(format!("minterm-cli-{}-{}.csv", name,
+	                                             std::process::id()));
+	fs::write(&path, contents).unwrap();
+	path
+}
+
+fn minterm(file: &PathBuf, args: &[&str]) -> Command {
+	let mut cmd = Command::cargo_bin("minterm").unwrap();
+	cmd.arg("--table").arg(file)
+		.args(["--ivar", "a", "--ivar", "b", "--ivar", "c"])
+		.args(["--ovar", "x", "--ovar", "y"])
+		.args(args);
+	cmd
+}
+
+fn stdout(out: &std::process::Output) -> String {
+	String::from_utf8_lossy(&out.stdout).to_string()
+}
+fn stderr(out: &std::process::Output) -> String {
+	String::from_utf8_lossy(&out.stderr).to_string()
+}
+
+#[test]
+fn quiet_success() {
+	let file = table("quiet", TABLE);
+	let out = minterm(&file, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0));
+	assert_eq!(stdout(&out), "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	assert_eq!(stderr(&out), "");
+	let out = minterm(&file, &["--quiet", "--table-order"]).output().unwrap();
+	assert_eq!(stdout(&out), "x = a'b'c + bc' + ac' + ;\ny = c' + ab' + ;\n");
+	// without --quiet, the notes go to stderr.
+	let out = minterm(&file, &[]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0));
+	assert!(stdout(&out).starts_with("x = "), "{}", stdout(&out));
+	assert!(stderr(&out).contains("Parsed truth table"), "{}", stderr(&out));
+}
+
+#[test]
+fn spreadsheet_cells() {
+	let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+	let out = minterm(&fixtures.join("spreadsheet-padded.csv"), &["--quiet", "--strict"])
+		.output().unwrap();
+	assert_eq!(stdout(&out), "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	let empty = fixtures.join("spreadsheet-empty.csv");
+	let out = minterm(&empty, &["--quiet", "--empty", "zero"]).output().unwrap();
+	assert_eq!(stdout(&out), "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	let out = minterm(&empty, &["--empty", "error"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("line 3: output cell in column 4 is empty"),
+	        "{}", stderr(&out));
+	let out = minterm(&empty, &["--empty", "none"]).output().unwrap();
+	assert_eq!(stderr(&out),
+	           "Invalid --empty 'none'; expected 'dc', 'zero' or 'error'.\n");
+}
+
+#[test]
+fn compose() {
+	let a = table("compose-a", "p,q,,s,c\nin,in,,out,out\n0,0,,0,0\n0,1,,1,0\n1,0,,1,0\n\
+	                            1,1,,0,1\n");
+	let b = table("compose-b", "t,r,,y,z\nin,in,,out,out\n0,0,,0,0\n0,1,,1,0\n1,0,,1,1\n\
+	                            1,1,,1,0\n");
+	let (a, b) = (a.to_str().unwrap(), b.to_str().unwrap());
+	let args = ["compose", "--quiet", "--table", a, "--ivar", "p", "--ivar", "q",
+	            "--ovar", "s", "--ovar", "c", "--table", b, "--ivar", "t", "--ivar", "r",
+	            "--ovar", "y", "--ovar", "z"];
+	let out = run(&[&args[..], &["--wire", "s=t", "--carry"]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "y = r + p'q + pq' + ;\nz = p'qr' + pq'r' + ;\nc = pq + ;\n");
+	let out = run(&[&args[..], &["--wire", "s=q"]].concat());
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "'q' in --wire 's=q' is not an input of the second table.\n");
+}
+
+#[test]
+fn usage_error() {
+	let file = table("usage", TABLE);
+	let out = minterm(&file, &["--algorithm", "fast"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stdout(&out), "");
+	assert!(stderr(&out).contains("Unknown --algorithm 'fast'"), "{}", stderr(&out));
+	let out = minterm(&file, &["--bogus"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	let out = minterm(&PathBuf::from("/nonexistent.csv"), &[]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	// the file can't be read, so there is no header to warn about.
+	let out = minterm(&PathBuf::from("/nonexistent.csv"), &["--deny-warnings"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).starts_with("Cannot open /nonexistent.csv"), "{}", stderr(&out));
+}
+
+#[test]
+fn miscounted_variables() {
+	let file = table("miscounted", TABLE);
+	// the fourth input is the empty column.
+	let out = minterm(&file, &["--ivar", "d"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	assert!(stderr(&out).contains("4 input(s) and 2 output(s) were declared, but column 4 \
+	                               is empty on every row, which reads as 3 input \
+	                               column(s) before and 2 output column(s) after: did you \
+	                               mean 3 --ivar and 2 --ovar?"), "{}", stderr(&out));
+	let out = minterm(&file, &["--ovar", "a"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "'a' names both --ivar 1 and --ovar 3; every variable needs \
+	                          a name of its own.\n");
+}
+
+#[test]
+fn invalid_table() {
+	// the second row repeats input 0.
+	let file = table("duplicate", "i,,x,y\n-,,-,-\n0,,0,1\n0,,1,0\n");
+	let out = minterm(&file, &["--input-encoding", "index"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	assert!(stderr(&out).contains("1 input pattern(s) given more than once: 000 on lines \
+	                               3, 4."), "{}", stderr(&out));
+	let short = TABLE.replace("0,1,1,,0,0\n", "0,1,1\n");
+	let out = minterm(&table("short", &short), &[]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	let partial = TABLE.replace("0,1,1,,0,0\n", "");
+	let out = minterm(&table("partial", &partial), &[]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	// nothing to minimize once the don't-cares are taken out; this panicked.
+	for (name, contents) in [("empty", ""), ("header", "a,b,c,,x,y\n-,-,-,,-,-\n")] {
+		let out = minterm(&table(name, contents), &["--default-output", "dc"])
+			.output().unwrap();
+		assert_eq!(out.status.code(), Some(3));
+		assert!(stderr(&out).contains("The table has no rows with outputs."), "{}",
+		        stderr(&out));
+	}
+}
+
+#[test]
+fn completeness_policies() {
+	// 011 is given twice, agreeing; 111 twice, disagreeing.
+	let agreeing = format!("{}0,1,1,,0,0\n", TABLE);
+	let conflicting = format!("{}1,1,1,,1,1\n", TABLE);
+	for &(name, contents, policy, status, expect) in [
+		("agreeing", agreeing.as_str(), "error", 3, "011 on lines 6, 11."),
+		("agreeing", agreeing.as_str(), "merge", 0, "x = ac' + bc' + a'b'c + ;"),
+		("agreeing", agreeing.as_str(), "last-wins", 0, "x = ac' + bc' + a'b'c + ;"),
+		("conflicting", conflicting.as_str(), "error", 3, "111 on lines 10, 11."),
+		("conflicting", conflicting.as_str(), "merge", 3,
+		 "input pattern 111 has different outputs on lines 10, 11."),
+		("conflicting", conflicting.as_str(), "last-wins", 0,
+		 "x = ab + ac' + bc' + a'b'c + ;"),
+	].iter() {
+		let file = table(&format!("duplicates-{}-{}", name, policy), contents);
+		let out = minterm(&file, &["--quiet", "--duplicates", policy]).output().unwrap();
+		assert_eq!(out.status.code(), Some(status), "{} {}", name, policy);
+		let text = if status == 0 { stdout(&out) } else { stderr(&out) };
+		assert!(text.contains(expect), "{} {}: {}", name, policy, text);
+	}
+	// 101 and 111 are missing.
+	let short = TABLE.replace("1,0,1,,0,1\n", "").replace("1,1,1,,0,0\n", "");
+	let file = table("missing", &short);
+	for &(args, status, out_expect, err_expect) in [
+		(&["--missing", "error"][..], 3, "",
+		 "no row for 2 of the 8 input patterns (101, 111)"),
+		(&["--missing", "warn"][..], 0, "y = c' + ;",
+		 "WARNING: No row for 2 of the 8 input patterns: 101, 111."),
+		(&["--missing", "warn", "--default-output", "dc"][..], 0, "x = a + b'c + bc' + ;",
+		 "WARNING: No row for 2 of the 8 input patterns"),
+		(&["--missing", "dc"][..], 0, "x = a + b'c + bc' + ;", ""),
+		(&["--missing", "zero"][..], 0, "y = c' + ;", ""),
+		(&["--missing", "zero", "--default-output", "dc"][..], 2, "",
+		 "--missing zero can't be combined with --default-output."),
+		(&["--missing", "error", "--default-output", "dc"][..], 0, "x = a + b'c + bc' + ;", ""),
+	].iter() {
+		let out = minterm(&file, args).output().unwrap();
+		assert_eq!(out.status.code(), Some(status), "{:?}: {}", args, stderr(&out));
+		assert!(stdout(&out).contains(out_expect), "{:?}: {}", args, stdout(&out));
+		assert!(stderr(&out).contains(err_expect), "{:?}: {}", args, stderr(&out));
+	}
+	// a table with every input isn't affected.
+	let out = minterm(&table("complete", TABLE), &["--quiet", "--missing", "warn"])
+		.output().unwrap();
+	assert_eq!((out.status.code(), stderr(&out)), (Some(0), String::new()));
+}
+
+#[test]
+fn filtered_spreadsheet() {
+	// a status, an owner and notes after the outputs; 100 is only a draft.
+	let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+	let status = fixtures.join("spreadsheet-status.csv");
+	let named = ["--quiet", "--ocol", "x", "--ocol", "y"];
+	let run = |args: &[&str]| minterm(&status, &[&named[..], args].concat()).output()
+		.unwrap();
+	let out = run(&["--filter", "status=final"]);
+	assert_eq!(out.status.code(), Some(3));
+	assert!(stderr(&out).contains("no row for 1 of the 8 input patterns (100)"), "{}",
+	        stderr(&out));
+	let kept = run(&["--filter", "status=final", "--missing", "dc"]);
+	assert_eq!(stdout(&kept), "x = bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	let dropped = run(&["--filter-out", "status=DRAFT", "--missing", "dc"]);
+	assert_eq!(stdout(&dropped), stdout(&kept));
+	let out = run(&["--filter", "staus=final"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("no column named 'staus' in the header. Did you mean \
+	                               'status'?"), "{}", stderr(&out));
+}
+
+#[test]
+fn dump_table() {
+	// 101 and 111 are missing, and don't-cares by --missing.
+	let short = TABLE.replace("1,0,1,,0,1\n", "").replace("1,1,1,,0,0\n", "");
+	let file = table("dump", &short);
+	let out = minterm(&file, &["--quiet", "--missing", "dc", "--dump-table"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stderr(&out), "a b c    x y\n0 0 0 -> 0 1\n0 0 1 -> 1 0\n0 1 0 -> 1 1\n\
+	                          0 1 1 -> 0 0\n1 0 0 -> 1 1\n1 1 0 -> 1 1\n1 x 1 -> x x\n");
+	assert!(stdout(&out).starts_with("x = "), "{}", stdout(&out));
+	// the rows --default-output fills in are listed like the table's own.
+	let out = minterm(&file, &["--quiet", "--default-output", "10", "--dump-table"])
+		.output().unwrap();
+	assert!(stderr(&out).ends_with("1 1 0 -> 1 1\n1 0 1 -> 1 0\n1 1 1 -> 1 0\n"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn empty_tables() {
+	let header = table("header", "a,b,c,,x,y\n-,-,-,,-,-\n");
+	let out = minterm(&header, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	assert_eq!(stderr(&out), "The table has no rows with outputs.\n");
+	let path = header.to_str().unwrap();
+	let vars = ["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"];
+	let convert = |args: &[&str]| Command::cargo_bin("minterm").unwrap()
+		.args([&["convert", path, "-", "--to", "pla", "--quiet"][..], &vars[..], args]
+		      .concat())
+		.output().unwrap();
+	assert_eq!(convert(&[]).status.code(), Some(3));
+	let out = convert(&["--allow-empty"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).contains(".p 0\n"), "{}", stdout(&out));
+	// --only and --skip selecting nothing.
+	let file = table("unselected", TABLE);
+	let out = minterm(&file, &["--quiet", "--skip", "x", "--skip", "y"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(3));
+	assert_eq!(stderr(&out), "No output is left to minimize by --skip x --skip y.\n");
+}
+
+#[test]
+fn output_groups() {
+	// s1 s0 take 00, 01 and 10, never 11.
+	let file = table("group", "a,b,c,,s1,s0,z\n-,-,-,,-,-,-\n0,0,0,,0,0,1\n0,0,1,,0,0,0\n\
+	                           0,1,0,,0,1,1\n0,1,1,,0,1,0\n1,0,0,,1,0,1\n1,0,1,,1,0,0\n\
+	                           1,1,0,,1,0,1\n1,1,1,,1,0,0\n");
+	let run = |args: &[&str]| {
+		let mut cmd = Command::cargo_bin("minterm").unwrap();
+		cmd.arg("--table").arg(&file)
+			.args(["--ivar", "a", "--ivar", "b", "--ivar", "c"])
+			.args(["--ovar", "s1", "--ovar", "s0", "--ovar", "z", "--quiet"])
+			.args(["--group", "sel=s1,s0"])
+			.args(args);
+		cmd.output().unwrap()
+	};
+	let out = run(&[]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "z = c' + ;\nsel = 00 when a'b';\nsel = 01 when a'b;\n\
+	                          sel = 10 when a;\n");
+	let out = run(&["--format", "switch-rust", "--msb-first"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).starts_with("// Returns s1, s0 as one value.  Packs s1 as bit 1 \
+	                                  and s0 as bit 0.\n\
+	                                  pub fn sel(a: bool, b: bool, c: bool) -> u8 {\n\
+	                                  \tmatch (a, b, c) {\n\
+	                                  \t\t(false, false, _) => 0b00,\n\
+	                                  \t\t(false, true, _) => 0b01,\n\
+	                                  \t\t_ => 0b10,\n\t}\n}\n"), "{}", stdout(&out));
+	assert!(stdout(&out).contains("pub fn minterm(a: bool, b: bool, c: bool) -> bool {"));
+	let out = run(&["--format", "vhdl"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("--group can't be combined with --format other than"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn estimate() {
+	let file = table("estimate", TABLE);
+	let mut cmd = Command::cargo_bin("minterm").unwrap();
+	cmd.arg("estimate");
+	let out = cmd.arg("--table").arg(&file)
+		.args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"])
+		.arg("--quiet").output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "8 rows over 3 inputs.\n\
+	                          x: 4 minterms, 0 don't-cares, 4 maxterms; at most 9 primes; \
+	                          not unate.\n\
+	                          y: 5 minterms, 0 don't-cares, 3 maxterms; at most 9 primes; \
+	                          unate.\n\
+	                          Recommended: --algorithm exact, taking milliseconds; the \
+	                          prime implicant chart is small enough to search.\n");
+	let mut cmd = Command::cargo_bin("minterm").unwrap();
+	cmd.arg("estimate");
+	let out = cmd.arg("--table").arg(&file)
+		.args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"])
+		.args(["--quiet", "--json"]).output().unwrap();
+	assert!(stdout(&out).starts_with("{\"inputs\":3,"), "{}", stdout(&out));
+}
+
+#[test]
+fn share_dot() {
+	let file = table("share-dot", TABLE);
+	// the conditions cover gives bc' and ac' to both outputs.
+	let out = minterm(&file, &["--quiet", "--cost", "conditions", "--format", "share-dot"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	let dot = stdout(&out);
+	assert!(dot.starts_with("digraph \"sharing\" {\n\trankdir=LR;\n\
+	                         \t// 5 products, 2 of them shared, for 2 outputs\n"),
+	        "{}", dot);
+	assert!(dot.contains("[label=\"bc'\", shape=box, penwidth=2];"), "{}", dot);
+	let out = minterm(&file, &["--quiet", "--collapse-unshared"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn cell_warnings() {
+	// a 2 in x, for a table where 2 means "unknown".
+	let file = table("cells", &TABLE.replace("0,1,1,,0,0\n", "0,1,1,,2,0\n"));
+	let out = minterm(&file, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).starts_with(&format!("WARNING: {}: line 6: output '2' in column \
+	                                           4 is not 0 or 1", file.display())),
+	        "{}", stderr(&out));
+	// a bad x leaves y as the table gives it: were 001 a don't-care for y, it
+	// would be c' + b'.
+	let bad_x = table("cells-x", &TABLE.replace("0,0,1,,1,0\n", "0,0,1,,2,0\n"));
+	let out = minterm(&bad_x, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).contains("\ny = c' + ab' + ;\n"), "{}", stdout(&out));
+	let out = minterm(&file, &["--quiet", "--deny-warnings"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).ends_with("1 warning(s), denied by --deny-warnings.\n"));
+	let out = minterm(&file, &["--quiet", "--strict"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	let out = minterm(&file, &["--quiet", "--lenient-ints"]).output().unwrap();
+	assert_eq!((out.status.code(), stderr(&out)), (Some(0), String::new()));
+}
+
+#[test]
+fn verification_failure() {
+	let file = table("verify", TABLE);
+	let out = minterm(&file, &["--assert-eq", "x = a", "--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(4));
+	assert_eq!(stdout(&out), "");
+	assert!(stderr(&out).starts_with("Assertion fails on 4 row(s): x = a"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn not_proven() {
+	let file = table("unproven", TABLE);
+	let out = minterm(&file, &["--algorithm", "exact", "--max-steps", "1", "--quiet"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(5));
+	assert!(stdout(&out).contains("(not proven minimal)"), "{}", stdout(&out));
+}
+
+#[test]
+fn memory_limit() {
+	let file = table("memory", TABLE);
+	// 100 bytes is less than the table's minterms, which are then the terms:
+	// still x, if not minimal.
+	let out = minterm(&file, &["--algorithm", "exact", "--memory-limit", "0.0001"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(5));
+	assert!(stdout(&out).starts_with("x = a'b'c + a'bc' + ab'c' + abc' + ; \
+	                                  (not proven minimal)\n"), "{}", stdout(&out));
+	assert!(stderr(&out).contains("Some outputs outgrew --memory-limit and were \
+	                               simplified greedily."), "{}", stderr(&out));
+	let out = minterm(&file, &["--memory-limit", "0"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+fn run(args: &[&str]) -> std::process::Output {
+	Command::cargo_bin("minterm").unwrap().args(args).output().unwrap()
+}
+
+#[test]
+fn help() {
+	let out = run(&["--help"]);
+	assert_eq!(out.status.code(), Some(0));
+	for cmd in ["minimize", "check", "assert", "diff", "template", "convert", "repl"].iter() {
+		assert!(stdout(&out).contains(&format!("\n  {} ", cmd)), "{}", cmd);
+		let out = run(&[cmd, "--help"]);
+		assert_eq!(out.status.code(), Some(0), "{}", cmd);
+		assert!(stdout(&out).contains(&format!("Usage: minterm {} ", cmd)), "{}", cmd);
+	}
+}
+
+#[test]
+fn flag_validation() {
+	let out = run(&["minimise"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("Did you mean 'minimize'?"), "{}", stderr(&out));
+	let file = table("flags", TABLE);
+	let out = minterm(&file, &["--fromat", "vhdl"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).starts_with("Unknown option '--fromat'. Did you mean '--format'?"),
+	        "{}", stderr(&out));
+	let out = minterm(&file, &["--format", "verilog-casex"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("Did you mean 'verilog-casez'?"), "{}", stderr(&out));
+	let out = run(&["template", "--bits", "0", "--ovar", "x"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "Invalid --bits '0'; expected a positive integer.\n");
+}
+
+#[test]
+fn commands() {
+	let out = run(&["template", "--bits", "2", "--ovar", "x"]);
+	assert_eq!(stdout(&out), "i0,i1,,x\nin,in,,out\n0,0,,0\n1,0,,0\n0,1,,0\n1,1,,0\n");
+	let out = run(&["template", "--bits", "2", "--ovar", "x", "--bit-order", "msb"]);
+	assert_eq!(stdout(&out), "i0,i1,,x\nin,in,,out\n0,0,,0\n0,1,,0\n1,0,,0\n1,1,,0\n");
+	let file = table("commands", TABLE);
+	let path = file.to_str().unwrap();
+	let vars = ["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"];
+	let out = run(&[&["check", "--table", path, "--assert-eq", "y = c' + ab'"][..],
+	                &vars[..]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).starts_with("8 rows, 3 inputs -> 2 outputs"), "{}", stdout(&out));
+	let out = run(&[&["assert", "--table", path, "--expr", "c' -> y", "--expr", "x | y"][..],
+	                &vars[..]].concat());
+	assert_eq!(out.status.code(), Some(4));
+	assert!(stderr(&out).contains(&format!("Invariant fails on 2 row(s): x | y\n  a=0 b=1 \
+	                                        c=1 ({} line 6): x=0 y=0", path)),
+	        "{}", stderr(&out));
+	let out = run(&[&["convert", path, "-", "--to-input-encoding", "index"][..],
+	                &vars[..]].concat());
+	assert!(stdout(&out).starts_with("a b c,,x,y\nin,,out,out\n0,,0,1\n4,,1,0\n"),
+	        "{}", stdout(&out));
+	let out = run(&[&["convert", path, "-", "--to", "pla", "--minimize"][..],
+	                &vars[..]].concat());
+	assert!(stdout(&out).starts_with(".i 3\n.o 2\n.ilb a b c\n.ob x y\n.type fd\n"),
+	        "{}", stdout(&out));
+	// a PLA without rows has no equations to minimize; this panicked.
+	let pla = std::env::temp_dir().join(format!("minterm-cli-empty-{}.pla",
+	                                            std::process::id()));
+	fs::write(&pla, ".i 2\n.o 1\n.type fr\n.e\n").unwrap();
+	let out = run(&["convert", pla.to_str().unwrap(), "-", "--to", "pla", "--minimize",
+	                "--allow-empty"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).contains(".p 0\n.e\n"), "{}", stdout(&out));
+	let out = minterm(&file, &["--format", "bdd", "--quiet"]).output().unwrap();
+	assert_eq!(stdout(&out), "x: 4 nodes in the order a b c, which sifting doesn't \
+	                          improve on.\ny: 3 nodes in the order a b c, which \
+	                          sifting doesn't improve on.\n");
+	let out = Command::cargo_bin("minterm").unwrap()
+		.args([&["repl", "--table", path, "--quiet"][..], &vars[..]].concat())
+		.write_stdin("eval 110\n").output().unwrap();
+	assert_eq!(stdout(&out), "x=1 y=1\n");
+}
+
+#[test]
+fn vectors() {
+	let file = table("vectors", TABLE);
+	let path = file.to_str().unwrap();
+	let vars = ["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"];
+	// x = ac' + bc' + a'b'c and y = c' + ab' take four inputs between them.
+	let out = run(&[&["vectors", "--table", path, "--quiet"][..], &vars[..]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "a,b,c,,x,y\nin,in,in,,out,out\n0,0,1,,1,0\n0,1,0,,1,1\n\
+	                          1,0,0,,1,1\n0,0,0,,0,1\n");
+	let out = run(&[&["vectors", "--table", path, "--quiet", "--format", "c"][..],
+	                &vars[..]].concat());
+	assert!(stdout(&out).contains("minterm_vectors[4][5] = {\n\t{0, 0, 1, 1, 0},\n"),
+	        "{}", stdout(&out));
+	let out = run(&[&["vectors", "--table", path, "--format", "py"][..], &vars[..]].concat());
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "Unknown --format 'py'; expected 'csv', 'rust' or 'c'.\n");
+}
+
+// Runs the command on a table of minterm::testutil, naming its variables.
+fn fixture(f: &testutil::Fixture, args: &[&str]) -> std::process::Output {
+	let dir = std::env::temp_dir().join(format!("minterm-cli-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = f.write(&dir);
+	Command::cargo_bin("minterm").unwrap().args(args).arg("--table").arg(path)
+		.args(f.args()).output().unwrap()
+}
+
+#[test]
+fn parity_fixture() {
+	// no two minterms of parity merge, so all 8 stay, but its ANF is just the
+	// inputs.
+	let f = testutil::parity(4);
+	let out = fixture(&f, &["--quiet"]);
+	assert_eq!(out.status.code(), Some(0));
+	assert_eq!(stdout(&out).matches('+').count(), 8, "{}", stdout(&out));
+	let out = fixture(&f, &["--quiet", "--form", "anf"]);
+	assert_eq!(stdout(&out), "p = i0 ^ i1 ^ i2 ^ i3;\n");
+}
+
+#[test]
+fn capabilities_fixture() {
+	let f = testutil::capabilities();
+	let out = fixture(&f, &["--quiet"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	// whether the libraries are required doesn't matter.
+	assert!(!stdout(&out).contains("REQUIRED"), "{}", stdout(&out));
+	let out = fixture(&f, &["check", "--assert-eq", "NEED_EGL = WANT_EGL"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	let out = fixture(&f, &["check", "--assert-eq", "NEED_GL = HAVE_GL"]);
+	assert_eq!(out.status.code(), Some(4), "{}", stderr(&out));
+	let out = fixture(&f, &["check", "--report", "--max-counterexamples", "2",
+	                        "--assert-eq", "NEED_GL = HAVE_GL"]);
+	assert_eq!(out.status.code(), Some(4), "{}", stderr(&out));
+	let report: serde_json::Value = serde_json::from_str(&stdout(&out)).unwrap();
+	let entry = &report["assertions"][0];
+	assert_eq!(entry["holds"], false);
+	assert_eq!(entry["counterexamples"].as_array().unwrap().len(), 2, "{}", entry);
+	assert!(entry["minimized"]["cube"].is_string(), "{}", entry);
+	assert_eq!(stderr(&out).lines().last(), Some("1 of 1 assertion(s) fail."));
+}
+
+#[test]
+fn reused_outputs() {
+	// x = ab, y = ab + c.
+	let file = table("reuse", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,0\n0,0,1,,0,1\n\
+	                           0,1,0,,0,0\n0,1,1,,0,1\n1,0,0,,0,0\n1,0,1,,0,1\n\
+	                           1,1,0,,1,1\n1,1,1,,1,1\n");
+	let out = minterm(&file, &["--quiet", "--reuse-outputs"]).output().unwrap();
+	assert_eq!(stdout(&out), "x = ab + ;\ny = x + c + ;\n");
+	let out = minterm(&file, &["--quiet", "--reuse-outputs", "--format", "python"])
+		.output().unwrap();
+	assert!(stdout(&out).contains("    x = bool(a and b)\n    y = bool(x or c)\n    \
+	                               return (x, y)\n"), "{}", stdout(&out));
+	let out = minterm(&file, &["--reuse-outputs", "--format", "vhdl"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("--reuse-outputs can't be combined with --format"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn explained_terms() {
+	let file = table("explain", TABLE);
+	let out = minterm(&file, &["--quiet", "--explain"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0));
+	assert!(stdout(&out).starts_with("x = ac' + bc' + a'b'c + ;\n  \
+	                                  ac' covers rows 5,7 (lines 7,9): merged from \
+	                                  ab'c' + abc'\n"), "{}", stdout(&out));
+	let out = minterm(&file, &["--explain", "--format", "python"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn coverage_report() {
+	// three of the eight inputs are listed, the others don't-cares.
+	let file = table("coverage", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,1,0\n0,0,1,,1,1\n\
+	                              1,1,1,,0,1\n");
+	let args = ["--quiet", "--missing", "dc", "--format", "coverage"];
+	let out = minterm(&file, &args).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "output  term    inputs    rows  don't-cares    space\n\
+	                          x       b'           4       2            2   50.00%\n\
+	                          y       c            4       2            2   50.00%\n");
+	let out = minterm(&file, &[&args[..], &["--broad", "101"]].concat()).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out),
+	           "Invalid --broad '101'; expected a percentage from 0 to 100.\n");
+}
+
+#[test]
+fn literal_cost() {
+	// y's ab'c' and ab'c are x's and z's terms too: merging them into ab'
+	// would add a term, not replace one.
+	let file = table("literals", "a,b,c,,x,y,z\n-,-,-,,-,-,-\n1,0,0,,1,1,0\n\
+	                              1,0,1,,0,1,1\n");
+	let args = ["--ovar", "z", "--default-output", "zero", "--quiet", "--cost",
+	            "literals"];
+	let out = minterm(&file, &args).output().unwrap();
+	assert_eq!(out.status.code(), Some(0));
+	assert_eq!(stdout(&out), "x = ab'c' + ;\ny = ab'c' + ab'c + ;\nz = ab'c + ;\n");
+	let out = minterm(&file, &[&args[..], &["--explain"]].concat()).output().unwrap();
+	assert!(stdout(&out).contains("\n  ab'c' + ab'c kept apart (2 terms, 6 literals; \
+	                               merged into ab': 3 terms, 8 literals)\n"),
+	        "{}", stdout(&out));
+	// the documented table: merging pays everywhere.
+	let file = table("literals-small", TABLE);
+	let out = minterm(&file, &["--quiet", "--cost", "literals", "--explain"]).output()
+		.unwrap();
+	assert!(stdout(&out).contains("\n  a'bc' + abc' merged into bc' for x, y (5 terms, \
+	                               14 literals; kept apart: 6 terms, 18 literals)\n"),
+	        "{}", stdout(&out));
+	let out = minterm(&file, &["--cost", "literals", "--algorithm", "exact"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn rust_const_table() {
+	let file = table("rust-const", TABLE);
+	let out = minterm(&file, &["--quiet", "--format", "rust-const", "--msb-first"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(0));
+	assert!(stdout(&out).contains("pub const MAP: [u8; 1 << 3] = [\n\t0b01, 0b10, 0b11, \
+	                               0b00, 0b11, 0b01, 0b11, 0b00,\n];\n"),
+	        "{}", stdout(&out));
+	let out = minterm(&file, &["--quiet", "--format", "rust-const", "--macro"]).output()
+		.unwrap();
+	assert!(stdout(&out).contains("macro_rules! minterm {\n"), "{}", stdout(&out));
+	let out = minterm(&file, &["--quiet", "--format", "switch-rust", "--macro"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--macro only applies to --format rust-const.\n");
+}
+
+#[test]
+fn observed_outputs() {
+	// with the data outputs only used while en is set, each is just its input.
+	let f = testutil::gated(5, 3, 11);
+	let observe = ["--observe", "d0 when en", "--observe", "d1 when en",
+	               "--observe", "d2 when en"];
+	let out = fixture(&f, &[&["--algorithm", "exact"], &observe[..]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).ends_with("d0 = i1 + ;\nd1 = i2 + ;\nd2 = i3 + ;\n"),
+	        "{}", stdout(&out));
+	assert!(stderr(&out).contains("--observe makes 16 row(s) don't-cares for d0."),
+	        "{}", stderr(&out));
+	let out = fixture(&f, &["--quiet", "--algorithm", "exact"]);
+	assert!(stdout(&out).matches('+').count() > 3 + 4 + 1, "{}", stdout(&out));
+	// an assertion is only checked where its output is used.
+	let asserted = ["--quiet", "--assert-eq", "d0 = i1"];
+	let out = fixture(&f, &[&asserted[..], &observe[..]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	let out = fixture(&f, &asserted);
+	assert_eq!(out.status.code(), Some(4));
+}
+
+#[test]
+fn improved_cover() {
+	// greedy covers this with 8 terms where 6 do.
+	let f = testutil::dense(5, 1, 57);
+	let out = fixture(&f, &["--improve", "0.5", "--seed", "1"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).contains("--improve: o0 went from 8 terms and 29 literals to "),
+	        "{}", stderr(&out));
+	assert!(stdout(&out).matches('+').count() < 8, "{}", stdout(&out));
+	let out = fixture(&f, &["--improve", "1", "--algorithm", "exact"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).ends_with("it can't be combined with --algorithm exact.\n"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn cover_files() {
+	let file = table("cover", TABLE);
+	let cov = std::env::temp_dir().join(format!("minterm-cli-cover-{}.cov",
+	                                            std::process::id()));
+	let cov = cov.to_str().unwrap();
+	let out = minterm(&file, &["--quiet", "--emit-cover", cov]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(fs::read_to_string(cov).unwrap().starts_with("minterm-cover 1\n"));
+	for format in ["text", "verilog-casez", "python"].iter() {
+		let minimized = minterm(&file, &["--quiet", "--format", format]).output().unwrap();
+		let out = run(&["minimize", "--quiet", "--from-cover", cov, "--format", format]);
+		assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+		assert_eq!(stdout(&out), stdout(&minimized), "{}", format);
+	}
+	let out = run(&["minimize", "--from-cover", cov, "--verify-table",
+	                file.to_str().unwrap()]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	// the table has since changed: 1,1,1 now sets x.
+	let changed = table("cover-changed", &TABLE.replace("1,1,1,,0,0", "1,1,1,,1,0"));
+	let out = run(&["minimize", "--from-cover", cov, "--verify-table",
+	                changed.to_str().unwrap()]);
+	assert_eq!(out.status.code(), Some(4));
+	assert!(stderr(&out).contains("\nx gets 1 row(s) wrong, the first 111 ("),
+	        "{}", stderr(&out));
+	let newer = fs::read_to_string(cov).unwrap().replace("cover 1", "cover 2");
+	fs::write(cov, newer).unwrap();
+	let out = run(&["minimize", "--from-cover", cov]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("version 2 of the cover format"), "{}", stderr(&out));
+}
+
+#[test]
+fn frequent_terms_first() {
+	// 001 is by far the most frequent input, and only a'b'c, last in the
+	// canonical order, holds for it.
+	let counts = TABLE.replace("a,b,c,,", "a,b,c,n,").replace("\n0,0,1,,", "\n0,0,1,500,");
+	let file = table("frequencies", &counts);
+	let out = minterm(&file, &["--freq-col", "n"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = a'b'c + ac' + bc' + ;\ny = c' + ab' + ;\n");
+	assert!(stderr(&out).contains("--freq-col: x tests 2.99 terms on average in canonical \
+	                               order, 1.02 with the frequent first.\n"),
+	        "{}", stderr(&out));
+	assert!(!stderr(&out).contains("WARNING"), "{}", stderr(&out));
+	let out = minterm(&file, &["--freq-col", "y"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("the --freq-col column is one of the outputs"),
+	        "{}", stderr(&out));
+	let out = minterm(&file, &["--freq-col", "n", "--format", "vhdl"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn algorithm_per_output() {
+	let file = table("algorithm-for", TABLE);
+	let out = minterm(&file, &["--algorithm-for", "y=exact"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	assert!(stderr(&out).contains("Algorithms: x greedy; y exact.\n"), "{}", stderr(&out));
+	let out = minterm(&file, &["--algorithm-for", "yy=exact"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("Unknown output 'yy' in --algorithm-for. Did you mean \
+	                               'y'?"), "{}", stderr(&out));
+	let out = minterm(&file, &["--algorithm-for", "y=exact", "--improve", "1"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn decoder_if_chain() {
+	// each of the 8 outputs is a minterm of 3 literals on its own.
+	let out = fixture(&testutil::decoder(3), &["--format", "if-chain-c"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).contains("Outputs o0, o1, o2, o3, o4, o5, o6, o7 are mutually \
+	                               exclusive; the chain tests 12 literals where separate \
+	                               ifs would test 24.\n"), "{}", stderr(&out));
+	let code = stdout(&out);
+	assert_eq!(code.matches("else if").count(), 6, "{}", code);
+	assert!(code.contains("\t} else {\n\t\t*o7 = true;\n\t}\n}\n"), "{}", code);
+	let file = table("if-chain", TABLE);
+	let out = minterm(&file, &["--format", "if-chain-c"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("but x and y are both 1 for ac'."), "{}", stderr(&out));
+}
+
+#[test]
+fn given_conditions() {
+	// x = ab'c + ab'd, two terms of 3 literals each, or of 2 with cond = ab'.
+	let mut csv = "a,b,c,d,,x\n-,-,-,-,,-\n".to_string();
+	for i in 0..16 {
+		let bit = |k: usize| i >> (3 - k) & 1;
+		let x = bit(0) == 1 && bit(1) == 0 && (bit(2) == 1 || bit(3) == 1);
+		csv += &format!("{},{},{},{},,{}\n", bit(0), bit(1), bit(2), bit(3), x as u8);
+	}
+	let file = table("given", &csv);
+	let run = |args: &[&str]| {
+		Command::cargo_bin("minterm").unwrap().arg("--table").arg(&file)
+			.args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ivar", "d"])
+			.args(["--ovar", "x"]).args(args).output().unwrap()
+	};
+	let out = run(&[]);
+	assert_eq!(stdout(&out), "x = ab'c + ab'd + ;\n");
+	let out = run(&["--given", "cond = a & !b"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = c & cond + d & cond + ;\n");
+	assert!(stderr(&out).contains("--given: x uses cond.\n"), "{}", stderr(&out));
+	let out = run(&["--given", "cond = a & !b", "--format", "python", "--quiet"]);
+	assert!(stdout(&out).starts_with("def map(a, b, c, d, cond):\n"), "{}", stdout(&out));
+	let out = run(&["--given", "b = a"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("--given 'b = a' names an input."), "{}", stderr(&out));
+}
+
+#[test]
+fn ovars_by_column() {
+	// TABLE with its output columns the other way round.
+	let swapped: String = TABLE.lines().map(|l| {
+		let cells: Vec<&str> = l.split(',').collect();
+		format!("{},,{},{}\n", cells[..3].join(","), cells[5], cells[4])
+	}).collect();
+	let file = table("by-column", &swapped);
+	let run = |args: &[&str]| Command::cargo_bin("minterm").unwrap().arg("--table")
+		.arg(&file).args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--quiet"])
+		.args(args).output().unwrap();
+	let expected = "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n";
+	let out = run(&["--ovar", "x", "--ovar", "y"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!((stdout(&out).as_str(), stderr(&out).as_str()), (expected, ""));
+	let out = run(&["--ovar", "y", "--ovar", "x"]);
+	assert_eq!(stdout(&out), "y = c' + ab' + ;\nx = ac' + bc' + a'b'c + ;\n");
+	let out = run(&["--ovar", "x", "--ovar", "z"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).starts_with("--ovar z names no column of the header of "),
+	        "{}", stderr(&out));
+	// without names in the header, by position with a warning, or as @ says.
+	let unnamed = swapped.replacen("a,b,c,,y,x", "-,-,-,,-,-", 1);
+	let file = table("unnamed", &unnamed);
+	let run = |args: &[&str]| Command::cargo_bin("minterm").unwrap().arg("--table")
+		.arg(&file).args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--quiet"])
+		.args(args).output().unwrap();
+	let out = run(&["--ovar", "x", "--ovar", "y"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = c' + ab' + ;\ny = ac' + bc' + a'b'c + ;\n");
+	assert!(stderr(&out).starts_with("WARNING: The header of ") &&
+	        stderr(&out).contains("names none of the outputs"), "{}", stderr(&out));
+	let out = run(&["--ovar", "x@5", "--ovar", "y@4"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!((stdout(&out).as_str(), stderr(&out).as_str()), (expected, ""));
+	let out = run(&["--ovar", "x@5", "--ovar", "y"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--ovar y names no column; give every --ovar as name@col, \
+	                          or none.\n");
+}
+
+#[test]
+fn schema_line() {
+	// TABLE without its last row, which sparse=dc makes a don't-care.
+	let rows: Vec<&str> = TABLE.lines().take(9).collect();
+	let schema = format!("#! minterm inputs=a,b,c outputs=x,y sparse=dc\n{}\n",
+	                     rows.join("\n"));
+	let file = table("schema", &schema);
+	let run = |args: &[&str]| Command::cargo_bin("minterm").unwrap().arg("--table")
+		.arg(&file).args(args).output().unwrap();
+	let out = run(&["--quiet"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = ac' + bc' + a'b'c + ;\ny = a + c' + ;\n");
+	assert_eq!(stderr(&out), "");
+	// the command line wins, with a warning.
+	let out = run(&["--quiet", "--ovar", "p", "--ovar", "q", "--missing", "zero"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "p = ac' + bc' + a'b'c + ;\nq = c' + ab' + ;\n");
+	assert_eq!(stderr(&out), "WARNING: --ovar overrides outputs=x,y of the schema line \
+	                          in ".to_string() + file.to_str().unwrap() + ".\n\
+	                          WARNING: --missing overrides sparse=dc of the schema line \
+	                          in " + file.to_str().unwrap() + ".\n\
+	                          WARNING: The header of " + file.to_str().unwrap() +
+	                          " names none of the outputs, so the --ovar names are bound \
+	                          to its output columns in order; give --ovar name@col to \
+	                          bind them by column.\n");
+	let out = run(&["--quiet", "--ivar", "a", "--ivar", "b", "--ivar", "c",
+	                "--deny-warnings", "--missing", "zero"]);
+	assert_eq!(out.status.code(), Some(2));
+	let file = table("schema-bad", &schema.replace("outputs=x,y", "outputs=x,,y"));
+	let out = Command::cargo_bin("minterm").unwrap().arg("--table").arg(&file)
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("Invalid schema line in ") &&
+	        stderr(&out).ends_with(": malformed outputs list 'x,,y': a name is empty.\n"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn batch() {
+	let dir = std::env::temp_dir()
+		.join(format!("minterm-cli-batch-{}", std::process::id()));
+	let tables = dir.join("tables");
+	let out_dir = dir.join("gen");
+	fs::create_dir_all(&tables).unwrap();
+	// names from a schema line, from a sidecar file, and a malformed schema line.
+	fs::write(tables.join("a.csv"),
+	          "#! minterm inputs=a,b,c outputs=x,y\n".to_string() + TABLE).unwrap();
+	fs::write(tables.join("b.csv"), "p,q,,z\n-,-,,-\n0,0,,0\n0,1,,1\n1,0,,1\n1,1,,0\n")
+		.unwrap();
+	fs::write(tables.join("b.toml"), "inputs = [\"p\", \"q\"]\noutputs = [\"z\"]\n")
+		.unwrap();
+	fs::write(tables.join("c.csv"), "#! minterm inputs=p,,q outputs=z\n0,0,,0\n").unwrap();
+	let out = Command::cargo_bin("minterm").unwrap()
+		.args(["batch", "--quiet", "--jobs", "2", "--dir"]).arg(&tables)
+		.arg("--out-dir").arg(&out_dir).output().unwrap();
+	assert_eq!(out.status.code(), Some(3), "{}", stderr(&out));
+	assert_eq!(stderr(&out), "1 of 3 tables failed.\n");
+	let summary = stdout(&out);
+	let lines: Vec<&str> = summary.lines().collect();
+	assert_eq!(lines.len(), 4, "{}", summary);
+	let starts = |k: usize, s: &str| assert!(lines[k].starts_with(s), "{}", summary);
+	starts(0, "table  inputs  outputs  terms  literals");
+	starts(1, "a.csv       3        2      5        10");
+	starts(2, "b.csv       2        1      2         4");
+	assert!(lines[1].ends_with("  ok"), "{}", summary);
+	let why = format!("failed: Invalid schema line in {}: malformed inputs list 'p,,q': a \
+	                   name is empty.", tables.join("c.csv").display());
+	starts(3, "c.csv       -");
+	assert!(lines[3].ends_with(&why), "{}", summary);
+	assert_eq!(fs::read_to_string(out_dir.join("a.txt")).unwrap(),
+	           "x = ac' + bc' + a'b'c + ;\ny = c' + ab' + ;\n");
+	assert_eq!(fs::read_to_string(out_dir.join("b.txt")).unwrap(), "z = p'q + pq' + ;\n");
+	assert!(!out_dir.join("c.txt").exists());
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn complements() {
+	// five of the eight inputs are listed, the others don't-cares.
+	let file = table("complements", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,1,,1,0\n0,1,0,,1,1\n\
+	                                 0,1,1,,1,0\n1,0,0,,0,1\n1,1,1,,1,1\n");
+	let args = ["--quiet", "--missing", "dc", "--emit-complements"];
+	let out = minterm(&file, &args).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	let text = stdout(&out);
+	assert_eq!(text, "x = b + c + ;\ny = a + c' + ;\nx_n = b'c' + ;\ny_n = a'c + ;\n");
+	// each pair is complementary on every input, don't-cares included.
+	let value = |eqn: &str, inp: &[bool]| {
+		let terms = eqn.split(" = ").nth(1).unwrap().split(" + ").filter(|&t| t != ";");
+		terms.map(|t| t.as_bytes()).any(|t| (0..t.len()).filter(|&i| t[i] != b'\'')
+			.all(|i| inp[(t[i] - b'a') as usize] != (t.get(i + 1) == Some(&b'\''))))
+	};
+	let eqns: Vec<&str> = text.lines().collect();
+	for i in 0..8 {
+		let inp = [i & 4 != 0, i & 2 != 0, i & 1 != 0];
+		assert_ne!(value(eqns[0], &inp), value(eqns[2], &inp), "{:?}", inp);
+		assert_ne!(value(eqns[1], &inp), value(eqns[3], &inp), "{:?}", inp);
+	}
+	let out = minterm(&file, &[&args[..], &["--loose-complements", "--cost", "conditions"]]
+		.concat()).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).contains("if(b'c'): y = x_n = 1\n"), "{}", stdout(&out));
+	let out = minterm(&file, &[&args[..], &["--cost", "conditions"]].concat()).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--emit-complements can't be combined with --cost conditions \
+	                          unless --loose-complements.\n");
+	let out = minterm(&file, &["--loose-complements"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--loose-complements only applies to --emit-complements.\n");
+}
+
+#[test]
+fn enumeration_limit() {
+	let out = run(&["template", "--bits", "40", "--ovar", "x"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "Cannot enumerate the 2^40 patterns of 40 inputs; at most \
+	                          2^24 are allowed; give --sample=<n> to list n patterns \
+	                          picked at random. Give a larger --max-enumerate to allow \
+	                          more.\n");
+	assert_eq!(stdout(&out), "");
+	let out = run(&["template", "--bits", "40", "--ovar", "x", "--sample", "3"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out).lines().count(), 5);
+	let out = run(&["template", "--bits", "3", "--ovar", "x", "--max-enumerate", "2"]);
+	assert_eq!(out.status.code(), Some(2));
+	// a sparse table's missing inputs are found by going through every pattern.
+	let file = table("enumeration", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,1,,1,0\n");
+	let args = ["--quiet", "--missing", "dc", "--max-enumerate"];
+	let out = minterm(&file, &[&args[..], &["2"]].concat()).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("The table doesn't list them all"), "{}", stderr(&out));
+	let out = minterm(&file, &[&args[..], &["3"]].concat()).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+}
+
+#[test]
+fn notation_styles() {
+	// need = ogl + Has'GL' EGL, an input name with an apostrophe in it.
+	let file = table("notation", "Has'GL,EGL,ogl,,need\n-,-,-,,-\n0,0,0,,0\n0,0,1,,1\n\
+	                              0,1,0,,1\n0,1,1,,1\n1,0,0,,0\n1,0,1,,1\n1,1,0,,0\n\
+	                              1,1,1,,1\n");
+	let run = |args: &[&str]| {
+		Command::cargo_bin("minterm").unwrap().arg("--table").arg(&file)
+			.args(["--ivar", "Has'GL", "--ivar", "EGL", "--ivar", "ogl", "--ovar", "need"])
+			.arg("--quiet").args(args).output().unwrap()
+	};
+	let golden = [
+		("prime", "concat", "\"Has'GL\"'\"EGL\""),
+		("prime", "dot", "\"Has'GL\"'·EGL"),
+		("prime", "amp", "\"Has'GL\"' & EGL"),
+		("prime", "word", "\"Has'GL\"' AND EGL"),
+		("bang", "concat", "!\"Has'GL\"\"EGL\""),
+		("bang", "dot", "!Has'GL·EGL"),
+		("bang", "amp", "!Has'GL & EGL"),
+		("bang", "word", "!Has'GL AND EGL"),
+		("overline-unicode", "concat",
+		 "\"H\u{305}a\u{305}s\u{305}'\u{305}G\u{305}L\u{305}\"\"EGL\""),
+		("overline-unicode", "dot",
+		 "H\u{305}a\u{305}s\u{305}'\u{305}G\u{305}L\u{305}·EGL"),
+		("overline-unicode", "amp",
+		 "H\u{305}a\u{305}s\u{305}'\u{305}G\u{305}L\u{305} & EGL"),
+		("overline-unicode", "word",
+		 "H\u{305}a\u{305}s\u{305}'\u{305}G\u{305}L\u{305} AND EGL"),
+		("not", "concat", "NOT \"Has'GL\"\"EGL\""),
+		("not", "dot", "NOT Has'GL·EGL"),
+		("not", "amp", "NOT Has'GL & EGL"),
+		("not", "word", "NOT Has'GL AND EGL"),
+	];
+	for &(neg, and, term) in golden.iter() {
+		let out = run(&["--neg-style", neg, "--and-style", and]);
+		assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+		assert_eq!(stdout(&out), format!("need = ogl + {} + ;\n", term), "{} {}", neg,
+		           and);
+	}
+	// the names are too long to concatenate, so amp unless told otherwise.
+	assert_eq!(stdout(&run(&[])), "need = ogl + \"Has'GL\"' & EGL + ;\n");
+	let out = run(&["--explain", "--neg-style", "bang"]);
+	assert!(stdout(&out).contains("  !Has'GL & EGL covers rows 3,4 (lines 5,6): merged \
+	                               from !Has'GL & EGL & !ogl + !Has'GL & EGL & ogl\n"),
+	        "{}", stdout(&out));
+	let out = run(&["--format", "coverage", "--neg-style", "not"]);
+	assert!(stdout(&out).contains("need    NOT Has'GL & EGL         2"), "{}",
+	        stdout(&out));
+	// DOT escapes the quotes a quoted name is written in.
+	let out = run(&["--format", "share-dot", "--and-style", "concat"]);
+	assert!(stdout(&out).contains("[label=\"\\\"Has'GL\\\"'\\\"EGL\\\"\""), "{}",
+	        stdout(&out));
+	let out = run(&["--and-style", "plus"]);
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "Invalid --and-style 'plus'; expected 'concat', 'dot', 'amp' \
+	                          or 'word'.\n");
+}
+
+#[test]
+fn emitted_tests() {
+	let file = table("emit-tests", TABLE);
+	let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+	let test = std::env::temp_dir().join(format!("minterm-cli-tests-{}.cc",
+	                                             std::process::id()));
+	let out = minterm(&file, &["--format", "switch-c", "--emit-tests", "gtest",
+	                           "--tests-file", test.to_str().unwrap()]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).contains("--emit-tests: 4 cases written to "), "{}",
+	        stderr(&out));
+	assert_eq!(fs::read_to_string(&test).unwrap(),
+	           fs::read_to_string(fixtures.join("switch-c-gtest.cc")).unwrap());
+	let out = minterm(&file, &["--quiet", "--emit-tests", "gtest", "--tests-file", "t.cc"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--emit-tests only applies to --format switch-c and \
+	                          if-chain-c.\n");
+	let out = minterm(&file, &["--quiet", "--format", "switch-c", "--emit-tests", "unity",
+	                           "--tests-file", "t.cc"]).output().unwrap();
+	assert_eq!(stderr(&out), "Invalid --emit-tests 'unity'; expected 'gtest', 'catch2' \
+	                          or 'cunit'.\n");
+}
+
+#[test]
+fn implications() {
+	// x = abc + a'b'c', and y is 1 on ab'c besides: x -> y holds, y -> x doesn't.
+	let file = table("implications", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,1,1\n0,0,1,,0,0\n\
+	                                  0,1,0,,0,0\n0,1,1,,0,0\n1,0,0,,0,0\n1,0,1,,0,1\n\
+	                                  1,1,0,,0,0\n1,1,1,,1,1\n");
+	let mut cmd = Command::cargo_bin("minterm").unwrap();
+	cmd.arg("implications");
+	let out = cmd.arg("--table").arg(&file)
+		.args(["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x -> y\n");
+	assert!(stderr(&out).contains("1 implication(s) between 2 output(s).\n"));
+	let out = minterm(&file, &["--quiet"]).output().unwrap();
+	assert_eq!(stdout(&out), "x = a'b'c' + abc + ;\ny = ac + a'b'c' + ;\n");
+	// y's cover loses the rows x settles.
+	let out = minterm(&file, &["--use-implications"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x = a'b'c' + abc + ;\ny = x + ac + ;\n");
+	assert!(stderr(&out).contains("--use-implications: x -> y holds, making 2 row(s) \
+	                               don't-cares for y.\n"), "{}", stderr(&out));
+	let out = minterm(&file, &["--quiet", "--use-implications", "--reuse-outputs"])
+		.output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert_eq!(stderr(&out), "--use-implications can't be combined with \
+	                          --reuse-outputs.\n");
+}
+
+#[test]
+fn profile() {
+	// x = ab + a'c and y = ac + b'c'; the trace has ab and b'c' hold, neither
+	// a'c nor ac.
+	let file = table("profile", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n\
+	                             0,1,0,,0,0\n0,1,1,,1,0\n1,0,0,,0,1\n1,0,1,,0,1\n\
+	                             1,1,0,,1,0\n1,1,1,,1,1\n");
+	let trace = table("profile-trace", "a,b,c,,count\n110,,3\n0,0,0,,2\n010\n");
+	let vars = ["--ivar", "a", "--ivar", "b", "--ivar", "c", "--ovar", "x", "--ovar", "y"];
+	let args = [&["profile", "--table", file.to_str().unwrap(), "--trace",
+	              trace.to_str().unwrap()][..], &vars[..]].concat();
+	let out = run(&args);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), "x: 1 on 3 of 6 traced input(s)\n  3  ab\n  0  a'c  (never \
+	                          activated)\ny: 1 on 2 of 6 traced input(s)\n  0  ac  (never \
+	                          activated)\n  2  b'c'\n");
+	assert!(stderr(&out).contains("2 of 4 term(s) never activated; the trace has 3 input \
+	                               pattern(s), 3 of them rows of the table.\n"),
+	        "{}", stderr(&out));
+	// on the traced inputs alone, x is a and y is b'.
+	let out = run(&[&args[..], &["--prune-unactivated", "--quiet"]].concat());
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).ends_with("\n# Trace-dependent: minimized with the 5 row(s) \
+	                                whose inputs the trace never has\n# as don't-cares; \
+	                                these equations agree with the table only on the \
+	                                traced inputs.\nx = a + ;\ny = b' + ;\n"),
+	        "{}", stdout(&out));
+	let bad = table("profile-bad", "0,1\n");
+	let out = run(&[&["profile", "--table", file.to_str().unwrap(), "--trace",
+	                  bad.to_str().unwrap()][..], &vars[..]].concat());
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).ends_with(", line 1: expected the 3 input(s) as 0s and 1s, \
+	                                optionally followed by a count, not '0,1'.\n"),
+	        "{}", stderr(&out));
+}
+
+#[test]
+fn watch_keeps_greedy_covers() {
+	// edits are merged in greedily, so --watch can't promise exact covers.
+	let file = table("watch-exact", TABLE);
+	for &(args, opt) in [(&["--algorithm", "exact"][..], "--algorithm exact"),
+	                     (&["--weight", "a=2"][..], "--weight")].iter() {
+		let out = minterm(&file, &[&["--watch", "--quiet"][..], args].concat()).output()
+			.unwrap();
+		assert_eq!(out.status.code(), Some(2));
+		assert_eq!(stderr(&out), format!("--watch can't be combined with {}.\n", opt));
+	}
+}
+
+#[test]
+fn compressed_tables() {
+	let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+	let gz = fixtures.join("small.csv.gz");
+	let plain = minterm(&table("uncompressed", TABLE), &["--quiet"]).output().unwrap();
+	let out = minterm(&gz, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), stdout(&plain));
+	// stdin, whose data is recognized as gzip by its first bytes.
+	let out = minterm(&PathBuf::from("-"), &["--quiet"])
+		.write_stdin(fs::read(&gz).unwrap()).output().unwrap();
+	assert_eq!(stdout(&out), stdout(&plain));
+	let data = fs::read(&gz).unwrap();
+	let cut = std::env::temp_dir().join(format!("minterm-cli-truncated-{}.csv.gz",
+	                                            std::process::id()));
+	fs::write(&cut, &data[..data.len() / 2]).unwrap();
+	let out = minterm(&cut, &["--quiet"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains(".csv.gz is a gzip archive that can't be \
+	                               decompressed: "), "{}", stderr(&out));
+}
+
+#[test]
+fn mapped_cells() {
+	let plain = minterm(&table("unmapped", TABLE), &["--quiet"]).output().unwrap();
+	// every 0/1 cell spelled out, the first data row's 'a' in lower case.
+	let lines: Vec<String> = TABLE.lines().enumerate().map(|(k, l)| match k {
+		0 | 1 => l.to_string(),
+		_ => l.split(',').map(|c| match c { "0" => "OFF", "1" => "ON", c => c })
+			.collect::<Vec<&str>>().join(","),
+	}).collect();
+	let spelled = lines.join("\n").replacen("OFF", "off", 1);
+	let file = table("mapped", &format!("#! minterm map=*:ON=1,*:OFF=0\n{}\n", spelled));
+	let out = minterm(&file, &["--quiet", "--strict"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("input 'off' in column 0 is not 0 or 1"),
+	        "{}", stderr(&out));
+	let out = minterm(&file, &["--quiet", "--strict", "--map", "a:off=0"]).output()
+		.unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert_eq!(stdout(&out), stdout(&plain));
+	let out = minterm(&file, &["--quiet", "--map", "a:off"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(2));
+	assert!(stderr(&out).contains("Invalid --map 'a:off'"), "{}", stderr(&out));
+}
+
+#[test]
+fn completed_dont_cares() {
+	// 111 is a don't-care row, and x a don't-care on 011, which gives y; the
+	// rest, unlisted, are x = 0, y = 1.
+	let file = table("completion", "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n\
+	                                0,1,0,,1,1\n1,0,0,,1,1\n1,1,1,,x,x\n0,1,1,,?,1\n");
+	let out = minterm(&file, &["--quiet", "--default-output", "01", "--format",
+	                           "rust-const", "--show-completion"]).output().unwrap();
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).ends_with("a,b,c,,x,y\n0,1,1,,1,1\n1,1,1,,0,1\n"),
+	        "{}", stderr(&out));
+	// the entries of 011 and 111, 6 and 7, are those rows, not the default 0b10.
+	assert!(stdout(&out).contains("\t0b10, 0b11, 0b11, 0b10, 0b01, 0b10, 0b11, 0b10,\n"),
+	        "{}", stdout(&out));
+	// both s1 = a and s0 = b hold on the don't-care row 11.
+	let file = table("completion-group", "a,b,,s1,s0\n-,-,,-,-\n0,0,,0,0\n0,1,,0,1\n\
+	                                      1,0,,1,0\n1,1,,x,x\n");
+	let run = |args: &[&str]| Command::cargo_bin("minterm").unwrap().arg("--table")
+		.arg(&file).args(["--ivar", "a", "--ivar", "b", "--ovar", "s1", "--ovar", "s0",
+		                  "--group", "sel=s1,s0", "--quiet"])
+		.args(args).output().unwrap();
+	let out = run(&["--show-completion"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).contains("WARNING: --group sel: the conditions of 01, 10 all \
+	                               hold for the don't-care row 11; it is completed with \
+	                               01, as the match gives it.\na,b,,s1,s0\n1,1,,0,1\n"),
+	        "{}", stderr(&out));
+	assert_eq!(run(&["--deny-warnings"]).status.code(), Some(2));
+}
+
+#[test]
+fn completed_missing_inputs() {
+	// 10 is missing, so a don't-care, and the covers x = b, y = a + b' give it 01.
+	let file = table("completion-missing", "a,b,,x,y\n-,-,,-,-\n0,0,,0,1\n0,1,,1,0\n\
+	                                        1,1,,1,1\n");
+	let run = |args: &[&str]| Command::cargo_bin("minterm").unwrap().arg("--table")
+		.arg(&file).args(["--ivar", "a", "--ivar", "b", "--ovar", "x", "--ovar", "y",
+		                  "--missing", "dc", "--quiet"])
+		.args(args).output().unwrap();
+	let out = run(&["--show-completion"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stderr(&out).ends_with("a,b,,x,y\n1,0,,0,1\n"), "{}", stderr(&out));
+	let out = run(&["--format", "python", "--dict-lookup"]);
+	assert_eq!(out.status.code(), Some(0), "{}", stderr(&out));
+	assert!(stdout(&out).contains("    (1, 0): (0, 1),\n"), "{}", stdout(&out));
+}
@@ -0,0 +1,43 @@
+#include <gtest/gtest.h>
+
+extern "C" {
+#include "minterm.h"
+}
+
+/* The inputs a, b, c followed by the outputs x, y expected of them,
+   -1 where the table leaves an output open. */
+static const signed char minterm_cases[4][5] = {
+	{1, 0, 0, 1, 1},
+	{0, 1, 0, 1, 1},
+	{0, 0, 1, 1, 0},
+	{0, 0, 0, 0, 1},
+};
+#define MINTERM_CASES (sizeof minterm_cases / sizeof minterm_cases[0])
+
+static void call(const signed char *c, bool *out) {
+	minterm(c[0] != 0, c[1] != 0, c[2] != 0, &out[0], &out[1]);
+}
+
+TEST(Minterm, x) {
+	unsigned k;
+	for (k = 0; k < MINTERM_CASES; k++) {
+		const signed char *c = minterm_cases[k];
+		bool out[2];
+		if (c[3] < 0)
+			continue;
+		call(c, out);
+		EXPECT_TRUE(out[0] == (c[3] != 0)) << "case " << k;
+	}
+}
+
+TEST(Minterm, y) {
+	unsigned k;
+	for (k = 0; k < MINTERM_CASES; k++) {
+		const signed char *c = minterm_cases[k];
+		bool out[2];
+		if (c[4] < 0)
+			continue;
+		call(c, out);
+		EXPECT_TRUE(out[1] == (c[4] != 0)) << "case " << k;
+	}
+}
@@ -0,0 +1,101 @@
+// Malformed files for the table readers: valid tables with random edits, and
+// the files that once made a reader panic.  Each must read as a table or fail
+// with a ParseError; none may panic.  A CSV table is read both into rows and
+// into the packed representation, which must agree.
+extern crate minterm;
+use minterm::{Cells, HEADER_LINES, InputEncoding, OutputEncoding, parse_cells,
+              parse_packed};
+use minterm::bits::BitOrder;
+use minterm::formats::read_pla;
+use minterm::testutil;
+
+// Seeds the edits start from, and files that panicked.
+const CSV: [&[u8]; 5] = [
+	b"a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n0,1,0,,1,1\n1,1,1,,0,2\n",
+	b"i,,x\n-,,-\n3,,5\n7,,1\n",
+	b"",
+	b"a,b,c,,x,y\n",
+	b"a,b,c,,x,y\n-,-,-,,-,-\n0,0\n\xff,\xc3\xa9,1,,1,1\n",
+];
+const PLA: [&[u8]; 6] = [
+	b"# small\n.i 3\n.o 2\n.ilb a b c\n.ob x y\n.type fr\n.p 3\n1-- 10\n-1- 01\n000 --\n.e\n",
+	b".i 1\n.o 1\n\xc3\xa9\n",
+	b".i 18446744073709551615\n.o 1\n01\n",
+	b".i 100000000\n.o 1\n.e\n",
+	b".i 2\n.o 1\n01 1\n.i 3\n011 1\n",
+	b".i 1\n.o 1\n\xff 1\n",
+];
+
+// The ways the command line may read a CSV table.
+fn csv_readings() -> Vec<(usize, usize, InputEncoding, OutputEncoding, Cells)> {
+	let mut rv = vec![];
+	for &(nin, nout) in [(3, 2), (1, 1), (0, 1), (2, 0), (8, 3)].iter() {
+		for &inenc in [InputEncoding::Binary, InputEncoding::Index{order: BitOrder::Msb},
+		               InputEncoding::Row{order: BitOrder::Lsb}].iter() {
+			for &outenc in [OutputEncoding::Binary,
+			                OutputEncoding::Decimal{order: BitOrder::Lsb}].iter() {
+				for &cells in [Cells::Warn, Cells::Strict, Cells::Lenient].iter() {
+					rv.push((nin, nout, inenc, outenc, cells));
+				}
+			}
+		}
+	}
+	rv
+}
+
+#[test]
+fn csv_never_panics() {
+	let readings = csv_readings();
+	for (k, seed) in CSV.iter().enumerate() {
+		for s in 0..200 {
+			let data = if s == 0 { seed.to_vec() }
+			           else { testutil::mangle(seed, (k * 1000 + s) as u64) };
+			let (nin, nout, inenc, outenc, cells) = readings[s % readings.len()];
+			let read = parse_cells(&data[..], HEADER_LINES, nin, nout, inenc, outenc,
+			                       cells, None);
+			if let Ok(ref outcome) = read {
+				for ent in outcome.truth.rows().iter() {
+					assert_eq!((ent.input.len(), ent.output.len()), (nin, nout));
+				}
+			}
+			// the packed reader takes the cells as Cells::Warn does.
+			let packed = parse_packed(&data[..], HEADER_LINES, nin, nout, inenc, outenc);
+			if cells != Cells::Warn {
+				continue;
+			}
+			match (read, packed) {
+				(Ok(outcome), Ok(packed)) => {
+					let unpacked = packed.to_truth();
+					let dc: Vec<_> = outcome.truth.dont_cares().collect();
+					assert_eq!((unpacked.rows(), unpacked.dont_cares().collect()),
+					           (outcome.truth.rows(), dc));
+				},
+				(Err(_), Err(_)) => {},
+				(read, packed) => panic!("{:?}: rows {:?}, packed {:?}", data,
+				                         read.map(|_| ()), packed.map(|_| ())),
+			}
+		}
+	}
+}
+
+#[test]
+fn pla_never_panics() {
+	for (k, seed) in PLA.iter().enumerate() {
+		for s in 0..500 {
+			let data = if s == 0 { seed.to_vec() }
+			           else { testutil::mangle(seed, (k * 1000 + s) as u64) };
+			let sheet = match read_pla(&data[..]) {
+				Ok(sheet) => sheet,
+				Err(_) => continue,
+			};
+			// the rows fit what '.i' and '.o' declared.
+			let (nin, nout) = (sheet.invars.len(), sheet.outvars.len());
+			for row in sheet.rows.iter() {
+				assert_eq!((row.input.len(), row.output.len()), (nin, nout));
+			}
+			if let Ok(tbl) = sheet.to_truth() {
+				assert!(tbl.rows().iter().all(|e| e.input.len() == nin));
+			}
+		}
+	}
+}
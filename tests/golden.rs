@@ -0,0 +1,408 @@
+// Golden files: the exact output of every format, and of the other commands
+// whose output something else reads, for a few fixture tables, each kept
+// under tests/golden/ as <case>.txt.  A case whose output differs from its
+// file fails with a diff of the two.  The library's public items are kept
+// the same way, in public-api.txt, so that any change to what a program using
+// the library can name or call shows up too.  When a change to the output is
+// intended, write the files anew with
+//
+//     MINTERM_UPDATE_GOLDEN=1 cargo test --test golden
+//
+// and review and commit them with the change.
+extern crate assert_cmd;
+extern crate minterm;
+use assert_cmd::Command;
+use minterm::testutil::{self, Fixture};
+use std::fs;
+use std::path::PathBuf;
+
+const UPDATE: &str = "MINTERM_UPDATE_GOLDEN";
+
+// Lines of context around each change in a diff.
+const CONTEXT: usize = 2;
+
+fn golden_dir() -> PathBuf {
+	PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn temp_dir() -> PathBuf {
+	let dir = std::env::temp_dir()
+		.join(format!("minterm-golden-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	dir
+}
+
+// The lines of 'old' and 'new' as a diff: '-' before those only 'old' has,
+// '+' before those only 'new' has, and a few unchanged lines around them,
+// each run of changes headed by the line it starts at in either.
+fn diff(old: &str, new: &str) -> String {
+	let (a, b): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+	// common[i][j]: the longest common subsequence of a[i..] and b[j..].
+	let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+	for i in (0..a.len()).rev() {
+		for j in (0..b.len()).rev() {
+			common[i][j] = if a[i] == b[j] { common[i + 1][j + 1] + 1 }
+			               else { std::cmp::max(common[i + 1][j], common[i][j + 1]) };
+		}
+	}
+	// each line: ' ', '-' or '+', its line in 'old' and in 'new', and the text.
+	let mut lines: Vec<(char, usize, usize, &str)> = vec![];
+	let (mut i, mut j) = (0, 0);
+	while i < a.len() || j < b.len() {
+		if i < a.len() && j < b.len() && a[i] == b[j] {
+			lines.push((' ', i, j, a[i]));
+			i += 1;
+			j += 1;
+		} else if j == b.len() ||
+		          (i < a.len() && common[i + 1][j] >= common[i][j + 1]) {
+			lines.push(('-', i, j, a[i]));
+			i += 1;
+		} else {
+			lines.push(('+', i, j, b[j]));
+			j += 1;
+		}
+	}
+	let changed: Vec<usize> = (0..lines.len()).filter(|&k| lines[k].0 != ' ').collect();
+	let near = |k: usize| changed.iter().any(|&c| c + CONTEXT >= k && k + CONTEXT >= c);
+	let mut s = String::new();
+	for (k, &(c, i, j, text)) in lines.iter().enumerate().filter(|&(k, _)| near(k)) {
+		if k == 0 || !near(k - 1) {
+			s += &format!("@@ line {} of the file, {} of the output @@\n", i + 1, j + 1);
+		}
+		s += &format!("{}{}\n", c, text);
+	}
+	if s.is_empty() && old != new {
+		s = "(the files differ only in their line endings)\n".to_string();
+	}
+	s
+}
+
+// Compares the output with the case's golden file, or with MINTERM_UPDATE_GOLDEN
+// set writes it there; what is wrong with it, if anything.
+fn check(case: &str, output: &str) -> Option<String> {
+	let path = golden_dir().join(format!("{}.txt", case));
+	if std::env::var_os(UPDATE).is_some() {
+		fs::create_dir_all(golden_dir()).unwrap();
+		fs::write(&path, output).unwrap();
+		return None;
+	}
+	match fs::read_to_string(&path) {
+		Ok(ref expected) if expected == output => None,
+		Ok(expected) => Some(format!("{} differs from {}:\n{}", case, path.display(),
+		                             diff(&expected, output))),
+		Err(e) => Some(format!("{}: cannot read {}: {}", case, path.display(), e)),
+	}
+}
+
+// Fails with every case that doesn't match its file.
+fn report(failures: Vec<String>) {
+	assert!(failures.is_empty(), "{}\nIf the changes are intended, run the tests with \
+	                              {}=1 set to update the files.", failures.join("\n"),
+	        UPDATE);
+}
+
+// The output of the command, which must succeed.
+fn run(args: &[String]) -> String {
+	let out = Command::cargo_bin("minterm").unwrap().args(args).output().unwrap();
+	assert!(out.status.success(), "{:?}: {}", args,
+	        String::from_utf8_lossy(&out.stderr));
+	String::from_utf8(out.stdout).unwrap()
+}
+
+fn strings(args: &[&str]) -> Vec<String> {
+	args.iter().map(|s| s.to_string()).collect()
+}
+
+// The command's arguments for the fixture's table, written out for it, and its
+// names.
+fn with_table(command: &str, f: &Fixture, args: &[&str]) -> Vec<String> {
+	let mut rv = strings(&[command, "--table"]);
+	rv.push(f.write(&temp_dir()).to_string_lossy().to_string());
+	rv.extend(f.args());
+	rv.push("--quiet".to_string());
+	rv.extend(strings(args));
+	rv
+}
+
+#[test]
+fn minimize_formats() {
+	let (caps, small) = (testutil::capabilities(), testutil::dense(3, 2, 7));
+	let sparse = testutil::sparse(4, 2, 3, 5);
+	let cases: [(&str, &Fixture, &[&str]); 25] = [
+		("text", &caps, &[]),
+		("text-exact", &small, &["--algorithm", "exact"]),
+		("text-styles", &small, &["--neg-style", "bang", "--and-style", "word"]),
+		("text-anf", &small, &["--form", "anf"]),
+		("text-share-terms", &caps, &["--share-terms", "--cost", "conditions"]),
+		("text-complements", &small, &["--emit-complements"]),
+		("text-group", &small, &["--group", "sel=o0,o1"]),
+		("text-explain", &small, &["--explain"]),
+		("text-dont-cares", &sparse, &[]),
+		("verilog-casez", &caps, &["--format", "verilog-casez"]),
+		("vhdl", &caps, &["--format", "vhdl"]),
+		("python", &caps, &["--format", "python", "--return-dict"]),
+		("python-dict-lookup", &sparse, &["--format", "python", "--dict-lookup"]),
+		("sets", &small, &["--format", "sets"]),
+		("bdd", &caps, &["--format", "bdd"]),
+		("bdd-dot", &small, &["--format", "bdd-dot"]),
+		("switch-c", &small, &["--format", "switch-c"]),
+		("switch-c-compressed", &sparse, &["--format", "switch-c", "--compress"]),
+		("switch-rust", &small, &["--format", "switch-rust", "--compress"]),
+		("switch-rust-group", &small,
+		 &["--format", "switch-rust", "--group", "sel=o0,o1"]),
+		("rust-const", &sparse, &["--format", "rust-const"]),
+		("rust-const-macro", &caps, &["--format", "rust-const", "--macro"]),
+		("share-dot", &caps, &["--format", "share-dot"]),
+		("coverage", &sparse, &["--format", "coverage"]),
+		("if-chain-c", &testutil::decoder(3), &["--format", "if-chain-c"]),
+	];
+	let mut failures = vec![];
+	for &(name, f, args) in cases.iter() {
+		failures.extend(check(&format!("minimize-{}", name),
+		                      &run(&with_table("minimize", f, args))));
+	}
+	// the files written beside the code.
+	let dir = temp_dir();
+	let (tests, cover) = (dir.join("minterm-test.cc"), dir.join("small.cover"));
+	run(&with_table("minimize", &testutil::decoder(3),
+	                &["--format", "switch-c", "--emit-tests", "gtest", "--tests-file",
+	                  &tests.to_string_lossy()]));
+	failures.extend(check("minimize-emit-tests-gtest",
+	                      &fs::read_to_string(&tests).unwrap()));
+	run(&with_table("minimize", &small, &["--emit-cover", &cover.to_string_lossy()]));
+	failures.extend(check("minimize-emit-cover", &fs::read_to_string(&cover).unwrap()));
+	report(failures);
+}
+
+#[test]
+fn other_commands() {
+	let (caps, small) = (testutil::capabilities(), testutil::dense(3, 2, 7));
+	let mut failures = vec![];
+	let cases: [(&str, Vec<String>); 5] = [
+		("vectors", with_table("vectors", &small, &[])),
+		("implications", with_table("implications", &caps, &[])),
+		("estimate", with_table("estimate", &caps, &[])),
+		("estimate-json", with_table("estimate", &caps, &["--json"])),
+		("template",
+		 strings(&["template", "--bits", "3", "--ovar", "x", "--ovar", "y"])),
+	];
+	for &(name, ref args) in cases.iter() {
+		failures.extend(check(name, &run(args)));
+	}
+	report(failures);
+}
+
+#[test]
+fn conversions() {
+	let small = testutil::dense(3, 2, 7);
+	let file = small.write(&temp_dir()).to_string_lossy().to_string();
+	let mut failures = vec![];
+	for &(name, args) in [("convert-pla", &["--to", "pla"][..]),
+	                      ("convert-json", &["--to", "json"][..]),
+	                      ("convert-csv-index", &["--to", "csv", "--to-input-encoding",
+	                                              "index"][..]),
+	                      ("convert-pla-minimized", &["--to", "pla", "--minimize"][..])]
+		.iter() {
+		let mut argv = strings(&["convert", &file, "-"]);
+		argv.extend(small.args());
+		argv.extend(strings(args));
+		argv.push("--quiet".to_string());
+		failures.extend(check(name, &run(&argv)));
+	}
+	report(failures);
+}
+
+// Each line of Rust source without its comments, with the depth of braces it
+// starts and ends at; braces in string and character literals don't count.
+fn code_lines(text: &str) -> Vec<(usize, usize, String)> {
+	let ident = |c: char| c.is_alphanumeric() || c == '_';
+	let mut rv = vec![];
+	// inside a string, and the number of #s that end it if it's raw.
+	let (mut depth, mut string, mut raw): (usize, bool, Option<usize>) = (0, false, None);
+	for line in text.lines() {
+		let (start, cs): (usize, Vec<char>) = (depth, line.chars().collect());
+		let mut code = String::new();
+		let mut i = 0;
+		while i < cs.len() {
+			let c = cs[i];
+			// what isn't a comment goes into the line as it is.
+			let first = i;
+			if let Some(hashes) = raw {
+				let closing = cs[i + 1..].iter().take_while(|&&h| h == '#').count();
+				if c == '"' && closing >= hashes {
+					raw = None;
+					i += hashes;
+				}
+			} else if string {
+				if c == '\\' {
+					i += 1;
+				} else if c == '"' {
+					string = false;
+				}
+			} else if c == '/' && cs.get(i + 1) == Some(&'/') {
+				break;
+			} else if c == '"' {
+				string = true;
+			} else if c == 'r' && (i == 0 || !ident(cs[i - 1])) &&
+			          cs.get(i + 1).is_some_and(|&n| n == '"' || n == '#') {
+				let hashes = cs[i + 1..].iter().take_while(|&&h| h == '#').count();
+				if cs.get(i + 1 + hashes) == Some(&'"') {
+					raw = Some(hashes);
+					i += hashes + 1;
+				}
+			} else if c == '\'' && cs.get(i + 1) == Some(&'\\') {
+				// an escaped character: skip to its closing quote.
+				i += 2;
+				while i < cs.len() && cs[i] != '\'' {
+					i += 1;
+				}
+			} else if c == '\'' && cs.get(i + 2) == Some(&'\'') {
+				i += 2;
+			} else if c == '{' {
+				depth += 1;
+			} else if c == '}' {
+				depth -= 1;
+			}
+			i = std::cmp::min(i + 1, cs.len());
+			code.extend(cs[first..i].iter());
+		}
+		rv.push((start, depth, code.trim().to_string()));
+	}
+	rv
+}
+
+// The line after the item starting at 'k', with its attributes.
+fn item_end(lines: &[(usize, usize, String)], k: usize) -> usize {
+	let depth = lines[k].0;
+	let mut braced = false;
+	for (j, &(_, end, ref code)) in lines.iter().enumerate().skip(k) {
+		if code.starts_with("#[") {
+			continue;
+		}
+		braced |= code.contains('{');
+		let ended = if braced { code.contains('}') } else { code.ends_with(';') };
+		if end == depth && ended {
+			return j + 1;
+		}
+	}
+	lines.len()
+}
+
+// The declaration starting at line 'k', on one line and without its body, and
+// the line after it.  A use runs to its semicolon, braces and all.
+fn declaration(lines: &[(usize, usize, String)], k: usize) -> (String, usize) {
+	let import = lines[k].2.starts_with("pub use");
+	let mut decl = String::new();
+	let mut j = k;
+	while j < lines.len() {
+		if !decl.is_empty() && !decl.ends_with('(') {
+			decl.push(' ');
+		}
+		decl += &lines[j].2;
+		j += 1;
+		let open = decl.matches('(').count() > decl.matches(')').count();
+		if import && decl.ends_with(';') || !import && !open &&
+		   (decl.contains('{') || decl.ends_with(';') || decl.ends_with(',')) {
+			break;
+		}
+	}
+	let decl = if import { decl.trim() }
+	           else { decl.split('{').next().unwrap().trim().trim_end_matches(',') };
+	(decl.split_whitespace().collect::<Vec<&str>>().join(" ").replace("( ", "("), j)
+}
+
+// The public items of a source file, with their attributes: every 'pub'
+// declaration, the variants of enums and the methods of traits, and the impl
+// blocks the public methods are in, each indented by its depth.  What
+// #[cfg(test)] marks is left out.
+fn public_items(text: &str) -> Vec<String> {
+	let lines = code_lines(text);
+	let (mut rv, mut attrs) = (vec![], vec![]);
+	let mut k = 0;
+	while k < lines.len() {
+		let (depth, _, ref code) = lines[k];
+		if code == "#[cfg(test)]" {
+			k = item_end(&lines, k + 1);
+			attrs.clear();
+			continue;
+		} else if code.starts_with("#[") {
+			attrs.push(code.clone());
+		} else if code.starts_with("pub ") || depth == 0 && code.starts_with("impl") {
+			let (decl, next) = declaration(&lines, k);
+			attrs.push(decl.clone());
+			rv.push(format!("{}{}", "  ".repeat(depth), attrs.join(" ")));
+			attrs.clear();
+			let enumeration = decl.starts_with("pub enum");
+			let members = (enumeration || decl.starts_with("pub trait")) &&
+			              lines[next - 1].1 > depth;
+			k = next;
+			while members && k < lines.len() && lines[k].0 > depth {
+				let (inner, _, ref code) = lines[k];
+				let member = inner == depth + 1 && !code.is_empty() &&
+				             !code.starts_with("#[") && !code.starts_with('}');
+				if member && enumeration {
+					// a variant, with its fields.
+					let variant = code.trim_end_matches(',');
+					rv.push(format!("{}{}", "  ".repeat(inner), variant));
+					k += 1;
+				} else if member {
+					let (method, after) = declaration(&lines, k);
+					rv.push(format!("{}{}", "  ".repeat(inner), method));
+					k = after;
+				} else {
+					k += 1;
+				}
+			}
+			continue;
+		} else if !code.is_empty() {
+			attrs.clear();
+		}
+		k += 1;
+	}
+	rv
+}
+
+// The library's public items, as its source declares them: those of lib.rs and
+// of each public module it declares, but testutil.
+fn public_api() -> String {
+	let src = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src");
+	let lib = fs::read_to_string(src.join("lib.rs")).unwrap();
+	let mut modules = vec!["lib"];
+	modules.extend(lib.lines().filter(|l| l.starts_with("pub mod ") && l.ends_with(';'))
+		.map(|l| l.trim_end_matches(';').rsplit(' ').next().unwrap()));
+	let mut rv = String::new();
+	for module in modules.into_iter().filter(|&m| m != "testutil") {
+		let text = fs::read_to_string(src.join(format!("{}.rs", module))).unwrap();
+		for item in public_items(&text) {
+			rv += &format!("{}: {}\n", module, item);
+		}
+	}
+	rv
+}
+
+#[test]
+fn public_items_of_source() {
+	let text = "#[derive(Clone)]\npub struct S {\n\tpub a: usize,\n\tb: usize,\n}\n\
+	            impl S {\n\tpub fn new(a: usize,\n\t           b: usize) -> Self {\n\
+	            \t\tS{a: a, b: b} // }\n\t}\n\tfn hidden(&self) -> char { '}' }\n}\n\
+	            pub enum E {\n\tX, // one\n\tY{z: &'static str},\n}\n\
+	            #[cfg(test)]\nmod test {\n\tpub fn t() { \"{\"; }\n}\n";
+	assert_eq!(public_items(text),
+	           vec!["#[derive(Clone)] pub struct S", "  pub a: usize", "impl S",
+	                "  pub fn new(a: usize, b: usize) -> Self", "pub enum E", "  X",
+	                "  Y{z: &'static str}"]);
+}
+
+#[test]
+fn api() {
+	report(check("public-api", &public_api()).into_iter().collect());
+}
+
+#[test]
+fn readable_diffs() {
+	assert_eq!(diff("a\nb\nc\n", "a\nb\nc\n"), "");
+	assert_eq!(diff("a\nb\nc\nd\ne\nf\ng\n", "a\nb\nc\nD\ne\nf\ng\n"),
+	           "@@ line 2 of the file, 2 of the output @@\n b\n c\n-d\n+D\n e\n f\n");
+	assert_eq!(diff("x\n", "x\ny\n"),
+	           "@@ line 1 of the file, 1 of the output @@\n x\n+y\n");
+}
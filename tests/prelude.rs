@@ -0,0 +1,78 @@
+// The library's stable surface, as minterm::prelude exports it: every item by
+// name, the signatures of the functions, and each setting and statistic, so
+// that changing any of them fails here and is a deliberate change.  What must
+// not compile outside the crate -- an exhaustive match on an enum that will
+// grow, MinimizeOptions's settings set directly -- is in the compile_fail
+// examples of Cost and MinimizeOptions.
+extern crate minterm;
+use minterm::prelude::*;
+use std::time::Duration;
+
+// The types no function below takes or returns.
+#[allow(dead_code)]
+fn named(_: Option<(Cover, Variable, EmptyTable, ParseError, Warning, WarningKind)>) {}
+#[allow(dead_code)]
+fn table<T: Table>(_: &T) {}
+
+type Render = fn(&MinimizeResult, &Truth, &[String], &str, DefaultOutput, BitOrder) ->
+	Result<String, Error>;
+
+#[test]
+fn signatures() {
+	let _: fn(&Truth, &Names, &MinimizeOptions) -> Result<MinimizeResult, Error> =
+		minimize;
+	let _: Render = render;
+	let _: fn(&Equation) -> &[Term] = Equation::terms;
+	let _: fn(&Equation) -> &[Term] = Equation::dont_cares;
+	let _: fn(&Equation) -> usize = Equation::index;
+	let _: fn(&Equation) -> &str = Equation::varname;
+	let _: fn(&mut Equation, Vec<Term>) = Equation::set_terms;
+	let _: fn(&mut Equation, Vec<Term>) = Equation::add_terms;
+	let _: fn(&mut Equation, Vec<Term>) = Equation::add_dont_cares;
+	let _: fn(&mut Equation) = Equation::clear_dont_cares;
+	let _: fn(usize, &str, Vec<Term>, Vec<Term>) -> Equation = Equation::from_terms;
+	let _: fn(&Truth) -> &[Entry] = Truth::rows;
+	let _: fn(&mut Truth, usize, usize) = Truth::swap_rows;
+	let _: fn(&mut Truth, Entry) = Truth::push;
+	let _: fn(&mut Truth, Vec<Vec<bool>>) = Truth::add_dont_cares;
+	let _: fn() -> MinimizeOptions = MinimizeOptions::new;
+}
+
+#[test]
+fn settings_and_results() {
+	let tbl = Truth::from_fn(2, 2, |i| vec![i[0] && i[1], i[0] || i[1]]).unwrap();
+	let names = Names::new(&["a", "b"], &["x", "y"]);
+	let opts = MinimizeOptions::new().algorithm(Algorithm::Greedy)
+		.algorithm_for("y", Algorithm::Exact).cost(Cost::Terms).share_terms(false)
+		.default_output(DefaultOutput::Zero).canonical(true)
+		.timeout(Duration::from_secs(10)).max_steps(1_000_000).max_passes(100)
+		.max_merges(10_000).seed(1).pregroup(Pregroup::None);
+	let r = minimize(&tbl, &names, &opts).unwrap();
+	assert_eq!(r.equations[1].to_string(), "y = a + b + ;");
+	assert_eq!(r.equations[0].terms().len(), 1);
+	assert!(r.equations[0].dont_cares().is_empty());
+	assert!(r.shared.is_empty() && r.warnings.is_empty() && r.minimal());
+	let s = &r.stats[1];
+	assert_eq!((s.ran, s.minimal, s.completed, s.terms, s.literals, s.pregrouped),
+	           (Some(Ran::Exact), true, true, 2, 2, false));
+	let p = &r.provenance;
+	assert_eq!((p.algorithm, p.cost, p.seed), ("mixed", "terms", None));
+	assert!(!p.version.is_empty());
+	assert_eq!(tbl.rows().len(), 4);
+	let text = render(&r, &tbl, &names.inputs, "python", DefaultOutput::Zero,
+	                  BitOrder::default()).unwrap();
+	assert!(text.contains("y = bool(a or b)"), "{}", text);
+	// the enums that will grow are matched with a wildcard.
+	let described = match minimize(&tbl, &Names::new(&["a"], &["x", "y"]), &opts) {
+		Ok(_) => "minimized",
+		Err(Error::Usage(_)) => "usage",
+		Err(_) => "other",
+	};
+	assert_eq!(described, "usage");
+	let cost = match Cost::parse("literals").unwrap() {
+		Cost::Terms => 0,
+		Cost::Literals => 2,
+		_ => 1,
+	};
+	assert_eq!(cost, 2);
+}
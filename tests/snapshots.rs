@@ -0,0 +1,89 @@
+// The exact output for several tables, so that any change to it, and any
+// dependence on the platform, the hash seed or the order of the table's
+// rows, shows up here.  Each table is run as written and with its rows
+// reversed.  When a change to the output is intended, update the strings.
+extern crate assert_cmd;
+extern crate minterm;
+use assert_cmd::Command;
+use minterm::testutil::{self, Fixture};
+use std::fs;
+
+// The output of minimizing the table, after checking that reversing its rows
+// doesn't change it.
+fn snapshot(f: &Fixture, args: &[&str]) -> String {
+	let dir = std::env::temp_dir().join(format!("minterm-snapshots-{}",
+	                                            std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let csv = f.csv();
+	let mut lines: Vec<&str> = csv.lines().collect();
+	lines[2..].reverse();
+	let mut outputs = vec![];
+	for (name, contents) in [("rows", csv.clone()), ("reversed", lines.join("\n"))].iter() {
+		let path = dir.join(format!("{}-{}.csv", f.name, name));
+		fs::write(&path, contents).unwrap();
+		let out = Command::cargo_bin("minterm").unwrap().arg("--table").arg(&path)
+			.args(f.args()).arg("--quiet").args(args).output().unwrap();
+		assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+		outputs.push(String::from_utf8(out.stdout).unwrap());
+	}
+	assert_eq!(outputs[0], outputs[1], "{} {:?}", f.name, args);
+	outputs.remove(0)
+}
+
+#[test]
+fn capabilities() {
+	let f = testutil::capabilities();
+	let text = "NEED_OGL = WANT_OGL + WANT_GLX' & WANT_EGL' & HAVE_OGL + \
+	            WANT_GLX' & WANT_EGL' & HAVE_GL' + ;\n\
+	            NEED_GLX = WANT_GLX + WANT_OGL' & WANT_EGL' & HAVE_OGL + \
+	            WANT_OGL' & WANT_EGL' & HAVE_GL' + ;\n\
+	            NEED_EGL = WANT_EGL + ;\n\
+	            NEED_GL = WANT_OGL' & WANT_GLX' & WANT_EGL' & HAVE_OGL' & HAVE_GL + ;\n";
+	assert_eq!(snapshot(&f, &[]), text);
+	assert_eq!(snapshot(&f, &["--algorithm", "exact"]), text);
+	assert_eq!(snapshot(&f, &["--format", "python"]),
+	           "def map(REQUIRED, WANT_OGL, WANT_GLX, WANT_EGL, HAVE_OGL, HAVE_GLX, \
+	            HAVE_EGL, HAVE_GL):\n    \
+	            NEED_OGL = bool(WANT_OGL or (not WANT_GLX and not WANT_EGL and HAVE_OGL) \
+	            or (not WANT_GLX and not WANT_EGL and not HAVE_GL))\n    \
+	            NEED_GLX = bool(WANT_GLX or (not WANT_OGL and not WANT_EGL and HAVE_OGL) \
+	            or (not WANT_OGL and not WANT_EGL and not HAVE_GL))\n    \
+	            NEED_EGL = bool(WANT_EGL)\n    \
+	            NEED_GL = bool(not WANT_OGL and not WANT_GLX and not WANT_EGL and not \
+	            HAVE_OGL and HAVE_GL)\n    \
+	            return (NEED_OGL, NEED_GLX, NEED_EGL, NEED_GL)\n");
+}
+
+#[test]
+fn dense() {
+	let f = testutil::dense(5, 2, 7);
+	let text = "o0 = i0' & i2' & i3' + i1 & i2 & i3 + i0' & i1' & i3' & i4' + \
+	            i0' & i1 & i3 & i4 + i0 & i1 & i3 & i4' + ;\n\
+	            o1 = i0' & i1' & i3' + i0 & i3 & i4 + i0 & i1' & i2 & i3 + \
+	            i0' & i1 & i2' & i4' + i1' & i2' & i3' & i4' + i1' & i2' & i3 & i4 + \
+	            i1 & i2' & i3' & i4 + i1 & i2 & i3' & i4' + i1 & i2 & i3 & i4 + ;\n";
+	assert_eq!(snapshot(&f, &[]), text);
+	// the greedy covers are minimal, and of the minimum covers of o1 the
+	// reduced chart leaves the same one.
+	assert_eq!(snapshot(&f, &["--algorithm", "exact"]), text);
+}
+
+#[test]
+fn sparse() {
+	let f = testutil::sparse(6, 2, 4, 3);
+	assert_eq!(snapshot(&f, &[]),
+	           "o0 = i0' & i3' + i2 & i3' + i3' & i5' + i4' & i5 + i1' & i2' & i3 + ;\n\
+	            o1 = i0 & i1' + i1 & i2 & i5' + i1' & i3 & i4' + i1 & i3' & i4' + \
+	            i2' & i3 & i4 + i2 & i4' & i5 + ;\n");
+	assert_eq!(snapshot(&f, &["--algorithm", "exact"]),
+	           "o0 = i3' & i5' + i0 & i1 & i2 + i0' & i2' & i5 + i1' & i2' & i3 + ;\n\
+	            o1 = i0 & i1' + i1' & i2' & i4' + i1 & i2 & i4' + i1 & i2 & i5' + \
+	            i1 & i3' & i4' + i2' & i3 & i4 + ;\n");
+}
+
+#[test]
+fn parity() {
+	assert_eq!(snapshot(&testutil::parity(3), &[]),
+	           "p = i0' & i1' & i2 + i0' & i1 & i2' + i0 & i1' & i2' + \
+	            i0 & i1 & i2 + ;\n");
+}
@@ -0,0 +1,39 @@
+# The Python module on the small example; run with pytest after
+# 'maturin develop'.
+import pytest
+import minterm
+
+SMALL = [[0,0,0, 0,1], [0,0,1, 1,0], [0,1,0, 1,1], [0,1,1, 0,0],
+         [1,0,0, 1,1], [1,0,1, 0,1], [1,1,0, 1,1], [1,1,1, 0,0]]
+
+
+def small():
+    return minterm.Truth(SMALL, nin=3, invars=["a", "b", "c"], outvars=["x", "y"])
+
+
+def test_minimize():
+    t = small()
+    eqns = minterm.minimize(t, exact=True)
+    assert [t.verify(e) for e in eqns] == [[], []]
+    assert str(eqns[1]) == "y = c' + ab' + ;"
+    assert eqns[1].terms == [{"c": False}, {"a": True, "b": False}]
+    assert eqns[1].to_string("python") == "y = not c or (a and not b)"
+    assert eqns[1].evaluate([1, 0, 1])
+    assert all(e.minimal for e in eqns)
+
+
+def test_check():
+    assert small().check("y = c'") == [[True, False, True]]
+    with pytest.raises(ValueError):
+        small().check("z = a")
+
+
+def test_dont_cares():
+    # pairs, a don't-care input standing for both values, and one output
+    # that may be either.
+    t = minterm.Truth([((0, None), (1,)), ((1, 0), (None,))])
+    assert (t.invars, t.outvars, len(t)) == (["i0", "i1"], ["o0"], 3)
+    eqn = minterm.minimize(t, default_output="dc")[0]
+    assert eqn.terms == [{}]
+    with pytest.raises(ValueError):
+        minterm.Truth([[0, 2, 1]], nin=2)
@@ -0,0 +1,21 @@
+// minimize_csv compiled to WebAssembly, in a headless browser:
+//   wasm-pack test --headless --firefox -- --features wasm
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+extern crate minterm;
+extern crate wasm_bindgen_test;
+use minterm::wasm::minimize_csv;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const SMALL: &'static str = "a,b,c,,x,y\n-,-,-,,-,-\n0,0,0,,0,1\n0,0,1,,1,0\n\
+                             0,1,0,,1,1\n0,1,1,,0,0\n1,0,0,,1,1\n1,0,1,,0,1\n\
+                             1,1,0,,1,1\n1,1,1,,0,0\n";
+
+#[wasm_bindgen_test]
+fn small_example() {
+	let out = minimize_csv(SMALL, 3, 2, r#"{"algorithm": "exact"}"#).ok().unwrap();
+	assert!(out.contains(r#""output":"x = "#), "{}", out);
+	assert!(out.contains(r#""minimal":true"#), "{}", out);
+	assert!(minimize_csv(SMALL, 3, 2, r#"{"format": "perl"}"#).is_err());
+}